@@ -0,0 +1,93 @@
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use wine_maker::dataset::{Dataset, WINE_DATA_CSV, load_csv_data_from_str};
+use wine_maker::engine::{Beverage, Closure, Inoculation, SimulationInput, run_simulation};
+
+const MONTE_CARLO_RUNS: u64 = 10_000;
+
+fn sample_input() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Cabernet Sauvignon".to_owned(),
+        fermentation_days: 14,
+        container_type: "Oak Barrel".to_owned(),
+        aging_container: String::new(),
+        aging_months: 0,
+        whole_cluster: 0.0,
+        press_wine: 0.0,
+        sugar_content: 240.0,
+        temperature: 24.0,
+        climate: "Warm".to_owned(),
+        seed: Some(42),
+        restart_day: None,
+        excursions: Vec::new(),
+        yeast: String::new(),
+        inoculation: Inoculation::Single,
+        lees_months: 0,
+        stirs_per_month: 0,
+        closure: Closure::NaturalCork,
+        interventions: Vec::new(),
+        beverage: Beverage::Wine,
+    }
+}
+
+fn single_run(c: &mut Criterion) {
+    let wine_data = Dataset::from_csv_str(WINE_DATA_CSV).unwrap();
+    let input = sample_input();
+
+    c.bench_function("single run", |b| {
+        b.iter(|| run_simulation(black_box(&input), black_box(&wine_data)))
+    });
+}
+
+fn monte_carlo_batch(c: &mut Criterion) {
+    let wine_data = Dataset::from_csv_str(WINE_DATA_CSV).unwrap();
+    let base = sample_input();
+
+    let mut group = c.benchmark_group("monte carlo");
+    group.sample_size(10);
+    group.bench_function("10k runs", |b| {
+        b.iter(|| {
+            for seed in 0..MONTE_CARLO_RUNS {
+                let input = SimulationInput {
+                    temperature: 10.0 + (seed % 200) as f64 * 0.1,
+                    sugar_content: 180.0 + (seed % 120) as f64,
+                    seed: Some(seed),
+                    ..base.clone()
+                };
+                let _ = black_box(run_simulation(&input, &wine_data));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn dataset_lookups(c: &mut Criterion) {
+    c.bench_function("load dataset", |b| {
+        b.iter(|| load_csv_data_from_str(black_box(WINE_DATA_CSV)).unwrap())
+    });
+    c.bench_function("index dataset", |b| {
+        let records = load_csv_data_from_str(WINE_DATA_CSV).unwrap();
+        b.iter_batched(|| records.clone(), Dataset::new, BatchSize::SmallInput)
+    });
+
+    let wine_data = Dataset::from_csv_str(WINE_DATA_CSV).unwrap();
+    let mut group = c.benchmark_group("grape lookup");
+    for grape in ["Cabernet Sauvignon", "Riesling", "Gamay"] {
+        let input = SimulationInput {
+            grape_type: grape.to_owned(),
+            ..sample_input()
+        };
+        group.bench_function(grape, |b| {
+            b.iter_batched(
+                || input.clone(),
+                |input| run_simulation(&input, black_box(&wine_data)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, single_run, monte_carlo_batch, dataset_lookups);
+criterion_main!(benches);
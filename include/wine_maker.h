@@ -0,0 +1,45 @@
+#ifndef WINE_MAKER_H
+#define WINE_MAKER_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stddef.h>
+
+/**
+ * `json_in` or `json_out_buf` was null.
+ */
+#define WM_ERR_NULL_POINTER -1
+
+/**
+ * `json_in` was not UTF-8 or not a valid simulation input object.
+ */
+#define WM_ERR_INVALID_INPUT -2
+
+/**
+ * The simulation ran but the fermentation failed; the buffer holds `{"error": ...}`.
+ */
+#define WM_ERR_SIMULATION -3
+
+/**
+ * The output did not fit in `json_out_len` bytes including the trailing NUL.
+ */
+#define WM_ERR_BUFFER_TOO_SMALL -4
+
+/**
+ * Runs a simulation against the bundled dataset.
+ *
+ * `json_in` is a NUL-terminated JSON object with any of the
+ * `SimulationInput` fields (missing fields take their defaults). On success
+ * the NUL-terminated JSON result, including a `narrative` string, is written
+ * to `json_out_buf` and its length (excluding the NUL) is returned. On
+ * failure a negative `WM_ERR_*` code is returned; for `WM_ERR_SIMULATION`
+ * the buffer holds `{"error": "..."}`. Results are well under 4 KiB.
+ *
+ * # Safety
+ *
+ * `json_in` must be a valid NUL-terminated string and `json_out_buf` must
+ * point to at least `json_out_len` writable bytes.
+ */
+ptrdiff_t wm_simulate(const char *json_in, char *json_out_buf, size_t json_out_len);
+
+#endif /* WINE_MAKER_H */
@@ -0,0 +1,125 @@
+//! Achievements earned by the wines made in the simulator, and milestones
+//! for how many have been made.
+
+use crate::cellar::DRY_SUGAR;
+use crate::descriptors::{self, SWEETNESS};
+use crate::engine::{Beverage, SimulationInput, SimulationResult, restart_day};
+use crate::optimizer::quality;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Achievement {
+    FirstWine,
+    BoneDry,
+    SurvivedStuckFerment,
+    MonsterZin,
+    Featherweight,
+    Flawless,
+    BuiltToLast,
+    Mead,
+    TenWines,
+    FiftyWines,
+    HundredWines,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 11] = [
+        Achievement::FirstWine,
+        Achievement::BoneDry,
+        Achievement::SurvivedStuckFerment,
+        Achievement::MonsterZin,
+        Achievement::Featherweight,
+        Achievement::Flawless,
+        Achievement::BuiltToLast,
+        Achievement::Mead,
+        Achievement::TenWines,
+        Achievement::FiftyWines,
+        Achievement::HundredWines,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Achievement::FirstWine => "First wine",
+            Achievement::BoneDry => "First bone-dry wine",
+            Achievement::SurvivedStuckFerment => "Survived a stuck ferment",
+            Achievement::MonsterZin => "15% monster Zin",
+            Achievement::Featherweight => "Featherweight",
+            Achievement::Flawless => "Flawless",
+            Achievement::BuiltToLast => "Built to last",
+            Achievement::Mead => "Honey and water",
+            Achievement::TenWines => "Ten wines",
+            Achievement::FiftyWines => "Fifty wines",
+            Achievement::HundredWines => "A hundred wines",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|achievement| achievement.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// What it takes.
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstWine => "Simulate a wine.",
+            Achievement::BoneDry => "Ferment a wine to bone dry.",
+            Achievement::SurvivedStuckFerment => "Restart a fermentation and still finish dry.",
+            Achievement::MonsterZin => "Make a Zinfandel of 15% ABV or more.",
+            Achievement::Featherweight => "Make a finished wine under 9% ABV.",
+            Achievement::Flawless => "Make a wine that scores 90 or more.",
+            Achievement::BuiltToLast => "Make a wine that keeps improving for 10 years.",
+            Achievement::Mead => "Make a mead.",
+            Achievement::TenWines => "Make 10 wines.",
+            Achievement::FiftyWines => "Make 50 wines.",
+            Achievement::HundredWines => "Make 100 wines.",
+        }
+    }
+
+    /// The number of wines made that earns a milestone, or `None` for an
+    /// achievement earned by a single wine.
+    pub fn wines_needed(self) -> Option<u32> {
+        match self {
+            Achievement::TenWines => Some(10),
+            Achievement::FiftyWines => Some(50),
+            Achievement::HundredWines => Some(100),
+            _ => None,
+        }
+    }
+
+    fn earned_by(self, input: &SimulationInput, result: &SimulationResult) -> bool {
+        let wine = input.beverage == Beverage::Wine;
+        let dry = result.residual_sugar <= DRY_SUGAR;
+        match self {
+            Achievement::FirstWine => true,
+            Achievement::BoneDry => {
+                descriptors::standard().describe(SWEETNESS, input.beverage, result.residual_sugar)
+                    == "bone dry"
+            }
+            Achievement::SurvivedStuckFerment => restart_day(input).is_some() && dry,
+            Achievement::MonsterZin => {
+                input.grape_type.eq_ignore_ascii_case("Zinfandel") && result.actual_abv >= 15.0
+            }
+            Achievement::Featherweight => wine && dry && result.actual_abv < 9.0,
+            Achievement::Flawless => quality(input, result).score >= 90.0,
+            Achievement::BuiltToLast => result.peak_years >= 10.0,
+            Achievement::Mead => input.beverage == Beverage::Mead,
+            Achievement::TenWines | Achievement::FiftyWines | Achievement::HundredWines => false,
+        }
+    }
+}
+
+/// The achievements the wine from `input` and `result` earns, with the
+/// milestones reached once `wines_made` wines have been made.
+pub fn earned(
+    input: &SimulationInput,
+    result: &SimulationResult,
+    wines_made: u32,
+) -> Vec<Achievement> {
+    Achievement::ALL
+        .into_iter()
+        .filter(|achievement| match achievement.wines_needed() {
+            Some(needed) => wines_made >= needed,
+            None => achievement.earned_by(input, result),
+        })
+        .collect()
+}
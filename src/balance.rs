@@ -0,0 +1,99 @@
+//! Whether a wine's alcohol, sugar, and acidity hold each other in check,
+//! or one of them stands out on the palate.
+
+use crate::engine::{Beverage, usual_acidity};
+
+/// Perceived sugar in g/L below which high alcohol is left uncovered,
+/// unless the wine has this much body beyond its alcohol.
+const HOT_SUGAR: f64 = 10.0;
+const HOT_BODY: f64 = 1.0;
+/// Residual sugar in g/L past which low acidity leaves a wine cloying.
+const FLABBY_SUGAR: f64 = 12.0;
+/// Perceived sugar in g/L below which nothing softens high acidity.
+const AUSTERE_SUGAR: f64 = 4.0;
+/// How far below or above the beverage's usual acidity, in g/L, acidity
+/// reads as low or high.
+const LOW_ACIDITY_MARGIN: f64 = 1.0;
+const HIGH_ACIDITY_MARGIN: f64 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BalanceFault {
+    /// High alcohol without the sugar or body to carry it.
+    Hot,
+    /// Low acidity under a lot of sugar.
+    Flabby,
+    /// High acidity in a lean, dry wine.
+    Austere,
+}
+
+impl BalanceFault {
+    pub const ALL: [BalanceFault; 3] = [
+        BalanceFault::Hot,
+        BalanceFault::Flabby,
+        BalanceFault::Austere,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BalanceFault::Hot => "Hot",
+            BalanceFault::Flabby => "Flabby",
+            BalanceFault::Austere => "Austere",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|fault| fault.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// How the fault shows in the glass.
+    pub fn description(self) -> &'static str {
+        match self {
+            BalanceFault::Hot => {
+                "it tastes hot, the alcohol burning with too little sugar or body to carry it"
+            }
+            BalanceFault::Flabby => "it tastes flabby, too little acidity to lift its sugar",
+            BalanceFault::Austere => "it tastes austere, its acidity sharp in a lean, dry wine",
+        }
+    }
+}
+
+/// % ABV past which `beverage` tastes hot if nothing covers it, and the
+/// body below which it is lean.
+fn abv_limits(beverage: Beverage) -> (f64, f64) {
+    match beverage {
+        Beverage::Wine => (11.0, 14.5),
+        Beverage::Mead => (10.0, 15.0),
+        Beverage::Cider => (5.5, 8.5),
+        Beverage::FruitWine => (10.0, 13.5),
+    }
+}
+
+/// What throws the wine out of balance, if anything.
+pub fn balance_faults(
+    beverage: Beverage,
+    abv: f64,
+    residual_sugar: f64,
+    perceived_sugar: f64,
+    titratable_acidity: f64,
+    body_index: f64,
+) -> Vec<BalanceFault> {
+    let (lean_abv, hot_abv) = abv_limits(beverage);
+    let usual = usual_acidity(beverage);
+    let mut faults = Vec::new();
+    if abv >= hot_abv && perceived_sugar < HOT_SUGAR && body_index - abv < HOT_BODY {
+        faults.push(BalanceFault::Hot);
+    }
+    if titratable_acidity <= usual - LOW_ACIDITY_MARGIN && residual_sugar >= FLABBY_SUGAR {
+        faults.push(BalanceFault::Flabby);
+    }
+    if titratable_acidity >= usual + HIGH_ACIDITY_MARGIN
+        && perceived_sugar < AUSTERE_SUGAR
+        && body_index < lean_abv
+    {
+        faults.push(BalanceFault::Austere);
+    }
+    faults
+}
@@ -0,0 +1,249 @@
+//! Famous wines to measure a simulated wine against: a profile of each on
+//! the same scales as the simulation, and how alike two profiles are.
+
+use crate::engine::SimulationResult;
+
+/// The scales a wine's profile is drawn on, each from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    Alcohol,
+    Sweetness,
+    Acidity,
+    Tannin,
+    Oxygen,
+    Color,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 6] = [
+        Axis::Alcohol,
+        Axis::Sweetness,
+        Axis::Acidity,
+        Axis::Tannin,
+        Axis::Oxygen,
+        Axis::Color,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Axis::Alcohol => "Alcohol",
+            Axis::Sweetness => "Sweetness",
+            Axis::Acidity => "Acidity",
+            Axis::Tannin => "Tannin",
+            Axis::Oxygen => "Oxygen",
+            Axis::Color => "Color depth",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|axis| axis.label().eq_ignore_ascii_case(label.trim()))
+    }
+}
+
+// The % ABV and g/L of sugar at either end of their scales.
+const LEAST_ABV: f64 = 5.0;
+const MOST_ABV: f64 = 16.0;
+const SWEETEST_SUGAR: f64 = 100.0;
+/// Oxygen uptake in mg/L at the top of its scale, a long stay in small
+/// barrels.
+const MOST_OXYGEN: f64 = 50.0;
+/// Color intensity at the top of its scale, an opaque red.
+const MOST_COLOR_INTENSITY: f64 = 16.0;
+
+/// A wine's place on each axis, from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Profile {
+    pub values: [f64; 6],
+}
+
+impl Profile {
+    /// Scales a wine's alcohol in % ABV, residual sugar in g/L, acidity and
+    /// tannin from 0 to 1, oxygen uptake in mg/L, and color intensity.
+    pub fn new(
+        abv: f64,
+        residual_sugar: f64,
+        acidity: f64,
+        tannin: f64,
+        oxygen_uptake: f64,
+        color_intensity: f64,
+    ) -> Self {
+        Profile {
+            values: [
+                (abv - LEAST_ABV) / (MOST_ABV - LEAST_ABV),
+                residual_sugar / SWEETEST_SUGAR,
+                acidity,
+                tannin,
+                oxygen_uptake / MOST_OXYGEN,
+                color_intensity / MOST_COLOR_INTENSITY,
+            ]
+            .map(|value| value.clamp(0.0, 1.0)),
+        }
+    }
+
+    /// The profile of a simulated wine.
+    pub fn of(result: &SimulationResult) -> Self {
+        Profile::new(
+            result.actual_abv,
+            result.residual_sugar,
+            acidity_level(&result.acidity),
+            tannin_level(&result.tannin_level),
+            result.oxygen_uptake,
+            result.color_intensity,
+        )
+    }
+
+    pub fn get(&self, axis: Axis) -> f64 {
+        self.values[Axis::ALL
+            .iter()
+            .position(|&a| a == axis)
+            .expect("every axis")]
+    }
+
+    /// How alike two profiles are, from 0 (opposites on every axis) to 100
+    /// (the same).
+    pub fn similarity(&self, other: &Profile) -> f64 {
+        let mean_square = self
+            .values
+            .iter()
+            .zip(other.values)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            / self.values.len() as f64;
+        100.0 * (1.0 - mean_square.sqrt())
+    }
+}
+
+/// Places the simulation's acidity descriptions on the 0 to 1 scale.
+fn acidity_level(acidity: &str) -> f64 {
+    match acidity {
+        "high" => 0.85,
+        "crisp" => 0.7,
+        "moderate" => 0.55,
+        "low" => 0.35,
+        "soft" => 0.3,
+        "very soft" => 0.15,
+        _ => 0.5,
+    }
+}
+
+/// Places the simulation's tannin descriptions on the 0 to 1 scale by
+/// their first words, ignoring the notes on stems and climate after them.
+fn tannin_level(tannin: &str) -> f64 {
+    let levels = [
+        ("robust, high", 0.9),
+        ("high", 0.8),
+        ("spicy, moderately high", 0.7),
+        ("smooth, moderate", 0.55),
+        ("moderate", 0.55),
+        ("delicate, low", 0.3),
+        ("very minimal", 0.05),
+        ("minimal", 0.1),
+    ];
+    levels
+        .iter()
+        .find(|(level, _)| tannin.starts_with(level))
+        .map_or(0.0, |(_, value)| *value)
+}
+
+/// A classic wine and its profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Benchmark {
+    pub name: &'static str,
+    pub grape: &'static str,
+    /// What the wine is like and how it is made.
+    pub note: &'static str,
+    pub profile: Profile,
+}
+
+const fn classic(
+    name: &'static str,
+    grape: &'static str,
+    note: &'static str,
+    values: [f64; 6],
+) -> Benchmark {
+    Benchmark {
+        name,
+        grape,
+        note,
+        profile: Profile { values },
+    }
+}
+
+// Profiles are alcohol, sweetness, acidity, tannin, oxygen, and color, as
+// `Profile::new` would scale them.
+pub const BENCHMARKS: [Benchmark; 9] = [
+    classic(
+        "Mosel Riesling Kabinett",
+        "Riesling",
+        "Light and off-dry, with racy acidity; fermented cool in steel and stopped early.",
+        [0.32, 0.5, 0.85, 0.05, 0.0, 0.02],
+    ),
+    classic(
+        "Napa Valley Cabernet Sauvignon",
+        "Cabernet Sauvignon",
+        "Ripe, powerful, and dry, with firm tannins and a year and more in new oak.",
+        [0.86, 0.02, 0.3, 0.9, 0.9, 0.6],
+    ),
+    classic(
+        "Red Burgundy",
+        "Pinot Noir",
+        "Silky and dry, with bright acidity and delicate tannins, aged in barrique.",
+        [0.73, 0.02, 0.55, 0.3, 0.6, 0.25],
+    ),
+    classic(
+        "Right Bank Bordeaux",
+        "Merlot",
+        "Plush and dry, with smooth tannins and oak aging.",
+        [0.77, 0.02, 0.3, 0.55, 0.8, 0.45],
+    ),
+    classic(
+        "Chablis",
+        "Chardonnay",
+        "Steely and dry, with high acidity and little or no oak.",
+        [0.68, 0.02, 0.85, 0.1, 0.05, 0.02],
+    ),
+    classic(
+        "Sancerre",
+        "Sauvignon Blanc",
+        "Crisp, dry, and aromatic; fermented cool and drunk young.",
+        [0.64, 0.03, 0.85, 0.1, 0.0, 0.01],
+    ),
+    classic(
+        "Chianti Classico",
+        "Sangiovese",
+        "Savory and dry, with high tannins and a long aging in large casks.",
+        [0.77, 0.02, 0.55, 0.8, 0.8, 0.3],
+    ),
+    classic(
+        "Barossa Shiraz",
+        "Syrah",
+        "Rich, ripe, and dry, from a warm climate, aged in oak.",
+        [0.86, 0.04, 0.3, 0.55, 0.75, 0.7],
+    ),
+    classic(
+        "Lodi Old Vine Zinfandel",
+        "Zinfandel",
+        "Big, jammy, and just off-dry, with spicy tannins.",
+        [0.91, 0.06, 0.35, 0.7, 0.5, 0.45],
+    ),
+];
+
+/// Looks a benchmark up by name, ignoring case.
+pub fn benchmark(name: &str) -> Option<&'static Benchmark> {
+    BENCHMARKS
+        .iter()
+        .find(|benchmark| benchmark.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Every benchmark and how alike the wine is to it, most alike first.
+pub fn rank(result: &SimulationResult) -> Vec<(&'static Benchmark, f64)> {
+    let profile = Profile::of(result);
+    let mut ranked: Vec<_> = BENCHMARKS
+        .iter()
+        .map(|benchmark| (benchmark, profile.similarity(&benchmark.profile)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
@@ -0,0 +1,93 @@
+//! How full a wine feels in the mouth. Alcohol does most of it, but sugar,
+//! glycerol from the yeast, and time on the lees or in oak all add weight,
+//! so an off-dry wine or one aged sur lie can feel fuller than its ABV.
+//! Body is measured as the % ABV of a dry, unoaked wine that would feel as
+//! full, so the descriptor scales' alcohol bands still apply.
+
+use crate::engine::{
+    AMBIENT_YEAST, Inoculation, Material, SimulationInput, aging_vessel, container, lees_contact,
+    yeast_strain,
+};
+
+/// Glycerol in g/L an average yeast makes for each % ABV at 20 °C from a
+/// must of ordinary sugar.
+pub const GLYCEROL_PER_ABV: f64 = 0.6;
+/// Share more glycerol, or less, for each °C the fermentation runs above
+/// or below 20 °C, and the furthest it goes either way.
+const GLYCEROL_PER_DEGREE: f64 = 0.03;
+const MAX_GLYCEROL_SHIFT: f64 = 0.3;
+/// Must sugar in g/L past which the yeast are under osmotic stress and make
+/// glycerol to cope, the share more for each g/L past it, and the most.
+const OSMOTIC_SUGAR: f64 = 220.0;
+const GLYCEROL_PER_OSMOTIC_SUGAR: f64 = 0.004;
+const MAX_OSMOTIC_SHIFT: f64 = 0.4;
+/// Glycerol from native yeast, and the boost a non-Saccharomyces co-pitch
+/// gives, relative to an average wine yeast.
+const AMBIENT_GLYCEROL: f64 = 1.1;
+const NON_SACCHAROMYCES_GLYCEROL: f64 = 1.2;
+
+/// Body, in % ABV, added by each g/L of residual sugar.
+const SUGAR_BODY: f64 = 0.05;
+/// Body added by each g/L of glycerol beyond what the alcohol brings.
+const GLYCEROL_BODY: f64 = 0.4;
+/// Body added by each month on the lees, as weighted by `lees_contact`,
+/// and the most it adds.
+const LEES_BODY: f64 = 0.1;
+const MAX_LEES_BODY: f64 = 1.0;
+/// Body added by each month in oak, and the most it adds.
+const OAK_BODY: f64 = 0.05;
+const MAX_OAK_BODY: f64 = 0.75;
+
+/// Days in a month in oak.
+const DAYS_PER_MONTH: f64 = 30.0;
+
+/// Glycerol the yeast of `input` make, relative to an average wine yeast.
+pub fn strain_glycerol(input: &SimulationInput) -> f64 {
+    let strain = if input.yeast.trim().eq_ignore_ascii_case(AMBIENT_YEAST) {
+        AMBIENT_GLYCEROL
+    } else {
+        yeast_strain(&input.yeast).map_or(1.0, |strain| strain.glycerol)
+    };
+    if input.inoculation == Inoculation::NonSaccharomyces {
+        strain * NON_SACCHAROMYCES_GLYCEROL
+    } else {
+        strain
+    }
+}
+
+/// Glycerol in g/L from fermenting a must of `sugar` g/L to `abv` at
+/// `mean_temperature` in °C, with yeast making `strain_glycerol` times an
+/// average yeast's. Warm fermentations and sugary musts make more.
+pub fn glycerol(abv: f64, mean_temperature: f64, sugar: f64, strain_glycerol: f64) -> f64 {
+    let warmth = ((mean_temperature - 20.0) * GLYCEROL_PER_DEGREE)
+        .clamp(-MAX_GLYCEROL_SHIFT, MAX_GLYCEROL_SHIFT);
+    let osmotic =
+        ((sugar - OSMOTIC_SUGAR).max(0.0) * GLYCEROL_PER_OSMOTIC_SUGAR).min(MAX_OSMOTIC_SHIFT);
+    abv * GLYCEROL_PER_ABV * strain_glycerol * (1.0 + warmth) * (1.0 + osmotic)
+}
+
+fn is_oak(name: &str) -> bool {
+    container(name).is_some_and(|container| container.material == Material::Oak)
+}
+
+/// Months the wine spends in oak, fermenting or aging.
+pub fn oak_months(input: &SimulationInput) -> f64 {
+    let mut months = 0.0;
+    if is_oak(&input.container_type) {
+        months += input.fermentation_days as f64 / DAYS_PER_MONTH;
+    }
+    if is_oak(aging_vessel(input)) {
+        months += input.aging_months as f64;
+    }
+    months
+}
+
+/// The body of a wine at `abv` with `residual_sugar` and `glycerol` in g/L,
+/// in % ABV of a dry, unoaked wine as full.
+pub fn body_index(input: &SimulationInput, abv: f64, residual_sugar: f64, glycerol: f64) -> f64 {
+    let extra_glycerol = glycerol - abv * GLYCEROL_PER_ABV;
+    abv + residual_sugar * SUGAR_BODY
+        + extra_glycerol * GLYCEROL_BODY
+        + (lees_contact(input) * LEES_BODY).min(MAX_LEES_BODY)
+        + (oak_months(input) * OAK_BODY).min(MAX_OAK_BODY)
+}
@@ -0,0 +1,70 @@
+//! "What if I had…" branches: a plan forked on a given day and changed from
+//! there on, to set against the plan as it was.
+
+use crate::engine::{
+    FermentationState, Intervention, InterventionKind, SimulationError, SimulationInput,
+    fermentation_curve, timeline,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+    pub fork_day: u32,
+    pub input: SimulationInput,
+}
+
+impl Branch {
+    /// Forks `input` on `fork_day`, with no changes yet.
+    pub fn fork(input: &SimulationInput, fork_day: u32) -> Self {
+        // Write excursions out as temperature changes so later ones can be
+        // replaced.
+        let interventions = timeline(&SimulationInput {
+            restart_day: None,
+            ..input.clone()
+        });
+        Branch {
+            fork_day,
+            input: SimulationInput {
+                excursions: Vec::new(),
+                interventions,
+                ..input.clone()
+            },
+        }
+    }
+
+    /// Holds `temperature` from the fork on, in place of any later changes.
+    pub fn set_temperature(&mut self, temperature: f64) {
+        let fork_day = self.fork_day;
+        self.input.interventions.retain(|intervention| {
+            intervention.kind != InterventionKind::Temperature || intervention.day < fork_day
+        });
+        self.add(InterventionKind::Temperature, temperature);
+    }
+
+    /// Adds an intervention on the fork day.
+    pub fn add(&mut self, kind: InterventionKind, amount: f64) {
+        self.input.interventions.push(Intervention {
+            day: self.fork_day,
+            kind,
+            amount,
+        });
+        self.input
+            .interventions
+            .sort_by_key(|intervention| intervention.day);
+    }
+
+    /// What the branch changes, e.g. "From day 5: Temperature 14.0 °C".
+    pub fn label(&self) -> String {
+        let changes: Vec<String> = self
+            .input
+            .interventions
+            .iter()
+            .filter(|intervention| intervention.day == self.fork_day)
+            .map(ToString::to_string)
+            .collect();
+        format!("From day {}: {}", self.fork_day, changes.join(", "))
+    }
+
+    pub fn curve(&self) -> Result<Vec<FermentationState>, SimulationError> {
+        fermentation_curve(&self.input)
+    }
+}
@@ -0,0 +1,597 @@
+//! Cellar arithmetic that sits alongside the simulation.
+
+use std::fmt;
+
+use crate::engine::is_red_grape;
+
+/// Calibration temperature of most modern hydrometers, in °C.
+pub const HYDROMETER_CALIBRATION_TEMPERATURE: f64 = 20.0;
+
+fn celsius_to_fahrenheit(celsius: f64) -> f64 {
+    celsius * 9.0 / 5.0 + 32.0
+}
+
+// Relative density of water at `fahrenheit`, from the usual cubic fit.
+fn water_density_factor(fahrenheit: f64) -> f64 {
+    1.00130346 - 0.000134722124 * fahrenheit + 0.00000204052596 * fahrenheit.powi(2)
+        - 0.00000000232820948 * fahrenheit.powi(3)
+}
+
+/// Corrects a hydrometer `reading` taken at `sample_temperature` °C for a
+/// hydrometer calibrated at `calibration_temperature` °C.
+pub fn corrected_gravity(
+    reading: f64,
+    sample_temperature: f64,
+    calibration_temperature: f64,
+) -> f64 {
+    reading * water_density_factor(celsius_to_fahrenheit(sample_temperature))
+        / water_density_factor(celsius_to_fahrenheit(calibration_temperature))
+}
+
+/// Fraction of the wine left behind on the gross lees at the first racking.
+pub const LEES_LOSS: f64 = 0.05;
+/// Fraction lost at each later racking to fine lees and transfer.
+pub const RACKING_LOSS: f64 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BottleFormat {
+    Half,
+    #[default]
+    Standard,
+    Magnum,
+}
+
+impl BottleFormat {
+    pub const ALL: [BottleFormat; 3] = [
+        BottleFormat::Half,
+        BottleFormat::Standard,
+        BottleFormat::Magnum,
+    ];
+
+    /// Fill volume in liters; the bottle leaves the usual headspace above it.
+    pub fn volume(self) -> f64 {
+        match self {
+            BottleFormat::Half => 0.375,
+            BottleFormat::Standard => 0.75,
+            BottleFormat::Magnum => 1.5,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BottleFormat::Half => "375 mL",
+            BottleFormat::Standard => "750 mL",
+            BottleFormat::Magnum => "Magnum (1.5 L)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BottlingPlan {
+    /// Liters left to bottle after lees and racking losses.
+    pub wine_volume: f64,
+    pub bottles: u32,
+    pub closures: u32,
+    /// Liters short of filling one more bottle.
+    pub leftover: f64,
+}
+
+/// Bottles and closures for `volume` liters of new wine racked off its
+/// gross lees and then `rackings` more times before bottling.
+pub fn bottling_plan(volume: f64, format: BottleFormat, rackings: u32) -> BottlingPlan {
+    let wine_volume =
+        volume.max(0.0) * (1.0 - LEES_LOSS) * (1.0 - RACKING_LOSS).powi(rackings as i32);
+    let bottles = (wine_volume / format.volume()).floor() as u32;
+    BottlingPlan {
+        wine_volume,
+        bottles,
+        closures: bottles,
+        leftover: wine_volume - bottles as f64 * format.volume(),
+    }
+}
+
+/// Press efficiency a small basket press reaches on average.
+pub const DEFAULT_PRESS_EFFICIENCY: f64 = 0.85;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrapeColor {
+    #[default]
+    Red,
+    White,
+}
+
+impl GrapeColor {
+    pub const ALL: [GrapeColor; 2] = [GrapeColor::Red, GrapeColor::White];
+
+    /// Liters of juice in a kilogram of grapes at a perfect pressing. Reds
+    /// press after fermenting on the skins, which gives up more liquid.
+    fn juice_per_kg(self) -> f64 {
+        match self {
+            GrapeColor::Red => 0.80,
+            GrapeColor::White => 0.75,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GrapeColor::Red => "Red",
+            GrapeColor::White => "White",
+        }
+    }
+}
+
+/// Expected liters of juice from `grapes` kilograms pressed at
+/// `press_efficiency`, the fraction of the juice the press gets out.
+pub fn juice_yield(grapes: f64, color: GrapeColor, press_efficiency: f64) -> f64 {
+    grapes.max(0.0) * color.juice_per_kg() * press_efficiency.clamp(0.0, 1.0)
+}
+
+/// Most that water and sugar may make up of an ameliorated must under US
+/// federal rules (27 CFR 24.178).
+pub const AMELIORATION_LIMIT: f64 = 0.35;
+/// Titratable acidity in g/L below which US rules don't allow amelioration.
+pub const MIN_AMELIORATED_ACIDITY: f64 = 5.0;
+/// Share of water past which the wine starts to taste noticeably thin.
+const FLAVOR_DILUTION_SHARE: f64 = 0.10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DilutionTarget {
+    /// Sugar in g/L.
+    Sugar(f64),
+    /// Titratable acidity in g/L.
+    Acidity(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DilutionWarning {
+    OverLegalLimit,
+    AcidityBelowMinimum,
+    FlavorDilution,
+}
+
+impl fmt::Display for DilutionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DilutionWarning::OverLegalLimit => write!(
+                f,
+                "Water would be over {:.0}% of the must, past the US legal limit; many other countries do not allow adding water at all.",
+                AMELIORATION_LIMIT * 100.0
+            ),
+            DilutionWarning::AcidityBelowMinimum => write!(
+                f,
+                "Acidity would drop below {:.1} g/L, the least US rules allow after amelioration.",
+                MIN_AMELIORATED_ACIDITY
+            ),
+            DilutionWarning::FlavorDilution => write!(
+                f,
+                "More than {:.0}% water will noticeably thin the flavor and color.",
+                FLAVOR_DILUTION_SHARE * 100.0
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dilution {
+    /// Liters of water to add.
+    pub water: f64,
+    /// Liters of must after the addition.
+    pub volume: f64,
+    pub sugar_content: f64,
+    pub acidity: Option<f64>,
+    pub warnings: Vec<DilutionWarning>,
+}
+
+/// Water to bring `volume` liters of must with `sugar_content` g/L sugar and
+/// `acidity` g/L TA down to `target`, or `None` if the must is already there
+/// (or the target needs the acidity, which isn't given).
+pub fn dilution(
+    volume: f64,
+    sugar_content: f64,
+    acidity: Option<f64>,
+    target: DilutionTarget,
+) -> Option<Dilution> {
+    let (current, target) = match target {
+        DilutionTarget::Sugar(target) => (sugar_content, target),
+        DilutionTarget::Acidity(target) => (acidity?, target),
+    };
+    if volume <= 0.0 || target <= 0.0 || current <= target {
+        return None;
+    }
+
+    let factor = target / current;
+    let water = volume * (current / target - 1.0);
+    let water_share = water / (volume + water);
+    let acidity = acidity.map(|acidity| acidity * factor);
+    let mut warnings = Vec::new();
+    if water_share > AMELIORATION_LIMIT {
+        warnings.push(DilutionWarning::OverLegalLimit);
+    }
+    if acidity.is_some_and(|acidity| acidity < MIN_AMELIORATED_ACIDITY) {
+        warnings.push(DilutionWarning::AcidityBelowMinimum);
+    }
+    if water_share > FLAVOR_DILUTION_SHARE {
+        warnings.push(DilutionWarning::FlavorDilution);
+    }
+    Some(Dilution {
+        water,
+        volume: volume + water,
+        sugar_content: sugar_content * factor,
+        acidity,
+        warnings,
+    })
+}
+
+/// Grams per liter of sucrose that ferment to one volume of CO2.
+pub const SUGAR_PER_CO2_VOLUME: f64 = 3.8;
+/// Most pressure a still-wine bottle should hold, in bar.
+pub const STILL_BOTTLE_PRESSURE: f64 = 2.0;
+/// Most pressure a sparkling wine bottle is rated for, in bar.
+const SPARKLING_BOTTLE_PRESSURE: f64 = 6.5;
+
+/// CO2 still dissolved in wine last held at `temperature` °C, in volumes.
+pub fn residual_co2(temperature: f64) -> f64 {
+    let fahrenheit = celsius_to_fahrenheit(temperature);
+    (3.0378 - 0.050062 * fahrenheit + 0.00026555 * fahrenheit.powi(2)).max(0.0)
+}
+
+/// Gauge pressure in bar of wine carbonated to `volumes` of CO2, at cellar
+/// temperature.
+pub fn co2_pressure(volumes: f64) -> f64 {
+    (volumes - 1.0).max(0.0)
+}
+
+/// Volumes of CO2 for a gauge pressure in bar; the inverse of `co2_pressure`.
+pub fn co2_volumes(pressure: f64) -> f64 {
+    pressure + 1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrimingWarning {
+    /// The wine already holds more sugar than the target needs; bottle it
+    /// once the sugar has fermented down to `bottle_at` g/L.
+    ResidualSugarTooHigh {
+        bottle_at: f64,
+    },
+    NeedsSparklingBottles,
+    OverBottleRating,
+}
+
+impl fmt::Display for PrimingWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimingWarning::ResidualSugarTooHigh { bottle_at } => write!(
+                f,
+                "The residual sugar alone overshoots the target; let it ferment down to {:.1} g/L before bottling.",
+                bottle_at
+            ),
+            PrimingWarning::NeedsSparklingBottles => write!(
+                f,
+                "Over {:.0} bar needs sparkling wine bottles and crown caps or wired corks.",
+                STILL_BOTTLE_PRESSURE
+            ),
+            PrimingWarning::OverBottleRating => write!(
+                f,
+                "Over {:.1} bar is more than sparkling wine bottles are rated for.",
+                SPARKLING_BOTTLE_PRESSURE
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Priming {
+    /// Sucrose to add in g/L.
+    pub sugar: f64,
+    /// Volumes of CO2 once the sugar has fermented.
+    pub volumes: f64,
+    /// Gauge pressure in bar once the sugar has fermented.
+    pub pressure: f64,
+    pub warnings: Vec<PrimingWarning>,
+}
+
+/// Tirage or priming sugar to carbonate wine to `target_volumes` of CO2. The
+/// wine was last held at `temperature` °C and still has `residual_sugar` g/L
+/// of fermentable sugar, as in a pét-nat bottled mid-ferment.
+pub fn priming(target_volumes: f64, temperature: f64, residual_sugar: f64) -> Priming {
+    let dissolved = residual_co2(temperature);
+    let needed = ((target_volumes - dissolved) * SUGAR_PER_CO2_VOLUME).max(0.0);
+    let residual_sugar = residual_sugar.max(0.0);
+    let sugar = (needed - residual_sugar).max(0.0);
+    let volumes = dissolved + (residual_sugar + sugar) / SUGAR_PER_CO2_VOLUME;
+    let pressure = co2_pressure(volumes);
+
+    let mut warnings = Vec::new();
+    if residual_sugar > needed {
+        warnings.push(PrimingWarning::ResidualSugarTooHigh { bottle_at: needed });
+    }
+    if pressure > SPARKLING_BOTTLE_PRESSURE {
+        warnings.push(PrimingWarning::OverBottleRating);
+    } else if pressure > STILL_BOTTLE_PRESSURE {
+        warnings.push(PrimingWarning::NeedsSparklingBottles);
+    }
+    Priming {
+        sugar,
+        volumes,
+        pressure,
+        warnings,
+    }
+}
+
+/// Fraction of potassium metabisulfite's weight released as SO2.
+pub const METABISULFITE_SO2_FRACTION: f64 = 0.576;
+/// pKa of the molecular to bisulfite SO2 equilibrium in wine.
+const SO2_PKA: f64 = 1.81;
+/// Free SO2 in mg/L past which sulfite starts to be smelled and tasted.
+const MAX_PRACTICAL_FREE_SO2: f64 = 50.0;
+
+impl GrapeColor {
+    /// Molecular SO2 in mg/L usually kept in a finished wine; tannin helps
+    /// protect reds, so they need less.
+    pub fn molecular_so2_target(self) -> f64 {
+        match self {
+            GrapeColor::Red => 0.5,
+            GrapeColor::White => 0.8,
+        }
+    }
+}
+
+/// Free SO2 in mg/L that gives `molecular` mg/L molecular SO2 at `ph`.
+pub fn free_so2_for_molecular(molecular: f64, ph: f64) -> f64 {
+    molecular * (1.0 + 10f64.powf(ph - SO2_PKA))
+}
+
+/// Molecular SO2 in mg/L from `free_so2` mg/L at `ph`; the inverse of
+/// `free_so2_for_molecular`.
+pub fn molecular_so2(free_so2: f64, ph: f64) -> f64 {
+    free_so2 / (1.0 + 10f64.powf(ph - SO2_PKA))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SulfiteAddition {
+    /// Free SO2 in mg/L to reach the molecular target.
+    pub free_so2_target: f64,
+    /// Grams of potassium metabisulfite to add.
+    pub metabisulfite: f64,
+}
+
+impl SulfiteAddition {
+    /// Whether the target can be reached without the sulfite showing;
+    /// high-pH wines may need acid first.
+    pub fn is_practical(&self) -> bool {
+        self.free_so2_target <= MAX_PRACTICAL_FREE_SO2
+    }
+}
+
+/// Potassium metabisulfite to bring `volume` liters of wine at `ph` with
+/// `free_so2` mg/L free SO2 up to `molecular_target` mg/L molecular SO2.
+pub fn sulfite_addition(
+    volume: f64,
+    ph: f64,
+    free_so2: f64,
+    molecular_target: f64,
+) -> SulfiteAddition {
+    let free_so2_target = free_so2_for_molecular(molecular_target, ph);
+    let so2 = (free_so2_target - free_so2).max(0.0) * volume.max(0.0) / 1000.0;
+    SulfiteAddition {
+        free_so2_target,
+        metabisulfite: so2 / METABISULFITE_SO2_FRACTION,
+    }
+}
+
+/// Months in a new barrel that a usual dose of oak, fully extracted, tastes
+/// like.
+const BARREL_MONTHS_PER_TYPICAL_DOSE: f64 = 12.0;
+/// Oak character past this many new-barrel months overwhelms the fruit.
+pub const OVER_OAKED_BARREL_MONTHS: f64 = 24.0;
+
+/// Oak pieces that give barrel character to wine in steel or glass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OakAdjunct {
+    #[default]
+    Chips,
+    Cubes,
+    Spirals,
+    Staves,
+}
+
+impl OakAdjunct {
+    pub const ALL: [OakAdjunct; 4] = [
+        OakAdjunct::Chips,
+        OakAdjunct::Cubes,
+        OakAdjunct::Spirals,
+        OakAdjunct::Staves,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OakAdjunct::Chips => "Chips",
+            OakAdjunct::Cubes => "Cubes",
+            OakAdjunct::Spirals => "Spirals",
+            OakAdjunct::Staves => "Staves",
+        }
+    }
+
+    /// Days until the wood has given up nearly all its flavor. Thin chips
+    /// are spent in a couple of weeks; thick staves take months, which
+    /// integrates the oak more like a barrel does.
+    pub fn extraction_days(self) -> u32 {
+        match self {
+            OakAdjunct::Chips => 14,
+            OakAdjunct::Spirals => 42,
+            OakAdjunct::Cubes => 60,
+            OakAdjunct::Staves => 90,
+        }
+    }
+
+    /// Dose in g/L for a moderate oak character.
+    pub fn typical_dosage(self) -> f64 {
+        match self {
+            OakAdjunct::Chips => 2.0,
+            OakAdjunct::Spirals => 1.5,
+            OakAdjunct::Cubes => 2.5,
+            OakAdjunct::Staves => 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OakAddition {
+    /// Grams of oak to add.
+    pub grams: f64,
+    /// Fraction of the wood's flavor given up after the contact time.
+    pub extracted: f64,
+    /// Months in a new barrel the oak character is roughly equal to.
+    pub barrel_months: f64,
+}
+
+impl OakAddition {
+    pub fn is_over_oaked(&self) -> bool {
+        self.barrel_months > OVER_OAKED_BARREL_MONTHS
+    }
+}
+
+/// Oak `adjunct` dosed at `dosage` g/L into `volume` liters of wine and left
+/// in for `contact_days`.
+pub fn oak_addition(
+    volume: f64,
+    adjunct: OakAdjunct,
+    dosage: f64,
+    contact_days: u32,
+) -> OakAddition {
+    let dosage = dosage.max(0.0);
+    // About 95% is out by the adjunct's extraction time.
+    let extracted =
+        1.0 - (-3.0 * f64::from(contact_days) / f64::from(adjunct.extraction_days())).exp();
+    OakAddition {
+        grams: dosage * volume.max(0.0),
+        extracted,
+        barrel_months: dosage / adjunct.typical_dosage()
+            * extracted
+            * BARREL_MONTHS_PER_TYPICAL_DOSE,
+    }
+}
+
+/// Heat-unstable protein in mg/L each g/hL of bentonite takes out.
+const PROTEIN_PER_BENTONITE: f64 = 2.5;
+/// Heat-unstable protein in mg/L a white can keep and still pass a heat test.
+pub const HEAT_STABLE_PROTEIN: f64 = 20.0;
+/// Storage temperature in °C from which a wine near the limit starts to haze.
+pub const WARM_STORAGE_TEMPERATURE: f64 = 25.0;
+/// Bentonite past this multiple of the dose needed strips aroma and body.
+const OVER_FINING_FACTOR: f64 = 1.5;
+
+/// Heat-unstable protein in mg/L a young wine from `grape` usually carries.
+/// Aromatic whites are the worst; reds have none, as their tannins bind it
+/// during fermentation.
+pub fn grape_protein(grape: &str) -> f64 {
+    if is_red_grape(grape) {
+        return 0.0;
+    }
+    match grape.trim().to_lowercase().as_str() {
+        "sauvignon blanc" | "muscat" | "gewürztraminer" | "gewurztraminer" => 200.0,
+        "chardonnay" | "chenin blanc" | "viognier" => 140.0,
+        "riesling" | "pinot grigio" | "pinot gris" => 100.0,
+        _ => 120.0,
+    }
+}
+
+/// Highest protein in mg/L that stays clear in bottle at `storage_temperature`.
+fn protein_limit(storage_temperature: f64) -> f64 {
+    if storage_temperature >= WARM_STORAGE_TEMPERATURE {
+        HEAT_STABLE_PROTEIN / 2.0
+    } else {
+        HEAT_STABLE_PROTEIN
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FiningWarning {
+    /// Too much protein is left for any cellar; `add` more g/hL of
+    /// bentonite.
+    HazeRisk {
+        add: f64,
+    },
+    /// Clear in a cool cellar, but likely to haze stored warm unless `add`
+    /// more g/hL of bentonite goes in.
+    WarmStorageHaze {
+        add: f64,
+    },
+    OverFined,
+}
+
+impl fmt::Display for FiningWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FiningWarning::HazeRisk { add } => write!(
+                f,
+                "The wine will likely throw a protein haze in bottle; add another {:.0} g/hL of bentonite.",
+                add
+            ),
+            FiningWarning::WarmStorageHaze { add } => write!(
+                f,
+                "Stored above {:.0}°C the wine may haze in bottle; add another {:.0} g/hL of bentonite or keep it cool.",
+                WARM_STORAGE_TEMPERATURE, add
+            ),
+            FiningWarning::OverFined => write!(
+                f,
+                "Much more bentonite than the wine needs strips aroma and body."
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BentoniteFining {
+    /// Grams of bentonite to add.
+    pub grams: f64,
+    /// Heat-unstable protein left in mg/L.
+    pub residual_protein: f64,
+    /// Dose in g/hL that makes the wine stable where it will be stored.
+    pub needed: f64,
+    pub warnings: Vec<FiningWarning>,
+}
+
+impl BentoniteFining {
+    pub fn is_heat_stable(&self) -> bool {
+        !self.warnings.iter().any(|warning| {
+            matches!(
+                warning,
+                FiningWarning::HazeRisk { .. } | FiningWarning::WarmStorageHaze { .. }
+            )
+        })
+    }
+}
+
+/// Bentonite dosed at `dosage` g/hL into `volume` liters of wine from
+/// `grape`, which will be kept at `storage_temperature` °C once bottled.
+pub fn bentonite_fining(
+    volume: f64,
+    grape: &str,
+    dosage: f64,
+    storage_temperature: f64,
+) -> BentoniteFining {
+    let dosage = dosage.max(0.0);
+    let protein = grape_protein(grape);
+    let residual_protein = (protein - dosage * PROTEIN_PER_BENTONITE).max(0.0);
+    let limit = protein_limit(storage_temperature);
+    let needed = (protein - limit).max(0.0) / PROTEIN_PER_BENTONITE;
+
+    let mut warnings = Vec::new();
+    let add = needed - dosage;
+    if residual_protein > HEAT_STABLE_PROTEIN {
+        warnings.push(FiningWarning::HazeRisk { add });
+    } else if residual_protein > limit {
+        warnings.push(FiningWarning::WarmStorageHaze { add });
+    }
+    if dosage > 0.0 && dosage > needed * OVER_FINING_FACTOR {
+        warnings.push(FiningWarning::OverFined);
+    }
+    BentoniteFining {
+        // g/hL over 100 L/hL.
+        grams: dosage * volume.max(0.0) / 100.0,
+        residual_protein,
+        needed,
+        warnings,
+    }
+}
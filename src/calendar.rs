@@ -0,0 +1,82 @@
+//! iCalendar export of batch schedules, so cellar tasks show up in calendar
+//! apps. Event UIDs come from the batch and task identities, so importing a
+//! re-exported schedule updates the events instead of duplicating them.
+
+use chrono::{NaiveDateTime, TimeDelta};
+
+use crate::cellar::{Batch, Task};
+
+// Content lines longer than this many octets must be folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Appends a content line, folding it onto continuation lines as needed.
+fn push_line(ics: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            ics.push_str("\r\n ");
+            octets = 1;
+        }
+        ics.push(c);
+        octets += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+/// The batch's tasks as all-day events with a reminder on the morning they
+/// are due. `stamp` is the export time in UTC.
+pub fn schedule_to_ical(batch: &Batch, tasks: &[Task], stamp: NaiveDateTime) -> String {
+    let mut ics = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//wine-maker//Cellar Schedule//EN",
+        "CALSCALE:GREGORIAN",
+    ] {
+        push_line(&mut ics, line);
+    }
+    push_line(&mut ics, &format!("X-WR-CALNAME:{}", escape(&batch.name)));
+
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    for task in tasks {
+        let summary = format!("{}: {}", batch.name, task.title);
+        let status = if task.done { "Done." } else { "To do." };
+        for line in [
+            "BEGIN:VEVENT".to_owned(),
+            format!("UID:{}.batch-{}@wine-maker", task.uid, batch.id),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", task.due_on.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (task.due_on + TimeDelta::days(1)).format("%Y%m%d")
+            ),
+            format!("SUMMARY:{}", escape(&summary)),
+            format!("DESCRIPTION:{}", status),
+            "TRANSP:TRANSPARENT".to_owned(),
+        ] {
+            push_line(&mut ics, &line);
+        }
+        if !task.done {
+            // All-day events start at midnight, so this fires at 9 am.
+            for line in [
+                "BEGIN:VALARM".to_owned(),
+                "ACTION:DISPLAY".to_owned(),
+                "TRIGGER:PT9H".to_owned(),
+                format!("DESCRIPTION:{}", escape(&summary)),
+                "END:VALARM".to_owned(),
+            ] {
+                push_line(&mut ics, &line);
+            }
+        }
+        push_line(&mut ics, "END:VEVENT");
+    }
+    push_line(&mut ics, "END:VCALENDAR");
+    ics
+}
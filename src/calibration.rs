@@ -0,0 +1,247 @@
+//! Fitting the fermentation kinetics to one's own cellar. The rate constant
+//! at 20 °C, its Q10, and the sugar that makes 1% ABV are refitted by least
+//! squares to the readings logged on past batches, and the fit can be
+//! installed in place of the standard constants for every later simulation.
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::cellar::{Batch, Measurement};
+use crate::engine::{
+    K_REF, MAX_YEAST_TEMPERATURE, MIN_YEAST_TEMPERATURE, Q10, REF_TEMPERATURE, SG_PER_SUGAR,
+    SUGAR_PER_ABV, fermentation_state_calibrated,
+};
+
+/// Fewest readings a fit is made from, one per constant.
+pub const MIN_READINGS: usize = 3;
+/// How far each constant may be fitted or set, low and high.
+pub const K_REF_RANGE: (f64, f64) = (0.02, 1.0);
+pub const Q10_RANGE: (f64, f64) = (1.0, 4.0);
+pub const SUGAR_PER_ABV_RANGE: (f64, f64) = (15.0, 19.0);
+/// First steps the search takes in each constant, and the smallest step in
+/// the rate constant before it stops.
+const FIRST_STEPS: [f64; 3] = [0.05, 0.5, 0.5];
+const LAST_K_REF_STEP: f64 = 1e-5;
+const MAX_ITERATIONS: usize = 500;
+
+/// The constants the fermentation kinetics run on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    /// Rate constant per day at 20 °C.
+    pub k_ref: f64,
+    /// How many times faster the fermentation runs for each 10 °C warmer.
+    pub q10: f64,
+    /// Sugar in g/L that makes 1% ABV.
+    pub sugar_per_abv: f64,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration {
+            k_ref: K_REF,
+            q10: Q10,
+            sugar_per_abv: SUGAR_PER_ABV,
+        }
+    }
+}
+
+impl Calibration {
+    /// Rate constant per day at `temperature`.
+    pub fn rate_constant(&self, temperature: f64) -> f64 {
+        self.k_ref * self.q10.powf((temperature - REF_TEMPERATURE) / 10.0)
+    }
+
+    fn params(&self) -> [f64; 3] {
+        [self.k_ref, self.q10, self.sugar_per_abv]
+    }
+
+    fn from_params([k_ref, q10, sugar_per_abv]: [f64; 3]) -> Self {
+        Calibration {
+            k_ref: k_ref.clamp(K_REF_RANGE.0, K_REF_RANGE.1),
+            q10: q10.clamp(Q10_RANGE.0, Q10_RANGE.1),
+            sugar_per_abv: sugar_per_abv.clamp(SUGAR_PER_ABV_RANGE.0, SUGAR_PER_ABV_RANGE.1),
+        }
+    }
+}
+
+/// A calibration kept under a name, such as "basement cellar".
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelProfile {
+    pub name: String,
+    pub calibration: Calibration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    /// Only this many readings were logged after the start of a batch.
+    TooFewReadings(usize),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::TooFewReadings(readings) => write!(
+                f,
+                "A calibration needs at least {} readings taken after batches started; there are {}.",
+                MIN_READINGS, readings
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// A calibration fitted to past batches, and how much closer it comes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fit {
+    pub calibration: Calibration,
+    pub batches: usize,
+    pub readings: usize,
+    /// RMS error over the readings in g/L of sugar, with the standard
+    /// constants and with the fitted ones.
+    pub standard_error: f64,
+    pub fitted_error: f64,
+}
+
+/// A reading the fit is made to.
+struct Reading<'a> {
+    batch: &'a Batch,
+    day: u32,
+    measurement: &'a Measurement,
+}
+
+impl Reading<'_> {
+    /// How far off `calibration` predicts the reading, in g/L of sugar.
+    /// Hydrometer readings are compared as gravity, where the sugar that
+    /// makes 1% ABV shows.
+    fn error(&self, calibration: &Calibration) -> f64 {
+        let input = &self.batch.input;
+        let k = calibration.rate_constant(input.temperature) * input.inoculation.rate_factor();
+        let predicted = fermentation_state_calibrated(input, self.day, k, calibration);
+        match self.measurement.specific_gravity {
+            Some(specific_gravity) => {
+                (predicted.specific_gravity() - specific_gravity) / SG_PER_SUGAR
+            }
+            None => predicted.residual_sugar - self.measurement.sugar_content,
+        }
+    }
+}
+
+fn rms_error(readings: &[Reading], calibration: &Calibration) -> f64 {
+    let sum: f64 = readings
+        .iter()
+        .map(|reading| reading.error(calibration).powi(2))
+        .sum();
+    (sum / readings.len() as f64).sqrt()
+}
+
+/// Fits the kinetics to the readings logged on `batches` by a compass
+/// search from the standard constants. Q10 can only be fitted from batches
+/// fermented at different temperatures, and the sugar per % ABV only from
+/// hydrometer readings; otherwise they stay near the standard.
+pub fn calibrate(batches: &[(Batch, Vec<Measurement>)]) -> Result<Fit, CalibrationError> {
+    let readings: Vec<Reading> = batches
+        .iter()
+        .filter(|(batch, _)| {
+            (MIN_YEAST_TEMPERATURE..=MAX_YEAST_TEMPERATURE).contains(&batch.input.temperature)
+        })
+        .flat_map(|(batch, measurements)| {
+            measurements.iter().filter_map(move |measurement| {
+                let day = u32::try_from(batch.day_of(measurement.measured_on)).ok()?;
+                (day > 0).then_some(Reading {
+                    batch,
+                    day,
+                    measurement,
+                })
+            })
+        })
+        .collect();
+    if readings.len() < MIN_READINGS {
+        return Err(CalibrationError::TooFewReadings(readings.len()));
+    }
+
+    let standard = Calibration::default();
+    let mut best = standard;
+    let mut best_error = rms_error(&readings, &best);
+    let mut steps = FIRST_STEPS;
+    for _ in 0..MAX_ITERATIONS {
+        let mut improved = false;
+        for (param, step) in steps.iter().enumerate() {
+            for direction in [1.0, -1.0] {
+                let mut params = best.params();
+                params[param] += direction * step;
+                let candidate = Calibration::from_params(params);
+                let error = rms_error(&readings, &candidate);
+                if error < best_error {
+                    best = candidate;
+                    best_error = error;
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            steps = steps.map(|step| step / 2.0);
+            if steps[0] < LAST_K_REF_STEP {
+                break;
+            }
+        }
+    }
+
+    let mut fitted: Vec<&Batch> = readings.iter().map(|reading| reading.batch).collect();
+    fitted.dedup_by_key(|batch| batch.id);
+    Ok(Fit {
+        calibration: best,
+        batches: fitted.len(),
+        readings: readings.len(),
+        standard_error: rms_error(&readings, &standard),
+        fitted_error: best_error,
+    })
+}
+
+static INSTALLED: RwLock<Option<Calibration>> = RwLock::new(None);
+
+thread_local! {
+    /// Constants this thread alone runs on, in place of the installed ones.
+    static THREAD: Cell<Option<Calibration>> = const { Cell::new(None) };
+}
+
+/// Makes every later simulation run on `calibration`, as when the app
+/// starts with one saved in its settings.
+pub fn install(calibration: Calibration) {
+    *INSTALLED.write().unwrap_or_else(|err| err.into_inner()) = Some(calibration);
+}
+
+/// Goes back to the standard constants.
+pub fn uninstall() {
+    *INSTALLED.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// Runs `run` with this thread's simulations on `calibration`, leaving
+/// other threads on theirs, as a server running many models at once needs.
+pub fn with_calibration<T>(calibration: Calibration, run: impl FnOnce() -> T) -> T {
+    let _restore = RestoreOnDrop(THREAD.replace(Some(calibration)));
+    run()
+}
+
+/// Puts back the thread's previous constants when dropped, even if the run
+/// panicked.
+struct RestoreOnDrop(Option<Calibration>);
+
+impl Drop for RestoreOnDrop {
+    fn drop(&mut self) {
+        THREAD.set(self.0);
+    }
+}
+
+/// The constants simulations in this thread run on.
+pub fn installed() -> Calibration {
+    THREAD.get().unwrap_or_else(|| {
+        INSTALLED
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .unwrap_or_default()
+    })
+}
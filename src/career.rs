@@ -0,0 +1,412 @@
+//! Career mode: run a winery over many vintages. Each year rolls weather and
+//! market events, the player makes a few batches from the harvest, and how
+//! good the wines turn out builds the winery's reputation and its prices.
+
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::costs::BOTTLE_VOLUME;
+use crate::dataset::Dataset;
+use crate::engine::{SimulationError, SimulationInput, run_simulation};
+use crate::optimizer::quality;
+use crate::vineyard::{REGIONS, Region, Vintage};
+
+/// The most batches the winery can make from one vintage.
+pub const MAX_BATCHES: usize = 3;
+/// What making a batch costs: fruit, yeast, barrels, and bottles.
+pub const BATCH_COST: f64 = 2500.0;
+const STARTING_CASH: f64 = 10000.0;
+const STARTING_REPUTATION: f64 = 50.0;
+/// Liters a batch makes in an ordinary year.
+const BATCH_LITERS: f64 = 500.0;
+/// Bottle price for a wine scoring `AVERAGE_SCORE` from a winery with a
+/// reputation of `STARTING_REPUTATION`.
+const BASE_BOTTLE_PRICE: f64 = 12.0;
+const AVERAGE_SCORE: f64 = 70.0;
+/// How far the reputation moves towards a vintage's average score.
+const REPUTATION_WEIGHT: f64 = 0.3;
+// How much a season strays from the region's typical one without any event.
+const DEGREE_DAY_SPREAD: f64 = 80.0;
+const RAINFALL_SPREAD: f64 = 0.2;
+
+/// Something that happens in a vintage. A year has at most one weather and
+/// one market event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    Heatwave,
+    CoolSummer,
+    WetHarvest,
+    Drought,
+    Hail,
+    Boom,
+    Glut,
+    CriticVisit,
+}
+
+impl Event {
+    pub const ALL: [Event; 8] = [
+        Event::Heatwave,
+        Event::CoolSummer,
+        Event::WetHarvest,
+        Event::Drought,
+        Event::Hail,
+        Event::Boom,
+        Event::Glut,
+        Event::CriticVisit,
+    ];
+    const WEATHER: [Event; 5] = [
+        Event::Heatwave,
+        Event::CoolSummer,
+        Event::WetHarvest,
+        Event::Drought,
+        Event::Hail,
+    ];
+    const MARKET: [Event; 3] = [Event::Boom, Event::Glut, Event::CriticVisit];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Event::Heatwave => "Heatwave",
+            Event::CoolSummer => "Cool summer",
+            Event::WetHarvest => "Wet harvest",
+            Event::Drought => "Drought",
+            Event::Hail => "Hail",
+            Event::Boom => "Boom",
+            Event::Glut => "Glut",
+            Event::CriticVisit => "Critic visit",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|event| event.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Event::Heatwave => "A hot summer ripens the grapes fast: more sugar, less acid.",
+            Event::CoolSummer => "A cool summer leaves the grapes lean and tart.",
+            Event::WetHarvest => "Rain at harvest swells the berries and thins the sugar.",
+            Event::Drought => "A dry year gives small berries and a short crop.",
+            Event::Hail => "Hail strips the vines and takes much of the crop.",
+            Event::Boom => "Wine is selling well; prices are up.",
+            Event::Glut => "Too much wine on the market; prices are down.",
+            Event::CriticVisit => "A critic tastes this vintage, so it counts double.",
+        }
+    }
+
+    pub fn is_weather(self) -> bool {
+        Self::WEATHER.contains(&self)
+    }
+
+    /// The chance of the event in any year.
+    fn chance(self) -> f64 {
+        match self {
+            Event::Heatwave | Event::CoolSummer | Event::WetHarvest => 0.15,
+            Event::Drought => 0.1,
+            Event::Hail => 0.05,
+            Event::Boom | Event::Glut => 0.15,
+            Event::CriticVisit => 0.1,
+        }
+    }
+
+    fn apply_to(self, vintage: &mut Vintage) {
+        match self {
+            Event::Heatwave => vintage.growing_degree_days += 250.0,
+            Event::CoolSummer => vintage.growing_degree_days -= 250.0,
+            Event::WetHarvest => vintage.harvest_rain += 60.0,
+            Event::Drought => vintage.rainfall *= 0.4,
+            _ => {}
+        }
+    }
+
+    fn yield_factor(self) -> f64 {
+        match self {
+            Event::Drought => 0.7,
+            Event::Hail => 0.5,
+            Event::WetHarvest => 1.1,
+            _ => 1.0,
+        }
+    }
+
+    fn price_factor(self) -> f64 {
+        match self {
+            Event::Boom => 1.3,
+            Event::Glut => 0.75,
+            _ => 1.0,
+        }
+    }
+
+    fn reputation_factor(self) -> f64 {
+        match self {
+            Event::CriticVisit => 2.0,
+            _ => 1.0,
+        }
+    }
+}
+
+// Picks at most one of `events`, each with its own chance.
+fn roll(rng: &mut StdRng, events: &[Event]) -> Option<Event> {
+    let mut draw: f64 = rng.random();
+    for &event in events {
+        if draw < event.chance() {
+            return Some(event);
+        }
+        draw -= event.chance();
+    }
+    None
+}
+
+/// A year's growing season and market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Season {
+    pub year: u32,
+    pub vintage: Vintage,
+    pub events: Vec<Event>,
+}
+
+impl Season {
+    /// How much of an ordinary crop the vines give.
+    pub fn yield_factor(&self) -> f64 {
+        self.events
+            .iter()
+            .map(|event| event.yield_factor())
+            .product()
+    }
+
+    /// How prices compare with an ordinary year.
+    pub fn price_factor(&self) -> f64 {
+        self.events
+            .iter()
+            .map(|event| event.price_factor())
+            .product()
+    }
+
+    fn reputation_weight(&self) -> f64 {
+        let factor: f64 = self
+            .events
+            .iter()
+            .map(|event| event.reputation_factor())
+            .product();
+        (REPUTATION_WEIGHT * factor).min(1.0)
+    }
+}
+
+/// A batch planned for the current vintage.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CareerBatch {
+    pub name: String,
+    pub input: SimulationInput,
+}
+
+/// How a batch turned out and sold.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchReport {
+    pub name: String,
+    /// The quality score out of 100.
+    pub score: f64,
+    pub bottles: u32,
+    pub bottle_price: f64,
+}
+
+impl BatchReport {
+    pub fn revenue(&self) -> f64 {
+        f64::from(self.bottles) * self.bottle_price
+    }
+}
+
+/// A finished vintage.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VintageReport {
+    pub year: u32,
+    pub events: Vec<Event>,
+    pub batches: Vec<BatchReport>,
+    /// The winery's reputation and cash after the vintage sold.
+    pub reputation: f64,
+    pub cash: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CareerError {
+    UnknownRegion(String),
+    TooManyBatches,
+    NotEnoughCash,
+    NoBatches,
+    Simulation(SimulationError),
+}
+
+impl fmt::Display for CareerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CareerError::UnknownRegion(name) => write!(f, "unknown region: {}", name),
+            CareerError::TooManyBatches => {
+                write!(f, "a vintage makes at most {} batches", MAX_BATCHES)
+            }
+            CareerError::NotEnoughCash => write!(f, "not enough cash for another batch"),
+            CareerError::NoBatches => write!(f, "plan at least one batch first"),
+            CareerError::Simulation(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CareerError {}
+
+impl From<SimulationError> for CareerError {
+    fn from(err: SimulationError) -> Self {
+        CareerError::Simulation(err)
+    }
+}
+
+/// A winery and every vintage it has made.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Career {
+    /// One of `vineyard::REGIONS`.
+    pub region: String,
+    /// Decides every season's weather and market.
+    pub seed: u64,
+    /// The vintage being made, from 1.
+    pub year: u32,
+    /// Out of 100.
+    pub reputation: f64,
+    pub cash: f64,
+    pub batches: Vec<CareerBatch>,
+    pub history: Vec<VintageReport>,
+}
+
+impl Career {
+    pub fn new(region: &str, seed: u64) -> Result<Self, CareerError> {
+        let region = find_region(region)?;
+        Ok(Career {
+            region: region.name.to_owned(),
+            seed,
+            year: 1,
+            reputation: STARTING_REPUTATION,
+            cash: STARTING_CASH,
+            batches: Vec::new(),
+            history: Vec::new(),
+        })
+    }
+
+    pub fn region(&self) -> Result<&'static Region, CareerError> {
+        find_region(&self.region)
+    }
+
+    /// This year's season. The same career and year always roll the same.
+    pub fn season(&self) -> Result<Season, CareerError> {
+        let mut vintage = self.region()?.typical;
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(u64::from(self.year)));
+        vintage.growing_degree_days += rng.random_range(-DEGREE_DAY_SPREAD..=DEGREE_DAY_SPREAD);
+        vintage.rainfall *= 1.0 + rng.random_range(-RAINFALL_SPREAD..=RAINFALL_SPREAD);
+        let events: Vec<Event> = [
+            roll(&mut rng, &Event::WEATHER),
+            roll(&mut rng, &Event::MARKET),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        for event in &events {
+            event.apply_to(&mut vintage);
+        }
+        Ok(Season {
+            year: self.year,
+            vintage,
+            events,
+        })
+    }
+
+    /// The region's classic wine made from this year's fruit, to start a
+    /// batch from.
+    pub fn plan(&self) -> Result<SimulationInput, CareerError> {
+        let mut input = SimulationInput::default();
+        self.region()?.apply_to(&mut input);
+        self.season()?.vintage.harvest().apply_to(&mut input);
+        Ok(input)
+    }
+
+    /// Plans a batch. The grape, climate, and sugar come from this year's
+    /// harvest; everything else is the player's choice from `input`.
+    pub fn add_batch(&mut self, name: &str, input: &SimulationInput) -> Result<(), CareerError> {
+        if self.batches.len() >= MAX_BATCHES {
+            return Err(CareerError::TooManyBatches);
+        }
+        if self.cash < BATCH_COST * (self.batches.len() + 1) as f64 {
+            return Err(CareerError::NotEnoughCash);
+        }
+        let fruit = self.plan()?;
+        self.batches.push(CareerBatch {
+            name: name.to_owned(),
+            input: SimulationInput {
+                grape_type: fruit.grape_type,
+                climate: fruit.climate,
+                sugar_content: fruit.sugar_content,
+                ..input.clone()
+            },
+        });
+        Ok(())
+    }
+
+    pub fn remove_batch(&mut self, index: usize) {
+        if index < self.batches.len() {
+            self.batches.remove(index);
+        }
+    }
+
+    /// Whether the winery can no longer pay for a batch.
+    pub fn is_bankrupt(&self) -> bool {
+        self.batches.is_empty() && self.cash < BATCH_COST
+    }
+
+    /// Makes and sells this year's batches, moves the reputation towards
+    /// their average score, and starts the next year. Nothing changes if a
+    /// batch can't be simulated.
+    pub fn finish_vintage(&mut self, wine_data: &Dataset) -> Result<&VintageReport, CareerError> {
+        if self.batches.is_empty() {
+            return Err(CareerError::NoBatches);
+        }
+        let season = self.season()?;
+        let bottles = (BATCH_LITERS * season.yield_factor() / BOTTLE_VOLUME).floor() as u32;
+        let mut batches = Vec::with_capacity(self.batches.len());
+        for batch in &self.batches {
+            let result = run_simulation(&batch.input, wine_data)?;
+            let score = quality(&batch.input, &result).score;
+            batches.push(BatchReport {
+                name: batch.name.clone(),
+                score,
+                bottles,
+                bottle_price: BASE_BOTTLE_PRICE
+                    * season.price_factor()
+                    * (self.reputation / STARTING_REPUTATION)
+                    * (score / AVERAGE_SCORE),
+            });
+        }
+
+        let average = batches.iter().map(|batch| batch.score).sum::<f64>() / batches.len() as f64;
+        self.reputation += (average - self.reputation) * season.reputation_weight();
+        self.cash += batches.iter().map(BatchReport::revenue).sum::<f64>()
+            - BATCH_COST * batches.len() as f64;
+        self.history.push(VintageReport {
+            year: self.year,
+            events: season.events,
+            batches,
+            reputation: self.reputation,
+            cash: self.cash,
+        });
+        self.year += 1;
+        self.batches.clear();
+        Ok(self.history.last().expect("just pushed"))
+    }
+}
+
+fn find_region(name: &str) -> Result<&'static Region, CareerError> {
+    REGIONS
+        .iter()
+        .find(|region| region.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| CareerError::UnknownRegion(name.to_owned()))
+}
@@ -0,0 +1,532 @@
+//! Real ferments tracked against the simulation they were planned with.
+
+use std::fmt;
+
+use chrono::{Months, NaiveDate, TimeDelta};
+
+use crate::engine::{
+    FermentationState, MALOLACTIC_DAYS, SimulationError, SimulationInput, fermentation_state,
+    fermentation_state_at_rate, rate_constant, restart_day, specific_gravity, sugar_per_abv,
+    temperature_on, yeast_tolerance,
+};
+use crate::stage::{STABILIZATION_DAYS, Stage, StageStatus, bottling_day, stages, status};
+
+/// Sugar at or below which a ferment counts as dry, in g/L.
+pub const DRY_SUGAR: f64 = 2.0;
+
+/// How often aging wine is racked off its fine lees.
+pub(crate) const RACKING_INTERVAL_MONTHS: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    pub id: i64,
+    pub name: String,
+    pub started_on: NaiveDate,
+    /// The simulation the batch was planned with.
+    pub input: SimulationInput,
+    pub predicted_abv: Option<f64>,
+    pub notes: String,
+    /// Liters of wine, once known.
+    pub volume: Option<f64>,
+}
+
+/// A reading taken from a real batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub measured_on: NaiveDate,
+    /// Residual sugar in g/L.
+    pub sugar_content: f64,
+    /// The hydrometer reading the sugar was derived from, if any.
+    pub specific_gravity: Option<f64>,
+    /// Must temperature in °C.
+    pub temperature: Option<f64>,
+    pub notes: String,
+}
+
+/// The cellar work after primary fermentation that a schedule is built from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Process {
+    pub malolactic: bool,
+    /// Months of bulk aging between sulfiting and bottling.
+    pub aging_months: u32,
+}
+
+impl Default for Process {
+    fn default() -> Self {
+        Process {
+            malolactic: false,
+            aging_months: 6,
+        }
+    }
+}
+
+/// A dated job on a batch's cellar schedule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    /// Identifies the task within its batch across edits and regeneration,
+    /// so exported calendar events update instead of duplicating.
+    pub uid: String,
+    pub due_on: NaiveDate,
+    pub title: String,
+    pub done: bool,
+}
+
+impl Task {
+    fn new(uid: impl Into<String>, due_on: NaiveDate, title: &str) -> Self {
+        Task {
+            uid: uid.into(),
+            due_on,
+            title: title.to_owned(),
+            done: false,
+        }
+    }
+}
+
+/// One day of a batch's plan, with the last reading logged that day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressPoint {
+    pub planned: FermentationState,
+    pub measured: Option<Measurement>,
+}
+
+/// A batch's outlook with its rate constant refitted to the measurements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Forecast {
+    /// Fitted rate constant per day.
+    pub rate_constant: f64,
+    /// Rate constant the plan assumed from its temperature.
+    pub planned_rate_constant: f64,
+    pub readings: usize,
+    /// First day the ferment is dry or stops at the yeast's alcohol tolerance.
+    pub finish_day: u32,
+    pub finish_date: NaiveDate,
+    pub final_abv: f64,
+}
+
+/// A reading set against the plan for its day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Variance {
+    pub day: u32,
+    pub measured_on: NaiveDate,
+    /// Planned and measured residual sugar in g/L.
+    pub planned_sugar: f64,
+    pub measured_sugar: f64,
+    /// Measured less planned temperature in °C, if the temperature was taken.
+    pub temperature_error: Option<f64>,
+}
+
+impl Variance {
+    /// Measured less planned sugar in g/L; positive when the ferment is
+    /// behind its plan.
+    pub fn sugar_error(&self) -> f64 {
+        self.measured_sugar - self.planned_sugar
+    }
+}
+
+/// How a batch's readings compared with its plan over the ferment, for
+/// judging how well the model fits one's own cellar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarianceReport {
+    /// One per day with a reading, the day's last reading, in order.
+    pub variances: Vec<Variance>,
+    /// Mean sugar error in g/L; positive when the ferment ran behind.
+    pub bias: f64,
+    pub mean_absolute_error: f64,
+    pub rms_error: f64,
+    /// Mean temperature error in °C over the readings that had one.
+    pub temperature_bias: Option<f64>,
+}
+
+impl VarianceReport {
+    /// The reading furthest from the plan.
+    pub fn worst(&self) -> &Variance {
+        self.variances
+            .iter()
+            .max_by(|a, b| a.sugar_error().abs().total_cmp(&b.sugar_error().abs()))
+            .expect("a variance report has readings")
+    }
+}
+
+impl fmt::Display for VarianceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let worst = self.worst();
+        write!(
+            f,
+            "Over {} readings the ferment ran {:.1} g/L {} plan on average (mean absolute error {:.1} g/L, RMS {:.1} g/L); furthest off on day {} by {:+.1} g/L.",
+            self.variances.len(),
+            self.bias.abs(),
+            if self.bias > 0.0 {
+                "behind"
+            } else {
+                "ahead of"
+            },
+            self.mean_absolute_error,
+            self.rms_error,
+            worst.day,
+            worst.sugar_error()
+        )?;
+        if let Some(temperature_bias) = self.temperature_bias {
+            write!(
+                f,
+                " It ran {:.1} °C {} than planned.",
+                temperature_bias.abs(),
+                if temperature_bias > 0.0 {
+                    "warmer"
+                } else {
+                    "cooler"
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Where a batch should be on a given date according to its plan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tracking {
+    /// Day of the ferment; negative before the start date.
+    pub day: i64,
+    pub planned_days: u32,
+    /// The planned state that day, or `None` before the start date.
+    pub planned: Option<FermentationState>,
+    /// The planned stage that day, or `None` before the start date.
+    pub stage: Option<StageStatus>,
+}
+
+/// Where a batch stands on a given day, from its latest reading where
+/// there is one and its plan otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchStatus {
+    /// Day of the ferment; negative before the start date.
+    pub day: i64,
+    /// Days until the ferment finishes, by the forecast once readings allow
+    /// one and by the plan before.
+    pub days_remaining: i64,
+    pub abv: f64,
+    pub residual_sugar: f64,
+    pub specific_gravity: f64,
+    /// The date of the reading the estimate comes from, or `None` when it
+    /// comes from the plan.
+    pub measured_on: Option<NaiveDate>,
+    /// The planned stage, or `None` before the start date.
+    pub stage: Option<Stage>,
+    /// The earliest task not yet done.
+    pub next_task: Option<Task>,
+}
+
+/// A stage of a batch's plan placed on the calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatedStage {
+    pub stage: Stage,
+    pub starts_on: NaiveDate,
+    /// The date the next stage starts, or `None` once bottled.
+    pub ends_on: Option<NaiveDate>,
+}
+
+impl Tracking {
+    /// Whether the batch has started and not yet reached its planned end.
+    pub fn is_active(&self) -> bool {
+        (0..self.planned_days as i64).contains(&self.day)
+    }
+}
+
+impl fmt::Display for Tracking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.planned {
+            None => write!(f, "Not started yet ({} days to go).", -self.day),
+            Some(planned) if self.day > self.planned_days as i64 => write!(
+                f,
+                "Day {}, past the planned {} days; predicted SG {:.3}, predicted ABV {:.1}%.",
+                self.day,
+                self.planned_days,
+                planned.specific_gravity(),
+                planned.abv
+            ),
+            Some(planned) => write!(
+                f,
+                "Today is day {} of {}; predicted SG {:.3}, predicted ABV so far {:.1}%.",
+                self.day,
+                self.planned_days,
+                planned.specific_gravity(),
+                planned.abv
+            ),
+        }
+    }
+}
+
+impl Batch {
+    /// Day of the ferment that `date` falls on; the start date is day 0.
+    pub fn day_of(&self, date: NaiveDate) -> i64 {
+        (date - self.started_on).num_days()
+    }
+
+    /// Climate-adjusted sugar at the start of the ferment in g/L.
+    pub fn initial_sugar(&self) -> f64 {
+        fermentation_state_at_rate(&self.input, 0, 0.0).sugar_content
+    }
+
+    /// The date the plan has the batch bottled.
+    pub fn bottled_on(&self) -> NaiveDate {
+        self.started_on + TimeDelta::days(bottling_day(&self.input).into())
+    }
+
+    /// The planned stages, from the start date to bottling.
+    pub fn timeline(&self) -> Result<Vec<DatedStage>, SimulationError> {
+        let date = |day: u32| self.started_on + TimeDelta::days(day.into());
+        Ok(stages(&self.input)?
+            .into_iter()
+            .map(|span| DatedStage {
+                stage: span.stage,
+                starts_on: date(span.start_day),
+                ends_on: span.end_day.map(date),
+            })
+            .collect())
+    }
+
+    pub fn tracking(&self, date: NaiveDate) -> Result<Tracking, SimulationError> {
+        let day = self.day_of(date);
+        let (planned, stage) = match u32::try_from(day) {
+            Ok(day) => (
+                Some(fermentation_state(&self.input, day)?),
+                Some(status(&self.input, day)?),
+            ),
+            Err(_) => (None, None),
+        };
+        Ok(Tracking {
+            day,
+            planned_days: self.input.fermentation_days,
+            planned,
+            stage,
+        })
+    }
+
+    /// Refits the rate constant to the readings taken after the start date,
+    /// or `None` if there are none that show any fermentation.
+    pub fn forecast(&self, measurements: &[Measurement]) -> Option<Forecast> {
+        let initial_sugar = self.initial_sugar();
+        if initial_sugar <= DRY_SUGAR {
+            return None;
+        }
+
+        // Least squares fit of ln(S0 / S) = k t through the origin.
+        let (sum_ty, sum_tt, readings) = measurements
+            .iter()
+            .filter_map(|measurement| {
+                let day = self.day_of(measurement.measured_on);
+                let sugar = measurement.sugar_content.clamp(0.1, initial_sugar);
+                (day > 0).then(|| (day as f64, (initial_sugar / sugar).ln()))
+            })
+            .fold((0.0, 0.0, 0), |(sum_ty, sum_tt, n), (t, y)| {
+                (sum_ty + t * y, sum_tt + t * t, n + 1)
+            });
+        if readings == 0 || sum_ty <= 0.0 {
+            return None;
+        }
+        let k = sum_ty / sum_tt;
+
+        let finish_sugar =
+            DRY_SUGAR.max(initial_sugar - yeast_tolerance(&self.input) * sugar_per_abv());
+        let finish_day = ((initial_sugar / finish_sugar).ln() / k).ceil() as u32;
+        Some(Forecast {
+            rate_constant: k,
+            planned_rate_constant: rate_constant(self.input.temperature),
+            readings,
+            finish_day,
+            finish_date: self.started_on + TimeDelta::days(finish_day as i64),
+            final_abv: fermentation_state_at_rate(&self.input, finish_day, k).abv,
+        })
+    }
+
+    /// Where the batch stands on `date`, given its readings and schedule.
+    pub fn status(
+        &self,
+        measurements: &[Measurement],
+        tasks: &[Task],
+        date: NaiveDate,
+    ) -> Result<BatchStatus, SimulationError> {
+        let tracking = self.tracking(date)?;
+        let finish_date = match self.forecast(measurements) {
+            Some(forecast) => forecast.finish_date,
+            None => self.started_on + TimeDelta::days(self.input.fermentation_days.into()),
+        };
+        let latest = measurements
+            .iter()
+            .filter(|measurement| measurement.measured_on <= date)
+            .max_by_key(|measurement| measurement.measured_on);
+        let (abv, residual_sugar) = match latest {
+            Some(measurement) => (
+                (self.initial_sugar() - measurement.sugar_content).max(0.0) / sugar_per_abv(),
+                measurement.sugar_content,
+            ),
+            None => {
+                let planned = fermentation_state(&self.input, tracking.day.max(0) as u32)?;
+                (planned.abv, planned.residual_sugar)
+            }
+        };
+        Ok(BatchStatus {
+            day: tracking.day,
+            days_remaining: (finish_date - date).num_days().max(0),
+            abv,
+            residual_sugar,
+            specific_gravity: latest
+                .and_then(|measurement| measurement.specific_gravity)
+                .unwrap_or_else(|| specific_gravity(residual_sugar, abv)),
+            measured_on: latest.map(|measurement| measurement.measured_on),
+            stage: tracking.stage.map(|stage| stage.stage()),
+            next_task: tasks
+                .iter()
+                .filter(|task| !task.done)
+                .min_by_key(|task| task.due_on)
+                .cloned(),
+        })
+    }
+
+    /// The plan for every day from the start through the later of the planned
+    /// end and the last reading, paired with that day's reading.
+    pub fn progress(
+        &self,
+        measurements: &[Measurement],
+    ) -> Result<Vec<ProgressPoint>, SimulationError> {
+        let last_measured_day = measurements
+            .iter()
+            .map(|measurement| self.day_of(measurement.measured_on))
+            .max()
+            .unwrap_or(0);
+        let last_day = (self.input.fermentation_days as i64).max(last_measured_day);
+
+        (0..=last_day as u32)
+            .map(|day| {
+                let planned = fermentation_state(&self.input, day)?;
+                let measured = measurements
+                    .iter()
+                    .rfind(|measurement| self.day_of(measurement.measured_on) == day as i64)
+                    .cloned();
+                Ok(ProgressPoint { planned, measured })
+            })
+            .collect()
+    }
+
+    /// The readings against the plan with their error statistics, or `None`
+    /// if there are no readings from the start date on.
+    pub fn variance(
+        &self,
+        measurements: &[Measurement],
+    ) -> Result<Option<VarianceReport>, SimulationError> {
+        let variances: Vec<Variance> = self
+            .progress(measurements)?
+            .into_iter()
+            .filter_map(|point| {
+                let measured = point.measured?;
+                let day = point.planned.day;
+                Some(Variance {
+                    day,
+                    measured_on: measured.measured_on,
+                    planned_sugar: point.planned.residual_sugar,
+                    measured_sugar: measured.sugar_content,
+                    temperature_error: measured
+                        .temperature
+                        .map(|temperature| temperature - temperature_on(&self.input, day)),
+                })
+            })
+            .collect();
+        if variances.is_empty() {
+            return Ok(None);
+        }
+
+        let readings = variances.len() as f64;
+        let errors = variances.iter().map(Variance::sugar_error);
+        let bias = errors.clone().sum::<f64>() / readings;
+        let mean_absolute_error = errors.clone().map(f64::abs).sum::<f64>() / readings;
+        let rms_error = (errors.map(|error| error * error).sum::<f64>() / readings).sqrt();
+        let temperature_errors: Vec<f64> = variances
+            .iter()
+            .filter_map(|variance| variance.temperature_error)
+            .collect();
+        let temperature_bias = (!temperature_errors.is_empty())
+            .then(|| temperature_errors.iter().sum::<f64>() / temperature_errors.len() as f64);
+        Ok(Some(VarianceReport {
+            variances,
+            bias,
+            mean_absolute_error,
+            rms_error,
+            temperature_bias,
+        }))
+    }
+
+    /// Nutrient additions during primary fermentation, then racking,
+    /// sulfiting, stabilization, and bottling dates counted from its planned end.
+    pub fn schedule(&self, process: &Process) -> Vec<Task> {
+        let fermentation_days = self.input.fermentation_days as i64;
+        let pressed_on = self.started_on + TimeDelta::days(fermentation_days);
+        // The second addition goes in at the one-third sugar break.
+        let sugar_break_day = ((1.5f64).ln() / rate_constant(self.input.temperature)).ceil() as i64;
+        let mut tasks = vec![Task::new(
+            "nutrient-1",
+            self.started_on + TimeDelta::days(1),
+            "Add yeast nutrient",
+        )];
+        // Meads get staggered additions on each of the first few days.
+        for day in 2..=self.input.beverage.early_nutrient_additions() {
+            tasks.push(Task::new(
+                format!("nutrient-day-{}", day),
+                self.started_on + TimeDelta::days(day as i64),
+                "Add yeast nutrient",
+            ));
+        }
+        if let Some(restart_day) = restart_day(&self.input)
+            && (1..fermentation_days).contains(&(restart_day as i64))
+        {
+            tasks.push(Task::new(
+                "restart",
+                self.started_on + TimeDelta::days(restart_day as i64),
+                "Pitch acclimated champagne yeast to restart the fermentation",
+            ));
+        }
+        if (2..fermentation_days).contains(&sugar_break_day) {
+            tasks.push(Task::new(
+                "nutrient-2",
+                self.started_on + TimeDelta::days(sugar_break_day),
+                "Add yeast nutrient at the one-third sugar break",
+            ));
+        }
+        let aging_from = if process.malolactic {
+            tasks.push(Task::new(
+                "rack-gross-lees",
+                pressed_on,
+                "Rack off the gross lees and inoculate for malolactic fermentation",
+            ));
+            let malolactic_done = pressed_on + TimeDelta::days(MALOLACTIC_DAYS.into());
+            tasks.push(Task::new(
+                "malolactic",
+                malolactic_done,
+                "Confirm malolactic fermentation is complete",
+            ));
+            malolactic_done
+        } else {
+            tasks.push(Task::new(
+                "rack-gross-lees",
+                pressed_on,
+                "Rack off the gross lees",
+            ));
+            pressed_on
+        };
+        tasks.push(Task::new("sulfite", aging_from, "Add sulfite"));
+
+        let bottle_on = aging_from + Months::new(process.aging_months);
+        let stabilize_on = (bottle_on - TimeDelta::days(STABILIZATION_DAYS.into())).max(aging_from);
+        for racking in 1.. {
+            let racked_on = aging_from + Months::new(racking * RACKING_INTERVAL_MONTHS);
+            if racked_on >= stabilize_on {
+                break;
+            }
+            tasks.push(Task::new(
+                format!("rack-fine-lees-{}", racking),
+                racked_on,
+                "Rack off the fine lees, top up, and check sulfite",
+            ));
+        }
+        tasks.push(Task::new("stabilize", stabilize_on, "Stabilize and fine"));
+        tasks.push(Task::new("bottle", bottle_on, "Bottle"));
+        tasks
+    }
+}
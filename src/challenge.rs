@@ -0,0 +1,283 @@
+//! Challenge mode: target wines to make, in levels of rising difficulty,
+//! and how close a simulated wine came to one.
+
+use crate::descriptors::{self, BODY, SWEETNESS};
+use crate::engine::{Beverage, SimulationInput, SimulationResult, is_red_grape};
+
+/// Score out of 100 that counts as making the target wine.
+pub const PASS_SCORE: f64 = 80.0;
+/// Points for the alcohol; the rest are shared among the other checks.
+const ABV_POINTS: f64 = 40.0;
+const STYLE_POINTS: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Apprentice,
+    Cellarhand,
+    Winemaker,
+    MasterOfWine,
+}
+
+impl Level {
+    pub const ALL: [Level; 4] = [
+        Level::Apprentice,
+        Level::Cellarhand,
+        Level::Winemaker,
+        Level::MasterOfWine,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Apprentice => "Apprentice",
+            Level::Cellarhand => "Cellarhand",
+            Level::Winemaker => "Winemaker",
+            Level::MasterOfWine => "Master of Wine",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// How far in % ABV the wine can be from the target for full marks.
+    /// Half as far again loses them all.
+    pub fn abv_tolerance(self) -> f64 {
+        match self {
+            Level::Apprentice => 1.5,
+            Level::Cellarhand => 1.0,
+            Level::Winemaker => 0.5,
+            Level::MasterOfWine => 0.25,
+        }
+    }
+
+    pub fn challenges(self) -> impl Iterator<Item = &'static Challenge> {
+        CHALLENGES
+            .iter()
+            .filter(move |challenge| challenge.level == self)
+    }
+}
+
+/// A target wine. Anything left as `None` can be whatever it turns out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Challenge {
+    pub name: &'static str,
+    pub level: Level,
+    pub red: bool,
+    pub abv: f64,
+    /// The sweetness, acidity, and body as the simulation describes them on
+    /// the standard descriptor scales.
+    pub sweetness: Option<&'static str>,
+    pub acidity: Option<&'static str>,
+    pub body: Option<&'static str>,
+    /// Whether the wine must be free of off-flavors.
+    pub clean: bool,
+}
+
+pub const CHALLENGES: [Challenge; 10] = [
+    Challenge {
+        name: "A dry red of about 13% ABV",
+        level: Level::Apprentice,
+        red: true,
+        abv: 13.0,
+        sweetness: Some("bone dry"),
+        acidity: None,
+        body: None,
+        clean: false,
+    },
+    Challenge {
+        name: "An off-dry white of about 11% ABV",
+        level: Level::Apprentice,
+        red: false,
+        abv: 11.0,
+        sweetness: Some("with just a subtle hint of sweetness"),
+        acidity: None,
+        body: None,
+        clean: false,
+    },
+    Challenge {
+        name: "A light white of about 9% ABV",
+        level: Level::Apprentice,
+        red: false,
+        abv: 9.0,
+        sweetness: None,
+        acidity: None,
+        body: Some("light-bodied"),
+        clean: false,
+    },
+    Challenge {
+        name: "A dry, high-acid, 11.5% ABV white",
+        level: Level::Cellarhand,
+        red: false,
+        abv: 11.5,
+        sweetness: Some("bone dry"),
+        acidity: Some("high"),
+        body: None,
+        clean: false,
+    },
+    Challenge {
+        name: "A full-bodied, dry red of 14% ABV",
+        level: Level::Cellarhand,
+        red: true,
+        abv: 14.0,
+        sweetness: Some("bone dry"),
+        acidity: None,
+        body: Some("full-bodied"),
+        clean: false,
+    },
+    Challenge {
+        name: "A noticeably sweet, moderate-acid white of 10% ABV",
+        level: Level::Cellarhand,
+        red: false,
+        abv: 10.0,
+        sweetness: Some("noticeably sweet"),
+        acidity: Some("moderate"),
+        body: None,
+        clean: false,
+    },
+    Challenge {
+        name: "A clean, dry, soft red of 13.5% ABV",
+        level: Level::Winemaker,
+        red: true,
+        abv: 13.5,
+        sweetness: Some("bone dry"),
+        acidity: Some("soft"),
+        body: None,
+        clean: true,
+    },
+    Challenge {
+        name: "A clean, dry, medium-bodied white of 11% ABV",
+        level: Level::Winemaker,
+        red: false,
+        abv: 11.0,
+        sweetness: Some("bone dry"),
+        acidity: None,
+        body: Some("medium-bodied"),
+        clean: true,
+    },
+    Challenge {
+        name: "A clean, very sweet, high-acid white of 9.5% ABV",
+        level: Level::MasterOfWine,
+        red: false,
+        abv: 9.5,
+        sweetness: Some("extremely sweet"),
+        acidity: Some("high"),
+        body: None,
+        clean: true,
+    },
+    Challenge {
+        name: "A clean, dry, full-bodied red of 15.5% ABV",
+        level: Level::MasterOfWine,
+        red: true,
+        abv: 15.5,
+        sweetness: Some("bone dry"),
+        acidity: None,
+        body: Some("full-bodied"),
+        clean: true,
+    },
+];
+
+/// Looks a challenge up by name, ignoring case.
+pub fn challenge(name: &str) -> Option<&'static Challenge> {
+    CHALLENGES
+        .iter()
+        .find(|challenge| challenge.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// One thing the target asks for and how the wine did on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    /// What was asked for and what the wine came out as.
+    pub description: String,
+    pub points: f64,
+    pub max_points: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeScore {
+    /// Out of 100.
+    pub score: f64,
+    pub checks: Vec<Check>,
+}
+
+impl ChallengeScore {
+    pub fn passed(&self) -> bool {
+        self.score >= PASS_SCORE
+    }
+}
+
+impl Challenge {
+    /// How close the wine from `input` and `result` came to the target.
+    pub fn score(&self, input: &SimulationInput, result: &SimulationResult) -> ChallengeScore {
+        let tolerance = self.level.abv_tolerance();
+        let off_by = (result.actual_abv - self.abv).abs();
+        let mut checks = vec![Check {
+            description: format!(
+                "{:.1}% ABV, against {:.1}% ± {:.2}",
+                result.actual_abv, self.abv, tolerance
+            ),
+            points: ABV_POINTS * (1.0 - (off_by - tolerance).max(0.0) / (tolerance / 2.0)).max(0.0),
+            max_points: ABV_POINTS,
+        }];
+
+        let red = input.beverage == Beverage::Wine && is_red_grape(&input.grape_type);
+        let white = input.beverage == Beverage::Wine && !red;
+        let color = if self.red { "red" } else { "white" };
+        let mut style = vec![(
+            format!("a {} wine", color),
+            if self.red { red } else { white },
+        )];
+        // On the standard scales, not any installed from a descriptors file.
+        let standard = descriptors::standard();
+        let sweetness = standard.describe(SWEETNESS, input.beverage, result.residual_sugar);
+        let body = standard.describe(BODY, input.beverage, result.body_index);
+        let described = [
+            ("sweetness", self.sweetness, &sweetness),
+            ("acidity", self.acidity, &result.acidity),
+            ("body", self.body, &body),
+        ];
+        for (what, wanted, got) in described {
+            if let Some(wanted) = wanted {
+                style.push((format!("{} {} (got {})", what, wanted, got), wanted == got));
+            }
+        }
+        if self.clean {
+            style.push((
+                format!("no off-flavors (got {})", result.off_flavors.len()),
+                result.off_flavors.is_empty(),
+            ));
+        }
+        let each = STYLE_POINTS / style.len() as f64;
+        checks.extend(style.into_iter().map(|(description, met)| Check {
+            description,
+            points: if met { each } else { 0.0 },
+            max_points: each,
+        }));
+
+        ChallengeScore {
+            score: checks.iter().map(|check| check.points).sum(),
+            checks,
+        }
+    }
+}
+
+/// The levels open to a player, given their best score for each challenge
+/// by name. Passing any challenge in a level opens the next.
+pub fn unlocked_levels(best_scores: &[(String, f64)]) -> Vec<Level> {
+    let passed = |level: Level| {
+        level.challenges().any(|challenge| {
+            best_scores
+                .iter()
+                .any(|(name, score)| name == challenge.name && *score >= PASS_SCORE)
+        })
+    };
+    let mut levels = vec![Level::ALL[0]];
+    for pair in Level::ALL.windows(2) {
+        if !passed(pair[0]) {
+            break;
+        }
+        levels.push(pair[1]);
+    }
+    levels
+}
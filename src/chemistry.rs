@@ -0,0 +1,230 @@
+//! The finished wine's chemistry as a lab sheet would list it, each figure
+//! with its unit and the range usual for the beverage. pH, volatile acidity,
+//! and SO2 are estimated from what the model tracks, not simulated.
+
+use crate::engine::{
+    Beverage, HEAT_STRESS_TEMPERATURE, SimulationInput, SimulationResult, VOLATILE_FLAW,
+    fermentation_temperature, is_red_grape, usual_acidity,
+};
+use crate::inventory::CRUSH_SO2;
+
+/// How far the pH falls for each g/L of acidity above the beverage's usual.
+const PH_PER_ACIDITY: f64 = 0.12;
+/// How much higher red wines' pH runs, from the potassium in their skins.
+const RED_PH: f64 = 0.15;
+/// Acetic acid in g/L every fermentation makes.
+const BASE_VOLATILE_ACIDITY: f64 = 0.3;
+/// Acetic acid in g/L added by each mg/L of oxygen taken up.
+const VOLATILE_ACIDITY_PER_OXYGEN: f64 = 0.01;
+/// Acetic acid in g/L added by a heat-stressed fermentation, and by a wild
+/// one gone volatile.
+const HEAT_VOLATILE_ACIDITY: f64 = 0.15;
+const WILD_VOLATILE_ACIDITY: f64 = 0.5;
+/// Share of the crush SO2 bound at once, the share more for each g/L of
+/// residual sugar, and the most that can be bound.
+const BOUND_SO2: f64 = 0.4;
+const BOUND_SO2_PER_SUGAR: f64 = 0.002;
+const MAX_BOUND_SO2: f64 = 0.7;
+/// SO2 in mg/L each mg/L of oxygen taken up uses.
+const SO2_PER_OXYGEN: f64 = 1.0;
+
+/// One line of the chemistry panel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub name: &'static str,
+    pub value: f64,
+    pub unit: &'static str,
+    /// Decimal places the value is shown with.
+    pub decimals: usize,
+    /// The usual range for the beverage, low and high.
+    pub typical: (f64, f64),
+}
+
+impl Measurement {
+    fn new(
+        name: &'static str,
+        value: f64,
+        unit: &'static str,
+        decimals: usize,
+        typical: (f64, f64),
+    ) -> Self {
+        Measurement {
+            name,
+            value,
+            unit,
+            decimals,
+            typical,
+        }
+    }
+
+    fn with_unit(&self, text: String) -> String {
+        if self.unit.is_empty() {
+            text
+        } else {
+            format!("{} {}", text, self.unit)
+        }
+    }
+
+    /// The value with its unit, e.g. "6.4 g/L".
+    pub fn value_text(&self) -> String {
+        self.with_unit(format!("{:.*}", self.decimals, self.value))
+    }
+
+    /// The usual range with its unit, e.g. "5.0–8.0 g/L".
+    pub fn typical_text(&self) -> String {
+        let (low, high) = self.typical;
+        self.with_unit(format!(
+            "{:.*}–{:.*}",
+            self.decimals, low, self.decimals, high
+        ))
+    }
+
+    pub fn is_typical(&self) -> bool {
+        (self.typical.0..=self.typical.1).contains(&self.value)
+    }
+}
+
+/// The pH of a wine at `titratable_acidity` in g/L: the beverage's usual pH,
+/// lower for more acid.
+pub fn estimated_ph(input: &SimulationInput, titratable_acidity: f64) -> f64 {
+    let usual = match input.beverage {
+        Beverage::Wine if is_red_grape(&input.grape_type) => 3.45 + RED_PH,
+        Beverage::Wine => 3.45,
+        Beverage::Mead => 3.8,
+        Beverage::Cider => 3.5,
+        Beverage::FruitWine => 3.35,
+    };
+    usual - (titratable_acidity - usual_acidity(input.beverage)) * PH_PER_ACIDITY
+}
+
+/// Volatile acidity in g/L as acetic acid, from the oxygen the wine took up
+/// and any heat stress or wild flaw in the fermentation.
+pub fn volatile_acidity(input: &SimulationInput, result: &SimulationResult) -> f64 {
+    let mut acidity = BASE_VOLATILE_ACIDITY + result.oxygen_uptake * VOLATILE_ACIDITY_PER_OXYGEN;
+    if fermentation_temperature(input) >= HEAT_STRESS_TEMPERATURE {
+        acidity += HEAT_VOLATILE_ACIDITY;
+    }
+    if result
+        .off_flavors
+        .iter()
+        .any(|flavor| flavor == VOLATILE_FLAW)
+    {
+        acidity += WILD_VOLATILE_ACIDITY;
+    }
+    acidity
+}
+
+/// Free and total SO2 in mg/L left from the crush addition once sugar has
+/// bound some and oxygen used some.
+pub fn sulfur_dioxide(result: &SimulationResult) -> (f64, f64) {
+    let bound = (BOUND_SO2 + result.residual_sugar * BOUND_SO2_PER_SUGAR).min(MAX_BOUND_SO2);
+    let used = result.oxygen_uptake * SO2_PER_OXYGEN;
+    let free = (CRUSH_SO2 * (1.0 - bound) - used).max(0.0);
+    let total = (CRUSH_SO2 - used).max(free);
+    (free, total)
+}
+
+fn typical_abv(beverage: Beverage) -> (f64, f64) {
+    match beverage {
+        Beverage::Wine => (11.0, 14.5),
+        Beverage::Mead => (8.0, 16.0),
+        Beverage::Cider => (4.5, 8.5),
+        Beverage::FruitWine => (10.0, 13.5),
+    }
+}
+
+fn typical_sugar(beverage: Beverage) -> (f64, f64) {
+    match beverage {
+        Beverage::Wine => (0.0, 12.0),
+        Beverage::Mead => (0.0, 60.0),
+        Beverage::Cider | Beverage::FruitWine => (0.0, 30.0),
+    }
+}
+
+fn typical_glycerol(beverage: Beverage) -> (f64, f64) {
+    match beverage {
+        Beverage::Wine => (5.0, 12.0),
+        Beverage::Mead => (4.0, 12.0),
+        Beverage::Cider => (2.0, 6.0),
+        Beverage::FruitWine => (4.0, 10.0),
+    }
+}
+
+fn typical_ph(input: &SimulationInput) -> (f64, f64) {
+    match input.beverage {
+        Beverage::Wine if is_red_grape(&input.grape_type) => (3.3, 3.8),
+        Beverage::Wine => (3.1, 3.6),
+        Beverage::Mead => (3.4, 4.2),
+        Beverage::Cider => (3.2, 3.9),
+        Beverage::FruitWine => (3.0, 3.6),
+    }
+}
+
+/// Dissolved CO2 in g/L usual in a still glass; reds are kept flatter.
+fn typical_co2(input: &SimulationInput) -> (f64, f64) {
+    if input.beverage == Beverage::Wine && is_red_grape(&input.grape_type) {
+        (0.2, 0.8)
+    } else {
+        (0.5, 1.2)
+    }
+}
+
+/// Everything the model knows of the wine from `input` and `result`, in
+/// the order a lab sheet lists it.
+pub fn chemistry_panel(input: &SimulationInput, result: &SimulationResult) -> Vec<Measurement> {
+    let usual = usual_acidity(input.beverage);
+    let (free_so2, total_so2) = sulfur_dioxide(result);
+    vec![
+        Measurement::new(
+            "Alcohol",
+            result.actual_abv,
+            "% ABV",
+            1,
+            typical_abv(input.beverage),
+        ),
+        Measurement::new(
+            "Residual sugar",
+            result.residual_sugar,
+            "g/L",
+            1,
+            typical_sugar(input.beverage),
+        ),
+        Measurement::new(
+            "Titratable acidity",
+            result.titratable_acidity,
+            "g/L",
+            1,
+            (usual - 1.5, usual + 1.5),
+        ),
+        Measurement::new(
+            "pH",
+            estimated_ph(input, result.titratable_acidity),
+            "",
+            2,
+            typical_ph(input),
+        ),
+        Measurement::new(
+            "Volatile acidity",
+            volatile_acidity(input, result),
+            "g/L",
+            2,
+            (0.2, 0.6),
+        ),
+        Measurement::new("Free SO2", free_so2, "mg/L", 0, (20.0, 40.0)),
+        Measurement::new("Total SO2", total_so2, "mg/L", 0, (40.0, 150.0)),
+        Measurement::new(
+            "Glycerol",
+            result.glycerol,
+            "g/L",
+            1,
+            typical_glycerol(input.beverage),
+        ),
+        Measurement::new(
+            "Dissolved CO2",
+            result.dissolved_co2,
+            "g/L",
+            2,
+            typical_co2(input),
+        ),
+    ]
+}
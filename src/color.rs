@@ -0,0 +1,179 @@
+//! Wine color from the skins to the bottle: anthocyanins extracted during
+//! fermentation, held by copigmentation while the wine is young, and bound
+//! into stable polymeric pigments as it ages, while oxygen browns it.
+
+use crate::engine::{
+    Beverage, SimulationInput, is_red_grape, oxygen_uptake, press_blend, stem_inclusion,
+};
+
+/// Days on the skins for extraction to get about two-thirds of the way.
+const EXTRACTION_DAYS: f64 = 3.0;
+/// Months of aging for about two-thirds of the anthocyanins to polymerize.
+const POLYMERIZATION_MONTHS: f64 = 18.0;
+/// Fraction of the polymerizing anthocyanins lost rather than bound.
+const POLYMERIZATION_LOSS: f64 = 0.3;
+/// Extra color copigmentation gives the free anthocyanins.
+const COPIGMENTATION: f64 = 0.4;
+/// Color stems take up when every cluster goes in whole.
+const STEM_ADSORPTION: f64 = 0.1;
+/// Extra color a full blend of press wine brings.
+const PRESS_WINE_COLOR: f64 = 0.15;
+// Color intensity per mg/L of free and of polymeric pigment.
+const INTENSITY_PER_FREE: f64 = 0.01;
+const INTENSITY_PER_POLYMERIC: f64 = 0.015;
+
+/// The wine's color at bottling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WineColor {
+    /// Free anthocyanins in mg/L.
+    pub anthocyanins: f64,
+    /// Anthocyanins bound into polymeric pigments, in mg/L.
+    pub polymeric: f64,
+    /// Color intensity, the sum of the absorbances at 420, 520, and 620 nm.
+    pub intensity: f64,
+    /// Browning from 0, fresh, to 1, fully oxidized.
+    pub browning: f64,
+}
+
+/// Anthocyanins in mg/L a full extraction gets from the skins, or `None`
+/// for musts that are not fermented on skins.
+fn anthocyanin_potential(input: &SimulationInput) -> Option<f64> {
+    match input.beverage {
+        Beverage::Wine if is_red_grape(&input.grape_type) => {
+            Some(match input.grape_type.trim().to_lowercase().as_str() {
+                "cabernet sauvignon" => 700.0,
+                "syrah" | "shiraz" => 750.0,
+                "merlot" => 550.0,
+                "zinfandel" | "tempranillo" => 500.0,
+                "sangiovese" => 350.0,
+                "pinot noir" => 250.0,
+                _ => 500.0,
+            })
+        }
+        Beverage::FruitWine => Some(match input.grape_type.trim().to_lowercase().as_str() {
+            "elderberry" => 900.0,
+            "blackberry" => 600.0,
+            "cherry" | "plum" => 300.0,
+            "strawberry" => 150.0,
+            _ => 60.0,
+        }),
+        _ => None,
+    }
+}
+
+/// Browning the must starts with, before any aging.
+fn base_browning(input: &SimulationInput) -> f64 {
+    match input.beverage {
+        Beverage::Mead if input.grape_type.eq_ignore_ascii_case("Buckwheat") => 0.6,
+        Beverage::Mead => 0.25,
+        Beverage::Cider => 0.1,
+        _ => 0.0,
+    }
+}
+
+/// The color of the wine after fermenting and then aging as `input` says.
+pub fn wine_color(input: &SimulationInput) -> WineColor {
+    wine_color_after(input, input.aging_months)
+}
+
+/// The color after `months` of the aging `input` sets up, for following it
+/// month by month.
+pub fn wine_color_after(input: &SimulationInput, months: u32) -> WineColor {
+    let oxygen = oxygen_uptake(&SimulationInput {
+        aging_months: months,
+        ..input.clone()
+    });
+    let months = months as f64;
+    let browning = (base_browning(input) + 0.003 * oxygen + 0.003 * months).min(1.0);
+    let Some(potential) = anthocyanin_potential(input) else {
+        return WineColor {
+            anthocyanins: 0.0,
+            polymeric: 0.0,
+            intensity: 0.1 + 0.5 * browning,
+            browning,
+        };
+    };
+
+    // Warmer ferments pull more color from the skins.
+    let warmth = (1.0 + (input.temperature - 20.0) / 40.0).max(0.5);
+    let extracted = potential
+        * warmth
+        * (1.0 - (-(input.fermentation_days as f64) / EXTRACTION_DAYS).exp())
+        * (1.0 - STEM_ADSORPTION * stem_inclusion(input))
+        * (1.0 + PRESS_WINE_COLOR * press_blend(input).unwrap_or(0.0));
+
+    // Oxygen speeds up the tannin-anthocyanin bonds that stabilize color.
+    let polymerizing = 1.0 - (-months * (1.0 + oxygen / 50.0) / POLYMERIZATION_MONTHS).exp();
+    let anthocyanins = extracted * (1.0 - polymerizing);
+    let polymeric = extracted * polymerizing * (1.0 - POLYMERIZATION_LOSS);
+    let intensity = anthocyanins * (1.0 + COPIGMENTATION) * INTENSITY_PER_FREE
+        + polymeric * INTENSITY_PER_POLYMERIC;
+    WineColor {
+        anthocyanins,
+        polymeric,
+        intensity,
+        browning,
+    }
+}
+
+impl WineColor {
+    fn is_red(&self) -> bool {
+        self.anthocyanins + self.polymeric > 0.0
+    }
+
+    /// Young pigments read purple; polymeric ones and browning push the hue
+    /// towards brick.
+    fn age(&self) -> f64 {
+        0.5 * self.polymeric / (self.anthocyanins + self.polymeric) + self.browning
+    }
+
+    /// What the color looks like in the glass, e.g. "deep ruby".
+    pub fn descriptor(&self) -> String {
+        if !self.is_red() {
+            return match self.browning {
+                b if b < 0.1 => "pale straw",
+                b if b < 0.3 => "lemon gold",
+                b if b < 0.6 => "deep gold",
+                _ => "amber",
+            }
+            .to_owned();
+        }
+        let depth = match self.intensity {
+            i if i < 4.0 => "pale",
+            i if i < 8.0 => "medium",
+            i if i < 14.0 => "deep",
+            _ => "opaque",
+        };
+        let hue = match self.age() {
+            a if a < 0.15 => "purple",
+            a if a < 0.5 => "ruby",
+            a if a < 0.9 => "garnet",
+            _ => "brick",
+        };
+        format!("{} {}", depth, hue)
+    }
+
+    /// The color as an RGB triple for the color preview.
+    pub fn rgb(&self) -> [u8; 3] {
+        let mix = |from: [f64; 3], to: [f64; 3], t: f64| {
+            let t = t.clamp(0.0, 1.0);
+            [0, 1, 2].map(|i| from[i] + (to[i] - from[i]) * t)
+        };
+        let rgb = if self.is_red() {
+            let hue = mix([110.0, 20.0, 70.0], [150.0, 60.0, 35.0], self.age());
+            // Deeper wines let less light through.
+            let light = (1.0 - self.intensity / 20.0).clamp(0.25, 1.0);
+            let pale = mix([235.0, 170.0, 180.0], hue, self.intensity / 4.0);
+            pale.map(|channel| channel * light)
+        } else {
+            mix([245.0, 235.0, 170.0], [190.0, 120.0, 40.0], self.browning)
+        };
+        rgb.map(|channel| channel.round().clamp(0.0, 255.0) as u8)
+    }
+
+    /// The color as `#rrggbb`.
+    pub fn hex(&self) -> String {
+        let [r, g, b] = self.rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
@@ -0,0 +1,294 @@
+//! A read-only web page following one batch: where it stands, its progress
+//! against the plan, and the tasks coming up. It is served on the local
+//! network so it can be checked from a phone in the cellar, and reloads
+//! itself every minute.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write as _};
+use std::net::{IpAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDate;
+
+use crate::cellar::{Batch, Measurement, ProgressPoint, Task};
+use crate::engine::SimulationError;
+use crate::log_sheet::escape;
+
+pub const DEFAULT_PORT: u16 = 8787;
+/// Tasks listed on the page, the earliest first.
+const UPCOMING_TASKS: usize = 6;
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+/// Header lines read from a request before answering it.
+const MAX_HEADERS: usize = 100;
+/// How long a client has to send its whole request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 300.0;
+const MARGIN: f64 = 50.0;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 1em; max-width: 40em; }
+h1 { font-size: 1.4em; margin: 0 0 0.3em; }
+h2 { font-size: 1.1em; margin: 1.2em 0 0.4em; }
+ul { padding-left: 1.2em; }
+li { margin: 0.3em 0; }
+p.updated { color: #666; font-size: 0.9em; }
+svg { width: 100%; height: auto; }";
+
+/// The batch's page as of `today`.
+pub fn page_html(
+    batch: &Batch,
+    measurements: &[Measurement],
+    tasks: &[Task],
+    today: NaiveDate,
+) -> Result<String, SimulationError> {
+    let status = batch.status(measurements, tasks, today)?;
+    let mut summary = if status.day < 0 {
+        format!("Starts on {}.", batch.started_on)
+    } else {
+        format!(
+            "Day {} of {}{}.",
+            status.day,
+            batch.input.fermentation_days,
+            status
+                .stage
+                .map(|stage| format!(", {}", stage.label().to_lowercase()))
+                .unwrap_or_default()
+        )
+    };
+    let _ = write!(
+        summary,
+        " {:.1}% ABV and SG {:.3}, {}.",
+        status.abv,
+        status.specific_gravity,
+        match status.measured_on {
+            Some(date) => format!("from the reading on {}", date),
+            None => "going by the plan".to_owned(),
+        }
+    );
+    if status.days_remaining > 0 {
+        let _ = write!(
+            summary,
+            " Fermentation should finish in about {} days.",
+            status.days_remaining
+        );
+    }
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<meta http-equiv=\"refresh\" content=\"60\">\n<title>{0}</title>\n<style>\n{1}\n</style>\n</head>\n<body>\n<h1>{0}</h1>\n<p>{2}</p>\n",
+        escape(&batch.name),
+        STYLE,
+        summary
+    );
+    html.push_str(&progress_svg(&batch.progress(measurements)?, status.day));
+    html.push_str("\n<h2>Upcoming tasks</h2>\n");
+    let mut upcoming: Vec<&Task> = tasks.iter().filter(|task| !task.done).collect();
+    upcoming.sort_by_key(|task| task.due_on);
+    if upcoming.is_empty() {
+        html.push_str("<p>Nothing left on the schedule.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for task in upcoming.iter().take(UPCOMING_TASKS) {
+            let overdue = if task.due_on < today {
+                " (overdue)"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                html,
+                "<li><strong>{}</strong>{} {}</li>",
+                task.due_on,
+                overdue,
+                escape(&task.title)
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+    let _ = write!(
+        html,
+        "<p class=\"updated\">As of {}.</p>\n</body>\n</html>\n",
+        today
+    );
+    Ok(html)
+}
+
+/// Planned residual sugar by day as a line, readings as dots, and `today`
+/// marked, as an SVG image.
+pub fn progress_svg(points: &[ProgressPoint], today: i64) -> String {
+    let last_day = points.len().saturating_sub(1).max(1) as f64;
+    let sugar_high = points
+        .iter()
+        .map(|point| point.planned.sugar_content)
+        .fold(1.0, f64::max);
+    let (left, right) = (MARGIN, CHART_WIDTH - MARGIN / 2.0);
+    let (top, bottom) = (MARGIN / 2.0, CHART_HEIGHT - MARGIN);
+    let x = |day: f64| left + day / last_day * (right - left);
+    let y = |sugar: f64| bottom - sugar.clamp(0.0, sugar_high) / sugar_high * (bottom - top);
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {CHART_WIDTH} {CHART_HEIGHT}" font-family="sans-serif" font-size="12"><rect width="{CHART_WIDTH}" height="{CHART_HEIGHT}" fill="#fff"/><line x1="{left}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="#000"/><line x1="{left}" y1="{top}" x2="{left}" y2="{bottom}" stroke="#000"/><text x="{left}" y="{title_y}">Residual sugar (g/L)</text><text x="{tick_x}" y="{bottom}" text-anchor="end">0</text><text x="{tick_x}" y="{top}" text-anchor="end">{sugar_high:.0}</text><text x="{left}" y="{label_y}" text-anchor="middle">0</text><text x="{right}" y="{label_y}" text-anchor="middle">{last_day}</text><text x="{axis_x}" y="{label_y}" text-anchor="middle">Day</text>"##,
+        title_y = top - 8.0,
+        tick_x = left - 4.0,
+        label_y = bottom + 16.0,
+        axis_x = (left + right) / 2.0,
+    );
+    if (0.0..=last_day).contains(&(today as f64)) {
+        let _ = write!(
+            svg,
+            r##"<line x1="{today_x:.1}" y1="{top}" x2="{today_x:.1}" y2="{bottom}" stroke="#999" stroke-dasharray="4 4"/><text x="{today_x:.1}" y="{top}" text-anchor="middle" fill="#666">today</text>"##,
+            today_x = x(today as f64),
+        );
+    }
+    let path = points
+        .iter()
+        .map(|point| {
+            format!(
+                "{:.1},{:.1}",
+                x(point.planned.day as f64),
+                y(point.planned.residual_sugar)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" L");
+    if !path.is_empty() {
+        let _ = write!(
+            svg,
+            r##"<path d="M{}" fill="none" stroke="#7b1e3a" stroke-width="2"/>"##,
+            path
+        );
+    }
+    for point in points {
+        if let Some(measured) = &point.measured {
+            let _ = write!(
+                svg,
+                r##"<circle cx="{:.1}" cy="{:.1}" r="4" fill="#c9a227"/>"##,
+                x(point.planned.day as f64),
+                y(measured.sugar_content)
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Serves one page to anyone on the network who asks, until dropped.
+pub struct CompanionServer {
+    port: u16,
+    page: Arc<Mutex<String>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for CompanionServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl CompanionServer {
+    /// Listens on every interface at `port`, or a free port if it is 0.
+    pub fn start(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+        tracing::info!(port, "serving the companion page");
+
+        let page = Arc::new(Mutex::new(String::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (served, stopped) = (page.clone(), stop.clone());
+        thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    // A slow or idle client only holds up its own thread.
+                    Ok((stream, _)) => {
+                        let page = served.lock().unwrap().clone();
+                        thread::spawn(move || {
+                            if let Err(err) = respond(stream, &page) {
+                                tracing::debug!("Companion page request failed: {}", err);
+                            }
+                        });
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(ACCEPT_INTERVAL)
+                    }
+                    Err(err) => {
+                        tracing::warn!("Companion page server error: {}", err);
+                        thread::sleep(ACCEPT_INTERVAL);
+                    }
+                }
+            }
+        });
+        Ok(CompanionServer { port, page, stop })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn set_page(&self, html: String) {
+        *self.page.lock().unwrap() = html;
+    }
+
+    /// The address to open from a phone on the same network, if this
+    /// machine's address on it can be found.
+    pub fn url(&self) -> Option<String> {
+        lan_address().map(|ip| format!("http://{}:{}/", ip, self.port))
+    }
+}
+
+/// This machine's address on the local network, going by the route out.
+/// Nothing is sent.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("192.0.2.1", 80)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified() && !ip.is_loopback()).then_some(ip)
+}
+
+fn respond(mut stream: TcpStream, page: &str) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    read_line_by(&mut reader, &mut request_line, deadline)?;
+    // Closing with the headers unread resets the connection rather than
+    // ending it, and the browser shows an error in place of the page.
+    let mut header = String::new();
+    for _ in 0..MAX_HEADERS {
+        header.clear();
+        if read_line_by(&mut reader, &mut header, deadline)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/" | "/index.html")) => ("200 OK", "text/html; charset=utf-8", page),
+        (Some("GET"), _) => ("404 Not Found", "text/plain", "Not found"),
+        _ => ("405 Method Not Allowed", "text/plain", "Only GET is served"),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Reads a line, giving up once `deadline` has passed.
+fn read_line_by(
+    reader: &mut BufReader<&TcpStream>,
+    line: &mut String,
+    deadline: Instant,
+) -> io::Result<usize> {
+    let left = deadline.saturating_duration_since(Instant::now());
+    if left.is_zero() {
+        return Err(io::ErrorKind::TimedOut.into());
+    }
+    reader.get_ref().set_read_timeout(Some(left))?;
+    reader.read_line(line)
+}
@@ -0,0 +1,125 @@
+//! Residual sugar and strength as different traditions write them: sugar in
+//! g/L or % by weight, strength in % ABV, proof, or grams of alcohol per
+//! liter. The model works in g/L and % ABV; these are for showing results.
+
+/// Density of water in g/mL at cellar temperature.
+const WATER_DENSITY: f64 = 0.998;
+/// How much each g/L of residual sugar raises a wine's density in g/mL.
+const DENSITY_PER_SUGAR: f64 = 0.000385;
+/// How much each % ABV lowers it.
+const DENSITY_PER_ABV: f64 = 0.00125;
+/// Density of ethanol in g/mL.
+const ETHANOL_DENSITY: f64 = 0.789;
+/// US proof is twice the ABV; the old UK proof, 7/4 of it.
+const US_PROOF_PER_ABV: f64 = 2.0;
+const UK_PROOF_PER_ABV: f64 = 1.75;
+
+/// Density in g/mL of a wine with `residual_sugar` in g/L at `abv`.
+pub fn wine_density(residual_sugar: f64, abv: f64) -> f64 {
+    WATER_DENSITY + residual_sugar * DENSITY_PER_SUGAR - abv * DENSITY_PER_ABV
+}
+
+/// `residual_sugar` in g/L as a percentage of the wine's weight.
+pub fn sugar_percent_by_weight(residual_sugar: f64, abv: f64) -> f64 {
+    residual_sugar / (wine_density(residual_sugar, abv) * 10.0)
+}
+
+/// Grams of ethanol in a liter of wine at `abv`.
+pub fn alcohol_grams_per_liter(abv: f64) -> f64 {
+    abv * 10.0 * ETHANOL_DENSITY
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SugarConvention {
+    #[default]
+    GramsPerLiter,
+    PercentByWeight,
+}
+
+impl SugarConvention {
+    pub const ALL: [SugarConvention; 2] = [
+        SugarConvention::GramsPerLiter,
+        SugarConvention::PercentByWeight,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SugarConvention::GramsPerLiter => "g/L",
+            SugarConvention::PercentByWeight => "% by weight",
+        }
+    }
+
+    /// `residual_sugar` in g/L of a wine at `abv`, written this way.
+    pub fn format(self, residual_sugar: f64, abv: f64) -> String {
+        match self {
+            SugarConvention::GramsPerLiter => format!("{:.1} g/L", residual_sugar),
+            SugarConvention::PercentByWeight => format!(
+                "{:.2}% by weight",
+                sugar_percent_by_weight(residual_sugar, abv)
+            ),
+        }
+    }
+
+    /// `residual_sugar` in g/L, followed by this way of writing it if it
+    /// is another, e.g. "85.8 g/L (8.25% by weight)".
+    pub fn alongside(self, residual_sugar: f64, abv: f64) -> String {
+        let grams = SugarConvention::GramsPerLiter.format(residual_sugar, abv);
+        match self {
+            SugarConvention::GramsPerLiter => grams,
+            _ => format!("{} ({})", grams, self.format(residual_sugar, abv)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrengthConvention {
+    #[default]
+    Abv,
+    UsProof,
+    UkProof,
+    GramsPerLiter,
+}
+
+impl StrengthConvention {
+    pub const ALL: [StrengthConvention; 4] = [
+        StrengthConvention::Abv,
+        StrengthConvention::UsProof,
+        StrengthConvention::UkProof,
+        StrengthConvention::GramsPerLiter,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StrengthConvention::Abv => "% ABV",
+            StrengthConvention::UsProof => "US proof",
+            StrengthConvention::UkProof => "UK proof",
+            StrengthConvention::GramsPerLiter => "g/L of alcohol",
+        }
+    }
+
+    /// `abv` written this way.
+    pub fn format(self, abv: f64) -> String {
+        match self {
+            StrengthConvention::Abv => format!("{:.1}% ABV", abv),
+            StrengthConvention::UsProof => format!("{:.1}° proof", abv * US_PROOF_PER_ABV),
+            StrengthConvention::UkProof => {
+                format!("{:.1}° UK proof", abv * UK_PROOF_PER_ABV)
+            }
+            StrengthConvention::GramsPerLiter => {
+                format!("{:.0} g/L of alcohol", alcohol_grams_per_liter(abv))
+            }
+        }
+    }
+
+    /// `abv`, followed by this way of writing it if it is another, e.g.
+    /// "14.4% ABV (28.8° proof)".
+    pub fn alongside(self, abv: f64) -> String {
+        let percent = StrengthConvention::Abv.format(abv);
+        match self {
+            StrengthConvention::Abv => percent,
+            _ => format!("{} ({})", percent, self.format(abv)),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! What a batch cost to make, and what that comes to per liter and per bottle.
+
+/// Categories offered for batch costs.
+pub const COST_CATEGORIES: [&str; 8] = [
+    "Grapes/Juice",
+    "Yeast",
+    "Additives",
+    "Oak",
+    "Bottles",
+    "Closures",
+    "Equipment",
+    "Other",
+];
+
+/// Volume of a standard wine bottle in liters.
+pub const BOTTLE_VOLUME: f64 = 0.75;
+
+/// One line of a batch's costs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cost {
+    pub category: String,
+    pub description: String,
+    pub quantity: f64,
+    pub unit_price: f64,
+}
+
+impl Cost {
+    pub fn total(&self) -> f64 {
+        self.quantity * self.unit_price
+    }
+}
+
+/// A reusable price for something bought often, e.g. a case of bottles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Price {
+    pub name: String,
+    pub category: String,
+    /// What one unit is, e.g. "kg" or "bottle".
+    pub unit: String,
+    pub unit_price: f64,
+}
+
+impl Price {
+    /// A batch cost of `quantity` units at this price.
+    pub fn cost(&self, quantity: f64) -> Cost {
+        Cost {
+            category: self.category.clone(),
+            description: if self.unit.is_empty() {
+                self.name.clone()
+            } else {
+                format!("{} ({})", self.name, self.unit)
+            },
+            quantity,
+            unit_price: self.unit_price,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostReport {
+    pub total: f64,
+    /// Totals per category in `COST_CATEGORIES` order, then any others,
+    /// leaving out empty categories.
+    pub by_category: Vec<(String, f64)>,
+    /// `None` until the batch volume is known.
+    pub per_liter: Option<f64>,
+    /// Whole bottles the batch fills.
+    pub bottles: Option<u32>,
+    pub per_bottle: Option<f64>,
+}
+
+/// Sums `costs` for a batch of `volume` liters.
+pub fn cost_report(costs: &[Cost], volume: Option<f64>) -> CostReport {
+    let mut by_category: Vec<(String, f64)> = COST_CATEGORIES
+        .iter()
+        .map(|category| (category.to_string(), 0.0))
+        .collect();
+    for cost in costs {
+        match by_category
+            .iter_mut()
+            .find(|(category, _)| *category == cost.category)
+        {
+            Some((_, total)) => *total += cost.total(),
+            None => by_category.push((cost.category.clone(), cost.total())),
+        }
+    }
+    by_category.retain(|&(_, total)| total != 0.0);
+
+    let total = costs.iter().map(Cost::total).sum();
+    let volume = volume.filter(|&volume| volume > 0.0);
+    let bottles = volume
+        .map(|volume| (volume / BOTTLE_VOLUME).floor() as u32)
+        .filter(|&bottles| bottles > 0);
+    CostReport {
+        total,
+        by_category,
+        per_liter: volume.map(|volume| total / volume),
+        bottles,
+        per_bottle: bottles.map(|bottles| total / bottles as f64),
+    }
+}
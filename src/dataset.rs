@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::LazyLock;
+
+use csv::{ReaderBuilder, StringRecord};
+use tracing::info;
+
+// The web build fetches the dataset at startup instead of bundling it.
+#[cfg(not(target_arch = "wasm32"))]
+pub const WINE_DATA_CSV: &str = include_str!("../WineDataset.csv");
+
+#[cfg(not(target_arch = "wasm32"))]
+static BUNDLED_DATASET: LazyLock<Dataset> =
+    LazyLock::new(|| Dataset::from_csv_str(WINE_DATA_CSV).unwrap_or_default());
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WineRecord {
+    #[cfg_attr(feature = "serde", serde(rename = "Grape"))]
+    pub grape: String,
+
+    #[cfg_attr(feature = "serde", serde(rename = "Characteristics"))]
+    pub characteristics: String,
+
+    /// Red, White, Rosé, and so on; empty if the dataset doesn't say.
+    #[cfg_attr(feature = "serde", serde(rename = "Type", default))]
+    pub wine_type: String,
+
+    /// How the wine is sold, e.g. "Crisp & Zesty"; empty if the dataset
+    /// doesn't say.
+    #[cfg_attr(feature = "serde", serde(rename = "Style", default))]
+    pub style: String,
+
+    /// Percent alcohol by volume.
+    #[cfg_attr(feature = "serde", serde(rename = "ABV", default))]
+    pub abv: Option<f64>,
+}
+
+/// Case-insensitive grape name, matching the `eq_ignore_ascii_case` comparison
+/// the simulator has always used.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GrapeKey(String);
+
+impl GrapeKey {
+    pub fn new(grape: &str) -> Self {
+        GrapeKey(grape.to_ascii_lowercase())
+    }
+}
+
+/// Dataset records plus an index from grape to record positions, so lookups
+/// don't scan every record on each simulation run.
+#[derive(Debug, Clone, Default)]
+pub struct Dataset {
+    records: Vec<WineRecord>,
+    index: HashMap<GrapeKey, Vec<usize>>,
+}
+
+impl Dataset {
+    pub fn new(records: Vec<WineRecord>) -> Self {
+        let mut dataset = Dataset {
+            records,
+            index: HashMap::new(),
+        };
+        dataset.rebuild_index();
+        dataset
+    }
+
+    pub fn from_csv_str(data: &str) -> Result<Self, csv::Error> {
+        load_csv_data_from_str(data).map(Dataset::new)
+    }
+
+    /// The dataset shipped with the simulator, parsed on first use.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn bundled() -> &'static Dataset {
+        &BUNDLED_DATASET
+    }
+
+    pub fn from_csv_path(path: impl AsRef<Path>) -> Result<Self, csv::Error> {
+        load_csv_data_from_path(path.as_ref()).map(Dataset::new)
+    }
+
+    pub fn records(&self) -> &[WineRecord] {
+        &self.records
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn push(&mut self, record: WineRecord) {
+        self.index
+            .entry(GrapeKey::new(&record.grape))
+            .or_default()
+            .push(self.records.len());
+        self.records.push(record);
+    }
+
+    pub fn set_records(&mut self, records: Vec<WineRecord>) {
+        self.records = records;
+        self.rebuild_index();
+    }
+
+    /// Records for `grape` in dataset order.
+    pub fn records_for(&self, grape: &str) -> Vec<&WineRecord> {
+        self.index
+            .get(&GrapeKey::new(grape))
+            .map(|positions| positions.iter().map(|&i| &self.records[i]).collect())
+            .unwrap_or_default()
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        for (i, record) in self.records.iter().enumerate() {
+            self.index
+                .entry(GrapeKey::new(&record.grape))
+                .or_default()
+                .push(i);
+        }
+    }
+}
+
+impl From<Vec<WineRecord>> for Dataset {
+    fn from(records: Vec<WineRecord>) -> Self {
+        Dataset::new(records)
+    }
+}
+
+fn column(headers: &StringRecord, name: &str) -> Result<usize, csv::Error> {
+    headers.iter().position(|header| header == name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("dataset is missing the \"{}\" column", name),
+        )
+        .into()
+    })
+}
+
+#[tracing::instrument(skip(data), fields(bytes = data.len()))]
+pub fn load_csv_data_from_str(data: &str) -> Result<Vec<WineRecord>, csv::Error> {
+    load_csv_data(data.as_bytes())
+}
+
+#[tracing::instrument]
+pub fn load_csv_data_from_path(path: &Path) -> Result<Vec<WineRecord>, csv::Error> {
+    load_csv_data(File::open(path)?)
+}
+
+/// Reads an ABV such as "ABV 14.00%" or "14".
+pub(crate) fn parse_abv(text: &str) -> Option<f64> {
+    text.trim()
+        .trim_start_matches("ABV")
+        .trim_end_matches('%')
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn load_csv_data(reader: impl io::Read) -> Result<Vec<WineRecord>, csv::Error> {
+    let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(reader);
+    let headers = rdr.headers()?.clone();
+    let grape = column(&headers, "Grape")?;
+    let characteristics = column(&headers, "Characteristics")?;
+    // Datasets without these columns still load.
+    let optional = |name: &str| headers.iter().position(|header| header == name);
+    let (wine_type, style, abv) = (optional("Type"), optional("Style"), optional("ABV"));
+
+    let mut records = Vec::new();
+    for result in rdr.records() {
+        let row = result?;
+        let cell =
+            |index: Option<usize>| index.and_then(|index| row.get(index)).unwrap_or_default();
+        records.push(WineRecord {
+            grape: row.get(grape).unwrap_or_default().to_owned(),
+            characteristics: row.get(characteristics).unwrap_or_default().to_owned(),
+            wine_type: cell(wine_type).trim().to_owned(),
+            style: cell(style).trim().to_owned(),
+            abv: parse_abv(cell(abv)),
+        });
+    }
+    info!(records = records.len(), "loaded wine dataset");
+    Ok(records)
+}
@@ -0,0 +1,106 @@
+//! Dissolved CO2 from the end of fermentation to bottling. A fermenting wine
+//! is saturated with CO2, and unless it is degassed, aged, or racked enough
+//! to lose it, a still wine tastes spritzy in the glass.
+
+use crate::calculators::residual_co2;
+use crate::cellar::RACKING_INTERVAL_MONTHS;
+use crate::engine::{
+    InterventionKind, SimulationInput, aging_vessel, is_red_grape, lees_contact, oxygen_ingress,
+    temperature_on, timeline,
+};
+
+/// Days in a month of aging.
+const DAYS_PER_MONTH: u32 = 30;
+
+/// Grams per liter of CO2 in one volume.
+pub const CO2_PER_VOLUME: f64 = 1.96;
+/// Share of the dissolved CO2 a racking knocks out.
+const RACKING_RELEASE: f64 = 0.2;
+/// Share of the dissolved CO2 a bâtonnage stir knocks out.
+const STIR_RELEASE: f64 = 0.03;
+/// Share of the dissolved CO2 lost each month from a tight vessel.
+const MONTHLY_LOSS: f64 = 0.06;
+/// Extra monthly loss per mg/L a year of oxygen the vessel lets in; what
+/// lets oxygen in lets CO2 out.
+const LOSS_PER_INGRESS: f64 = 0.004;
+// Dissolved CO2 in g/L above which a still wine tastes spritzy. Tannin
+// makes the prickle harsher in reds.
+const RED_SPRITZ_CO2: f64 = 0.8;
+const SPRITZ_CO2: f64 = 1.2;
+
+/// Dissolved CO2 in g/L as the fermentation finishes, saturated at the
+/// temperature it ended at.
+pub fn co2_at_pressing(input: &SimulationInput) -> f64 {
+    let last_day = input.fermentation_days.saturating_sub(1);
+    residual_co2(temperature_on(input, last_day)) * CO2_PER_VOLUME
+}
+
+/// Rackings in `month` of aging, counting from 1. Rackings on the timeline
+/// take the place of the cellar schedule's.
+fn rackings_in(input: &SimulationInput, month: u32, racking_days: &[u32]) -> usize {
+    if racking_days.is_empty() {
+        // The schedule racks off the fine lees, but not in the month the wine
+        // is bottled.
+        return usize::from(month % RACKING_INTERVAL_MONTHS == 0 && month < input.aging_months);
+    }
+    let aging_days = (month - 1) * DAYS_PER_MONTH + 1..=month * DAYS_PER_MONTH;
+    racking_days
+        .iter()
+        .filter(|&&day| aging_days.contains(&day.saturating_sub(input.fermentation_days)))
+        .count()
+}
+
+/// Dissolved CO2 in g/L after `months` of the aging `input` sets up, for
+/// following it month by month.
+pub fn dissolved_co2_after(input: &SimulationInput, months: u32) -> f64 {
+    let racking_days: Vec<u32> = timeline(input)
+        .iter()
+        .filter(|intervention| intervention.kind == InterventionKind::Racking)
+        .map(|intervention| intervention.day)
+        .collect();
+    // Racking off the gross lees.
+    let mut co2 = co2_at_pressing(input) * (1.0 - RACKING_RELEASE);
+    let monthly_loss =
+        (MONTHLY_LOSS + LOSS_PER_INGRESS * oxygen_ingress(aging_vessel(input))).min(1.0);
+    let stirs = if lees_contact(input) > 0.0 {
+        input.stirs_per_month
+    } else {
+        0
+    };
+    for month in 1..=months {
+        co2 *= 1.0 - monthly_loss;
+        if month <= input.lees_months {
+            co2 *= (1.0 - STIR_RELEASE).powi(stirs as i32);
+        }
+        co2 *= (1.0 - RACKING_RELEASE).powi(rackings_in(input, month, &racking_days) as i32);
+    }
+    co2
+}
+
+/// Dissolved CO2 in g/L on `day`, counting from the start of fermentation.
+/// A fermenting wine is saturated at its temperature that day.
+pub fn dissolved_co2_on(input: &SimulationInput, day: u32) -> f64 {
+    match day.checked_sub(input.fermentation_days) {
+        None => residual_co2(temperature_on(input, day)) * CO2_PER_VOLUME,
+        Some(aged) => dissolved_co2_after(input, (aged / DAYS_PER_MONTH).min(input.aging_months)),
+    }
+}
+
+/// Dissolved CO2 in g/L at bottling.
+pub fn dissolved_co2(input: &SimulationInput) -> f64 {
+    dissolved_co2_after(input, input.aging_months)
+}
+
+/// Dissolved CO2 in g/L past which the wine tastes spritzy.
+pub fn spritz_threshold(input: &SimulationInput) -> f64 {
+    if is_red_grape(&input.grape_type) {
+        RED_SPRITZ_CO2
+    } else {
+        SPRITZ_CO2
+    }
+}
+
+/// Whether the wine needs degassing before it is bottled as a still wine.
+pub fn needs_degassing(input: &SimulationInput) -> bool {
+    dissolved_co2(input) > spritz_threshold(input)
+}
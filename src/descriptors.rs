@@ -0,0 +1,326 @@
+//! The bands that turn residual sugar, alcohol, and body into words such as
+//! "bone dry" or "full-bodied". They are data rather than code so that they can be
+//! tuned from a `descriptors.json` in the config directory, and scales of
+//! one's own added. Scales the simulator doesn't know are reported alongside
+//! its own, in `SimulationResult::extra_descriptors`.
+//!
+//! The file is a JSON object of scales by name. A scale in it replaces the
+//! built-in one of the same name:
+//!
+//! ```json
+//! {
+//!   "sweetness": {
+//!     "measure": "ResidualSugar",
+//!     "base": "bone dry",
+//!     "bands": [
+//!       { "from": 4.0, "label": "off-dry" },
+//!       { "from": 12.0, "label": "medium" },
+//!       { "from": 45.0, "label": "sweet" }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! A value takes the label of the last band it passes, or `base` if it
+//! passes none. It passes a band when it is above `from`, or at it if the
+//! band is `inclusive`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::engine::Beverage;
+
+/// What a scale classifies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Measure {
+    /// Residual sugar in g/L.
+    ResidualSugar,
+    /// Percent alcohol by volume.
+    Abv,
+    /// The body index, in % ABV of a dry, unoaked wine as full; see
+    /// `body::body_index`.
+    Body,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Band {
+    pub from: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inclusive: bool,
+    pub label: String,
+}
+
+impl Band {
+    fn passed_by(&self, value: f64) -> bool {
+        if self.inclusive {
+            value >= self.from
+        } else {
+            value > self.from
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorScale {
+    pub measure: Measure,
+    /// The label below every band.
+    pub base: String,
+    /// In rising order of `from`.
+    pub bands: Vec<Band>,
+}
+
+impl DescriptorScale {
+    pub fn classify(&self, value: f64) -> &str {
+        self.bands
+            .iter()
+            .rev()
+            .find(|band| band.passed_by(value))
+            .map_or(&self.base, |band| &band.label)
+    }
+
+    #[cfg(feature = "serde")]
+    fn is_sorted(&self) -> bool {
+        self.bands
+            .windows(2)
+            .all(|pair| pair[0].from <= pair[1].from)
+    }
+}
+
+// Names of the scales the simulator describes its results with. Meads and
+// ciders have their own sweetness and body scales, under the name followed
+// by `.mead` or `.cider`.
+pub const SWEETNESS: &str = "sweetness";
+pub const BODY: &str = "body";
+pub const ALCOHOL: &str = "alcohol";
+
+fn scale(measure: Measure, base: &str, bands: &[(f64, bool, &str)]) -> DescriptorScale {
+    DescriptorScale {
+        measure,
+        base: base.to_owned(),
+        bands: bands
+            .iter()
+            .map(|&(from, inclusive, label)| Band {
+                from,
+                inclusive,
+                label: label.to_owned(),
+            })
+            .collect(),
+    }
+}
+
+#[derive(Debug)]
+pub enum DescriptorError {
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    /// The named scale's bands are out of order.
+    Unsorted(String),
+}
+
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "serde")]
+            DescriptorError::Json(err) => write!(f, "invalid descriptor scales: {}", err),
+            DescriptorError::Unsorted(name) => {
+                write!(f, "the bands of the {} scale are out of order", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DescriptorError {}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for DescriptorError {
+    fn from(err: serde_json::Error) -> Self {
+        DescriptorError::Json(err)
+    }
+}
+
+/// Every scale, by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescriptorScales {
+    pub scales: BTreeMap<String, DescriptorScale>,
+}
+
+impl Default for DescriptorScales {
+    /// The simulator's own scales.
+    fn default() -> Self {
+        use Measure::{Abv, Body, ResidualSugar};
+
+        let scales = [
+            (
+                SWEETNESS.to_owned(),
+                scale(
+                    ResidualSugar,
+                    "bone dry",
+                    &[
+                        (5.0, false, "with just a subtle hint of sweetness"),
+                        (20.0, false, "noticeably sweet"),
+                        (35.0, false, "extremely sweet"),
+                    ],
+                ),
+            ),
+            (
+                format!("{}.mead", SWEETNESS),
+                scale(
+                    ResidualSugar,
+                    "dry",
+                    &[
+                        (10.0, false, "semi-sweet"),
+                        (25.0, false, "sweet"),
+                        (50.0, false, "sack-sweet"),
+                    ],
+                ),
+            ),
+            (
+                format!("{}.cider", SWEETNESS),
+                scale(
+                    ResidualSugar,
+                    "dry",
+                    &[
+                        (9.0, false, "medium dry"),
+                        (25.0, false, "medium sweet"),
+                        (45.0, false, "sweet"),
+                    ],
+                ),
+            ),
+            (
+                BODY.to_owned(),
+                scale(
+                    Body,
+                    "light-bodied",
+                    &[(10.0, true, "medium-bodied"), (12.0, false, "full-bodied")],
+                ),
+            ),
+            (
+                format!("{}.mead", BODY),
+                scale(
+                    Body,
+                    "light-bodied",
+                    &[(8.0, true, "medium-bodied"), (14.0, false, "full-bodied")],
+                ),
+            ),
+            (
+                format!("{}.cider", BODY),
+                scale(
+                    Body,
+                    "light-bodied",
+                    &[(6.5, true, "medium-bodied"), (8.5, false, "full-bodied")],
+                ),
+            ),
+            (
+                ALCOHOL.to_owned(),
+                scale(
+                    Abv,
+                    "extremely low",
+                    &[
+                        (1.0, false, "very low"),
+                        (5.0, true, "low"),
+                        (10.0, true, "moderate"),
+                        (13.5, true, "high"),
+                        (15.0, true, "very high"),
+                        (20.0, true, "extremely high"),
+                    ],
+                ),
+            ),
+        ];
+        DescriptorScales {
+            scales: scales.into_iter().collect(),
+        }
+    }
+}
+
+/// Whether `name` is one of the scales the simulator describes results with.
+fn is_builtin(name: &str) -> bool {
+    let kind = name.split('.').next().unwrap_or_default();
+    [SWEETNESS, BODY, ALCOHOL].contains(&kind)
+}
+
+impl DescriptorScales {
+    /// The built-in scales with those in `json` added or put in their place.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, DescriptorError> {
+        let overrides: BTreeMap<String, DescriptorScale> = serde_json::from_str(json)?;
+        let mut scales = DescriptorScales::default();
+        for (name, scale) in overrides {
+            if !scale.is_sorted() {
+                return Err(DescriptorError::Unsorted(name));
+            }
+            scales.scales.insert(name, scale);
+        }
+        Ok(scales)
+    }
+
+    /// The scale `name` for `beverage`: its own if it has one, as
+    /// `sweetness.mead` is for meads, or else the general one.
+    pub fn scale(&self, name: &str, beverage: Beverage) -> Option<&DescriptorScale> {
+        let own = match beverage {
+            Beverage::Mead => Some("mead"),
+            Beverage::Cider => Some("cider"),
+            Beverage::Wine | Beverage::FruitWine => None,
+        };
+        own.and_then(|own| self.scales.get(&format!("{}.{}", name, own)))
+            .or_else(|| self.scales.get(name))
+    }
+
+    /// How `value` reads on the scale `name` for `beverage`, or an empty
+    /// string if there is no such scale.
+    pub fn describe(&self, name: &str, beverage: Beverage, value: f64) -> String {
+        self.scale(name, beverage)
+            .map(|scale| scale.classify(value).to_owned())
+            .unwrap_or_default()
+    }
+
+    /// Scales added beyond the simulator's own, with how the wine reads on
+    /// each.
+    pub fn extra(&self, residual_sugar: f64, abv: f64, body: f64) -> Vec<(String, String)> {
+        self.scales
+            .iter()
+            .filter(|(name, _)| !is_builtin(name))
+            .map(|(name, scale)| {
+                let value = match scale.measure {
+                    Measure::ResidualSugar => residual_sugar,
+                    Measure::Abv => abv,
+                    Measure::Body => body,
+                };
+                (name.clone(), scale.classify(value).to_owned())
+            })
+            .collect()
+    }
+}
+
+static BUILT_IN: LazyLock<Arc<DescriptorScales>> =
+    LazyLock::new(|| Arc::new(DescriptorScales::default()));
+
+static INSTALLED: RwLock<Option<Arc<DescriptorScales>>> = RwLock::new(None);
+
+/// Makes `run_simulation` describe results with `scales`, as when the app
+/// starts with a descriptors file.
+pub fn install(scales: DescriptorScales) {
+    *INSTALLED.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(scales));
+}
+
+/// Goes back to the built-in scales.
+pub fn uninstall() {
+    *INSTALLED.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// The built-in scales, whatever is installed. Challenges and achievements
+/// are judged on these so that everyone plays by the same rules.
+pub fn standard() -> &'static DescriptorScales {
+    &BUILT_IN
+}
+
+/// The scales results are described with.
+pub fn installed() -> Arc<DescriptorScales> {
+    INSTALLED
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+        .unwrap_or_else(|| Arc::clone(&BUILT_IN))
+}
@@ -0,0 +1,277 @@
+//! Import of logs exported by digital hydrometers such as the Tilt and the
+//! iSpindel. Both CSV and JSON exports are accepted, and the timestamp,
+//! gravity, and temperature fields are found by name.
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveDateTime};
+use serde_json::Value;
+
+use crate::cellar::Measurement;
+use crate::engine::sugar_from_gravity;
+
+const TIME_NAMES: [&str; 5] = ["timestamp", "time", "datetime", "date", "created_at"];
+const GRAVITY_NAMES: [&str; 4] = ["sg", "gravity", "specific gravity", "specificgravity"];
+const TEMPERATURE_NAMES: [&str; 3] = ["temp", "temperature", "tempf"];
+const UNIT_NAMES: [&str; 2] = ["temp_units", "tempunits"];
+
+const TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%m/%d/%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M:%S",
+];
+
+// Fermenting must never gets this warm in °C, so hotter readings are °F.
+const MAX_CELSIUS_READING: f64 = 45.0;
+
+/// One reading from a device log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceReading {
+    pub taken_at: NaiveDateTime,
+    pub specific_gravity: f64,
+    /// Temperature in °C.
+    pub temperature: Option<f64>,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Csv(csv::Error),
+    Json(serde_json::Error),
+    MissingField(&'static str),
+    Invalid { line: usize, message: String },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Csv(err) => write!(f, "{}", err),
+            ImportError::Json(err) => write!(f, "{}", err),
+            ImportError::MissingField(field) => write!(f, "no {} column found", field),
+            ImportError::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<csv::Error> for ImportError {
+    fn from(err: csv::Error) -> Self {
+        ImportError::Csv(err)
+    }
+}
+
+impl From<serde_json::Error> for ImportError {
+    fn from(err: serde_json::Error) -> Self {
+        ImportError::Json(err)
+    }
+}
+
+impl DeviceReading {
+    /// The reading as a batch measurement, converting gravity to sugar for a
+    /// must that started with `initial_sugar` g/L.
+    pub fn to_measurement(&self, initial_sugar: f64, notes: &str) -> Measurement {
+        Measurement {
+            measured_on: self.taken_at.date(),
+            sugar_content: sugar_from_gravity(self.specific_gravity, initial_sugar),
+            specific_gravity: Some(self.specific_gravity),
+            temperature: self.temperature,
+            notes: notes.to_owned(),
+        }
+    }
+}
+
+// Lowercases a header and drops any unit suffix, e.g. "Temp (°F)" -> "temp".
+fn normalize(name: &str) -> String {
+    name.split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+fn is_fahrenheit_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains("°f") || name.contains("(f)") || name.ends_with("tempf")
+}
+
+fn parse_time(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.naive_utc());
+    }
+    if let Ok(seconds) = text.parse::<i64>() {
+        return DateTime::from_timestamp(seconds, 0).map(|time| time.naive_utc());
+    }
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+}
+
+/// Converts a gravity that may be in °Plato to specific gravity.
+pub(crate) fn to_specific_gravity(gravity: f64) -> f64 {
+    if gravity > 2.0 {
+        1.0 + gravity / (258.6 - gravity / 258.2 * 227.1)
+    } else {
+        gravity
+    }
+}
+
+/// Converts a temperature to °C, treating it as °F if `fahrenheit` says so
+/// or, when unknown, if it is too hot to be °C.
+pub(crate) fn to_celsius(temperature: f64, fahrenheit: Option<bool>) -> f64 {
+    if fahrenheit.unwrap_or(temperature > MAX_CELSIUS_READING) {
+        (temperature - 32.0) * 5.0 / 9.0
+    } else {
+        temperature
+    }
+}
+
+fn reading(
+    line: usize,
+    time: Option<&str>,
+    gravity: Option<f64>,
+    temperature: Option<f64>,
+    fahrenheit: Option<bool>,
+) -> Result<DeviceReading, ImportError> {
+    let invalid = |message: &str| ImportError::Invalid {
+        line,
+        message: message.to_owned(),
+    };
+    let taken_at = time
+        .and_then(parse_time)
+        .ok_or_else(|| invalid("unrecognized timestamp"))?;
+    let gravity = gravity.ok_or_else(|| invalid("missing gravity"))?;
+    Ok(DeviceReading {
+        taken_at,
+        specific_gravity: to_specific_gravity(gravity),
+        temperature: temperature.map(|temperature| to_celsius(temperature, fahrenheit)),
+    })
+}
+
+fn import_csv(text: &str) -> Result<Vec<DeviceReading>, ImportError> {
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let find = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|header| names.contains(&normalize(header).as_str()))
+    };
+    let time = find(&TIME_NAMES).ok_or(ImportError::MissingField("timestamp"))?;
+    let gravity = find(&GRAVITY_NAMES).ok_or(ImportError::MissingField("gravity"))?;
+    let temperature = find(&TEMPERATURE_NAMES);
+    let units = find(&UNIT_NAMES);
+    let fahrenheit_header = temperature.map(|column| is_fahrenheit_name(&headers[column]));
+
+    reader
+        .records()
+        .enumerate()
+        .map(|(i, record)| {
+            let record = record?;
+            let number = |column: usize| record.get(column)?.trim().parse::<f64>().ok();
+            let fahrenheit = units
+                .and_then(|column| record.get(column))
+                .map(|unit| unit.trim().eq_ignore_ascii_case("f"))
+                .or(fahrenheit_header.filter(|&fahrenheit| fahrenheit));
+            reading(
+                i + 2,
+                record.get(time),
+                number(gravity),
+                temperature.and_then(number),
+                fahrenheit,
+            )
+        })
+        .collect()
+}
+
+/// The raw reading fields of a JSON object, found by name.
+pub(crate) struct JsonFields {
+    pub time: Option<String>,
+    pub gravity: Option<f64>,
+    pub temperature: Option<f64>,
+    pub fahrenheit: Option<bool>,
+}
+
+impl JsonFields {
+    pub fn from_value(object: &Value) -> Self {
+        let field = |names: &[&str]| {
+            object.as_object()?.iter().find_map(|(key, value)| {
+                names
+                    .contains(&normalize(key).as_str())
+                    .then_some((key, value))
+            })
+        };
+        let number = |value: &Value| {
+            value
+                .as_f64()
+                .or_else(|| value.as_str()?.trim().parse().ok())
+        };
+        let temperature = field(&TEMPERATURE_NAMES);
+        JsonFields {
+            time: field(&TIME_NAMES).and_then(|(_, value)| match value {
+                Value::String(text) => Some(text.clone()),
+                Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            }),
+            gravity: field(&GRAVITY_NAMES).and_then(|(_, value)| number(value)),
+            temperature: temperature.and_then(|(_, value)| number(value)),
+            fahrenheit: field(&UNIT_NAMES)
+                .and_then(|(_, unit)| unit.as_str())
+                .map(|unit| unit.trim().eq_ignore_ascii_case("f"))
+                .or(temperature
+                    .map(|(key, _)| is_fahrenheit_name(key))
+                    .filter(|&fahrenheit| fahrenheit)),
+        }
+    }
+
+    /// Gravity as SG and temperature in °C.
+    pub fn normalized(&self) -> (Option<f64>, Option<f64>) {
+        (
+            self.gravity.map(to_specific_gravity),
+            self.temperature
+                .map(|temperature| to_celsius(temperature, self.fahrenheit)),
+        )
+    }
+}
+
+fn import_json(text: &str) -> Result<Vec<DeviceReading>, ImportError> {
+    let trimmed = text.trim_start();
+    let objects: Vec<Value> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed)?
+    } else {
+        // One object per line, as logged by iSpindel HTTP endpoints.
+        trimmed
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+
+    objects
+        .iter()
+        .enumerate()
+        .map(|(i, object)| {
+            let fields = JsonFields::from_value(object);
+            reading(
+                i + 1,
+                fields.time.as_deref(),
+                fields.gravity,
+                fields.temperature,
+                fields.fahrenheit,
+            )
+        })
+        .collect()
+}
+
+/// Parses a Tilt or iSpindel log export in CSV or JSON form.
+#[tracing::instrument(skip(text), fields(bytes = text.len()))]
+pub fn import_readings(text: &str) -> Result<Vec<DeviceReading>, ImportError> {
+    let trimmed = text.trim_start();
+    let readings = if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        import_json(text)?
+    } else {
+        import_csv(text)?
+    };
+    tracing::info!(readings = readings.len(), "imported device log");
+    Ok(readings)
+}
@@ -0,0 +1,1863 @@
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use tracing::{debug, warn};
+
+use crate::balance::{BalanceFault, balance_faults};
+use crate::body::{body_index, glycerol, strain_glycerol};
+use crate::calibration::{self, Calibration};
+use crate::color::wine_color;
+use crate::dataset::Dataset;
+use crate::degassing::{dissolved_co2, needs_degassing};
+use crate::descriptors::{self, ALCOHOL, BODY, SWEETNESS};
+use crate::grapes::grape_profile;
+use crate::sulfides::sulfide_fault;
+use crate::sweetness_category::{SparklingCategory, StillCategory};
+
+/// Bumped whenever a change to the kinetics or the descriptors changes what
+/// `run_simulation` returns for the same inputs; see `model_changelog`.
+pub const MODEL_VERSION: u32 = 6;
+
+/// Sugar in g/L that makes 1% ABV, before any calibration.
+pub const SUGAR_PER_ABV: f64 = 16.83;
+pub const YEAST_ALCOHOL_TOLERANCE: f64 = 15.0;
+/// Percent ABV below the tolerance at which the yeast start to slow down.
+pub const YEAST_DIE_OFF_WINDOW: f64 = 2.0;
+/// Alcohol tolerance of the champagne yeast pitched to restart a sluggish
+/// fermentation.
+pub const RESTART_YEAST_TOLERANCE: f64 = 18.0;
+pub const MIN_YEAST_TEMPERATURE: f64 = 5.0;
+pub const MAX_YEAST_TEMPERATURE: f64 = 40.0;
+/// Longest fermentation the simulator runs; anything longer is aging.
+pub const MAX_FERMENTATION_DAYS: u32 = 365;
+
+pub const GRAPES: [&str; 11] = [
+    "Cabernet Sauvignon",
+    "Merlot",
+    "Pinot Noir",
+    "Chardonnay",
+    "Sauvignon Blanc",
+    "Riesling",
+    "Syrah",
+    "Shiraz",
+    "Zinfandel",
+    "Tempranillo",
+    "Sangiovese",
+];
+/// Names of the vessels in `CONTAINER_CATALOG`, in the same order.
+pub const CONTAINERS: [&str; CONTAINER_CATALOG.len()] = {
+    let mut names = [""; CONTAINER_CATALOG.len()];
+    let mut i = 0;
+    while i < names.len() {
+        names[i] = CONTAINER_CATALOG[i].name;
+        i += 1;
+    }
+    names
+};
+pub const CLIMATES: [&str; 3] = ["Cool", "Moderate", "Warm"];
+/// The red grapes in `GRAPES`, which ferment on their skins.
+pub const RED_GRAPES: [&str; 8] = [
+    "Cabernet Sauvignon",
+    "Merlot",
+    "Pinot Noir",
+    "Syrah",
+    "Shiraz",
+    "Zinfandel",
+    "Tempranillo",
+    "Sangiovese",
+];
+pub const HONEYS: [&str; 5] = [
+    "Wildflower",
+    "Orange Blossom",
+    "Clover",
+    "Buckwheat",
+    "Acacia",
+];
+pub const APPLES: [&str; 5] = [
+    "Dabinett",
+    "Kingston Black",
+    "Bramley",
+    "Golden Delicious",
+    "Granny Smith",
+];
+pub const FRUITS: [&str; 6] = [
+    "Blackberry",
+    "Elderberry",
+    "Cherry",
+    "Plum",
+    "Strawberry",
+    "Rhubarb",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Material {
+    Oak,
+    Steel,
+    Clay,
+    Concrete,
+    Glass,
+    Polyethylene,
+}
+
+impl Material {
+    pub fn label(self) -> &'static str {
+        match self {
+            Material::Oak => "oak",
+            Material::Steel => "stainless steel",
+            Material::Clay => "clay",
+            Material::Concrete => "concrete",
+            Material::Glass => "glass",
+            Material::Polyethylene => "polyethylene",
+        }
+    }
+}
+
+/// A fermentation or aging vessel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Container {
+    pub name: &'static str,
+    /// Usual capacity in liters.
+    pub size: f64,
+    pub material: Material,
+    /// Oxygen let into the wine through the walls and bung, in mg/L per year
+    /// at the vessel's usual size.
+    pub oxygen_transfer: f64,
+    /// What the vessel adds to the wine.
+    pub note: &'static str,
+}
+
+pub const CONTAINER_CATALOG: [Container; 9] = [
+    Container {
+        name: "Oak Barrel",
+        size: 225.0,
+        material: Material::Oak,
+        oxygen_transfer: 25.0,
+        note: "woody, oaky undertones",
+    },
+    Container {
+        name: "Steel Tank",
+        size: 1000.0,
+        material: Material::Steel,
+        // Kept topped up, it lets in almost nothing.
+        oxygen_transfer: 1.0,
+        note: "a pristine, clean character",
+    },
+    Container {
+        name: "Clay Amphora",
+        size: 300.0,
+        material: Material::Clay,
+        oxygen_transfer: 15.0,
+        note: "earthy nuances",
+    },
+    Container {
+        name: "Barrique",
+        size: 225.0,
+        material: Material::Oak,
+        // New French oak breathes more than a seasoned barrel.
+        oxygen_transfer: 30.0,
+        note: "toasty vanilla and spice",
+    },
+    Container {
+        name: "Puncheon",
+        size: 500.0,
+        material: Material::Oak,
+        // Less surface per liter than a barrique.
+        oxygen_transfer: 18.0,
+        note: "subtle oak that lets the fruit lead",
+    },
+    Container {
+        name: "Concrete Egg",
+        size: 1600.0,
+        material: Material::Concrete,
+        oxygen_transfer: 8.0,
+        note: "a round, textured mouthfeel",
+    },
+    Container {
+        name: "Qvevri",
+        size: 2000.0,
+        material: Material::Clay,
+        // Buried and lined with beeswax, so it breathes far less than an
+        // amphora.
+        oxygen_transfer: 5.0,
+        note: "deep, earthy, savory notes",
+    },
+    Container {
+        name: "Glass Carboy",
+        size: 23.0,
+        material: Material::Glass,
+        oxygen_transfer: 1.0,
+        note: "a neutral, unadorned character",
+    },
+    Container {
+        name: "Flex Tank",
+        size: 1000.0,
+        material: Material::Polyethylene,
+        oxygen_transfer: 12.0,
+        note: "a clean character that still breathes",
+    },
+];
+
+/// Looks a vessel up by name, ignoring case.
+pub fn container(name: &str) -> Option<&'static Container> {
+    CONTAINER_CATALOG
+        .iter()
+        .find(|container| container.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// A commercial yeast strain and the aromas it brings out. Which ones show
+/// depends on how warm the fermentation runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YeastStrain {
+    pub name: &'static str,
+    /// What the strain is known for.
+    pub note: &'static str,
+    /// Fermentations averaging this cool or cooler get the cool aromas.
+    pub cool_below: f64,
+    pub cool_aromas: &'static [&'static str],
+    pub warm_aromas: &'static [&'static str],
+    /// Thiols the strain frees from thiol-rich grapes in a cool fermentation.
+    pub thiols: &'static [&'static str],
+    /// Glycerol the strain makes, relative to an average wine yeast.
+    pub glycerol: f64,
+}
+
+pub const YEAST_STRAINS: [YeastStrain; 5] = [
+    YeastStrain {
+        name: "EC-1118",
+        note: "a vigorous, neutral champagne strain",
+        cool_below: 15.0,
+        cool_aromas: &[],
+        warm_aromas: &[],
+        thiols: &[],
+        glycerol: 0.9,
+    },
+    YeastStrain {
+        name: "QA23",
+        note: "a crisp white wine strain that releases thiols",
+        cool_below: 16.0,
+        cool_aromas: &["citrus blossom"],
+        warm_aromas: &["lemon"],
+        thiols: &["passionfruit", "grapefruit"],
+        glycerol: 1.0,
+    },
+    YeastStrain {
+        name: "71B",
+        note: "a fruity strain for young wines and ciders",
+        cool_below: 18.0,
+        cool_aromas: &["pear", "banana"],
+        warm_aromas: &["soft red fruit"],
+        thiols: &[],
+        glycerol: 1.1,
+    },
+    YeastStrain {
+        name: "RC212",
+        note: "a Burgundian red wine strain",
+        cool_below: 22.0,
+        cool_aromas: &["bright cherry"],
+        warm_aromas: &["ripe berry", "spice"],
+        thiols: &[],
+        glycerol: 1.05,
+    },
+    YeastStrain {
+        name: "D47",
+        note: "a rich strain for whites and meads that likes it cool",
+        cool_below: 18.0,
+        cool_aromas: &["tropical fruit", "honeysuckle"],
+        warm_aromas: &["orange peel"],
+        thiols: &[],
+        glycerol: 1.15,
+    },
+];
+
+/// Grapes rich in the thiol precursors that the right yeast turn into
+/// passionfruit and grapefruit aromas.
+const THIOL_GRAPES: [&str; 1] = ["Sauvignon Blanc"];
+
+/// Looks a yeast strain up by name, ignoring case.
+pub fn yeast_strain(name: &str) -> Option<&'static YeastStrain> {
+    YEAST_STRAINS
+        .iter()
+        .find(|strain| strain.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Choose as `SimulationInput::yeast` to leave the fermentation to the native
+/// yeast on the fruit and in the cellar.
+pub const AMBIENT_YEAST: &str = "Ambient";
+/// Days a non-Saccharomyces co-pitch holds the start back.
+const NON_SACCHAROMYCES_LAG: f64 = 1.0;
+/// Factor co-inoculated malolactic bacteria slow the yeast by.
+const CO_INOCULATION_SLOWDOWN: f64 = 0.9;
+/// Typical length of a malolactic fermentation on its own.
+pub const MALOLACTIC_DAYS: u32 = 42;
+/// Chance a wild fermentation picks up a flaw rather than complexity.
+const WILD_FLAW_CHANCE: f64 = 0.15;
+const WILD_CHARACTERS: [&str; 4] = [
+    "savory, funky complexity",
+    "wild herbs and orchard fruit",
+    "a creamy, layered texture",
+    "earthy, forest-floor depth",
+];
+/// The wild flaw that shows up as volatile acidity on the lab sheet.
+pub const VOLATILE_FLAW: &str = "volatile, nail-polish acidity";
+const WILD_FLAWS: [&str; 3] = [
+    VOLATILE_FLAW,
+    "mousy, barnyard Brettanomyces",
+    "sulfurous, rotten-egg reduction",
+];
+
+/// `input` with a seed drawn from the OS if it has none. Running, charting,
+/// and describing the result all from this keeps one run's draw together.
+pub fn seeded(input: &SimulationInput) -> SimulationInput {
+    SimulationInput {
+        seed: Some(input.seed.unwrap_or_else(rand::random)),
+        ..input.clone()
+    }
+}
+
+/// How a wild fermentation turns out. Drawn from the input's seed, so the
+/// same seed always gives the same ferment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WildFerment {
+    /// Days before the native yeast get going.
+    pub lag_days: f64,
+    /// Alcohol tolerance of the native population in % ABV.
+    pub tolerance: f64,
+    /// What the native yeast add to the wine.
+    pub character: &'static str,
+    /// Whether that character is a flaw.
+    pub flawed: bool,
+}
+
+/// The draw for an ambient-yeast fermentation, or `None` for any other yeast.
+/// An unseeded input is read as seed 0, so that every day of one plan agrees;
+/// pass it through `seeded` first to give each run its own draw.
+pub fn wild_ferment(input: &SimulationInput) -> Option<WildFerment> {
+    if !input.yeast.trim().eq_ignore_ascii_case(AMBIENT_YEAST) {
+        return None;
+    }
+    let mut rng = StdRng::seed_from_u64(input.seed.unwrap_or_default());
+    let lag_days = rng.random_range(1.0..5.0);
+    // Native populations rarely finish as strong as a cultured strain.
+    let tolerance = rng.random_range(11.0..YEAST_ALCOHOL_TOLERANCE);
+    let flawed = rng.random_bool(WILD_FLAW_CHANCE);
+    let characters: &[&str] = if flawed {
+        &WILD_FLAWS
+    } else {
+        &WILD_CHARACTERS
+    };
+    Some(WildFerment {
+        lag_days,
+        tolerance,
+        character: characters.choose(&mut rng).unwrap(),
+        flawed,
+    })
+}
+
+/// How the yeast and any malolactic bacteria are pitched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Inoculation {
+    /// The chosen yeast on its own, with no malolactic fermentation.
+    #[default]
+    Single,
+    /// A non-Saccharomyces yeast such as Torulaspora pitched alongside. It
+    /// holds the start back but adds complex esters.
+    NonSaccharomyces,
+    /// Malolactic bacteria pitched once the yeast have finished.
+    SequentialMalolactic,
+    /// Malolactic bacteria pitched with the yeast, competing with them for
+    /// nutrients but finishing alongside.
+    CoInoculatedMalolactic,
+}
+
+impl Inoculation {
+    pub const ALL: [Inoculation; 4] = [
+        Inoculation::Single,
+        Inoculation::NonSaccharomyces,
+        Inoculation::SequentialMalolactic,
+        Inoculation::CoInoculatedMalolactic,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Inoculation::Single => "Single yeast",
+            Inoculation::NonSaccharomyces => "Non-Saccharomyces co-pitch",
+            Inoculation::SequentialMalolactic => "Sequential malolactic",
+            Inoculation::CoInoculatedMalolactic => "Malolactic co-inoculation",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|inoculation| inoculation.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    pub fn is_malolactic(self) -> bool {
+        matches!(
+            self,
+            Inoculation::SequentialMalolactic | Inoculation::CoInoculatedMalolactic
+        )
+    }
+
+    /// Days the strategy holds the start of the fermentation back.
+    fn lag_days(self) -> f64 {
+        match self {
+            Inoculation::NonSaccharomyces => NON_SACCHAROMYCES_LAG,
+            _ => 0.0,
+        }
+    }
+
+    /// Factor the strategy scales the fermentation rate by.
+    pub(crate) fn rate_factor(self) -> f64 {
+        match self {
+            Inoculation::CoInoculatedMalolactic => CO_INOCULATION_SLOWDOWN,
+            _ => 1.0,
+        }
+    }
+
+    /// Days the malolactic fermentation runs on after the yeast finish.
+    pub fn malolactic_days_after(self) -> u32 {
+        match self {
+            Inoculation::SequentialMalolactic => MALOLACTIC_DAYS,
+            _ => 0,
+        }
+    }
+}
+
+/// What the bottles are sealed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Closure {
+    #[default]
+    NaturalCork,
+    /// A technical cork of cleaned, agglomerated granules.
+    Diam,
+    Screwcap,
+}
+
+impl Closure {
+    pub const ALL: [Closure; 3] = [Closure::NaturalCork, Closure::Diam, Closure::Screwcap];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Closure::NaturalCork => "Natural cork",
+            Closure::Diam => "DIAM",
+            Closure::Screwcap => "Screwcap",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|closure| closure.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// Oxygen let into the bottle in mg/L per year.
+    pub fn oxygen_transfer(self) -> f64 {
+        match self {
+            Closure::NaturalCork => 1.5,
+            Closure::Diam => 0.7,
+            Closure::Screwcap => 0.2,
+        }
+    }
+}
+
+/// Alcohol tolerance in % ABV of the yeast doing the fermenting.
+pub fn yeast_tolerance(input: &SimulationInput) -> f64 {
+    wild_ferment(input).map_or(YEAST_ALCOHOL_TOLERANCE, |wild| wild.tolerance)
+}
+
+/// Fraction the water in the stems dilutes the must's sugar by when every
+/// cluster goes in whole.
+const STEM_DILUTION: f64 = 0.03;
+
+/// Fermenting this hot or hotter stresses the yeast into making fusel
+/// alcohols.
+pub const HEAT_STRESS_TEMPERATURE: f64 = 30.0;
+/// A sudden drop of this many °C or more shocks the yeast.
+pub const COLD_SHOCK_DROP: f64 = 8.0;
+/// The fermentation counts as still active until this fraction is done.
+const ACTIVE_FRACTION: f64 = 0.9;
+/// A fermentation averaging this warm or warmer leaves a hint of fusel.
+pub const FUSEL_TEMPERATURE: f64 = 27.0;
+/// White ferments averaging this cool or cooler hold on to their esters.
+pub const ESTER_TEMPERATURE: f64 = 16.0;
+
+/// Development in bottle, in mg/L of oxygen a year, that happens without
+/// any oxygen getting in.
+const ANAEROBIC_DEVELOPMENT: f64 = 0.8;
+/// Closures letting in less oxygen than this, in mg/L a year, can leave a
+/// wine reductive.
+const REDUCTION_TRANSFER: f64 = 0.5;
+/// Years a reductive wine takes to open up in bottle.
+const REDUCTIVE_YEARS: u32 = 1;
+/// Oxygen in mg/L even a heavily oxidized wine can still take in bottle.
+const MIN_BOTTLE_CAPACITY: f64 = 2.0;
+
+/// How much each monthly stir adds to a month on the lees.
+const STIRRING_WEIGHT: f64 = 0.25;
+/// Lees contact, as weighted by `lees_contact`, past which the acidity
+/// reads a step softer.
+const LEES_SOFTENING_CONTACT: f64 = 6.0;
+
+/// Titratable acidity in g/L of a wine grape without a profile in
+/// `grapes`, and of the other beverages.
+const WINE_ACIDITY: f64 = 6.5;
+const MEAD_ACIDITY: f64 = 4.0;
+const CIDER_ACIDITY: f64 = 6.0;
+const FRUIT_WINE_ACIDITY: f64 = 7.5;
+/// How much higher in g/L the acidity of grapes from a cool climate is, or
+/// lower from a warm one.
+const CLIMATE_ACIDITY: f64 = 1.0;
+/// Acidity in g/L lost when malolactic bacteria turn malic acid into
+/// lactic.
+const MALOLACTIC_ACIDITY: f64 = 1.5;
+/// Acidity in g/L lost to long lees contact, as tartrates settle out.
+const LEES_ACIDITY: f64 = 0.3;
+/// Residual sugar in g/L that each g/L of acidity above the beverage's
+/// usual masks, or that each g/L below it seems to add.
+const ACID_MASKING: f64 = 3.0;
+
+/// Share of a red wine that runs free from the fermenter; the rest has to be
+/// pressed out of the skins.
+pub const FREE_RUN_SHARE: f64 = 0.8;
+
+/// Fraction of honey's weight that is fermentable sugar.
+pub const HONEY_SUGAR_FRACTION: f64 = 0.8;
+
+// Gravity contributed by each g/L of sugar, by the non-sugar extract, and
+// removed by each % ABV of ethanol.
+pub const SG_PER_SUGAR: f64 = 0.0004;
+const SG_DRY_EXTRACT: f64 = 0.007;
+const SG_PER_ABV: f64 = 0.0013;
+
+pub const REF_TEMPERATURE: f64 = 20.0;
+/// Rate constant per day at `REF_TEMPERATURE`, and how many times faster
+/// each 10 °C warmer runs, before any calibration.
+pub const K_REF: f64 = 0.20;
+pub const Q10: f64 = 2.0;
+
+/// What is being fermented. Everything but wine starts from a must with no
+/// skins in it and no vineyard climate behind it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Beverage {
+    #[default]
+    Wine,
+    Mead,
+    Cider,
+    // Spelled as in `label`, like the others.
+    #[cfg_attr(feature = "serde", serde(rename = "Fruit wine"))]
+    FruitWine,
+}
+
+impl Beverage {
+    pub const ALL: [Beverage; 4] = [
+        Beverage::Wine,
+        Beverage::Mead,
+        Beverage::Cider,
+        Beverage::FruitWine,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Beverage::Wine => "Wine",
+            Beverage::Mead => "Mead",
+            Beverage::Cider => "Cider",
+            Beverage::FruitWine => "Fruit wine",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|beverage| beverage.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// What `SimulationInput::grape_type` names for this beverage.
+    pub fn substrate_label(self) -> &'static str {
+        match self {
+            Beverage::Wine => "Grape",
+            Beverage::Mead => "Honey",
+            Beverage::Cider => "Apple",
+            Beverage::FruitWine => "Fruit",
+        }
+    }
+
+    pub fn substrates(self) -> &'static [&'static str] {
+        match self {
+            Beverage::Wine => &GRAPES,
+            Beverage::Mead => &HONEYS,
+            Beverage::Cider => &APPLES,
+            Beverage::FruitWine => &FRUITS,
+        }
+    }
+
+    /// What the finished drink is called, as in "Your Clover mead".
+    pub fn noun(self) -> &'static str {
+        match self {
+            Beverage::Wine | Beverage::FruitWine => "wine",
+            Beverage::Mead => "mead",
+            Beverage::Cider => "cider",
+        }
+    }
+
+    /// Whether the climate the grapes grew in changes the must; honey and
+    /// pressed fruit are taken as they come.
+    pub fn uses_climate(self) -> bool {
+        self == Beverage::Wine
+    }
+
+    /// Days at the start of fermentation that each get a nutrient addition.
+    /// Honey has almost no yeast nutrients of its own.
+    pub fn early_nutrient_additions(self) -> u32 {
+        match self {
+            Beverage::Mead => 3,
+            _ => 1,
+        }
+    }
+
+    /// Yeast-assimilable nitrogen in mg/L the must brings with it.
+    pub fn yeast_nitrogen(self) -> f64 {
+        match self {
+            Beverage::Wine => 200.0,
+            Beverage::Mead => 20.0,
+            Beverage::Cider => 100.0,
+            Beverage::FruitWine => 120.0,
+        }
+    }
+
+    /// Typical must sugar in g/L, for the input hint.
+    pub fn sugar_range(self) -> (f64, f64) {
+        match self {
+            Beverage::Wine => (180.0, 300.0),
+            Beverage::Mead => (200.0, 400.0),
+            Beverage::Cider => (90.0, 140.0),
+            Beverage::FruitWine => (180.0, 260.0),
+        }
+    }
+
+    /// A typical starting point for this beverage.
+    pub fn defaults(self) -> SimulationInput {
+        let (substrate, fermentation_days, container, sugar_content, temperature) = match self {
+            Beverage::Wine => ("Cabernet Sauvignon", 14, "Oak Barrel", 240.0, 24.0),
+            Beverage::Mead => ("Wildflower", 30, "Steel Tank", 300.0, 18.0),
+            Beverage::Cider => ("Dabinett", 14, "Steel Tank", 110.0, 15.0),
+            Beverage::FruitWine => ("Blackberry", 21, "Steel Tank", 220.0, 20.0),
+        };
+        SimulationInput {
+            beverage: self,
+            grape_type: substrate.to_owned(),
+            fermentation_days,
+            container_type: container.to_owned(),
+            sugar_content,
+            temperature,
+            climate: "Moderate".to_owned(),
+            interventions: (0..self.early_nutrient_additions())
+                .map(|day| Intervention::new(day, InterventionKind::Nutrient))
+                .collect(),
+            ..SimulationInput::default()
+        }
+    }
+}
+
+/// Must sugar in g/L from `honey` kg made up to `volume` liters.
+pub fn sugar_from_honey(honey: f64, volume: f64) -> f64 {
+    if volume <= 0.0 {
+        return 0.0;
+    }
+    honey * HONEY_SUGAR_FRACTION * 1000.0 / volume
+}
+
+/// Must sugar in g/L from its original gravity, as cider juice is usually
+/// measured; the inverse of `specific_gravity` before any alcohol.
+pub fn sugar_from_original_gravity(original_gravity: f64) -> f64 {
+    ((original_gravity - 1.0 - SG_DRY_EXTRACT) / SG_PER_SUGAR).max(0.0)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SimulationInput {
+    pub beverage: Beverage,
+    /// The grape, or the honey, apple, or fruit for other beverages.
+    pub grape_type: String,
+    pub fermentation_days: u32,
+    /// The vessel fermentation happens in.
+    pub container_type: String,
+    /// The vessel the wine is moved to for aging, or empty to age it where
+    /// it fermented.
+    pub aging_container: String,
+    pub aging_months: u32,
+    /// Percent of the grapes fermented as whole clusters, stems and all.
+    /// Only counts for red wines.
+    pub whole_cluster: f64,
+    /// Percent of the press wine blended back into the free-run wine. Only
+    /// counts for red wines.
+    pub press_wine: f64,
+    /// Must sugar in g/L, before the climate adjustment.
+    pub sugar_content: f64,
+    /// Fermentation temperature in °C.
+    pub temperature: f64,
+    pub climate: String,
+    /// Fixes the dataset pick so the same inputs always produce the same wine.
+    pub seed: Option<u64>,
+    /// The day fresh, more tolerant yeast is pitched to restart a
+    /// fermentation that has slowed near the first yeast's tolerance.
+    pub restart_day: Option<u32>,
+    /// Spells away from the set temperature, such as a power cut or a heat
+    /// wave. Later ones take precedence where they overlap.
+    pub excursions: Vec<Excursion>,
+    /// Name of the strain in `YEAST_STRAINS`, `AMBIENT_YEAST` for a wild
+    /// fermentation, or empty for an unspecified yeast that adds no aromas
+    /// of its own.
+    pub yeast: String,
+    pub inoculation: Inoculation,
+    /// Months a white wine rests on its lees after fermentation.
+    pub lees_months: u32,
+    /// Times a month the lees are stirred back up (bâtonnage).
+    pub stirs_per_month: u32,
+    pub closure: Closure,
+    /// Temperature changes, additions, rackings, and remedies, in any
+    /// order. `timeline` merges them with the restart and excursions.
+    pub interventions: Vec<Intervention>,
+}
+
+/// A spell of `days` days at `temperature` °C starting on `start_day`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Excursion {
+    pub start_day: u32,
+    pub days: u32,
+    pub temperature: f64,
+}
+
+impl Excursion {
+    /// Three days at 10 °C with the heating off.
+    pub fn power_outage(start_day: u32) -> Self {
+        Excursion {
+            start_day,
+            days: 3,
+            temperature: 10.0,
+        }
+    }
+
+    /// Two days at 32 °C.
+    pub fn heat_wave(start_day: u32) -> Self {
+        Excursion {
+            start_day,
+            days: 2,
+            temperature: 32.0,
+        }
+    }
+
+    pub fn covers(&self, day: u32) -> bool {
+        (self.start_day..self.start_day.saturating_add(self.days)).contains(&day)
+    }
+}
+
+/// Something done to the must or wine on a given day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InterventionKind {
+    /// A new set temperature in °C, held until the next change.
+    Temperature,
+    /// Yeast nutrient, in mg/L of assimilable nitrogen. It only helps before
+    /// the one-third sugar break.
+    Nutrient,
+    /// A splashy stir that blows off hydrogen sulfide.
+    Aeration,
+    /// Copper sulfate in mg/L of copper, which binds hydrogen sulfide and
+    /// mercaptans.
+    Copper,
+    /// Racking off the lees, which also aerates the wine and lets CO2 out.
+    Racking,
+    /// Fresh, more tolerant yeast pitched into a sluggish fermentation.
+    Restart,
+}
+
+impl InterventionKind {
+    pub const ALL: [InterventionKind; 6] = [
+        InterventionKind::Temperature,
+        InterventionKind::Nutrient,
+        InterventionKind::Aeration,
+        InterventionKind::Copper,
+        InterventionKind::Racking,
+        InterventionKind::Restart,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InterventionKind::Temperature => "Temperature",
+            InterventionKind::Nutrient => "Yeast nutrient",
+            InterventionKind::Aeration => "Aeration",
+            InterventionKind::Copper => "Copper",
+            InterventionKind::Racking => "Racking",
+            InterventionKind::Restart => "Restart yeast",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// The unit of `Intervention::amount`, or `None` if the amount doesn't
+    /// matter.
+    pub fn unit(self) -> Option<&'static str> {
+        match self {
+            InterventionKind::Temperature => Some("°C"),
+            InterventionKind::Nutrient => Some("mg/L N"),
+            InterventionKind::Copper => Some("mg/L"),
+            _ => None,
+        }
+    }
+
+    /// A usual amount, for new interventions.
+    pub fn default_amount(self) -> f64 {
+        match self {
+            InterventionKind::Temperature => REF_TEMPERATURE,
+            InterventionKind::Nutrient => NUTRIENT_NITROGEN,
+            InterventionKind::Copper => 0.2,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Nitrogen in mg/L a usual dose of yeast nutrient adds.
+pub const NUTRIENT_NITROGEN: f64 = 60.0;
+
+/// An intervention made on `day`, counted from the start of fermentation
+/// and on through aging.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Intervention {
+    pub day: u32,
+    pub kind: InterventionKind,
+    /// How much, in `kind.unit()`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub amount: f64,
+}
+
+impl Intervention {
+    /// An intervention of the kind's usual amount.
+    pub fn new(day: u32, kind: InterventionKind) -> Self {
+        Intervention {
+            day,
+            kind,
+            amount: kind.default_amount(),
+        }
+    }
+}
+
+/// The kind and amount, e.g. "Yeast nutrient 60.0 mg/L N".
+impl fmt::Display for Intervention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind.unit() {
+            Some(unit) => write!(f, "{} {:.1} {}", self.kind.label(), self.amount, unit),
+            None => f.write_str(self.kind.label()),
+        }
+    }
+}
+
+/// Everything done to the must and wine in day order: the interventions,
+/// the restart, and the excursions as temperature changes. This is what the
+/// engine works from.
+pub fn timeline(input: &SimulationInput) -> Vec<Intervention> {
+    let mut timeline = input.interventions.clone();
+    if let Some(day) = input.restart_day {
+        timeline.push(Intervention::new(day, InterventionKind::Restart));
+    }
+    // Each excursion's ends become changes to whatever temperature is in
+    // force then, so overlapping spells still go to the later one.
+    let mut boundaries: Vec<u32> = input
+        .excursions
+        .iter()
+        .flat_map(|excursion| {
+            [
+                excursion.start_day,
+                excursion.start_day.saturating_add(excursion.days),
+            ]
+        })
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    timeline.extend(boundaries.into_iter().map(|day| Intervention {
+        day,
+        kind: InterventionKind::Temperature,
+        amount: excursion_temperature(input, day).unwrap_or(input.temperature),
+    }));
+    timeline.sort_by_key(|intervention| intervention.day);
+    timeline
+}
+
+/// The day fresh yeast are pitched, if they are.
+pub fn restart_day(input: &SimulationInput) -> Option<u32> {
+    timeline(input)
+        .iter()
+        .find(|intervention| intervention.kind == InterventionKind::Restart)
+        .map(|intervention| intervention.day)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationResult {
+    /// Climate-adjusted starting sugar in g/L.
+    pub sugar_content: f64,
+    pub potential_abv: f64,
+    pub fraction_fermented: f64,
+    pub actual_abv: f64,
+    pub residual_sugar: f64,
+    /// How sweet the residual sugar tastes, in g/L, once the acidity is
+    /// taken into account. `sweetness` describes this.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub perceived_sugar: f64,
+    pub sweetness: String,
+    pub body: String,
+    /// What `body` describes: the % ABV of a dry, unoaked wine that would
+    /// feel as full.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub body_index: f64,
+    /// Glycerol in g/L.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub glycerol: f64,
+    pub alcohol_level: String,
+    pub tannin_level: String,
+    pub acidity: String,
+    /// What throws the wine out of balance, if anything.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub balance_faults: Vec<BalanceFault>,
+    /// Titratable acidity in g/L, as tartaric acid.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub titratable_acidity: f64,
+    /// The official EU category of a still wine, such as "medium dry".
+    /// Empty for meads and ciders, which the rules don't cover.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eu_sweetness: String,
+    /// The category the wine would carry if it were made sparkling, such
+    /// as "brut". Empty like `eu_sweetness`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eu_sparkling_sweetness: String,
+    pub grape_characteristics: String,
+    pub container_note: String,
+    /// What the aging vessel adds; empty if the wine wasn't aged.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub aging_note: String,
+    /// Oxygen taken up through the vessels in mg/L.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub oxygen_uptake: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub oxygen_exposure: String,
+    /// Fraction of the wine off the skins that is kept, after any press wine
+    /// left out.
+    #[cfg_attr(feature = "serde", serde(default = "full_yield"))]
+    pub wine_yield: f64,
+    /// Chance from 0 to 1 that the temperature excursions stall the
+    /// fermentation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stall_risk: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub off_flavors: Vec<String>,
+    /// Fruity esters a cool white fermentation kept; empty otherwise.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub esters: String,
+    /// Aromas the yeast strain brought out.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub yeast_aromas: Vec<String>,
+    /// What a malolactic fermentation left; empty if there wasn't one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub malolactic_note: String,
+    /// What time on the lees added; empty for none.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lees_note: String,
+    /// Years after bottling until the wine is at its best.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peak_years: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reduction_risk: bool,
+    /// The color in the glass, e.g. "deep ruby", and as `#rrggbb`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color_hex: String,
+    /// Color intensity, the sum of the absorbances at 420, 520, and 620 nm.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color_intensity: f64,
+    /// Dissolved CO2 in g/L at bottling.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dissolved_co2: f64,
+    /// Whether the wine would taste spritzy unless degassed before bottling.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub needs_degassing: bool,
+    /// The `MODEL_VERSION` that produced this result, or 0 for one saved
+    /// before results were versioned.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub model_version: u32,
+    /// How the wine reads on scales added in a descriptors file, by scale.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extra_descriptors: Vec<(String, String)>,
+}
+
+#[cfg(feature = "serde")]
+fn full_yield() -> f64 {
+    1.0
+}
+
+/// Where the fermentation stands after a given number of days.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FermentationState {
+    pub day: u32,
+    /// Climate-adjusted starting sugar in g/L.
+    pub sugar_content: f64,
+    pub potential_abv: f64,
+    pub fraction_fermented: f64,
+    pub abv: f64,
+    pub residual_sugar: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimulationError {
+    TemperatureOutOfRange(f64),
+    TooManyDays(u32),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::TemperatureOutOfRange(_) => write!(
+                f,
+                "Fermentation failed: temperature out of range for yeast activity."
+            ),
+            SimulationError::TooManyDays(days) => write!(
+                f,
+                "Fermentation failed: {} days is longer than the {} the simulator runs.",
+                days, MAX_FERMENTATION_DAYS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+/// Oxygen a vessel lets into the wine, in mg/L per year.
+pub fn oxygen_ingress(name: &str) -> f64 {
+    container(name).map_or(5.0, |container| container.oxygen_transfer)
+}
+
+fn container_note(name: &str) -> &'static str {
+    container(name).map_or("a distinct vessel charm", |container| container.note)
+}
+
+pub(crate) fn aging_vessel(input: &SimulationInput) -> &str {
+    if input.aging_container.trim().is_empty() {
+        &input.container_type
+    } else {
+        &input.aging_container
+    }
+}
+
+/// Oxygen in mg/L the wine takes up through its vessels before bottling.
+pub fn oxygen_uptake(input: &SimulationInput) -> f64 {
+    oxygen_ingress(&input.container_type) * input.fermentation_days as f64 / 365.0
+        + oxygen_ingress(aging_vessel(input)) * input.aging_months as f64 / 12.0
+}
+
+/// Oxygen in mg/L the wine can take up, in its vessels and then in bottle,
+/// before it declines.
+fn oxygen_capacity(input: &SimulationInput) -> f64 {
+    let capacity = match input.beverage {
+        Beverage::Wine if is_red_grape(&input.grape_type) => 20.0,
+        Beverage::Wine => 8.0,
+        Beverage::Mead => 12.0,
+        Beverage::Cider => 5.0,
+        Beverage::FruitWine => 6.0,
+    };
+    (capacity - 0.5 * oxygen_uptake(input)).max(MIN_BOTTLE_CAPACITY)
+}
+
+/// How a bottle of the wine is doing `year` years after bottling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BottleYear {
+    pub year: u32,
+    /// Oxygen let in through the closure so far, in mg/L.
+    pub oxygen: f64,
+    pub stage: &'static str,
+}
+
+/// Whether the closure lets in so little oxygen that a wine kept from it
+/// in its vessels turns reductive in bottle.
+pub fn reduction_risk(input: &SimulationInput) -> bool {
+    input.closure.oxygen_transfer() < REDUCTION_TRANSFER && oxygen_uptake(input) < 3.0
+}
+
+/// Years after bottling until the wine is at its best.
+pub fn peak_years(input: &SimulationInput) -> f64 {
+    0.5 * oxygen_capacity(input) / bottle_development_rate(input)
+}
+
+fn bottle_development_rate(input: &SimulationInput) -> f64 {
+    input.closure.oxygen_transfer() + ANAEROBIC_DEVELOPMENT
+}
+
+/// The bottle-aging projection for each year from bottling to `years`.
+pub fn bottle_aging(input: &SimulationInput, years: u32) -> Vec<BottleYear> {
+    let capacity = oxygen_capacity(input);
+    let rate = bottle_development_rate(input);
+    let reductive = reduction_risk(input);
+    (0..=years)
+        .map(|year| {
+            let developed = year as f64 * rate / capacity;
+            let stage = if reductive && year <= REDUCTIVE_YEARS {
+                "tight and reductive, with struck-match notes"
+            } else if developed < 0.3 {
+                "youthful, with primary fruit"
+            } else if developed < 0.7 {
+                "at its peak, with tertiary notes"
+            } else if developed < 1.0 {
+                "mature, with the fruit fading"
+            } else {
+                "past its best and oxidized"
+            };
+            BottleYear {
+                year,
+                oxygen: input.closure.oxygen_transfer() * year as f64,
+                stage,
+            }
+        })
+        .collect()
+}
+
+/// Approximate specific gravity of wine with `residual_sugar` g/L sugar and `abv` % alcohol.
+pub fn specific_gravity(residual_sugar: f64, abv: f64) -> f64 {
+    1.0 + SG_DRY_EXTRACT + SG_PER_SUGAR * residual_sugar - SG_PER_ABV * abv
+}
+
+/// Residual sugar in g/L implied by a gravity reading, for a must that
+/// started with `initial_sugar` g/L; the inverse of `specific_gravity`
+/// assuming all sugar consumed so far became alcohol.
+pub fn sugar_from_gravity(specific_gravity: f64, initial_sugar: f64) -> f64 {
+    let per_abv = SG_PER_ABV / sugar_per_abv();
+    let sugar = (specific_gravity - 1.0 - SG_DRY_EXTRACT + per_abv * initial_sugar)
+        / (SG_PER_SUGAR + per_abv);
+    sugar.clamp(0.0, initial_sugar.max(0.0))
+}
+
+impl FermentationState {
+    pub fn specific_gravity(&self) -> f64 {
+        specific_gravity(self.residual_sugar, self.abv)
+    }
+}
+
+/// Fermentation rate constant per day at `temperature`, scaled from 20 °C by
+/// Q10, as the installed calibration has it.
+pub fn rate_constant(temperature: f64) -> f64 {
+    calibration::installed().rate_constant(temperature)
+}
+
+/// Sugar in g/L that makes 1% ABV, as the installed calibration has it.
+pub fn sugar_per_abv() -> f64 {
+    calibration::installed().sugar_per_abv
+}
+
+fn climate_modifiers(climate: &str) -> (f64, f64, f64) {
+    match climate {
+        "cool" => (0.90, 1.10, 1.00),
+        "moderate" => (1.00, 1.00, 1.00),
+        "warm" => (1.10, 0.90, 1.10),
+        _ => (1.00, 1.00, 1.00),
+    }
+}
+
+fn modifiers(input: &SimulationInput) -> (f64, f64, f64) {
+    if input.beverage.uses_climate() {
+        climate_modifiers(&input.climate.to_lowercase())
+    } else {
+        (1.00, 1.00, 1.00)
+    }
+}
+
+pub fn is_red_grape(grape: &str) -> bool {
+    RED_GRAPES
+        .iter()
+        .any(|red| red.eq_ignore_ascii_case(grape.trim()))
+}
+
+/// Fraction of the must fermented with its stems.
+pub(crate) fn stem_inclusion(input: &SimulationInput) -> f64 {
+    if input.beverage == Beverage::Wine && is_red_grape(&input.grape_type) {
+        (input.whole_cluster / 100.0).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Fraction of the press wine blended back, or `None` if nothing is fermented
+/// on skins.
+pub(crate) fn press_blend(input: &SimulationInput) -> Option<f64> {
+    (input.beverage == Beverage::Wine && is_red_grape(&input.grape_type))
+        .then(|| (input.press_wine / 100.0).clamp(0.0, 1.0))
+}
+
+/// The temperature from any excursion covering `day`, later ones first.
+fn excursion_temperature(input: &SimulationInput, day: u32) -> Option<f64> {
+    input
+        .excursions
+        .iter()
+        .rev()
+        .find(|excursion| excursion.covers(day))
+        .map(|excursion| excursion.temperature)
+}
+
+/// The temperature on `day`, counting from 0, after the timeline's
+/// temperature changes so far.
+pub fn temperature_on(input: &SimulationInput, day: u32) -> f64 {
+    timeline(input)
+        .iter()
+        .rev()
+        .find(|intervention| {
+            intervention.kind == InterventionKind::Temperature && intervention.day <= day
+        })
+        .map_or(input.temperature, |intervention| intervention.amount)
+}
+
+/// The temperature on each of the first `days` days, from one pass over the
+/// timeline.
+fn temperatures(input: &SimulationInput, days: u32) -> Vec<f64> {
+    let timeline = timeline(input);
+    let mut changes = timeline
+        .iter()
+        .filter(|intervention| intervention.kind == InterventionKind::Temperature)
+        .peekable();
+    let mut temperature = input.temperature;
+    (0..days)
+        .map(|day| {
+            while let Some(change) = changes.next_if(|change| change.day <= day) {
+                temperature = change.amount;
+            }
+            temperature
+        })
+        .collect()
+}
+
+/// Days at the set temperature that the first `day` days of fermentation
+/// amount to, for each of `days` in ascending order. The yeast go dormant on
+/// days outside their range.
+fn effective_days(input: &SimulationInput, days: &[u32], calibration: &Calibration) -> Vec<f64> {
+    let steady = input.excursions.is_empty()
+        && !input
+            .interventions
+            .iter()
+            .any(|intervention| intervention.kind == InterventionKind::Temperature);
+    if steady {
+        return days.iter().map(|&day| day as f64).collect();
+    }
+    let timeline = timeline(input);
+    let mut changes = timeline
+        .iter()
+        .filter(|intervention| intervention.kind == InterventionKind::Temperature)
+        .peekable();
+    let set_rate = calibration.rate_constant(input.temperature);
+    let mut temperature = input.temperature;
+    let mut day = 0;
+    let mut total = 0.0;
+    days.iter()
+        .map(|&until| {
+            // Whole spells at one temperature at a time.
+            while day < until {
+                while let Some(change) = changes.next_if(|change| change.day <= day) {
+                    temperature = change.amount;
+                }
+                let spell_end = changes.peek().map_or(until, |change| change.day.min(until));
+                if (MIN_YEAST_TEMPERATURE..=MAX_YEAST_TEMPERATURE).contains(&temperature) {
+                    total += calibration.rate_constant(temperature) / set_rate
+                        * (spell_end - day) as f64;
+                }
+                day = spell_end;
+            }
+            total
+        })
+        .collect()
+}
+
+/// The yeast die off as the alcohol builds, so the ABV creeps towards the
+/// `tolerance` instead of stopping dead at it.
+fn soft_cap(abv: f64, tolerance: f64) -> f64 {
+    let slowdown_abv = tolerance - YEAST_DIE_OFF_WINDOW;
+    if abv > slowdown_abv {
+        tolerance - YEAST_DIE_OFF_WINDOW * (-(abv - slowdown_abv) / YEAST_DIE_OFF_WINDOW).exp()
+    } else {
+        abv
+    }
+}
+
+/// Whether the must holds more sugar than the yeast can ferment, leaving a
+/// sluggish, sweet finish unless the fermentation is restarted.
+pub fn is_high_gravity(input: &SimulationInput) -> bool {
+    fermentation_state_at_rate(input, 0, 0.0).potential_abv > yeast_tolerance(input)
+}
+
+/// Factor the climate scales the must sugar by before fermentation.
+pub fn climate_sugar_factor(climate: &str) -> f64 {
+    climate_modifiers(&climate.to_lowercase()).0
+}
+
+/// The rate constant for fermenting `input` for `day` days, if the yeast can.
+fn checked_rate(input: &SimulationInput, day: u32) -> Result<f64, SimulationError> {
+    let temperature = input.temperature;
+    if !(MIN_YEAST_TEMPERATURE..=MAX_YEAST_TEMPERATURE).contains(&temperature) {
+        return Err(SimulationError::TemperatureOutOfRange(temperature));
+    }
+    if day > MAX_FERMENTATION_DAYS {
+        return Err(SimulationError::TooManyDays(day));
+    }
+    Ok(rate_constant(temperature) * input.inoculation.rate_factor())
+}
+
+pub fn fermentation_state(
+    input: &SimulationInput,
+    day: u32,
+) -> Result<FermentationState, SimulationError> {
+    let k = checked_rate(input, day)?;
+    Ok(fermentation_state_at_rate(input, day, k))
+}
+
+/// Like `fermentation_state`, but with a rate constant `k` per day in place
+/// of the one implied by the temperature, e.g. one fitted to measurements.
+pub fn fermentation_state_at_rate(input: &SimulationInput, day: u32, k: f64) -> FermentationState {
+    fermentation_state_calibrated(input, day, k, &calibration::installed())
+}
+
+/// Like `fermentation_state_at_rate`, but with `calibration` in place of the
+/// installed one, as when fitting a calibration.
+pub fn fermentation_state_calibrated(
+    input: &SimulationInput,
+    day: u32,
+    k: f64,
+    calibration: &Calibration,
+) -> FermentationState {
+    match restart_day(input) {
+        Some(restart_day) if day > restart_day => {
+            let effective = effective_days(input, &[restart_day, day], calibration);
+            fermentation_state_from(
+                input,
+                day,
+                k,
+                calibration,
+                effective[1],
+                Some((restart_day, effective[0])),
+            )
+        }
+        _ => {
+            let effective = effective_days(input, &[day], calibration)[0];
+            fermentation_state_from(input, day, k, calibration, effective, None)
+        }
+    }
+}
+
+/// `fermentation_state_calibrated` for every day from 0 to `last_day`,
+/// working through the timeline once.
+fn fermentation_states(input: &SimulationInput, last_day: u32, k: f64) -> Vec<FermentationState> {
+    let calibration = calibration::installed();
+    let days: Vec<u32> = (0..=last_day).collect();
+    let effective = effective_days(input, &days, &calibration);
+    let restart = restart_day(input)
+        .filter(|&restart_day| restart_day < last_day)
+        .map(|restart_day| (restart_day, effective[restart_day as usize]));
+    days.iter()
+        .zip(&effective)
+        .map(|(&day, &effective)| {
+            let restarted = restart.filter(|&(restart_day, _)| day > restart_day);
+            fermentation_state_from(input, day, k, &calibration, effective, restarted)
+        })
+        .collect()
+}
+
+/// The state on `day` once the timeline has been worked out: `effective` days
+/// at the set temperature by then and, if fresh yeast were pitched before
+/// it, the day they were and the effective days by that day.
+fn fermentation_state_from(
+    input: &SimulationInput,
+    day: u32,
+    k: f64,
+    calibration: &Calibration,
+    effective: f64,
+    restarted: Option<(u32, f64)>,
+) -> FermentationState {
+    let (sugar_mod, _acidity_mod, _tannin_mod) = modifiers(input);
+    let sugar_per_abv = calibration.sugar_per_abv;
+
+    let sugar_content =
+        input.sugar_content.max(0.0) * sugar_mod * (1.0 - STEM_DILUTION * stem_inclusion(input));
+    let potential_abv = sugar_content / sugar_per_abv;
+
+    let wild = wild_ferment(input);
+    let lag_days = wild.map_or(0.0, |wild| wild.lag_days) + input.inoculation.lag_days();
+    let tolerance = wild.map_or(YEAST_ALCOHOL_TOLERANCE, |wild| wild.tolerance);
+    let days = (effective - lag_days).max(0.0);
+    let mut fraction_fermented = 1.0 - (-k * days).exp();
+    if fraction_fermented > 1.0 {
+        fraction_fermented = 1.0;
+    }
+
+    let mut sugar_consumed = fraction_fermented * sugar_content;
+    let mut abv = sugar_consumed / sugar_per_abv;
+
+    let capped = match restarted {
+        Some((restart_day, effective_at_restart)) => {
+            // The fresh yeast take on the sugar the first yeast left behind.
+            let restarted_at = fermentation_state_from(
+                input,
+                restart_day,
+                k,
+                calibration,
+                effective_at_restart,
+                None,
+            )
+            .abv;
+            let fresh_days = effective - effective_at_restart;
+            let unchecked =
+                restarted_at + (potential_abv - restarted_at) * (1.0 - (-k * fresh_days).exp());
+            restarted_at.max(soft_cap(unchecked, RESTART_YEAST_TOLERANCE))
+        }
+        _ => soft_cap(abv, tolerance),
+    };
+    if capped != abv {
+        abv = capped;
+        sugar_consumed = abv * sugar_per_abv;
+        fraction_fermented = sugar_consumed / sugar_content;
+    }
+
+    FermentationState {
+        day,
+        sugar_content,
+        potential_abv,
+        fraction_fermented,
+        abv,
+        residual_sugar: sugar_content - sugar_consumed,
+    }
+}
+
+/// One state per day from the start of fermentation to `input.fermentation_days`.
+pub fn fermentation_curve(
+    input: &SimulationInput,
+) -> Result<Vec<FermentationState>, SimulationError> {
+    let k = checked_rate(input, input.fermentation_days)?;
+    Ok(fermentation_states(input, input.fermentation_days, k))
+}
+
+#[tracing::instrument(
+    skip_all,
+    fields(grape = %input.grape_type, container = %input.container_type, climate = %input.climate)
+)]
+pub fn run_simulation(
+    input: &SimulationInput,
+    wine_data: &Dataset,
+) -> Result<SimulationResult, SimulationError> {
+    let input = &seeded(input);
+    let climate = input.climate.to_lowercase();
+    let (_sugar_mod, _acidity_mod, tannin_mod) = modifiers(input);
+
+    let FermentationState {
+        sugar_content,
+        potential_abv,
+        fraction_fermented,
+        abv: actual_abv,
+        residual_sugar,
+        ..
+    } = fermentation_state(input, input.fermentation_days).inspect_err(|err| {
+        warn!(
+            temperature = input.temperature,
+            days = input.fermentation_days,
+            "{}",
+            err
+        )
+    })?;
+    debug!(fraction_fermented, actual_abv, residual_sugar, "fermentation kinetics");
+
+    let titratable_acidity = titratable_acidity(input);
+    let perceived_sugar = perceived_sugar(residual_sugar, titratable_acidity, input.beverage);
+    let scales = descriptors::installed();
+    let sweetness = scales.describe(SWEETNESS, input.beverage, perceived_sugar);
+    let sweetness = if sweetness == scales.describe(SWEETNESS, input.beverage, residual_sugar) {
+        sweetness
+    } else if perceived_sugar < residual_sugar {
+        format!("{}, balanced by its acidity", sweetness)
+    } else {
+        format!("{}, seeming sweeter for its soft acidity", sweetness)
+    };
+    let glycerol = glycerol(
+        actual_abv,
+        fermentation_temperature(input),
+        sugar_content,
+        strain_glycerol(input),
+    );
+    let body_index = body_index(input, actual_abv, residual_sugar, glycerol);
+    let body = scales.describe(BODY, input.beverage, body_index);
+    let alcohol_level = scales.describe(ALCOHOL, input.beverage, actual_abv);
+
+    let tannin_level = if input.beverage == Beverage::Wine {
+        let tannin_base = match input.grape_type.to_lowercase().as_str() {
+            "cabernet sauvignon" => "robust, high tannins",
+            "merlot" => "smooth, moderate tannins",
+            "pinot noir" => "delicate, low tannins",
+            "syrah" | "shiraz" => "moderate tannins",
+            "tempranillo" => "moderate tannins",
+            "zinfandel" => "spicy, moderately high tannins",
+            "sangiovese" => "high tannins",
+            "chardonnay" => "minimal tannins",
+            "sauvignon blanc" => "minimal tannins",
+            "riesling" => "very minimal tannins",
+            _ => "unknown tannin levels",
+        };
+
+        let stems = stem_inclusion(input);
+        let tannin_base = if stems >= 0.5 {
+            format!("{}, with green, spicy stem tannins", tannin_base)
+        } else if stems > 0.0 {
+            format!("{}, with a touch of stem spice", tannin_base)
+        } else {
+            tannin_base.to_owned()
+        };
+        let tannin_base = match press_blend(input) {
+            Some(blend) if blend >= 0.5 => {
+                format!("{}, coarsened by astringent press wine", tannin_base)
+            }
+            Some(blend) if blend > 0.0 => format!("{}, firmed up by some press wine", tannin_base),
+            _ => tannin_base,
+        };
+
+        if tannin_mod > 1.0 {
+            format!("{} (slightly accentuated by the warm climate)", tannin_base)
+        } else if tannin_mod < 1.0 {
+            format!(
+                "{} (somewhat less pronounced in the cool climate)",
+                tannin_base
+            )
+        } else {
+            tannin_base
+        }
+    } else {
+        // Nothing is fermented on skins.
+        "no".to_owned()
+    };
+
+    let acidity = match input.beverage {
+        Beverage::Wine => match climate.as_str() {
+            "cool" => "high",
+            "moderate" => "moderate",
+            "warm" => "low",
+            _ => "unknown",
+        },
+        Beverage::Mead => "low",
+        Beverage::Cider => "crisp",
+        Beverage::FruitWine => "high",
+    };
+    // Malolactic bacteria turn sharp malic acid into softer lactic acid,
+    // and long, stirred lees contact rounds it off a little more.
+    let lees = lees_contact(input);
+    let acidity = if input.inoculation.is_malolactic() {
+        soften(acidity)
+    } else {
+        acidity
+    };
+    let acidity = if lees >= LEES_SOFTENING_CONTACT {
+        soften(acidity)
+    } else {
+        acidity
+    };
+    let (eu_sweetness, eu_sparkling_sweetness) = match input.beverage {
+        Beverage::Wine | Beverage::FruitWine => (
+            StillCategory::classify(residual_sugar, titratable_acidity)
+                .label()
+                .to_owned(),
+            SparklingCategory::classify(residual_sugar)
+                .label()
+                .to_owned(),
+        ),
+        Beverage::Mead | Beverage::Cider => (String::new(), String::new()),
+    };
+    let lees_note = if lees <= 0.0 {
+        ""
+    } else if lees < 3.0 {
+        "a subtle, creamy texture"
+    } else if lees < 8.0 {
+        "a creamy texture with brioche notes"
+    } else {
+        "a rich, creamy texture with toasty brioche and nutty notes"
+    };
+    let malolactic_note = match input.inoculation {
+        Inoculation::SequentialMalolactic => "buttery, creamy diacetyl",
+        Inoculation::CoInoculatedMalolactic => "a rounder palate with the fresh fruit intact",
+        _ => "",
+    };
+
+    let grape_characteristics = match input.beverage {
+        Beverage::Wine => {
+            let matches = wine_data.records_for(&input.grape_type);
+            debug!(matches = matches.len(), "dataset records matching grape");
+            if matches.is_empty() {
+                "unknown flavor profile".to_owned()
+            } else {
+                let mut rng = StdRng::seed_from_u64(input.seed.unwrap_or_default());
+                matches.choose(&mut rng).unwrap().characteristics.clone()
+            }
+        }
+        Beverage::Mead => format!("{} honey, beeswax", input.grape_type),
+        Beverage::Cider => format!("{} apple, orchard blossom", input.grape_type),
+        Beverage::FruitWine => format!("ripe {}, jam", input.grape_type),
+    };
+
+    let aging_note = if input.aging_months > 0 {
+        container_note(aging_vessel(input))
+    } else {
+        ""
+    };
+    let oxygen_uptake = oxygen_uptake(input);
+    let color = wine_color(input);
+    let oxygen_exposure = if oxygen_uptake < 3.0 {
+        "protected from oxygen, fresh and fruit-forward"
+    } else if oxygen_uptake < 15.0 {
+        "gently oxidized, with softened tannins"
+    } else {
+        "noticeably oxidized, with nutty, developed notes"
+    };
+
+    let (stall_risk, excursion_flavors) = excursion_effects(input);
+    let mean_temperature = fermentation_temperature(input);
+    let mut off_flavors = Vec::new();
+    if mean_temperature >= HEAT_STRESS_TEMPERATURE {
+        off_flavors.push("hot, solvent-like fusel alcohols".to_owned());
+    } else if mean_temperature >= FUSEL_TEMPERATURE {
+        off_flavors.push("a faint fusel warmth".to_owned());
+    }
+    for off_flavor in excursion_flavors {
+        if !off_flavors.contains(&off_flavor) {
+            off_flavors.push(off_flavor);
+        }
+    }
+    if let Some(wild) = wild_ferment(input).filter(|wild| wild.flawed) {
+        off_flavors.push(wild.character.to_owned());
+    }
+    if let Some(stage) = sulfide_fault(input).and_then(|fault| fault.remaining) {
+        off_flavors.push(stage.descriptor().to_owned());
+    }
+    let esters = if input.beverage == Beverage::Wine && is_red_grape(&input.grape_type) {
+        ""
+    } else if mean_temperature <= ESTER_TEMPERATURE - 4.0 {
+        "vivid pear-drop and banana esters"
+    } else if mean_temperature <= ESTER_TEMPERATURE {
+        "fresh, fruity esters"
+    } else {
+        ""
+    };
+
+    Ok(SimulationResult {
+        sugar_content,
+        potential_abv,
+        fraction_fermented,
+        actual_abv,
+        residual_sugar,
+        perceived_sugar,
+        sweetness,
+        body,
+        body_index,
+        glycerol,
+        alcohol_level,
+        tannin_level,
+        acidity: acidity.to_owned(),
+        balance_faults: balance_faults(
+            input.beverage,
+            actual_abv,
+            residual_sugar,
+            perceived_sugar,
+            titratable_acidity,
+            body_index,
+        ),
+        titratable_acidity,
+        eu_sweetness,
+        eu_sparkling_sweetness,
+        grape_characteristics,
+        container_note: container_note(&input.container_type).to_owned(),
+        aging_note: aging_note.to_owned(),
+        oxygen_uptake,
+        oxygen_exposure: oxygen_exposure.to_owned(),
+        wine_yield: press_blend(input)
+            .map_or(1.0, |blend| FREE_RUN_SHARE + (1.0 - FREE_RUN_SHARE) * blend),
+        stall_risk,
+        off_flavors,
+        esters: esters.to_owned(),
+        yeast_aromas: yeast_aromas(input, mean_temperature),
+        malolactic_note: malolactic_note.to_owned(),
+        lees_note: lees_note.to_owned(),
+        peak_years: peak_years(input),
+        reduction_risk: reduction_risk(input),
+        color: color.descriptor(),
+        color_hex: color.hex(),
+        color_intensity: color.intensity,
+        dissolved_co2: dissolved_co2(input),
+        needs_degassing: needs_degassing(input),
+        model_version: MODEL_VERSION,
+        extra_descriptors: scales.extra(residual_sugar, actual_abv, body_index),
+    })
+}
+
+/// One step softer on the acidity scale.
+fn soften(acidity: &str) -> &'static str {
+    match acidity {
+        "high" => "moderate",
+        "moderate" | "crisp" => "soft",
+        _ => "very soft",
+    }
+}
+
+/// The acidity a beverage usually has, at which its sweetness reads as the
+/// residual sugar alone says.
+pub fn usual_acidity(beverage: Beverage) -> f64 {
+    match beverage {
+        Beverage::Wine => WINE_ACIDITY,
+        Beverage::Mead => MEAD_ACIDITY,
+        Beverage::Cider => CIDER_ACIDITY,
+        Beverage::FruitWine => FRUIT_WINE_ACIDITY,
+    }
+}
+
+/// How sweet `residual_sugar` in g/L tastes, in g/L, given the acidity:
+/// crisp acidity hides some of the sugar and soft acidity shows it more.
+pub fn perceived_sugar(residual_sugar: f64, titratable_acidity: f64, beverage: Beverage) -> f64 {
+    (residual_sugar - ACID_MASKING * (titratable_acidity - usual_acidity(beverage))).max(0.0)
+}
+
+/// Titratable acidity in g/L as tartaric acid: the grape's usual harvest
+/// acidity for the climate, less what malolactic fermentation and long lees
+/// contact take out.
+pub fn titratable_acidity(input: &SimulationInput) -> f64 {
+    let harvest = match input.beverage {
+        Beverage::Wine => {
+            let usual = grape_profile(&input.grape_type).map_or(WINE_ACIDITY, |profile| {
+                (profile.acidity.0 + profile.acidity.1) / 2.0
+            });
+            match input.climate.to_lowercase().as_str() {
+                "cool" => usual + CLIMATE_ACIDITY,
+                "warm" => usual - CLIMATE_ACIDITY,
+                _ => usual,
+            }
+        }
+        beverage => usual_acidity(beverage),
+    };
+    let mut acidity = harvest;
+    if input.inoculation.is_malolactic() {
+        acidity -= MALOLACTIC_ACIDITY;
+    }
+    if lees_contact(input) >= LEES_SOFTENING_CONTACT {
+        acidity -= LEES_ACIDITY;
+    }
+    acidity
+}
+
+/// Months on the lees, weighted up for stirring. Only white wines are left
+/// on their lees this way.
+pub fn lees_contact(input: &SimulationInput) -> f64 {
+    if input.beverage != Beverage::Wine || is_red_grape(&input.grape_type) {
+        return 0.0;
+    }
+    input.lees_months as f64 * (1.0 + STIRRING_WEIGHT * input.stirs_per_month as f64)
+}
+
+/// What the yeast add at the fermentation's `mean_temperature`.
+fn yeast_aromas(input: &SimulationInput, mean_temperature: f64) -> Vec<String> {
+    let mut aromas = strain_aromas(input, mean_temperature);
+    if input.inoculation == Inoculation::NonSaccharomyces {
+        aromas.push("complex, floral esters".to_owned());
+    }
+    aromas
+}
+
+/// What the yeast strain, or the native yeast, add on their own.
+fn strain_aromas(input: &SimulationInput, mean_temperature: f64) -> Vec<String> {
+    if let Some(wild) = wild_ferment(input) {
+        if wild.flawed {
+            return Vec::new();
+        }
+        return vec![wild.character.to_owned()];
+    }
+    let Some(strain) = yeast_strain(&input.yeast) else {
+        return Vec::new();
+    };
+    let aromas = if mean_temperature > strain.cool_below {
+        strain.warm_aromas.to_vec()
+    } else if input.beverage == Beverage::Wine
+        && THIOL_GRAPES
+            .iter()
+            .any(|grape| grape.eq_ignore_ascii_case(input.grape_type.trim()))
+    {
+        [strain.thiols, strain.cool_aromas].concat()
+    } else {
+        strain.cool_aromas.to_vec()
+    };
+    aromas.into_iter().map(str::to_owned).collect()
+}
+
+/// The mean temperature over the fermentation, weighted by how much sugar
+/// was fermented each day, so that excursions count for what the yeast did
+/// while they lasted.
+pub fn fermentation_temperature(input: &SimulationInput) -> f64 {
+    let k = rate_constant(input.temperature) * input.inoculation.rate_factor();
+    let states = fermentation_states(input, input.fermentation_days, k);
+    let mut fermented = 0.0;
+    let mut weighted = 0.0;
+    for (days, temperature) in states
+        .windows(2)
+        .zip(temperatures(input, input.fermentation_days))
+    {
+        let fraction = days[1].fraction_fermented - days[0].fraction_fermented;
+        fermented += fraction;
+        weighted += fraction * temperature;
+    }
+    if fermented > 0.0 {
+        weighted / fermented
+    } else {
+        input.temperature
+    }
+}
+
+/// The chance the excursions stall the fermentation, and the off-flavors
+/// they leave. Only days while the yeast are still working count.
+fn excursion_effects(input: &SimulationInput) -> (f64, Vec<String>) {
+    let k = rate_constant(input.temperature) * input.inoculation.rate_factor();
+    let states = fermentation_states(input, input.fermentation_days, k);
+    let mut carries_on = 1.0;
+    let mut off_flavors = Vec::new();
+    for (state, temperature) in states
+        .iter()
+        .zip(temperatures(input, input.fermentation_days))
+    {
+        if temperature == input.temperature || state.fraction_fermented >= ACTIVE_FRACTION {
+            continue;
+        }
+        let drop = input.temperature - temperature;
+        let (stall_chance, off_flavor) = if temperature >= HEAT_STRESS_TEMPERATURE {
+            (
+                ((temperature - HEAT_STRESS_TEMPERATURE + 2.0) / 8.0).clamp(0.0, 1.0) * 0.3,
+                Some("hot, solvent-like fusel alcohols"),
+            )
+        } else if drop >= COLD_SHOCK_DROP {
+            (
+                ((drop - 5.0) / 10.0).clamp(0.0, 1.0) * 0.15,
+                Some("sulfurous reduction from shocked yeast"),
+            )
+        } else {
+            (0.0, None)
+        };
+        carries_on *= 1.0 - stall_chance;
+        if let Some(off_flavor) = off_flavor
+            && !off_flavors.iter().any(|seen| seen == off_flavor)
+        {
+            off_flavors.push(off_flavor.to_owned());
+        }
+    }
+    (1.0 - carries_on, off_flavors)
+}
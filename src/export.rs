@@ -0,0 +1,306 @@
+//! Simulations laid out as a spreadsheet, one row per wine with a column
+//! for every input and output, for analysis in Excel or elsewhere.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::engine::{SimulationError, SimulationInput, SimulationResult};
+
+pub const COLUMNS: [&str; 45] = [
+    "Name",
+    "Date",
+    "Beverage",
+    "Grape",
+    "Fermentation days",
+    "Container",
+    "Aging container",
+    "Aging months",
+    "Whole cluster (%)",
+    "Press wine (%)",
+    "Sugar (g/L)",
+    "Temperature (°C)",
+    "Climate",
+    "Seed",
+    "Restart day",
+    "Temperature excursions",
+    "Yeast",
+    "Inoculation",
+    "Lees months",
+    "Stirs per month",
+    "Closure",
+    "Interventions",
+    "Starting sugar (g/L)",
+    "Potential ABV (%)",
+    "Fraction fermented",
+    "ABV (%)",
+    "Residual sugar (g/L)",
+    "Sweetness",
+    "Body",
+    "Alcohol level",
+    "Tannin level",
+    "Acidity",
+    "TA (g/L)",
+    "EU sweetness",
+    "Glycerol (g/L)",
+    "Oxygen uptake (mg/L)",
+    "Wine yield",
+    "Stall risk",
+    "Off flavors",
+    "Peak years",
+    "Reduction risk",
+    "Color",
+    "Color intensity",
+    "Dissolved CO2 (g/L)",
+    "Error",
+];
+
+/// A spreadsheet cell, kept typed so spreadsheets get real numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Empty,
+    Text(String),
+    Number(f64),
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Empty => Ok(()),
+            Cell::Text(text) => write!(f, "{}", text),
+            Cell::Number(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::Text(text.to_owned())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::Text(text)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(number: f64) -> Self {
+        Cell::Number(number)
+    }
+}
+
+impl From<u32> for Cell {
+    fn from(number: u32) -> Self {
+        Cell::Number(number.into())
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(yes: bool) -> Self {
+        Cell::Text(if yes { "yes" } else { "no" }.to_owned())
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Cell::Empty, Into::into)
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Csv(csv::Error),
+    #[cfg(feature = "xlsx")]
+    Xlsx(rust_xlsxwriter::XlsxError),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "{}", err),
+            ExportError::Csv(err) => write!(f, "could not write CSV: {}", err),
+            #[cfg(feature = "xlsx")]
+            ExportError::Xlsx(err) => write!(f, "could not write the workbook: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+impl From<csv::Error> for ExportError {
+    fn from(err: csv::Error) -> Self {
+        ExportError::Csv(err)
+    }
+}
+
+#[cfg(feature = "xlsx")]
+impl From<rust_xlsxwriter::XlsxError> for ExportError {
+    fn from(err: rust_xlsxwriter::XlsxError) -> Self {
+        ExportError::Xlsx(err)
+    }
+}
+
+/// Rows of wines under the `COLUMNS` header.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Spreadsheet {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Spreadsheet {
+    pub fn rows(&self) -> &[Vec<Cell>] {
+        &self.rows
+    }
+
+    /// Adds a wine. The outputs are left empty and the error filled in if
+    /// its simulation failed.
+    pub fn push(
+        &mut self,
+        name: &str,
+        date: &str,
+        input: &SimulationInput,
+        result: Result<&SimulationResult, &SimulationError>,
+    ) {
+        let excursions = input
+            .excursions
+            .iter()
+            .map(|excursion| {
+                format!(
+                    "day {} for {} days at {} °C",
+                    excursion.start_day, excursion.days, excursion.temperature
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        let interventions = input
+            .interventions
+            .iter()
+            .map(|intervention| match intervention.kind.unit() {
+                Some(unit) => format!(
+                    "day {}: {} {} {}",
+                    intervention.day,
+                    intervention.kind.label(),
+                    intervention.amount,
+                    unit
+                ),
+                None => format!("day {}: {}", intervention.day, intervention.kind.label()),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        let mut row: Vec<Cell> = vec![
+            name.into(),
+            date.into(),
+            input.beverage.label().into(),
+            input.grape_type.as_str().into(),
+            input.fermentation_days.into(),
+            input.container_type.as_str().into(),
+            input.aging_container.as_str().into(),
+            input.aging_months.into(),
+            input.whole_cluster.into(),
+            input.press_wine.into(),
+            input.sugar_content.into(),
+            input.temperature.into(),
+            input.climate.as_str().into(),
+            // Seeds can be past what a spreadsheet number holds exactly.
+            input.seed.map(|seed| seed.to_string()).into(),
+            input.restart_day.into(),
+            excursions.into(),
+            input.yeast.as_str().into(),
+            input.inoculation.label().into(),
+            input.lees_months.into(),
+            input.stirs_per_month.into(),
+            input.closure.label().into(),
+            interventions.into(),
+        ];
+        match result {
+            Ok(result) => row.extend([
+                result.sugar_content.into(),
+                result.potential_abv.into(),
+                result.fraction_fermented.into(),
+                result.actual_abv.into(),
+                result.residual_sugar.into(),
+                result.sweetness.as_str().into(),
+                result.body.as_str().into(),
+                result.alcohol_level.as_str().into(),
+                result.tannin_level.as_str().into(),
+                result.acidity.as_str().into(),
+                result.titratable_acidity.into(),
+                result.eu_sweetness.as_str().into(),
+                result.glycerol.into(),
+                result.oxygen_uptake.into(),
+                result.wine_yield.into(),
+                result.stall_risk.into(),
+                result.off_flavors.join("; ").into(),
+                result.peak_years.into(),
+                result.reduction_risk.into(),
+                result.color.as_str().into(),
+                result.color_intensity.into(),
+                result.dissolved_co2.into(),
+                Cell::Empty,
+            ]),
+            Err(err) => {
+                row.resize(COLUMNS.len() - 1, Cell::Empty);
+                row.push(err.to_string().into());
+            }
+        }
+        self.rows.push(row);
+    }
+
+    pub fn to_csv_string(&self) -> Result<String, ExportError> {
+        let mut buffer = Vec::new();
+        {
+            let mut writer = csv::Writer::from_writer(&mut buffer);
+            writer.write_record(COLUMNS)?;
+            for row in &self.rows {
+                writer.write_record(row.iter().map(Cell::to_string))?;
+            }
+            writer.flush()?;
+        }
+        // Every cell came from a `String`, so the output is UTF-8.
+        Ok(String::from_utf8(buffer).expect("CSV of strings is UTF-8"))
+    }
+
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+        std::fs::write(path, self.to_csv_string()?)?;
+        Ok(())
+    }
+
+    /// Saves an Excel workbook with the header row frozen.
+    #[cfg(feature = "xlsx")]
+    pub fn save_xlsx(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+        use rust_xlsxwriter::{Format, Workbook};
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet().set_name("Wines")?;
+        let bold = Format::new().set_bold();
+        for (column, header) in COLUMNS.iter().enumerate() {
+            sheet.write_string_with_format(0, column as u16, *header, &bold)?;
+        }
+        for (row, cells) in self.rows.iter().enumerate() {
+            let row = row as u32 + 1;
+            for (column, cell) in cells.iter().enumerate() {
+                let column = column as u16;
+                match cell {
+                    Cell::Empty => {}
+                    Cell::Text(text) => {
+                        sheet.write_string(row, column, text)?;
+                    }
+                    Cell::Number(number) => {
+                        sheet.write_number(row, column, *number)?;
+                    }
+                }
+            }
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        workbook.save(path)?;
+        Ok(())
+    }
+}
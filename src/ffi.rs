@@ -0,0 +1,108 @@
+//! C API for embedding the engine. Inputs and results cross the boundary as
+//! JSON so the interface stays stable as the model grows; see
+//! `include/wine_maker.h` (regenerate with `cbindgen --config cbindgen.toml
+//! --output include/wine_maker.h`).
+
+use std::ffi::{CStr, c_char};
+use std::ptr;
+
+use serde::Serialize;
+
+use crate::dataset::Dataset;
+use crate::engine::{SimulationInput, SimulationResult, run_simulation, seeded};
+use crate::narrative::narrative;
+
+/// `json_in` or `json_out_buf` was null.
+pub const WM_ERR_NULL_POINTER: isize = -1;
+/// `json_in` was not UTF-8 or not a valid simulation input object.
+pub const WM_ERR_INVALID_INPUT: isize = -2;
+/// The simulation ran but the fermentation failed; the buffer holds `{"error": ...}`.
+pub const WM_ERR_SIMULATION: isize = -3;
+/// The output did not fit in `json_out_len` bytes including the trailing NUL.
+pub const WM_ERR_BUFFER_TOO_SMALL: isize = -4;
+
+#[derive(Serialize)]
+struct Output<'a> {
+    #[serde(flatten)]
+    result: &'a SimulationResult,
+    narrative: String,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    error: String,
+}
+
+unsafe fn write_out(json: &str, json_out_buf: *mut c_char, json_out_len: usize) -> isize {
+    if json.len() + 1 > json_out_len {
+        return WM_ERR_BUFFER_TOO_SMALL;
+    }
+    // SAFETY: the caller guarantees `json_out_buf` points to `json_out_len`
+    // writable bytes, and we checked the JSON plus NUL fits.
+    unsafe {
+        ptr::copy_nonoverlapping(json.as_ptr(), json_out_buf.cast::<u8>(), json.len());
+        *json_out_buf.add(json.len()) = 0;
+    }
+    json.len() as isize
+}
+
+/// Runs a simulation against the bundled dataset.
+///
+/// `json_in` is a NUL-terminated JSON object with any of the
+/// `SimulationInput` fields (missing fields take their defaults). On success
+/// the NUL-terminated JSON result, including a `narrative` string, is written
+/// to `json_out_buf` and its length (excluding the NUL) is returned. On
+/// failure a negative `WM_ERR_*` code is returned; for `WM_ERR_SIMULATION`
+/// the buffer holds `{"error": "..."}`. Results are well under 4 KiB.
+///
+/// # Safety
+///
+/// `json_in` must be a valid NUL-terminated string and `json_out_buf` must
+/// point to at least `json_out_len` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wm_simulate(
+    json_in: *const c_char,
+    json_out_buf: *mut c_char,
+    json_out_len: usize,
+) -> isize {
+    if json_in.is_null() || json_out_buf.is_null() {
+        return WM_ERR_NULL_POINTER;
+    }
+
+    // SAFETY: checked for null above; the caller guarantees NUL termination.
+    let json_in = unsafe { CStr::from_ptr(json_in) };
+    let Ok(input) = json_in
+        .to_str()
+        .map_err(|err| err.to_string())
+        .and_then(|json| {
+            serde_json::from_str::<SimulationInput>(json).map_err(|err| err.to_string())
+        })
+        .inspect_err(|err| tracing::warn!("invalid simulation input: {}", err))
+    else {
+        return WM_ERR_INVALID_INPUT;
+    };
+
+    let input = seeded(&input);
+    match run_simulation(&input, Dataset::bundled()) {
+        Ok(result) => {
+            let output = Output {
+                result: &result,
+                narrative: narrative(&input, &result),
+            };
+            let json = serde_json::to_string(&output).expect("simulation results serialize");
+            // SAFETY: forwarded from this function's contract.
+            unsafe { write_out(&json, json_out_buf, json_out_len) }
+        }
+        Err(err) => {
+            let json = serde_json::to_string(&ErrorOutput {
+                error: err.to_string(),
+            })
+            .expect("errors serialize");
+            // SAFETY: forwarded from this function's contract.
+            match unsafe { write_out(&json, json_out_buf, json_out_len) } {
+                WM_ERR_BUFFER_TOO_SMALL => WM_ERR_BUFFER_TOO_SMALL,
+                _ => WM_ERR_SIMULATION,
+            }
+        }
+    }
+}
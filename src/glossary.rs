@@ -0,0 +1,338 @@
+//! Winemaking terms used across the simulator, kept as data under stable
+//! keys so the interface can link to them and translations can replace the
+//! English names and definitions.
+
+use std::collections::HashMap;
+
+use csv::ReaderBuilder;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Term {
+    /// Stable key that links and translations refer to.
+    pub key: &'static str,
+    pub name: &'static str,
+    pub abbreviation: Option<&'static str>,
+    pub definition: &'static str,
+    /// Keys of related terms.
+    pub see_also: &'static [&'static str],
+}
+
+const fn define(
+    key: &'static str,
+    name: &'static str,
+    abbreviation: Option<&'static str>,
+    definition: &'static str,
+    see_also: &'static [&'static str],
+) -> Term {
+    Term {
+        key,
+        name,
+        abbreviation,
+        definition,
+        see_also,
+    }
+}
+
+pub const TERMS: [Term; 28] = [
+    define(
+        "abv",
+        "Alcohol by volume",
+        Some("ABV"),
+        "The share of the wine's volume that is alcohol, in percent. Each 16.83 g/L of sugar fermented gives about 1% ABV.",
+        &["residual_sugar", "specific_gravity"],
+    ),
+    define(
+        "anthocyanins",
+        "Anthocyanins",
+        None,
+        "The red and purple pigments in grape skins. They bind with tannins as the wine ages, turning it from purple towards brick.",
+        &["tannin", "phenolic_ripeness"],
+    ),
+    define(
+        "batonnage",
+        "Bâtonnage",
+        None,
+        "Stirring the lees back into the wine, which speeds up the creamy texture and softening they bring.",
+        &["lees"],
+    ),
+    define(
+        "chaptalization",
+        "Chaptalization",
+        None,
+        "Adding sugar to the must before or during fermentation to raise the final alcohol, common where grapes struggle to ripen.",
+        &["abv", "must"],
+    ),
+    define(
+        "degassing",
+        "Degassing",
+        None,
+        "Removing the carbon dioxide left dissolved after fermentation, so a still wine doesn't taste spritzy.",
+        &["racking"],
+    ),
+    define(
+        "esters",
+        "Esters",
+        None,
+        "Fruity aroma compounds the yeast make, kept best by cool fermentations.",
+        &["fusel_alcohols", "thiols"],
+    ),
+    define(
+        "free_run",
+        "Free-run wine",
+        None,
+        "The wine that drains from the skins without pressing; the softest part of a red.",
+        &["press_wine"],
+    ),
+    define(
+        "fusel_alcohols",
+        "Fusel alcohols",
+        None,
+        "Heavier alcohols stressed yeast make in a hot fermentation, tasting harsh and solvent-like.",
+        &["esters"],
+    ),
+    define(
+        "growing_degree_days",
+        "Growing degree days",
+        Some("GDD"),
+        "The heat of a growing season: each day's mean temperature above 10 °C, added up from April to October.",
+        &["winkler_region", "veraison"],
+    ),
+    define(
+        "h2s",
+        "Hydrogen sulfide",
+        Some("H2S"),
+        "A rotten-egg smell from yeast short of nitrogen. Left alone it turns into harder-to-remove mercaptans.",
+        &["yan", "reduction"],
+    ),
+    define(
+        "lees",
+        "Lees",
+        None,
+        "The spent yeast that settles after fermentation. Resting a white on them, sur lie, adds a creamy texture and brioche notes.",
+        &["batonnage", "racking"],
+    ),
+    define(
+        "mlf",
+        "Malolactic fermentation",
+        Some("MLF"),
+        "A second fermentation in which bacteria turn sharp malic acid into softer lactic acid, softening the acidity and adding buttery notes.",
+        &["ta"],
+    ),
+    define(
+        "must",
+        "Must",
+        None,
+        "Crushed grapes or juice before it has fermented into wine.",
+        &["yan", "chaptalization"],
+    ),
+    define(
+        "oxidation",
+        "Oxidation",
+        None,
+        "What too much oxygen does to wine: browning, and nutty, bruised-apple flavors. A little softens a red.",
+        &["reduction"],
+    ),
+    define(
+        "phenolic_ripeness",
+        "Phenolic ripeness",
+        None,
+        "How ripe the skins and seeds are, apart from the sugar. Unripe grapes give green, bitter tannins.",
+        &["tannin", "veraison"],
+    ),
+    define(
+        "press_wine",
+        "Press wine",
+        None,
+        "Wine pressed out of the skins after the free-run wine drains. It adds volume but carries harsher tannins.",
+        &["free_run", "tannin"],
+    ),
+    define(
+        "racking",
+        "Racking",
+        None,
+        "Moving wine off its sediment into a clean vessel, which also lets in a little oxygen.",
+        &["lees", "oxidation"],
+    ),
+    define(
+        "reduction",
+        "Reduction",
+        None,
+        "Struck-match or rubbery smells from too little oxygen, often under a very tight closure.",
+        &["h2s", "oxidation"],
+    ),
+    define(
+        "residual_sugar",
+        "Residual sugar",
+        Some("RS"),
+        "Sugar left unfermented in the finished wine, in g/L. Under about 4 g/L tastes dry.",
+        &["abv", "stuck_fermentation"],
+    ),
+    define(
+        "so2",
+        "Sulfur dioxide",
+        Some("SO2"),
+        "Added to protect wine from oxygen and microbes. The free, unbound part does the protecting.",
+        &["oxidation"],
+    ),
+    define(
+        "specific_gravity",
+        "Specific gravity",
+        Some("SG"),
+        "How dense the must or wine is compared with water. It falls as sugar turns into lighter alcohol, so a hydrometer tracks fermentation.",
+        &["abv", "residual_sugar"],
+    ),
+    define(
+        "stuck_fermentation",
+        "Stuck fermentation",
+        None,
+        "A fermentation that stops with sugar left, from too much alcohol, cold, heat, or too little nitrogen. Fresh, tolerant yeast can restart it.",
+        &["residual_sugar", "yan"],
+    ),
+    define(
+        "ta",
+        "Titratable acidity",
+        Some("TA"),
+        "The total acid in the must or wine, in g/L as tartaric acid. Cool climates give more.",
+        &["mlf"],
+    ),
+    define(
+        "tannin",
+        "Tannin",
+        None,
+        "Compounds from skins, seeds, stems, and oak that dry the mouth. They give a red its grip and help it age.",
+        &["press_wine", "phenolic_ripeness", "anthocyanins"],
+    ),
+    define(
+        "thiols",
+        "Thiols",
+        None,
+        "Aroma compounds some yeast strains free from grapes like Sauvignon Blanc, giving passion fruit and grapefruit.",
+        &["esters"],
+    ),
+    define(
+        "veraison",
+        "Véraison",
+        None,
+        "When the berries soften and change color, about six weeks before harvest. Sugar loads from here on.",
+        &["growing_degree_days", "phenolic_ripeness"],
+    ),
+    define(
+        "winkler_region",
+        "Winkler region",
+        None,
+        "A grouping of wine regions by growing degree days, from I (coolest) to V (warmest).",
+        &["growing_degree_days"],
+    ),
+    define(
+        "yan",
+        "Yeast assimilable nitrogen",
+        Some("YAN"),
+        "The nitrogen in the must the yeast can use, in mg/L. Too little slows the fermentation and brings hydrogen sulfide.",
+        &["h2s", "stuck_fermentation"],
+    ),
+];
+
+/// Looks a term up by key.
+pub fn term(key: &str) -> Option<&'static Term> {
+    TERMS.iter().find(|term| term.key == key)
+}
+
+/// A term as shown, in English or as translated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entry<'a> {
+    pub term: &'static Term,
+    pub name: &'a str,
+    pub definition: &'a str,
+}
+
+/// The terms with any translations laid over them.
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    /// Translated name and definition by term key.
+    translations: HashMap<&'static str, (String, String)>,
+}
+
+impl Glossary {
+    /// Reads translations from CSV with `key`, `name`, and `definition`
+    /// columns. Rows for keys that aren't terms are skipped, and terms
+    /// without a row stay in English.
+    pub fn from_csv_str(data: &str) -> Result<Self, csv::Error> {
+        let mut reader = ReaderBuilder::new().from_reader(data.as_bytes());
+        let mut translations = HashMap::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |i: usize| record.get(i).unwrap_or_default().trim().to_owned();
+            let key = field(0);
+            match term(&key) {
+                Some(term) => {
+                    translations.insert(term.key, (field(1), field(2)));
+                }
+                None => warn!(key = %key, "no glossary term to translate"),
+            }
+        }
+        Ok(Glossary { translations })
+    }
+
+    pub fn entry(&self, key: &str) -> Option<Entry<'_>> {
+        term(key).map(|term| self.show(term))
+    }
+
+    fn show(&self, term: &'static Term) -> Entry<'_> {
+        match self.translations.get(term.key) {
+            Some((name, definition)) => Entry {
+                term,
+                name,
+                definition,
+            },
+            None => Entry {
+                term,
+                name: term.name,
+                definition: term.definition,
+            },
+        }
+    }
+
+    /// Every term, sorted by name.
+    pub fn entries(&self) -> Vec<Entry<'_>> {
+        let mut entries: Vec<Entry> = TERMS.iter().map(|term| self.show(term)).collect();
+        entries.sort_by_key(|entry| entry.name.to_lowercase());
+        entries
+    }
+
+    /// Terms matching `query`, ignoring case: a name or abbreviation that is
+    /// the query, then ones that contain it, then definitions that do.
+    pub fn search(&self, query: &str) -> Vec<Entry<'_>> {
+        let query = query.trim().to_lowercase();
+        let rank = |entry: &Entry| {
+            let names = [Some(entry.name), entry.term.abbreviation]
+                .into_iter()
+                .flatten()
+                .map(str::to_lowercase);
+            let mut rank = None;
+            for name in names {
+                if name == query {
+                    return Some(0);
+                }
+                if name.contains(&query) {
+                    rank = Some(1);
+                }
+            }
+            rank.or_else(|| {
+                entry
+                    .definition
+                    .to_lowercase()
+                    .contains(&query)
+                    .then_some(2)
+            })
+        };
+        let mut found: Vec<(u8, Entry)> = self
+            .entries()
+            .into_iter()
+            .filter_map(|entry| rank(&entry).map(|rank| (rank, entry)))
+            .collect();
+        // Stable, so each rank stays in name order.
+        found.sort_by_key(|(rank, _)| *rank);
+        found.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
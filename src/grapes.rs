@@ -0,0 +1,302 @@
+//! What each grape in `engine::GRAPES` usually comes in with and how it is
+//! usually fermented, so a new plan can start from sensible numbers and
+//! stray ones can be pointed out.
+
+use crate::dataset::Dataset;
+use crate::engine::{GRAPES, SimulationInput, is_red_grape};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrapeProfile {
+    pub name: &'static str,
+    /// Harvest sugar in g/L, low to high.
+    pub sugar: (f64, f64),
+    /// Titratable acidity at harvest in g/L.
+    pub acidity: (f64, f64),
+    pub fermentation_days: (u32, u32),
+    /// Fermentation temperature in °C.
+    pub temperature: (f64, f64),
+    /// The `engine::CLIMATES` the grape ripens well in.
+    pub climates: &'static [&'static str],
+}
+
+const fn profile(
+    name: &'static str,
+    sugar: (f64, f64),
+    acidity: (f64, f64),
+    fermentation_days: (u32, u32),
+    temperature: (f64, f64),
+    climates: &'static [&'static str],
+) -> GrapeProfile {
+    GrapeProfile {
+        name,
+        sugar,
+        acidity,
+        fermentation_days,
+        temperature,
+        climates,
+    }
+}
+
+/// A profile for every grape in `engine::GRAPES`. Reds ferment warm and
+/// short on their skins; whites cool and long to keep their aromas.
+pub const GRAPE_PROFILES: [GrapeProfile; GRAPES.len()] = [
+    profile(
+        "Cabernet Sauvignon",
+        (220.0, 260.0),
+        (5.5, 7.0),
+        (10, 21),
+        (25.0, 30.0),
+        &["Moderate", "Warm"],
+    ),
+    profile(
+        "Merlot",
+        (220.0, 260.0),
+        (5.0, 6.5),
+        (10, 18),
+        (24.0, 29.0),
+        &["Moderate", "Warm"],
+    ),
+    profile(
+        "Pinot Noir",
+        (200.0, 240.0),
+        (6.0, 8.0),
+        (8, 14),
+        (25.0, 30.0),
+        &["Cool", "Moderate"],
+    ),
+    profile(
+        "Chardonnay",
+        (200.0, 240.0),
+        (6.0, 8.0),
+        (14, 28),
+        (14.0, 18.0),
+        &["Cool", "Moderate", "Warm"],
+    ),
+    profile(
+        "Sauvignon Blanc",
+        (190.0, 230.0),
+        (7.0, 9.0),
+        (14, 28),
+        (12.0, 16.0),
+        &["Cool", "Moderate"],
+    ),
+    profile(
+        "Riesling",
+        (170.0, 220.0),
+        (7.5, 10.0),
+        (14, 35),
+        (12.0, 16.0),
+        &["Cool"],
+    ),
+    profile(
+        "Syrah",
+        (220.0, 260.0),
+        (5.0, 6.5),
+        (10, 18),
+        (25.0, 30.0),
+        &["Moderate", "Warm"],
+    ),
+    profile(
+        "Shiraz",
+        (230.0, 270.0),
+        (5.0, 6.5),
+        (10, 18),
+        (25.0, 30.0),
+        &["Warm"],
+    ),
+    profile(
+        "Zinfandel",
+        (240.0, 290.0),
+        (5.0, 6.5),
+        (10, 18),
+        (25.0, 30.0),
+        &["Warm"],
+    ),
+    profile(
+        "Tempranillo",
+        (210.0, 250.0),
+        (5.0, 6.5),
+        (10, 18),
+        (24.0, 29.0),
+        &["Moderate", "Warm"],
+    ),
+    profile(
+        "Sangiovese",
+        (210.0, 250.0),
+        (6.0, 7.5),
+        (10, 18),
+        (24.0, 29.0),
+        &["Moderate", "Warm"],
+    ),
+];
+
+/// The profile of `grape`, ignoring case, if it is one of `engine::GRAPES`.
+pub fn grape_profile(grape: &str) -> Option<&'static GrapeProfile> {
+    GRAPE_PROFILES
+        .iter()
+        .find(|profile| profile.name.eq_ignore_ascii_case(grape.trim()))
+}
+
+fn middle((low, high): (f64, f64)) -> f64 {
+    (low + high) / 2.0
+}
+
+/// Whether `value` lies in the range `(low, high)`.
+pub fn within((low, high): (f64, f64), value: f64) -> bool {
+    (low..=high).contains(&value)
+}
+
+impl GrapeProfile {
+    pub fn suits_climate(&self, climate: &str) -> bool {
+        self.climates
+            .iter()
+            .any(|suited| suited.eq_ignore_ascii_case(climate.trim()))
+    }
+
+    pub fn typical_sugar(&self) -> f64 {
+        middle(self.sugar)
+    }
+
+    pub fn typical_fermentation_days(&self) -> u32 {
+        let (low, high) = self.fermentation_days;
+        (low + high) / 2
+    }
+
+    pub fn typical_temperature(&self) -> f64 {
+        middle(self.temperature)
+    }
+
+    /// Sets the sugar, fermentation days, and temperature of `input` to the
+    /// middle of this grape's ranges.
+    pub fn apply_to(&self, input: &mut SimulationInput) {
+        input.grape_type = self.name.to_owned();
+        input.sugar_content = self.typical_sugar();
+        input.fermentation_days = self.typical_fermentation_days();
+        input.temperature = self.typical_temperature();
+    }
+}
+
+/// The kind of wine a grape is being picked for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StyleGoal {
+    #[default]
+    LightDryWhite,
+    AromaticWhite,
+    RichWhite,
+    LightRed,
+    FruityRed,
+    BigTannicRed,
+}
+
+impl StyleGoal {
+    pub const ALL: [StyleGoal; 6] = [
+        StyleGoal::LightDryWhite,
+        StyleGoal::AromaticWhite,
+        StyleGoal::RichWhite,
+        StyleGoal::LightRed,
+        StyleGoal::FruityRed,
+        StyleGoal::BigTannicRed,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StyleGoal::LightDryWhite => "Light, dry white",
+            StyleGoal::AromaticWhite => "Aromatic white",
+            StyleGoal::RichWhite => "Rich, oaky white",
+            StyleGoal::LightRed => "Light, elegant red",
+            StyleGoal::FruityRed => "Juicy, fruity red",
+            StyleGoal::BigTannicRed => "Big, tannic red",
+        }
+    }
+
+    pub fn is_red(self) -> bool {
+        matches!(
+            self,
+            StyleGoal::LightRed | StyleGoal::FruityRed | StyleGoal::BigTannicRed
+        )
+    }
+
+    /// The dataset's `Style` values that describe wines of this kind.
+    pub fn styles(self) -> &'static [&'static str] {
+        match self {
+            StyleGoal::LightDryWhite => &[
+                "Crisp & Zesty",
+                "Delicate & Dry",
+                "Fresh & Elegant",
+                "Light & Refreshing",
+                "Crisp & Fruity",
+            ],
+            StyleGoal::AromaticWhite => &["Aromatic & Floral", "Fresh & Elegant"],
+            StyleGoal::RichWhite => &["Rich & Toasty", "Ripe & Rounded"],
+            StyleGoal::LightRed => &[
+                "Light & Elegant",
+                "Smooth & Light",
+                "Soft & Fruity",
+                "Crisp & Fruity",
+            ],
+            StyleGoal::FruityRed => &[
+                "Rich & Juicy",
+                "Ripe & Fruity",
+                "Smooth & Mellow",
+                "Soft & Fruity",
+            ],
+            StyleGoal::BigTannicRed => &["Savoury & Full Bodied", "Bold & Spicy"],
+        }
+    }
+
+    fn matches(self, style: &str) -> bool {
+        self.styles()
+            .iter()
+            .any(|goal| goal.eq_ignore_ascii_case(style.trim()))
+    }
+}
+
+/// How much less a grape scores in a climate it doesn't ripen well in.
+const UNSUITED_CLIMATE_PENALTY: f64 = 0.5;
+
+/// A grape ranked for a style and climate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion {
+    pub grape: &'static str,
+    /// From 0 to 1: the share of the grape's wines in the dataset made in
+    /// the style, halved if the climate doesn't suit it.
+    pub score: f64,
+    /// The grape's wines in the dataset, and how many are in the style.
+    pub wines: usize,
+    pub in_style: usize,
+    pub suits_climate: bool,
+}
+
+/// The simulator's grapes of the goal's color that the dataset has wines of
+/// in that style, best first.
+pub fn suggest_grapes(dataset: &Dataset, climate: &str, goal: StyleGoal) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = GRAPE_PROFILES
+        .iter()
+        .filter(|profile| is_red_grape(profile.name) == goal.is_red())
+        .filter_map(|profile| {
+            let records = dataset.records_for(profile.name);
+            let in_style = records
+                .iter()
+                .filter(|record| goal.matches(&record.style))
+                .count();
+            if in_style == 0 {
+                return None;
+            }
+            let suits_climate = profile.suits_climate(climate);
+            let share = in_style as f64 / records.len() as f64;
+            Some(Suggestion {
+                grape: profile.name,
+                score: if suits_climate {
+                    share
+                } else {
+                    share * UNSUITED_CLIMATE_PENALTY
+                },
+                wines: records.len(),
+                in_style,
+                suits_climate,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    suggestions
+}
@@ -0,0 +1,218 @@
+//! The simulator as a gRPC service, for research users submitting scenarios
+//! by the ten thousand. The service is defined in `proto/wine_maker.proto`;
+//! requests carry scenario files as `wine-maker run` reads them.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::thread;
+
+use tokio::sync::mpsc;
+use tokio::task::spawn_blocking;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::calibration::ModelProfile;
+use crate::dataset::Dataset;
+use crate::engine::{SimulationInput, SimulationResult, seeded};
+use crate::narrative::narrative;
+use crate::scenario::Scenario;
+
+pub mod proto {
+    tonic::include_proto!("wine_maker.v1");
+}
+
+use proto::simulate_response::Outcome;
+use proto::simulator_server::{Simulator, SimulatorServer};
+use proto::{SimulateRequest, SimulateResponse};
+
+/// Scenarios of a batch simulated or waiting to be sent at once, per core.
+const IN_FLIGHT_PER_CORE: usize = 2;
+
+/// Simulates scenarios on a dataset and the model profiles they may name.
+#[derive(Clone)]
+pub struct SimulatorService {
+    wine_data: Arc<Dataset>,
+    profiles: Arc<[ModelProfile]>,
+}
+
+impl SimulatorService {
+    pub fn new(wine_data: Dataset, profiles: Vec<ModelProfile>) -> Self {
+        SimulatorService {
+            wine_data: Arc::new(wine_data),
+            profiles: profiles.into(),
+        }
+    }
+
+    /// The answers to `request`: one, or one for each run of a sweep.
+    fn respond(&self, request: &SimulateRequest) -> Vec<SimulateResponse> {
+        let scenario = match Scenario::from_toml(&request.scenario) {
+            Ok(scenario) => Scenario {
+                input: seeded(&scenario.input),
+                ..scenario
+            },
+            Err(err) => return vec![response(request, String::new(), Err(err.to_string()))],
+        };
+        if scenario.sweeps.is_empty() {
+            let result = scenario
+                .run(&self.wine_data, &self.profiles)
+                .map(|result| (scenario.input.clone(), result))
+                .map_err(|err| err.to_string());
+            return vec![response(request, String::new(), result)];
+        }
+        match scenario.run_sweeps(&self.wine_data, &self.profiles) {
+            Ok(runs) => runs
+                .into_iter()
+                .map(|run| {
+                    let name = run.name(&scenario.sweeps);
+                    let result = run
+                        .result
+                        .map(|result| (run.input, result))
+                        .map_err(|err| err.to_string());
+                    response(request, name, result)
+                })
+                .collect(),
+            Err(err) => vec![response(request, String::new(), Err(err.to_string()))],
+        }
+    }
+}
+
+fn response(
+    request: &SimulateRequest,
+    run: String,
+    result: Result<(SimulationInput, SimulationResult), String>,
+) -> SimulateResponse {
+    SimulateResponse {
+        id: request.id.clone(),
+        run,
+        outcome: Some(match result {
+            Ok((input, result)) => Outcome::Wine(wine(&input, &result, request.narrative)),
+            Err(err) => Outcome::Error(err),
+        }),
+    }
+}
+
+fn wine(
+    input: &SimulationInput,
+    result: &SimulationResult,
+    with_narrative: bool,
+) -> proto::SimulationResult {
+    proto::SimulationResult {
+        sugar_content: result.sugar_content,
+        potential_abv: result.potential_abv,
+        fraction_fermented: result.fraction_fermented,
+        actual_abv: result.actual_abv,
+        residual_sugar: result.residual_sugar,
+        sweetness: result.sweetness.clone(),
+        body: result.body.clone(),
+        alcohol_level: result.alcohol_level.clone(),
+        tannin_level: result.tannin_level.clone(),
+        acidity: result.acidity.clone(),
+        titratable_acidity: result.titratable_acidity,
+        glycerol: result.glycerol,
+        oxygen_uptake: result.oxygen_uptake,
+        wine_yield: result.wine_yield,
+        stall_risk: result.stall_risk,
+        off_flavors: result.off_flavors.clone(),
+        peak_years: result.peak_years,
+        reduction_risk: result.reduction_risk,
+        color: result.color.clone(),
+        color_intensity: result.color_intensity,
+        dissolved_co2: result.dissolved_co2,
+        model_version: result.model_version,
+        narrative: if with_narrative {
+            narrative(input, result)
+        } else {
+            String::new()
+        },
+    }
+}
+
+#[tonic::async_trait]
+impl Simulator for SimulatorService {
+    async fn simulate(
+        &self,
+        request: Request<SimulateRequest>,
+    ) -> Result<Response<SimulateResponse>, Status> {
+        let request = request.into_inner();
+        if let Ok(scenario) = Scenario::from_toml(&request.scenario)
+            && !scenario.sweeps.is_empty()
+        {
+            return Err(Status::invalid_argument(
+                "the scenario has a [sweep]; send it to SimulateBatch",
+            ));
+        }
+        let service = self.clone();
+        let mut responses = spawn_blocking(move || service.respond(&request))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        Ok(Response::new(responses.remove(0)))
+    }
+
+    type SimulateBatchStream = ReceiverStream<Result<SimulateResponse, Status>>;
+
+    /// Simulates the requests on the blocking pool as they come in, a few
+    /// per core at a time, and sends the answers back in request order.
+    async fn simulate_batch(
+        &self,
+        request: Request<Streaming<SimulateRequest>>,
+    ) -> Result<Response<Self::SimulateBatchStream>, Status> {
+        let mut requests = request.into_inner();
+        let in_flight =
+            thread::available_parallelism().map_or(1, |cores| cores.get()) * IN_FLIGHT_PER_CORE;
+        let (jobs, mut pending) = mpsc::channel(in_flight);
+        let (responses, stream) = mpsc::channel(in_flight);
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let job = match requests.message().await {
+                    Ok(Some(request)) => {
+                        let service = service.clone();
+                        Ok(spawn_blocking(move || service.respond(&request)))
+                    }
+                    Ok(None) => break,
+                    Err(status) => Err(status),
+                };
+                let failed = job.is_err();
+                if jobs.send(job).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        tokio::spawn(async move {
+            while let Some(job) = pending.recv().await {
+                let answers = match job {
+                    Ok(handle) => handle
+                        .await
+                        .map_err(|err| Status::internal(err.to_string())),
+                    Err(status) => Err(status),
+                };
+                match answers {
+                    Ok(answers) => {
+                        for answer in answers {
+                            if responses.send(Ok(answer)).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(status) => {
+                        let _ = responses.send(Err(status)).await;
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(stream)))
+    }
+}
+
+/// Serves `service` on `address` until the process is stopped.
+pub async fn serve(
+    address: SocketAddr,
+    service: SimulatorService,
+) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(SimulatorServer::new(service))
+        .serve(address)
+        .await
+}
@@ -0,0 +1,1678 @@
+mod benchmark;
+mod calculators;
+mod career;
+#[cfg(feature = "sqlite")]
+mod cellar;
+mod challenge;
+mod chemistry;
+#[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+mod companion;
+#[cfg(feature = "sqlite")]
+mod costs;
+#[cfg(feature = "sqlite")]
+mod dashboard;
+mod dropped;
+#[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+mod export;
+mod glossary;
+#[cfg(all(feature = "sqlite", feature = "mqtt"))]
+mod home_assistant;
+#[cfg(feature = "sqlite")]
+mod inventory;
+mod kit;
+mod model_changes;
+mod neighbors;
+mod optimizer;
+mod popout;
+#[cfg(feature = "sqlite")]
+mod profile;
+mod quiz;
+#[cfg(feature = "sqlite")]
+mod reminders;
+#[cfg(all(
+    feature = "sqlite",
+    any(feature = "mqtt", feature = "serial", feature = "ble")
+))]
+mod sensors;
+mod settings;
+mod share;
+mod snapshot;
+mod stability;
+mod stepper;
+mod suggest;
+mod tour;
+mod vineyard;
+
+#[cfg(feature = "sqlite")]
+use std::collections::HashSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+use eframe::egui;
+use tracing::info;
+#[cfg(feature = "sqlite")]
+use wine_maker::calibration::{Calibration, calibrate, with_calibration};
+use wine_maker::conventions::{StrengthConvention, SugarConvention};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    AMBIENT_YEAST, Beverage, CLIMATES, CONTAINER_CATALOG, Closure, Container, Excursion,
+    Inoculation, Intervention, InterventionKind, RESTART_YEAST_TOLERANCE, SimulationInput,
+    SimulationResult, YEAST_STRAINS, is_high_gravity, is_red_grape, run_simulation, seeded,
+    sugar_from_honey, sugar_from_original_gravity, timeline, yeast_tolerance,
+};
+#[cfg(any(
+    all(not(target_arch = "wasm32"), feature = "sqlite"),
+    feature = "webhook"
+))]
+use wine_maker::export::Spreadsheet;
+use wine_maker::glossary::Glossary;
+use wine_maker::grapes::{GrapeProfile, grape_profile, within};
+use wine_maker::narrative::narrative;
+use wine_maker::recipe::Recipe;
+use wine_maker::save::SaveFile;
+#[cfg(feature = "scenario")]
+use wine_maker::scenario::Scenario;
+use wine_maker::settings::TemperatureUnit;
+#[cfg(not(target_arch = "wasm32"))]
+use wine_maker::settings::{RecentKind, Settings};
+#[cfg(feature = "sqlite")]
+use wine_maker::store::{HistoryEntry, Store};
+#[cfg(feature = "scenario")]
+use wine_maker::sweep::run_count;
+#[cfg(feature = "webhook")]
+use wine_maker::webhook::{self, Upload};
+
+use crate::logging::LogBuffer;
+use dropped::Dropped;
+use settings::SettingsEvent;
+use tour::{Target, TourEvent};
+
+pub struct WineFermentationApp {
+    wine_data: Dataset,
+    pending_dataset: Option<Receiver<Result<Dataset, String>>>,
+    beverage: Beverage,
+    grape_type: String,
+    fermentation_days: String,
+    container_type: String,
+    /// Empty to age in the fermentation vessel.
+    aging_container: String,
+    aging_months: String,
+    /// Percent of whole clusters, for red grapes.
+    whole_cluster: f64,
+    /// Percent of the press wine blended back, for red grapes.
+    press_wine: f64,
+    sugar_content: String,
+    temperature: String,
+    climate: String,
+    seed: String,
+    /// The model profile the simulation runs on, or empty for the standard
+    /// constants.
+    model_profile: String,
+    restart_day: Option<u32>,
+    /// The intervention timeline, with any excursions from a loaded input
+    /// folded in as temperature changes.
+    interventions: Vec<Intervention>,
+    /// Empty for an unspecified yeast.
+    yeast: String,
+    inoculation: Inoculation,
+    /// Lees contact and stirring, for white grapes.
+    lees_months: u32,
+    stirs_per_month: u32,
+    closure: Closure,
+    /// Honey in kg per 10 L for meads, or the original gravity for ciders.
+    sugar_source: String,
+
+    result_text: String,
+    last_result: Option<SimulationResult>,
+    /// The constants the last result was simulated on.
+    last_calibration: Calibration,
+    /// Whether the results are popped out into their own window.
+    results_popped: bool,
+    /// Whether the benchmark chart is popped out into its own window.
+    chart_popped: bool,
+
+    log_buffer: LogBuffer,
+    show_log: bool,
+    calculators: calculators::CalculatorsWindow,
+    vineyard: vineyard::VineyardPanel,
+    kit: kit::KitPanel,
+    suggest: suggest::SuggestPanel,
+    stability: stability::StabilityPanel,
+    benchmark: benchmark::BenchmarkPanel,
+    neighbors: neighbors::NeighborsPanel,
+    stepper: stepper::StepperWindow,
+    optimizer: optimizer::OptimizerPanel,
+    challenges: challenge::ChallengeWindow,
+    career: career::CareerWindow,
+    quiz: quiz::QuizWindow,
+    glossary: glossary::GlossaryWindow,
+    model_changes: model_changes::ModelChangesWindow,
+    tour: tour::Tour,
+    share: share::ShareWindow,
+    settings: settings::SettingsWindow,
+    dropped: dropped::DropWindow,
+
+    #[cfg(feature = "sqlite")]
+    store: Option<Store>,
+    #[cfg(feature = "sqlite")]
+    history: Vec<HistoryEntry>,
+    #[cfg(feature = "sqlite")]
+    show_history: bool,
+    /// History entries ticked for export, by id.
+    #[cfg(feature = "sqlite")]
+    history_export: HashSet<i64>,
+    #[cfg(feature = "sqlite")]
+    tab: Tab,
+    #[cfg(feature = "sqlite")]
+    cellar: cellar::CellarTab,
+    #[cfg(feature = "sqlite")]
+    reminders: reminders::Reminders,
+    #[cfg(feature = "sqlite")]
+    profile: profile::ProfileWindow,
+    #[cfg(all(feature = "sqlite", feature = "mqtt"))]
+    home_assistant: home_assistant::HomeAssistantWindow,
+}
+
+#[cfg(feature = "sqlite")]
+#[derive(Clone, Copy, PartialEq)]
+enum Tab {
+    Simulator,
+    Cellar,
+}
+
+#[cfg(feature = "sqlite")]
+const HISTORY_LIMIT: usize = 100;
+
+impl WineFermentationApp {
+    pub fn new(wine_data: Dataset, log_buffer: LogBuffer) -> Self {
+        Self {
+            wine_data,
+            pending_dataset: None,
+            beverage: Beverage::Wine,
+            grape_type: String::new(),
+            fermentation_days: String::new(),
+            container_type: String::new(),
+            aging_container: String::new(),
+            aging_months: String::new(),
+            whole_cluster: 0.0,
+            press_wine: 0.0,
+            sugar_content: String::new(),
+            temperature: String::new(),
+            climate: String::new(),
+            seed: String::new(),
+            model_profile: String::new(),
+            restart_day: None,
+            interventions: Vec::new(),
+            yeast: String::new(),
+            inoculation: Inoculation::Single,
+            lees_months: 0,
+            stirs_per_month: 0,
+            closure: Closure::NaturalCork,
+            sugar_source: String::new(),
+            result_text: String::new(),
+            last_result: None,
+            last_calibration: Calibration::default(),
+            results_popped: false,
+            chart_popped: false,
+            log_buffer,
+            show_log: false,
+            calculators: calculators::CalculatorsWindow::default(),
+            vineyard: vineyard::VineyardPanel::default(),
+            kit: kit::KitPanel::default(),
+            suggest: suggest::SuggestPanel::default(),
+            stability: stability::StabilityPanel::default(),
+            benchmark: benchmark::BenchmarkPanel::default(),
+            neighbors: neighbors::NeighborsPanel::default(),
+            stepper: stepper::StepperWindow::default(),
+            optimizer: optimizer::OptimizerPanel::default(),
+            challenges: challenge::ChallengeWindow::default(),
+            career: career::CareerWindow::default(),
+            quiz: quiz::QuizWindow::default(),
+            glossary: glossary::GlossaryWindow::default(),
+            model_changes: model_changes::ModelChangesWindow::default(),
+            tour: tour::Tour::default(),
+            share: share::ShareWindow::default(),
+            settings: settings::SettingsWindow::default(),
+            dropped: dropped::DropWindow::default(),
+            #[cfg(feature = "sqlite")]
+            store: None,
+            #[cfg(feature = "sqlite")]
+            history: Vec::new(),
+            #[cfg(feature = "sqlite")]
+            show_history: false,
+            #[cfg(feature = "sqlite")]
+            history_export: HashSet::new(),
+            #[cfg(feature = "sqlite")]
+            tab: Tab::Simulator,
+            #[cfg(feature = "sqlite")]
+            cellar: cellar::CellarTab::default(),
+            #[cfg(feature = "sqlite")]
+            reminders: reminders::Reminders::default(),
+            #[cfg(feature = "sqlite")]
+            profile: profile::ProfileWindow::default(),
+            #[cfg(all(feature = "sqlite", feature = "mqtt"))]
+            home_assistant: home_assistant::HomeAssistantWindow::default(),
+        }
+    }
+
+    /// Shows `glossary`'s translations in place of the English terms.
+    pub fn with_glossary(mut self, glossary: Glossary) -> Self {
+        self.glossary.set_glossary(glossary);
+        self
+    }
+
+    /// Applies `settings`, saved at `path`, and starts from their default
+    /// inputs if there are any.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_settings(mut self, settings: Settings, path: PathBuf) -> Self {
+        let defaults = settings.defaults.clone();
+        self.model_profile = settings.profile.clone();
+        self.settings.set_settings(settings, path);
+        if let Some(defaults) = defaults {
+            self.set_input(&defaults);
+        }
+        self
+    }
+
+    /// Builds share links on `base`, the page the web build is served from.
+    #[cfg(target_arch = "wasm32")]
+    pub fn set_share_link_base(&mut self, base: String) {
+        self.share.set_link_base(base);
+    }
+
+    /// Loads the inputs from a shared simulation code.
+    #[cfg(target_arch = "wasm32")]
+    pub fn open_shared(&mut self, code: &str) {
+        match wine_maker::permalink::decode(code) {
+            Ok(input) => {
+                self.set_input(&input);
+                info!("shared simulation opened");
+            }
+            Err(err) => self.result_text = format!("Could not open the shared simulation: {}", err),
+        }
+    }
+
+    /// Records each simulation in `store` and shows past runs in a History window.
+    #[cfg(feature = "sqlite")]
+    pub fn with_store(mut self, store: Store) -> Self {
+        match store.challenge_scores() {
+            Ok(best) => self.challenges.set_best(best),
+            Err(err) => tracing::error!("Could not read challenge scores: {}", err),
+        }
+        self.profile.load(&store);
+        match store.tour_seen() {
+            Ok(seen) => self.tour.open = !seen,
+            Err(err) => tracing::error!("Could not read whether the tour was seen: {}", err),
+        }
+        self.store = Some(store);
+        self.refresh_history();
+        self
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn refresh_history(&mut self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        match store.history(HISTORY_LIMIT) {
+            Ok(history) => self.history = history,
+            Err(err) => tracing::error!("Could not read history: {}", err),
+        }
+    }
+
+    /// Fits the model to every batch in the cellar and offers the fit in
+    /// the settings.
+    #[cfg(feature = "sqlite")]
+    fn calibrate(&mut self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let batches = store.batches().and_then(|batches| {
+            batches
+                .into_iter()
+                .map(|batch| {
+                    let measurements = store.measurements(batch.id)?;
+                    Ok((batch, measurements))
+                })
+                .collect::<rusqlite::Result<Vec<_>>>()
+        });
+        self.settings.set_fit(match batches {
+            Ok(batches) => calibrate(&batches).map_err(|err| err.to_string()),
+            Err(err) => Err(format!("Could not read the cellar: {}", err)),
+        });
+    }
+
+    fn input(&self) -> SimulationInput {
+        SimulationInput {
+            beverage: self.beverage,
+            grape_type: self.grape_type.clone(),
+            fermentation_days: self.fermentation_days.trim().parse().unwrap_or_default(),
+            container_type: self.container_type.clone(),
+            aging_container: self.aging_container.clone(),
+            aging_months: self.aging_months.trim().parse().unwrap_or_default(),
+            whole_cluster: self.whole_cluster,
+            press_wine: self.press_wine,
+            sugar_content: self.sugar_content.trim().parse().unwrap_or_default(),
+            temperature: self
+                .temperature_unit()
+                .to_celsius(self.temperature.trim().parse().unwrap_or_default()),
+            climate: self.climate.clone(),
+            seed: self.seed.trim().parse().ok(),
+            restart_day: self.restart_day,
+            excursions: Vec::new(),
+            yeast: self.yeast.clone(),
+            inoculation: self.inoculation,
+            lees_months: self.lees_months,
+            stirs_per_month: self.stirs_per_month,
+            closure: self.closure,
+            interventions: self.interventions.clone(),
+        }
+    }
+
+    fn temperature_unit(&self) -> TemperatureUnit {
+        self.settings.settings.temperature_unit
+    }
+
+    /// `celsius` for the temperature field, in the chosen unit.
+    fn temperature_text(&self, celsius: f64) -> String {
+        match self.temperature_unit() {
+            TemperatureUnit::Celsius => celsius.to_string(),
+            unit => format!("{:.1}", unit.from_celsius(celsius)),
+        }
+    }
+
+    /// The typical ranges for the selected grape, when making wine.
+    fn grape_profile(&self) -> Option<&'static GrapeProfile> {
+        if self.beverage == Beverage::Wine {
+            grape_profile(&self.grape_type)
+        } else {
+            None
+        }
+    }
+
+    /// Fills in the sugar, days, and temperature a newly picked grape is
+    /// usually fermented with, to be edited from there.
+    fn fill_grape_defaults(&mut self, profile: &GrapeProfile) {
+        let mut input = self.input();
+        profile.apply_to(&mut input);
+        self.sugar_content = input.sugar_content.to_string();
+        self.fermentation_days = input.fermentation_days.to_string();
+        self.temperature = self.temperature_text(input.temperature);
+    }
+
+    fn set_input(&mut self, input: &SimulationInput) {
+        self.beverage = input.beverage;
+        self.grape_type = input.grape_type.clone();
+        self.fermentation_days = input.fermentation_days.to_string();
+        self.container_type = input.container_type.clone();
+        self.aging_container = input.aging_container.clone();
+        self.aging_months = input.aging_months.to_string();
+        self.whole_cluster = input.whole_cluster;
+        self.press_wine = input.press_wine;
+        self.sugar_content = input.sugar_content.to_string();
+        self.temperature = self.temperature_text(input.temperature);
+        self.climate = input.climate.clone();
+        self.seed = input.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        self.restart_day = input.restart_day;
+        self.yeast = input.yeast.clone();
+        self.inoculation = input.inoculation;
+        self.lees_months = input.lees_months;
+        self.stirs_per_month = input.stirs_per_month;
+        self.closure = input.closure;
+        // The restart has its own control; everything else goes in the table.
+        self.interventions = timeline(&SimulationInput {
+            restart_day: None,
+            ..input.clone()
+        });
+    }
+
+    fn show_interventions(&mut self, ui: &mut egui::Ui) {
+        let mut removed = None;
+        let mut resort = false;
+        egui::Grid::new("interventions")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Day");
+                ui.strong("Type");
+                ui.strong("Amount");
+                ui.end_row();
+                for (i, intervention) in self.interventions.iter_mut().enumerate() {
+                    let day = ui.add(egui::DragValue::new(&mut intervention.day).range(0..=1000));
+                    resort |= day.drag_stopped() || day.lost_focus();
+                    egui::ComboBox::from_id_salt(("intervention_kind", i))
+                        .selected_text(intervention.kind.label())
+                        .show_ui(ui, |ui| {
+                            for kind in InterventionKind::ALL {
+                                if ui
+                                    .selectable_value(&mut intervention.kind, kind, kind.label())
+                                    .clicked()
+                                {
+                                    intervention.amount = kind.default_amount();
+                                }
+                            }
+                        });
+                    match intervention.kind.unit() {
+                        Some(unit) => {
+                            ui.add(
+                                egui::DragValue::new(&mut intervention.amount)
+                                    .speed(0.1)
+                                    .suffix(format!(" {}", unit)),
+                            );
+                        }
+                        None => {
+                            ui.label("");
+                        }
+                    }
+                    if ui.small_button("Remove").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            self.interventions.remove(i);
+        }
+        if resort {
+            self.interventions
+                .sort_by_key(|intervention| intervention.day);
+        }
+
+        ui.horizontal(|ui| {
+            let next_day = self.interventions.last().map_or(0, |last| last.day);
+            if ui.button("Add").clicked() {
+                self.interventions
+                    .push(Intervention::new(next_day, InterventionKind::Nutrient));
+            }
+            // Set temperature to go back to after a spell.
+            let set = self
+                .temperature
+                .trim()
+                .parse()
+                .unwrap_or(InterventionKind::Temperature.default_amount());
+            for (name, hover, spell) in [
+                (
+                    "Power Outage",
+                    "Three days at 10 °C.",
+                    Excursion::power_outage(next_day),
+                ),
+                (
+                    "Heat Wave",
+                    "Two days at 32 °C.",
+                    Excursion::heat_wave(next_day),
+                ),
+            ] {
+                if ui.button(name).on_hover_text(hover).clicked() {
+                    self.interventions.extend([
+                        Intervention {
+                            day: spell.start_day,
+                            kind: InterventionKind::Temperature,
+                            amount: spell.temperature,
+                        },
+                        Intervention {
+                            day: spell.start_day + spell.days,
+                            kind: InterventionKind::Temperature,
+                            amount: set,
+                        },
+                    ]);
+                    self.interventions
+                        .sort_by_key(|intervention| intervention.day);
+                }
+            }
+        });
+        ui.label(
+            "Days count from the start of fermentation, on through aging. Temperature \
+             changes hold until the next one. Nutrient only counts before the one-third sugar \
+             break. Aeration or racking clears fresh hydrogen sulfide; copper also clears the \
+             mercaptans it turns into, but neither fixes the disulfides they end up as. \
+             Rackings replace the usual every-three-months schedule.",
+        );
+    }
+
+    /// Runs the inputs on the picked model profile, leaving the cellar and
+    /// everything else on theirs.
+    fn simulate(&mut self) {
+        let input = seeded(&self.input());
+        let calibration = self.settings.calibration(&self.model_profile);
+
+        self.last_result = None;
+        let run = with_calibration(calibration, || {
+            run_simulation(&input, &self.wine_data).map(|result| {
+                let text = narrative(&input, &result);
+                (result, text)
+            })
+        });
+        self.result_text = match run {
+            Ok((result, text)) => {
+                #[cfg(feature = "sqlite")]
+                self.record(&input, &result, &text);
+                #[cfg(feature = "webhook")]
+                if self.settings.settings.webhook.send_each_run {
+                    let mut sheet = Spreadsheet::default();
+                    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+                    sheet.push("Simulation", &now.to_string(), &input, Ok(&result));
+                    self.send_upload(Upload::wines(&sheet));
+                }
+                self.last_result = Some(result);
+                self.last_calibration = calibration;
+                text
+            }
+            Err(err) => err.to_string(),
+        };
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_file_menu(&mut self, ui: &mut egui::Ui) {
+        let items: [(&str, fn(&mut Self)); 3] = [
+            ("Open Session…", Self::open_session),
+            ("Save Session…", Self::save_session),
+            ("Open Dataset…", Self::open_dataset),
+        ];
+        for (label, action) in items {
+            if ui.button(label).clicked() {
+                ui.close_menu();
+                action(self);
+            }
+        }
+        ui.menu_button("Recent", |ui| {
+            let recent = self.settings.settings.recent.clone();
+            if recent.is_empty() {
+                ui.label("Nothing opened yet.");
+            }
+            for file in recent {
+                let name = file.path.file_name().unwrap_or_default().to_string_lossy();
+                if ui
+                    .button(format!("{}: {}", file.kind.label(), name))
+                    .on_hover_text(file.path.display().to_string())
+                    .clicked()
+                {
+                    ui.close_menu();
+                    match file.kind {
+                        RecentKind::Dataset => self.open_dataset_path(&file.path),
+                        RecentKind::Session => self.open_session_path(&file.path),
+                    }
+                }
+            }
+        });
+        ui.separator();
+        if ui.button("Import Recipe…").clicked() {
+            ui.close_menu();
+            self.import_recipe();
+        }
+        if ui.button("Export Recipe…").clicked() {
+            ui.close_menu();
+            self.export_recipe();
+        }
+        #[cfg(feature = "scenario")]
+        if ui.button("Import Scenario…").clicked() {
+            ui.close_menu();
+            self.import_scenario();
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_dataset(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Wine dataset", &["csv"])
+            .pick_file()
+        else {
+            return;
+        };
+        self.open_dataset_path(&path);
+    }
+
+    /// Uses the dataset at `path`, and remembers it among the recent files.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_dataset_path(&mut self, path: &Path) {
+        match Dataset::from_csv_path(path) {
+            Ok(dataset) => {
+                info!(records = dataset.len(), path = %path.display(), "dataset loaded");
+                self.wine_data = dataset;
+                self.settings
+                    .add_recent(RecentKind::Dataset, path.to_owned());
+            }
+            Err(err) => {
+                self.result_text = format!("Could not open {}: {}", path.display(), err);
+                self.settings.remove_recent(path);
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_session(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Wine session", &["json"])
+            .set_file_name("session.json")
+            .save_file()
+        else {
+            return;
+        };
+        let mut save_file = SaveFile::new(self.input(), self.last_result.clone());
+        if let Some(career) = self.career.career() {
+            save_file = save_file.with_career(career.clone());
+        }
+        match save_file.save(&path) {
+            Ok(()) => {
+                info!(path = %path.display(), "session saved");
+                self.settings.add_recent(RecentKind::Session, path);
+            }
+            Err(err) => tracing::error!("Could not save session: {}", err),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_session(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Wine session", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        self.open_session_path(&path);
+    }
+
+    /// Opens the session at `path`, and remembers it among the recent files.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_session_path(&mut self, path: &Path) {
+        match SaveFile::load(path) {
+            Ok(save_file) => {
+                self.open_save_file(save_file);
+                self.settings
+                    .add_recent(RecentKind::Session, path.to_owned());
+                info!(path = %path.display(), "session opened");
+            }
+            Err(err) => {
+                self.result_text = format!("Could not open {}: {}", path.display(), err);
+                self.settings.remove_recent(path);
+            }
+        }
+    }
+
+    fn open_save_file(&mut self, save_file: SaveFile) {
+        self.set_input(&save_file.input);
+        if let Some(fresh) = save_file.resimulated(&self.wine_data)
+            && let Some(stored) = &save_file.result
+        {
+            self.model_changes.offer(stored.clone(), fresh);
+        }
+        self.result_text = match &save_file.result {
+            Some(result) => narrative(&save_file.input, result),
+            None => String::new(),
+        };
+        self.last_result = save_file.result;
+        self.career.set_career(save_file.career);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_recipe(&self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Recipe", &["json"])
+            .set_file_name("recipe.json")
+            .save_file()
+        else {
+            return;
+        };
+        let name = path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        match Recipe::new(name, self.input()).save(&path) {
+            Ok(()) => info!(path = %path.display(), "recipe exported"),
+            Err(err) => tracing::error!("Could not export recipe: {}", err),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_recipe(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Recipe", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        match Recipe::load(&path) {
+            Ok(recipe) => {
+                self.open_recipe(recipe);
+                info!(path = %path.display(), "recipe imported");
+            }
+            Err(err) => self.result_text = format!("Could not import {}: {}", path.display(), err),
+        }
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "scenario"))]
+    fn import_scenario(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Scenario", &["toml"])
+            .pick_file()
+        else {
+            return;
+        };
+        match Scenario::load(&path) {
+            Ok(scenario) => self.open_scenario(scenario),
+            Err(err) => self.result_text = format!("Could not import {}: {}", path.display(), err),
+        }
+    }
+
+    /// Takes up a scenario's inputs and model, and runs it.
+    #[cfg(feature = "scenario")]
+    fn open_scenario(&mut self, scenario: Scenario) {
+        self.set_input(&scenario.input);
+        match self.settings.use_scenario_model(&scenario) {
+            Ok(profile) => {
+                self.model_profile = profile;
+                self.simulate();
+                if !scenario.sweeps.is_empty() {
+                    self.result_text.push_str(&format!(
+                        "\n\nThis is the first run of a sweep over {} runs. Run them all with \
+                         `wine-maker run`.",
+                        run_count(&scenario.sweeps)
+                    ));
+                }
+                info!(name = %scenario.name, "scenario imported");
+            }
+            Err(err) => {
+                self.result_text = format!("Could not run {}: {}", scenario.name, err);
+                self.last_result = None;
+            }
+        }
+    }
+
+    fn open_recipe(&mut self, recipe: Recipe) {
+        self.set_input(&recipe.input);
+        self.result_text = recipe.notes;
+        self.last_result = None;
+    }
+
+    /// Hands a confirmed dropped file to its importer.
+    fn open_dropped(&mut self, name: &str, dropped: Dropped) {
+        match dropped {
+            Dropped::Dataset(dataset) => {
+                info!(records = dataset.len(), file = name, "dataset loaded");
+                self.wine_data = dataset;
+            }
+            Dropped::Session(save_file) => {
+                self.open_save_file(*save_file);
+                info!(file = name, "session opened");
+            }
+            Dropped::Recipe(recipe) => {
+                self.open_recipe(recipe);
+                info!(file = name, "recipe imported");
+            }
+            #[cfg(feature = "scenario")]
+            Dropped::Scenario(scenario) => self.open_scenario(*scenario),
+            #[cfg(feature = "sqlite")]
+            Dropped::DeviceLog(readings) => {
+                if let Some(store) = &self.store {
+                    self.cellar.add_dropped_readings(store, name, &readings);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn record(&mut self, input: &SimulationInput, result: &SimulationResult, text: &str) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if let Err(err) = store.record_simulation(input, result, text) {
+            tracing::error!("Could not save simulation: {}", err);
+        }
+        self.profile.record(store, input, result);
+        self.refresh_history();
+    }
+
+    /// Sends `upload` to the webhook set up in Settings, logging how it went.
+    #[cfg(feature = "webhook")]
+    fn send_upload(&self, upload: Upload) {
+        let Some(target) = &self.settings.settings.webhook.target else {
+            tracing::warn!("No webhook is set up in Settings to send results to");
+            return;
+        };
+        let rows = upload.rows.len();
+        webhook::send(target, &upload, move |result| match result {
+            Ok(()) => info!(rows, "results sent"),
+            Err(err) => tracing::error!("Could not send results: {}", err),
+        });
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn restore(&mut self, entry: &HistoryEntry) {
+        self.set_input(&entry.input);
+        self.result_text = entry.narrative.clone();
+        self.last_result = None;
+    }
+
+    /// The ticked history runs, one row each.
+    #[cfg(all(
+        feature = "sqlite",
+        any(not(target_arch = "wasm32"), feature = "webhook")
+    ))]
+    fn history_sheet(&self) -> Spreadsheet {
+        let mut sheet = Spreadsheet::default();
+        for entry in &self.history {
+            if self.history_export.contains(&entry.id) {
+                let result = run_simulation(&entry.input, &self.wine_data);
+                sheet.push(
+                    &format!("Run {}", entry.id),
+                    &entry.created_at,
+                    &entry.input,
+                    result.as_ref(),
+                );
+            }
+        }
+        sheet
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+    fn export_history(&self) {
+        let sheet = self.history_sheet();
+        match export::save_spreadsheet(&sheet, "history") {
+            Ok(Some(path)) => {
+                info!(runs = sheet.rows().len(), path = %path.display(), "history exported")
+            }
+            Ok(None) => {}
+            Err(err) => tracing::error!("Could not export history: {}", err),
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn show_history_window(&mut self, ctx: &egui::Context) {
+        let mut show_history = self.show_history;
+        let mut selected = None;
+        let mut clear = false;
+        egui::Window::new("History")
+            .open(&mut show_history)
+            .default_size([500.0, 300.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Clear").clicked() {
+                        clear = true;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui
+                        .add_enabled(
+                            !self.history_export.is_empty(),
+                            egui::Button::new("Export Selected…"),
+                        )
+                        .on_hover_text("Saves the ticked runs as a spreadsheet, one row each.")
+                        .clicked()
+                    {
+                        self.export_history();
+                    }
+                    #[cfg(feature = "webhook")]
+                    if ui
+                        .add_enabled(
+                            !self.history_export.is_empty(),
+                            egui::Button::new("Send Selected"),
+                        )
+                        .on_hover_text("Sends the ticked runs to the webhook set up in Settings.")
+                        .clicked()
+                    {
+                        self.send_upload(Upload::wines(&self.history_sheet()));
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for (i, entry) in self.history.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let mut ticked = self.history_export.contains(&entry.id);
+                                if ui.checkbox(&mut ticked, "").changed() {
+                                    if ticked {
+                                        self.history_export.insert(entry.id);
+                                    } else {
+                                        self.history_export.remove(&entry.id);
+                                    }
+                                }
+                                if ui.button("Load").clicked() {
+                                    selected = Some(i);
+                                }
+                                ui.label(format!(
+                                    "{}  {}, {} days, {}: {:.2}% ABV",
+                                    entry.created_at,
+                                    entry.input.grape_type,
+                                    entry.input.fermentation_days,
+                                    entry.input.container_type,
+                                    entry.actual_abv
+                                ));
+                            });
+                        }
+                    });
+            });
+        self.show_history = show_history;
+
+        if let Some(i) = selected {
+            let entry = self.history[i].clone();
+            self.restore(&entry);
+        }
+        if clear && let Some(store) = &self.store {
+            if let Err(err) = store.clear_history() {
+                tracing::error!("Could not clear history: {}", err);
+            }
+            self.history_export.clear();
+            self.refresh_history();
+        }
+    }
+
+    /// Swaps in the dataset from `receiver` once a background load finishes.
+    #[cfg(target_arch = "wasm32")]
+    pub fn load_dataset_from(&mut self, receiver: Receiver<Result<Dataset, String>>) {
+        self.pending_dataset = Some(receiver);
+    }
+
+    fn poll_pending_dataset(&mut self) {
+        let Some(receiver) = &self.pending_dataset else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(Ok(dataset)) => {
+                info!(records = dataset.len(), "dataset loaded");
+                self.wine_data = dataset;
+                self.pending_dataset = None;
+            }
+            Ok(Err(err)) => {
+                tracing::error!("Could not load dataset: {}", err);
+                self.pending_dataset = None;
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.pending_dataset = None,
+        }
+    }
+
+    fn show_simulator(&mut self, ui: &mut egui::Ui) {
+        let beverage = ui.horizontal(|ui| {
+            ui.label("Beverage:");
+            for beverage in Beverage::ALL {
+                if ui
+                    .selectable_label(self.beverage == beverage, beverage.label())
+                    .clicked()
+                    && self.beverage != beverage
+                {
+                    self.set_input(&beverage.defaults());
+                    self.sugar_source.clear();
+                }
+            }
+        });
+        self.tour.mark(Target::Beverage, beverage.response.rect);
+
+        if self.beverage.uses_climate() {
+            egui::CollapsingHeader::new("Region and vineyard (optional)").show(ui, |ui| {
+                if let Some(picked) = self.vineyard.show(ui) {
+                    let mut input = self.input();
+                    if let Some(region) = &picked.region {
+                        region.apply_to(&mut input);
+                        #[cfg(feature = "sqlite")]
+                        self.cellar.set_process(region.process);
+                    }
+                    picked.harvest.apply_to(&mut input);
+                    self.set_input(&input);
+                }
+            });
+            egui::CollapsingHeader::new("Wine kit (optional)").show(ui, |ui| {
+                if let Some(plan) = self.kit.show(ui) {
+                    self.set_input(&plan.input);
+                }
+            });
+        }
+
+        let substrate = self.beverage.substrate_label();
+        ui.label(format!("{} Type:", substrate));
+        let mut picked = false;
+        let grape = egui::ComboBox::from_label(format!("Select a {}", substrate))
+            .selected_text(&self.grape_type)
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(100.0)
+                    .show(ui, |ui| {
+                        for grape in self.beverage.substrates() {
+                            picked |= ui
+                                .selectable_value(&mut self.grape_type, grape.to_string(), *grape)
+                                .changed();
+                        }
+                    });
+            });
+        self.tour.mark(Target::Grape, grape.response.rect);
+        if self.beverage == Beverage::Wine {
+            egui::CollapsingHeader::new("Suggest a grape (optional)").show(ui, |ui| {
+                if let Some(grape) = self.suggest.show(ui, &self.wine_data, &self.climate) {
+                    self.grape_type = grape.to_owned();
+                    picked = true;
+                }
+            });
+        }
+        let profile = self.grape_profile();
+        if picked && let Some(profile) = profile {
+            self.fill_grape_defaults(profile);
+        }
+
+        if self.beverage == Beverage::Wine && is_red_grape(&self.grape_type) {
+            ui.horizontal(|ui| {
+                ui.label("Whole Clusters:");
+                self.glossary.link(ui, "tannin");
+            });
+            ui.add(egui::Slider::new(&mut self.whole_cluster, 0.0..=100.0).suffix("%"))
+                .on_hover_text(
+                    "Stems add green, spicy tannins and a little water, as in many Pinot Noirs and Syrahs.",
+                );
+            ui.horizontal(|ui| {
+                ui.label("Press Wine Blended Back:");
+                self.glossary.link(ui, "press_wine");
+            });
+            ui.add(egui::Slider::new(&mut self.press_wine, 0.0..=100.0).suffix("%"))
+                .on_hover_text(
+                    "Press wine adds volume but carries harsher tannins than the free-run wine.",
+                );
+        }
+
+        let (low, high) = profile.map_or((5, 21), |profile| profile.fermentation_days);
+        ui.label(format!("Fermentation Days (Usually {}-{}):", low, high));
+        let days = ui.text_edit_singleline(&mut self.fermentation_days);
+        self.tour.mark(Target::Days, days.rect);
+
+        ui.label("Container Type:");
+        let container = egui::ComboBox::from_label("Select Container")
+            .selected_text(&self.container_type)
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(100.0)
+                    .show(ui, |ui| {
+                        for container in &CONTAINER_CATALOG {
+                            ui.selectable_value(
+                                &mut self.container_type,
+                                container.name.to_owned(),
+                                container.name,
+                            )
+                            .on_hover_text(describe_container(container));
+                        }
+                    });
+            });
+        self.tour.mark(Target::Container, container.response.rect);
+
+        ui.label("Aging Vessel:");
+        egui::ComboBox::from_label("Select Aging Vessel")
+            .selected_text(if self.aging_container.is_empty() {
+                "Same as fermentation"
+            } else {
+                self.aging_container.as_str()
+            })
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut self.aging_container,
+                    String::new(),
+                    "Same as fermentation",
+                );
+                for container in &CONTAINER_CATALOG {
+                    ui.selectable_value(
+                        &mut self.aging_container,
+                        container.name.to_owned(),
+                        container.name,
+                    )
+                    .on_hover_text(describe_container(container));
+                }
+            });
+
+        ui.label("Aging Months:");
+        ui.text_edit_singleline(&mut self.aging_months);
+
+        egui::ComboBox::from_label("Closure")
+            .selected_text(self.closure.label())
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                for closure in Closure::ALL {
+                    ui.selectable_value(&mut self.closure, closure, closure.label())
+                        .on_hover_text(format!(
+                            "Lets in about {:.1} mg/L of oxygen a year.",
+                            closure.oxygen_transfer()
+                        ));
+                }
+            });
+
+        if self.beverage == Beverage::Wine && !is_red_grape(&self.grape_type) {
+            ui.horizontal(|ui| {
+                ui.label("Sur lie:");
+                self.glossary.link(ui, "lees");
+                ui.add(
+                    egui::DragValue::new(&mut self.lees_months)
+                        .range(0..=24)
+                        .suffix(" months"),
+                )
+                .on_hover_text("Resting on the lees adds a creamy texture and brioche notes.");
+                ui.label("stirred");
+                ui.add(
+                    egui::DragValue::new(&mut self.stirs_per_month)
+                        .range(0..=8)
+                        .suffix("× a month"),
+                )
+                .on_hover_text("Bâtonnage speeds up what the lees add and softens the acidity.");
+            });
+        }
+
+        // 5) Climate dropdown, for grapes only:
+        if self.beverage.uses_climate() {
+            ui.label("Climate:");
+            egui::ComboBox::from_label("Select a Climate")
+                .selected_text(&self.climate)
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(100.0)
+                        .show(ui, |ui| {
+                            for climate_option in &CLIMATES {
+                                ui.selectable_value(
+                                    &mut self.climate,
+                                    climate_option.to_string(),
+                                    *climate_option,
+                                );
+                            }
+                        });
+                });
+        }
+
+        let (low, high) = profile.map_or(self.beverage.sugar_range(), |profile| profile.sugar);
+        ui.label(format!(
+            "Sugar Content (g/L) (Usually {:.0}g-{:.0}g):",
+            low, high
+        ));
+        let sugar = ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.sugar_content);
+            self.glossary.link(ui, "chaptalization");
+        });
+        self.tour.mark(Target::Sugar, sugar.response.rect);
+        if let Some(profile) = profile {
+            if let Ok(sugar) = self.sugar_content.trim().parse()
+                && !within(profile.sugar, sugar)
+            {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "{} usually comes in at {:.0}-{:.0} g/L of sugar.",
+                        profile.name, profile.sugar.0, profile.sugar.1
+                    ),
+                );
+            }
+            ui.horizontal(|ui| {
+                let (low, high) = profile.acidity;
+                ui.label(format!(
+                    "{} usually has {:.1}-{:.1} g/L of acidity (TA).",
+                    profile.name, low, high
+                ));
+                self.glossary.link(ui, "ta");
+            });
+        }
+        // Meads are made up from honey and ciders measured by gravity, so
+        // either can be entered that way instead.
+        let source = match self.beverage {
+            Beverage::Mead => Some("Or honey (kg per 10 L):"),
+            Beverage::Cider => Some("Or original gravity:"),
+            Beverage::Wine | Beverage::FruitWine => None,
+        };
+        if let Some(source) = source {
+            ui.horizontal(|ui| {
+                ui.label(source);
+                if ui.text_edit_singleline(&mut self.sugar_source).changed()
+                    && let Ok(value) = self.sugar_source.trim().parse::<f64>()
+                {
+                    let sugar = if self.beverage == Beverage::Mead {
+                        sugar_from_honey(value, 10.0)
+                    } else {
+                        sugar_from_original_gravity(value)
+                    };
+                    self.sugar_content = format!("{:.0}", sugar);
+                }
+            });
+        }
+        if is_high_gravity(&self.input()) || self.restart_day.is_some() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "This must could pass {:.0}% ABV, more than the yeast can take, so expect a sluggish, sweet finish.",
+                    yeast_tolerance(&self.input())
+                ),
+            );
+            ui.horizontal(|ui| {
+                let mut restart = self.restart_day.is_some();
+                if ui
+                    .checkbox(&mut restart, "Restart with fresh yeast on day")
+                    .on_hover_text(format!(
+                        "Pitches champagne yeast that tolerates up to {:.0}% ABV.",
+                        RESTART_YEAST_TOLERANCE
+                    ))
+                    .changed()
+                {
+                    let days: u32 = self.fermentation_days.trim().parse().unwrap_or_default();
+                    self.restart_day = restart.then_some((days / 2).max(1));
+                }
+                if let Some(day) = &mut self.restart_day {
+                    ui.add(egui::DragValue::new(day).range(1..=120));
+                }
+                self.glossary.link(ui, "stuck_fermentation");
+            });
+        }
+
+        ui.label("Yeast Strain:");
+        let yeast = egui::ComboBox::from_label("Select Yeast")
+            .selected_text(if self.yeast.is_empty() {
+                "Unspecified"
+            } else {
+                self.yeast.as_str()
+            })
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.yeast, String::new(), "Unspecified");
+                ui.selectable_value(&mut self.yeast, AMBIENT_YEAST.to_owned(), AMBIENT_YEAST)
+                    .on_hover_text(
+                        "Native yeast: the start, finish, and character vary with the seed, and sometimes go wrong.",
+                    );
+                for strain in &YEAST_STRAINS {
+                    ui.selectable_value(&mut self.yeast, strain.name.to_owned(), strain.name)
+                        .on_hover_text(strain.note);
+                }
+            });
+        self.tour.mark(Target::Yeast, yeast.response.rect);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Inoculation")
+                .selected_text(self.inoculation.label())
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for inoculation in Inoculation::ALL {
+                        ui.selectable_value(
+                            &mut self.inoculation,
+                            inoculation,
+                            inoculation.label(),
+                        );
+                    }
+                });
+            self.glossary.link(ui, "mlf");
+        });
+
+        let unit = self.temperature_unit();
+        let (low, high) = profile.map_or((10.0, 30.0), |profile| profile.temperature);
+        ui.label(format!(
+            "Temperature ({0}) (Usually {1:.1}{0} to {2:.1}{0}):",
+            unit.symbol(),
+            unit.from_celsius(low),
+            unit.from_celsius(high)
+        ));
+        let temperature = ui.text_edit_singleline(&mut self.temperature);
+        self.tour.mark(Target::Temperature, temperature.rect);
+        egui::CollapsingHeader::new("Interventions (optional)").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Nutrient is added as mg/L of nitrogen the yeast can use.");
+                self.glossary.link(ui, "yan");
+            });
+            self.show_interventions(ui);
+        });
+
+        ui.label("Seed (optional, for repeatable results):");
+        ui.text_edit_singleline(&mut self.seed);
+        self.settings.profile_picker(ui, &mut self.model_profile);
+
+        let simulate = ui.button(format!("Simulate {} Fermentation", self.beverage.label()));
+        self.tour.mark(Target::Simulate, simulate.rect);
+        if simulate.clicked() {
+            self.simulate();
+        }
+        egui::CollapsingHeader::new("Best plans for this fruit").show(ui, |ui| {
+            let plan = self.input();
+            if let Some(best) = self.optimizer.show(ui, &plan, &self.wine_data) {
+                self.set_input(&best);
+                self.simulate();
+            }
+        });
+
+        ui.separator();
+        if self.results_popped {
+            ui.horizontal(|ui| {
+                ui.label("Results are in their own window.");
+                if ui.small_button("Bring Back").clicked() {
+                    self.results_popped = false;
+                }
+            });
+        } else {
+            let calibration = self.last_calibration;
+            with_calibration(calibration, || self.show_results(ui));
+        }
+    }
+
+    /// The report on the last simulation, in the main window or popped out.
+    fn show_results(&mut self, ui: &mut egui::Ui) {
+        let popped = self.results_popped;
+        ui.horizontal(|ui| {
+            ui.label("Results:");
+            if !popped {
+                popout::button(ui, &mut self.results_popped);
+            }
+            if let Some(result) = &self.last_result
+                && let Ok(color) = egui::Color32::from_hex(&result.color_hex)
+            {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(40.0, 16.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 4.0, color);
+                response.on_hover_text(format!(
+                    "{} (intensity {:.1})",
+                    result.color, result.color_intensity
+                ));
+            }
+        });
+        let results = ui.text_edit_multiline(&mut self.result_text);
+        if let Some(result) = &self.last_result {
+            let settings = &self.settings.settings;
+            if settings.sugar_convention != SugarConvention::default()
+                || settings.strength_convention != StrengthConvention::default()
+            {
+                ui.label(format!(
+                    "Residual sugar {}, strength {}.",
+                    settings
+                        .sugar_convention
+                        .alongside(result.residual_sugar, result.actual_abv),
+                    settings.strength_convention.alongside(result.actual_abv)
+                ));
+            }
+            self.model_changes.note(ui, result.model_version);
+            let input = self.input();
+            egui::CollapsingHeader::new("Chemistry").show(ui, |ui| {
+                chemistry::show(ui, &input, result);
+            });
+            let stability = egui::CollapsingHeader::new("Stability report").show(ui, |ui| {
+                self.stability.show(ui, &input, result);
+            });
+            let compare = egui::CollapsingHeader::new("Compare with a classic").show(ui, |ui| {
+                if self.chart_popped {
+                    ui.horizontal(|ui| {
+                        ui.label("The chart is in its own window.");
+                        if ui.small_button("Bring Back").clicked() {
+                            self.chart_popped = false;
+                        }
+                    });
+                } else {
+                    popout::button(ui, &mut self.chart_popped);
+                    self.benchmark.show(ui, result);
+                }
+            });
+            egui::CollapsingHeader::new("Compare with real wines").show(ui, |ui| {
+                self.neighbors.show(ui, &input, result);
+            });
+            // The tour only points into the main window.
+            if !popped {
+                self.tour.mark(
+                    Target::Compare,
+                    stability
+                        .header_response
+                        .rect
+                        .union(compare.header_response.rect),
+                );
+            }
+        }
+        if !popped {
+            self.tour.mark(Target::Results, results.rect);
+        }
+    }
+
+    fn show_log_window(&mut self, ctx: &egui::Context) {
+        let log_buffer = &self.log_buffer;
+        egui::Window::new("Log")
+            .open(&mut self.show_log)
+            .default_size([600.0, 300.0])
+            .show(ctx, |ui| {
+                if ui.button("Clear").clicked() {
+                    log_buffer.clear();
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for line in log_buffer.lines() {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+    }
+}
+
+impl eframe::App for WineFermentationApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_pending_dataset();
+        snapshot::handle_screenshots(ctx);
+        self.settings.apply(ctx);
+        self.dropped.collect(ctx);
+        #[cfg(feature = "sqlite")]
+        if let Some(store) = &self.store {
+            self.reminders.poll(ctx, store);
+            #[cfg(feature = "mqtt")]
+            self.home_assistant.poll(ctx, store);
+            #[cfg(not(target_arch = "wasm32"))]
+            self.cellar.poll_companion(ctx, store);
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Wine Fermentation Simulator");
+                ui.toggle_value(&mut self.show_log, "Log");
+                ui.toggle_value(&mut self.calculators.open, "Calculators");
+                ui.toggle_value(&mut self.stepper.open, "Step through");
+                ui.toggle_value(&mut self.challenges.open, "Challenges");
+                ui.toggle_value(&mut self.career.open, "Career");
+                ui.toggle_value(&mut self.quiz.open, "Quiz");
+                ui.toggle_value(&mut self.glossary.open, "Glossary");
+                ui.toggle_value(&mut self.share.open, "Share");
+                ui.toggle_value(&mut self.settings.open, "Settings");
+                ui.menu_button("Help", |ui| {
+                    if ui.button("Tour").clicked() {
+                        self.tour.start();
+                        ui.close_menu();
+                    }
+                    if ui.button("What Changed in the Model").clicked() {
+                        self.model_changes.open = true;
+                        ui.close_menu();
+                    }
+                });
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let file = ui.menu_button("File", |ui| self.show_file_menu(ui));
+                    self.tour.mark(Target::Save, file.response.rect);
+                }
+                #[cfg(feature = "sqlite")]
+                if self.store.is_some() {
+                    ui.toggle_value(&mut self.show_history, "History");
+                    let profile = self.profile.label();
+                    ui.toggle_value(&mut self.profile.open, profile);
+                    #[cfg(feature = "mqtt")]
+                    ui.toggle_value(&mut self.home_assistant.open, "Home Assistant");
+                    ui.separator();
+                    ui.selectable_value(&mut self.tab, Tab::Simulator, "Simulator");
+                    let due = self.reminders.due();
+                    let cellar = ui.selectable_value(
+                        &mut self.tab,
+                        Tab::Cellar,
+                        match due.len() {
+                            0 => "Cellar".to_owned(),
+                            count => format!("Cellar ({} due)", count),
+                        },
+                    );
+                    if !due.is_empty() {
+                        cellar.on_hover_text(
+                            due.iter()
+                                .map(|due| {
+                                    format!(
+                                        "{}: {} ({})",
+                                        due.batch_name, due.task.title, due.task.due_on
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                        );
+                    }
+                }
+            });
+
+            #[cfg(feature = "sqlite")]
+            if self.tab == Tab::Cellar
+                && let Some(store) = &self.store
+            {
+                let plan = self.input();
+                self.cellar.show(ui, store, &plan, &self.wine_data);
+                if self.cellar.take_tasks_changed() {
+                    self.reminders.refresh();
+                }
+                #[cfg(feature = "webhook")]
+                if let Some(upload) = self.cellar.take_upload() {
+                    self.send_upload(upload);
+                }
+                return;
+            }
+
+            self.show_simulator(ui);
+        });
+
+        self.show_log_window(ctx);
+        let mut results_popped = self.results_popped;
+        let calibration = self.last_calibration;
+        popout::show(ctx, &mut results_popped, "Results", [520.0, 640.0], |ui| {
+            with_calibration(calibration, || self.show_results(ui))
+        });
+        self.results_popped = results_popped;
+        if let Some(result) = &self.last_result {
+            popout::show(
+                ctx,
+                &mut self.chart_popped,
+                "Compare with a classic",
+                [420.0, 420.0],
+                |ui| self.benchmark.show(ui, result),
+            );
+        }
+        #[cfg(feature = "sqlite")]
+        if let Some(store) = &self.store {
+            self.cellar.show_popped_dashboard(ctx, store);
+        }
+        self.calculators.show(
+            ctx,
+            &mut self.sugar_content,
+            &self.grape_type,
+            self.last_result
+                .as_ref()
+                .map(|result| result.residual_sugar),
+        );
+        if self.stepper.open {
+            let plan = self.input();
+            if let Some(input) = self.stepper.show(ctx, &plan) {
+                self.set_input(&input);
+            }
+        }
+        if self.challenges.open {
+            let input = self.input();
+            let made = self.last_result.as_ref().map(|result| (&input, result));
+            #[cfg_attr(not(feature = "sqlite"), allow(unused_variables))]
+            let scored = self.challenges.show(ctx, made);
+            #[cfg(feature = "sqlite")]
+            if let Some((challenge, score)) = scored
+                && let Some(store) = &self.store
+                && let Err(err) = store.record_challenge_score(challenge, score)
+            {
+                tracing::error!("Could not save the challenge score: {}", err);
+            }
+        }
+        if self.career.open {
+            let plan = self.input();
+            if let Some(input) = self.career.show(ctx, &plan, &self.wine_data) {
+                self.set_input(&input);
+            }
+        }
+        if self.quiz.open {
+            self.quiz.show(ctx, &self.wine_data);
+        }
+        if self.glossary.open {
+            self.glossary.show(ctx);
+        }
+        if self.model_changes.show_offer(ctx) {
+            self.simulate();
+        }
+        if self.model_changes.open {
+            self.model_changes.show(ctx);
+        }
+        if self.share.open {
+            let plan = self.input();
+            if let Some(input) = self.share.show(ctx, &plan) {
+                self.set_input(&input);
+                self.simulate();
+            }
+        }
+        #[cfg(feature = "sqlite")]
+        let batch = self.cellar.selected_name().map(str::to_owned);
+        #[cfg(not(feature = "sqlite"))]
+        let batch: Option<String> = None;
+        if let Some((name, dropped)) = self.dropped.show(ctx, batch.as_deref()) {
+            self.open_dropped(&name, dropped);
+        }
+        if self.settings.open {
+            match self.settings.show(ctx) {
+                Some(SettingsEvent::TemperatureUnit(previous)) => {
+                    if let Ok(temperature) = self.temperature.trim().parse() {
+                        self.temperature = self.temperature_text(previous.to_celsius(temperature));
+                    }
+                }
+                Some(SettingsEvent::UseCurrentInputs) => {
+                    let input = self.input();
+                    self.settings.set_defaults(input);
+                }
+                #[cfg(feature = "sqlite")]
+                Some(SettingsEvent::Calibrate) => self.calibrate(),
+                Some(SettingsEvent::Profile(name)) => self.model_profile = name,
+                None => {}
+            }
+        }
+        if self.tour.open {
+            match self.tour.show(ctx) {
+                Some(TourEvent::RunSample) => {
+                    self.set_input(&SimulationInput {
+                        seed: Some(1),
+                        ..Beverage::Wine.defaults()
+                    });
+                    self.simulate();
+                }
+                #[cfg(feature = "sqlite")]
+                Some(TourEvent::Dismissed) => {
+                    if let Some(store) = &self.store
+                        && let Err(err) = store.mark_tour_seen()
+                    {
+                        tracing::error!("Could not save that the tour was seen: {}", err);
+                    }
+                }
+                _ => {}
+            }
+        }
+        #[cfg(feature = "sqlite")]
+        self.show_history_window(ctx);
+        #[cfg(feature = "sqlite")]
+        if self.profile.open {
+            self.profile.show(ctx);
+        }
+        #[cfg(all(feature = "sqlite", feature = "mqtt"))]
+        if self.home_assistant.open {
+            self.home_assistant.show(ctx);
+        }
+    }
+}
+
+fn describe_container(container: &Container) -> String {
+    format!(
+        "{:.0} L of {}, letting in about {:.0} mg/L of oxygen a year.",
+        container.size,
+        container.material.label(),
+        container.oxygen_transfer
+    )
+}
+
+pub fn run(app: WineFermentationApp) -> eframe::Result<()> {
+    let native_options = eframe::NativeOptions::default();
+
+    eframe::run_native(
+        "Wine Fermentation Simulator",
+        native_options,
+        Box::new(|_creation_context| Ok(Box::new(app))),
+    )
+}
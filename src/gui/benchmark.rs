@@ -0,0 +1,86 @@
+use std::f64::consts::TAU;
+
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoint, PlotPoints, Text};
+use wine_maker::benchmark::{Axis, Benchmark, Profile, rank};
+use wine_maker::engine::SimulationResult;
+
+use super::snapshot::chart_buttons;
+
+/// Compares the last simulated wine with a famous wine on a radar chart.
+#[derive(Default)]
+pub struct BenchmarkPanel {
+    /// The benchmark picked, or the most alike if none is.
+    picked: Option<&'static Benchmark>,
+}
+
+impl BenchmarkPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, result: &SimulationResult) {
+        let ranked = rank(result);
+        let (benchmark, similarity) = match self.picked {
+            Some(picked) => *ranked
+                .iter()
+                .find(|(benchmark, _)| *benchmark == picked)
+                .expect("every benchmark is ranked"),
+            None => ranked[0],
+        };
+        egui::ComboBox::from_label("Benchmark")
+            .selected_text(benchmark.name)
+            .show_ui(ui, |ui| {
+                for (candidate, similarity) in &ranked {
+                    let label = format!("{} ({:.0})", candidate.name, similarity);
+                    if ui
+                        .selectable_label(*candidate == benchmark, label)
+                        .clicked()
+                    {
+                        self.picked = Some(*candidate);
+                    }
+                }
+            });
+        ui.label(format!("{} ({})", benchmark.note, benchmark.grape));
+        ui.strong(format!("{:.0}% alike", similarity));
+
+        let wine = Profile::of(result);
+        let chart = Plot::new("benchmark_radar")
+            .legend(Legend::default())
+            .height(240.0)
+            .data_aspect(1.0)
+            .show_axes(false)
+            .show_grid(false)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                for (index, axis) in Axis::ALL.into_iter().enumerate() {
+                    let [x, y] = spoke(index, 1.0);
+                    plot_ui.line(
+                        Line::new(PlotPoints::new(vec![[0.0, 0.0], [x, y]]))
+                            .color(egui::Color32::GRAY),
+                    );
+                    let [x, y] = spoke(index, 1.2);
+                    plot_ui.text(Text::new(PlotPoint::new(x, y), axis.label()));
+                }
+                plot_ui.line(Line::new(outline(&benchmark.profile)).name(benchmark.name));
+                plot_ui.line(Line::new(outline(&wine)).name("Your wine"));
+            })
+            .response;
+        chart_buttons(ui, chart.rect, "wine-radar.png");
+    }
+}
+
+/// The point `length` out along the spoke for the `index`th axis, with the
+/// first axis straight up.
+fn spoke(index: usize, length: f64) -> [f64; 2] {
+    let angle = TAU / 4.0 - TAU * index as f64 / Axis::ALL.len() as f64;
+    [length * angle.cos(), length * angle.sin()]
+}
+
+/// A profile as a closed outline on the radar chart.
+fn outline(profile: &Profile) -> PlotPoints {
+    (0..=Axis::ALL.len())
+        .map(|index| {
+            let index = index % Axis::ALL.len();
+            spoke(index, profile.values[index])
+        })
+        .collect()
+}
@@ -0,0 +1,504 @@
+use eframe::egui;
+use wine_maker::calculators::{
+    BottleFormat, BottlingPlan, DEFAULT_PRESS_EFFICIENCY, DilutionTarget, GrapeColor,
+    HYDROMETER_CALIBRATION_TEMPERATURE, OVER_OAKED_BARREL_MONTHS, OakAdjunct, bentonite_fining,
+    bottling_plan, co2_volumes, corrected_gravity, dilution, grape_protein, juice_yield,
+    oak_addition, priming, sulfite_addition,
+};
+
+pub struct CalculatorsWindow {
+    pub open: bool,
+    gravity: String,
+    sample_temperature: String,
+    calibration_temperature: String,
+    bottling_volume: String,
+    bottle_format: BottleFormat,
+    rackings: u32,
+    juice_yield: YieldCalculator,
+    must_volume: String,
+    must_acidity: String,
+    dilute_to_acidity: bool,
+    dilution_target: String,
+    carbonation: String,
+    carbonation_in_bar: bool,
+    wine_temperature: String,
+    residual_sugar: String,
+    priming_volume: String,
+    so2_volume: String,
+    ph: String,
+    free_so2: String,
+    molecular_so2: f64,
+    oak_volume: String,
+    oak_adjunct: OakAdjunct,
+    oak_dosage: f64,
+    oak_days: u32,
+    fining_volume: String,
+    bentonite: f64,
+    storage_temperature: String,
+}
+
+/// Grape weight to juice volume, shown wherever a volume is needed.
+pub struct YieldCalculator {
+    grapes: String,
+    color: GrapeColor,
+    press_efficiency: f64,
+}
+
+impl Default for YieldCalculator {
+    fn default() -> Self {
+        Self {
+            grapes: String::new(),
+            color: GrapeColor::default(),
+            press_efficiency: DEFAULT_PRESS_EFFICIENCY * 100.0,
+        }
+    }
+}
+
+impl YieldCalculator {
+    /// Shows the inputs and returns the expected liters of juice, if the
+    /// weight is filled in.
+    pub fn show(&mut self, ui: &mut egui::Ui, id: &str) -> Option<f64> {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            ui.label("Grapes (kg):");
+            ui.text_edit_singleline(&mut self.grapes);
+            ui.end_row();
+            ui.label("Color:");
+            ui.horizontal(|ui| {
+                for color in GrapeColor::ALL {
+                    ui.selectable_value(&mut self.color, color, color.label());
+                }
+            });
+            ui.end_row();
+            ui.label("Press efficiency:");
+            ui.add(egui::Slider::new(&mut self.press_efficiency, 50.0..=100.0).suffix("%"));
+            ui.end_row();
+        });
+
+        let liters = juice_yield(
+            parse(&self.grapes)?,
+            self.color,
+            self.press_efficiency / 100.0,
+        );
+        ui.strong(format!("Expected juice: {:.1} L", liters));
+        Some(liters)
+    }
+}
+
+impl Default for CalculatorsWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            gravity: String::new(),
+            sample_temperature: String::new(),
+            calibration_temperature: HYDROMETER_CALIBRATION_TEMPERATURE.to_string(),
+            bottling_volume: String::new(),
+            bottle_format: BottleFormat::Standard,
+            rackings: 2,
+            juice_yield: YieldCalculator::default(),
+            must_volume: String::new(),
+            must_acidity: String::new(),
+            dilute_to_acidity: false,
+            dilution_target: String::new(),
+            carbonation: "2.5".to_owned(),
+            carbonation_in_bar: false,
+            wine_temperature: "20".to_owned(),
+            residual_sugar: "0".to_owned(),
+            priming_volume: String::new(),
+            so2_volume: String::new(),
+            ph: String::new(),
+            free_so2: "0".to_owned(),
+            molecular_so2: GrapeColor::Red.molecular_so2_target(),
+            oak_volume: String::new(),
+            oak_adjunct: OakAdjunct::default(),
+            oak_dosage: OakAdjunct::default().typical_dosage(),
+            oak_days: OakAdjunct::default().extraction_days(),
+            fining_volume: String::new(),
+            bentonite: 0.0,
+            storage_temperature: "15".to_owned(),
+        }
+    }
+}
+
+fn parse(text: &str) -> Option<f64> {
+    text.trim().parse().ok()
+}
+
+/// Picks a bottle format, for use in a grid or row.
+pub fn bottle_format_combo(ui: &mut egui::Ui, id: &str, format: &mut BottleFormat) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(format.label())
+        .show_ui(ui, |ui| {
+            for option in BottleFormat::ALL {
+                ui.selectable_value(format, option, option.label());
+            }
+        });
+}
+
+pub fn describe_bottling(plan: &BottlingPlan, format: BottleFormat) -> String {
+    format!(
+        "{:.1} L to bottle: {} {} bottles and {} corks, with {:.2} L left over.",
+        plan.wine_volume,
+        plan.bottles,
+        format.label(),
+        plan.closures,
+        plan.leftover
+    )
+}
+
+impl CalculatorsWindow {
+    /// `plan_sugar` is the simulator's sugar input, which the dilution
+    /// calculator reads and can update. `simulated_residual_sugar` comes from
+    /// the last simulation, for priming a pét-nat. `plan_grape` sets the
+    /// protein the bentonite calculator starts from.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        plan_sugar: &mut String,
+        plan_grape: &str,
+        simulated_residual_sugar: Option<f64>,
+    ) {
+        let mut open = self.open;
+        egui::Window::new("Calculators")
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                egui::CollapsingHeader::new("Hydrometer temperature correction")
+                    .default_open(true)
+                    .show(ui, |ui| self.show_hydrometer(ui));
+                egui::CollapsingHeader::new("Juice yield").show(ui, |ui| {
+                    self.juice_yield.show(ui, "juice_yield");
+                });
+                egui::CollapsingHeader::new("Dilution").show(ui, |ui| {
+                    self.show_dilution(ui, plan_sugar);
+                });
+                egui::CollapsingHeader::new("SO2 addition").show(ui, |ui| self.show_so2(ui));
+                egui::CollapsingHeader::new("Oak alternatives").show(ui, |ui| self.show_oak(ui));
+                egui::CollapsingHeader::new("Bentonite fining").show(ui, |ui| {
+                    self.show_fining(ui, plan_grape);
+                });
+                egui::CollapsingHeader::new("Priming sugar").show(ui, |ui| {
+                    self.show_priming(ui, simulated_residual_sugar);
+                });
+                egui::CollapsingHeader::new("Bottling").show(ui, |ui| self.show_bottling(ui));
+            });
+        self.open = open;
+    }
+
+    fn show_hydrometer(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("hydrometer").num_columns(2).show(ui, |ui| {
+            ui.label("Reading (SG):");
+            ui.text_edit_singleline(&mut self.gravity);
+            ui.end_row();
+            ui.label("Sample temperature (°C):");
+            ui.text_edit_singleline(&mut self.sample_temperature);
+            ui.end_row();
+            ui.label("Calibrated at (°C):");
+            ui.text_edit_singleline(&mut self.calibration_temperature);
+            ui.end_row();
+        });
+
+        if let (Some(gravity), Some(sample_temperature), Some(calibration_temperature)) = (
+            parse(&self.gravity),
+            parse(&self.sample_temperature),
+            parse(&self.calibration_temperature),
+        ) {
+            let corrected = corrected_gravity(gravity, sample_temperature, calibration_temperature);
+            ui.strong(format!("Corrected SG: {:.3}", corrected));
+        }
+    }
+
+    fn show_bottling(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("bottling").num_columns(2).show(ui, |ui| {
+            ui.label("New wine (L):");
+            ui.text_edit_singleline(&mut self.bottling_volume);
+            ui.end_row();
+            ui.label("Bottle:");
+            bottle_format_combo(ui, "bottling_format", &mut self.bottle_format);
+            ui.end_row();
+            ui.label("Rackings after the first:");
+            ui.add(egui::DragValue::new(&mut self.rackings).range(0..=10));
+            ui.end_row();
+        });
+
+        if let Some(volume) = parse(&self.bottling_volume) {
+            let plan = bottling_plan(volume, self.bottle_format, self.rackings);
+            ui.strong(describe_bottling(&plan, self.bottle_format));
+        }
+    }
+
+    fn show_dilution(&mut self, ui: &mut egui::Ui, plan_sugar: &mut String) {
+        egui::Grid::new("dilution").num_columns(2).show(ui, |ui| {
+            ui.label("Must volume (L):");
+            ui.text_edit_singleline(&mut self.must_volume);
+            ui.end_row();
+            ui.label("Sugar (g/L):")
+                .on_hover_text("The simulator's sugar input.");
+            ui.text_edit_singleline(plan_sugar);
+            ui.end_row();
+            ui.label("TA (g/L):");
+            ui.text_edit_singleline(&mut self.must_acidity);
+            ui.end_row();
+            ui.label("Dilute to:");
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.dilute_to_acidity, false, "Sugar");
+                ui.selectable_value(&mut self.dilute_to_acidity, true, "TA");
+                ui.add(egui::TextEdit::singleline(&mut self.dilution_target).desired_width(50.0));
+                ui.label("g/L");
+            });
+            ui.end_row();
+        });
+
+        let (Some(volume), Some(sugar_content), Some(target)) = (
+            parse(&self.must_volume),
+            parse(plan_sugar),
+            parse(&self.dilution_target),
+        ) else {
+            return;
+        };
+        let target = if self.dilute_to_acidity {
+            DilutionTarget::Acidity(target)
+        } else {
+            DilutionTarget::Sugar(target)
+        };
+        let Some(diluted) = dilution(volume, sugar_content, parse(&self.must_acidity), target)
+        else {
+            ui.label("The must is already at or below the target; enter TA to dilute by acidity.");
+            return;
+        };
+
+        ui.strong(format!(
+            "Add {:.1} L of water for {:.1} L at {:.0} g/L sugar{}.",
+            diluted.water,
+            diluted.volume,
+            diluted.sugar_content,
+            diluted
+                .acidity
+                .map(|acidity| format!(" and {:.1} g/L TA", acidity))
+                .unwrap_or_default()
+        ));
+        for warning in &diluted.warnings {
+            ui.colored_label(ui.visuals().warn_fg_color, warning.to_string());
+        }
+        if ui
+            .button("Apply to Simulator")
+            .on_hover_text("Sets the simulator's sugar to the diluted must's.")
+            .clicked()
+        {
+            *plan_sugar = format!("{:.1}", diluted.sugar_content);
+            tracing::info!(water = diluted.water, "dilution applied to the simulator");
+        }
+    }
+
+    fn show_priming(&mut self, ui: &mut egui::Ui, simulated_residual_sugar: Option<f64>) {
+        egui::Grid::new("priming").num_columns(2).show(ui, |ui| {
+            ui.label("Target carbonation:");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.carbonation).desired_width(50.0));
+                ui.selectable_value(&mut self.carbonation_in_bar, false, "volumes");
+                ui.selectable_value(&mut self.carbonation_in_bar, true, "bar");
+            });
+            ui.end_row();
+            ui.label("Warmest since fermenting (°C):")
+                .on_hover_text("Sets how much CO2 the wine still holds.");
+            ui.text_edit_singleline(&mut self.wine_temperature);
+            ui.end_row();
+            ui.label("Residual sugar (g/L):")
+                .on_hover_text("Fermentable sugar left in the wine, e.g. for a pét-nat.");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.residual_sugar).desired_width(50.0));
+                if let Some(residual_sugar) = simulated_residual_sugar
+                    && ui.button("From Simulation").clicked()
+                {
+                    self.residual_sugar = format!("{:.1}", residual_sugar);
+                }
+            });
+            ui.end_row();
+            ui.label("Wine volume (L):");
+            ui.text_edit_singleline(&mut self.priming_volume);
+            ui.end_row();
+        });
+
+        let (Some(carbonation), Some(temperature)) =
+            (parse(&self.carbonation), parse(&self.wine_temperature))
+        else {
+            return;
+        };
+        let target = if self.carbonation_in_bar {
+            co2_volumes(carbonation)
+        } else {
+            carbonation
+        };
+        let primed = priming(
+            target,
+            temperature,
+            parse(&self.residual_sugar).unwrap_or_default(),
+        );
+        let total = parse(&self.priming_volume)
+            .map(|volume| format!(" ({:.0} g in all)", primed.sugar * volume))
+            .unwrap_or_default();
+        ui.strong(format!(
+            "Add {:.1} g/L sugar{} for {:.1} volumes of CO2, about {:.1} bar.",
+            primed.sugar, total, primed.volumes, primed.pressure
+        ));
+        for warning in &primed.warnings {
+            ui.colored_label(ui.visuals().warn_fg_color, warning.to_string());
+        }
+    }
+
+    fn show_so2(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("so2").num_columns(2).show(ui, |ui| {
+            ui.label("Wine volume (L):");
+            ui.text_edit_singleline(&mut self.so2_volume);
+            ui.end_row();
+            ui.label("pH:");
+            ui.text_edit_singleline(&mut self.ph);
+            ui.end_row();
+            ui.label("Free SO2 now (mg/L):");
+            ui.text_edit_singleline(&mut self.free_so2);
+            ui.end_row();
+            ui.label("Molecular SO2 (mg/L):");
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut self.molecular_so2)
+                        .range(0.1..=2.0)
+                        .speed(0.05),
+                );
+                for color in GrapeColor::ALL {
+                    if ui
+                        .small_button(color.label())
+                        .on_hover_text("Use the usual target for this wine.")
+                        .clicked()
+                    {
+                        self.molecular_so2 = color.molecular_so2_target();
+                    }
+                }
+            });
+            ui.end_row();
+        });
+
+        let (Some(volume), Some(ph), Some(free_so2)) = (
+            parse(&self.so2_volume),
+            parse(&self.ph),
+            parse(&self.free_so2),
+        ) else {
+            return;
+        };
+        let addition = sulfite_addition(volume, ph, free_so2, self.molecular_so2);
+        ui.strong(format!(
+            "Add {:.2} g potassium metabisulfite for {:.0} mg/L free SO2.",
+            addition.metabisulfite, addition.free_so2_target
+        ));
+        if !addition.is_practical() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "At this pH the sulfite needed would be smelled and tasted; lower the pH with acid first.",
+            );
+        }
+    }
+
+    fn show_oak(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("oak").num_columns(2).show(ui, |ui| {
+            ui.label("Wine volume (L):");
+            ui.text_edit_singleline(&mut self.oak_volume);
+            ui.end_row();
+            ui.label("Oak:");
+            ui.horizontal(|ui| {
+                for adjunct in OakAdjunct::ALL {
+                    if ui
+                        .selectable_value(&mut self.oak_adjunct, adjunct, adjunct.label())
+                        .clicked()
+                    {
+                        self.oak_dosage = adjunct.typical_dosage();
+                        self.oak_days = adjunct.extraction_days();
+                    }
+                }
+            });
+            ui.end_row();
+            ui.label("Dosage:");
+            ui.add(
+                egui::DragValue::new(&mut self.oak_dosage)
+                    .range(0.0..=20.0)
+                    .speed(0.1)
+                    .suffix(" g/L"),
+            );
+            ui.end_row();
+            ui.label("Contact time:");
+            ui.add(
+                egui::DragValue::new(&mut self.oak_days)
+                    .range(1..=365)
+                    .suffix(" days"),
+            );
+            ui.end_row();
+        });
+
+        let Some(volume) = parse(&self.oak_volume) else {
+            return;
+        };
+        let addition = oak_addition(volume, self.oak_adjunct, self.oak_dosage, self.oak_days);
+        ui.strong(format!(
+            "Add {:.0} g of oak {}.",
+            addition.grams,
+            self.oak_adjunct.label().to_lowercase()
+        ));
+        ui.label(format!(
+            "After {} days about {:.0}% is extracted, like {:.0} months in a new barrel.",
+            self.oak_days,
+            addition.extracted * 100.0,
+            addition.barrel_months
+        ));
+        if addition.is_over_oaked() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "More oak than {:.0} months in a new barrel will bury the fruit; use less or taste and pull it sooner.",
+                    OVER_OAKED_BARREL_MONTHS
+                ),
+            );
+        }
+    }
+
+    fn show_fining(&mut self, ui: &mut egui::Ui, grape: &str) {
+        egui::Grid::new("fining").num_columns(2).show(ui, |ui| {
+            ui.label("Wine volume (L):");
+            ui.text_edit_singleline(&mut self.fining_volume);
+            ui.end_row();
+            ui.label("Bentonite:");
+            ui.add(
+                egui::DragValue::new(&mut self.bentonite)
+                    .range(0.0..=200.0)
+                    .suffix(" g/hL"),
+            );
+            ui.end_row();
+            ui.label("Storage temperature (°C):");
+            ui.text_edit_singleline(&mut self.storage_temperature);
+            ui.end_row();
+        });
+        let grape = if grape.trim().is_empty() {
+            "white wine"
+        } else {
+            grape
+        };
+        ui.label(format!(
+            "Young {} carries about {:.0} mg/L of heat-unstable protein.",
+            grape,
+            grape_protein(grape)
+        ));
+
+        let (Some(volume), Some(storage_temperature)) =
+            (parse(&self.fining_volume), parse(&self.storage_temperature))
+        else {
+            return;
+        };
+        let fining = bentonite_fining(volume, grape, self.bentonite, storage_temperature);
+        ui.strong(format!(
+            "Add {:.0} g bentonite, leaving {:.0} mg/L protein.",
+            fining.grams, fining.residual_protein
+        ));
+        if fining.is_heat_stable() {
+            ui.label("The wine should stay clear in bottle.");
+        }
+        for warning in &fining.warnings {
+            ui.colored_label(ui.visuals().warn_fg_color, warning.to_string());
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use eframe::egui;
+use wine_maker::career::{BATCH_COST, Career, MAX_BATCHES};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::SimulationInput;
+use wine_maker::vineyard::REGIONS;
+
+/// A winery run over many vintages, making batches from the simulator's plan.
+pub struct CareerWindow {
+    pub open: bool,
+    career: Option<Career>,
+    /// The region a new career starts in.
+    region: &'static str,
+    batch_name: String,
+    error: Option<String>,
+}
+
+impl Default for CareerWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            career: None,
+            region: REGIONS[0].name,
+            batch_name: "Estate".to_owned(),
+            error: None,
+        }
+    }
+}
+
+impl CareerWindow {
+    /// The career in progress, to save with the session.
+    pub fn career(&self) -> Option<&Career> {
+        self.career.as_ref()
+    }
+
+    pub fn set_career(&mut self, career: Option<Career>) {
+        self.career = career;
+        self.error = None;
+    }
+
+    /// Shows the window. `plan` is the simulator's plan, made into a batch
+    /// with this year's fruit. Returns this year's fruit if it is picked to
+    /// plan with.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        plan: &SimulationInput,
+        wine_data: &Dataset,
+    ) -> Option<SimulationInput> {
+        let mut open = self.open;
+        let mut picked = None;
+        egui::Window::new("Career")
+            .open(&mut open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_salt("career_region")
+                        .selected_text(self.region)
+                        .show_ui(ui, |ui| {
+                            for region in &REGIONS {
+                                ui.selectable_value(&mut self.region, region.name, region.name);
+                            }
+                        });
+                    if ui.button("New career").clicked() {
+                        self.career = Career::new(self.region, rand::random()).ok();
+                        self.error = None;
+                    }
+                });
+                let Some(career) = &mut self.career else {
+                    ui.label("Start a winery in a region and make wine year after year.");
+                    return;
+                };
+                ui.separator();
+                ui.strong(format!("{}, vintage {}", career.region, career.year));
+                ui.label(format!(
+                    "Reputation {:.0}/100, cash {:.0}",
+                    career.reputation, career.cash
+                ));
+                match career.season() {
+                    Ok(season) => {
+                        if season.events.is_empty() {
+                            ui.label("An ordinary year.");
+                        }
+                        for event in &season.events {
+                            ui.label(format!("{}: {}", event.label(), event.description()));
+                        }
+                        let harvest = season.vintage.harvest();
+                        ui.label(format!(
+                            "The grapes come in at about {:.0} g/L sugar and {:.1} g/L acid.",
+                            harvest.expected_sugar(),
+                            harvest.expected_acidity()
+                        ));
+                    }
+                    Err(err) => {
+                        ui.colored_label(egui::Color32::RED, err.to_string());
+                    }
+                }
+                if ui.button("Plan with this year's fruit").clicked() {
+                    picked = career.plan().ok();
+                }
+
+                ui.separator();
+                let mut remove = None;
+                for (index, batch) in career.batches.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "{}: {} days at {:.0} °C, {}",
+                            batch.name,
+                            batch.input.fermentation_days,
+                            batch.input.temperature,
+                            batch.input.container_type
+                        ));
+                        if ui.small_button("Remove").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    career.remove_batch(index);
+                }
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.batch_name);
+                    let add = ui
+                        .add_enabled(
+                            career.batches.len() < MAX_BATCHES,
+                            egui::Button::new("Add batch"),
+                        )
+                        .on_hover_text(format!(
+                            "Makes the simulator's plan for {:.0}.",
+                            BATCH_COST
+                        ));
+                    if add.clicked() {
+                        self.error = career
+                            .add_batch(&self.batch_name, plan)
+                            .err()
+                            .map(|err| err.to_string());
+                    }
+                });
+                if ui.button("Finish the vintage").clicked() {
+                    self.error = career
+                        .finish_vintage(wine_data)
+                        .err()
+                        .map(|err| err.to_string());
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                if career.is_bankrupt() {
+                    ui.strong("The winery can't pay for another batch.");
+                }
+
+                if !career.history.is_empty() {
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for report in career.history.iter().rev() {
+                                let events: Vec<&str> =
+                                    report.events.iter().map(|event| event.label()).collect();
+                                ui.strong(format!(
+                                    "Vintage {}{}",
+                                    report.year,
+                                    if events.is_empty() {
+                                        String::new()
+                                    } else {
+                                        format!(" ({})", events.join(", "))
+                                    }
+                                ));
+                                for batch in &report.batches {
+                                    ui.label(format!(
+                                        "{}: scored {:.0}, {} bottles at {:.2}",
+                                        batch.name, batch.score, batch.bottles, batch.bottle_price
+                                    ));
+                                }
+                                ui.label(format!(
+                                    "Reputation {:.0}, cash {:.0}",
+                                    report.reputation, report.cash
+                                ));
+                            }
+                        });
+                }
+            });
+        self.open = open;
+        picked
+    }
+}
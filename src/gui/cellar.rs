@@ -0,0 +1,1144 @@
+use std::collections::HashSet;
+
+#[cfg(not(target_arch = "wasm32"))]
+use chrono::Utc;
+use chrono::{Local, NaiveDate};
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points, VLine};
+use wine_maker::branch::Branch;
+use wine_maker::calculators::{
+    BottleFormat, HYDROMETER_CALIBRATION_TEMPERATURE, bottling_plan, corrected_gravity,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use wine_maker::calendar::schedule_to_ical;
+use wine_maker::cellar::{
+    Batch, Forecast, Measurement, Process, ProgressPoint, Task, VarianceReport,
+};
+use wine_maker::dataset::Dataset;
+use wine_maker::devices::DeviceReading;
+#[cfg(not(target_arch = "wasm32"))]
+use wine_maker::devices::import_readings;
+use wine_maker::engine::{
+    InterventionKind, SimulationInput, fermentation_state, fermentation_state_at_rate,
+    run_simulation, seeded, sugar_from_gravity,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use wine_maker::export::Spreadsheet;
+use wine_maker::inventory::{Shortage, batch_requirements, shortages};
+#[cfg(not(target_arch = "wasm32"))]
+use wine_maker::log_sheet::log_sheet_html;
+use wine_maker::stage::Stage;
+use wine_maker::store::Store;
+use wine_maker::sulfides::sulfide_fault;
+#[cfg(feature = "webhook")]
+use wine_maker::webhook::Upload;
+
+use super::calculators::{YieldCalculator, bottle_format_combo, describe_bottling};
+use super::popout;
+use super::snapshot::chart_buttons;
+
+#[derive(Default)]
+pub struct CellarTab {
+    loaded: bool,
+    batches: Vec<Batch>,
+    selected: Option<i64>,
+    measurements: Vec<Measurement>,
+    progress: Vec<ProgressPoint>,
+    forecast: Option<Forecast>,
+    variance: Option<VarianceReport>,
+    what_if: WhatIf,
+    notes: String,
+    process: Process,
+    schedule: Vec<ScheduleRow>,
+    bottle_format: BottleFormat,
+    costs: super::costs::CostsPanel,
+    dashboard: super::dashboard::Dashboard,
+    /// Whether the dashboard is shown instead of the selected batch.
+    show_dashboard: bool,
+    /// Whether the dashboard is popped out into its own window.
+    dashboard_popped: bool,
+    /// Batches ticked for export, by id.
+    export: HashSet<i64>,
+
+    new_batch_name: String,
+    new_batch_started_on: String,
+    new_batch_volume: String,
+    juice_yield: YieldCalculator,
+    inventory: super::inventory::InventoryPanel,
+
+    measured_on: String,
+    measured_gravity: String,
+    hydrometer_calibration: String,
+    measured_sugar: String,
+    measured_temperature: String,
+    measured_notes: String,
+
+    error: Option<String>,
+    warning: Option<String>,
+    /// Set when tasks were saved, added or removed since the last call.
+    tasks_changed: bool,
+
+    #[cfg(any(feature = "mqtt", feature = "serial", feature = "ble"))]
+    sensors: super::sensors::SensorPanel,
+    #[cfg(not(target_arch = "wasm32"))]
+    companion: super::companion::CompanionPanel,
+    /// A batch log waiting to be sent to the webhook.
+    #[cfg(feature = "webhook")]
+    upload: Option<Upload>,
+}
+
+/// A task as edited in the schedule grid.
+struct ScheduleRow {
+    uid: String,
+    due_on: String,
+    title: String,
+    done: bool,
+}
+
+impl From<Task> for ScheduleRow {
+    fn from(task: Task) -> Self {
+        ScheduleRow {
+            uid: task.uid,
+            due_on: task.due_on.to_string(),
+            title: task.title,
+            done: task.done,
+        }
+    }
+}
+
+/// A "what if I had…" branch of the selected batch's plan.
+#[derive(Default)]
+struct WhatIf {
+    fork_day: u32,
+    /// The temperature held from the fork on, if it changes.
+    temperature: Option<f64>,
+    /// Extra yeast nutrient on the fork day, in mg/L N.
+    nutrient: f64,
+}
+
+impl WhatIf {
+    fn branch(&self, plan: &SimulationInput) -> Option<Branch> {
+        if self.temperature.is_none() && self.nutrient <= 0.0 {
+            return None;
+        }
+        let mut branch = Branch::fork(plan, self.fork_day);
+        if let Some(temperature) = self.temperature {
+            branch.set_temperature(temperature);
+        }
+        if self.nutrient > 0.0 {
+            branch.add(InterventionKind::Nutrient, self.nutrient);
+        }
+        Some(branch)
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, plan: &SimulationInput) {
+        egui::Grid::new("what_if").num_columns(2).show(ui, |ui| {
+            ui.label("Fork on day:");
+            ui.add(egui::DragValue::new(&mut self.fork_day).range(0..=plan.fermentation_days));
+            ui.end_row();
+            let mut change_temperature = self.temperature.is_some();
+            ui.checkbox(&mut change_temperature, "Temperature from then:");
+            if change_temperature != self.temperature.is_some() {
+                self.temperature = change_temperature.then_some(plan.temperature);
+            }
+            if let Some(temperature) = &mut self.temperature {
+                ui.add(
+                    egui::DragValue::new(temperature)
+                        .range(0.0..=45.0)
+                        .speed(0.1)
+                        .suffix(" °C"),
+                );
+            }
+            ui.end_row();
+            ui.label("Extra nutrient:");
+            ui.add(
+                egui::DragValue::new(&mut self.nutrient)
+                    .range(0.0..=300.0)
+                    .suffix(" mg/L N"),
+            );
+            ui.end_row();
+        });
+
+        let Some(branch) = self.branch(plan) else {
+            ui.label("Change the temperature or add nutrient to see the other branch on the plot.");
+            return;
+        };
+        let pressing = plan.fermentation_days;
+        if let (Ok(planned), Ok(branched)) = (
+            fermentation_state(plan, pressing),
+            fermentation_state(&branch.input, pressing),
+        ) {
+            ui.label(format!(
+                "{}. At pressing: {:.1}% ABV and {:.1} g/L sugar, against {:.1}% and {:.1} g/L as planned.",
+                branch.label(),
+                branched.abv,
+                branched.residual_sugar,
+                planned.abv,
+                planned.residual_sugar
+            ));
+        }
+        match (
+            sulfide_fault(plan).is_some(),
+            sulfide_fault(&branch.input).is_some(),
+        ) {
+            (true, false) => {
+                ui.label("The yeast would not have given off hydrogen sulfide.");
+            }
+            (false, true) => {
+                ui.label("The yeast would have given off hydrogen sulfide.");
+            }
+            _ => {}
+        }
+    }
+}
+
+fn describe_shortages(shortages: &[Shortage]) -> String {
+    shortages
+        .iter()
+        .map(|shortage| {
+            format!(
+                "{}: need {} {}, have {}",
+                shortage.needed.name,
+                shortage.needed.quantity,
+                shortage.needed.unit,
+                shortage.on_hand
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn parse_date(text: &str) -> Result<NaiveDate, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("{:?} is not a date like 2025-09-28", text))
+}
+
+impl CellarTab {
+    fn selected_batch(&self) -> Option<&Batch> {
+        let selected = self.selected?;
+        self.batches.iter().find(|batch| batch.id == selected)
+    }
+
+    fn reload(&mut self, store: &Store) {
+        self.loaded = true;
+        match store.batches() {
+            Ok(batches) => self.batches = batches,
+            Err(err) => tracing::error!("Could not read batches: {}", err),
+        }
+        self.inventory.load(store);
+        self.dashboard.load(store);
+        if self.selected_batch().is_none() {
+            self.selected = self.batches.first().map(|batch| batch.id);
+        }
+        self.reload_selected(store);
+    }
+
+    fn reload_selected(&mut self, store: &Store) {
+        self.measurements.clear();
+        self.progress.clear();
+        self.forecast = None;
+        self.variance = None;
+        self.notes.clear();
+        self.schedule.clear();
+        let Some(batch) = self.selected_batch().cloned() else {
+            return;
+        };
+        self.notes = batch.notes.clone();
+        match store.measurements(batch.id) {
+            Ok(measurements) => self.measurements = measurements,
+            Err(err) => tracing::error!("Could not read measurements: {}", err),
+        }
+        match store.tasks(batch.id) {
+            Ok(tasks) => self.schedule = tasks.into_iter().map(ScheduleRow::from).collect(),
+            Err(err) => tracing::error!("Could not read the schedule: {}", err),
+        }
+        self.costs.load(store, &batch);
+        self.progress = batch.progress(&self.measurements).unwrap_or_default();
+        self.forecast = batch.forecast(&self.measurements);
+        self.variance = batch.variance(&self.measurements).ok().flatten();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.companion.refresh();
+    }
+
+    /// Keeps the companion page current, whatever tab is open.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn poll_companion(&mut self, ctx: &egui::Context, store: &Store) {
+        self.companion.poll(ctx, store);
+    }
+
+    fn start_batch(&mut self, store: &Store, plan: &SimulationInput, wine_data: &Dataset) {
+        let name = self.new_batch_name.trim();
+        if name.is_empty() {
+            self.error = Some("Give the batch a name.".to_owned());
+            return;
+        }
+        let started_on = match parse_date(&self.new_batch_started_on) {
+            Ok(date) => date,
+            Err(err) => {
+                self.error = Some(err);
+                return;
+            }
+        };
+        let volume = match self.new_batch_volume.trim() {
+            "" => None,
+            volume => match volume.parse::<f64>() {
+                Ok(volume) => Some(volume),
+                Err(_) => {
+                    self.error = Some(format!("{:?} is not a volume in liters", volume));
+                    return;
+                }
+            },
+        };
+        // Stored with its seed, so the batch's plan keeps the run's draw.
+        let plan = &seeded(plan);
+        let predicted_abv = match run_simulation(plan, wine_data) {
+            Ok(result) => result.actual_abv,
+            Err(err) => {
+                self.error = Some(err.to_string());
+                return;
+            }
+        };
+
+        match store.add_batch(name, started_on, plan, Some(predicted_abv)) {
+            Ok(id) => {
+                tracing::info!(id, name, "batch started");
+                self.tasks_changed = true;
+                self.selected = Some(id);
+                self.new_batch_name.clear();
+                self.error = None;
+                self.warning = None;
+                if let Some(volume) = volume {
+                    let needed = batch_requirements(volume);
+                    let short = shortages(self.inventory.stock(), &needed);
+                    if let Err(err) = store
+                        .set_batch_volume(id, Some(volume))
+                        .and_then(|()| store.take_stock(&needed))
+                    {
+                        self.error = Some(err.to_string());
+                    }
+                    if !short.is_empty() {
+                        self.warning = Some(format!(
+                            "Started without enough stock. {}",
+                            describe_shortages(&short)
+                        ));
+                    }
+                }
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+        self.reload(store);
+    }
+
+    fn log_measurement(&mut self, store: &Store) {
+        let Some(batch) = self.selected_batch() else {
+            return;
+        };
+        let batch_id = batch.id;
+        let initial_sugar = batch.initial_sugar();
+        let measured_on = match parse_date(&self.measured_on) {
+            Ok(date) => date,
+            Err(err) => {
+                self.error = Some(err);
+                return;
+            }
+        };
+        let temperature = self.measured_temperature.trim().parse::<f64>().ok();
+        // Gravity readings are corrected to the hydrometer's calibration temperature.
+        let specific_gravity =
+            self.measured_gravity
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .map(|reading| {
+                    match (
+                        temperature,
+                        self.hydrometer_calibration.trim().parse::<f64>(),
+                    ) {
+                        (Some(temperature), Ok(calibration)) => {
+                            corrected_gravity(reading, temperature, calibration)
+                        }
+                        _ => reading,
+                    }
+                });
+        let sugar_content = match (specific_gravity, self.measured_sugar.trim().parse::<f64>()) {
+            (_, Ok(sugar_content)) => sugar_content,
+            (Some(specific_gravity), Err(_)) => sugar_from_gravity(specific_gravity, initial_sugar),
+            (None, Err(_)) => {
+                self.error = Some("Enter a gravity reading or the sugar in g/L.".to_owned());
+                return;
+            }
+        };
+        let measurement = Measurement {
+            measured_on,
+            sugar_content,
+            specific_gravity,
+            temperature,
+            notes: self.measured_notes.trim().to_owned(),
+        };
+
+        match store.add_measurement(batch_id, &measurement) {
+            Ok(_) => {
+                self.measured_gravity.clear();
+                self.measured_sugar.clear();
+                self.measured_notes.clear();
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+        self.reload_selected(store);
+    }
+
+    fn save_schedule(&mut self, store: &Store, batch_id: i64) {
+        let tasks = self
+            .schedule
+            .iter()
+            .filter(|row| !row.title.trim().is_empty())
+            .map(|row| {
+                Ok(Task {
+                    uid: row.uid.clone(),
+                    due_on: parse_date(&row.due_on)?,
+                    title: row.title.trim().to_owned(),
+                    done: row.done,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>();
+        match tasks.and_then(|tasks| {
+            store
+                .set_tasks(batch_id, &tasks)
+                .map_err(|err| err.to_string())
+        }) {
+            Ok(()) => {
+                self.error = None;
+                self.tasks_changed = true;
+            }
+            Err(err) => {
+                self.error = Some(err);
+                return;
+            }
+        }
+        self.dashboard.load(store);
+        self.reload_selected(store);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_schedule(&mut self, store: &Store, batch: &Batch) {
+        let tasks = match store.tasks(batch.id) {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                self.error = Some(err.to_string());
+                return;
+            }
+        };
+        if tasks.is_empty() {
+            self.error = Some("Save a schedule before exporting it.".to_owned());
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("iCalendar", &["ics"])
+            .set_file_name(format!("{}.ics", batch.name))
+            .save_file()
+        else {
+            return;
+        };
+        let ics = schedule_to_ical(batch, &tasks, Utc::now().naive_utc());
+        match std::fs::write(&path, ics) {
+            Ok(()) => {
+                tracing::info!(tasks = tasks.len(), path = %path.display(), "schedule exported");
+                self.error = None;
+            }
+            Err(err) => {
+                self.error = Some(format!("Could not write {}: {}", path.display(), err));
+            }
+        }
+    }
+
+    /// Saves the batch's log sheet and opens it in the browser to print.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_log_sheet(&mut self, ctx: &egui::Context, batch: &Batch) {
+        let html = match log_sheet_html(batch) {
+            Ok(html) => html,
+            Err(err) => {
+                self.error = Some(err.to_string());
+                return;
+            }
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Web page", &["html"])
+            .set_file_name(format!("{} log sheet.html", batch.name))
+            .save_file()
+        else {
+            return;
+        };
+        match std::fs::write(&path, html) {
+            Ok(()) => {
+                tracing::info!(path = %path.display(), "log sheet saved");
+                self.error = None;
+                ctx.open_url(egui::OpenUrl::new_tab(format!("file://{}", path.display())));
+            }
+            Err(err) => {
+                self.error = Some(format!("Could not write {}: {}", path.display(), err));
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_device_log(&mut self, store: &Store, batch: &Batch) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Device log", &["csv", "json", "txt"])
+            .pick_file()
+        else {
+            return;
+        };
+        let readings = match std::fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| import_readings(&text).map_err(|err| err.to_string()))
+        {
+            Ok(readings) => readings,
+            Err(err) => {
+                self.error = Some(format!("Could not import {}: {}", path.display(), err));
+                return;
+            }
+        };
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        self.add_readings(store, batch, &file_name, &readings);
+    }
+
+    /// The batch a dropped hydrometer log goes to.
+    pub fn selected_name(&self) -> Option<&str> {
+        self.selected_batch().map(|batch| batch.name.as_str())
+    }
+
+    /// Adds readings from a dropped hydrometer log to the selected batch.
+    pub fn add_dropped_readings(
+        &mut self,
+        store: &Store,
+        file_name: &str,
+        readings: &[DeviceReading],
+    ) {
+        if let Some(batch) = self.selected_batch().cloned() {
+            self.add_readings(store, &batch, file_name, readings);
+        }
+    }
+
+    fn add_readings(
+        &mut self,
+        store: &Store,
+        batch: &Batch,
+        file_name: &str,
+        readings: &[DeviceReading],
+    ) {
+        let notes = format!("Imported from {}", file_name);
+        let initial_sugar = batch.initial_sugar();
+        let measurements: Vec<Measurement> = readings
+            .iter()
+            .map(|reading| reading.to_measurement(initial_sugar, &notes))
+            .collect();
+        match store.add_measurements(batch.id, &measurements) {
+            Ok(()) => {
+                tracing::info!(
+                    readings = measurements.len(),
+                    batch = batch.id,
+                    "device log imported"
+                );
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+        self.reload_selected(store);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_batches(&mut self, wine_data: &Dataset) {
+        let mut sheet = Spreadsheet::default();
+        for batch in &self.batches {
+            if self.export.contains(&batch.id) {
+                let result = run_simulation(&batch.input, wine_data);
+                sheet.push(
+                    &batch.name,
+                    &batch.started_on.to_string(),
+                    &batch.input,
+                    result.as_ref(),
+                );
+            }
+        }
+        match super::export::save_spreadsheet(&sheet, "batches") {
+            Ok(Some(path)) => {
+                tracing::info!(batches = sheet.rows().len(), path = %path.display(), "batches exported");
+                self.error = None;
+            }
+            Ok(None) => {}
+            Err(err) => self.error = Some(format!("Could not export the batches: {}", err)),
+        }
+    }
+
+    /// Whether the cellar tasks changed since the last call.
+    pub fn take_tasks_changed(&mut self) -> bool {
+        std::mem::take(&mut self.tasks_changed)
+    }
+
+    /// The batch log asked to be sent since the last call.
+    #[cfg(feature = "webhook")]
+    pub fn take_upload(&mut self) -> Option<Upload> {
+        self.upload.take()
+    }
+
+    /// Sets the malolactic and aging choices the next schedule is built from.
+    pub fn set_process(&mut self, process: Process) {
+        self.process = process;
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        store: &Store,
+        plan: &SimulationInput,
+        wine_data: &Dataset,
+    ) {
+        if !self.loaded {
+            self.new_batch_started_on = today().to_string();
+            self.measured_on = today().to_string();
+            self.hydrometer_calibration = HYDROMETER_CALIBRATION_TEMPERATURE.to_string();
+            self.reload(store);
+        }
+        #[cfg(any(feature = "mqtt", feature = "serial", feature = "ble"))]
+        if self
+            .sensors
+            .poll(store)
+            .is_some_and(|batch_id| self.selected == Some(batch_id))
+        {
+            self.reload_selected(store);
+        }
+
+        egui::SidePanel::left("batches")
+            .resizable(true)
+            .default_width(220.0)
+            .show_inside(ui, |ui| {
+                ui.heading("Batches");
+                ui.label("New batch from the simulator inputs:");
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.new_batch_name);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Started:");
+                    ui.text_edit_singleline(&mut self.new_batch_started_on);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Volume (L):")
+                        .on_hover_text("Supplies for this volume are taken from the inventory.");
+                    ui.text_edit_singleline(&mut self.new_batch_volume);
+                });
+                egui::CollapsingHeader::new("Volume from grape weight").show(ui, |ui| {
+                    if let Some(liters) = self.juice_yield.show(ui, "new_batch_yield")
+                        && ui.button("Use as Volume").clicked()
+                    {
+                        self.new_batch_volume = format!("{:.1}", liters);
+                    }
+                });
+                if let Ok(volume) = self.new_batch_volume.trim().parse::<f64>() {
+                    let short = shortages(self.inventory.stock(), &batch_requirements(volume));
+                    if !short.is_empty() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("Short of stock. {}", describe_shortages(&short)),
+                        );
+                    }
+                }
+                if ui.button("Start Batch").clicked() {
+                    self.start_batch(store, plan, wine_data);
+                }
+                egui::CollapsingHeader::new("Inventory")
+                    .show(ui, |ui| self.inventory.show(ui, store));
+                ui.separator();
+
+                if ui
+                    .selectable_label(self.show_dashboard, "Dashboard")
+                    .on_hover_text("Every batch still in the cellar, with the tasks coming up.")
+                    .clicked()
+                {
+                    self.show_dashboard = true;
+                }
+                let mut clicked = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for batch in &self.batches {
+                        let stage = batch.tracking(today()).ok().and_then(|t| t.stage);
+                        let mut ticked = self.export.contains(&batch.id);
+                        let label = match stage {
+                            Some(status) if status.stage() != Stage::Bottled => format!(
+                                "{} ({}, day {})",
+                                batch.name,
+                                status.stage().label().to_lowercase(),
+                                status.day
+                            ),
+                            _ => format!("{} ({})", batch.name, batch.started_on),
+                        };
+                        ui.horizontal(|ui| {
+                            if ui
+                                .checkbox(&mut ticked, "")
+                                .on_hover_text("Tick to export")
+                                .changed()
+                            {
+                                if ticked {
+                                    self.export.insert(batch.id);
+                                } else {
+                                    self.export.remove(&batch.id);
+                                }
+                            }
+                            if ui
+                                .selectable_label(
+                                    !self.show_dashboard && self.selected == Some(batch.id),
+                                    label,
+                                )
+                                .clicked()
+                            {
+                                clicked = Some(batch.id);
+                            }
+                        });
+                    }
+                });
+                if clicked.is_some() {
+                    self.selected = clicked;
+                    self.show_dashboard = false;
+                    self.reload_selected(store);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui
+                    .add_enabled(
+                        !self.export.is_empty(),
+                        egui::Button::new("Export Ticked Batches…"),
+                    )
+                    .on_hover_text(
+                        "Saves the ticked batches' plans as a spreadsheet, one row each.",
+                    )
+                    .clicked()
+                {
+                    self.export_batches(wine_data);
+                }
+            });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            if let Some(warning) = &self.warning {
+                ui.colored_label(ui.visuals().warn_fg_color, warning);
+            }
+            if self.show_dashboard {
+                if self.dashboard_popped {
+                    ui.horizontal(|ui| {
+                        ui.label("The dashboard is in its own window.");
+                        if ui.small_button("Bring Back").clicked() {
+                            self.dashboard_popped = false;
+                        }
+                    });
+                    return;
+                }
+                popout::button(ui, &mut self.dashboard_popped);
+                let clicked = egui::ScrollArea::vertical()
+                    .show(ui, |ui| self.dashboard.show(ui, &self.batches))
+                    .inner;
+                if clicked.is_some() {
+                    self.selected = clicked;
+                    self.show_dashboard = false;
+                    self.reload_selected(store);
+                }
+                return;
+            }
+            let Some(batch) = self.selected_batch().cloned() else {
+                ui.label("Start a batch to track a real ferment against its plan.");
+                return;
+            };
+            egui::ScrollArea::vertical().show(ui, |ui| self.show_batch(ui, store, &batch));
+        });
+    }
+
+    /// Shows the dashboard in its own window, if it's popped out.
+    pub fn show_popped_dashboard(&mut self, ctx: &egui::Context, store: &Store) {
+        let clicked = popout::show(
+            ctx,
+            &mut self.dashboard_popped,
+            "Cellar dashboard",
+            [720.0, 640.0],
+            |ui| self.dashboard.show(ui, &self.batches),
+        )
+        .flatten();
+        if clicked.is_some() {
+            self.selected = clicked;
+            self.show_dashboard = false;
+            self.reload_selected(store);
+        }
+    }
+
+    fn show_batch(&mut self, ui: &mut egui::Ui, store: &Store, batch: &Batch) {
+        let plan = &batch.input;
+        ui.heading(&batch.name);
+        let tracking = batch.tracking(today()).ok();
+        if let Some(tracking) = &tracking {
+            ui.strong(tracking.to_string());
+            if let Some(stage) = &tracking.stage {
+                ui.label(stage.to_string());
+            }
+        }
+        ui.label(format!(
+            "Started {}, planned as {} for {} days in a {} at {:.1} °C from {:.0} g/L sugar ({} climate).",
+            batch.started_on,
+            plan.grape_type,
+            plan.fermentation_days,
+            plan.container_type,
+            plan.temperature,
+            plan.sugar_content,
+            plan.climate
+        ));
+        if let Some(predicted_abv) = batch.predicted_abv {
+            ui.label(format!("Predicted ABV: {:.2}%", predicted_abv));
+        }
+
+        if let Some(forecast) = &self.forecast {
+            ui.label(format!(
+                "Refitted to {} readings: k = {:.3}/day (plan {:.3}); finishing around {} (day {}) at {:.1}% ABV.",
+                forecast.readings,
+                forecast.rate_constant,
+                forecast.planned_rate_constant,
+                forecast.finish_date,
+                forecast.finish_day,
+                forecast.final_abv
+            ));
+        }
+
+        self.show_progress_plot(ui, batch, tracking.map(|tracking| tracking.day));
+        egui::CollapsingHeader::new("What if…").show(ui, |ui| self.what_if.show(ui, plan));
+        if let Some(variance) = &self.variance {
+            egui::CollapsingHeader::new("Plan vs actual")
+                .show(ui, |ui| show_variance(ui, variance));
+        }
+
+        ui.separator();
+        ui.strong("Measurements");
+        egui::Grid::new("measurements")
+            .striped(true)
+            .num_columns(7)
+            .show(ui, |ui| {
+                for header in [
+                    "Day",
+                    "Date",
+                    "SG",
+                    "Sugar (g/L)",
+                    "Planned",
+                    "Temp (°C)",
+                    "Notes",
+                ] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for point in &self.progress {
+                    let Some(measured) = &point.measured else {
+                        continue;
+                    };
+                    ui.label(point.planned.day.to_string());
+                    ui.label(measured.measured_on.to_string());
+                    ui.label(
+                        measured
+                            .specific_gravity
+                            .map(|specific_gravity| format!("{:.3}", specific_gravity))
+                            .unwrap_or_default(),
+                    );
+                    ui.label(format!("{:.1}", measured.sugar_content));
+                    ui.label(format!(
+                        "{:.1} ({:+.1})",
+                        point.planned.residual_sugar,
+                        measured.sugar_content - point.planned.residual_sugar
+                    ));
+                    ui.label(
+                        measured
+                            .temperature
+                            .map(|temperature| format!("{:.1}", temperature))
+                            .unwrap_or_default(),
+                    );
+                    ui.label(&measured.notes);
+                    ui.end_row();
+                }
+            });
+
+        ui.horizontal(|ui| {
+            ui.label("Date:");
+            ui.add(egui::TextEdit::singleline(&mut self.measured_on).desired_width(90.0));
+            ui.label("SG:");
+            ui.add(egui::TextEdit::singleline(&mut self.measured_gravity).desired_width(50.0));
+            ui.label("or Sugar (g/L):");
+            ui.add(egui::TextEdit::singleline(&mut self.measured_sugar).desired_width(60.0));
+            ui.label("Temp (°C):");
+            ui.add(egui::TextEdit::singleline(&mut self.measured_temperature).desired_width(50.0));
+            ui.label("Hydrometer calibrated at (°C):").on_hover_text(
+                "SG readings are corrected from the sample temperature to this one.",
+            );
+            ui.add(
+                egui::TextEdit::singleline(&mut self.hydrometer_calibration).desired_width(40.0),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Notes:");
+            ui.text_edit_singleline(&mut self.measured_notes);
+            if ui.button("Log Measurement").clicked() {
+                self.log_measurement(store);
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui
+            .button("Import Tilt/iSpindel Log…")
+            .on_hover_text(
+                "CSV or JSON exports; gravity and temperature columns are found by name.",
+            )
+            .clicked()
+        {
+            self.import_device_log(store, batch);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui
+            .button("Print Log Sheet…")
+            .on_hover_text(
+                "A page of predicted gravities with blank columns for readings taken by hand.",
+            )
+            .clicked()
+        {
+            self.save_log_sheet(ui.ctx(), batch);
+        }
+        #[cfg(feature = "webhook")]
+        if ui
+            .add_enabled(!self.measurements.is_empty(), egui::Button::new("Send Log"))
+            .on_hover_text("Sends the readings to the webhook set up in Settings.")
+            .clicked()
+        {
+            self.upload = Some(Upload::batch_log(batch, &self.measurements));
+        }
+        #[cfg(any(feature = "mqtt", feature = "serial", feature = "ble"))]
+        self.sensors.show(ui, batch);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.companion.show(ui, batch);
+
+        ui.separator();
+        self.show_schedule(ui, store, batch);
+
+        ui.separator();
+        self.show_bottling(ui, batch);
+
+        ui.separator();
+        if self.costs.show(ui, store, batch) {
+            self.reload(store);
+        }
+
+        ui.separator();
+        ui.strong("Notes");
+        ui.text_edit_multiline(&mut self.notes);
+        ui.horizontal(|ui| {
+            if ui.button("Save Notes").clicked() {
+                if let Err(err) = store.set_batch_notes(batch.id, &self.notes) {
+                    self.error = Some(err.to_string());
+                }
+                self.reload(store);
+            }
+            if ui.button("Delete Batch").clicked() {
+                if let Err(err) = store.delete_batch(batch.id) {
+                    self.error = Some(err.to_string());
+                }
+                self.tasks_changed = true;
+                self.selected = None;
+                self.reload(store);
+            }
+        });
+    }
+
+    fn show_schedule(&mut self, ui: &mut egui::Ui, store: &Store, batch: &Batch) {
+        ui.strong("Schedule");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.process.malolactic, "Malolactic fermentation");
+            ui.label("Aging:");
+            ui.add(
+                egui::DragValue::new(&mut self.process.aging_months)
+                    .range(0..=60)
+                    .suffix(" months"),
+            );
+            if ui
+                .button("Generate Schedule")
+                .on_hover_text("Replaces the tasks below; save to keep them.")
+                .clicked()
+            {
+                self.schedule = batch
+                    .schedule(&self.process)
+                    .into_iter()
+                    .map(ScheduleRow::from)
+                    .collect();
+            }
+        });
+
+        let today = today().to_string();
+        let mut removed = None;
+        egui::Grid::new("schedule")
+            .striped(true)
+            .num_columns(4)
+            .show(ui, |ui| {
+                for header in ["Done", "Date", "Task", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for (i, row) in self.schedule.iter_mut().enumerate() {
+                    ui.checkbox(&mut row.done, "");
+                    // ISO dates compare correctly as text.
+                    let overdue = !row.done && row.due_on.trim() < today.as_str();
+                    let mut date = egui::TextEdit::singleline(&mut row.due_on).desired_width(90.0);
+                    if overdue {
+                        date = date.text_color(ui.visuals().warn_fg_color);
+                    }
+                    ui.add(date);
+                    ui.add(egui::TextEdit::singleline(&mut row.title).desired_width(320.0));
+                    if ui.small_button("Remove").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            self.schedule.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Add Task").clicked() {
+                self.schedule.push(ScheduleRow {
+                    uid: format!("task-{}", Local::now().format("%Y%m%d%H%M%S%f")),
+                    due_on: today,
+                    title: String::new(),
+                    done: false,
+                });
+            }
+            if ui.button("Save Schedule").clicked() {
+                self.save_schedule(store, batch.id);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .button("Export to Calendar…")
+                .on_hover_text("Saves the schedule as an .ics file. Importing it again updates the same events.")
+                .clicked()
+            {
+                self.export_schedule(store, batch);
+            }
+        });
+    }
+
+    fn show_bottling(&mut self, ui: &mut egui::Ui, batch: &Batch) {
+        ui.strong("Bottling");
+        let Some(volume) = batch.volume else {
+            ui.label("Enter the batch volume under Costs to plan the bottling.");
+            return;
+        };
+        let rackings = self
+            .schedule
+            .iter()
+            .filter(|row| row.uid.starts_with("rack-fine-lees"))
+            .count() as u32;
+        ui.horizontal(|ui| {
+            ui.label("Bottle:");
+            bottle_format_combo(ui, "batch_bottle_format", &mut self.bottle_format);
+        });
+        let plan = bottling_plan(volume, self.bottle_format, rackings);
+        ui.label(format!(
+            "After the gross lees and {} later rackings on the schedule, {}",
+            rackings,
+            describe_bottling(&plan, self.bottle_format)
+        ));
+    }
+
+    fn show_progress_plot(&self, ui: &mut egui::Ui, batch: &Batch, today: Option<i64>) {
+        let planned: PlotPoints = self
+            .progress
+            .iter()
+            .map(|point| [point.planned.day as f64, point.planned.residual_sugar])
+            .collect();
+        let measured: PlotPoints = self
+            .measurements
+            .iter()
+            .map(|measurement| {
+                [
+                    batch.day_of(measurement.measured_on) as f64,
+                    measurement.sugar_content,
+                ]
+            })
+            .collect();
+
+        let chart = Plot::new("batch_progress")
+            .legend(Legend::default())
+            .height(220.0)
+            .x_axis_label("Day")
+            .y_axis_label("Sugar (g/L)")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(planned).name("Planned"));
+                if let Some(forecast) = &self.forecast {
+                    let last_day = forecast.finish_day.max(batch.input.fermentation_days);
+                    let refitted: PlotPoints = (0..=last_day)
+                        .map(|day| {
+                            let state = fermentation_state_at_rate(
+                                &batch.input,
+                                day,
+                                forecast.rate_constant,
+                            );
+                            [day as f64, state.residual_sugar]
+                        })
+                        .collect();
+                    plot_ui.line(Line::new(refitted).name("Refitted"));
+                }
+                if let Some(branch) = self.what_if.branch(&batch.input)
+                    && let Ok(curve) = branch.curve()
+                {
+                    let branched: PlotPoints = curve
+                        .iter()
+                        .map(|state| [state.day as f64, state.residual_sugar])
+                        .collect();
+                    plot_ui.line(Line::new(branched).name("What if"));
+                }
+                plot_ui.points(Points::new(measured).radius(4.0).name("Measured"));
+                if let Some(day) = today.filter(|&day| day >= 0) {
+                    plot_ui.vline(VLine::new(day as f64).name("Today"));
+                }
+            })
+            .response;
+        chart_buttons(ui, chart.rect, "fermentation.png");
+    }
+}
+
+/// The readings set against the plan, with how far off the model ran.
+fn show_variance(ui: &mut egui::Ui, report: &VarianceReport) {
+    ui.label(report.to_string());
+    egui::Grid::new("variance")
+        .striped(true)
+        .num_columns(6)
+        .show(ui, |ui| {
+            for header in [
+                "Day",
+                "Date",
+                "Planned (g/L)",
+                "Measured (g/L)",
+                "Error (g/L)",
+                "Temp error (°C)",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for variance in &report.variances {
+                ui.label(variance.day.to_string());
+                ui.label(variance.measured_on.to_string());
+                ui.label(format!("{:.1}", variance.planned_sugar));
+                ui.label(format!("{:.1}", variance.measured_sugar));
+                ui.label(format!("{:+.1}", variance.sugar_error()));
+                ui.label(
+                    variance
+                        .temperature_error
+                        .map(|error| format!("{:+.1}", error))
+                        .unwrap_or_default(),
+                );
+                ui.end_row();
+            }
+        });
+}
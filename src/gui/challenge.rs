@@ -0,0 +1,129 @@
+use eframe::egui;
+use wine_maker::challenge::{
+    CHALLENGES, Challenge, ChallengeScore, Level, PASS_SCORE, unlocked_levels,
+};
+use wine_maker::engine::{SimulationInput, SimulationResult};
+
+/// Target wines to make in the simulator, scored against the last wine.
+pub struct ChallengeWindow {
+    pub open: bool,
+    challenge: &'static Challenge,
+    /// Best score for each challenge played, by name.
+    best: Vec<(String, f64)>,
+    last: Option<ChallengeScore>,
+}
+
+impl Default for ChallengeWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            challenge: &CHALLENGES[0],
+            best: Vec::new(),
+            last: None,
+        }
+    }
+}
+
+impl ChallengeWindow {
+    pub fn set_best(&mut self, best: Vec<(String, f64)>) {
+        self.best = best;
+    }
+
+    fn best_for(&self, challenge: &Challenge) -> Option<f64> {
+        self.best
+            .iter()
+            .find(|(name, _)| name == challenge.name)
+            .map(|(_, score)| *score)
+    }
+
+    /// Shows the window. `made` is the last simulated wine. Returns the
+    /// challenge and score when the wine is scored, to keep the best.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        made: Option<(&SimulationInput, &SimulationResult)>,
+    ) -> Option<(&'static str, f64)> {
+        let mut open = self.open;
+        let mut scored = None;
+        egui::Window::new("Challenges")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                let unlocked = unlocked_levels(&self.best);
+                for level in Level::ALL {
+                    let enabled = unlocked.contains(&level);
+                    ui.add_enabled_ui(enabled, |ui| {
+                        egui::CollapsingHeader::new(level.label())
+                            .id_salt(level.label())
+                            .default_open(level == Level::Apprentice)
+                            .show(ui, |ui| {
+                                for challenge in level.challenges() {
+                                    let label = match self.best_for(challenge) {
+                                        Some(best) => {
+                                            format!("{} (best {:.0})", challenge.name, best)
+                                        }
+                                        None => challenge.name.to_owned(),
+                                    };
+                                    if ui
+                                        .selectable_label(self.challenge == challenge, label)
+                                        .clicked()
+                                    {
+                                        self.challenge = challenge;
+                                        self.last = None;
+                                    }
+                                }
+                            })
+                    })
+                    .response
+                    .on_disabled_hover_text(
+                        "Pass a challenge in the level before to open this one.",
+                    );
+                }
+                ui.separator();
+                ui.strong(self.challenge.name);
+                ui.label(format!(
+                    "Within {:.2}% ABV for full marks; {:.0} points to pass.",
+                    self.challenge.level.abv_tolerance(),
+                    PASS_SCORE
+                ));
+                match made {
+                    Some((input, result)) => {
+                        if ui.button("Score my last wine").clicked() {
+                            let score = self.challenge.score(input, result);
+                            scored = Some((self.challenge.name, score.score));
+                            self.last = Some(score);
+                        }
+                    }
+                    None => {
+                        ui.label("Simulate a wine, then score it here.");
+                    }
+                }
+                if let Some(score) = &self.last {
+                    for check in &score.checks {
+                        ui.label(format!(
+                            "{:.0}/{:.0}  {}",
+                            check.points, check.max_points, check.description
+                        ));
+                    }
+                    if score.passed() {
+                        ui.strong(format!("Passed with {:.0}!", score.score));
+                    } else {
+                        ui.strong(format!("{:.0} points; not quite.", score.score));
+                    }
+                }
+            });
+        self.open = open;
+
+        if let Some((name, score)) = scored {
+            match self
+                .best
+                .iter_mut()
+                .find(|(best_name, _)| best_name == name)
+            {
+                Some((_, best)) => *best = best.max(score),
+                None => self.best.push((name.to_owned(), score)),
+            }
+        }
+        scored
+    }
+}
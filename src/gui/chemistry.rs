@@ -0,0 +1,27 @@
+use eframe::egui;
+use wine_maker::chemistry::chemistry_panel;
+use wine_maker::engine::{SimulationInput, SimulationResult};
+
+/// The last simulated wine's chemistry, figures outside the usual range
+/// picked out.
+pub fn show(ui: &mut egui::Ui, input: &SimulationInput, result: &SimulationResult) {
+    egui::Grid::new("chemistry")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("");
+            ui.strong("Value");
+            ui.strong("Typical");
+            ui.end_row();
+            for measurement in chemistry_panel(input, result) {
+                ui.label(measurement.name);
+                if measurement.is_typical() {
+                    ui.label(measurement.value_text());
+                } else {
+                    ui.colored_label(ui.visuals().warn_fg_color, measurement.value_text());
+                }
+                ui.label(measurement.typical_text());
+                ui.end_row();
+            }
+        });
+}
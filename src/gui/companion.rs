@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use chrono::Local;
+use eframe::egui;
+use wine_maker::cellar::Batch;
+use wine_maker::companion::{CompanionServer, DEFAULT_PORT, page_html};
+use wine_maker::store::Store;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Serves a batch's companion page while it is being tracked.
+pub struct CompanionPanel {
+    port: String,
+    /// The running server and the batch it shows.
+    serving: Option<(CompanionServer, Batch)>,
+    refreshed_at: Option<f64>,
+    error: Option<String>,
+}
+
+impl Default for CompanionPanel {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT.to_string(),
+            serving: None,
+            refreshed_at: None,
+            error: None,
+        }
+    }
+}
+
+impl CompanionPanel {
+    /// Rebuilds the page once a minute, so new readings, ticked tasks, and
+    /// the day rolling over all show up.
+    pub fn poll(&mut self, ctx: &egui::Context, store: &Store) {
+        let Some((server, batch)) = &self.serving else {
+            return;
+        };
+        let now = ctx.input(|input| input.time);
+        ctx.request_repaint_after(REFRESH_INTERVAL);
+        if self
+            .refreshed_at
+            .is_some_and(|refreshed_at| now - refreshed_at < REFRESH_INTERVAL.as_secs_f64())
+        {
+            return;
+        }
+        self.refreshed_at = Some(now);
+
+        let (measurements, tasks) = match (store.measurements(batch.id), store.tasks(batch.id)) {
+            (Ok(measurements), Ok(tasks)) => (measurements, tasks),
+            (Err(err), _) | (_, Err(err)) => {
+                tracing::error!("Could not read the batch for its companion page: {}", err);
+                return;
+            }
+        };
+        match page_html(batch, &measurements, &tasks, Local::now().date_naive()) {
+            Ok(html) => server.set_page(html),
+            Err(err) => tracing::error!("Could not build the companion page: {}", err),
+        }
+    }
+
+    /// Rebuilds the page on the next poll, as after the batch changes.
+    pub fn refresh(&mut self) {
+        self.refreshed_at = None;
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, batch: &Batch) {
+        egui::CollapsingHeader::new("Companion page").show(ui, |ui| {
+            if let Some((server, serving)) = &self.serving {
+                ui.label(format!(
+                    "Serving a read-only page for {} to phones and tablets on this network.",
+                    serving.name
+                ));
+                match server.url() {
+                    Some(url) => {
+                        ui.hyperlink(url);
+                    }
+                    None => {
+                        ui.label(format!(
+                            "Open port {} on this computer's address.",
+                            server.port()
+                        ));
+                    }
+                }
+                if ui.button("Stop").clicked() {
+                    self.serving = None;
+                }
+                return;
+            }
+
+            ui.label(
+                "Shows this batch's progress chart and upcoming tasks on a web page that a \
+                 phone on the same network can open. Nothing can be changed from it.",
+            );
+            ui.horizontal(|ui| {
+                ui.label("Port:");
+                ui.add(egui::TextEdit::singleline(&mut self.port).desired_width(50.0));
+                if ui.button("Start").clicked() {
+                    self.start(batch);
+                }
+            });
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+    }
+
+    fn start(&mut self, batch: &Batch) {
+        let Ok(port) = self.port.trim().parse::<u16>() else {
+            self.error = Some(format!("{:?} is not a port number", self.port));
+            return;
+        };
+        match CompanionServer::start(port) {
+            Ok(server) => {
+                self.serving = Some((server, batch.clone()));
+                self.refreshed_at = None;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("Could not listen on port {}: {}", port, err)),
+        }
+    }
+}
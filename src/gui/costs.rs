@@ -0,0 +1,268 @@
+use eframe::egui;
+use wine_maker::cellar::Batch;
+use wine_maker::costs::{BOTTLE_VOLUME, COST_CATEGORIES, Cost, Price, cost_report};
+use wine_maker::store::Store;
+
+/// A cost as edited in the costs grid.
+struct CostRow {
+    category: String,
+    description: String,
+    quantity: String,
+    unit_price: String,
+}
+
+impl From<Cost> for CostRow {
+    fn from(cost: Cost) -> Self {
+        CostRow {
+            category: cost.category,
+            description: cost.description,
+            quantity: cost.quantity.to_string(),
+            unit_price: cost.unit_price.to_string(),
+        }
+    }
+}
+
+impl CostRow {
+    fn parse(&self) -> Result<Cost, String> {
+        let number = |text: &str, what: &str| {
+            text.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{:?} is not a valid {}", text, what))
+        };
+        Ok(Cost {
+            category: self.category.clone(),
+            description: self.description.trim().to_owned(),
+            quantity: number(&self.quantity, "quantity")?,
+            unit_price: number(&self.unit_price, "price")?,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct CostsPanel {
+    volume: String,
+    rows: Vec<CostRow>,
+    prices: Vec<Price>,
+    error: Option<String>,
+}
+
+impl CostsPanel {
+    pub fn load(&mut self, store: &Store, batch: &Batch) {
+        self.volume = batch
+            .volume
+            .map(|volume| volume.to_string())
+            .unwrap_or_default();
+        self.error = None;
+        match store.costs(batch.id) {
+            Ok(costs) => self.rows = costs.into_iter().map(CostRow::from).collect(),
+            Err(err) => tracing::error!("Could not read costs: {}", err),
+        }
+        self.load_prices(store);
+    }
+
+    fn load_prices(&mut self, store: &Store) {
+        match store.prices() {
+            Ok(prices) => self.prices = prices,
+            Err(err) => tracing::error!("Could not read the price list: {}", err),
+        }
+    }
+
+    fn volume(&self) -> Result<Option<f64>, String> {
+        let volume = self.volume.trim();
+        if volume.is_empty() {
+            return Ok(None);
+        }
+        volume
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("{:?} is not a volume in liters", volume))
+    }
+
+    fn save(&mut self, store: &Store, batch_id: i64) -> Result<(), String> {
+        let volume = self.volume()?;
+        let costs = self
+            .rows
+            .iter()
+            .map(CostRow::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        store
+            .set_batch_volume(batch_id, volume)
+            .and_then(|()| store.set_costs(batch_id, &costs))
+            .map_err(|err| err.to_string())
+    }
+
+    /// Shows the costs section, returning whether the batch was saved.
+    pub fn show(&mut self, ui: &mut egui::Ui, store: &Store, batch: &Batch) -> bool {
+        ui.strong("Costs");
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        ui.horizontal(|ui| {
+            ui.label("Volume (L):");
+            ui.add(egui::TextEdit::singleline(&mut self.volume).desired_width(60.0));
+        });
+
+        let mut removed = None;
+        let mut priced = None;
+        egui::Grid::new("costs")
+            .striped(true)
+            .num_columns(6)
+            .show(ui, |ui| {
+                for header in ["Category", "Item", "Quantity", "Unit price", "Total", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for (i, row) in self.rows.iter_mut().enumerate() {
+                    egui::ComboBox::from_id_salt(("cost_category", i))
+                        .selected_text(row.category.as_str())
+                        .show_ui(ui, |ui| {
+                            for category in COST_CATEGORIES {
+                                ui.selectable_value(
+                                    &mut row.category,
+                                    category.to_owned(),
+                                    category,
+                                );
+                            }
+                        });
+                    ui.add(egui::TextEdit::singleline(&mut row.description).desired_width(180.0));
+                    ui.add(egui::TextEdit::singleline(&mut row.quantity).desired_width(50.0));
+                    ui.add(egui::TextEdit::singleline(&mut row.unit_price).desired_width(60.0));
+                    ui.label(
+                        row.parse()
+                            .map(|cost| format!("{:.2}", cost.total()))
+                            .unwrap_or_default(),
+                    );
+                    ui.horizontal(|ui| {
+                        if ui.small_button("Remove").clicked() {
+                            removed = Some(i);
+                        }
+                        if ui
+                            .small_button("Save Price")
+                            .on_hover_text("Adds the item and its unit price to the price list.")
+                            .clicked()
+                        {
+                            priced = Some(i);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            self.rows.remove(i);
+        }
+        if let Some(i) = priced {
+            match self.rows[i].parse() {
+                Ok(cost) if !cost.description.is_empty() => {
+                    let price = Price {
+                        name: cost.description,
+                        category: cost.category,
+                        unit: String::new(),
+                        unit_price: cost.unit_price,
+                    };
+                    if let Err(err) = store.save_price(&price) {
+                        self.error = Some(err.to_string());
+                    }
+                    self.load_prices(store);
+                }
+                Ok(_) => self.error = Some("Name the item before saving its price.".to_owned()),
+                Err(err) => self.error = Some(err),
+            }
+        }
+
+        let mut saved = false;
+        ui.horizontal(|ui| {
+            if ui.button("Add Cost").clicked() {
+                self.rows.push(CostRow {
+                    category: COST_CATEGORIES[0].to_owned(),
+                    description: String::new(),
+                    quantity: "1".to_owned(),
+                    unit_price: String::new(),
+                });
+            }
+            let mut picked = None;
+            egui::ComboBox::from_id_salt("price_list")
+                .selected_text("Add from Price List")
+                .show_ui(ui, |ui| {
+                    for price in &self.prices {
+                        let label = format!("{} ({:.2})", price.name, price.unit_price);
+                        if ui.selectable_label(false, label).clicked() {
+                            picked = Some(price.cost(1.0));
+                        }
+                    }
+                });
+            if let Some(cost) = picked {
+                self.rows.push(CostRow::from(cost));
+            }
+            if ui.button("Save Costs").clicked() {
+                match self.save(store, batch.id) {
+                    Ok(()) => {
+                        tracing::info!(batch = batch.id, costs = self.rows.len(), "costs saved");
+                        self.error = None;
+                        saved = true;
+                    }
+                    Err(err) => self.error = Some(err),
+                }
+            }
+        });
+
+        let costs: Vec<Cost> = self
+            .rows
+            .iter()
+            .filter_map(|row| row.parse().ok())
+            .collect();
+        let report = cost_report(&costs, self.volume().ok().flatten());
+        ui.label(format!("Total: {:.2}", report.total));
+        if !report.by_category.is_empty() {
+            ui.label(
+                report
+                    .by_category
+                    .iter()
+                    .map(|(category, total)| format!("{} {:.2}", category, total))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+        match (report.per_liter, report.bottles, report.per_bottle) {
+            (Some(per_liter), Some(bottles), Some(per_bottle)) => {
+                ui.label(format!(
+                    "{:.2} per liter; {:.2} per bottle over {} bottles of {} mL.",
+                    per_liter,
+                    per_bottle,
+                    bottles,
+                    BOTTLE_VOLUME * 1000.0
+                ));
+            }
+            (Some(per_liter), _, _) => {
+                ui.label(format!("{:.2} per liter.", per_liter));
+            }
+            _ => {
+                ui.label("Enter the batch volume to see the cost per liter and per bottle.");
+            }
+        }
+
+        ui.collapsing("Price List", |ui| {
+            if self.prices.is_empty() {
+                ui.label("Save a cost's price to reuse it in other batches.");
+            }
+            let mut deleted = None;
+            for price in &self.prices {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} ({}): {:.2}",
+                        price.name, price.category, price.unit_price
+                    ));
+                    if ui.small_button("Delete").clicked() {
+                        deleted = Some(price.name.clone());
+                    }
+                });
+            }
+            if let Some(name) = deleted {
+                if let Err(err) = store.delete_price(&name) {
+                    self.error = Some(err.to_string());
+                }
+                self.load_prices(store);
+            }
+        });
+        saved
+    }
+}
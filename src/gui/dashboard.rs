@@ -0,0 +1,153 @@
+use std::ops::RangeInclusive;
+
+use chrono::{Local, NaiveDate, TimeDelta};
+use eframe::egui;
+use egui_plot::{Bar, BarChart, GridMark, Legend, Line, Plot, PlotPoints, VLine};
+use wine_maker::cellar::Batch;
+use wine_maker::engine::fermentation_state;
+use wine_maker::stage::Stage;
+use wine_maker::store::{DueTask, Store};
+
+use super::snapshot::chart_buttons;
+
+/// How far ahead the task list looks.
+const UPCOMING_DAYS: i64 = 14;
+
+/// Every batch still in the cellar on one timeline, with their tasks.
+#[derive(Default)]
+pub struct Dashboard {
+    /// Unfinished tasks due in the next fortnight, overdue ones included.
+    upcoming: Vec<DueTask>,
+}
+
+/// Days from `today` to `date`, as a plot coordinate.
+fn x(today: NaiveDate, date: NaiveDate) -> f64 {
+    (date - today).num_days() as f64
+}
+
+/// Labels a plot axis in days from `today` with dates.
+fn date_axis(today: NaiveDate) -> impl Fn(GridMark, &RangeInclusive<f64>) -> String {
+    move |mark, _range| (today + TimeDelta::days(mark.value.round() as i64)).to_string()
+}
+
+impl Dashboard {
+    pub fn load(&mut self, store: &Store) {
+        let until = Local::now().date_naive() + TimeDelta::days(UPCOMING_DAYS);
+        match store.due_tasks(until) {
+            Ok(upcoming) => self.upcoming = upcoming,
+            Err(err) => tracing::error!("Could not read upcoming tasks: {}", err),
+        }
+    }
+
+    /// Shows the dashboard, returning the batch clicked on, if any.
+    pub fn show(&self, ui: &mut egui::Ui, batches: &[Batch]) -> Option<i64> {
+        let today = Local::now().date_naive();
+        let active: Vec<&Batch> = batches
+            .iter()
+            .filter(|batch| batch.bottled_on() > today)
+            .collect();
+        let mut clicked = None;
+
+        ui.heading("Dashboard");
+        if active.is_empty() {
+            ui.label("No batches are in the cellar. Bottled batches are left off.");
+        } else {
+            ui.strong("Stages");
+            let names: Vec<String> = active.iter().map(|batch| batch.name.clone()).collect();
+            let timelines: Vec<_> = active
+                .iter()
+                .map(|batch| batch.timeline().unwrap_or_default())
+                .collect();
+            Plot::new("dashboard_stages")
+                .legend(Legend::default())
+                .height(40.0 + 30.0 * active.len() as f32)
+                .x_axis_formatter(date_axis(today))
+                .y_axis_formatter(move |mark, _range| {
+                    let lane = mark.value.round();
+                    if (mark.value - lane).abs() > 1e-6 || lane < 0.0 {
+                        return String::new();
+                    }
+                    names.get(lane as usize).cloned().unwrap_or_default()
+                })
+                .show(ui, |plot_ui| {
+                    for stage in Stage::ALL {
+                        let bars: Vec<Bar> = timelines
+                            .iter()
+                            .enumerate()
+                            .flat_map(|(lane, timeline)| {
+                                timeline
+                                    .iter()
+                                    .filter(move |dated| dated.stage == stage)
+                                    .filter_map(move |dated| {
+                                        let start = x(today, dated.starts_on);
+                                        let end = x(today, dated.ends_on?);
+                                        Some(
+                                            Bar::new(lane as f64, end - start)
+                                                .base_offset(start)
+                                                .width(0.6),
+                                        )
+                                    })
+                            })
+                            .collect();
+                        if !bars.is_empty() {
+                            plot_ui.bar_chart(BarChart::new(bars).horizontal().name(stage.label()));
+                        }
+                    }
+                    plot_ui.vline(VLine::new(0.0).name("Today"));
+                });
+
+            ui.strong("Predicted fermentation");
+            let chart = Plot::new("dashboard_progress")
+                .legend(Legend::default())
+                .height(200.0)
+                .x_axis_formatter(date_axis(today))
+                .y_axis_label("Sugar fermented (%)")
+                .show(ui, |plot_ui| {
+                    for batch in &active {
+                        let points: PlotPoints = (0..=batch.input.fermentation_days)
+                            .filter_map(|day| {
+                                let state = fermentation_state(&batch.input, day).ok()?;
+                                let date = batch.started_on + TimeDelta::days(day.into());
+                                Some([x(today, date), 100.0 * state.fraction_fermented])
+                            })
+                            .collect();
+                        plot_ui.line(Line::new(points).name(&batch.name));
+                    }
+                    plot_ui.vline(VLine::new(0.0).name("Today"));
+                })
+                .response;
+            chart_buttons(ui, chart.rect, "cellar-fermentation.png");
+        }
+
+        ui.separator();
+        ui.strong(format!("Tasks in the next {} days", UPCOMING_DAYS));
+        if self.upcoming.is_empty() {
+            ui.label("Nothing due. Generate a schedule on a batch to fill this in.");
+            return None;
+        }
+        egui::Grid::new("dashboard_tasks")
+            .striped(true)
+            .num_columns(3)
+            .show(ui, |ui| {
+                for header in ["Date", "Batch", "Task"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for due in &self.upcoming {
+                    let date = due.task.due_on.to_string();
+                    if due.task.due_on < today {
+                        ui.colored_label(ui.visuals().warn_fg_color, date)
+                            .on_hover_text("Overdue");
+                    } else {
+                        ui.label(date);
+                    }
+                    if ui.link(&due.batch_name).clicked() {
+                        clicked = Some(due.batch_id);
+                    }
+                    ui.label(&due.task.title);
+                    ui.end_row();
+                }
+            });
+        clicked
+    }
+}
@@ -0,0 +1,199 @@
+use eframe::egui;
+use wine_maker::dataset::Dataset;
+#[cfg(feature = "sqlite")]
+use wine_maker::devices::{DeviceReading, import_readings};
+use wine_maker::recipe::Recipe;
+use wine_maker::save::SaveFile;
+#[cfg(feature = "scenario")]
+use wine_maker::scenario::Scenario;
+
+/// A file dropped on the window, read by the importer it belongs to.
+pub enum Dropped {
+    Dataset(Dataset),
+    Session(Box<SaveFile>),
+    Recipe(Recipe),
+    #[cfg(feature = "scenario")]
+    Scenario(Box<Scenario>),
+    #[cfg(feature = "sqlite")]
+    DeviceLog(Vec<DeviceReading>),
+}
+
+impl Dropped {
+    /// Tries each importer in turn, most particular first: recipes and
+    /// sessions are JSON with known fields, scenarios TOML with a known
+    /// format, and a device log needs a gravity
+    /// column that a dataset doesn't have.
+    fn read(text: &str) -> Option<Dropped> {
+        if let Ok(recipe) = Recipe::from_json(text) {
+            return Some(Dropped::Recipe(recipe));
+        }
+        if let Ok(save_file) = SaveFile::from_json(text) {
+            return Some(Dropped::Session(Box::new(save_file)));
+        }
+        #[cfg(feature = "scenario")]
+        if let Ok(scenario) = Scenario::from_toml(text) {
+            return Some(Dropped::Scenario(Box::new(scenario)));
+        }
+        #[cfg(feature = "sqlite")]
+        if let Ok(readings) = import_readings(text)
+            && !readings.is_empty()
+        {
+            return Some(Dropped::DeviceLog(readings));
+        }
+        Dataset::from_csv_str(text)
+            .ok()
+            .filter(|dataset| !dataset.is_empty())
+            .map(Dropped::Dataset)
+    }
+
+    #[cfg_attr(not(feature = "sqlite"), allow(unused_variables))]
+    fn describe(&self, batch: Option<&str>) -> String {
+        match self {
+            Dropped::Dataset(dataset) => format!(
+                "a dataset of {} wines. Use it in place of the current one?",
+                dataset.len()
+            ),
+            Dropped::Session(save_file) => format!(
+                "a saved session of {}. Open it, replacing the current inputs?",
+                save_file.input.grape_type
+            ),
+            Dropped::Recipe(recipe) => format!(
+                "a recipe for {}. Import it, replacing the current inputs?",
+                recipe.input.grape_type
+            ),
+            #[cfg(feature = "scenario")]
+            Dropped::Scenario(scenario) => format!(
+                "a scenario for {}. Run it, replacing the current inputs and model profile?",
+                scenario.input.grape_type
+            ),
+            #[cfg(feature = "sqlite")]
+            Dropped::DeviceLog(readings) => match batch {
+                Some(batch) => format!(
+                    "a hydrometer log of {} readings. Add them to {}?",
+                    readings.len(),
+                    batch
+                ),
+                None => format!(
+                    "a hydrometer log of {} readings. Open a batch in the Cellar tab, then drop it again.",
+                    readings.len()
+                ),
+            },
+        }
+    }
+
+    fn needs_batch(&self) -> bool {
+        #[cfg(feature = "sqlite")]
+        if let Dropped::DeviceLog(_) = self {
+            return true;
+        }
+        false
+    }
+}
+
+/// Picks up files dropped on the window and asks before importing them.
+#[derive(Default)]
+pub struct DropWindow {
+    /// The dropped file's name, and what it turned out to be.
+    pending: Option<(String, Option<Dropped>)>,
+}
+
+fn read_text(file: &egui::DroppedFile) -> Option<String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = &file.path {
+        return std::fs::read_to_string(path).ok();
+    }
+    let bytes = file.bytes.as_ref()?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+fn file_name(file: &egui::DroppedFile) -> String {
+    match &file.path {
+        Some(path) if file.name.is_empty() => path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+        _ => file.name.clone(),
+    }
+}
+
+impl DropWindow {
+    /// Notes a newly dropped file and shades the window while one is
+    /// dragged over it. Called every frame.
+    pub fn collect(&mut self, ctx: &egui::Context) {
+        let (hovering, dropped) = ctx.input(|input| {
+            (
+                !input.raw.hovered_files.is_empty(),
+                input.raw.dropped_files.first().cloned(),
+            )
+        });
+        if hovering {
+            let screen = ctx.screen_rect();
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("drop_overlay"),
+            ));
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop a dataset, session, recipe, or hydrometer log",
+                egui::FontId::proportional(20.0),
+                egui::Color32::WHITE,
+            );
+        }
+        if let Some(file) = dropped {
+            let dropped = read_text(&file).and_then(|text| Dropped::read(&text));
+            self.pending = Some((file_name(&file), dropped));
+        }
+    }
+
+    /// Asks whether to import the dropped file, returning its name and
+    /// contents once confirmed. `batch` names the batch a hydrometer log
+    /// would go to.
+    pub fn show(&mut self, ctx: &egui::Context, batch: Option<&str>) -> Option<(String, Dropped)> {
+        let (name, dropped) = self.pending.as_ref()?;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("Open Dropped File")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| match dropped {
+                Some(dropped) => {
+                    ui.label(format!("{} is {}", name, dropped.describe(batch)));
+                    ui.horizontal(|ui| {
+                        let can_import = batch.is_some() || !dropped.needs_batch();
+                        if ui
+                            .add_enabled(can_import, egui::Button::new("Import"))
+                            .clicked()
+                        {
+                            confirmed = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                }
+                None => {
+                    ui.label(format!(
+                        "{} isn't a dataset CSV, saved session, recipe, or Tilt or iSpindel log.",
+                        name
+                    ));
+                    if ui.button("OK").clicked() {
+                        cancelled = true;
+                    }
+                }
+            });
+        if confirmed {
+            return self
+                .pending
+                .take()
+                .and_then(|(name, dropped)| Some((name, dropped?)));
+        }
+        if cancelled {
+            self.pending = None;
+        }
+        None
+    }
+}
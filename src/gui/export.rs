@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use wine_maker::export::{ExportError, Spreadsheet};
+
+/// Asks where to save `sheet` and saves it, as an Excel workbook if the
+/// name ends in `.xlsx` and as CSV otherwise. Returns where it went, or
+/// `None` if the dialog was cancelled.
+pub fn save_spreadsheet(
+    sheet: &Spreadsheet,
+    file_name: &str,
+) -> Result<Option<PathBuf>, ExportError> {
+    let dialog = rfd::FileDialog::new()
+        .add_filter("CSV", &["csv"])
+        .set_file_name(format!("{}.csv", file_name));
+    #[cfg(feature = "xlsx")]
+    let dialog = dialog.add_filter("Excel workbook", &["xlsx"]);
+    let Some(path) = dialog.save_file() else {
+        return Ok(None);
+    };
+    #[cfg(feature = "xlsx")]
+    if path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xlsx"))
+    {
+        sheet.save_xlsx(&path)?;
+        return Ok(Some(path));
+    }
+    sheet.save_csv(&path)?;
+    Ok(Some(path))
+}
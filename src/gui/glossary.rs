@@ -0,0 +1,86 @@
+use eframe::egui;
+use wine_maker::glossary::Glossary;
+
+/// A searchable glossary that the rest of the interface links into.
+#[derive(Default)]
+pub struct GlossaryWindow {
+    pub open: bool,
+    glossary: Glossary,
+    query: String,
+    /// Key of the term shown in full.
+    selected: Option<&'static str>,
+}
+
+impl GlossaryWindow {
+    pub fn set_glossary(&mut self, glossary: Glossary) {
+        self.glossary = glossary;
+    }
+
+    /// A small link to the term `key`: hovering shows its definition and
+    /// clicking opens it in the glossary.
+    pub fn link(&mut self, ui: &mut egui::Ui, key: &'static str) {
+        let Some(entry) = self.glossary.entry(key) else {
+            return;
+        };
+        let hover = format!("{}: {}\nClick for more.", entry.name, entry.definition);
+        if ui.small_button("?").on_hover_text(hover).clicked() {
+            self.open = true;
+            self.selected = Some(key);
+            self.query.clear();
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        egui::Window::new("Glossary")
+            .open(&mut open)
+            .default_size([480.0, 320.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Search:");
+                    ui.text_edit_singleline(&mut self.query);
+                });
+                ui.separator();
+                ui.columns(2, |columns| {
+                    egui::ScrollArea::vertical().id_salt("glossary_terms").show(
+                        &mut columns[0],
+                        |ui| {
+                            for entry in self.glossary.search(&self.query) {
+                                let label = match entry.term.abbreviation {
+                                    Some(abbreviation) => {
+                                        format!("{} ({})", entry.name, abbreviation)
+                                    }
+                                    None => entry.name.to_owned(),
+                                };
+                                let selected = self.selected == Some(entry.term.key);
+                                if ui.selectable_label(selected, label).clicked() {
+                                    self.selected = Some(entry.term.key);
+                                }
+                            }
+                        },
+                    );
+                    let ui = &mut columns[1];
+                    let Some(entry) = self.selected.and_then(|key| self.glossary.entry(key)) else {
+                        ui.label("Pick a term to read about it.");
+                        return;
+                    };
+                    ui.heading(entry.name);
+                    ui.label(entry.definition);
+                    if !entry.term.see_also.is_empty() {
+                        ui.add_space(8.0);
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("See also:");
+                            for key in entry.term.see_also {
+                                if let Some(related) = self.glossary.entry(key)
+                                    && ui.link(related.name).clicked()
+                                {
+                                    self.selected = Some(related.term.key);
+                                }
+                            }
+                        });
+                    }
+                });
+            });
+        self.open = open;
+    }
+}
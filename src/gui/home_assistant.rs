@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use eframe::egui;
+use wine_maker::cellar::{Batch, BatchStatus};
+use wine_maker::mqtt::{HomeAssistantConfig, Publisher};
+use wine_maker::store::Store;
+
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Publishes the batches in progress to Home Assistant while connected.
+pub struct HomeAssistantWindow {
+    pub open: bool,
+    host: String,
+    port: String,
+    discovery_prefix: String,
+    publisher: Option<Publisher>,
+    published_at: Option<f64>,
+    status: String,
+}
+
+impl Default for HomeAssistantWindow {
+    fn default() -> Self {
+        let config = HomeAssistantConfig::default();
+        Self {
+            open: false,
+            host: config.host,
+            port: config.port.to_string(),
+            discovery_prefix: config.discovery_prefix,
+            publisher: None,
+            published_at: None,
+            status: String::new(),
+        }
+    }
+}
+
+impl HomeAssistantWindow {
+    /// Publishes every five minutes while connected, whatever tab is open.
+    pub fn poll(&mut self, ctx: &egui::Context, store: &Store) {
+        let Some(publisher) = &self.publisher else {
+            return;
+        };
+        if let Some(err) = publisher.errors.try_iter().last() {
+            self.status = format!("Could not publish: {}", err);
+        }
+        let now = ctx.input(|input| input.time);
+        ctx.request_repaint_after(PUBLISH_INTERVAL);
+        if self
+            .published_at
+            .is_some_and(|published_at| now - published_at < PUBLISH_INTERVAL.as_secs_f64())
+        {
+            return;
+        }
+        self.published_at = Some(now);
+
+        let today = Local::now().date_naive();
+        let batches = match active_batches(store, today) {
+            Ok(batches) => batches,
+            Err(err) => {
+                tracing::error!("Could not read batch status: {}", err);
+                return;
+            }
+        };
+        self.status = format!(
+            "Publishing {} batches as of {}",
+            batches.len(),
+            Local::now().format("%H:%M")
+        );
+        publisher.publish(batches);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        egui::Window::new("Home Assistant")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Batches in progress show up in Home Assistant as devices, with their \
+                     days remaining, ABV, gravity, stage, and next task.",
+                );
+                if self.publisher.is_some() {
+                    if !self.status.is_empty() {
+                        ui.label(&self.status);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Publish now").clicked() {
+                            self.published_at = None;
+                        }
+                        if ui.button("Disconnect").clicked() {
+                            self.publisher = None;
+                            self.status.clear();
+                        }
+                    });
+                    return;
+                }
+
+                egui::Grid::new("home_assistant")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Broker:");
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut self.host);
+                            ui.add(egui::TextEdit::singleline(&mut self.port).desired_width(50.0));
+                        });
+                        ui.end_row();
+                        ui.label("Discovery prefix:");
+                        ui.text_edit_singleline(&mut self.discovery_prefix);
+                        ui.end_row();
+                    });
+                if !self.status.is_empty() {
+                    ui.label(&self.status);
+                }
+                if ui.button("Connect").clicked() {
+                    self.connect();
+                }
+            });
+        self.open = open;
+    }
+
+    fn connect(&mut self) {
+        let Ok(port) = self.port.trim().parse::<u16>() else {
+            self.status = format!("{:?} is not a port number", self.port);
+            return;
+        };
+        let config = HomeAssistantConfig {
+            host: self.host.trim().to_owned(),
+            port,
+            discovery_prefix: self.discovery_prefix.trim().to_owned(),
+            ..HomeAssistantConfig::default()
+        };
+        self.publisher = Some(Publisher::connect(&config));
+        self.published_at = None;
+        self.status.clear();
+    }
+}
+
+/// The batches started and not yet bottled, with where each stands.
+fn active_batches(store: &Store, today: NaiveDate) -> rusqlite::Result<Vec<(Batch, BatchStatus)>> {
+    let mut active = Vec::new();
+    for batch in store.batches()? {
+        if batch.started_on > today || batch.bottled_on() <= today {
+            continue;
+        }
+        let measurements = store.measurements(batch.id)?;
+        match batch.status(&measurements, &store.tasks(batch.id)?, today) {
+            Ok(status) => active.push((batch, status)),
+            Err(err) => tracing::warn!("Could not work out the status of {}: {}", batch.name, err),
+        }
+    }
+    Ok(active)
+}
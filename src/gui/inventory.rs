@@ -0,0 +1,133 @@
+use eframe::egui;
+use wine_maker::inventory::{BOTTLES, CORKS, SULFITE, StockItem, YEAST};
+use wine_maker::store::Store;
+
+/// A stock item as edited in the inventory grid.
+struct StockRow {
+    name: String,
+    unit: String,
+    quantity: String,
+}
+
+#[derive(Default)]
+pub struct InventoryPanel {
+    stock: Vec<StockItem>,
+    rows: Vec<StockRow>,
+    error: Option<String>,
+}
+
+impl InventoryPanel {
+    /// What was on hand when the inventory was last loaded or saved.
+    pub fn stock(&self) -> &[StockItem] {
+        &self.stock
+    }
+
+    pub fn load(&mut self, store: &Store) {
+        match store.stock() {
+            Ok(stock) => self.stock = stock,
+            Err(err) => tracing::error!("Could not read the inventory: {}", err),
+        }
+        self.rows = self
+            .stock
+            .iter()
+            .map(|item| StockRow {
+                name: item.name.clone(),
+                unit: item.unit.clone(),
+                quantity: item.quantity.to_string(),
+            })
+            .collect();
+    }
+
+    fn save(&mut self, store: &Store) -> Result<(), String> {
+        for row in &self.rows {
+            let name = row.name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let quantity = row
+                .quantity
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{:?} is not a quantity for {}", row.quantity, name))?;
+            store
+                .save_stock_item(&StockItem::new(name, row.unit.trim(), quantity))
+                .map_err(|err| err.to_string())?;
+        }
+        Ok(())
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, store: &Store) {
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        let mut removed = None;
+        egui::Grid::new("inventory")
+            .striped(true)
+            .num_columns(4)
+            .show(ui, |ui| {
+                for header in ["Item", "On hand", "Unit", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for (i, row) in self.rows.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut row.name).desired_width(110.0));
+                    ui.add(egui::TextEdit::singleline(&mut row.quantity).desired_width(45.0));
+                    ui.add(egui::TextEdit::singleline(&mut row.unit).desired_width(50.0));
+                    if ui.small_button("Remove").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            let row = self.rows.remove(i);
+            if let Err(err) = store.delete_stock_item(row.name.trim()) {
+                self.error = Some(err.to_string());
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Add Item").clicked() {
+                self.rows.push(StockRow {
+                    name: String::new(),
+                    unit: String::new(),
+                    quantity: "0".to_owned(),
+                });
+            }
+            if ui
+                .button("Add Usual Items")
+                .on_hover_text("Adds the supplies a new batch draws on, plus sugar.")
+                .clicked()
+            {
+                for (name, unit) in [
+                    (YEAST, "packets"),
+                    (SULFITE, "g"),
+                    ("Sugar", "kg"),
+                    (BOTTLES, "bottles"),
+                    (CORKS, "corks"),
+                ] {
+                    if !self
+                        .rows
+                        .iter()
+                        .any(|row| row.name.trim().eq_ignore_ascii_case(name))
+                    {
+                        self.rows.push(StockRow {
+                            name: name.to_owned(),
+                            unit: unit.to_owned(),
+                            quantity: "0".to_owned(),
+                        });
+                    }
+                }
+            }
+            if ui.button("Save").clicked() {
+                match self.save(store) {
+                    Ok(()) => {
+                        self.error = None;
+                        self.load(store);
+                    }
+                    Err(err) => self.error = Some(err),
+                }
+            }
+        });
+    }
+}
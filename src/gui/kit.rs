@@ -0,0 +1,119 @@
+use eframe::egui;
+use wine_maker::engine::{GRAPES, sugar_per_abv};
+use wine_maker::kit::{Kit, KitPlan, KitSize};
+
+/// Sets the simulator up from a wine kit's specs.
+pub struct KitPanel {
+    size: KitSize,
+    kit: Kit,
+}
+
+impl Default for KitPanel {
+    fn default() -> Self {
+        Self {
+            size: KitSize::default(),
+            kit: Kit::new(GRAPES[0], KitSize::default()),
+        }
+    }
+}
+
+impl KitPanel {
+    /// Returns the kit's plan when the user asks to simulate it.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<KitPlan> {
+        egui::Grid::new("kit").num_columns(2).show(ui, |ui| {
+            ui.label("Kit:");
+            egui::ComboBox::from_id_salt("kit_size")
+                .selected_text(self.size.label())
+                .show_ui(ui, |ui| {
+                    for size in KitSize::ALL {
+                        if ui
+                            .selectable_value(&mut self.size, size, size.label())
+                            .clicked()
+                        {
+                            self.kit = Kit::new(&self.kit.grape_type, size);
+                        }
+                    }
+                });
+            ui.end_row();
+            ui.label("Grape:");
+            egui::ComboBox::from_id_salt("kit_grape")
+                .selected_text(&self.kit.grape_type)
+                .show_ui(ui, |ui| {
+                    for grape in GRAPES {
+                        ui.selectable_value(&mut self.kit.grape_type, grape.to_owned(), grape);
+                    }
+                });
+            ui.end_row();
+            ui.label("Bag volume:");
+            ui.add(
+                egui::DragValue::new(&mut self.kit.concentrate)
+                    .range(1.0..=30.0)
+                    .speed(0.1)
+                    .suffix(" L"),
+            );
+            ui.end_row();
+            ui.label("Instructions:");
+            ui.horizontal(|ui| {
+                ui.label("make up to");
+                ui.add(
+                    egui::DragValue::new(&mut self.kit.batch_volume)
+                        .range(1.0..=60.0)
+                        .speed(0.1)
+                        .suffix(" L"),
+                );
+                ui.label("at SG");
+                ui.add(
+                    egui::DragValue::new(&mut self.kit.starting_gravity)
+                        .range(1.000..=1.150)
+                        .speed(0.001)
+                        .fixed_decimals(3),
+                );
+            });
+            ui.end_row();
+            ui.label("Water added:");
+            ui.add(
+                egui::DragValue::new(&mut self.kit.water)
+                    .range(0.0..=60.0)
+                    .speed(0.1)
+                    .suffix(" L"),
+            );
+            ui.end_row();
+        });
+
+        let plan = match self.kit.plan() {
+            Ok(plan) => plan,
+            Err(err) => {
+                ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+                return None;
+            }
+        };
+        ui.label(format!(
+            "{:.1} L starting at SG {:.3}, up to {:.1}% ABV.",
+            plan.volume,
+            plan.starting_gravity,
+            plan.input.sugar_content / sugar_per_abv()
+        ));
+        if let Some(rack_day) = plan.rack_day {
+            ui.label(format!("Day {}: rack to secondary at SG 1.010.", rack_day));
+        }
+        match (plan.stabilize_day, plan.bottle_day) {
+            (Some(stabilize_day), Some(bottle_day)) => {
+                ui.label(format!(
+                    "Day {}: stabilize and clear once below SG 0.996.",
+                    stabilize_day
+                ));
+                ui.label(format!("Day {}: bottle.", bottle_day));
+            }
+            _ => {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "The must is too strong to finish dry; add more water.",
+                );
+            }
+        }
+        ui.button("Use Kit")
+            .on_hover_text("Sets the simulator inputs from the kit.")
+            .clicked()
+            .then_some(plan)
+    }
+}
@@ -0,0 +1,160 @@
+use eframe::egui;
+use wine_maker::engine::{MODEL_VERSION, SimulationResult};
+use wine_maker::model_changelog::{CHANGELOG, is_outdated};
+
+/// What changed in the model, newest first, for working out why a saved
+/// result differs from a fresh run of the same inputs.
+#[derive(Default)]
+pub struct ModelChangesWindow {
+    pub open: bool,
+    /// The model version of the result being explained, whose later changes
+    /// are highlighted.
+    since: Option<u32>,
+    /// A loaded session's stored result, and what this model makes of the
+    /// same inputs, while asking which to keep.
+    offer: Option<(SimulationResult, SimulationResult)>,
+}
+
+impl ModelChangesWindow {
+    /// Opens the window on the changes since model `version`.
+    pub fn explain(&mut self, version: u32) {
+        self.open = true;
+        self.since = Some(version);
+    }
+
+    /// A note under a result from an older model, with a link to what
+    /// changed since. Nothing for a result from the current model.
+    pub fn note(&mut self, ui: &mut egui::Ui, version: u32) {
+        if !is_outdated(version) {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                match version {
+                    0 => "Simulated before results recorded their model version.".to_owned(),
+                    version => format!(
+                        "Simulated with model version {}; this release has version {}.",
+                        version, MODEL_VERSION
+                    ),
+                },
+            );
+            if ui.small_button("What Changed").clicked() {
+                self.explain(version);
+            }
+        });
+    }
+
+    /// Asks whether to replace `stored`, from an older model, with `fresh`.
+    pub fn offer(&mut self, stored: SimulationResult, fresh: SimulationResult) {
+        self.offer = Some((stored, fresh));
+    }
+
+    /// Asks about the offered result, returning true once the user chooses
+    /// to simulate again.
+    pub fn show_offer(&mut self, ctx: &egui::Context) -> bool {
+        let Some((stored, fresh)) = &self.offer else {
+            return false;
+        };
+        let version = stored.model_version;
+        let mut resimulate = false;
+        let mut keep = false;
+        let mut explain = false;
+        egui::Window::new("Saved With an Older Model")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(match version {
+                    0 => "This session's result was saved before results recorded their \
+                          model version, and this release's model gives a different one."
+                        .to_owned(),
+                    version => format!(
+                        "This session's result came from model version {}, and version {} \
+                         gives a different one.",
+                        version, MODEL_VERSION
+                    ),
+                });
+                egui::Grid::new("model_offer")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.strong("Stored");
+                        ui.strong("Simulated again");
+                        ui.end_row();
+                        let rows = [
+                            ("ABV", stored.actual_abv, fresh.actual_abv, "%"),
+                            (
+                                "Residual sugar",
+                                stored.residual_sugar,
+                                fresh.residual_sugar,
+                                " g/L",
+                            ),
+                        ];
+                        for (name, before, after, unit) in rows {
+                            ui.label(name);
+                            ui.label(format!("{:.1}{}", before, unit));
+                            ui.label(format!("{:.1}{}", after, unit));
+                            ui.end_row();
+                        }
+                        ui.label("Sweetness");
+                        ui.label(&stored.sweetness);
+                        ui.label(&fresh.sweetness);
+                        ui.end_row();
+                    });
+                ui.horizontal(|ui| {
+                    resimulate = ui.button("Re-simulate").clicked();
+                    keep = ui.button("Keep Stored Result").clicked();
+                    explain = ui.button("What Changed").clicked();
+                });
+            });
+        if explain {
+            self.explain(version);
+        }
+        if resimulate || keep {
+            self.offer = None;
+        }
+        resimulate
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        egui::Window::new("What Changed in the Model")
+            .open(&mut open)
+            .default_size([440.0, 360.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "This release simulates with model version {}. Results keep the \
+                     version that produced them, so the same inputs can give different \
+                     numbers after an update.",
+                    MODEL_VERSION
+                ));
+                if let Some(since) = self.since.filter(|&since| is_outdated(since)) {
+                    ui.label(format!(
+                        "Changes after version {} are marked; simulate again to see their effect.",
+                        since
+                    ));
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for change in CHANGELOG.iter().rev() {
+                        let newer = self.since.is_some_and(|since| change.version > since);
+                        let heading = format!("Version {}: {}", change.version, change.title);
+                        if newer {
+                            ui.colored_label(ui.visuals().warn_fg_color, heading);
+                        } else {
+                            ui.strong(heading);
+                        }
+                        for line in change.changes {
+                            ui.label(format!("• {}", line));
+                        }
+                        ui.add_space(6.0);
+                    }
+                });
+            });
+        self.open = open;
+        if !self.open {
+            self.since = None;
+        }
+    }
+}
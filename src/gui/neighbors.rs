@@ -0,0 +1,176 @@
+use eframe::egui;
+#[cfg(not(target_arch = "wasm32"))]
+use wine_maker::dataset::WINE_DATA_CSV;
+use wine_maker::engine::{SimulationInput, SimulationResult};
+use wine_maker::neighbors::{
+    ColumnMapping, ReferenceWine, csv_headers, describe, load_reference_wines, nearest_wines,
+};
+
+/// How many of the closest wines are listed.
+const SHOWN: usize = 3;
+
+/// A dataset being imported, while its columns are matched up.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+struct Import {
+    name: String,
+    text: String,
+    headers: Vec<String>,
+    mapping: ColumnMapping,
+}
+
+/// The real wines closest to the simulated one.
+#[derive(Default)]
+pub struct NeighborsPanel {
+    wines: Vec<ReferenceWine>,
+    /// Where `wines` came from, once loaded.
+    source: Option<String>,
+    import: Option<Import>,
+    error: Option<String>,
+}
+
+/// A combo box choosing one of `headers`, or none if `optional`.
+fn column_picker(
+    ui: &mut egui::Ui,
+    id: &str,
+    headers: &[String],
+    column: &mut Option<String>,
+    optional: bool,
+) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(column.as_deref().unwrap_or("(none)"))
+        .show_ui(ui, |ui| {
+            if optional {
+                ui.selectable_value(column, None, "(none)");
+            }
+            for header in headers {
+                ui.selectable_value(column, Some(header.clone()), header);
+            }
+        });
+}
+
+impl NeighborsPanel {
+    /// Starts matching up the columns of the CSV `text`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import(&mut self, name: String, text: String) {
+        match csv_headers(&text) {
+            Ok(headers) => {
+                let mapping = ColumnMapping::guess(&headers).unwrap_or_else(|| ColumnMapping {
+                    name: None,
+                    grape: headers.first().cloned().unwrap_or_default(),
+                    wine_type: None,
+                    abv: None,
+                    country: None,
+                    region: None,
+                });
+                self.import = Some(Import {
+                    name,
+                    text,
+                    headers,
+                    mapping,
+                });
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("Could not read {}: {}", name, err)),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pick_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .pick_file()
+        else {
+            return;
+        };
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        match std::fs::read_to_string(&path) {
+            Ok(text) => self.import(name, text),
+            Err(err) => self.error = Some(format!("Could not read {}: {}", name, err)),
+        }
+    }
+
+    /// Lets the user say which column holds what, then loads the wines.
+    fn show_import(&mut self, ui: &mut egui::Ui) {
+        let Some(import) = &mut self.import else {
+            return;
+        };
+        ui.label(format!("Which columns of {} hold:", import.name));
+        let mut loaded = None;
+        let mut cancelled = false;
+        egui::Grid::new("neighbors_mapping")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let headers = &import.headers;
+                let mapping = &mut import.mapping;
+                ui.label("Grape");
+                let mut grape = Some(mapping.grape.clone());
+                column_picker(ui, "mapping_grape", headers, &mut grape, false);
+                mapping.grape = grape.unwrap_or_default();
+                ui.end_row();
+                let optional = [
+                    ("Name", &mut mapping.name),
+                    ("Type (red or white)", &mut mapping.wine_type),
+                    ("ABV", &mut mapping.abv),
+                    ("Country", &mut mapping.country),
+                    ("Region", &mut mapping.region),
+                ];
+                for (label, column) in optional {
+                    ui.label(label);
+                    column_picker(ui, label, headers, column, true);
+                    ui.end_row();
+                }
+            });
+        ui.horizontal(|ui| {
+            if ui.button("Load").clicked() {
+                loaded = Some(load_reference_wines(&import.text, &import.mapping));
+            }
+            cancelled = ui.button("Cancel").clicked();
+        });
+        match loaded {
+            Some(Ok(wines)) => {
+                self.source = Some(format!("{} ({} wines)", import.name, wines.len()));
+                self.wines = wines;
+                self.import = None;
+            }
+            Some(Err(err)) => {
+                self.error = Some(format!("Could not load {}: {}", import.name, err));
+            }
+            None if cancelled => self.import = None,
+            None => {}
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, input: &SimulationInput, result: &SimulationResult) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.source.is_none() {
+            // Until another is imported, compare with the wines the simulator ships with.
+            self.wines =
+                load_reference_wines(WINE_DATA_CSV, &ColumnMapping::default()).unwrap_or_default();
+            self.source = Some(format!("the bundled dataset ({} wines)", self.wines.len()));
+        }
+
+        if self.import.is_some() {
+            self.show_import(ui);
+        } else if self.wines.is_empty() {
+            ui.label("No commercial wines to compare with yet.");
+        } else {
+            for neighbor in nearest_wines(input, result, &self.wines, SHOWN) {
+                ui.label(describe(&neighbor));
+            }
+        }
+        if let Some(source) = &self.source {
+            ui.small(format!("From {}.", source));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.import.is_none() && ui.small_button("Import Dataset…").clicked() {
+            self.pick_file();
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+}
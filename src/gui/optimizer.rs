@@ -0,0 +1,75 @@
+use eframe::egui;
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::SimulationInput;
+use wine_maker::optimizer::{Plan, SearchSpace, optimize};
+
+/// How many of the best plans to list.
+const TOP_PLANS: usize = 5;
+
+/// Searches fermentation length, temperature, yeast, and aging for the plans
+/// that score best with the simulator's fruit and sugar.
+#[derive(Default)]
+pub struct OptimizerPanel {
+    plans: Vec<Plan>,
+}
+
+impl OptimizerPanel {
+    /// Shows the panel. Returns a plan's input if it is picked to use.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        plan: &SimulationInput,
+        wine_data: &Dataset,
+    ) -> Option<SimulationInput> {
+        let space = SearchSpace::default();
+        if ui
+            .button("Find the best plans")
+            .on_hover_text(format!("Tries {} plans.", space.size()))
+            .clicked()
+        {
+            self.plans = optimize(plan, &space, wine_data, TOP_PLANS);
+        }
+        if self.plans.is_empty() {
+            return None;
+        }
+
+        let mut picked = None;
+        egui::Grid::new("optimizer")
+            .num_columns(8)
+            .striped(true)
+            .show(ui, |ui| {
+                for header in [
+                    "Score", "Days", "Temp", "Yeast", "Aging", "ABV", "Sugar", "",
+                ] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for best in &self.plans {
+                    let factors: Vec<String> = best
+                        .quality
+                        .factors
+                        .iter()
+                        .map(|(factor, points)| format!("{}: {:+.1}", factor, points))
+                        .collect();
+                    ui.label(format!("{:.0}", best.quality.score))
+                        .on_hover_text(factors.join("\n"));
+                    ui.label(best.input.fermentation_days.to_string());
+                    ui.label(format!("{:.0} °C", best.input.temperature));
+                    ui.label(&best.input.yeast);
+                    ui.label(format!("{} mo", best.input.aging_months));
+                    ui.label(format!("{:.1}%", best.result.actual_abv));
+                    ui.label(format!("{:.1} g/L", best.result.residual_sugar));
+                    if ui
+                        .small_button("Use")
+                        .on_hover_text(format!("Bottled after {} days.", best.days_to_bottle))
+                        .clicked()
+                    {
+                        picked = Some(best.input.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.label("Hover over a score to see what went into it.");
+        picked
+    }
+}
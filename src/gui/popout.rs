@@ -0,0 +1,58 @@
+use eframe::egui;
+
+/// A button that pops a panel out into its own window.
+pub fn button(ui: &mut egui::Ui, popped: &mut bool) {
+    if ui
+        .small_button("⬈ Pop Out")
+        .on_hover_text("Open in a separate window, e.g. to move it to another monitor.")
+        .clicked()
+    {
+        *popped = true;
+    }
+}
+
+/// Shows `add_contents` in its own OS window while `popped` is set, and
+/// clears it when that window is closed. Where there can only be one OS
+/// window, as in the web build, it floats inside the main one instead.
+pub fn show<R>(
+    ctx: &egui::Context,
+    popped: &mut bool,
+    title: &str,
+    size: [f32; 2],
+    mut add_contents: impl FnMut(&mut egui::Ui) -> R,
+) -> Option<R> {
+    if !*popped {
+        return None;
+    }
+    let id = egui::ViewportId::from_hash_of(title);
+    let builder = egui::ViewportBuilder::default()
+        .with_title(title)
+        .with_inner_size(size);
+    ctx.show_viewport_immediate(id, builder, |ctx, class| {
+        if class == egui::ViewportClass::Embedded {
+            egui::Window::new(title)
+                .open(popped)
+                .default_size(size)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical()
+                        .show(ui, |ui| add_contents(ui))
+                        .inner
+                })
+                .and_then(|response| response.inner)
+        } else {
+            if ctx.input(|input| input.viewport().close_requested()) {
+                *popped = false;
+            }
+            // Screenshots of charts in this window arrive here, not in the main one.
+            super::snapshot::handle_screenshots(ctx);
+            let inner = egui::CentralPanel::default()
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical()
+                        .show(ui, |ui| add_contents(ui))
+                        .inner
+                })
+                .inner;
+            Some(inner)
+        }
+    })
+}
@@ -0,0 +1,117 @@
+use eframe::egui;
+use tracing::info;
+use wine_maker::achievements::{Achievement, earned};
+use wine_maker::engine::{SimulationInput, SimulationResult};
+use wine_maker::store::Store;
+
+/// Achievements and milestones, kept in the store across sessions.
+#[derive(Default)]
+pub struct ProfileWindow {
+    pub open: bool,
+    wines_made: u32,
+    /// Achievements earned, with when.
+    earned: Vec<(Achievement, String)>,
+    /// Achievements earned since the window was last looked at.
+    new: Vec<Achievement>,
+}
+
+impl ProfileWindow {
+    pub fn load(&mut self, store: &Store) {
+        match store.wines_made() {
+            Ok(wines_made) => self.wines_made = wines_made,
+            Err(err) => tracing::error!("Could not read the wine count: {}", err),
+        }
+        match store.achievements() {
+            Ok(earned) => self.earned = earned,
+            Err(err) => tracing::error!("Could not read achievements: {}", err),
+        }
+    }
+
+    /// Counts a wine made and records any achievements it earns.
+    pub fn record(&mut self, store: &Store, input: &SimulationInput, result: &SimulationResult) {
+        let wines_made = match store.count_wine_made() {
+            Ok(wines_made) => wines_made,
+            Err(err) => {
+                tracing::error!("Could not count the wine: {}", err);
+                return;
+            }
+        };
+        for achievement in earned(input, result, wines_made) {
+            match store.record_achievement(achievement) {
+                Ok(true) => {
+                    info!(achievement = achievement.label(), "achievement earned");
+                    self.new.push(achievement);
+                }
+                Ok(false) => {}
+                Err(err) => tracing::error!("Could not save the achievement: {}", err),
+            }
+        }
+        self.load(store);
+    }
+
+    /// The button label, flagging newly earned achievements.
+    pub fn label(&self) -> String {
+        match self.new.len() {
+            0 => "Profile".to_owned(),
+            count => format!("Profile ({} new)", count),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        egui::Window::new("Profile")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} wines made, {} of {} achievements earned.",
+                    self.wines_made,
+                    self.earned.len(),
+                    Achievement::ALL.len()
+                ));
+                if let Some(next) = Achievement::ALL
+                    .into_iter()
+                    .filter_map(|achievement| achievement.wines_needed())
+                    .find(|&needed| needed > self.wines_made)
+                {
+                    ui.add(
+                        egui::ProgressBar::new(self.wines_made as f32 / next as f32)
+                            .text(format!("{} of {} wines", self.wines_made, next)),
+                    );
+                }
+                ui.separator();
+                egui::Grid::new("achievements")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for achievement in Achievement::ALL {
+                            match self
+                                .earned
+                                .iter()
+                                .find(|(earned, _)| *earned == achievement)
+                            {
+                                Some((_, earned_at)) => {
+                                    let label = if self.new.contains(&achievement) {
+                                        format!("★ {} (new)", achievement.label())
+                                    } else {
+                                        format!("★ {}", achievement.label())
+                                    };
+                                    ui.strong(label).on_hover_text(achievement.description());
+                                    ui.label(format!("Earned {}", earned_at));
+                                }
+                                None => {
+                                    ui.weak(format!("☆ {}", achievement.label()));
+                                    ui.weak(achievement.description());
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        // Once the window has been looked at and closed, nothing is new.
+        if !open {
+            self.new.clear();
+        }
+        self.open = open;
+    }
+}
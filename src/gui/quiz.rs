@@ -0,0 +1,124 @@
+use eframe::egui;
+use wine_maker::dataset::Dataset;
+use wine_maker::quiz::{Question, quiz};
+
+/// Questions in a quiz.
+const QUIZ_LENGTH: usize = 10;
+
+/// True-or-false questions checked against the model. A class can share a
+/// seed to all get the same quiz.
+pub struct QuizWindow {
+    pub open: bool,
+    seed: u64,
+    questions: Vec<Question>,
+    /// The question being asked.
+    current: usize,
+    /// The answer given to the current question, if any.
+    answer: Option<bool>,
+    correct: usize,
+    error: Option<String>,
+}
+
+impl Default for QuizWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            seed: 1,
+            questions: Vec::new(),
+            current: 0,
+            answer: None,
+            correct: 0,
+            error: None,
+        }
+    }
+}
+
+impl QuizWindow {
+    fn start(&mut self, wine_data: &Dataset) {
+        match quiz(self.seed, QUIZ_LENGTH, wine_data) {
+            Ok(questions) => {
+                self.questions = questions;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+        self.current = 0;
+        self.answer = None;
+        self.correct = 0;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, wine_data: &Dataset) {
+        let mut open = self.open;
+        egui::Window::new("Quiz")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Quiz number:");
+                    ui.add(egui::DragValue::new(&mut self.seed));
+                    if ui.button("New quiz").clicked() {
+                        self.start(wine_data);
+                    }
+                    if ui.button("Random").clicked() {
+                        self.seed = rand::random::<u32>().into();
+                        self.start(wine_data);
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                ui.separator();
+
+                let Some(question) = self.questions.get(self.current) else {
+                    if self.questions.is_empty() {
+                        ui.label("Start a quiz. Every answer is checked by running the model.");
+                    } else {
+                        ui.strong(format!(
+                            "{} of {} right.",
+                            self.correct,
+                            self.questions.len()
+                        ));
+                    }
+                    return;
+                };
+                ui.label(format!(
+                    "Question {} of {} ({})",
+                    self.current + 1,
+                    self.questions.len(),
+                    question.topic.label()
+                ));
+                ui.strong(&question.statement);
+                match self.answer {
+                    None => {
+                        ui.horizontal(|ui| {
+                            for (label, answer) in [("True", true), ("False", false)] {
+                                if ui.button(label).clicked() {
+                                    self.answer = Some(answer);
+                                    if question.is_correct(answer) {
+                                        self.correct += 1;
+                                    }
+                                }
+                            }
+                        });
+                    }
+                    Some(answer) => {
+                        if question.is_correct(answer) {
+                            ui.colored_label(egui::Color32::DARK_GREEN, "Right.");
+                        } else {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!("Wrong: it's {}.", question.answer),
+                            );
+                        }
+                        ui.label(&question.explanation);
+                        if ui.button("Next").clicked() {
+                            self.current += 1;
+                            self.answer = None;
+                        }
+                    }
+                }
+                ui.label(format!("Score: {} of {}", self.correct, self.current));
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::Local;
+use eframe::egui;
+use wine_maker::store::{DueTask, Store};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Watches for cellar tasks coming due while the app is running.
+#[derive(Default)]
+pub struct Reminders {
+    due: Vec<DueTask>,
+    /// Tasks already announced this session, by batch and task uid.
+    notified: HashSet<(i64, String)>,
+    checked_at: Option<f64>,
+}
+
+impl Reminders {
+    pub fn due(&self) -> &[DueTask] {
+        &self.due
+    }
+
+    /// Re-reads the due tasks on the next poll, as after the schedule changes.
+    pub fn refresh(&mut self) {
+        self.checked_at = None;
+    }
+
+    /// Re-reads the due tasks once a minute and raises a desktop
+    /// notification for any that are new.
+    pub fn poll(&mut self, ctx: &egui::Context, store: &Store) {
+        let now = ctx.input(|input| input.time);
+        ctx.request_repaint_after(CHECK_INTERVAL);
+        if self
+            .checked_at
+            .is_some_and(|checked_at| now - checked_at < CHECK_INTERVAL.as_secs_f64())
+        {
+            return;
+        }
+        self.checked_at = Some(now);
+
+        match store.due_tasks(Local::now().date_naive()) {
+            Ok(due) => self.due = due,
+            Err(err) => {
+                tracing::error!("Could not read due tasks: {}", err);
+                return;
+            }
+        }
+        let new: Vec<&DueTask> = self
+            .due
+            .iter()
+            .filter(|due| self.notified.insert((due.batch_id, due.task.uid.clone())))
+            .collect();
+        if !new.is_empty() {
+            tracing::info!(tasks = new.len(), "cellar tasks due");
+            #[cfg(not(target_arch = "wasm32"))]
+            notify(&new);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn notify(tasks: &[&DueTask]) {
+    let line = |due: &DueTask| format!("{}: {}", due.batch_name, due.task.title);
+    let (summary, body) = match tasks {
+        [due] => (line(due), format!("Due {}", due.task.due_on)),
+        _ => (
+            format!("{} cellar tasks are due", tasks.len()),
+            tasks
+                .iter()
+                .map(|due| line(due))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+    };
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("Wine Maker")
+        .summary(&summary)
+        .body(&body)
+        .show()
+    {
+        tracing::warn!("Could not show a notification: {}", err);
+    }
+}
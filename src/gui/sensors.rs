@@ -0,0 +1,273 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use eframe::egui;
+use wine_maker::cellar::Batch;
+#[cfg(feature = "mqtt")]
+use wine_maker::mqtt::MqttConfig;
+#[cfg(feature = "ble")]
+use wine_maker::sensors::{BleConfig, TiltColor};
+use wine_maker::sensors::{SensorEvent, SensorSource, SensorStream, connect};
+#[cfg(feature = "serial")]
+use wine_maker::sensors::{SerialConfig, serial_ports};
+use wine_maker::store::Store;
+
+/// Devices streaming every few seconds are logged at most this often.
+const LOG_INTERVAL: TimeDelta = TimeDelta::minutes(15);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Source {
+    #[cfg(feature = "mqtt")]
+    Mqtt,
+    #[cfg(feature = "serial")]
+    Serial,
+    #[cfg(feature = "ble")]
+    Tilt,
+}
+
+impl Source {
+    const ALL: &'static [Source] = &[
+        #[cfg(feature = "mqtt")]
+        Source::Mqtt,
+        #[cfg(feature = "serial")]
+        Source::Serial,
+        #[cfg(feature = "ble")]
+        Source::Tilt,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            #[cfg(feature = "mqtt")]
+            Source::Mqtt => "MQTT",
+            #[cfg(feature = "serial")]
+            Source::Serial => "Serial",
+            #[cfg(feature = "ble")]
+            Source::Tilt => "Tilt",
+        }
+    }
+}
+
+pub struct SensorPanel {
+    source: Source,
+    #[cfg(feature = "mqtt")]
+    host: String,
+    #[cfg(feature = "mqtt")]
+    port: String,
+    #[cfg(feature = "mqtt")]
+    gravity_topic: String,
+    #[cfg(feature = "mqtt")]
+    temperature_topic: String,
+    #[cfg(feature = "serial")]
+    serial_path: String,
+    #[cfg(feature = "serial")]
+    baud_rate: String,
+    #[cfg(feature = "ble")]
+    tilt_color: Option<TiltColor>,
+    /// The live stream and the batch its readings go to.
+    stream: Option<(SensorStream, Batch)>,
+    last_logged: Option<NaiveDateTime>,
+    status: String,
+}
+
+impl Default for SensorPanel {
+    fn default() -> Self {
+        #[cfg(feature = "mqtt")]
+        let mqtt = MqttConfig::default();
+        #[cfg(feature = "serial")]
+        let serial = SerialConfig::default();
+        Self {
+            source: Source::ALL[0],
+            #[cfg(feature = "mqtt")]
+            host: mqtt.host,
+            #[cfg(feature = "mqtt")]
+            port: mqtt.port.to_string(),
+            #[cfg(feature = "mqtt")]
+            gravity_topic: mqtt.gravity_topic,
+            #[cfg(feature = "mqtt")]
+            temperature_topic: mqtt.temperature_topic.unwrap_or_default(),
+            #[cfg(feature = "serial")]
+            serial_path: serial_ports().into_iter().next().unwrap_or_default(),
+            #[cfg(feature = "serial")]
+            baud_rate: serial.baud_rate.to_string(),
+            #[cfg(feature = "ble")]
+            tilt_color: None,
+            stream: None,
+            last_logged: None,
+            status: String::new(),
+        }
+    }
+}
+
+impl SensorPanel {
+    /// Stores any readings that arrived since the last frame, returning the
+    /// batch they went to.
+    pub fn poll(&mut self, store: &Store) -> Option<i64> {
+        let (stream, batch) = self.stream.as_ref()?;
+        let mut updated = None;
+        for event in stream.events().try_iter() {
+            match event {
+                SensorEvent::Reading(reading) => {
+                    self.status = format!(
+                        "Last reading {:.3} at {}",
+                        reading.specific_gravity,
+                        reading.taken_at.format("%H:%M:%S")
+                    );
+                    if self
+                        .last_logged
+                        .is_some_and(|logged| reading.taken_at - logged < LOG_INTERVAL)
+                    {
+                        continue;
+                    }
+                    let measurement =
+                        reading.to_measurement(batch.initial_sugar(), self.source.label());
+                    match store.add_measurement(batch.id, &measurement) {
+                        Ok(_) => {
+                            self.last_logged = Some(reading.taken_at);
+                            updated = Some(batch.id);
+                        }
+                        Err(err) => tracing::error!("Could not store sensor reading: {}", err),
+                    }
+                }
+                SensorEvent::Disconnected(err) => self.status = format!("Disconnected: {}", err),
+            }
+        }
+        updated
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, batch: &Batch) {
+        egui::CollapsingHeader::new("Live sensors").show(ui, |ui| {
+            if let Some((_, live_batch)) = &self.stream {
+                ui.label(format!(
+                    "Streaming {} readings into {}, logging one every {} minutes.",
+                    self.source.label(),
+                    live_batch.name,
+                    LOG_INTERVAL.num_minutes()
+                ));
+                if !self.status.is_empty() {
+                    ui.label(&self.status);
+                }
+                if ui.button("Disconnect").clicked() {
+                    self.stream = None;
+                    self.status.clear();
+                }
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                for &source in Source::ALL {
+                    ui.selectable_value(&mut self.source, source, source.label());
+                }
+            });
+            egui::Grid::new("sensor_source")
+                .num_columns(2)
+                .show(ui, |ui| match self.source {
+                    #[cfg(feature = "mqtt")]
+                    Source::Mqtt => {
+                        ui.label("Broker:");
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut self.host);
+                            ui.add(egui::TextEdit::singleline(&mut self.port).desired_width(50.0));
+                        });
+                        ui.end_row();
+                        ui.label("Gravity topic:");
+                        ui.text_edit_singleline(&mut self.gravity_topic);
+                        ui.end_row();
+                        ui.label("Temperature topic:");
+                        ui.text_edit_singleline(&mut self.temperature_topic);
+                        ui.end_row();
+                    }
+                    #[cfg(feature = "serial")]
+                    Source::Serial => {
+                        ui.label("Port:");
+                        egui::ComboBox::from_id_salt("serial_port")
+                            .selected_text(&self.serial_path)
+                            .show_ui(ui, |ui| {
+                                for path in serial_ports() {
+                                    ui.selectable_value(&mut self.serial_path, path.clone(), path);
+                                }
+                            });
+                        ui.end_row();
+                        ui.label("Baud rate:");
+                        ui.add(egui::TextEdit::singleline(&mut self.baud_rate).desired_width(70.0));
+                        ui.end_row();
+                    }
+                    #[cfg(feature = "ble")]
+                    Source::Tilt => {
+                        ui.label("Color:");
+                        egui::ComboBox::from_id_salt("tilt_color")
+                            .selected_text(self.tilt_color.map_or("Any", TiltColor::label))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.tilt_color, None, "Any");
+                                for color in TiltColor::ALL {
+                                    ui.selectable_value(
+                                        &mut self.tilt_color,
+                                        Some(color),
+                                        color.label(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                    }
+                });
+            if !self.status.is_empty() {
+                ui.label(&self.status);
+            }
+            if ui.button("Connect").clicked() {
+                self.connect(ui.ctx(), batch);
+            }
+        });
+    }
+
+    fn source(&self) -> Result<SensorSource, String> {
+        match self.source {
+            #[cfg(feature = "mqtt")]
+            Source::Mqtt => {
+                let Ok(port) = self.port.trim().parse::<u16>() else {
+                    return Err(format!("{:?} is not a port number", self.port));
+                };
+                let temperature_topic = self.temperature_topic.trim();
+                Ok(SensorSource::Mqtt(MqttConfig {
+                    host: self.host.trim().to_owned(),
+                    port,
+                    gravity_topic: self.gravity_topic.trim().to_owned(),
+                    temperature_topic: (!temperature_topic.is_empty())
+                        .then(|| temperature_topic.to_owned()),
+                }))
+            }
+            #[cfg(feature = "serial")]
+            Source::Serial => {
+                if self.serial_path.trim().is_empty() {
+                    return Err("Choose a serial port".to_owned());
+                }
+                let Ok(baud_rate) = self.baud_rate.trim().parse::<u32>() else {
+                    return Err(format!("{:?} is not a baud rate", self.baud_rate));
+                };
+                Ok(SensorSource::Serial(SerialConfig {
+                    path: self.serial_path.trim().to_owned(),
+                    baud_rate,
+                }))
+            }
+            #[cfg(feature = "ble")]
+            Source::Tilt => Ok(SensorSource::Ble(BleConfig {
+                color: self.tilt_color,
+            })),
+        }
+    }
+
+    fn connect(&mut self, ctx: &egui::Context, batch: &Batch) {
+        let source = match self.source() {
+            Ok(source) => source,
+            Err(err) => {
+                self.status = err;
+                return;
+            }
+        };
+        let ctx = ctx.clone();
+        match connect(&source, move || ctx.request_repaint()) {
+            Ok(stream) => {
+                self.stream = Some((stream, batch.clone()));
+                self.last_logged = None;
+                self.status.clear();
+            }
+            Err(err) => self.status = format!("Could not connect: {}", err),
+        }
+    }
+}
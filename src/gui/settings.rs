@@ -0,0 +1,598 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+use wine_maker::calibration::{
+    Calibration, Fit, K_REF_RANGE, ModelProfile, Q10_RANGE, SUGAR_PER_ABV_RANGE,
+};
+use wine_maker::conventions::{StrengthConvention, SugarConvention};
+use wine_maker::engine::{
+    MAX_YEAST_TEMPERATURE, MIN_YEAST_TEMPERATURE, RESTART_YEAST_TOLERANCE, SimulationInput,
+    YEAST_ALCOHOL_TOLERANCE,
+};
+#[cfg(feature = "scenario")]
+use wine_maker::scenario::{Scenario, ScenarioError};
+#[cfg(not(target_arch = "wasm32"))]
+use wine_maker::settings::RecentKind;
+use wine_maker::settings::{Settings, TemperatureUnit, Theme};
+#[cfg(feature = "webhook")]
+use wine_maker::webhook::{WebhookSettings, WebhookTarget};
+
+/// A change the rest of the app has to follow.
+pub enum SettingsEvent {
+    /// The temperature unit changed from this one.
+    TemperatureUnit(TemperatureUnit),
+    /// Save the current inputs as the starting inputs.
+    UseCurrentInputs,
+    /// Fit the model to the batches in the cellar.
+    #[cfg(feature = "sqlite")]
+    Calibrate,
+    /// Simulate on the profile of this name, or on the standard constants
+    /// if it is empty.
+    Profile(String),
+}
+
+/// Every preference in one window, saved to the settings file.
+#[derive(Default)]
+pub struct SettingsWindow {
+    pub open: bool,
+    pub settings: Settings,
+    /// Where the settings are saved, in the desktop app.
+    #[cfg(not(target_arch = "wasm32"))]
+    path: Option<PathBuf>,
+    /// The theme last handed to egui.
+    applied_theme: Option<Theme>,
+    /// The last fit to the cellar's batches, not yet saved as a profile.
+    fit: Option<Result<Fit, String>>,
+    /// The name a new profile is saved under.
+    new_profile: String,
+    error: Option<String>,
+}
+
+impl SettingsWindow {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_settings(&mut self, settings: Settings, path: PathBuf) {
+        self.settings = settings;
+        self.path = Some(path);
+    }
+
+    /// The constants of the profile called `name`, or the standard ones if
+    /// there is none.
+    pub fn calibration(&self, name: &str) -> Calibration {
+        self.settings
+            .profile_named(name)
+            .map_or_else(Calibration::default, |profile| profile.calibration)
+    }
+
+    /// The name of the profile `scenario` runs on. Constants of its own are
+    /// saved as a profile named for the scenario.
+    #[cfg(feature = "scenario")]
+    pub fn use_scenario_model(&mut self, scenario: &Scenario) -> Result<String, ScenarioError> {
+        let model = &scenario.model;
+        if !model.has_constants() {
+            let name = model.profile.clone().unwrap_or_default();
+            if !name.is_empty() && self.settings.profile_named(&name).is_none() {
+                return Err(ScenarioError::UnknownProfile(name));
+            }
+            return Ok(name);
+        }
+        let name = match scenario.name.trim() {
+            "" => "Imported scenario",
+            name => name,
+        };
+        self.settings.save_profile(ModelProfile {
+            name: name.to_owned(),
+            calibration: model.calibration(&self.settings.profiles)?,
+        });
+        self.save();
+        Ok(name.to_owned())
+    }
+
+    /// Picks the model profile one simulation runs on, beside the
+    /// simulator's inputs. Nothing until a profile has been saved.
+    pub fn profile_picker(&self, ui: &mut egui::Ui, picked: &mut String) {
+        // A deleted profile leaves the simulation on the standard constants.
+        if self.settings.profile_named(picked).is_none() {
+            picked.clear();
+        }
+        if self.settings.profiles.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Model profile:");
+            profile_combo(ui, "input_profile", &self.settings.profiles, picked);
+        });
+    }
+
+    /// Applies the theme, once when it changes. Called every frame.
+    pub fn apply(&mut self, ctx: &egui::Context) {
+        if self.applied_theme == Some(self.settings.theme) {
+            return;
+        }
+        ctx.set_theme(match self.settings.theme {
+            Theme::System => egui::ThemePreference::System,
+            Theme::Light => egui::ThemePreference::Light,
+            Theme::Dark => egui::ThemePreference::Dark,
+        });
+        self.applied_theme = Some(self.settings.theme);
+    }
+
+    /// Writes the settings out, in the desktop app.
+    pub fn save(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = &self.path {
+            self.error = self
+                .settings
+                .save(path)
+                .err()
+                .map(|err| format!("Could not save {}: {}", path.display(), err));
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_recent(&mut self, kind: RecentKind, path: PathBuf) {
+        self.settings.add_recent(kind, path);
+        self.save();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn remove_recent(&mut self, path: &Path) {
+        self.settings.remove_recent(path);
+        self.save();
+    }
+
+    pub fn set_fit(&mut self, fit: Result<Fit, String>) {
+        self.fit = Some(fit);
+    }
+
+    pub fn set_defaults(&mut self, input: SimulationInput) {
+        self.settings.defaults = Some(input);
+        self.save();
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<SettingsEvent> {
+        let mut event = None;
+        let mut changed = false;
+        let mut open = self.open;
+        let picked = self.settings.profile.clone();
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.strong("Appearance");
+                egui::Grid::new("settings_appearance")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Theme:");
+                        ui.horizontal(|ui| {
+                            for theme in Theme::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut self.settings.theme,
+                                        theme,
+                                        theme.label(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Temperature:");
+                        let unit = self.settings.temperature_unit;
+                        ui.horizontal(|ui| {
+                            for candidate in TemperatureUnit::ALL {
+                                ui.selectable_value(
+                                    &mut self.settings.temperature_unit,
+                                    candidate,
+                                    candidate.label(),
+                                );
+                            }
+                        });
+                        if self.settings.temperature_unit != unit {
+                            event = Some(SettingsEvent::TemperatureUnit(unit));
+                            changed = true;
+                        }
+                        ui.end_row();
+
+                        ui.label("Residual sugar:");
+                        ui.horizontal(|ui| {
+                            for convention in SugarConvention::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut self.settings.sugar_convention,
+                                        convention,
+                                        convention.label(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Strength:");
+                        ui.horizontal(|ui| {
+                            for convention in StrengthConvention::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut self.settings.strength_convention,
+                                        convention,
+                                        convention.label(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Locale:");
+                        changed |= ui
+                            .add(
+                                egui::TextEdit::singleline(&mut self.settings.locale)
+                                    .hint_text("en")
+                                    .desired_width(60.0),
+                            )
+                            .on_hover_text(
+                                "A language code such as fr, for a glossary.fr.csv translation. \
+                                 Takes effect on the next launch.",
+                            )
+                            .changed();
+                        ui.end_row();
+                    });
+
+                ui.separator();
+                ui.strong("Default values");
+                ui.label(match &self.settings.defaults {
+                    Some(defaults) => format!(
+                        "New sessions start from {} ({}).",
+                        defaults.grape_type,
+                        defaults.beverage.label()
+                    ),
+                    None => "New sessions start from the beverage's usual inputs.".to_owned(),
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Use Current Inputs").clicked() {
+                        event = Some(SettingsEvent::UseCurrentInputs);
+                    }
+                    if ui
+                        .add_enabled(self.settings.defaults.is_some(), egui::Button::new("Clear"))
+                        .clicked()
+                    {
+                        self.settings.defaults = None;
+                        changed = true;
+                    }
+                });
+
+                ui.separator();
+                ui.strong("Model profiles");
+                ui.horizontal(|ui| {
+                    ui.label("Simulate with:").on_hover_text(
+                        "The profile new simulations start on, and whose constants are shown \
+                         below. Each simulation can pick its own beside its inputs.",
+                    );
+                    changed |= profile_combo(
+                        ui,
+                        "settings_profile",
+                        &self.settings.profiles,
+                        &mut self.settings.profile,
+                    );
+                });
+                egui::Grid::new("settings_model")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let settings = &mut self.settings;
+                        match settings
+                            .profiles
+                            .iter_mut()
+                            .find(|profile| profile.name == settings.profile)
+                        {
+                            Some(profile) => {
+                                let calibration = &mut profile.calibration;
+                                let rows = [
+                                    (
+                                        "Rate constant at 20 °C (per day)",
+                                        &mut calibration.k_ref,
+                                        K_REF_RANGE,
+                                        3,
+                                    ),
+                                    ("Q10", &mut calibration.q10, Q10_RANGE, 2),
+                                    (
+                                        "Sugar per % ABV (g/L)",
+                                        &mut calibration.sugar_per_abv,
+                                        SUGAR_PER_ABV_RANGE,
+                                        2,
+                                    ),
+                                ];
+                                for (name, value, (low, high), decimals) in rows {
+                                    ui.label(name);
+                                    changed |= ui
+                                        .add(
+                                            egui::DragValue::new(value)
+                                                .range(low..=high)
+                                                .speed(0.001 * (high - low))
+                                                .fixed_decimals(decimals),
+                                        )
+                                        .changed();
+                                    ui.end_row();
+                                }
+                            }
+                            None => {
+                                let standard = Calibration::default();
+                                let rows = [
+                                    (
+                                        "Rate constant at 20 °C (per day)",
+                                        format!("{:.2}", standard.k_ref),
+                                    ),
+                                    ("Q10", format!("{:.1}", standard.q10)),
+                                    (
+                                        "Sugar per % ABV (g/L)",
+                                        format!("{:.2}", standard.sugar_per_abv),
+                                    ),
+                                ];
+                                for (name, value) in rows {
+                                    ui.label(name);
+                                    ui.label(value);
+                                    ui.end_row();
+                                }
+                            }
+                        }
+                        let rows = [
+                            (
+                                "Yeast tolerance (% ABV)",
+                                format!("{:.0}", YEAST_ALCOHOL_TOLERANCE),
+                            ),
+                            (
+                                "Restart yeast tolerance (% ABV)",
+                                format!("{:.0}", RESTART_YEAST_TOLERANCE),
+                            ),
+                            (
+                                "Yeast temperature range (°C)",
+                                format!(
+                                    "{:.0} to {:.0}",
+                                    MIN_YEAST_TEMPERATURE, MAX_YEAST_TEMPERATURE
+                                ),
+                            ),
+                        ];
+                        for (name, value) in rows {
+                            ui.label(name);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_profile)
+                            .hint_text("basement cellar")
+                            .desired_width(140.0),
+                    );
+                    let name = self.new_profile.trim();
+                    if ui
+                        .add_enabled(!name.is_empty(), egui::Button::new("Save as Profile"))
+                        .on_hover_text("Keep the constants above under this name.")
+                        .clicked()
+                    {
+                        let calibration = self
+                            .settings
+                            .selected_profile()
+                            .map_or_else(Calibration::default, |profile| profile.calibration);
+                        self.settings.save_profile(ModelProfile {
+                            name: name.to_owned(),
+                            calibration,
+                        });
+                        self.new_profile.clear();
+                        changed = true;
+                    }
+                    if ui
+                        .add_enabled(
+                            self.settings.selected_profile().is_some(),
+                            egui::Button::new("Delete Profile"),
+                        )
+                        .clicked()
+                    {
+                        let name = self.settings.profile.clone();
+                        self.settings.remove_profile(&name);
+                        changed = true;
+                    }
+                });
+                #[cfg(feature = "sqlite")]
+                if ui
+                    .button("Fit to Cellar Batches")
+                    .on_hover_text(
+                        "Refit the rate constant, Q10, and sugar per % ABV to the readings \
+                         logged on your batches.",
+                    )
+                    .clicked()
+                {
+                    event = Some(SettingsEvent::Calibrate);
+                }
+                match &self.fit {
+                    Some(Ok(fit)) => {
+                        ui.label(format!(
+                            "Fitted to {} readings from {} batches: rate constant {:.3} per day, \
+                             Q10 {:.2}, {:.2} g/L of sugar per % ABV. The readings are off by \
+                             {:.1} g/L, against {:.1} g/L with the standard constants.",
+                            fit.readings,
+                            fit.batches,
+                            fit.calibration.k_ref,
+                            fit.calibration.q10,
+                            fit.calibration.sugar_per_abv,
+                            fit.fitted_error,
+                            fit.standard_error
+                        ));
+                        let name = self.new_profile.trim();
+                        if ui
+                            .add_enabled(!name.is_empty(), egui::Button::new("Save Fit as Profile"))
+                            .on_disabled_hover_text("Name the profile first.")
+                            .clicked()
+                        {
+                            self.settings.save_profile(ModelProfile {
+                                name: name.to_owned(),
+                                calibration: fit.calibration,
+                            });
+                            self.new_profile.clear();
+                            self.fit = None;
+                            changed = true;
+                        }
+                    }
+                    Some(Err(err)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, err);
+                    }
+                    None => {}
+                }
+
+                #[cfg(feature = "webhook")]
+                {
+                    ui.separator();
+                    ui.strong("Sending results");
+                    changed |= show_webhook(ui, &mut self.settings.webhook);
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.separator();
+                    ui.strong("Data directory");
+                    let mut custom = self.settings.data_dir.is_some();
+                    if ui
+                        .checkbox(&mut custom, "Keep history and the glossary somewhere else")
+                        .changed()
+                    {
+                        self.settings.data_dir = custom.then(PathBuf::new);
+                        changed = true;
+                    }
+                    if let Some(dir) = &mut self.settings.data_dir {
+                        ui.horizontal(|ui| {
+                            ui.label(if dir.as_os_str().is_empty() {
+                                "No folder chosen".to_owned()
+                            } else {
+                                dir.display().to_string()
+                            });
+                            if ui.button("Choose…").clicked()
+                                && let Some(picked) = rfd::FileDialog::new().pick_folder()
+                            {
+                                *dir = picked;
+                                changed = true;
+                            }
+                        });
+                    }
+                    ui.label("Takes effect on the next launch.");
+                    if let Some(path) = &self.path {
+                        ui.small(format!("Settings are saved in {}", path.display()));
+                    }
+                }
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        self.open = open;
+        if self.settings.profile != picked {
+            event = Some(SettingsEvent::Profile(self.settings.profile.clone()));
+        }
+        if changed {
+            self.save();
+        }
+        event
+    }
+}
+
+/// A menu of `profiles` and the standard constants, returning whether the
+/// pick changed.
+fn profile_combo(
+    ui: &mut egui::Ui,
+    id: &str,
+    profiles: &[ModelProfile],
+    picked: &mut String,
+) -> bool {
+    let before = picked.clone();
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(if picked.is_empty() {
+            "Standard"
+        } else {
+            picked.as_str()
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(picked, String::new(), "Standard");
+            for profile in profiles {
+                ui.selectable_value(picked, profile.name.clone(), &profile.name);
+            }
+        });
+    *picked != before
+}
+
+#[cfg(feature = "webhook")]
+const WEBHOOK_KINDS: [&str; 3] = ["Nowhere", "Webhook", "Google Sheets"];
+
+/// The webhook or Google Sheet results go to. Returns whether anything
+/// changed.
+#[cfg(feature = "webhook")]
+fn show_webhook(ui: &mut egui::Ui, webhook: &mut WebhookSettings) -> bool {
+    let mut changed = false;
+    let current = match &webhook.target {
+        None => 0,
+        Some(WebhookTarget::Webhook { .. }) => 1,
+        Some(WebhookTarget::GoogleSheets { .. }) => 2,
+    };
+    let mut picked = current;
+    ui.horizontal(|ui| {
+        ui.label("Send to:");
+        egui::ComboBox::from_id_salt("settings_webhook")
+            .selected_text(WEBHOOK_KINDS[current])
+            .show_ui(ui, |ui| {
+                for (i, kind) in WEBHOOK_KINDS.iter().enumerate() {
+                    ui.selectable_value(&mut picked, i, *kind);
+                }
+            });
+    });
+    if picked != current {
+        webhook.target = match picked {
+            1 => Some(WebhookTarget::Webhook { url: String::new() }),
+            2 => Some(WebhookTarget::GoogleSheets {
+                spreadsheet_id: String::new(),
+                sheet: "Sheet1".to_owned(),
+                access_token: String::new(),
+            }),
+            _ => None,
+        };
+        changed = true;
+    }
+    let Some(target) = &mut webhook.target else {
+        return changed;
+    };
+    egui::Grid::new("settings_webhook_target")
+        .num_columns(2)
+        .show(ui, |ui| match target {
+            WebhookTarget::Webhook { url } => {
+                ui.label("URL:");
+                changed |= ui.text_edit_singleline(url).changed();
+                ui.end_row();
+            }
+            WebhookTarget::GoogleSheets {
+                spreadsheet_id,
+                sheet,
+                access_token,
+            } => {
+                ui.label("Spreadsheet ID:");
+                changed |= ui.text_edit_singleline(spreadsheet_id).changed();
+                ui.end_row();
+                ui.label("Sheet:");
+                changed |= ui.text_edit_singleline(sheet).changed();
+                ui.end_row();
+                ui.label("Access token:");
+                changed |= ui
+                    .add(egui::TextEdit::singleline(access_token).password(true))
+                    .on_hover_text(
+                        "An OAuth token with the spreadsheets scope, such as from \
+                         gcloud auth print-access-token. Tokens last an hour.",
+                    )
+                    .changed();
+                ui.end_row();
+            }
+        });
+    changed |= ui
+        .checkbox(
+            &mut webhook.send_each_run,
+            "Send each simulation as it is run",
+        )
+        .changed();
+    ui.label("History and batch logs can also be sent by hand.");
+    changed
+}
@@ -0,0 +1,150 @@
+use eframe::egui;
+use wine_maker::engine::SimulationInput;
+use wine_maker::permalink::{URL_KEY, decode, encode};
+use wine_maker::qr::{QrImage, recipe_summary};
+
+/// Pixels per module of the QR code on screen.
+const MODULE_SIZE: f32 = 3.0;
+/// Pixels per module of a saved QR code, enough to print sharply on a label.
+#[cfg(not(target_arch = "wasm32"))]
+const SAVED_MODULE_SIZE: usize = 8;
+
+/// What the QR code holds.
+#[derive(Clone, Copy, PartialEq, Default)]
+enum QrContent {
+    /// The share code or link, so scanning it reopens the simulation.
+    #[default]
+    Link,
+    /// A recipe to read on a phone.
+    Recipe,
+}
+
+/// Shares the current inputs as a code, and loads codes from others.
+#[derive(Default)]
+pub struct ShareWindow {
+    pub open: bool,
+    /// The page a link is built from, in the web build.
+    link_base: Option<String>,
+    pasted: String,
+    error: Option<String>,
+    qr_content: QrContent,
+    /// The text last turned into a QR code, and the code.
+    qr: Option<(String, Result<QrImage, String>)>,
+}
+
+impl ShareWindow {
+    pub fn set_link_base(&mut self, link_base: String) {
+        self.link_base = Some(link_base);
+    }
+
+    /// Shows the window, returning the inputs from a code loaded in it.
+    pub fn show(&mut self, ctx: &egui::Context, plan: &SimulationInput) -> Option<SimulationInput> {
+        let mut loaded = None;
+        let mut open = self.open;
+        egui::Window::new("Share")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let code = encode(plan);
+                let shared = match &self.link_base {
+                    Some(base) => format!("{}#{}={}", base, URL_KEY, code),
+                    None => code,
+                };
+                ui.label("Anyone who loads this gets exactly the same simulation, seed included:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut shared.as_str())
+                            .desired_width(320.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    if ui.button("Copy").clicked() {
+                        ctx.copy_text(shared.clone());
+                    }
+                });
+                if plan.seed.is_none() {
+                    ui.label("Without a seed, the tasting notes are still picked at random.");
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("QR code for a label:");
+                    ui.selectable_value(&mut self.qr_content, QrContent::Link, "Link")
+                        .on_hover_text("Scanning it opens this simulation again.");
+                    ui.selectable_value(&mut self.qr_content, QrContent::Recipe, "Recipe")
+                        .on_hover_text("Scanning it shows the recipe as text.");
+                });
+                let text = match self.qr_content {
+                    QrContent::Link => shared,
+                    QrContent::Recipe => recipe_summary(plan),
+                };
+                self.show_qr(ui, text);
+
+                ui.separator();
+                ui.label("Paste a code or link to load it:");
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.pasted).desired_width(320.0));
+                    if ui.button("Load").clicked() {
+                        match decode(&self.pasted) {
+                            Ok(input) => {
+                                loaded = Some(input);
+                                self.pasted.clear();
+                                self.error = None;
+                            }
+                            Err(err) => self.error = Some(err.to_string()),
+                        }
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        self.open = open;
+        loaded
+    }
+
+    fn show_qr(&mut self, ui: &mut egui::Ui, text: String) {
+        if self.qr.as_ref().is_none_or(|(encoded, _)| *encoded != text) {
+            let image = QrImage::new(&text).map_err(|err| err.to_string());
+            self.qr = Some((text, image));
+        }
+        let Some((_, image)) = &self.qr else {
+            return;
+        };
+        let image = match image {
+            Ok(image) => image,
+            Err(err) => {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+                return;
+            }
+        };
+        // Light modules all round, as scanners need.
+        let side = (image.width() + 8) as f32 * MODULE_SIZE;
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(side, side), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+        for y in 0..image.width() {
+            for x in 0..image.width() {
+                if image.is_dark(x, y) {
+                    let min = rect.min + egui::vec2(x as f32 + 4.0, y as f32 + 4.0) * MODULE_SIZE;
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(min, egui::Vec2::splat(MODULE_SIZE)),
+                        0.0,
+                        egui::Color32::BLACK,
+                    );
+                }
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button("Save QR Code…").clicked()
+            && let Some(path) = rfd::FileDialog::new()
+                .add_filter("SVG image", &["svg"])
+                .set_file_name("wine-qr.svg")
+                .save_file()
+        {
+            match std::fs::write(&path, image.to_svg(SAVED_MODULE_SIZE)) {
+                Ok(()) => tracing::info!(path = %path.display(), "QR code saved"),
+                Err(err) => tracing::error!("Could not save the QR code: {}", err),
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use eframe::egui;
+
+/// What to do with a chart once the screenshot of it arrives.
+enum Action {
+    Copy,
+    #[cfg(not(target_arch = "wasm32"))]
+    Save(&'static str),
+}
+
+/// A chart waiting for the next frame's screenshot, which is cropped to it.
+struct Request {
+    rect: egui::Rect,
+    action: Action,
+}
+
+/// Buttons to copy the chart drawn in `rect` as an image, or save it as a
+/// PNG named `file_name`, so it can go in a forum post without a
+/// screenshot tool.
+pub fn chart_buttons(ui: &mut egui::Ui, rect: egui::Rect, file_name: &'static str) {
+    #[cfg(target_arch = "wasm32")]
+    let _ = file_name;
+    ui.horizontal(|ui| {
+        if ui.small_button("Copy Chart").clicked() {
+            request(ui.ctx(), rect, Action::Copy);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.small_button("Save PNG…").clicked() {
+            request(ui.ctx(), rect, Action::Save(file_name));
+        }
+    });
+}
+
+fn request(ctx: &egui::Context, rect: egui::Rect, action: Action) {
+    ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot(egui::UserData::new(
+        Request { rect, action },
+    )));
+}
+
+/// Crops the screenshots asked for by `chart_buttons` and copies or saves
+/// them. Called once a frame.
+pub fn handle_screenshots(ctx: &egui::Context) {
+    let screenshots: Vec<(Arc<egui::ColorImage>, egui::UserData)> = ctx.input(|input| {
+        input
+            .raw
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                egui::Event::Screenshot {
+                    image, user_data, ..
+                } => Some((image.clone(), user_data.clone())),
+                _ => None,
+            })
+            .collect()
+    });
+    for (image, user_data) in screenshots {
+        let Some(request) = user_data
+            .data
+            .as_ref()
+            .and_then(|data| data.downcast_ref::<Request>())
+        else {
+            continue;
+        };
+        let chart = image.region(&request.rect, Some(ctx.pixels_per_point()));
+        match request.action {
+            Action::Copy => ctx.copy_image(chart),
+            #[cfg(not(target_arch = "wasm32"))]
+            Action::Save(file_name) => save_png(&chart, file_name),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_png(chart: &egui::ColorImage, file_name: &str) {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("PNG image", &["png"])
+        .set_file_name(file_name)
+        .save_file()
+    else {
+        return;
+    };
+    let [width, height] = chart.size;
+    match image::save_buffer(
+        &path,
+        chart.as_raw(),
+        width as u32,
+        height as u32,
+        image::ExtendedColorType::Rgba8,
+    ) {
+        Ok(()) => tracing::info!(path = %path.display(), "chart saved"),
+        Err(err) => tracing::error!("Could not save the chart: {}", err),
+    }
+}
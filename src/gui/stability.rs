@@ -0,0 +1,82 @@
+use eframe::egui;
+use wine_maker::engine::{SimulationInput, SimulationResult};
+use wine_maker::stability::{BottlingConditions, Risk, stability_report};
+
+/// Checks the last simulated wine against how it will be bottled and kept.
+#[derive(Default)]
+pub struct StabilityPanel {
+    conditions: BottlingConditions,
+}
+
+impl StabilityPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, input: &SimulationInput, result: &SimulationResult) {
+        let conditions = &mut self.conditions;
+        egui::Grid::new("stability").num_columns(2).show(ui, |ui| {
+            ui.label("Volume:");
+            ui.add(
+                egui::DragValue::new(&mut conditions.volume)
+                    .range(1.0..=1000.0)
+                    .suffix(" L"),
+            );
+            ui.end_row();
+            ui.label("Stabilized:");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut conditions.sterile_filtered, "Sterile filtered");
+                ui.checkbox(&mut conditions.sorbate, "Potassium sorbate");
+            });
+            ui.end_row();
+            ui.label("Free SO2:");
+            ui.add(
+                egui::DragValue::new(&mut conditions.free_so2)
+                    .range(0.0..=100.0)
+                    .suffix(" mg/L"),
+            );
+            ui.end_row();
+            ui.label("pH:");
+            ui.add(
+                egui::DragValue::new(&mut conditions.ph)
+                    .range(2.8..=4.2)
+                    .speed(0.01),
+            );
+            ui.end_row();
+            ui.label("Bentonite:");
+            ui.add(
+                egui::DragValue::new(&mut conditions.bentonite)
+                    .range(0.0..=200.0)
+                    .suffix(" g/hL"),
+            );
+            ui.end_row();
+            ui.label("Storage temperature:");
+            ui.add(
+                egui::DragValue::new(&mut conditions.storage_temperature)
+                    .range(0.0..=40.0)
+                    .suffix(" °C"),
+            );
+            ui.end_row();
+        });
+
+        let report = stability_report(input, result, &self.conditions);
+        let refermentation = &report.refermentation;
+        ui.label(format!(
+            "Refermentation risk: {} ({:.0}%), with {:.1} g/L of sugar that could build {:.1} bar.",
+            refermentation.risk.label(),
+            refermentation.probability * 100.0,
+            refermentation.fermentable_sugar,
+            refermentation.pressure
+        ));
+        if let Some(protein) = &report.protein {
+            ui.label(format!(
+                "Protein left: {:.0} mg/L; {:.0} g of bentonite goes in.",
+                protein.residual_protein, protein.grams
+            ));
+        }
+        if report.is_stable() && refermentation.risk == Risk::Negligible {
+            ui.strong("Stable: ready to bottle.");
+        } else if report.is_stable() {
+            ui.strong("Stable enough to bottle.");
+        }
+        for warning in report.warnings() {
+            ui.colored_label(ui.visuals().warn_fg_color, warning);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use eframe::egui;
+use wine_maker::engine::{InterventionKind, SimulationInput};
+use wine_maker::stepper::Stepper;
+
+/// Steps through the plan a day at a time, showing every variable and
+/// letting conditions change partway.
+pub struct StepperWindow {
+    pub open: bool,
+    stepper: Option<Stepper>,
+    error: Option<String>,
+    temperature: f64,
+    kind: InterventionKind,
+    amount: f64,
+}
+
+impl Default for StepperWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            stepper: None,
+            error: None,
+            temperature: InterventionKind::Temperature.default_amount(),
+            kind: InterventionKind::Nutrient,
+            amount: InterventionKind::Nutrient.default_amount(),
+        }
+    }
+}
+
+impl StepperWindow {
+    /// Shows the window, starting from `plan`. Returns the input with the
+    /// changes made while stepping if it is sent back to the simulator.
+    pub fn show(&mut self, ctx: &egui::Context, plan: &SimulationInput) -> Option<SimulationInput> {
+        let mut open = self.open;
+        let mut use_as_plan = None;
+        egui::Window::new("Step through")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Start from plan").clicked() {
+                        self.start(plan);
+                    }
+                    if let Some(stepper) = &self.stepper
+                        && ui.button("Use as plan").clicked()
+                    {
+                        use_as_plan = Some(stepper.input().clone());
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                if let Some(stepper) = &mut self.stepper {
+                    Self::show_controls(ui, stepper);
+                }
+                self.show_tweaks(ui);
+                self.show_state(ui);
+            });
+        self.open = open;
+        use_as_plan
+    }
+
+    fn start(&mut self, plan: &SimulationInput) {
+        self.temperature = plan.temperature;
+        match Stepper::new(plan.clone()) {
+            Ok(stepper) => {
+                self.stepper = Some(stepper);
+                self.error = None;
+            }
+            Err(err) => {
+                self.stepper = None;
+                self.error = Some(err.to_string());
+            }
+        }
+    }
+
+    fn show_controls(ui: &mut egui::Ui, stepper: &mut Stepper) {
+        ui.horizontal(|ui| {
+            if ui.button("◀ Day").clicked() {
+                stepper.back(1);
+            }
+            if ui.button("Day ▶").clicked() {
+                stepper.step(1);
+            }
+            if ui.button("Week ▶").clicked() {
+                stepper.step(7);
+            }
+            if ui.button("Next stage ▶").clicked() {
+                // Out-of-range temperatures are caught when stepping starts.
+                let _ = stepper.next_stage();
+            }
+        });
+        let mut day = stepper.day();
+        if ui
+            .add(egui::Slider::new(&mut day, 0..=stepper.last_day()).text("day"))
+            .changed()
+        {
+            stepper.go_to(day);
+        }
+    }
+
+    fn show_tweaks(&mut self, ui: &mut egui::Ui) {
+        let Some(stepper) = &mut self.stepper else {
+            return;
+        };
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.temperature)
+                    .range(0.0..=45.0)
+                    .speed(0.1)
+                    .suffix(" °C"),
+            );
+            if ui.button("Set temperature from today").clicked() {
+                stepper.set_temperature(self.temperature);
+            }
+        });
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("stepper_intervention")
+                .selected_text(self.kind.label())
+                .show_ui(ui, |ui| {
+                    for kind in InterventionKind::ALL {
+                        if kind != InterventionKind::Temperature
+                            && ui
+                                .selectable_value(&mut self.kind, kind, kind.label())
+                                .clicked()
+                        {
+                            self.amount = kind.default_amount();
+                        }
+                    }
+                });
+            if let Some(unit) = self.kind.unit() {
+                ui.add(
+                    egui::DragValue::new(&mut self.amount)
+                        .speed(0.1)
+                        .suffix(format!(" {}", unit)),
+                );
+            }
+            if ui.button("Add today").clicked() {
+                stepper.intervene(self.kind, self.amount);
+            }
+        });
+    }
+
+    fn show_state(&self, ui: &mut egui::Ui) {
+        let Some(stepper) = &self.stepper else {
+            ui.label("Start from the simulator's inputs to step through them day by day.");
+            return;
+        };
+        ui.separator();
+        match stepper.state() {
+            Ok(state) => {
+                egui::Grid::new("stepper_state")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (label, value) in state.fields() {
+                            ui.label(label);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+            }
+            Err(err) => {
+                ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use eframe::egui;
+use wine_maker::dataset::Dataset;
+use wine_maker::grapes::{StyleGoal, suggest_grapes};
+
+/// How many of the best grapes are offered.
+const SHOWN: usize = 4;
+
+/// Ranks grapes for the style of wine wanted in the chosen climate.
+#[derive(Default)]
+pub struct SuggestPanel {
+    goal: StyleGoal,
+}
+
+impl SuggestPanel {
+    /// Returns the grape the user picks.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        dataset: &Dataset,
+        climate: &str,
+    ) -> Option<&'static str> {
+        ui.horizontal(|ui| {
+            ui.label("I'd like a");
+            egui::ComboBox::from_id_salt("suggest_goal")
+                .selected_text(self.goal.label())
+                .show_ui(ui, |ui| {
+                    for goal in StyleGoal::ALL {
+                        ui.selectable_value(&mut self.goal, goal, goal.label());
+                    }
+                });
+            ui.label(format!("from a {} climate.", climate.to_lowercase()));
+        });
+        let suggestions = suggest_grapes(dataset, climate, self.goal);
+        if suggestions.is_empty() {
+            ui.label("The dataset has no wines in this style.");
+            return None;
+        }
+        let mut picked = None;
+        egui::Grid::new("suggestions")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for suggestion in suggestions.iter().take(SHOWN) {
+                    ui.label(suggestion.grape);
+                    let note = format!(
+                        "{} of {} wines in this style",
+                        suggestion.in_style, suggestion.wines
+                    );
+                    if suggestion.suits_climate {
+                        ui.label(note);
+                    } else {
+                        ui.label(format!("{}; struggles to ripen here", note));
+                    }
+                    if ui.small_button("Use").clicked() {
+                        picked = Some(suggestion.grape);
+                    }
+                    ui.end_row();
+                }
+            });
+        picked
+    }
+}
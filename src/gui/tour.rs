@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+/// A part of the interface the tour points out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    Beverage,
+    Grape,
+    Days,
+    Container,
+    Sugar,
+    Temperature,
+    Yeast,
+    Simulate,
+    Results,
+    Compare,
+    Save,
+}
+
+struct Step {
+    title: &'static str,
+    text: &'static str,
+    target: Option<Target>,
+}
+
+const STEPS: [Step; 13] = [
+    Step {
+        title: "Welcome",
+        text: "This tour walks through the simulator's inputs, runs a sample wine, and shows where to look at and keep the results. Skip it at any time; it's under Help whenever you want it back.",
+        target: None,
+    },
+    Step {
+        title: "What you're making",
+        text: "Start by picking wine, mead, cider, or fruit wine. Switching fills in sensible defaults for it.",
+        target: Some(Target::Beverage),
+    },
+    Step {
+        title: "The fruit",
+        text: "Pick the grape, honey, apple, or fruit. Reds get extra controls for whole clusters and press wine.",
+        target: Some(Target::Grape),
+    },
+    Step {
+        title: "Fermentation length",
+        text: "How many days the yeast work before the wine is pressed or racked off. Stop early and sugar is left behind.",
+        target: Some(Target::Days),
+    },
+    Step {
+        title: "The fermenter",
+        text: "Oak lets in a little oxygen and adds tannin; steel keeps things fresh and clean. Hover a container for its details.",
+        target: Some(Target::Container),
+    },
+    Step {
+        title: "Sugar",
+        text: "The must's sugar sets how strong the wine can get: about 17 g/L for each 1% ABV. The ? buttons open the glossary.",
+        target: Some(Target::Sugar),
+    },
+    Step {
+        title: "Temperature",
+        text: "Warm ferments run fast and can lose aroma or stall; cool ones are slow and fruity.",
+        target: Some(Target::Temperature),
+    },
+    Step {
+        title: "Yeast",
+        text: "Strains differ in how much alcohol they take, how warm they like it, and the aromas they make.",
+        target: Some(Target::Yeast),
+    },
+    Step {
+        title: "Run it",
+        text: "Press Simulate to ferment. Try a sample Cabernet Sauvignon now.",
+        target: Some(Target::Simulate),
+    },
+    Step {
+        title: "Results",
+        text: "The result reads like a tasting note: strength, sweetness, acidity, color, and how the fermentation went.",
+        target: Some(Target::Results),
+    },
+    Step {
+        title: "Plots",
+        text: "Open these sections for a stability report and a radar chart comparing your wine with a classic. Step through plots the fermentation day by day.",
+        target: Some(Target::Compare),
+    },
+    Step {
+        title: "Keeping it",
+        text: "File > Save Session writes the inputs and result to a session file you can open again or share. Recent sessions are a click away under File > Recent.",
+        target: Some(Target::Save),
+    },
+    Step {
+        title: "That's it",
+        text: "Change one input at a time and simulate again to see what it does. Help brings this tour back.",
+        target: None,
+    },
+];
+
+/// What the tour asks of the app.
+pub enum TourEvent {
+    /// Run the sample wine.
+    RunSample,
+    /// The tour was finished or skipped.
+    Dismissed,
+}
+
+/// A guided tour over the simulator that highlights each part in turn.
+pub struct Tour {
+    pub open: bool,
+    step: usize,
+    /// Where each target was drawn this frame.
+    targets: HashMap<Target, egui::Rect>,
+}
+
+impl Default for Tour {
+    /// Starts open, for a first launch.
+    fn default() -> Self {
+        Self {
+            open: true,
+            step: 0,
+            targets: HashMap::new(),
+        }
+    }
+}
+
+impl Tour {
+    pub fn start(&mut self) {
+        self.open = true;
+        self.step = 0;
+    }
+
+    /// Notes where `target` was drawn, so the tour can point at it.
+    pub fn mark(&mut self, target: Target, rect: egui::Rect) {
+        if self.open {
+            self.targets.insert(target, rect);
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<TourEvent> {
+        let step = &STEPS[self.step];
+        let highlight = step
+            .target
+            .and_then(|target| self.targets.get(&target))
+            .copied();
+        if let Some(rect) = highlight {
+            ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("tour_highlight"),
+            ))
+            .rect_stroke(
+                rect.expand(4.0),
+                4.0,
+                egui::Stroke::new(3.0, ctx.style().visuals.selection.bg_fill),
+                egui::StrokeKind::Outside,
+            );
+        }
+
+        let mut event = None;
+        let mut window = egui::Window::new("Tour")
+            .id(egui::Id::new("tour"))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(320.0);
+        window = match highlight {
+            Some(rect) => window.current_pos(rect.right_top() + egui::vec2(24.0, 0.0)),
+            None => window.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO),
+        };
+        window.show(ctx, |ui| {
+            ui.strong(step.title);
+            ui.label(step.text);
+            if step.target == Some(Target::Simulate) && ui.button("Run a sample").clicked() {
+                event = Some(TourEvent::RunSample);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(format!("{} of {}", self.step + 1, STEPS.len()));
+                if self.step > 0 && ui.button("Back").clicked() {
+                    self.step -= 1;
+                }
+                if self.step + 1 < STEPS.len() {
+                    if ui.button("Next").clicked() {
+                        self.step += 1;
+                    }
+                    if ui.button("Skip tour").clicked() {
+                        event = Some(TourEvent::Dismissed);
+                    }
+                } else if ui.button("Done").clicked() {
+                    event = Some(TourEvent::Dismissed);
+                }
+            });
+        });
+        if let Some(TourEvent::Dismissed) = event {
+            self.open = false;
+        }
+        // Targets are marked afresh each frame, so ones no longer shown drop out.
+        self.targets.clear();
+        event
+    }
+}
@@ -0,0 +1,229 @@
+#[cfg(feature = "weather")]
+use std::sync::mpsc::{self, Receiver};
+
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, VLine};
+use wine_maker::vineyard::{
+    Harvest, LATEST_HARVEST_DAY, REGIONS, Region, TYPICAL_HARVEST_DAY, Vintage,
+};
+#[cfg(feature = "weather")]
+use wine_maker::weather::{self, ClimateNormals, WeatherError};
+
+const ROMAN: [&str; 5] = ["I", "II", "III", "IV", "V"];
+
+/// What the simulator inputs should be set from.
+pub struct Picked {
+    pub harvest: Harvest,
+    /// Set when a region was just chosen, so its classic wine is set up too.
+    pub region: Option<Region>,
+}
+
+/// The optional vineyard stage above the simulator inputs.
+pub struct VineyardPanel {
+    /// The preset the season started from, until one is looked up.
+    region: Option<usize>,
+    vintage: Vintage,
+    harvest_day: u32,
+    #[cfg(feature = "weather")]
+    location: String,
+    #[cfg(feature = "weather")]
+    lookup: Option<Receiver<Result<ClimateNormals, WeatherError>>>,
+    #[cfg(feature = "weather")]
+    lookup_status: Option<String>,
+}
+
+impl Default for VineyardPanel {
+    fn default() -> Self {
+        Self {
+            region: Some(2),
+            vintage: REGIONS[2].typical,
+            harvest_day: TYPICAL_HARVEST_DAY,
+            #[cfg(feature = "weather")]
+            location: String::new(),
+            #[cfg(feature = "weather")]
+            lookup: None,
+            #[cfg(feature = "weather")]
+            lookup_status: None,
+        }
+    }
+}
+
+impl VineyardPanel {
+    /// Shows the season and what it ripens, and returns the harvest when the
+    /// user picks a region, moves the pick date, looks up a location, or asks
+    /// to start the simulation from it.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<Picked> {
+        #[cfg(feature = "weather")]
+        let looked_up = self.show_lookup(ui);
+        #[cfg(not(feature = "weather"))]
+        let looked_up = false;
+
+        let mut picked_region = None;
+        egui::Grid::new("vineyard").num_columns(2).show(ui, |ui| {
+            ui.label("Region:");
+            egui::ComboBox::from_id_salt("region")
+                .selected_text(self.region.map_or("Custom", |i| REGIONS[i].name))
+                .show_ui(ui, |ui| {
+                    for (i, region) in REGIONS.iter().enumerate() {
+                        if ui
+                            .selectable_value(&mut self.region, Some(i), region.name)
+                            .clicked()
+                        {
+                            self.vintage = region.typical;
+                            picked_region = Some(*region);
+                        }
+                    }
+                });
+            ui.end_row();
+            if let Some(region) = self.region.map(|i| &REGIONS[i]) {
+                ui.label("Classic blend:");
+                ui.label(region.describe_blend());
+                ui.end_row();
+            }
+            ui.label("Growing degree days:");
+            ui.add(egui::Slider::new(
+                &mut self.vintage.growing_degree_days,
+                800.0..=2600.0,
+            ));
+            ui.end_row();
+            ui.label("Season rainfall:");
+            ui.add(egui::Slider::new(&mut self.vintage.rainfall, 0.0..=1000.0).suffix(" mm"));
+            ui.end_row();
+            ui.label("Rain before harvest:");
+            ui.add(egui::Slider::new(&mut self.vintage.harvest_rain, 0.0..=100.0).suffix(" mm"));
+            ui.end_row();
+        });
+        if let Some(region) = self.region.map(|i| &REGIONS[i]) {
+            ui.label(format!(
+                "{} Simulated as {} in {} at {:.0} °C, then aged {} months{}.",
+                region.style,
+                region.main_grape(),
+                region.container.to_lowercase(),
+                region.temperature,
+                region.process.aging_months,
+                if region.process.malolactic {
+                    " after malolactic fermentation"
+                } else {
+                    ""
+                }
+            ));
+        }
+        let picked = ui
+            .horizontal(|ui| {
+                ui.label("Pick:");
+                ui.add(
+                    egui::Slider::new(&mut self.harvest_day, 0..=LATEST_HARVEST_DAY)
+                        .suffix(" days after véraison"),
+                )
+                .changed()
+            })
+            .inner;
+
+        self.show_ripening_plot(ui);
+        let harvest = self.vintage.harvest_on(self.harvest_day);
+        ui.label(format!(
+            "Winkler region {} ({} climate)",
+            ROMAN[usize::from(self.vintage.winkler_region()) - 1],
+            harvest.climate
+        ));
+        ui.label(format!(
+            "Sugar {:.0}–{:.0} g/L, acidity {:.1}–{:.1} g/L, phenolic ripeness {:.0}–{:.0}%",
+            harvest.sugar.start(),
+            harvest.sugar.end(),
+            harvest.acidity.start(),
+            harvest.acidity.end(),
+            harvest.phenolic_ripeness.start() * 100.0,
+            harvest.phenolic_ripeness.end() * 100.0,
+        ));
+        let used = ui
+            .button("Use Harvest")
+            .on_hover_text("Sets the climate and sugar content from this pick.")
+            .clicked();
+        (picked || used || looked_up || picked_region.is_some()).then_some(Picked {
+            harvest,
+            region: picked_region,
+        })
+    }
+
+    /// Returns whether normals for a location just arrived.
+    #[cfg(feature = "weather")]
+    fn show_lookup(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut looked_up = false;
+        if let Some(result) = self
+            .lookup
+            .as_ref()
+            .and_then(|lookup| lookup.try_recv().ok())
+        {
+            self.lookup = None;
+            match result {
+                Ok(normals) => {
+                    self.lookup_status = Some(format!(
+                        "{}, {}: averaged over {} seasons.",
+                        normals.place.name, normals.place.country, normals.seasons
+                    ));
+                    self.region = None;
+                    self.vintage = normals.vintage;
+                    looked_up = true;
+                }
+                Err(err) => self.lookup_status = Some(format!("Lookup failed: {}", err)),
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Location:");
+            let edit = ui.add(
+                egui::TextEdit::singleline(&mut self.location)
+                    .hint_text("e.g. Willamette Valley")
+                    .desired_width(160.0),
+            );
+            let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let enabled = self.lookup.is_none() && !self.location.trim().is_empty();
+            let clicked = ui
+                .add_enabled(enabled, egui::Button::new("Look Up Climate"))
+                .on_hover_text(format!(
+                    "Fetches the last {} years of weather from Open-Meteo.",
+                    weather::NORMAL_YEARS
+                ))
+                .clicked();
+            if enabled && (clicked || submitted) {
+                let (sender, receiver) = mpsc::channel();
+                let ctx = ui.ctx().clone();
+                weather::fetch_normals(&self.location, move |result| {
+                    let _ = sender.send(result);
+                    ctx.request_repaint();
+                });
+                self.lookup = Some(receiver);
+                self.lookup_status = None;
+            }
+            if self.lookup.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(status) = &self.lookup_status {
+            ui.label(status);
+        }
+        looked_up
+    }
+
+    fn show_ripening_plot(&self, ui: &mut egui::Ui) {
+        let curve = self.vintage.ripening_curve();
+        let sugar: PlotPoints = curve
+            .iter()
+            .map(|(day, harvest)| [f64::from(*day), harvest.expected_sugar()])
+            .collect();
+        // Scaled so acidity shares the sugar axis.
+        let acidity: PlotPoints = curve
+            .iter()
+            .map(|(day, harvest)| [f64::from(*day), harvest.expected_acidity() * 10.0])
+            .collect();
+        Plot::new("ripening")
+            .legend(Legend::default())
+            .height(160.0)
+            .x_axis_label("Days after véraison")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(sugar).name("Sugar (g/L)"));
+                plot_ui.line(Line::new(acidity).name("Acidity (g/L × 10)"));
+                plot_ui.vline(VLine::new(f64::from(self.harvest_day)).name("Pick"));
+            });
+    }
+}
@@ -0,0 +1,76 @@
+//! On-hand cellar supplies and what starting a batch takes from them.
+
+use crate::calculators::METABISULFITE_SO2_FRACTION;
+use crate::costs::BOTTLE_VOLUME;
+
+pub const YEAST: &str = "Yeast packets";
+pub const SULFITE: &str = "Potassium metabisulfite";
+pub const BOTTLES: &str = "Bottles";
+pub const CORKS: &str = "Corks";
+
+/// Liters of must one yeast packet is sized for.
+const YEAST_PACKET_VOLUME: f64 = 23.0;
+/// SO2 added at the crush, in mg/L.
+pub const CRUSH_SO2: f64 = 50.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StockItem {
+    pub name: String,
+    pub unit: String,
+    pub quantity: f64,
+}
+
+impl StockItem {
+    pub fn new(name: &str, unit: &str, quantity: f64) -> Self {
+        StockItem {
+            name: name.to_owned(),
+            unit: unit.to_owned(),
+            quantity,
+        }
+    }
+}
+
+/// Something a batch needs more of than is on hand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shortage {
+    pub needed: StockItem,
+    pub on_hand: f64,
+}
+
+impl Shortage {
+    pub fn missing(&self) -> f64 {
+        self.needed.quantity - self.on_hand
+    }
+}
+
+/// Supplies a batch of `volume` liters uses from crush to bottling.
+pub fn batch_requirements(volume: f64) -> Vec<StockItem> {
+    if volume <= 0.0 {
+        return Vec::new();
+    }
+    let bottles = (volume / BOTTLE_VOLUME).ceil();
+    let sulfite = volume * CRUSH_SO2 / 1000.0 / METABISULFITE_SO2_FRACTION;
+    vec![
+        StockItem::new(YEAST, "packets", (volume / YEAST_PACKET_VOLUME).ceil()),
+        StockItem::new(SULFITE, "g", (sulfite * 10.0).ceil() / 10.0),
+        StockItem::new(BOTTLES, "bottles", bottles),
+        StockItem::new(CORKS, "corks", bottles),
+    ]
+}
+
+/// Items in `needed` that `stock` can't cover; names match case-insensitively.
+pub fn shortages(stock: &[StockItem], needed: &[StockItem]) -> Vec<Shortage> {
+    needed
+        .iter()
+        .filter_map(|needed| {
+            let on_hand = stock
+                .iter()
+                .find(|item| item.name.eq_ignore_ascii_case(&needed.name))
+                .map_or(0.0, |item| item.quantity);
+            (on_hand < needed.quantity).then(|| Shortage {
+                needed: needed.clone(),
+                on_hand,
+            })
+        })
+        .collect()
+}
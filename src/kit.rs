@@ -0,0 +1,165 @@
+//! Wine kits: a bag of juice and grape concentrate made up with water. The
+//! kit's own specs fix the must, so no grape or climate inputs are needed.
+
+use crate::engine::{
+    SimulationError, SimulationInput, fermentation_state, specific_gravity,
+    sugar_from_original_gravity,
+};
+
+/// Volume in liters almost every kit is made up to.
+pub const KIT_BATCH_VOLUME: f64 = 23.0;
+/// Starting gravity kit instructions usually aim for.
+pub const KIT_STARTING_GRAVITY: f64 = 1.090;
+/// Fermentation temperature kit instructions ask for, in °C.
+pub const KIT_TEMPERATURE: f64 = 22.0;
+/// Gravity at which kits are racked off the primary lees.
+const RACK_GRAVITY: f64 = 1.010;
+/// Gravity a kit has to be down to before it is stabilized.
+const DRY_GRAVITY: f64 = 0.996;
+/// Kits are stabilized no earlier than this day, however fast they ferment.
+const EARLIEST_STABILIZE_DAY: u32 = 14;
+/// Days the wine clears after stabilizing before it can be bottled.
+const CLEARING_DAYS: u32 = 14;
+/// How far ahead to look for a kit to finish.
+const MAX_KIT_DAYS: u32 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KitSize {
+    /// About 7.5 L of concentrate, ready in four weeks.
+    FourWeek,
+    /// About 10 L of juice and concentrate, ready in six weeks.
+    #[default]
+    SixWeek,
+    /// About 16 L of juice, ready in eight weeks.
+    EightWeek,
+}
+
+impl KitSize {
+    pub const ALL: [KitSize; 3] = [KitSize::FourWeek, KitSize::SixWeek, KitSize::EightWeek];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KitSize::FourWeek => "4-week (7.5 L)",
+            KitSize::SixWeek => "6-week (10 L)",
+            KitSize::EightWeek => "8-week (16 L)",
+        }
+    }
+
+    /// Liters in the bag.
+    pub fn concentrate(self) -> f64 {
+        match self {
+            KitSize::FourWeek => 7.5,
+            KitSize::SixWeek => 10.0,
+            KitSize::EightWeek => 16.0,
+        }
+    }
+
+    pub fn weeks(self) -> u32 {
+        match self {
+            KitSize::FourWeek => 4,
+            KitSize::SixWeek => 6,
+            KitSize::EightWeek => 8,
+        }
+    }
+}
+
+/// A kit as printed on the box, and how much water actually went in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kit {
+    pub grape_type: String,
+    /// Liters of juice and concentrate in the bag.
+    pub concentrate: f64,
+    /// The volume and starting gravity the instructions make it up to.
+    pub batch_volume: f64,
+    pub starting_gravity: f64,
+    /// Liters of water added.
+    pub water: f64,
+    pub weeks: u32,
+}
+
+impl Kit {
+    /// A kit of `size` made up exactly as instructed.
+    pub fn new(grape_type: &str, size: KitSize) -> Self {
+        Kit {
+            grape_type: grape_type.to_owned(),
+            concentrate: size.concentrate(),
+            batch_volume: KIT_BATCH_VOLUME,
+            starting_gravity: KIT_STARTING_GRAVITY,
+            water: KIT_BATCH_VOLUME - size.concentrate(),
+            weeks: size.weeks(),
+        }
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.concentrate + self.water.max(0.0)
+    }
+
+    /// Must sugar in g/L once made up with `water`. The bag holds the sugar
+    /// the instructions' gravity implies at their volume.
+    pub fn sugar_content(&self) -> f64 {
+        let volume = self.volume();
+        if volume <= 0.0 {
+            return 0.0;
+        }
+        sugar_from_original_gravity(self.starting_gravity) * self.batch_volume / volume
+    }
+
+    /// Simulation inputs for the kit, fermented until it is due to be
+    /// stabilized.
+    pub fn input(&self) -> SimulationInput {
+        SimulationInput {
+            grape_type: self.grape_type.clone(),
+            fermentation_days: EARLIEST_STABILIZE_DAY,
+            container_type: "Steel Tank".to_owned(),
+            sugar_content: self.sugar_content(),
+            temperature: KIT_TEMPERATURE,
+            // Neutral, since the kit's sugar is already known.
+            climate: "Moderate".to_owned(),
+            ..SimulationInput::default()
+        }
+    }
+
+    pub fn plan(&self) -> Result<KitPlan, SimulationError> {
+        let input = self.input();
+        let mut rack_day = None;
+        let mut stabilize_day = None;
+        for day in 0..=MAX_KIT_DAYS {
+            let gravity = fermentation_state(&input, day)?.specific_gravity();
+            if rack_day.is_none() && gravity <= RACK_GRAVITY {
+                rack_day = Some(day);
+            }
+            if gravity <= DRY_GRAVITY {
+                stabilize_day = Some(day.max(EARLIEST_STABILIZE_DAY));
+                break;
+            }
+        }
+        let bottle_day = stabilize_day.map(|day| (day + CLEARING_DAYS).max(self.weeks * 7));
+        let input = SimulationInput {
+            fermentation_days: stabilize_day.unwrap_or(MAX_KIT_DAYS),
+            ..input
+        };
+        Ok(KitPlan {
+            starting_gravity: specific_gravity(input.sugar_content, 0.0),
+            volume: self.volume(),
+            input,
+            rack_day,
+            stabilize_day,
+            bottle_day,
+        })
+    }
+}
+
+/// What a kit starts at and when each step of the instructions falls due,
+/// in days from pitching the yeast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KitPlan {
+    pub input: SimulationInput,
+    pub starting_gravity: f64,
+    pub volume: f64,
+    /// When the gravity is down to 1.010 and the wine goes to secondary.
+    pub rack_day: Option<u32>,
+    /// `None` if the kit isn't expected to finish dry, e.g. when too little
+    /// water leaves more sugar than the yeast can take.
+    pub stabilize_day: Option<u32>,
+    pub bottle_day: Option<u32>,
+}
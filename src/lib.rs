@@ -0,0 +1,68 @@
+pub mod achievements;
+pub mod balance;
+pub mod benchmark;
+pub mod body;
+pub mod branch;
+pub mod calculators;
+pub mod calendar;
+pub mod calibration;
+pub mod career;
+pub mod cellar;
+pub mod challenge;
+pub mod chemistry;
+pub mod color;
+pub mod companion;
+pub mod conventions;
+pub mod costs;
+pub mod dataset;
+pub mod degassing;
+pub mod descriptors;
+#[cfg(feature = "serde")]
+pub mod devices;
+pub mod engine;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod glossary;
+pub mod grapes;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod inventory;
+pub mod kit;
+pub mod log_sheet;
+pub mod model_changelog;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod narrative;
+pub mod neighbors;
+pub mod optimizer;
+pub mod permalink;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod quiz;
+#[cfg(feature = "serde")]
+pub mod recipe;
+#[cfg(feature = "serde")]
+pub mod save;
+#[cfg(feature = "scenario")]
+pub mod scenario;
+#[cfg(any(feature = "mqtt", feature = "serial", feature = "ble"))]
+pub mod sensors;
+#[cfg(feature = "serde")]
+pub mod settings;
+pub mod stability;
+pub mod stage;
+pub mod stepper;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod sulfides;
+#[cfg(feature = "scenario")]
+pub mod sweep;
+pub mod sweetness_category;
+pub mod vineyard;
+#[cfg(feature = "weather")]
+pub mod weather;
+#[cfg(feature = "webhook")]
+pub mod webhook;
@@ -0,0 +1,139 @@
+//! Printable log sheets for winemakers who record readings on paper. Each
+//! sheet lists a batch's days with the plan's predicted gravity and sugar,
+//! and blank columns for the real gravity, temperature, and notes. It is a
+//! standalone HTML page that prints cleanly from any browser.
+
+use std::fmt::Write as _;
+
+use chrono::{NaiveDate, TimeDelta};
+
+use crate::cellar::Batch;
+use crate::engine::{Intervention, SimulationError, fermentation_state, timeline};
+use crate::stage::{Stage, stage_on};
+
+/// Weekly rows after the planned end of fermentation, for ferments that
+/// run long.
+const EXTRA_WEEKS: u32 = 3;
+
+/// One line of a log sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRow {
+    pub date: NaiveDate,
+    pub day: u32,
+    pub stage: Stage,
+    pub specific_gravity: f64,
+    /// Residual sugar in g/L.
+    pub residual_sugar: f64,
+    /// What the plan has done on the day.
+    pub planned: Vec<Intervention>,
+}
+
+/// A row for every day of the planned fermentation, then one a week for
+/// `EXTRA_WEEKS` weeks.
+pub fn log_rows(batch: &Batch) -> Result<Vec<LogRow>, SimulationError> {
+    let planned = timeline(&batch.input);
+    let last_day = batch.input.fermentation_days;
+    let extra_days = (1..=EXTRA_WEEKS).map(|week| last_day + 7 * week);
+    (0..=last_day)
+        .chain(extra_days)
+        .map(|day| {
+            let state = fermentation_state(&batch.input, day)?;
+            Ok(LogRow {
+                date: batch.started_on + TimeDelta::days(day.into()),
+                day,
+                stage: stage_on(&batch.input, day)?,
+                specific_gravity: state.specific_gravity(),
+                residual_sugar: state.residual_sugar,
+                planned: planned
+                    .iter()
+                    .filter(|intervention| intervention.day == day)
+                    .copied()
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "body { font-family: sans-serif; font-size: 11pt; margin: 1.5cm; }
+h1 { font-size: 16pt; margin: 0 0 0.3em; }
+p.plan { margin: 0 0 1em; }
+table { width: 100%; border-collapse: collapse; }
+th, td { border: 1px solid #444; padding: 0.3em 0.4em; text-align: left; }
+th { background: #eee; }
+td.blank { min-width: 5em; }
+tr { page-break-inside: avoid; }
+thead { display: table-header-group; }
+@page { margin: 1.5cm; }
+@media print { body { margin: 0; } }";
+
+/// The batch's log sheet as an HTML page.
+pub fn log_sheet_html(batch: &Batch) -> Result<String, SimulationError> {
+    let rows = log_rows(batch)?;
+    let input = &batch.input;
+    let mut plan = format!(
+        "{}, started {}. {} days at {:.1} °C",
+        escape(&input.grape_type),
+        batch.started_on,
+        input.fermentation_days,
+        input.temperature
+    );
+    if !input.yeast.is_empty() {
+        let _ = write!(plan, " with {}", escape(&input.yeast));
+    }
+    if let Some(volume) = batch.volume {
+        let _ = write!(plan, ", {:.0} L", volume);
+    }
+    if let Some(abv) = batch.predicted_abv {
+        let _ = write!(plan, ", {:.1}% ABV predicted", abv);
+    }
+    plan.push('.');
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0} log sheet</title>\n<style>\n{1}\n</style>\n</head>\n<body>\n<h1>{0}</h1>\n<p class=\"plan\">{2}</p>\n",
+        escape(&batch.name),
+        STYLE,
+        plan
+    );
+    html.push_str("<table>\n<thead><tr>");
+    for header in [
+        "Date",
+        "Day",
+        "Stage",
+        "Predicted SG",
+        "Predicted sugar (g/L)",
+        "Planned",
+        "Actual SG",
+        "Temp",
+        "Notes",
+    ] {
+        let _ = write!(html, "<th>{}</th>", header);
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for row in &rows {
+        let planned = row
+            .planned
+            .iter()
+            .map(|intervention| escape(&intervention.to_string()))
+            .collect::<Vec<_>>()
+            .join("<br>");
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.3}</td><td>{:.0}</td><td>{}</td><td class=\"blank\"></td><td class=\"blank\"></td><td class=\"blank\"></td></tr>",
+            row.date,
+            row.day,
+            row.stage.label(),
+            row.specific_gravity,
+            row.residual_sugar,
+            planned
+        );
+    }
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    Ok(html)
+}
@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::{self, MakeWriter};
+use tracing_subscriber::prelude::*;
+
+const MAX_LOG_LINES: usize = 2000;
+
+#[derive(Clone, Default)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogBuffer {
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.lines.lock().unwrap().clear();
+    }
+
+    fn push(&self, text: &str) {
+        let mut lines = self.lines.lock().unwrap();
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            if lines.len() == MAX_LOG_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_owned());
+        }
+    }
+}
+
+pub struct LogWriter {
+    buffer: LogBuffer,
+}
+
+impl io::Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.push(&String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogBuffer {
+    type Writer = LogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogWriter {
+            buffer: self.clone(),
+        }
+    }
+}
+
+// Logs go to an in-memory buffer that the log viewers read, since the Windows
+// GUI build has no console attached, and optionally to stderr as well. The TUI
+// owns the terminal, so it keeps stderr quiet.
+pub fn init(verbose: bool, to_stderr: bool) -> LogBuffer {
+    let buffer = LogBuffer::default();
+    let level = if verbose {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    tracing_subscriber::registry()
+        .with(to_stderr.then(|| fmt::layer().with_writer(io::stderr).with_filter(level)))
+        .with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(buffer.clone())
+                .with_filter(level),
+        )
+        .init();
+
+    // No stderr or system clock in the browser.
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = to_stderr;
+        tracing_subscriber::registry()
+            .with(
+                fmt::layer()
+                    .with_ansi(false)
+                    .without_time()
+                    .with_writer(buffer.clone())
+                    .with_filter(level),
+            )
+            .init();
+    }
+
+    buffer
+}
@@ -1,324 +1,377 @@
-#![windows_subsystem = "windows"]
-
-use csv::ReaderBuilder;
-use eframe::egui;
-use rand::rng;
-use rand::seq::IndexedRandom;
-use serde::Deserialize;
-
-const WINE_DATA_CSV: &str = include_str!("../WineDataset.csv");
+#![cfg_attr(not(feature = "tui"), windows_subsystem = "windows")]
+
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("the web build needs `--no-default-features --features wasm`");
+
+#[cfg(feature = "gui")]
+mod gui;
+#[cfg(any(feature = "gui", feature = "tui"))]
+mod logging;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+mod web;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::error::Error;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "cli"))]
+use clap::Parser;
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+#[cfg_attr(feature = "cli", derive(clap::Parser))]
+#[cfg_attr(feature = "cli", command(version, about = "Wine Fermentation Simulator"))]
+struct Cli {
+    /// Log debug-level detail to stderr and the in-app log viewer
+    #[cfg_attr(feature = "cli", arg(short, long, global = true))]
+    verbose: bool,
+
+    #[cfg_attr(feature = "cli", command(subcommand))]
+    command: Option<Command>,
+}
 
-#[derive(Debug, Deserialize)]
-struct WineRecord {
-    #[serde(rename = "Grape")]
-    grape: String,
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg_attr(feature = "cli", derive(clap::Subcommand))]
+enum Command {
+    /// Run the simulator in the terminal instead of a window
+    Tui,
+    /// Simulate a scenario file and print the report
+    #[cfg(feature = "scenario")]
+    Run {
+        /// The scenario, a TOML file
+        scenario: std::path::PathBuf,
+        /// Write the report, or a sweep's CSV, to this file instead
+        #[cfg_attr(feature = "cli", arg(short, long))]
+        output: Option<std::path::PathBuf>,
+        /// Write an SVG plot of a sweep's runs to this file
+        #[cfg_attr(feature = "cli", arg(long))]
+        plot: Option<std::path::PathBuf>,
+    },
+    /// Simulate a scenario file again whenever it changes, rewriting the
+    /// report
+    #[cfg(feature = "scenario")]
+    Watch {
+        /// The scenario, a TOML file
+        scenario: std::path::PathBuf,
+        /// The report to rewrite [default: the scenario's name with .txt,
+        /// or .csv for a sweep]
+        #[cfg_attr(feature = "cli", arg(short, long))]
+        output: Option<std::path::PathBuf>,
+        /// An SVG plot of a sweep's runs to rewrite too
+        #[cfg_attr(feature = "cli", arg(long))]
+        plot: Option<std::path::PathBuf>,
+    },
+    /// Serve simulations over gRPC, as defined in proto/wine_maker.proto
+    #[cfg(feature = "grpc")]
+    Serve {
+        /// The address to listen on
+        #[cfg_attr(feature = "cli", arg(long, default_value = "127.0.0.1:50051"))]
+        address: std::net::SocketAddr,
+    },
+}
 
-    #[serde(rename = "Characteristics")]
-    characteristics: String,
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "cli")))]
+impl Cli {
+    fn parse() -> Self {
+        Cli::default()
+    }
 }
 
-fn load_csv_data_from_str(data: &str) -> Result<Vec<WineRecord>, csv::Error> {
-    let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(data.as_bytes());
-    let mut records = Vec::new();
-    for result in rdr.deserialize() {
-        let record: WineRecord = result?;
-        records.push(record);
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(feature = "gui", feature = "tui", feature = "scenario")
+))]
+fn load_dataset() -> wine_maker::dataset::Dataset {
+    use wine_maker::dataset::{Dataset, WINE_DATA_CSV};
+
+    match Dataset::from_csv_str(WINE_DATA_CSV) {
+        Ok(data) => data,
+        Err(err) => {
+            tracing::error!("Could not load CSV: {}", err);
+            Dataset::default()
+        }
     }
-    Ok(records)
 }
 
+/// Reads `settings.json` from the config directory, falling back to the
+/// defaults if it can't be read.
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(feature = "gui", feature = "scenario")
+))]
+fn load_settings() -> (wine_maker::settings::Settings, Option<std::path::PathBuf>) {
+    use wine_maker::settings::Settings;
+
+    let Some(path) = dirs::config_dir().map(|dir| dir.join("wine-maker").join("settings.json")) else {
+        return (Settings::default(), None);
+    };
+    let settings = Settings::load(&path).unwrap_or_else(|err| {
+        tracing::error!("Could not read {}: {}", path.display(), err);
+        Settings::default()
+    });
+    (settings, Some(path))
+}
 
-struct WineFermentationApp {
-    wine_data: Vec<WineRecord>,
-    grape_type: String,
-    fermentation_days: String,
-    container_type: String,
-    sugar_content: String,
-    temperature: String,
-    climate: String,
+/// Installs the descriptor scales in `descriptors.json` from the config
+/// directory, if there is one.
+#[cfg(all(not(target_arch = "wasm32"), feature = "gui"))]
+fn load_descriptors() {
+    use wine_maker::descriptors::{self, DescriptorScales};
 
-    result_text: String,
+    let Some(path) = dirs::config_dir().map(|dir| dir.join("wine-maker").join("descriptors.json")) else {
+        return;
+    };
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match DescriptorScales::from_json(&json) {
+        Ok(scales) => descriptors::install(scales),
+        Err(err) => tracing::error!("Could not read {}: {}", path.display(), err),
+    }
 }
 
-impl WineFermentationApp {
-    fn new(wine_data: Vec<WineRecord>) -> Self {
-        Self {
-            wine_data,
-            grape_type: String::new(),
-            fermentation_days: String::new(),
-            container_type: String::new(),
-            sugar_content: String::new(),
-            temperature: String::new(),
-            climate: String::new(),
-            result_text: String::new(),
-        }
+/// The settings' data directory, or the platform's.
+#[cfg(all(not(target_arch = "wasm32"), feature = "gui"))]
+fn data_dir(settings: &wine_maker::settings::Settings) -> Option<std::path::PathBuf> {
+    match &settings.data_dir {
+        Some(dir) if !dir.as_os_str().is_empty() => Some(dir.clone()),
+        _ => Some(dirs::data_dir()?.join("wine-maker")),
     }
+}
 
-    fn simulate(&mut self) {
-        let fermentation_days: i32 = self.fermentation_days.trim().parse().unwrap_or_default();
-        let user_sugar_input: i32 = self.sugar_content.trim().parse().unwrap_or_default();
-        let temperature: f64 = self.temperature.trim().parse().unwrap_or_default();
+#[cfg(all(not(target_arch = "wasm32"), feature = "gui", feature = "sqlite"))]
+fn open_store(dir: Option<std::path::PathBuf>) -> Option<wine_maker::store::Store> {
+    let dir = dir?;
+    if let Err(err) = std::fs::create_dir_all(&dir) {
+        tracing::error!("Could not create {}: {}", dir.display(), err);
+        return None;
+    }
+    wine_maker::store::Store::open(dir.join("wine-maker.sqlite3"))
+        .inspect_err(|err| tracing::error!("Could not open store: {}", err))
+        .ok()
+}
 
-        let (sugar_mod, _acidity_mod, tannin_mod) = match self.climate.to_lowercase().as_str() {
-            "cool" => (0.90, 1.10, 1.00),
-            "moderate" => (1.00, 1.00, 1.00),
-            "warm" => (1.10, 0.90, 1.10),
-            _ => (1.00, 1.00, 1.00),
-        };
+/// Reads a translated glossary from the data directory, `glossary.csv` or
+/// the one for the settings' locale, if there is one.
+#[cfg(all(not(target_arch = "wasm32"), feature = "gui"))]
+fn load_glossary(settings: &wine_maker::settings::Settings) -> wine_maker::glossary::Glossary {
+    use wine_maker::glossary::Glossary;
 
-        let sugar_content = (user_sugar_input as f64) * sugar_mod;
+    let Some(path) = data_dir(settings).map(|dir| dir.join(settings.glossary_file())) else {
+        return Glossary::default();
+    };
+    let Ok(data) = std::fs::read_to_string(&path) else {
+        return Glossary::default();
+    };
+    Glossary::from_csv_str(&data).unwrap_or_else(|err| {
+        tracing::error!("Could not read {}: {}", path.display(), err);
+        Glossary::default()
+    })
+}
 
-        let conversion_factor = 16.83;
-        let potential_abv = sugar_content / conversion_factor;
+#[cfg(all(not(target_arch = "wasm32"), feature = "gui"))]
+fn run_gui(verbose: bool) -> Result<(), Box<dyn Error>> {
+    let log_buffer = logging::init(verbose, true);
+    let (settings, settings_path) = load_settings();
+    load_descriptors();
+    let app = gui::WineFermentationApp::new(load_dataset(), log_buffer)
+        .with_glossary(load_glossary(&settings));
+    #[cfg(feature = "sqlite")]
+    let app = match open_store(data_dir(&settings)) {
+        Some(store) => app.with_store(store),
+        None => app,
+    };
+    let app = match settings_path {
+        Some(path) => app.with_settings(settings, path),
+        None => app,
+    };
+    gui::run(app)?;
+    Ok(())
+}
 
-        if temperature < 5.0 || temperature > 40.0 {
-            self.result_text =
-                "Fermentation failed: temperature out of range for yeast activity.".to_owned();
-            return;
-        }
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "gui")))]
+fn run_gui(_verbose: bool) -> Result<(), Box<dyn Error>> {
+    Err("wine-maker was built without the `gui` feature; try `wine-maker tui`".into())
+}
 
-        let ref_temp = 20.0;
-        let k_ref = 0.20;
-        let q10: f64 = 2.0;
-        let k = k_ref * q10.powf((temperature - ref_temp) / 10.0);
+#[cfg(all(not(target_arch = "wasm32"), feature = "tui"))]
+fn run_tui(verbose: bool) -> Result<(), Box<dyn Error>> {
+    let log_buffer = logging::init(verbose, false);
+    tui::run(load_dataset(), log_buffer)?;
+    Ok(())
+}
 
-        let mut fraction_fermented = 1.0 - (-k * (fermentation_days as f64)).exp();
-        if fraction_fermented > 1.0 {
-            fraction_fermented = 1.0;
-        }
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "tui")))]
+fn run_tui(_verbose: bool) -> Result<(), Box<dyn Error>> {
+    Err("wine-maker was built without the `tui` feature".into())
+}
 
-        let sugar_consumed = fraction_fermented * sugar_content;
-        let mut actual_abv = sugar_consumed / conversion_factor;
+/// What running a scenario gives: a description of the wine, or for a
+/// sweep a CSV of its runs and a plot of them.
+#[cfg(all(not(target_arch = "wasm32"), feature = "scenario"))]
+struct Report {
+    text: String,
+    plot: Option<String>,
+}
 
-        let max_abv = 15.0;
-        if actual_abv > max_abv {
-            actual_abv = max_abv;
-            let sugar_consumed_capped = max_abv * conversion_factor;
-            fraction_fermented = sugar_consumed_capped / sugar_content;
-        }
+/// Simulates the scenario at `path` on the profiles in the settings.
+#[cfg(all(not(target_arch = "wasm32"), feature = "scenario"))]
+fn scenario_report(path: &std::path::Path) -> Result<Report, Box<dyn Error>> {
+    use wine_maker::engine::seeded;
+    use wine_maker::narrative::narrative;
+    use wine_maker::scenario::Scenario;
+    use wine_maker::sweep;
+
+    let scenario = Scenario::load(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    // One draw for the whole report, so the narrative matches the run.
+    let scenario = Scenario {
+        input: seeded(&scenario.input),
+        ..scenario
+    };
+    let (settings, _) = load_settings();
+    let wine_data = load_dataset();
+    if scenario.sweeps.is_empty() {
+        let result = scenario.run(&wine_data, &settings.profiles)?;
+        return Ok(Report {
+            text: narrative(&scenario.input, &result),
+            plot: None,
+        });
+    }
+    let runs = scenario.run_sweeps(&wine_data, &settings.profiles)?;
+    Ok(Report {
+        text: sweep::spreadsheet(&runs, &scenario.sweeps).to_csv_string()?,
+        plot: Some(sweep::plot(&runs, &scenario.sweeps)),
+    })
+}
 
-        let residual_sugar = sugar_content - sugar_consumed;
-        let sweetness_description = if residual_sugar > 35.0 {
-            "extremely sweet"
-        } else if residual_sugar > 20.0 {
-            "noticeably sweet"
-        } else if residual_sugar > 5.0 {
-            "with just a subtle hint of sweetness"
-        } else {
-            "bone dry"
-        };
-
-        let body_description = if actual_abv > 12.0 {
-            "full-bodied"
-        } else if actual_abv >= 10.0 {
-            "medium-bodied"
-        } else {
-            "light-bodied"
-        };
-        
-        let alcohol_level = if actual_abv <= 1.0 {
-            "extremely low"
-        } else if actual_abv < 5.0 {
-            "very low"
-        } else if actual_abv < 10.0 {
-            "low"
-        } else if actual_abv < 13.5 {
-            "moderate"
-        } else if actual_abv < 15.0 {
-            "high"
-        } else if actual_abv < 20.0 {
-            "very high"
-        } else {
-            "extremely high"
-        };
-
-        let tannin_base = match self.grape_type.to_lowercase().as_str() {
-            "cabernet sauvignon" => "robust, high tannins",
-            "merlot" => "smooth, moderate tannins",
-            "pinot noir" => "delicate, low tannins",
-            "syrah" | "shiraz" => "moderate tannins",
-            "tempranillo" => "moderate tannins",
-            "zinfandel" => "spicy, moderately high tannins",
-            "sangiovese" => "high tannins",
-            "chardonnay" => "minimal tannins",
-            "sauvignon blanc" => "minimal tannins",
-            "riesling" => "very minimal tannins",
-            _ => "unknown tannin levels",
-        };
-
-        let tannin_level = if tannin_mod > 1.0 {
-            format!("{} (slightly accentuated by the warm climate)", tannin_base)
-        } else if tannin_mod < 1.0 {
-            format!(
-                "{} (somewhat less pronounced in the cool climate)",
-                tannin_base
-            )
-        } else {
-            tannin_base.to_string()
-        };
-
-        let acidity_description = match self.climate.to_lowercase().as_str() {
-            "cool" => "high",
-            "moderate" => "moderate",
-            "warm" => "low",
-            _ => "unknown",
-        };
-
-        let matches: Vec<&WineRecord> = self
-            .wine_data
-            .iter()
-            .filter(|record| record.grape.eq_ignore_ascii_case(&self.grape_type))
-            .collect();
-        let grape_characteristics = if matches.is_empty() {
-            "unknown flavor profile".to_owned()
-        } else {
-            let mut rng = rng();
-            matches.choose(&mut rng).unwrap().characteristics.clone()
-        };
-
-        let container_lower = self.container_type.to_lowercase();
-        let container_note = match container_lower.as_str() {
-            "oak barrel" => "woody, oaky undertones",
-            "steel tank" => "a pristine, clean character",
-            "clay amphora" => "earthy nuances",
-            _ => "a distinct vessel charm",
-        };
-
-        let climate = self.climate.to_lowercase();
-        self.result_text = format!(
-            "Your {} wine was fermented over {} days in a {} that adds {}. \
-             The initial sugar level was {:.1} g/L (adjusted for a {} climate), which could have reached a potential of {:.1}% ABV.\n\n\
-             Fermenting at {}°C, about {:.1}% of that potential was met, resulting in a final ABV of {:.1}%% and leaving behind a residual sugar of {:.1} g/L, making it {}.\n\n\
-             The wine is {} in body, with {} tannins and {} acidity. It shows hints of {} in its flavor profile.\n\n\
-             The alcohol content is classified as {}.\n\n\
-             Enjoy your wine.",
-            self.grape_type,
-            fermentation_days,
-            self.container_type,
-            container_note,
-            sugar_content,
-            climate,
-            potential_abv,
-            temperature,
-            fraction_fermented * 100.0,
-            actual_abv,
-            residual_sugar,
-            sweetness_description,
-            body_description,
-            tannin_level,
-            acidity_description,
-            grape_characteristics.to_ascii_lowercase(),
-            alcohol_level
-        );
+#[cfg(all(not(target_arch = "wasm32"), feature = "scenario"))]
+fn run_scenario(
+    path: &std::path::Path,
+    output: Option<&std::path::Path>,
+    plot: Option<&std::path::Path>,
+) -> Result<(), Box<dyn Error>> {
+    let report = scenario_report(path)?;
+    match output {
+        Some(output) => std::fs::write(output, report.text)?,
+        None => println!("{}", report.text),
+    }
+    match (plot, report.plot) {
+        (Some(path), Some(plot)) => std::fs::write(path, plot)?,
+        (Some(_), None) => return Err("only a scenario with a [sweep] has a plot".into()),
+        (None, _) => {}
     }
+    Ok(())
 }
 
-impl eframe::App for WineFermentationApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Wine Fermentation Simulator");
-
-            ui.label("Grape Type:");
-            egui::ComboBox::from_label("Select a Grape")
-                .selected_text(&self.grape_type)
-                .width(200.0)
-                .show_ui(ui, |ui| {
-                    egui::ScrollArea::vertical()
-                        .max_height(100.0)
-                        .show(ui, |ui| {
-                            for grape in &[
-                                "Cabernet Sauvignon",
-                                "Merlot",
-                                "Pinot Noir",
-                                "Chardonnay",
-                                "Sauvignon Blanc",
-                                "Riesling",
-                                "Syrah",
-                                "Shiraz",
-                                "Zinfandel",
-                                "Tempranillo",
-                                "Sangiovese",
-                            ] {
-                                ui.selectable_value(
-                                    &mut self.grape_type,
-                                    grape.to_string(),
-                                    *grape,
-                                );
-                            }
-                        });
-                });
-
-            ui.label("Fermentation Days (Usually 5-21):");
-            ui.text_edit_singleline(&mut self.fermentation_days);
-
-            ui.label("Container Type:");
-            egui::ComboBox::from_label("Select Container")
-                .selected_text(&self.container_type)
-                .width(200.0)
-                .show_ui(ui, |ui| {
-                    egui::ScrollArea::vertical()
-                        .max_height(100.0)
-                        .show(ui, |ui| {
-                            for container in &["Oak Barrel", "Steel Tank", "Clay Amphora"] {
-                                ui.selectable_value(
-                                    &mut self.container_type,
-                                    container.to_string(),
-                                    *container,
-                                );
-                            }
-                        });
-                });
-
-            // 5) Climate dropdown:
-            ui.label("Climate:");
-            egui::ComboBox::from_label("Select a Climate")
-                .selected_text(&self.climate)
-                .width(200.0)
-                .show_ui(ui, |ui| {
-                    egui::ScrollArea::vertical()
-                        .max_height(100.0)
-                        .show(ui, |ui| {
-                            for climate_option in &["Cool", "Moderate", "Warm"] {
-                                ui.selectable_value(
-                                    &mut self.climate,
-                                    climate_option.to_string(),
-                                    *climate_option,
-                                );
-                            }
-                        });
-                });
-
-            ui.label("Sugar Content (g/L) (Usually 180g-300g):");
-            ui.text_edit_singleline(&mut self.sugar_content);
-
-            ui.label("Temperature (°C) (Usually 10.0°C to 30.0°C):");
-            ui.text_edit_singleline(&mut self.temperature);
-
-            if ui.button("Simulate Wine Fermentation").clicked() {
-                self.simulate();
+/// How often `watch` looks for a change to the scenario.
+#[cfg(all(not(target_arch = "wasm32"), feature = "scenario"))]
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Rewrites the report for the scenario at `path` each time the file is
+/// saved, until interrupted. A scenario that can't be run leaves its error
+/// in the report, so an old report is never taken for the current one.
+#[cfg(all(not(target_arch = "wasm32"), feature = "scenario"))]
+fn watch_scenario(
+    path: &std::path::Path,
+    output: Option<std::path::PathBuf>,
+    plot: Option<std::path::PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    eprintln!("Watching {}. Press Ctrl+C to stop.", path.display());
+    let mut last_modified = None;
+    let mut extension = "txt";
+    loop {
+        let modified = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_some() && modified != last_modified {
+            last_modified = modified;
+            let report = scenario_report(path).unwrap_or_else(|err| Report {
+                text: err.to_string(),
+                plot: None,
+            });
+            if report.plot.is_some() {
+                extension = "csv";
             }
-
-            ui.separator();
-            ui.label("Results:");
-            ui.text_edit_multiline(&mut self.result_text);
-        });
+            let output = output
+                .clone()
+                .unwrap_or_else(|| path.with_extension(extension));
+            if output == path {
+                return Err("the report would replace the scenario; pass --output".into());
+            }
+            std::fs::write(&output, &report.text)?;
+            if let (Some(path), Some(plot)) = (&plot, &report.plot) {
+                std::fs::write(path, plot)?;
+            }
+            eprintln!(
+                "{} Wrote {}",
+                chrono::Local::now().format("%H:%M:%S"),
+                output.display()
+            );
+        }
+        std::thread::sleep(WATCH_INTERVAL);
     }
 }
 
-fn main() -> eframe::Result<()> {
-    let native_options = eframe::NativeOptions::default();
+/// Serves simulations over gRPC on the dataset and the settings' model
+/// profiles, until interrupted.
+#[cfg(all(not(target_arch = "wasm32"), feature = "grpc"))]
+fn serve_grpc(address: std::net::SocketAddr) -> Result<(), Box<dyn Error>> {
+    use wine_maker::grpc::{SimulatorService, serve};
+
+    let (settings, _) = load_settings();
+    let service = SimulatorService::new(load_dataset(), settings.profiles);
+    eprintln!("Serving gRPC on {}. Press Ctrl+C to stop.", address);
+    tokio::runtime::Runtime::new()?.block_on(serve(address, service))?;
+    Ok(())
+}
 
-    let wine_data = match load_csv_data_from_str(WINE_DATA_CSV) {
-        Ok(data) => data,
-        Err(err) => {
-            eprintln!("Could not load CSV: {}", err);
-            Vec::new()
-        }
-    };
+/// Writes to the console the app was run from. The Windows build has no
+/// console of its own, so a subcommand's output would otherwise be lost.
+#[cfg(windows)]
+fn attach_console() {
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    // SAFETY: takes no pointers. It fails harmlessly if there is no parent
+    // console or one is already attached.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> Result<(), Box<dyn Error>> {
+    // Only with arguments, so opening the GUI from a terminal leaves it free.
+    #[cfg(windows)]
+    if std::env::args_os().len() > 1 {
+        attach_console();
+    }
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Command::Tui) => run_tui(cli.verbose),
+        #[cfg(feature = "scenario")]
+        Some(Command::Run {
+            scenario,
+            output,
+            plot,
+        }) => run_scenario(&scenario, output.as_deref(), plot.as_deref()),
+        #[cfg(feature = "scenario")]
+        Some(Command::Watch {
+            scenario,
+            output,
+            plot,
+        }) => watch_scenario(&scenario, output, plot),
+        #[cfg(feature = "grpc")]
+        Some(Command::Serve { address }) => serve_grpc(address),
+        None => run_gui(cli.verbose),
+    }
+}
 
-    eframe::run_native(
-        "Wine Fermentation Simulator",
-        native_options,
-        Box::new(|_creation_context| Ok(Box::new(WineFermentationApp::new(wine_data)))),
-    )
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+fn main() {
+    web::start();
 }
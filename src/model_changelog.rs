@@ -0,0 +1,95 @@
+//! What changed in the model from one version to the next, so that the same
+//! inputs giving different numbers in a newer release can be explained. Add
+//! an entry, and bump `engine::MODEL_VERSION`, whenever a change to the
+//! kinetics or the descriptors changes what a simulation returns.
+
+use crate::engine::MODEL_VERSION;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelChange {
+    pub version: u32,
+    pub title: &'static str,
+    /// One line for each change to the numbers or the descriptions.
+    pub changes: &'static [&'static str],
+}
+
+/// Every model version, oldest first.
+pub const CHANGELOG: [ModelChange; 6] = [
+    ModelChange {
+        version: 1,
+        title: "Versioned results",
+        changes: &[
+            "Results now record the model version that produced them. Results saved earlier \
+             show as version 0 and may predate any of the changes below.",
+            "Fermentation slows as the alcohol nears the yeast's tolerance instead of stopping \
+             abruptly, so strong musts finish a little lower and sweeter.",
+            "Each yeast strain has its own tolerance, lag, and aromas; ambient yeast adds a \
+             seeded lag and character.",
+            "Temperature changes on the timeline shift the rate day by day, with stall risk and \
+             fusel or ester aromas from the temperature history.",
+            "Fermentation and aging vessels are separate, each adding its own oxygen uptake.",
+            "Whole clusters dilute the sugar slightly and add tannin; leaving out press wine \
+             lowers the yield.",
+            "Sur-lie aging, closures, color, dissolved CO2, and hydrogen sulfide are modeled \
+             and described.",
+        ],
+    },
+    ModelChange {
+        version: 2,
+        title: "Titratable acidity and EU sweetness categories",
+        changes: &[
+            "Results estimate titratable acidity from the grape, climate, malolactic \
+             fermentation, and lees contact.",
+            "Wines are given their official EU sweetness category, still and sparkling, from \
+             residual sugar and acidity.",
+        ],
+    },
+    ModelChange {
+        version: 3,
+        title: "Sweetness as it tastes",
+        changes: &[
+            "Sweetness is described from the sugar as it tastes: crisp acidity masks some of \
+             it and soft acidity shows it more, so a tart Riesling with 22 g/L reads only \
+             subtly sweet.",
+        ],
+    },
+    ModelChange {
+        version: 4,
+        title: "Balance",
+        changes: &[
+            "Wines are flagged as hot, flabby, or austere when their alcohol, sugar, and \
+             acidity are out of balance, and each flag costs quality points.",
+        ],
+    },
+    ModelChange {
+        version: 5,
+        title: "Body from more than alcohol",
+        changes: &[
+            "Body is judged from residual sugar, glycerol, lees contact, and time in oak as \
+             well as alcohol, so off-dry and sur-lie wines read fuller.",
+            "Glycerol is estimated from the alcohol and the fermentation temperature.",
+            "A high-alcohol wine with enough body to carry it is no longer flagged as hot.",
+        ],
+    },
+    ModelChange {
+        version: 6,
+        title: "Glycerol from the yeast and must",
+        changes: &[
+            "Glycerol depends on the yeast strain and on osmotic stress in sugary musts as \
+             well as the temperature, and adds to the body accordingly.",
+        ],
+    },
+];
+
+/// The changes made since `version`, oldest first. Empty for a result from
+/// the current model.
+pub fn changes_since(version: u32) -> impl Iterator<Item = &'static ModelChange> {
+    CHANGELOG
+        .iter()
+        .filter(move |change| change.version > version)
+}
+
+/// Whether a result from model `version` might differ if simulated again.
+pub fn is_outdated(version: u32) -> bool {
+    version < MODEL_VERSION
+}
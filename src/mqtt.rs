@@ -0,0 +1,360 @@
+//! Live readings from sensors publishing over MQTT, such as iSpindels or a
+//! Tilt bridge. Messages are either a bare number or a JSON object with the
+//! same field names accepted by `devices::import_readings`.
+//!
+//! The other way, batch status is published for Home Assistant, announced
+//! through its MQTT discovery so each batch shows up as a device.
+
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread;
+use std::time::Duration;
+
+use chrono::Local;
+use rumqttc::{Client, ClientError, Event, MqttOptions, Packet, QoS};
+use serde_json::json;
+use tracing::{debug, info, warn};
+
+use crate::cellar::{Batch, BatchStatus};
+use crate::devices::{DeviceReading, JsonFields, to_celsius, to_specific_gravity};
+use crate::sensors::RECONNECT_DELAY;
+pub use crate::sensors::SensorEvent;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    /// Topic filter for gravity messages; `+` and `#` wildcards are allowed.
+    pub gravity_topic: String,
+    /// Topic filter for temperature messages, if temperature is published
+    /// separately from gravity.
+    pub temperature_topic: Option<String>,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        MqttConfig {
+            host: "localhost".to_owned(),
+            port: 1883,
+            gravity_topic: "ispindel/+/gravity".to_owned(),
+            temperature_topic: Some("ispindel/+/temperature".to_owned()),
+        }
+    }
+}
+
+/// A running subscription. Readings arrive on `events` until it is dropped.
+pub struct Subscription {
+    client: Client,
+    pub events: Receiver<SensorEvent>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let _ = self.client.disconnect();
+    }
+}
+
+fn topic_matches(filter: &str, topic: &str) -> bool {
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        match (level, topic_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (level, Some(topic_level)) if level == topic_level => {}
+            _ => return false,
+        }
+    }
+    topic_levels.next().is_none()
+}
+
+/// Gravity as SG and temperature in °C, as far as a sensor message says. A
+/// bare number is gravity or temperature depending on the topic it came in on.
+pub fn parse_message(payload: &[u8], is_gravity: bool) -> (Option<f64>, Option<f64>) {
+    let Ok(text) = std::str::from_utf8(payload) else {
+        return (None, None);
+    };
+    match text.trim().parse::<f64>() {
+        Ok(value) if is_gravity => (Some(to_specific_gravity(value)), None),
+        Ok(value) => (None, Some(to_celsius(value, None))),
+        Err(_) => serde_json::from_str(text)
+            .map(|value| JsonFields::from_value(&value).normalized())
+            .unwrap_or((None, None)),
+    }
+}
+
+/// Connects to the broker and streams a reading for every gravity message,
+/// paired with the latest temperature. `notify` is called after each event,
+/// e.g. to wake a UI.
+pub fn subscribe(
+    config: &MqttConfig,
+    notify: impl Fn() + Send + 'static,
+) -> Result<Subscription, ClientError> {
+    let client_id = format!("wine-maker-{}", std::process::id());
+    let mut options = MqttOptions::new(client_id, config.host.clone(), config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+
+    let (client, mut connection) = Client::new(options, 16);
+    client.subscribe(config.gravity_topic.clone(), QoS::AtMostOnce)?;
+    if let Some(topic) = &config.temperature_topic {
+        client.subscribe(topic.clone(), QoS::AtMostOnce)?;
+    }
+    info!(host = %config.host, port = config.port, "subscribed to MQTT sensors");
+
+    let (sender, events) = mpsc::channel();
+    let config = config.clone();
+    thread::spawn(move || {
+        let mut temperature = None;
+        for notification in connection.iter() {
+            let event = match notification {
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    let is_gravity = topic_matches(&config.gravity_topic, &publish.topic);
+                    let is_temperature = config
+                        .temperature_topic
+                        .as_deref()
+                        .is_some_and(|filter| topic_matches(filter, &publish.topic));
+                    if !is_gravity && !is_temperature {
+                        continue;
+                    }
+                    let (gravity, message_temperature) =
+                        parse_message(&publish.payload, is_gravity);
+                    temperature = message_temperature.or(temperature);
+                    debug!(topic = %publish.topic, ?gravity, ?temperature, "sensor message");
+                    match gravity {
+                        Some(specific_gravity) if is_gravity => {
+                            SensorEvent::Reading(DeviceReading {
+                                taken_at: Local::now().naive_local(),
+                                specific_gravity,
+                                temperature,
+                            })
+                        }
+                        _ => continue,
+                    }
+                }
+                Ok(_) => continue,
+                Err(err) => {
+                    warn!("MQTT connection error: {}", err);
+                    thread::sleep(RECONNECT_DELAY);
+                    SensorEvent::Disconnected(err.to_string())
+                }
+            };
+            if sender.send(event).is_err() {
+                break;
+            }
+            notify();
+        }
+    });
+
+    Ok(Subscription { client, events })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HomeAssistantConfig {
+    pub host: String,
+    pub port: u16,
+    /// The prefix Home Assistant watches for discovery messages.
+    pub discovery_prefix: String,
+    /// The prefix batch state is published under.
+    pub state_prefix: String,
+}
+
+impl Default for HomeAssistantConfig {
+    fn default() -> Self {
+        HomeAssistantConfig {
+            host: "localhost".to_owned(),
+            port: 1883,
+            discovery_prefix: "homeassistant".to_owned(),
+            state_prefix: "wine-maker".to_owned(),
+        }
+    }
+}
+
+/// The sensors announced for each batch: the state field each reads, its
+/// name, and its unit and device class, if any.
+const ENTITIES: [(&str, &str, Option<&str>, Option<&str>); 6] = [
+    (
+        "days_remaining",
+        "Days remaining",
+        Some("d"),
+        Some("duration"),
+    ),
+    ("abv", "ABV", Some("%"), None),
+    ("specific_gravity", "Specific gravity", None, None),
+    ("stage", "Stage", None, None),
+    ("next_task", "Next task", None, None),
+    ("next_task_due", "Next task due", None, Some("date")),
+];
+
+impl HomeAssistantConfig {
+    /// The topic a batch's state is published on.
+    pub fn state_topic(&self, batch_id: i64) -> String {
+        format!("{}/batch/{}/state", self.state_prefix, batch_id)
+    }
+
+    fn config_topic(&self, batch_id: i64, key: &str) -> String {
+        format!(
+            "{}/sensor/wine_maker_batch_{}/{}/config",
+            self.discovery_prefix, batch_id, key
+        )
+    }
+}
+
+/// The discovery messages announcing a batch's sensors, as topic and
+/// payload pairs.
+pub fn discovery_messages(config: &HomeAssistantConfig, batch: &Batch) -> Vec<(String, String)> {
+    let device = format!("wine_maker_batch_{}", batch.id);
+    ENTITIES
+        .iter()
+        .map(|&(key, name, unit, device_class)| {
+            let mut payload = json!({
+                "name": name,
+                "unique_id": format!("{}_{}", device, key),
+                "state_topic": config.state_topic(batch.id),
+                "value_template": format!("{{{{ value_json.{} }}}}", key),
+                "device": {
+                    "identifiers": [device],
+                    "name": batch.name,
+                    "model": batch.input.grape_type,
+                    "manufacturer": "Wine Maker",
+                },
+            });
+            if let Some(unit) = unit {
+                payload["unit_of_measurement"] = json!(unit);
+            }
+            if let Some(device_class) = device_class {
+                payload["device_class"] = json!(device_class);
+            }
+            (config.config_topic(batch.id, key), payload.to_string())
+        })
+        .collect()
+}
+
+/// A batch's state message, as topic and payload. Numbers are rounded to
+/// the precision a hydrometer gives.
+pub fn state_message(
+    config: &HomeAssistantConfig,
+    batch_id: i64,
+    status: &BatchStatus,
+) -> (String, String) {
+    let payload = json!({
+        "day": status.day,
+        "days_remaining": status.days_remaining,
+        "abv": (status.abv * 10.0).round() / 10.0,
+        "specific_gravity": (status.specific_gravity * 1000.0).round() / 1000.0,
+        "residual_sugar": (status.residual_sugar * 10.0).round() / 10.0,
+        "measured_on": status.measured_on.map(|date| date.to_string()),
+        "stage": status.stage.map(|stage| stage.label()),
+        "next_task": status.next_task.as_ref().map(|task| task.title.clone()),
+        "next_task_due": status.next_task.as_ref().map(|task| task.due_on.to_string()),
+    });
+    (config.state_topic(batch_id), payload.to_string())
+}
+
+/// A connection publishing batch status for Home Assistant from a
+/// background thread. Connection and publishing errors arrive on `errors`
+/// until it is dropped.
+pub struct Publisher {
+    client: Client,
+    updates: SyncSender<Vec<(Batch, BatchStatus)>>,
+    pub errors: Receiver<String>,
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        let _ = self.client.disconnect();
+    }
+}
+
+impl Publisher {
+    pub fn connect(config: &HomeAssistantConfig) -> Self {
+        let client_id = format!("wine-maker-ha-{}", std::process::id());
+        let mut options = MqttOptions::new(client_id, config.host.clone(), config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+
+        let (client, mut connection) = Client::new(options, 64);
+        info!(host = %config.host, port = config.port, "publishing to Home Assistant");
+
+        let (sender, errors) = mpsc::channel();
+        let connection_errors = sender.clone();
+        thread::spawn(move || {
+            for notification in connection.iter() {
+                if let Err(err) = notification {
+                    warn!("MQTT connection error: {}", err);
+                    if connection_errors.send(err.to_string()).is_err() {
+                        break;
+                    }
+                    thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        });
+
+        // Publishing blocks while the request queue is full or the broker
+        // isn't connected yet, so it is kept off the caller's thread.
+        let (updates, pending) = mpsc::sync_channel::<Vec<(Batch, BatchStatus)>>(1);
+        let (publisher, config) = (client.clone(), config.clone());
+        thread::spawn(move || {
+            let mut announced = HashSet::new();
+            for batches in pending {
+                if let Err(err) = publish(&publisher, &config, &mut announced, &batches) {
+                    warn!("Could not publish to Home Assistant: {}", err);
+                    if sender.send(err.to_string()).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Publisher {
+            client,
+            updates,
+            errors,
+        }
+    }
+
+    /// Queues the state of `batches` to be published, unless the last
+    /// batches queued are still waiting to go out.
+    pub fn publish(&self, batches: Vec<(Batch, BatchStatus)>) {
+        if let Err(TrySendError::Full(_)) = self.updates.try_send(batches) {
+            debug!("still publishing the last batch status");
+        }
+    }
+}
+
+/// Publishes the state of `batches`, announcing any not in `announced` yet
+/// and withdrawing the sensors of any announced before but missing now.
+/// Messages are retained, so Home Assistant has them after a restart.
+fn publish(
+    client: &Client,
+    config: &HomeAssistantConfig,
+    announced: &mut HashSet<i64>,
+    batches: &[(Batch, BatchStatus)],
+) -> Result<(), ClientError> {
+    for (batch, status) in batches {
+        if !announced.contains(&batch.id) {
+            for (topic, payload) in discovery_messages(config, batch) {
+                client.publish(topic, QoS::AtLeastOnce, true, payload)?;
+            }
+            // Only once every sensor is queued, so a failure retries them all.
+            announced.insert(batch.id);
+        }
+        let (topic, payload) = state_message(config, batch.id, status);
+        client.publish(topic, QoS::AtLeastOnce, true, payload)?;
+    }
+
+    let current: HashSet<i64> = batches.iter().map(|(batch, _)| batch.id).collect();
+    let gone: Vec<i64> = announced.difference(&current).copied().collect();
+    for batch_id in gone {
+        // An empty retained config removes the sensor.
+        for (key, ..) in ENTITIES {
+            client.publish(
+                config.config_topic(batch_id, key),
+                QoS::AtLeastOnce,
+                true,
+                "",
+            )?;
+        }
+        client.publish(config.state_topic(batch_id), QoS::AtLeastOnce, true, "")?;
+        announced.remove(&batch_id);
+    }
+    debug!(batches = batches.len(), "published batch status");
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use crate::engine::{
+    Beverage, Inoculation, InterventionKind, SimulationInput, SimulationResult, is_red_grape,
+    restart_day, wild_ferment,
+};
+use crate::sulfides::{SulfideStage, sulfide_fault};
+
+pub fn narrative(input: &SimulationInput, result: &SimulationResult) -> String {
+    let noun = input.beverage.noun();
+    let climate = if input.beverage.uses_climate() {
+        format!(" (adjusted for a {} climate)", input.climate.to_lowercase())
+    } else {
+        String::new()
+    };
+    let excursions = if input.excursions.is_empty() {
+        String::new()
+    } else {
+        let spells: Vec<String> = input
+            .excursions
+            .iter()
+            .map(|excursion| {
+                format!(
+                    "{} days at {}°C from day {}",
+                    excursion.days, excursion.temperature, excursion.start_day
+                )
+            })
+            .collect();
+        let off_flavors = if result.off_flavors.is_empty() {
+            String::new()
+        } else {
+            format!(", and it picked up {}", result.off_flavors.join(" and "))
+        };
+        format!(
+            "Along the way it went through {}, giving about a {:.0}% chance of a stall{}.\n\n",
+            spells.join(", "),
+            result.stall_risk * 100.0,
+            off_flavors
+        )
+    };
+    let wild = wild_ferment(input);
+    let mut aromas = String::new();
+    if wild.is_none() && !result.yeast_aromas.is_empty() {
+        let yeast = match input.yeast.trim() {
+            "" => "yeast".to_owned(),
+            strain => format!("{} yeast", strain),
+        };
+        aromas += &format!(
+            " The {} brought out {}.",
+            yeast,
+            result.yeast_aromas.join(" and ")
+        );
+    }
+    if !result.esters.is_empty() {
+        aromas += &format!(" The cool fermentation preserved {}.", result.esters);
+    }
+    let sulfides = sulfide_fault(input);
+    // A wild ferment's flaw and sulfides are the yeast's doing, not the
+    // temperature's.
+    let temperature_flavors: Vec<&str> = result
+        .off_flavors
+        .iter()
+        .map(String::as_str)
+        .filter(|&off_flavor| wild.is_none_or(|wild| wild.character != off_flavor))
+        .filter(|&off_flavor| {
+            sulfides
+                .and_then(|fault| fault.remaining)
+                .is_none_or(|stage| stage.descriptor() != off_flavor)
+        })
+        .collect();
+    if input.excursions.is_empty() && !temperature_flavors.is_empty() {
+        aromas += &format!(
+            " The warm fermentation left {}.",
+            temperature_flavors.join(" and ")
+        );
+    }
+    let sulfide = match sulfides {
+        Some(fault) => {
+            let outcome = match (fault.cured_by, fault.remaining) {
+                (Some((InterventionKind::Aeration, day)), _) => {
+                    format!(", but aerating it on day {} blew it off", day)
+                }
+                (Some((InterventionKind::Racking, day)), _) => {
+                    format!(", but racking it on day {} blew it off", day)
+                }
+                (Some((_, day)), _) => format!(", but copper added on day {} cleared it", day),
+                (None, Some(SulfideStage::HydrogenSulfide)) => {
+                    format!(", leaving {}", SulfideStage::HydrogenSulfide.descriptor())
+                }
+                (None, Some(stage)) => {
+                    format!(", which by bottling had turned into {}", stage.descriptor())
+                }
+                (None, None) => String::new(),
+            };
+            format!(
+                "Short of nitrogen, the yeast began giving off hydrogen sulfide on day {}{}.\n\n",
+                fault.onset_day, outcome
+            )
+        }
+        None => String::new(),
+    };
+    let malolactic = match input.inoculation {
+        Inoculation::SequentialMalolactic => format!(
+            "Malolactic bacteria pitched once the yeast finished softened the acidity over about {} more days, leaving {}.\n\n",
+            input.inoculation.malolactic_days_after(),
+            result.malolactic_note
+        ),
+        Inoculation::CoInoculatedMalolactic => format!(
+            "Malolactic bacteria pitched with the yeast softened the acidity as it fermented, leaving {}.\n\n",
+            result.malolactic_note
+        ),
+        _ => String::new(),
+    };
+    let lees = if result.lees_note.is_empty() {
+        String::new()
+    } else if input.stirs_per_month == 0 {
+        format!(
+            "It rested undisturbed on its lees for {} months, gaining {}.\n\n",
+            input.lees_months, result.lees_note
+        )
+    } else {
+        format!(
+            "It rested on its lees for {} months, stirred {} times a month, gaining {}.\n\n",
+            input.lees_months, input.stirs_per_month, result.lees_note
+        )
+    };
+    let degassing = if result.needs_degassing {
+        format!(
+            "It still holds about {:.1} g/L of dissolved CO2 and would taste spritzy unless degassed before bottling.\n\n",
+            result.dissolved_co2
+        )
+    } else {
+        String::new()
+    };
+    let closure = format!(
+        "Sealed with a {}, it should be at its best about {:.1} years after bottling{}.\n\n",
+        input.closure.label().to_lowercase(),
+        result.peak_years,
+        if result.reduction_risk {
+            ", though it may be reductive for the first year"
+        } else {
+            ""
+        }
+    );
+    let native = match wild {
+        Some(wild) => {
+            let outcome = if wild.flawed {
+                "but left"
+            } else {
+                "bringing out"
+            };
+            format!(
+                "The native yeast took {:.1} days to get going and could take no more than {:.1}% ABV, {} {}.\n\n",
+                wild.lag_days, wild.tolerance, outcome, wild.character
+            )
+        }
+        None => String::new(),
+    };
+    let restart = match restart_day(input) {
+        Some(day) if day < input.fermentation_days => format!(
+            "Fresh champagne yeast pitched on day {} restarted the sluggish fermentation.\n\n",
+            day
+        ),
+        _ => String::new(),
+    };
+    let pressing = if input.beverage == Beverage::Wine
+        && is_red_grape(&input.grape_type)
+        && input.press_wine > 0.0
+    {
+        format!(
+            "Blending back {:.0}% of the press wine kept {:.0}% of the wine off the skins.\n\n",
+            input.press_wine.min(100.0),
+            result.wine_yield * 100.0
+        )
+    } else {
+        String::new()
+    };
+    let aging = if input.aging_months > 0 {
+        let vessel = if input.aging_container.trim().is_empty() {
+            &input.container_type
+        } else {
+            &input.aging_container
+        };
+        format!(
+            "It then aged for {} months in a {} that adds {}, taking up about {:.0} mg/L of oxygen and leaving it {}.\n\n",
+            input.aging_months,
+            vessel,
+            result.aging_note,
+            result.oxygen_uptake,
+            result.oxygen_exposure
+        )
+    } else {
+        String::new()
+    };
+    let category = if result.eu_sweetness.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " (labelled {} under EU rules, or {} if made sparkling)",
+            result.eu_sweetness, result.eu_sparkling_sweetness
+        )
+    };
+    let balance: String = result
+        .balance_faults
+        .iter()
+        .map(|fault| format!("; {}", fault.description()))
+        .collect();
+    let scales: String = result
+        .extra_descriptors
+        .iter()
+        .map(|(scale, label)| format!("On the {} scale it is {}.\n\n", scale, label))
+        .collect();
+    format!(
+        "Your {} {} was fermented over {} days in a {} that adds {}. \
+         The initial sugar level was {:.1} g/L{}, which could have reached a potential of {:.1}% ABV.\n\n\
+         Fermenting at {}°C, about {:.1}% of that potential was met, resulting in a final ABV of {:.1}% and leaving behind a residual sugar of {:.1} g/L, making it {}{}.\n\n\
+         The {} is {} in body, with {} tannins and {} acidity{}. In the glass it is {}. It shows hints of {} in its flavor profile.{}\n\n\
+         {}{}{}{}{}{}{}{}{}{}The alcohol content is classified as {}.\n\n\
+         {}Enjoy your {}.",
+        input.grape_type,
+        noun,
+        input.fermentation_days,
+        input.container_type,
+        result.container_note,
+        result.sugar_content,
+        climate,
+        result.potential_abv,
+        input.temperature,
+        result.fraction_fermented * 100.0,
+        result.actual_abv,
+        result.residual_sugar,
+        result.sweetness,
+        category,
+        noun,
+        result.body,
+        result.tannin_level,
+        result.acidity,
+        balance,
+        result.color,
+        result.grape_characteristics.to_ascii_lowercase(),
+        aromas,
+        native,
+        sulfide,
+        malolactic,
+        excursions,
+        restart,
+        pressing,
+        lees,
+        aging,
+        degassing,
+        closure,
+        result.alcohol_level,
+        scales,
+        noun
+    )
+}
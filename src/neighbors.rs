@@ -0,0 +1,236 @@
+//! Real wines a simulated one resembles, from a table of commercial wines
+//! such as `WineDataset.csv` or a public review dataset. A `ColumnMapping`
+//! says which of the file's columns holds each attribute, so any layout can
+//! be read.
+
+use std::fmt::Write as _;
+use std::io;
+
+use csv::ReaderBuilder;
+
+use crate::dataset::parse_abv;
+use crate::engine::{SimulationInput, SimulationResult, is_red_grape};
+
+/// The file's column for each attribute, by header. Optional attributes
+/// can be left unmapped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+    pub name: Option<String>,
+    pub grape: String,
+    /// Red, White, Rosé, and so on.
+    pub wine_type: Option<String>,
+    pub abv: Option<String>,
+    pub country: Option<String>,
+    pub region: Option<String>,
+}
+
+impl Default for ColumnMapping {
+    /// The layout of `WineDataset.csv`.
+    fn default() -> Self {
+        ColumnMapping {
+            name: Some("Title".to_owned()),
+            grape: "Grape".to_owned(),
+            wine_type: Some("Type".to_owned()),
+            abv: Some("ABV".to_owned()),
+            country: Some("Country".to_owned()),
+            region: Some("Region".to_owned()),
+        }
+    }
+}
+
+/// Header names other datasets use for each attribute, lowercase.
+const NAME_HEADERS: [&str; 4] = ["title", "name", "wine", "label"];
+const GRAPE_HEADERS: [&str; 4] = ["grape", "variety", "varietal", "grape variety"];
+const TYPE_HEADERS: [&str; 4] = ["type", "color", "colour", "wine type"];
+const ABV_HEADERS: [&str; 4] = ["abv", "alcohol", "alcohol %", "alc"];
+const COUNTRY_HEADERS: [&str; 1] = ["country"];
+const REGION_HEADERS: [&str; 4] = ["region", "region_1", "province", "appellation"];
+
+fn find(headers: &[String], candidates: &[&str]) -> Option<String> {
+    candidates.iter().find_map(|candidate| {
+        headers
+            .iter()
+            .find(|header| header.trim().eq_ignore_ascii_case(candidate))
+            .cloned()
+    })
+}
+
+impl ColumnMapping {
+    /// A mapping from common header names, or `None` if no column looks like
+    /// the grape.
+    pub fn guess(headers: &[String]) -> Option<Self> {
+        Some(ColumnMapping {
+            name: find(headers, &NAME_HEADERS),
+            grape: find(headers, &GRAPE_HEADERS)?,
+            wine_type: find(headers, &TYPE_HEADERS),
+            abv: find(headers, &ABV_HEADERS),
+            country: find(headers, &COUNTRY_HEADERS),
+            region: find(headers, &REGION_HEADERS),
+        })
+    }
+}
+
+/// A commercial wine to compare against.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReferenceWine {
+    pub name: String,
+    pub grape: String,
+    pub wine_type: String,
+    pub abv: Option<f64>,
+    pub country: String,
+    pub region: String,
+}
+
+impl ReferenceWine {
+    fn is_red(&self) -> bool {
+        if self.wine_type.is_empty() {
+            is_red_grape(&self.grape)
+        } else {
+            self.wine_type.trim().eq_ignore_ascii_case("red")
+        }
+    }
+
+    /// Where the wine is from, most precise first, e.g. "Marlborough, New
+    /// Zealand".
+    pub fn origin(&self) -> String {
+        [self.region.trim(), self.country.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The header row of a CSV file, for choosing a mapping.
+pub fn csv_headers(data: &str) -> Result<Vec<String>, csv::Error> {
+    let mut rdr = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(data.as_bytes());
+    Ok(rdr
+        .headers()?
+        .iter()
+        .map(|header| header.to_owned())
+        .collect())
+}
+
+/// Reads the wines in `data` through `mapping`, skipping rows without a
+/// grape.
+pub fn load_reference_wines(
+    data: &str,
+    mapping: &ColumnMapping,
+) -> Result<Vec<ReferenceWine>, csv::Error> {
+    let mut rdr = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(data.as_bytes());
+    let headers = rdr.headers()?.clone();
+    let position = |name: &str| headers.iter().position(|header| header == name);
+    let grape = position(&mapping.grape).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("dataset is missing the \"{}\" column", mapping.grape),
+        )
+    })?;
+    let optional = |column: &Option<String>| column.as_deref().and_then(position);
+    let (name, wine_type, abv, country, region) = (
+        optional(&mapping.name),
+        optional(&mapping.wine_type),
+        optional(&mapping.abv),
+        optional(&mapping.country),
+        optional(&mapping.region),
+    );
+
+    let mut wines = Vec::new();
+    for result in rdr.records() {
+        let row = result?;
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|index| row.get(index))
+                .unwrap_or_default()
+                .trim()
+                .to_owned()
+        };
+        let wine = ReferenceWine {
+            name: cell(name),
+            grape: cell(Some(grape)),
+            wine_type: cell(wine_type),
+            abv: parse_abv(&cell(abv)),
+            country: cell(country),
+            region: cell(region),
+        };
+        if !wine.grape.is_empty() {
+            wines.push(wine);
+        }
+    }
+    Ok(wines)
+}
+
+// How far apart two wines are: each point is worth a percent of ABV.
+const OTHER_GRAPE: f64 = 2.0;
+const OTHER_COLOR: f64 = 4.0;
+/// Assumed ABV difference to a wine that doesn't give its ABV.
+const UNKNOWN_ABV: f64 = 1.5;
+
+/// A reference wine and how far it is from the simulated one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Neighbor<'a> {
+    pub wine: &'a ReferenceWine,
+    pub distance: f64,
+}
+
+fn distance(input: &SimulationInput, result: &SimulationResult, wine: &ReferenceWine) -> f64 {
+    let abv = wine
+        .abv
+        .map_or(UNKNOWN_ABV, |abv| (abv - result.actual_abv).abs());
+    let grape = if wine.grape.eq_ignore_ascii_case(input.grape_type.trim()) {
+        0.0
+    } else {
+        OTHER_GRAPE
+    };
+    let color = if wine.is_red() == is_red_grape(&input.grape_type) {
+        0.0
+    } else {
+        OTHER_COLOR
+    };
+    abv + grape + color
+}
+
+/// The `count` reference wines closest to the simulated one, nearest first.
+pub fn nearest_wines<'a>(
+    input: &SimulationInput,
+    result: &SimulationResult,
+    wines: &'a [ReferenceWine],
+    count: usize,
+) -> Vec<Neighbor<'a>> {
+    let mut neighbors: Vec<Neighbor> = wines
+        .iter()
+        .map(|wine| Neighbor {
+            wine,
+            distance: distance(input, result, wine),
+        })
+        .collect();
+    neighbors.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    neighbors.truncate(count);
+    neighbors
+}
+
+/// "Resembles Oyster Bay Sauvignon Blanc 2022 (Marlborough, New Zealand),
+/// a Sauvignon Blanc at ~13.0% ABV".
+pub fn describe(neighbor: &Neighbor) -> String {
+    let wine = neighbor.wine;
+    let mut text = if wine.name.is_empty() {
+        format!("Resembles a {}", wine.grape)
+    } else {
+        format!("Resembles {}", wine.name)
+    };
+    let origin = wine.origin();
+    if !origin.is_empty() {
+        let _ = write!(text, " ({})", origin);
+    }
+    if !wine.name.is_empty() {
+        let _ = write!(text, ", a {}", wine.grape);
+    }
+    if let Some(abv) = wine.abv {
+        let _ = write!(text, " at ~{:.1}% ABV", abv);
+    }
+    text
+}
@@ -0,0 +1,189 @@
+//! A quality score for a simulated wine, and a grid search for the plans
+//! that score best for a given fruit and sugar.
+
+use crate::cellar::DRY_SUGAR;
+use crate::dataset::Dataset;
+use crate::engine::{
+    Beverage, SimulationInput, SimulationResult, YEAST_STRAINS, is_red_grape, run_simulation,
+    yeast_tolerance,
+};
+use crate::stage::bottling_day;
+
+/// The score every wine starts from before its strengths and faults.
+const BASE_SCORE: f64 = 70.0;
+const OFF_FLAVOR_PENALTY: f64 = 12.0;
+/// Points off for a fermentation that stopped short with
+/// `UNFINISHED_SUGAR` g/L or more left over dry, scaled down for less.
+const UNFINISHED_PENALTY: f64 = 20.0;
+const UNFINISHED_SUGAR: f64 = 20.0;
+/// Points off for a certain stall, scaled by the stall risk.
+const STALL_PENALTY: f64 = 30.0;
+const ESTER_BONUS: f64 = 8.0;
+const YEAST_AROMA_BONUS: f64 = 3.0;
+/// Points for the right amount of oxygen for the style.
+const OXYGEN_BONUS: f64 = 6.0;
+const OXIDIZED_PENALTY: f64 = 10.0;
+const REDUCTION_PENALTY: f64 = 8.0;
+const SPRITZ_PENALTY: f64 = 4.0;
+/// Points off for each way the wine is out of balance.
+const BALANCE_PENALTY: f64 = 6.0;
+/// Points per year the wine keeps improving in bottle, up to
+/// `MAX_PEAK_YEARS`.
+const PEAK_YEAR_BONUS: f64 = 1.0;
+const MAX_PEAK_YEARS: f64 = 10.0;
+// Oxygen uptake in mg/L that softens a red, and past which any wine tastes
+// oxidized. Whites are best kept under the first.
+const GENTLE_OXYGEN: f64 = 3.0;
+const OXIDIZED_OXYGEN: f64 = 15.0;
+
+/// A score out of 100 and what went into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quality {
+    pub score: f64,
+    /// Points each strength added or fault took off.
+    pub factors: Vec<(&'static str, f64)>,
+}
+
+/// How good the wine from `input` and `result` is likely to be.
+pub fn quality(input: &SimulationInput, result: &SimulationResult) -> Quality {
+    let mut factors = Vec::new();
+    let left_over = result.residual_sugar - DRY_SUGAR;
+    if left_over > 0.0 && result.actual_abv < yeast_tolerance(input) - 0.5 {
+        factors.push((
+            "Unfinished fermentation",
+            -UNFINISHED_PENALTY * (left_over / UNFINISHED_SUGAR).min(1.0),
+        ));
+    }
+    if result.stall_risk > 0.0 {
+        factors.push(("Stall risk", -STALL_PENALTY * result.stall_risk));
+    }
+    if !result.off_flavors.is_empty() {
+        factors.push((
+            "Off-flavors",
+            -OFF_FLAVOR_PENALTY * result.off_flavors.len() as f64,
+        ));
+    }
+    if !result.esters.is_empty() {
+        factors.push(("Esters", ESTER_BONUS));
+    }
+    if !result.yeast_aromas.is_empty() {
+        factors.push((
+            "Yeast aromas",
+            YEAST_AROMA_BONUS * result.yeast_aromas.len() as f64,
+        ));
+    }
+    let red = input.beverage == Beverage::Wine && is_red_grape(&input.grape_type);
+    if result.oxygen_uptake >= OXIDIZED_OXYGEN {
+        factors.push(("Oxidized", -OXIDIZED_PENALTY));
+    } else if red == (result.oxygen_uptake >= GENTLE_OXYGEN) {
+        factors.push(("Oxygen suits the style", OXYGEN_BONUS));
+    }
+    if result.reduction_risk {
+        factors.push(("Reduction risk", -REDUCTION_PENALTY));
+    }
+    if result.needs_degassing {
+        factors.push(("Needs degassing", -SPRITZ_PENALTY));
+    }
+    for fault in &result.balance_faults {
+        factors.push((fault.label(), -BALANCE_PENALTY));
+    }
+    if result.peak_years > 0.0 {
+        factors.push((
+            "Aging potential",
+            PEAK_YEAR_BONUS * result.peak_years.min(MAX_PEAK_YEARS),
+        ));
+    }
+    let score = factors
+        .iter()
+        .fold(BASE_SCORE, |score, (_, points)| score + points)
+        .clamp(0.0, 100.0);
+    Quality { score, factors }
+}
+
+/// The values the optimizer tries for each of the settings it searches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchSpace {
+    pub fermentation_days: Vec<u32>,
+    /// Fermentation temperatures in °C.
+    pub temperatures: Vec<f64>,
+    pub yeasts: Vec<String>,
+    pub aging_months: Vec<u32>,
+}
+
+impl Default for SearchSpace {
+    fn default() -> Self {
+        Self {
+            fermentation_days: vec![7, 10, 14, 21, 28],
+            temperatures: vec![12.0, 15.0, 18.0, 21.0, 24.0, 27.0, 30.0],
+            yeasts: YEAST_STRAINS
+                .iter()
+                .map(|strain| strain.name.to_owned())
+                .collect(),
+            aging_months: vec![0, 3, 6, 12, 18],
+        }
+    }
+}
+
+impl SearchSpace {
+    /// The number of plans the search tries.
+    pub fn size(&self) -> usize {
+        self.fermentation_days.len()
+            * self.temperatures.len()
+            * self.yeasts.len()
+            * self.aging_months.len()
+    }
+}
+
+/// A plan the optimizer tried, with the wine it makes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub input: SimulationInput,
+    pub result: SimulationResult,
+    pub quality: Quality,
+    /// Days from pitching to bottling, the time the quality costs.
+    pub days_to_bottle: u32,
+}
+
+/// The `top` best plans for `base`'s fruit and sugar, trying every
+/// combination in `space`. Ties go to the plan that is bottled soonest.
+pub fn optimize(
+    base: &SimulationInput,
+    space: &SearchSpace,
+    wine_data: &Dataset,
+    top: usize,
+) -> Vec<Plan> {
+    let mut plans = Vec::with_capacity(space.size());
+    for &fermentation_days in &space.fermentation_days {
+        for &temperature in &space.temperatures {
+            for yeast in &space.yeasts {
+                for &aging_months in &space.aging_months {
+                    let input = SimulationInput {
+                        fermentation_days,
+                        temperature,
+                        yeast: yeast.clone(),
+                        aging_months,
+                        ..base.clone()
+                    };
+                    // Temperatures the yeast can't work at aren't plans.
+                    let Ok(result) = run_simulation(&input, wine_data) else {
+                        continue;
+                    };
+                    plans.push(Plan {
+                        quality: quality(&input, &result),
+                        days_to_bottle: bottling_day(&input),
+                        input,
+                        result,
+                    });
+                }
+            }
+        }
+    }
+    plans.sort_by(|a, b| {
+        b.quality
+            .score
+            .total_cmp(&a.quality.score)
+            .then(a.days_to_bottle.cmp(&b.days_to_bottle))
+    });
+    plans.truncate(top);
+    plans
+}
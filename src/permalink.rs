@@ -0,0 +1,195 @@
+//! Simulation inputs packed into a short, URL-safe code, so a simulation can
+//! be shared by pasting the code into the app or adding it to a web link.
+//!
+//! The code is the inputs one per line, after a version, in URL-safe base64.
+//! Numbers are written so they read back bit for bit, and the seed goes
+//! with them, so the same code always gives the same wine.
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+use crate::engine::{
+    Beverage, Closure, Excursion, Inoculation, Intervention, InterventionKind, SimulationInput,
+};
+
+const VERSION: &str = "1";
+
+/// The part of a web-build URL that carries a code, as in `#sim=…`.
+pub const URL_KEY: &str = "sim";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PermalinkError {
+    /// Not base64, or not text once decoded.
+    Malformed,
+    UnsupportedVersion(String),
+    /// A field is missing or can't be read.
+    InvalidField(&'static str),
+}
+
+impl fmt::Display for PermalinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermalinkError::Malformed => write!(f, "not a simulation code"),
+            PermalinkError::UnsupportedVersion(version) => write!(
+                f,
+                "simulation code version {} is newer than this release supports ({})",
+                version, VERSION
+            ),
+            PermalinkError::InvalidField(field) => {
+                write!(f, "simulation code has a bad {}", field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PermalinkError {}
+
+fn join<T>(items: &[T], write: impl Fn(&T) -> String) -> String {
+    items.iter().map(write).collect::<Vec<_>>().join(";")
+}
+
+/// The code for `input`.
+pub fn encode(input: &SimulationInput) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let lines = [
+        VERSION.to_owned(),
+        input.beverage.label().to_owned(),
+        input.grape_type.clone(),
+        input.fermentation_days.to_string(),
+        input.container_type.clone(),
+        input.aging_container.clone(),
+        input.aging_months.to_string(),
+        input.whole_cluster.to_string(),
+        input.press_wine.to_string(),
+        input.sugar_content.to_string(),
+        input.temperature.to_string(),
+        input.climate.clone(),
+        optional(input.seed.map(|seed| seed.to_string())),
+        optional(input.restart_day.map(|day| day.to_string())),
+        join(&input.excursions, |excursion| {
+            format!(
+                "{}:{}:{}",
+                excursion.start_day, excursion.days, excursion.temperature
+            )
+        }),
+        input.yeast.clone(),
+        input.inoculation.label().to_owned(),
+        input.lees_months.to_string(),
+        input.stirs_per_month.to_string(),
+        input.closure.label().to_owned(),
+        join(&input.interventions, |intervention| {
+            format!(
+                "{}:{}:{}",
+                intervention.day,
+                intervention.kind.label(),
+                intervention.amount
+            )
+        }),
+    ];
+    URL_SAFE_NO_PAD.encode(lines.join("\n"))
+}
+
+/// Reads the lines of a code back in order.
+struct Fields<'a>(std::str::Split<'a, char>);
+
+impl<'a> Fields<'a> {
+    fn text(&mut self, field: &'static str) -> Result<&'a str, PermalinkError> {
+        self.0.next().ok_or(PermalinkError::InvalidField(field))
+    }
+
+    fn parse<T: FromStr>(&mut self, field: &'static str) -> Result<T, PermalinkError> {
+        self.text(field)?
+            .parse()
+            .map_err(|_| PermalinkError::InvalidField(field))
+    }
+
+    fn optional<T: FromStr>(&mut self, field: &'static str) -> Result<Option<T>, PermalinkError> {
+        match self.text(field)? {
+            "" => Ok(None),
+            text => text
+                .parse()
+                .map(Some)
+                .map_err(|_| PermalinkError::InvalidField(field)),
+        }
+    }
+
+    fn list<T>(
+        &mut self,
+        field: &'static str,
+        read: impl Fn(&[&str]) -> Option<T>,
+    ) -> Result<Vec<T>, PermalinkError> {
+        self.text(field)?
+            .split(';')
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                let parts: Vec<&str> = item.split(':').collect();
+                read(&parts).ok_or(PermalinkError::InvalidField(field))
+            })
+            .collect()
+    }
+
+    fn label<T>(
+        &mut self,
+        field: &'static str,
+        from_label: fn(&str) -> Option<T>,
+    ) -> Result<T, PermalinkError> {
+        from_label(self.text(field)?).ok_or(PermalinkError::InvalidField(field))
+    }
+}
+
+/// The inputs behind `code`. Surrounding whitespace and a whole link
+/// ending in `#sim=…` are both accepted.
+pub fn decode(code: &str) -> Result<SimulationInput, PermalinkError> {
+    let code = code.trim();
+    let code = code
+        .rsplit_once(&format!("{}=", URL_KEY))
+        .map_or(code, |(_, code)| code);
+    let bytes = URL_SAFE_NO_PAD
+        .decode(code)
+        .map_err(|_| PermalinkError::Malformed)?;
+    let text = String::from_utf8(bytes).map_err(|_| PermalinkError::Malformed)?;
+    let mut fields = Fields(text.split('\n'));
+    let version = fields.text("version")?;
+    if version != VERSION {
+        return Err(PermalinkError::UnsupportedVersion(version.to_owned()));
+    }
+    Ok(SimulationInput {
+        beverage: fields.label("beverage", Beverage::from_label)?,
+        grape_type: fields.text("grape")?.to_owned(),
+        fermentation_days: fields.parse("fermentation days")?,
+        container_type: fields.text("container")?.to_owned(),
+        aging_container: fields.text("aging container")?.to_owned(),
+        aging_months: fields.parse("aging months")?,
+        whole_cluster: fields.parse("whole cluster")?,
+        press_wine: fields.parse("press wine")?,
+        sugar_content: fields.parse("sugar content")?,
+        temperature: fields.parse("temperature")?,
+        climate: fields.text("climate")?.to_owned(),
+        seed: fields.optional("seed")?,
+        restart_day: fields.optional("restart day")?,
+        excursions: fields.list("excursions", |parts| match parts {
+            [start_day, days, temperature] => Some(Excursion {
+                start_day: start_day.parse().ok()?,
+                days: days.parse().ok()?,
+                temperature: temperature.parse().ok()?,
+            }),
+            _ => None,
+        })?,
+        yeast: fields.text("yeast")?.to_owned(),
+        inoculation: fields.label("inoculation", Inoculation::from_label)?,
+        lees_months: fields.parse("lees months")?,
+        stirs_per_month: fields.parse("stirs per month")?,
+        closure: fields.label("closure", Closure::from_label)?,
+        interventions: fields.list("interventions", |parts| match parts {
+            [day, kind, amount] => Some(Intervention {
+                day: day.parse().ok()?,
+                kind: InterventionKind::from_label(kind)?,
+                amount: amount.parse().ok()?,
+            }),
+            _ => None,
+        })?,
+    })
+}
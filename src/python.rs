@@ -0,0 +1,265 @@
+use pyo3::exceptions::{PyIOError, PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::dataset;
+use crate::engine::{
+    self, Beverage, Closure, Excursion, Inoculation, Intervention, InterventionKind,
+    SimulationInput,
+};
+use crate::narrative::narrative;
+
+const INPUT_KEYS: [&str; 20] = [
+    "beverage",
+    "grape_type",
+    "fermentation_days",
+    "container_type",
+    "aging_container",
+    "aging_months",
+    "whole_cluster",
+    "press_wine",
+    "sugar_content",
+    "temperature",
+    "climate",
+    "seed",
+    "restart_day",
+    "excursions",
+    "yeast",
+    "inoculation",
+    "lees_months",
+    "stirs_per_month",
+    "closure",
+    "interventions",
+];
+
+/// A wine dataset, indexed by grape.
+#[pyclass(name = "Dataset", frozen)]
+struct PyDataset(dataset::Dataset);
+
+#[pymethods]
+impl PyDataset {
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Records as `{"grape": ..., "characteristics": ..., "type": ...,
+    /// "style": ..., "abv": ...}` dicts, optionally only those for `grape`.
+    #[pyo3(signature = (grape = None))]
+    fn records<'py>(
+        &self,
+        py: Python<'py>,
+        grape: Option<&str>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let records: Vec<&dataset::WineRecord> = match grape {
+            Some(grape) => self.0.records_for(grape),
+            None => self.0.records().iter().collect(),
+        };
+        records
+            .into_iter()
+            .map(|record| {
+                let dict = PyDict::new(py);
+                dict.set_item("grape", record.grape.as_str())?;
+                dict.set_item("characteristics", record.characteristics.as_str())?;
+                dict.set_item("type", record.wine_type.as_str())?;
+                dict.set_item("style", record.style.as_str())?;
+                dict.set_item("abv", record.abv)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+}
+
+/// Loads a dataset CSV from `path`, or the dataset bundled with the simulator
+/// when no path is given.
+#[pyfunction]
+#[pyo3(signature = (path = None))]
+fn load_dataset(path: Option<&str>) -> PyResult<PyDataset> {
+    match path {
+        Some(path) => dataset::Dataset::from_csv_path(path)
+            .map(PyDataset)
+            .map_err(|err| PyIOError::new_err(err.to_string())),
+        None => Ok(PyDataset(dataset::Dataset::bundled().clone())),
+    }
+}
+
+fn get<'py, T: FromPyObject<'py>>(dict: &Bound<'py, PyDict>, key: &str) -> PyResult<Option<T>> {
+    dict.get_item(key)?.map(|value| value.extract()).transpose()
+}
+
+fn input_from_dict(inputs: &Bound<'_, PyDict>) -> PyResult<SimulationInput> {
+    for key in inputs.keys() {
+        let key: String = key.extract()?;
+        if !INPUT_KEYS.contains(&key.as_str()) {
+            return Err(PyKeyError::new_err(format!(
+                "unknown simulation input {:?}; expected one of {:?}",
+                key, INPUT_KEYS
+            )));
+        }
+    }
+
+    let beverage = match get::<String>(inputs, "beverage")? {
+        Some(label) => Beverage::from_label(&label).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "unknown beverage {:?}; expected one of {:?}",
+                label,
+                Beverage::ALL.map(Beverage::label)
+            ))
+        })?,
+        None => Beverage::Wine,
+    };
+    let inoculation = match get::<String>(inputs, "inoculation")? {
+        Some(label) => Inoculation::from_label(&label).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "unknown inoculation {:?}; expected one of {:?}",
+                label,
+                Inoculation::ALL.map(Inoculation::label)
+            ))
+        })?,
+        None => Inoculation::Single,
+    };
+    let closure = match get::<String>(inputs, "closure")? {
+        Some(label) => Closure::from_label(&label).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "unknown closure {:?}; expected one of {:?}",
+                label,
+                Closure::ALL.map(Closure::label)
+            ))
+        })?,
+        None => Closure::NaturalCork,
+    };
+    // A list of (day, kind) or (day, kind, amount) tuples.
+    let interventions = get::<Vec<Bound<'_, PyAny>>>(inputs, "interventions")?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|item| {
+            let (day, label, amount) = match item.extract::<(u32, String, f64)>() {
+                Ok((day, label, amount)) => (day, label, Some(amount)),
+                Err(_) => {
+                    let (day, label) = item.extract::<(u32, String)>()?;
+                    (day, label, None)
+                }
+            };
+            let kind = InterventionKind::from_label(&label).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unknown intervention {:?}; expected one of {:?}",
+                    label,
+                    InterventionKind::ALL.map(InterventionKind::label)
+                ))
+            })?;
+            Ok(match amount {
+                Some(amount) => Intervention { day, kind, amount },
+                None => Intervention::new(day, kind),
+            })
+        })
+        .collect::<PyResult<_>>()?;
+    let defaults = SimulationInput::default();
+    Ok(SimulationInput {
+        beverage,
+        grape_type: get(inputs, "grape_type")?.unwrap_or(defaults.grape_type),
+        fermentation_days: get(inputs, "fermentation_days")?
+            .unwrap_or(defaults.fermentation_days),
+        container_type: get(inputs, "container_type")?.unwrap_or(defaults.container_type),
+        aging_container: get(inputs, "aging_container")?.unwrap_or(defaults.aging_container),
+        aging_months: get(inputs, "aging_months")?.unwrap_or(defaults.aging_months),
+        whole_cluster: get(inputs, "whole_cluster")?.unwrap_or(defaults.whole_cluster),
+        press_wine: get(inputs, "press_wine")?.unwrap_or(defaults.press_wine),
+        sugar_content: get(inputs, "sugar_content")?.unwrap_or(defaults.sugar_content),
+        temperature: get(inputs, "temperature")?.unwrap_or(defaults.temperature),
+        climate: get(inputs, "climate")?.unwrap_or(defaults.climate),
+        seed: get(inputs, "seed")?.flatten(),
+        restart_day: get(inputs, "restart_day")?.flatten(),
+        // A list of (start_day, days, temperature) tuples.
+        excursions: get::<Vec<(u32, u32, f64)>>(inputs, "excursions")?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(start_day, days, temperature)| Excursion {
+                start_day,
+                days,
+                temperature,
+            })
+            .collect(),
+        yeast: get(inputs, "yeast")?.unwrap_or(defaults.yeast),
+        inoculation,
+        lees_months: get(inputs, "lees_months")?.unwrap_or(defaults.lees_months),
+        stirs_per_month: get(inputs, "stirs_per_month")?.unwrap_or(defaults.stirs_per_month),
+        closure,
+        interventions,
+    })
+}
+
+/// Runs the simulator on a dict of inputs (same names as `SimulationInput`)
+/// and returns the result fields as a dict, including the narrative text.
+#[pyfunction]
+#[pyo3(signature = (inputs, dataset = None))]
+fn run_simulation<'py>(
+    py: Python<'py>,
+    inputs: &Bound<'py, PyDict>,
+    dataset: Option<PyRef<'py, PyDataset>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let input = engine::seeded(&input_from_dict(inputs)?);
+    let wine_data = match &dataset {
+        Some(dataset) => &dataset.0,
+        None => dataset::Dataset::bundled(),
+    };
+    let result = engine::run_simulation(&input, wine_data)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    let narrative = narrative(&input, &result);
+
+    let dict = PyDict::new(py);
+    dict.set_item("sugar_content", result.sugar_content)?;
+    dict.set_item("potential_abv", result.potential_abv)?;
+    dict.set_item("fraction_fermented", result.fraction_fermented)?;
+    dict.set_item("actual_abv", result.actual_abv)?;
+    dict.set_item("residual_sugar", result.residual_sugar)?;
+    dict.set_item("perceived_sugar", result.perceived_sugar)?;
+    dict.set_item("sweetness", result.sweetness)?;
+    dict.set_item("body", result.body)?;
+    dict.set_item("body_index", result.body_index)?;
+    dict.set_item("glycerol", result.glycerol)?;
+    dict.set_item("alcohol_level", result.alcohol_level)?;
+    dict.set_item("tannin_level", result.tannin_level)?;
+    dict.set_item("acidity", result.acidity)?;
+    dict.set_item(
+        "balance_faults",
+        result
+            .balance_faults
+            .iter()
+            .map(|fault| fault.label())
+            .collect::<Vec<_>>(),
+    )?;
+    dict.set_item("titratable_acidity", result.titratable_acidity)?;
+    dict.set_item("eu_sweetness", result.eu_sweetness)?;
+    dict.set_item("eu_sparkling_sweetness", result.eu_sparkling_sweetness)?;
+    dict.set_item("grape_characteristics", result.grape_characteristics)?;
+    dict.set_item("container_note", result.container_note)?;
+    dict.set_item("aging_note", result.aging_note)?;
+    dict.set_item("oxygen_uptake", result.oxygen_uptake)?;
+    dict.set_item("oxygen_exposure", result.oxygen_exposure)?;
+    dict.set_item("wine_yield", result.wine_yield)?;
+    dict.set_item("stall_risk", result.stall_risk)?;
+    dict.set_item("off_flavors", result.off_flavors)?;
+    dict.set_item("esters", result.esters)?;
+    dict.set_item("yeast_aromas", result.yeast_aromas)?;
+    dict.set_item("malolactic_note", result.malolactic_note)?;
+    dict.set_item("lees_note", result.lees_note)?;
+    dict.set_item("peak_years", result.peak_years)?;
+    dict.set_item("reduction_risk", result.reduction_risk)?;
+    dict.set_item("color", result.color)?;
+    dict.set_item("color_hex", result.color_hex)?;
+    dict.set_item("color_intensity", result.color_intensity)?;
+    dict.set_item("dissolved_co2", result.dissolved_co2)?;
+    dict.set_item("needs_degassing", result.needs_degassing)?;
+    dict.set_item("model_version", result.model_version)?;
+    dict.set_item("extra_descriptors", result.extra_descriptors)?;
+    dict.set_item("narrative", narrative)?;
+    Ok(dict)
+}
+
+/// The `wine_maker` Python module.
+#[pymodule]
+pub fn wine_maker(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDataset>()?;
+    m.add_function(wrap_pyfunction!(load_dataset, m)?)?;
+    m.add_function(wrap_pyfunction!(run_simulation, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! QR codes for sharing a simulation off the screen, such as on a bottle
+//! label: either its share code or link, or a recipe anyone can read.
+
+use std::fmt::Write as _;
+
+use qrcode::types::QrError;
+use qrcode::{Color, QrCode};
+
+use crate::engine::SimulationInput;
+
+/// Light modules left around the code so scanners can find its edge.
+const QUIET_ZONE: usize = 4;
+
+/// A QR code as a square of dark and light modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrImage {
+    width: usize,
+    dark: Vec<bool>,
+}
+
+impl QrImage {
+    /// Encodes `text`, failing if it is too long for a QR code.
+    pub fn new(text: &str) -> Result<Self, QrError> {
+        let code = QrCode::new(text)?;
+        Ok(QrImage {
+            width: code.width(),
+            dark: code
+                .to_colors()
+                .into_iter()
+                .map(|color| color == Color::Dark)
+                .collect(),
+        })
+    }
+
+    /// Modules along each side, not counting the quiet zone.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.dark[y * self.width + x]
+    }
+
+    /// The code as an SVG image with `module_size` pixels per module,
+    /// quiet zone included.
+    pub fn to_svg(&self, module_size: usize) -> String {
+        let side = (self.width + 2 * QUIET_ZONE) * module_size;
+        let mut path = String::new();
+        for y in 0..self.width {
+            for x in 0..self.width {
+                if self.is_dark(x, y) {
+                    let _ = write!(
+                        path,
+                        "M{} {}h{}v{}h-{}z",
+                        (x + QUIET_ZONE) * module_size,
+                        (y + QUIET_ZONE) * module_size,
+                        module_size,
+                        module_size,
+                        module_size
+                    );
+                }
+            }
+        }
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{side}" height="{side}" viewBox="0 0 {side} {side}" shape-rendering="crispEdges"><rect width="{side}" height="{side}" fill="#fff"/><path d="{path}" fill="#000"/></svg>"##
+        )
+    }
+}
+
+/// A short recipe for a label, readable without the app.
+pub fn recipe_summary(input: &SimulationInput) -> String {
+    let mut lines = vec![
+        format!("{} ({})", input.grape_type, input.beverage.label()),
+        format!(
+            "{:.0} g/L sugar, {} climate",
+            input.sugar_content, input.climate
+        ),
+        format!(
+            "{} days at {:.1} °C in {}",
+            input.fermentation_days, input.temperature, input.container_type
+        ),
+    ];
+    if !input.yeast.is_empty() {
+        lines.push(format!("Yeast: {}", input.yeast));
+    }
+    if input.aging_months > 0 {
+        let vessel = if input.aging_container.is_empty() {
+            &input.container_type
+        } else {
+            &input.aging_container
+        };
+        lines.push(format!("Aged {} months in {}", input.aging_months, vessel));
+    }
+    lines.push(format!("Closure: {}", input.closure.label()));
+    if let Some(seed) = input.seed {
+        lines.push(format!("Seed: {}", seed));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,287 @@
+//! A true-or-false quiz made up from the model: each statement is checked
+//! by running the engine, and the answer comes with the numbers behind it.
+
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+
+use crate::dataset::Dataset;
+use crate::descriptors::{self, SWEETNESS};
+use crate::engine::{
+    CONTAINERS, GRAPES, Inoculation, SimulationError, SimulationInput, SimulationResult,
+    is_red_grape, rate_constant, run_simulation,
+};
+
+/// How far the model can be from a claimed speed-up, as a fraction of it,
+/// for "roughly" to hold.
+const SPEED_TOLERANCE: f64 = 0.15;
+/// How far in % ABV the model can be from a claimed strength for "about".
+const ABV_TOLERANCE: f64 = 0.75;
+/// Speed-ups a statement can claim, and how it words them.
+const SPEED_UPS: [(f64, &str); 4] = [
+    (1.5, "speeds it up by about half"),
+    (2.0, "roughly doubles its speed"),
+    (3.0, "roughly triples its speed"),
+    (4.0, "roughly quadruples its speed"),
+];
+const SWEETNESSES: [&str; 4] = [
+    "bone dry",
+    "with just a subtle hint of sweetness",
+    "noticeably sweet",
+    "extremely sweet",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    Temperature,
+    Alcohol,
+    Climate,
+    Sweetness,
+    Oxygen,
+    Malolactic,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 6] = [
+        Topic::Temperature,
+        Topic::Alcohol,
+        Topic::Climate,
+        Topic::Sweetness,
+        Topic::Oxygen,
+        Topic::Malolactic,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Topic::Temperature => "Temperature",
+            Topic::Alcohol => "Alcohol",
+            Topic::Climate => "Climate",
+            Topic::Sweetness => "Sweetness",
+            Topic::Oxygen => "Oxygen",
+            Topic::Malolactic => "Malolactic fermentation",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|topic| topic.label().eq_ignore_ascii_case(label.trim()))
+    }
+}
+
+/// A statement about winemaking and whether the model bears it out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Question {
+    pub topic: Topic,
+    pub statement: String,
+    pub answer: bool,
+    /// What the model says, with its numbers.
+    pub explanation: String,
+}
+
+impl Question {
+    pub fn is_correct(&self, answer: bool) -> bool {
+        answer == self.answer
+    }
+}
+
+fn must(grape: &str, sugar: f64, temperature: f64, days: u32) -> SimulationInput {
+    SimulationInput {
+        grape_type: grape.to_owned(),
+        fermentation_days: days,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content: sugar,
+        temperature,
+        climate: "Moderate".to_owned(),
+        seed: Some(1),
+        ..SimulationInput::default()
+    }
+}
+
+fn pick<T: Copy>(rng: &mut StdRng, choices: &[T]) -> T {
+    *choices.choose(rng).expect("choices are never empty")
+}
+
+/// A question on `topic`, with its numbers drawn from `rng`.
+pub fn question(
+    topic: Topic,
+    rng: &mut StdRng,
+    wine_data: &Dataset,
+) -> Result<Question, SimulationError> {
+    let run = |input: &SimulationInput| -> Result<SimulationResult, SimulationError> {
+        run_simulation(input, wine_data)
+    };
+    let grape = pick(rng, &GRAPES);
+    let sugar = pick(rng, &[180.0, 200.0, 220.0, 240.0]);
+    let question = match topic {
+        Topic::Temperature => {
+            let low = pick(rng, &[12.0, 15.0, 18.0]);
+            let high = low + pick(rng, &[5.0, 10.0, 15.0]);
+            let (claimed, wording) = pick(rng, &SPEED_UPS);
+            let speed_up = rate_constant(high) / rate_constant(low);
+            Question {
+                topic,
+                statement: format!(
+                    "Raising a fermentation from {:.0} °C to {:.0} °C {}.",
+                    low, high, wording
+                ),
+                answer: (speed_up / claimed - 1.0).abs() <= SPEED_TOLERANCE,
+                explanation: format!(
+                    "The model ferments {:.1} times as fast at {:.0} °C as at {:.0} °C.",
+                    speed_up, high, low
+                ),
+            }
+        }
+        Topic::Alcohol => {
+            let result = run(&must(grape, sugar, 20.0, 28))?;
+            let off_by = pick(rng, &[0.0, 0.0, -2.0, 2.0]);
+            let claimed = (result.actual_abv + off_by).round();
+            Question {
+                topic,
+                statement: format!(
+                    "{} must with {:.0} g/L of sugar, fermented for four weeks at 20 °C, ends up at about {:.0}% ABV.",
+                    grape, sugar, claimed
+                ),
+                answer: (result.actual_abv - claimed).abs() <= ABV_TOLERANCE,
+                explanation: format!(
+                    "The model finishes at {:.1}% ABV with {:.1} g/L of sugar left.",
+                    result.actual_abv, result.residual_sugar
+                ),
+            }
+        }
+        Topic::Climate => {
+            let grown_in = |climate: &str| SimulationInput {
+                climate: climate.to_owned(),
+                ..must(grape, sugar, 20.0, 28)
+            };
+            let cool = run(&grown_in("Cool"))?;
+            let warm = run(&grown_in("Warm"))?;
+            let claims_warm = rng.random_bool(0.5);
+            let (stronger, weaker) = if claims_warm {
+                ("warm", "cool")
+            } else {
+                ("cool", "warm")
+            };
+            Question {
+                topic,
+                statement: format!(
+                    "{} grown in a {} climate makes a stronger wine than the same vines in a {} one.",
+                    grape, stronger, weaker
+                ),
+                answer: (warm.actual_abv > cool.actual_abv) == claims_warm,
+                explanation: format!(
+                    "The model gives {:.1}% ABV from the warm climate and {:.1}% from the cool one.",
+                    warm.actual_abv, cool.actual_abv
+                ),
+            }
+        }
+        Topic::Sweetness => {
+            let days = pick(rng, &[3, 5, 7, 14]);
+            let temperature = pick(rng, &[14.0, 18.0, 22.0]);
+            let input = must(grape, sugar, temperature, days);
+            let result = run(&input)?;
+            // On the standard scale, which `SWEETNESSES` is drawn from.
+            let sweetness =
+                descriptors::standard().describe(SWEETNESS, input.beverage, result.residual_sugar);
+            // Half the time, claim what the model says.
+            let claimed = if rng.random_bool(0.5) {
+                sweetness.clone()
+            } else {
+                pick(rng, &SWEETNESSES).to_owned()
+            };
+            Question {
+                topic,
+                statement: format!(
+                    "Stopping a {:.0} °C fermentation of {:.0} g/L sugar after {} days leaves a wine {}.",
+                    temperature, sugar, days, claimed
+                ),
+                answer: sweetness == claimed,
+                explanation: format!(
+                    "The model leaves {:.1} g/L of sugar, which is {}.",
+                    result.residual_sugar, sweetness
+                ),
+            }
+        }
+        Topic::Oxygen => {
+            let months = pick(rng, &[6, 12, 18]);
+            let first = pick(rng, &CONTAINERS);
+            let others: Vec<&str> = CONTAINERS
+                .into_iter()
+                .filter(|&container| container != first)
+                .collect();
+            let second = pick(rng, &others);
+            let aged_in = |container: &str| SimulationInput {
+                aging_container: container.to_owned(),
+                aging_months: months,
+                ..must(grape, sugar, 20.0, 21)
+            };
+            let first_uptake = run(&aged_in(first))?.oxygen_uptake;
+            let second_uptake = run(&aged_in(second))?.oxygen_uptake;
+            Question {
+                topic,
+                statement: format!(
+                    "Aging for {} months in a {} lets more oxygen into the wine than a {}.",
+                    months,
+                    first.to_lowercase(),
+                    second.to_lowercase()
+                ),
+                answer: first_uptake > second_uptake,
+                explanation: format!(
+                    "The model has the wine take up {:.1} mg/L in the {} and {:.1} mg/L in the {}.",
+                    first_uptake,
+                    first.to_lowercase(),
+                    second_uptake,
+                    second.to_lowercase()
+                ),
+            }
+        }
+        Topic::Malolactic => {
+            let climate = pick(rng, &["Cool", "Moderate", "Warm"]);
+            let plain = SimulationInput {
+                climate: climate.to_owned(),
+                ..must(grape, sugar, 20.0, 21)
+            };
+            let with_bacteria = SimulationInput {
+                inoculation: Inoculation::SequentialMalolactic,
+                ..plain.clone()
+            };
+            let before = run(&plain)?.acidity;
+            let after = run(&with_bacteria)?.acidity;
+            let claims_softer = rng.random_bool(0.5);
+            Question {
+                topic,
+                statement: format!(
+                    "Malolactic fermentation after the yeast leaves a {} {} from a {} climate with {} acidity than without it.",
+                    grape,
+                    if is_red_grape(grape) { "red" } else { "white" },
+                    climate.to_lowercase(),
+                    if claims_softer { "softer" } else { "sharper" }
+                ),
+                answer: claims_softer && after != before,
+                explanation: format!(
+                    "The model describes the acidity as {} without it and {} with it.",
+                    before, after
+                ),
+            }
+        }
+    };
+    Ok(question)
+}
+
+/// `count` questions from the same `seed`, going through the topics in a
+/// shuffled order before coming back round to them.
+pub fn quiz(
+    seed: u64,
+    count: usize,
+    wine_data: &Dataset,
+) -> Result<Vec<Question>, SimulationError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut topics = Topic::ALL;
+    topics.shuffle(&mut rng);
+    topics
+        .into_iter()
+        .cycle()
+        .take(count)
+        .map(|topic| question(topic, &mut rng, wine_data))
+        .collect()
+}
@@ -0,0 +1,356 @@
+//! Recipes in a documented JSON format, for moving a plan to and from other
+//! fermentation software.
+//!
+//! The fields follow BeerXML's recipe, yeast, and fermentation-stage fields
+//! where wine has an equivalent, in lower case and with units in SI as
+//! BeerXML has them: `og` is the original gravity, `primary_age` the days of
+//! fermentation, `primary_temp` its temperature in °C, and `age` the months
+//! of aging. Wine-only fields carry their unit in the name. A file looks like
+//!
+//! ```json
+//! {
+//!   "format": "wine-maker-recipe",
+//!   "version": 1,
+//!   "name": "Estate Cabernet",
+//!   "type": "Wine",
+//!   "fruit": "Cabernet Sauvignon",
+//!   "climate": "Moderate",
+//!   "sugar_g_per_l": 240.0,
+//!   "og": 1.1,
+//!   "yeast": { "name": "EC-1118", "inoculation": "Single yeast" },
+//!   "primary_age": 14,
+//!   "primary_temp": 24.0,
+//!   "primary_vessel": "Steel Tank",
+//!   "age": 12,
+//!   "age_vessel": "Oak Barrel",
+//!   "closure": "Natural cork"
+//! }
+//! ```
+//!
+//! Only `format`, `version`, `type`, and one of `sugar_g_per_l` or `og` are
+//! required. Leaving out `excursions` or `additions` means there are none,
+//! and every other field falls back to the beverage's defaults. When
+//! both sugar fields are given, `sugar_g_per_l` wins, and `og` is only
+//! written for other software to read. Labels such as `type`, `closure`, and
+//! addition `kind`s are the ones shown in the app.
+
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{
+    Beverage, Closure, Excursion, Inoculation, Intervention, InterventionKind, SimulationInput,
+    specific_gravity, sugar_from_original_gravity,
+};
+
+/// The `format` every recipe file names.
+pub const FORMAT: &str = "wine-maker-recipe";
+pub const VERSION: u32 = 1;
+
+/// A recipe: a named plan with notes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+    pub name: String,
+    pub notes: String,
+    pub input: SimulationInput,
+}
+
+#[derive(Debug)]
+pub enum RecipeError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// Not a recipe in this format, going by its `format` field.
+    UnknownFormat(String),
+    UnsupportedVersion(u32),
+    MissingSugar,
+    /// A label that names nothing in the app, as `(field, label)`.
+    UnknownLabel(&'static str, String),
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::Io(err) => write!(f, "I/O error: {}", err),
+            RecipeError::Json(err) => write!(f, "invalid recipe: {}", err),
+            RecipeError::UnknownFormat(format) => {
+                write!(f, "not a {} file (format \"{}\")", FORMAT, format)
+            }
+            RecipeError::UnsupportedVersion(version) => write!(
+                f,
+                "recipe version {} is newer than this release supports ({})",
+                version, VERSION
+            ),
+            RecipeError::MissingSugar => write!(f, "recipe has neither sugar_g_per_l nor og"),
+            RecipeError::UnknownLabel(field, label) => {
+                write!(f, "recipe has an unknown {} \"{}\"", field, label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecipeError {}
+
+impl From<io::Error> for RecipeError {
+    fn from(err: io::Error) -> Self {
+        RecipeError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for RecipeError {
+    fn from(err: serde_json::Error) -> Self {
+        RecipeError::Json(err)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecipeFile {
+    format: String,
+    version: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    notes: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(flatten)]
+    input: InputFields,
+}
+
+/// The fields describing the must and how it's made, shared with scenario
+/// files.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct InputFields {
+    #[serde(rename = "type")]
+    beverage: String,
+    #[serde(default)]
+    fruit: Option<String>,
+    #[serde(default)]
+    climate: Option<String>,
+    #[serde(default)]
+    sugar_g_per_l: Option<f64>,
+    #[serde(default)]
+    og: Option<f64>,
+    #[serde(default)]
+    yeast: Option<YeastField>,
+    #[serde(default)]
+    primary_age: Option<u32>,
+    #[serde(default)]
+    primary_temp: Option<f64>,
+    #[serde(default)]
+    primary_vessel: Option<String>,
+    #[serde(default)]
+    age: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    age_vessel: Option<String>,
+    #[serde(default)]
+    whole_cluster_percent: Option<f64>,
+    #[serde(default)]
+    press_wine_percent: Option<f64>,
+    #[serde(default)]
+    lees_months: Option<u32>,
+    #[serde(default)]
+    stirs_per_month: Option<u32>,
+    #[serde(default)]
+    closure: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    excursions: Vec<ExcursionField>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    additions: Vec<AdditionField>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct YeastField {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    inoculation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    restart_day: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExcursionField {
+    start_day: u32,
+    days: u32,
+    temp: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AdditionField {
+    day: u32,
+    kind: String,
+    /// In the kind's unit; the kind's usual amount if left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    amount: Option<f64>,
+}
+
+fn label<T>(
+    field: &'static str,
+    label: &str,
+    from_label: fn(&str) -> Option<T>,
+) -> Result<T, RecipeError> {
+    from_label(label).ok_or_else(|| RecipeError::UnknownLabel(field, label.to_owned()))
+}
+
+impl InputFields {
+    pub(crate) fn new(input: &SimulationInput) -> Self {
+        InputFields {
+            beverage: input.beverage.label().to_owned(),
+            fruit: Some(input.grape_type.clone()),
+            climate: Some(input.climate.clone()),
+            sugar_g_per_l: Some(input.sugar_content),
+            og: Some((specific_gravity(input.sugar_content, 0.0) * 1000.0).round() / 1000.0),
+            yeast: Some(YeastField {
+                name: input.yeast.clone(),
+                inoculation: Some(input.inoculation.label().to_owned()),
+                restart_day: input.restart_day,
+            }),
+            primary_age: Some(input.fermentation_days),
+            primary_temp: Some(input.temperature),
+            primary_vessel: Some(input.container_type.clone()),
+            age: Some(input.aging_months),
+            age_vessel: (!input.aging_container.is_empty()).then(|| input.aging_container.clone()),
+            whole_cluster_percent: Some(input.whole_cluster),
+            press_wine_percent: Some(input.press_wine),
+            lees_months: Some(input.lees_months),
+            stirs_per_month: Some(input.stirs_per_month),
+            closure: Some(input.closure.label().to_owned()),
+            excursions: input
+                .excursions
+                .iter()
+                .map(|excursion| ExcursionField {
+                    start_day: excursion.start_day,
+                    days: excursion.days,
+                    temp: excursion.temperature,
+                })
+                .collect(),
+            additions: input
+                .interventions
+                .iter()
+                .map(|intervention| AdditionField {
+                    day: intervention.day,
+                    kind: intervention.kind.label().to_owned(),
+                    amount: Some(intervention.amount),
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn into_input(self, seed: Option<u64>) -> Result<SimulationInput, RecipeError> {
+        let beverage = label("type", &self.beverage, Beverage::from_label)?;
+        let defaults = beverage.defaults();
+        let sugar_content = match (self.sugar_g_per_l, self.og) {
+            (Some(sugar), _) => sugar,
+            (None, Some(og)) => sugar_from_original_gravity(og),
+            (None, None) => return Err(RecipeError::MissingSugar),
+        };
+        let (yeast, inoculation, restart_day) = match self.yeast {
+            Some(yeast) => (
+                yeast.name,
+                match yeast.inoculation {
+                    Some(inoculation) => {
+                        label("inoculation", &inoculation, Inoculation::from_label)?
+                    }
+                    None => defaults.inoculation,
+                },
+                yeast.restart_day,
+            ),
+            None => (defaults.yeast.clone(), defaults.inoculation, None),
+        };
+        Ok(SimulationInput {
+            beverage,
+            grape_type: self.fruit.unwrap_or(defaults.grape_type),
+            fermentation_days: self.primary_age.unwrap_or(defaults.fermentation_days),
+            container_type: self.primary_vessel.unwrap_or(defaults.container_type),
+            aging_container: self.age_vessel.unwrap_or(defaults.aging_container),
+            aging_months: self.age.unwrap_or(defaults.aging_months),
+            whole_cluster: self.whole_cluster_percent.unwrap_or(defaults.whole_cluster),
+            press_wine: self.press_wine_percent.unwrap_or(defaults.press_wine),
+            sugar_content,
+            temperature: self.primary_temp.unwrap_or(defaults.temperature),
+            climate: self.climate.unwrap_or(defaults.climate),
+            seed,
+            restart_day,
+            excursions: self
+                .excursions
+                .into_iter()
+                .map(|excursion| Excursion {
+                    start_day: excursion.start_day,
+                    days: excursion.days,
+                    temperature: excursion.temp,
+                })
+                .collect(),
+            yeast,
+            inoculation,
+            lees_months: self.lees_months.unwrap_or(defaults.lees_months),
+            stirs_per_month: self.stirs_per_month.unwrap_or(defaults.stirs_per_month),
+            closure: match self.closure {
+                Some(closure) => label("closure", &closure, Closure::from_label)?,
+                None => defaults.closure,
+            },
+            interventions: self
+                .additions
+                .into_iter()
+                .map(|addition| {
+                    let kind = label(
+                        "addition kind",
+                        &addition.kind,
+                        InterventionKind::from_label,
+                    )?;
+                    Ok(Intervention {
+                        day: addition.day,
+                        kind,
+                        amount: addition.amount.unwrap_or(kind.default_amount()),
+                    })
+                })
+                .collect::<Result<_, RecipeError>>()?,
+        })
+    }
+}
+
+impl Recipe {
+    pub fn new(name: impl Into<String>, input: SimulationInput) -> Self {
+        Recipe {
+            name: name.into(),
+            notes: String::new(),
+            input,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let file = RecipeFile {
+            format: FORMAT.to_owned(),
+            version: VERSION,
+            name: self.name.clone(),
+            notes: self.notes.clone(),
+            seed: self.input.seed,
+            input: InputFields::new(&self.input),
+        };
+        serde_json::to_string_pretty(&file).expect("recipes always serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, RecipeError> {
+        let file: RecipeFile = serde_json::from_str(text)?;
+        if file.format != FORMAT {
+            return Err(RecipeError::UnknownFormat(file.format));
+        }
+        if file.version > VERSION {
+            return Err(RecipeError::UnsupportedVersion(file.version));
+        }
+        Ok(Recipe {
+            name: file.name,
+            notes: file.notes,
+            input: file.input.into_input(file.seed)?,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecipeError> {
+        fs::write(path, self.to_json())?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecipeError> {
+        Recipe::from_json(&fs::read_to_string(path)?)
+    }
+}
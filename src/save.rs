@@ -0,0 +1,143 @@
+//! Saved simulator sessions. Files carry a `version`; older files are upgraded
+//! one version at a time through `MIGRATIONS` before being deserialized, so
+//! add a migration whenever a saved field is renamed, removed, or changes
+//! meaning.
+
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::{debug, instrument};
+
+use crate::career::Career;
+use crate::dataset::Dataset;
+use crate::engine::{SimulationInput, SimulationResult, run_simulation};
+use crate::model_changelog::is_outdated;
+
+pub const CURRENT_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveFile {
+    pub version: u64,
+    pub input: SimulationInput,
+    #[serde(default)]
+    pub result: Option<SimulationResult>,
+    #[serde(default)]
+    pub career: Option<Career>,
+    /// The `MODEL_VERSION` that simulated `result`, or 0 if there is no
+    /// result or it predates versioning.
+    #[serde(default)]
+    pub model_version: u32,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    InvalidVersion,
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "{}", err),
+            SaveError::Json(err) => write!(f, "invalid save file: {}", err),
+            SaveError::InvalidVersion => write!(f, "invalid save file: bad version"),
+            SaveError::UnsupportedVersion(version) => write!(
+                f,
+                "save file version {} is newer than this release supports ({})",
+                version, CURRENT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> Self {
+        SaveError::Json(err)
+    }
+}
+
+type Migration = fn(Value) -> Value;
+
+// `MIGRATIONS[n]` upgrades a version `n` file to version `n + 1`.
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [v0_to_v1];
+
+// Version 0 files were a bare `SimulationInput` object.
+fn v0_to_v1(value: Value) -> Value {
+    json!({ "version": 1, "input": value })
+}
+
+impl SaveFile {
+    pub fn new(input: SimulationInput, result: Option<SimulationResult>) -> Self {
+        SaveFile {
+            version: CURRENT_VERSION,
+            model_version: result.as_ref().map_or(0, |result| result.model_version),
+            input,
+            result,
+            career: None,
+        }
+    }
+
+    /// Saves `career` along with the session.
+    pub fn with_career(mut self, career: Career) -> Self {
+        self.career = Some(career);
+        self
+    }
+
+    /// A fresh result for the session's inputs, if the stored one came from
+    /// an older model and simulating again under this one changes it.
+    pub fn resimulated(&self, dataset: &Dataset) -> Option<SimulationResult> {
+        let stored = self.result.as_ref()?;
+        if !is_outdated(stored.model_version) {
+            return None;
+        }
+        let fresh = run_simulation(&self.input, dataset).ok()?;
+        let unchanged = SimulationResult {
+            model_version: fresh.model_version,
+            ..stored.clone()
+        } == fresh;
+        (!unchanged).then_some(fresh)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        let mut value: Value = serde_json::from_str(json)?;
+        let version = match value.get("version") {
+            Some(version) => version.as_u64().ok_or(SaveError::InvalidVersion)?,
+            None => 0,
+        };
+        if version > CURRENT_VERSION {
+            return Err(SaveError::UnsupportedVersion(version));
+        }
+        for (from, migrate) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            debug!(from, "migrating save file");
+            value = migrate(value);
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("save files serialize")
+    }
+
+    #[instrument]
+    pub fn load(path: &Path) -> Result<Self, SaveError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        fs::write(path, self.to_json())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,324 @@
+//! Scenarios: a whole experiment in one TOML file, for keeping under version
+//! control and sharing. A scenario is a recipe's inputs and additions with
+//! the seed that makes its run repeatable and the model constants it runs
+//! on. A file looks like
+//!
+//! ```toml
+//! format = "wine-maker-scenario"
+//! version = 1
+//! name = "Cool Cabernet"
+//! seed = 42
+//! type = "Wine"
+//! fruit = "Cabernet Sauvignon"
+//! sugar_g_per_l = 240.0
+//! primary_age = 14
+//! primary_temp = 18.0
+//!
+//! [yeast]
+//! name = "EC-1118"
+//!
+//! [model]
+//! profile = "basement cellar"
+//! k_ref = 0.25
+//!
+//! [[additions]]
+//! day = 3
+//! kind = "Nutrient"
+//!
+//! [sweep]
+//! primary_temp = "14..28 step 2"
+//! ```
+//!
+//! Every field but `format`, `version`, and `[model]` is a recipe field
+//! (see [`crate::recipe`]) and means the same there. `[model]` names a
+//! saved model profile, gives constants of its own (`k_ref`, `q10`, and
+//! `sugar_per_abv`), or both, when the constants given replace the
+//! profile's. Leaving it out runs on the standard constants. `[sweep]`
+//! turns the scenario into a batch of runs (see [`crate::sweep`]).
+
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::calibration::{self, Calibration, ModelProfile};
+use crate::dataset::Dataset;
+use crate::engine::{SimulationError, SimulationInput, SimulationResult, run_simulation};
+use crate::recipe::{InputFields, RecipeError};
+use crate::sweep::{MAX_RUNS, Sweep, SweepError, SweepRun, run_count, run_sweeps};
+
+/// The `format` every scenario file names.
+pub const FORMAT: &str = "wine-maker-scenario";
+pub const VERSION: u32 = 1;
+
+/// An experiment: the inputs with their interventions and seed, and the
+/// model constants to run them on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub name: String,
+    pub notes: String,
+    pub input: SimulationInput,
+    pub model: ScenarioModel,
+    /// Inputs varied over a grid of runs, or none for a single run.
+    pub sweeps: Vec<Sweep>,
+}
+
+/// The model constants a scenario runs on: a saved profile's, or the
+/// standard ones, with any constant given here in place of its own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioModel {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k_ref: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q10: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sugar_per_abv: Option<f64>,
+}
+
+impl ScenarioModel {
+    /// The constants of `calibration`, all given here.
+    pub fn from_calibration(calibration: Calibration) -> Self {
+        ScenarioModel {
+            profile: None,
+            k_ref: Some(calibration.k_ref),
+            q10: Some(calibration.q10),
+            sugar_per_abv: Some(calibration.sugar_per_abv),
+        }
+    }
+
+    /// Whether it gives any constant of its own.
+    pub fn has_constants(&self) -> bool {
+        self.k_ref.is_some() || self.q10.is_some() || self.sugar_per_abv.is_some()
+    }
+
+    /// The constants to run on, looking the profile up in `profiles`.
+    pub fn calibration(&self, profiles: &[ModelProfile]) -> Result<Calibration, ScenarioError> {
+        let base = match &self.profile {
+            Some(name) => {
+                profiles
+                    .iter()
+                    .find(|profile| &profile.name == name)
+                    .ok_or_else(|| ScenarioError::UnknownProfile(name.clone()))?
+                    .calibration
+            }
+            None => Calibration::default(),
+        };
+        Ok(Calibration {
+            k_ref: self.k_ref.unwrap_or(base.k_ref),
+            q10: self.q10.unwrap_or(base.q10),
+            sugar_per_abv: self.sugar_per_abv.unwrap_or(base.sugar_per_abv),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    /// Not a scenario in this format, going by its `format` field.
+    UnknownFormat(String),
+    UnsupportedVersion(u32),
+    /// The inputs are not a valid recipe.
+    Input(RecipeError),
+    /// `[model]` names a profile that isn't saved.
+    UnknownProfile(String),
+    Sweep(SweepError),
+    /// The sweeps expand into this many runs, more than `MAX_RUNS`.
+    TooManyRuns(usize),
+    Simulation(SimulationError),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "I/O error: {}", err),
+            ScenarioError::Toml(err) => write!(f, "invalid scenario: {}", err),
+            ScenarioError::UnknownFormat(format) => {
+                write!(f, "not a {} file (format \"{}\")", FORMAT, format)
+            }
+            ScenarioError::UnsupportedVersion(version) => write!(
+                f,
+                "scenario version {} is newer than this release supports ({})",
+                version, VERSION
+            ),
+            ScenarioError::Input(err) => write!(f, "{}", err),
+            ScenarioError::UnknownProfile(name) => {
+                write!(
+                    f,
+                    "scenario runs on model profile \"{}\", which isn't saved",
+                    name
+                )
+            }
+            ScenarioError::Sweep(err) => write!(f, "{}", err),
+            ScenarioError::TooManyRuns(runs) => write!(
+                f,
+                "scenario sweeps over {} runs, more than the {} allowed",
+                runs, MAX_RUNS
+            ),
+            ScenarioError::Simulation(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl From<io::Error> for ScenarioError {
+    fn from(err: io::Error) -> Self {
+        ScenarioError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ScenarioError {
+    fn from(err: toml::de::Error) -> Self {
+        ScenarioError::Toml(err)
+    }
+}
+
+impl From<RecipeError> for ScenarioError {
+    fn from(err: RecipeError) -> Self {
+        ScenarioError::Input(err)
+    }
+}
+
+impl From<SweepError> for ScenarioError {
+    fn from(err: SweepError) -> Self {
+        ScenarioError::Sweep(err)
+    }
+}
+
+impl From<SimulationError> for ScenarioError {
+    fn from(err: SimulationError) -> Self {
+        ScenarioError::Simulation(err)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScenarioFile {
+    format: String,
+    version: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    notes: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(flatten)]
+    input: InputFields,
+    #[serde(default, skip_serializing_if = "is_standard")]
+    model: ScenarioModel,
+    /// Each swept field and its range, in the order written.
+    #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+    sweep: toml::Table,
+}
+
+fn is_standard(model: &ScenarioModel) -> bool {
+    *model == ScenarioModel::default()
+}
+
+impl Scenario {
+    /// A scenario running `input` on the standard constants.
+    pub fn new(name: impl Into<String>, input: SimulationInput) -> Self {
+        Scenario {
+            name: name.into(),
+            notes: String::new(),
+            input,
+            model: ScenarioModel::default(),
+            sweeps: Vec::new(),
+        }
+    }
+
+    pub fn with_model(mut self, model: ScenarioModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn to_toml(&self) -> String {
+        let file = ScenarioFile {
+            format: FORMAT.to_owned(),
+            version: VERSION,
+            name: self.name.clone(),
+            notes: self.notes.clone(),
+            seed: self.input.seed,
+            input: InputFields::new(&self.input),
+            model: self.model.clone(),
+            sweep: self
+                .sweeps
+                .iter()
+                .map(|sweep| (sweep.parameter.label().to_owned(), sweep.to_string().into()))
+                .collect(),
+        };
+        toml::to_string_pretty(&file).expect("scenarios always serialize")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ScenarioError> {
+        let file: ScenarioFile = toml::from_str(text)?;
+        if file.format != FORMAT {
+            return Err(ScenarioError::UnknownFormat(file.format));
+        }
+        if file.version > VERSION {
+            return Err(ScenarioError::UnsupportedVersion(file.version));
+        }
+        let sweeps = file
+            .sweep
+            .iter()
+            .map(|(field, range)| match range.as_str() {
+                Some(range) => Sweep::parse(field, range),
+                None => Err(SweepError(field.clone(), range.to_string())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let runs = run_count(&sweeps);
+        if runs > MAX_RUNS {
+            return Err(ScenarioError::TooManyRuns(runs));
+        }
+        Ok(Scenario {
+            name: file.name,
+            notes: file.notes,
+            input: file.input.into_input(file.seed)?,
+            model: file.model,
+            sweeps,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ScenarioError> {
+        fs::write(path, self.to_toml())?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        Scenario::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Simulates the scenario on its model constants, looking its profile
+    /// up in `profiles`. Other threads' simulations are left on theirs.
+    pub fn run(
+        &self,
+        wine_data: &Dataset,
+        profiles: &[ModelProfile],
+    ) -> Result<SimulationResult, ScenarioError> {
+        Ok(self.on_model(profiles, || run_simulation(&self.input, wine_data))??)
+    }
+
+    /// Simulates every run of the scenario's sweeps, in parallel, on its
+    /// model constants as `run` does.
+    pub fn run_sweeps(
+        &self,
+        wine_data: &Dataset,
+        profiles: &[ModelProfile],
+    ) -> Result<Vec<SweepRun>, ScenarioError> {
+        self.on_model(profiles, || {
+            run_sweeps(&self.input, &self.sweeps, wine_data)
+        })
+    }
+
+    fn on_model<T>(
+        &self,
+        profiles: &[ModelProfile],
+        run: impl FnOnce() -> T,
+    ) -> Result<T, ScenarioError> {
+        let calibration = self.model.calibration(profiles)?;
+        Ok(calibration::with_calibration(calibration, run))
+    }
+}
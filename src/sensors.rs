@@ -0,0 +1,468 @@
+//! Devices that stream gravity and temperature, read in the background into
+//! one stream of readings whatever they are connected by: an MQTT broker,
+//! a serial port, or Bluetooth LE (a Tilt hydrometer).
+//!
+//! A serial device sends one reading per line, as a bare gravity, a gravity
+//! and temperature separated by a comma, semicolon, or spaces, or a JSON
+//! object with the field names `devices::import_readings` accepts.
+
+use std::fmt;
+#[cfg(any(feature = "serial", feature = "ble"))]
+use std::sync::Arc;
+#[cfg(any(feature = "serial", feature = "ble"))]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+#[cfg(any(feature = "serial", feature = "ble"))]
+use std::sync::mpsc::{self, Sender};
+#[cfg(any(feature = "serial", feature = "ble"))]
+use std::thread;
+use std::time::Duration;
+
+#[cfg(any(feature = "serial", feature = "ble"))]
+use chrono::Local;
+
+use crate::devices::{DeviceReading, JsonFields, to_celsius, to_specific_gravity};
+#[cfg(feature = "mqtt")]
+use crate::mqtt::{MqttConfig, Subscription};
+
+/// How long a lost device is left before it is tried again.
+pub(crate) const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SensorEvent {
+    Reading(DeviceReading),
+    /// The connection dropped; it keeps being retried.
+    Disconnected(String),
+}
+
+/// Gravity as SG and temperature in °C from one line a device sent, or
+/// `None` if the line has no gravity.
+pub fn parse_line(line: &str) -> Option<(f64, Option<f64>)> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        let (gravity, temperature) = JsonFields::from_value(&value).normalized();
+        return Some((gravity?, temperature));
+    }
+    let mut numbers = line
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(str::parse::<f64>);
+    let gravity = numbers.next()?.ok()?;
+    let temperature = match numbers.next() {
+        Some(temperature) => Some(to_celsius(temperature.ok()?, None)),
+        None => None,
+    };
+    Some((to_specific_gravity(gravity), temperature))
+}
+
+/// The colors Tilt hydrometers come in, each advertising its own UUID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TiltColor {
+    Red,
+    Green,
+    Black,
+    Purple,
+    Orange,
+    Blue,
+    Yellow,
+    Pink,
+}
+
+impl TiltColor {
+    /// In the order of the digit in their UUIDs, 1 to 8.
+    pub const ALL: [TiltColor; 8] = [
+        TiltColor::Red,
+        TiltColor::Green,
+        TiltColor::Black,
+        TiltColor::Purple,
+        TiltColor::Orange,
+        TiltColor::Blue,
+        TiltColor::Yellow,
+        TiltColor::Pink,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TiltColor::Red => "Red",
+            TiltColor::Green => "Green",
+            TiltColor::Black => "Black",
+            TiltColor::Purple => "Purple",
+            TiltColor::Orange => "Orange",
+            TiltColor::Blue => "Blue",
+            TiltColor::Yellow => "Yellow",
+            TiltColor::Pink => "Pink",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|color| color.label().eq_ignore_ascii_case(label.trim()))
+    }
+}
+
+/// The manufacturer ID Tilts advertise under, as iBeacons.
+pub const APPLE_COMPANY_ID: u16 = 0x004c;
+
+/// A Tilt's UUID, with its color digit in the 4th byte's high nibble.
+const TILT_UUID: [u8; 16] = [
+    0xa4, 0x95, 0xbb, 0x00, 0xc5, 0xb1, 0x4b, 0x44, 0xb5, 0x12, 0x13, 0x70, 0xf0, 0x2d, 0x74, 0xde,
+];
+
+/// A Tilt's color, gravity as SG, and temperature in °C from the data of
+/// its iBeacon advertisement, or `None` if the data is from something else.
+/// Tilt Pros send ten times the resolution.
+pub fn parse_tilt(manufacturer_data: &[u8]) -> Option<(TiltColor, f64, f64)> {
+    let [0x02, 0x15, rest @ ..] = manufacturer_data else {
+        return None;
+    };
+    let (uuid, rest) = rest.split_first_chunk::<16>()?;
+    let &[major_high, major_low, minor_high, minor_low, _tx_power] = rest else {
+        return None;
+    };
+    let digit = uuid[3] >> 4;
+    if uuid[3] & 0x0f != 0
+        || uuid[..3] != TILT_UUID[..3]
+        || uuid[4..] != TILT_UUID[4..]
+        || !(1..=8).contains(&digit)
+    {
+        return None;
+    }
+    let color = TiltColor::ALL[digit as usize - 1];
+    let fahrenheit = u16::from_be_bytes([major_high, major_low]) as f64;
+    let gravity = u16::from_be_bytes([minor_high, minor_low]) as f64;
+    let (gravity, fahrenheit) = if gravity > 5000.0 {
+        (gravity / 10000.0, fahrenheit / 10.0)
+    } else {
+        (gravity / 1000.0, fahrenheit)
+    };
+    Some((color, gravity, to_celsius(fahrenheit, Some(true))))
+}
+
+/// A serial port a device prints its readings to, one per line.
+#[cfg(feature = "serial")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerialConfig {
+    /// Such as `/dev/ttyUSB0` or `COM3`.
+    pub path: String,
+    pub baud_rate: u32,
+}
+
+#[cfg(feature = "serial")]
+impl Default for SerialConfig {
+    fn default() -> Self {
+        SerialConfig {
+            path: String::new(),
+            baud_rate: 115_200,
+        }
+    }
+}
+
+/// The serial ports on this machine.
+#[cfg(feature = "serial")]
+pub fn serial_ports() -> Vec<String> {
+    serialport::available_ports()
+        .map(|ports| ports.into_iter().map(|port| port.port_name).collect())
+        .unwrap_or_default()
+}
+
+/// Which Tilt to listen for, or `None` for whichever is heard.
+#[cfg(feature = "ble")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BleConfig {
+    pub color: Option<TiltColor>,
+}
+
+/// Where readings come from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SensorSource {
+    #[cfg(feature = "mqtt")]
+    Mqtt(MqttConfig),
+    #[cfg(feature = "serial")]
+    Serial(SerialConfig),
+    #[cfg(feature = "ble")]
+    Ble(BleConfig),
+}
+
+#[derive(Debug)]
+pub enum SensorError {
+    #[cfg(feature = "mqtt")]
+    Mqtt(rumqttc::ClientError),
+    #[cfg(feature = "serial")]
+    Serial(serialport::Error),
+    #[cfg(feature = "ble")]
+    Ble(btleplug::Error),
+    /// No Bluetooth adapter was found.
+    #[cfg(feature = "ble")]
+    NoAdapter,
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SensorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "mqtt")]
+            SensorError::Mqtt(err) => write!(f, "{}", err),
+            #[cfg(feature = "serial")]
+            SensorError::Serial(err) => write!(f, "{}", err),
+            #[cfg(feature = "ble")]
+            SensorError::Ble(err) => write!(f, "Bluetooth error: {}", err),
+            #[cfg(feature = "ble")]
+            SensorError::NoAdapter => write!(f, "no Bluetooth adapter was found"),
+            SensorError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SensorError {}
+
+#[cfg(feature = "mqtt")]
+impl From<rumqttc::ClientError> for SensorError {
+    fn from(err: rumqttc::ClientError) -> Self {
+        SensorError::Mqtt(err)
+    }
+}
+
+#[cfg(feature = "serial")]
+impl From<serialport::Error> for SensorError {
+    fn from(err: serialport::Error) -> Self {
+        SensorError::Serial(err)
+    }
+}
+
+#[cfg(feature = "ble")]
+impl From<btleplug::Error> for SensorError {
+    fn from(err: btleplug::Error) -> Self {
+        SensorError::Ble(err)
+    }
+}
+
+impl From<std::io::Error> for SensorError {
+    fn from(err: std::io::Error) -> Self {
+        SensorError::Io(err)
+    }
+}
+
+/// A connected source. Readings arrive on `events()` until it is dropped.
+pub struct SensorStream {
+    link: Link,
+}
+
+enum Link {
+    #[cfg(feature = "mqtt")]
+    Mqtt(Subscription),
+    #[cfg(any(feature = "serial", feature = "ble"))]
+    Thread {
+        events: Receiver<SensorEvent>,
+        _stop: StopOnDrop,
+    },
+}
+
+/// Tells a reading thread to finish when dropped.
+#[cfg(any(feature = "serial", feature = "ble"))]
+struct StopOnDrop(Arc<AtomicBool>);
+
+#[cfg(any(feature = "serial", feature = "ble"))]
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl SensorStream {
+    pub fn events(&self) -> &Receiver<SensorEvent> {
+        match &self.link {
+            #[cfg(feature = "mqtt")]
+            Link::Mqtt(subscription) => &subscription.events,
+            #[cfg(any(feature = "serial", feature = "ble"))]
+            Link::Thread { events, .. } => events,
+        }
+    }
+}
+
+/// Connects to `source` and streams its readings. `notify` is called after
+/// each event, e.g. to wake a UI.
+pub fn connect(
+    source: &SensorSource,
+    notify: impl Fn() + Send + 'static,
+) -> Result<SensorStream, SensorError> {
+    let link = match source {
+        #[cfg(feature = "mqtt")]
+        SensorSource::Mqtt(config) => Link::Mqtt(crate::mqtt::subscribe(config, notify)?),
+        #[cfg(feature = "serial")]
+        SensorSource::Serial(config) => read_serial(config, notify)?,
+        #[cfg(feature = "ble")]
+        SensorSource::Ble(config) => read_ble(config, notify)?,
+    };
+    Ok(SensorStream { link })
+}
+
+#[cfg(any(feature = "serial", feature = "ble"))]
+fn reading(specific_gravity: f64, temperature: Option<f64>) -> SensorEvent {
+    SensorEvent::Reading(DeviceReading {
+        taken_at: Local::now().naive_local(),
+        specific_gravity,
+        temperature,
+    })
+}
+
+#[cfg(any(feature = "serial", feature = "ble"))]
+fn thread_link() -> (Link, Sender<SensorEvent>, Arc<AtomicBool>) {
+    let (sender, events) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let link = Link::Thread {
+        events,
+        _stop: StopOnDrop(stop.clone()),
+    };
+    (link, sender, stop)
+}
+
+#[cfg(feature = "serial")]
+fn read_serial(
+    config: &SerialConfig,
+    notify: impl Fn() + Send + 'static,
+) -> Result<Link, SensorError> {
+    use std::io::{BufRead, BufReader, ErrorKind};
+
+    let open = |config: &SerialConfig| {
+        serialport::new(config.path.trim(), config.baud_rate)
+            .timeout(Duration::from_secs(1))
+            .open()
+    };
+    let mut port = Some(BufReader::new(open(config)?));
+    tracing::info!(port = %config.path, baud_rate = config.baud_rate, "reading a serial sensor");
+
+    let (link, sender, stop) = thread_link();
+    let config = config.clone();
+    thread::spawn(move || {
+        // Kept across timeouts, which can land partway through a line.
+        let mut line = String::new();
+        while !stop.load(Ordering::Relaxed) {
+            let Some(reader) = &mut port else {
+                thread::sleep(RECONNECT_DELAY);
+                match open(&config) {
+                    Ok(reopened) => port = Some(BufReader::new(reopened)),
+                    Err(err) => tracing::debug!("Could not reopen {}: {}", config.path, err),
+                }
+                continue;
+            };
+            let event = match reader.read_line(&mut line) {
+                Ok(0) => {
+                    port = None;
+                    SensorEvent::Disconnected("the port closed".to_owned())
+                }
+                Ok(_) => {
+                    let parsed = parse_line(&line);
+                    if parsed.is_none() {
+                        tracing::debug!(line = line.trim(), "not a reading");
+                    }
+                    line.clear();
+                    match parsed {
+                        Some((gravity, temperature)) => reading(gravity, temperature),
+                        None => continue,
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::TimedOut => continue,
+                Err(err) => {
+                    tracing::warn!("Serial sensor error: {}", err);
+                    port = None;
+                    line.clear();
+                    SensorEvent::Disconnected(err.to_string())
+                }
+            };
+            if sender.send(event).is_err() {
+                break;
+            }
+            notify();
+        }
+    });
+    Ok(link)
+}
+
+/// Advertisements from `central`, scanning for them from now on.
+#[cfg(feature = "ble")]
+async fn scan(
+    central: &btleplug::platform::Adapter,
+) -> Result<
+    std::pin::Pin<Box<dyn tokio_stream::Stream<Item = btleplug::api::CentralEvent> + Send>>,
+    btleplug::Error,
+> {
+    use btleplug::api::{Central, ScanFilter};
+
+    let advertisements = central.events().await?;
+    central.start_scan(ScanFilter::default()).await?;
+    Ok(advertisements)
+}
+
+#[cfg(feature = "ble")]
+fn read_ble(config: &BleConfig, notify: impl Fn() + Send + 'static) -> Result<Link, SensorError> {
+    use btleplug::api::{Central, CentralEvent, Manager as _};
+    use btleplug::platform::Manager;
+    use tokio_stream::StreamExt;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let (central, advertisements) = runtime.block_on(async {
+        let manager = Manager::new().await?;
+        let central = manager
+            .adapters()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(SensorError::NoAdapter)?;
+        let advertisements = scan(&central).await?;
+        Ok::<_, SensorError>((central, advertisements))
+    })?;
+    tracing::info!(color = ?config.color, "listening for Tilt hydrometers");
+
+    let (link, sender, stop) = thread_link();
+    let wanted = config.color;
+    thread::spawn(move || {
+        runtime.block_on(async move {
+            let mut advertisements = Some(advertisements);
+            while !stop.load(Ordering::Relaxed) {
+                let Some(events) = &mut advertisements else {
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    match scan(&central).await {
+                        Ok(events) => advertisements = Some(events),
+                        Err(err) => {
+                            tracing::debug!("Could not restart the Bluetooth scan: {}", err)
+                        }
+                    }
+                    continue;
+                };
+                let event = match tokio::time::timeout(Duration::from_secs(1), events.next()).await
+                {
+                    Ok(Some(CentralEvent::ManufacturerDataAdvertisement {
+                        manufacturer_data,
+                        ..
+                    })) => {
+                        let Some((color, gravity, temperature)) = manufacturer_data
+                            .get(&APPLE_COMPANY_ID)
+                            .and_then(|data| parse_tilt(data))
+                        else {
+                            continue;
+                        };
+                        if wanted.is_some_and(|wanted| wanted != color) {
+                            continue;
+                        }
+                        reading(gravity, Some(temperature))
+                    }
+                    Ok(Some(_)) | Err(_) => continue,
+                    Ok(None) => {
+                        advertisements = None;
+                        SensorEvent::Disconnected("the adapter stopped scanning".to_owned())
+                    }
+                };
+                if sender.send(event).is_err() {
+                    break;
+                }
+                notify();
+            }
+            let _ = central.stop_scan().await;
+        });
+    });
+    Ok(link)
+}
@@ -0,0 +1,260 @@
+//! App preferences, kept together in one `settings.json` in the config
+//! directory. Fields missing from an older file take their defaults.
+
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::calibration::{Calibration, ModelProfile};
+use crate::conventions::{StrengthConvention, SugarConvention};
+use crate::engine::SimulationInput;
+#[cfg(feature = "webhook")]
+use crate::webhook::WebhookSettings;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    /// Follow the operating system.
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::System => "System",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    pub const ALL: [TemperatureUnit; 2] = [TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "Celsius",
+            TemperatureUnit::Fahrenheit => "Fahrenheit",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
+    /// `celsius` in this unit.
+    pub fn from_celsius(self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// `temperature`, in this unit, in °C.
+    pub fn to_celsius(self, temperature: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => temperature,
+            TemperatureUnit::Fahrenheit => (temperature - 32.0) * 5.0 / 9.0,
+        }
+    }
+}
+
+/// How many recently opened files are remembered.
+pub const RECENT_LIMIT: usize = 8;
+/// The profile a calibration saved before there were profiles is kept as.
+pub const LEGACY_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecentKind {
+    Dataset,
+    Session,
+}
+
+impl RecentKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            RecentKind::Dataset => "Dataset",
+            RecentKind::Session => "Session",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub kind: RecentKind,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: Theme,
+    pub temperature_unit: TemperatureUnit,
+    /// How residual sugar and strength are shown alongside g/L and % ABV.
+    pub sugar_convention: SugarConvention,
+    pub strength_convention: StrengthConvention,
+    /// A language code such as `fr`, picking `glossary.fr.csv` from the data
+    /// directory, or empty for `glossary.csv`.
+    pub locale: String,
+    /// The inputs the simulator starts with, or `None` for the beverage's
+    /// defaults.
+    pub defaults: Option<SimulationInput>,
+    /// Named sets of model constants, such as one fitted to a basement
+    /// cellar or a quick one for a classroom demo.
+    pub profiles: Vec<ModelProfile>,
+    /// The name of the profile simulations run on, or empty for the
+    /// standard constants.
+    pub profile: String,
+    /// Where the history database and glossary live, or `None` for the
+    /// platform's data directory.
+    pub data_dir: Option<PathBuf>,
+    /// Datasets and sessions opened lately, the latest first.
+    pub recent: Vec<RecentFile>,
+    /// Where simulations and batch logs are sent.
+    #[cfg(feature = "webhook")]
+    pub webhook: WebhookSettings,
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(err) => write!(f, "I/O error: {}", err),
+            SettingsError::Json(err) => write!(f, "invalid settings: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<io::Error> for SettingsError {
+    fn from(err: io::Error) -> Self {
+        SettingsError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SettingsError {
+    fn from(err: serde_json::Error) -> Self {
+        SettingsError::Json(err)
+    }
+}
+
+impl Settings {
+    /// Reads the settings at `path`, or the defaults if there is no file yet.
+    /// A calibration saved before there were profiles becomes the
+    /// `LEGACY_PROFILE` profile, still picked.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SettingsError> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                let mut value: serde_json::Value = serde_json::from_str(&text)?;
+                let legacy = value
+                    .as_object_mut()
+                    .and_then(|object| object.remove("calibration"))
+                    .filter(|calibration| !calibration.is_null());
+                let mut settings: Settings = serde_json::from_value(value)?;
+                if let Some(calibration) = legacy {
+                    settings.import_calibration(serde_json::from_value(calibration)?);
+                }
+                Ok(settings)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Writes the settings to `path`, creating its directory if need be.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Puts `path` at the top of the recent files, dropping the oldest past
+    /// `RECENT_LIMIT`.
+    pub fn add_recent(&mut self, kind: RecentKind, path: PathBuf) {
+        self.recent.retain(|recent| recent.path != path);
+        self.recent.insert(0, RecentFile { kind, path });
+        self.recent.truncate(RECENT_LIMIT);
+    }
+
+    /// Forgets `path`, as when it can no longer be opened.
+    pub fn remove_recent(&mut self, path: &Path) {
+        self.recent.retain(|recent| recent.path != path);
+    }
+
+    /// The profile called `name`.
+    pub fn profile_named(&self, name: &str) -> Option<&ModelProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// The profile simulations run on, or `None` for the standard constants.
+    pub fn selected_profile(&self) -> Option<&ModelProfile> {
+        self.profile_named(&self.profile)
+    }
+
+    /// Keeps `profile`, in place of any with the same name, and picks it.
+    pub fn save_profile(&mut self, profile: ModelProfile) {
+        self.profile = profile.name.clone();
+        match self
+            .profiles
+            .iter_mut()
+            .find(|saved| saved.name == profile.name)
+        {
+            Some(saved) => *saved = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    fn import_calibration(&mut self, calibration: Calibration) {
+        if self.profile_named(LEGACY_PROFILE).is_some() {
+            return;
+        }
+        self.profiles.push(ModelProfile {
+            name: LEGACY_PROFILE.to_owned(),
+            calibration,
+        });
+        if self.profile.is_empty() {
+            self.profile = LEGACY_PROFILE.to_owned();
+        }
+    }
+
+    /// Forgets the profile called `name`, going back to the standard
+    /// constants if it was picked.
+    pub fn remove_profile(&mut self, name: &str) {
+        self.profiles.retain(|profile| profile.name != name);
+        if self.profile == name {
+            self.profile.clear();
+        }
+    }
+
+    /// The glossary file name for the chosen locale.
+    pub fn glossary_file(&self) -> String {
+        match self.locale.trim() {
+            "" => "glossary.csv".to_owned(),
+            locale => format!("glossary.{}.csv", locale),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! A final check on the bottled wine: will it referment, and will it haze?
+
+use crate::calculators::{
+    BentoniteFining, STILL_BOTTLE_PRESSURE, SUGAR_PER_CO2_VOLUME, bentonite_fining, co2_pressure,
+    co2_volumes, grape_protein, molecular_so2,
+};
+use crate::engine::{Beverage, SimulationInput, SimulationResult, yeast_tolerance};
+
+/// Sugar in g/L yeast can't ferment, like the pentoses left in dry wine.
+const UNFERMENTABLE_SUGAR: f64 = 1.0;
+/// Fermentable sugar in g/L that gives surviving yeast all they need to
+/// restart.
+const FULL_REFERMENTATION_SUGAR: f64 = 4.0;
+/// Molecular SO2 in mg/L that stops nearly all yeast.
+const YEAST_STOPPING_MOLECULAR_SO2: f64 = 0.8;
+/// Share of the yeast that gets through a sterile filter.
+const FILTERED_YEAST: f64 = 0.01;
+/// Share of the yeast still able to bud with potassium sorbate in the wine.
+const SORBATE_YEAST: f64 = 0.1;
+/// Share of the yeast that is still active once the wine reached their
+/// alcohol tolerance.
+const EXHAUSTED_YEAST: f64 = 0.3;
+// Storage temperatures in °C at which yeast are nearly dormant and fully
+// active.
+const DORMANT_TEMPERATURE: f64 = 5.0;
+const ACTIVE_TEMPERATURE: f64 = 20.0;
+
+/// What was done to the wine at bottling and where it will be kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BottlingConditions {
+    /// Liters of wine being bottled.
+    pub volume: f64,
+    pub sterile_filtered: bool,
+    pub sorbate: bool,
+    /// Free SO2 in mg/L.
+    pub free_so2: f64,
+    pub ph: f64,
+    /// Bentonite in g/hL.
+    pub bentonite: f64,
+    /// Storage temperature in °C.
+    pub storage_temperature: f64,
+}
+
+impl Default for BottlingConditions {
+    fn default() -> Self {
+        Self {
+            volume: 23.0,
+            sterile_filtered: false,
+            sorbate: false,
+            free_so2: 30.0,
+            ph: 3.5,
+            bentonite: 0.0,
+            storage_temperature: 15.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Risk {
+    Negligible,
+    Low,
+    Moderate,
+    High,
+}
+
+impl Risk {
+    fn from_probability(probability: f64) -> Self {
+        match probability {
+            p if p < 0.05 => Risk::Negligible,
+            p if p < 0.2 => Risk::Low,
+            p if p < 0.5 => Risk::Moderate,
+            _ => Risk::High,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Risk::Negligible => "negligible",
+            Risk::Low => "low",
+            Risk::Moderate => "moderate",
+            Risk::High => "high",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Refermentation {
+    /// Sugar in g/L left for yeast to ferment in bottle.
+    pub fermentable_sugar: f64,
+    /// Share of the yeast still able to grow, from 0 to 1.
+    pub viable_yeast: f64,
+    /// Gauge pressure in bar if all the sugar ferments in bottle.
+    pub pressure: f64,
+    /// Chance the wine referments in bottle, from 0 to 1.
+    pub probability: f64,
+    pub risk: Risk,
+}
+
+impl Refermentation {
+    /// Whether a refermentation would build more pressure than a still wine
+    /// bottle holds.
+    pub fn could_burst(&self) -> bool {
+        self.risk >= Risk::Low && self.pressure > STILL_BOTTLE_PRESSURE
+    }
+}
+
+/// How likely the wine from `input` and `result` is to start fermenting
+/// again once bottled under `conditions`.
+pub fn refermentation(
+    input: &SimulationInput,
+    result: &SimulationResult,
+    conditions: &BottlingConditions,
+) -> Refermentation {
+    let fermentable_sugar = (result.residual_sugar - UNFERMENTABLE_SUGAR).max(0.0);
+    let pressure = co2_pressure(co2_volumes(0.0) + fermentable_sugar / SUGAR_PER_CO2_VOLUME);
+
+    let molecular = molecular_so2(conditions.free_so2, conditions.ph);
+    let mut viable_yeast = 1.0 - 0.9 * (molecular / YEAST_STOPPING_MOLECULAR_SO2).clamp(0.0, 1.0);
+    if conditions.sterile_filtered {
+        viable_yeast *= FILTERED_YEAST;
+    }
+    if conditions.sorbate {
+        viable_yeast *= SORBATE_YEAST;
+    }
+    if result.actual_abv >= yeast_tolerance(input) - 0.5 {
+        viable_yeast *= EXHAUSTED_YEAST;
+    }
+
+    let warmth = ((conditions.storage_temperature - DORMANT_TEMPERATURE)
+        / (ACTIVE_TEMPERATURE - DORMANT_TEMPERATURE))
+        .clamp(0.05, 1.0);
+    let fuel = (fermentable_sugar / FULL_REFERMENTATION_SUGAR).min(1.0);
+    let probability = viable_yeast * warmth * fuel;
+    Refermentation {
+        fermentable_sugar,
+        viable_yeast,
+        pressure,
+        probability,
+        risk: Risk::from_probability(probability),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabilityReport {
+    pub refermentation: Refermentation,
+    /// Heat stability, for white grape wines.
+    pub protein: Option<BentoniteFining>,
+}
+
+impl StabilityReport {
+    pub fn is_stable(&self) -> bool {
+        self.refermentation.risk <= Risk::Low
+            && self
+                .protein
+                .as_ref()
+                .is_none_or(BentoniteFining::is_heat_stable)
+    }
+
+    /// The problems to fix before bottling, in plain words.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let refermentation = &self.refermentation;
+        if refermentation.could_burst() {
+            warnings.push(format!(
+                "{:.1} g/L of sugar could ferment in bottle, building {:.1} bar; still wine bottles may burst. Sterile filter, add sorbate, or keep it cold.",
+                refermentation.fermentable_sugar, refermentation.pressure
+            ));
+        } else if refermentation.risk >= Risk::Moderate {
+            warnings.push(format!(
+                "The wine may referment in bottle and turn fizzy ({:.0}% chance).",
+                refermentation.probability * 100.0
+            ));
+        }
+        if let Some(protein) = &self.protein {
+            warnings.extend(protein.warnings.iter().map(ToString::to_string));
+        }
+        warnings
+    }
+}
+
+/// The stability of the wine from `input` and `result` once bottled under
+/// `conditions`.
+pub fn stability_report(
+    input: &SimulationInput,
+    result: &SimulationResult,
+    conditions: &BottlingConditions,
+) -> StabilityReport {
+    let white = input.beverage == Beverage::Wine && grape_protein(&input.grape_type) > 0.0;
+    let protein = white.then(|| {
+        bentonite_fining(
+            conditions.volume,
+            &input.grape_type,
+            conditions.bentonite,
+            conditions.storage_temperature,
+        )
+    });
+    StabilityReport {
+        refermentation: refermentation(input, result, conditions),
+        protein,
+    }
+}
@@ -0,0 +1,174 @@
+//! The stages a wine goes through from pitching to bottle, and the day each
+//! one starts, so schedules and warnings can go by where the wine is.
+
+use std::fmt;
+
+use crate::engine::{SimulationError, SimulationInput, fermentation_state};
+
+/// Share of the sugar fermented once the yeast are visibly working.
+const PRIMARY_START_FRACTION: f64 = 0.02;
+/// Days before bottling that the wine is stabilized and fined.
+pub(crate) const STABILIZATION_DAYS: u32 = 30;
+/// Days in a month of aging.
+const DAYS_PER_MONTH: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// Pitched, but the yeast haven't got going yet.
+    Lag,
+    Primary,
+    /// A sequential malolactic fermentation after pressing.
+    Malolactic,
+    Aging,
+    /// Stabilized and fined, waiting to be bottled.
+    Stabilized,
+    Bottled,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Lag,
+        Stage::Primary,
+        Stage::Malolactic,
+        Stage::Aging,
+        Stage::Stabilized,
+        Stage::Bottled,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Lag => "Lag phase",
+            Stage::Primary => "Primary fermentation",
+            Stage::Malolactic => "Malolactic fermentation",
+            Stage::Aging => "Aging",
+            Stage::Stabilized => "Stabilized",
+            Stage::Bottled => "Bottled",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|stage| stage.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// Whether the yeast or bacteria are still fermenting.
+    pub fn is_fermenting(self) -> bool {
+        matches!(self, Stage::Primary | Stage::Malolactic)
+    }
+}
+
+/// The day the wine is pressed off and primary fermentation ends.
+pub fn pressing_day(input: &SimulationInput) -> u32 {
+    input.fermentation_days
+}
+
+/// The day bulk aging starts, after any sequential malolactic fermentation.
+pub fn aging_day(input: &SimulationInput) -> u32 {
+    pressing_day(input) + input.inoculation.malolactic_days_after()
+}
+
+/// The day the wine is stabilized ahead of bottling.
+pub fn stabilization_day(input: &SimulationInput) -> u32 {
+    bottling_day(input)
+        .saturating_sub(STABILIZATION_DAYS)
+        .max(aging_day(input))
+}
+
+/// The day the wine is bottled, counting from the start of fermentation.
+pub fn bottling_day(input: &SimulationInput) -> u32 {
+    aging_day(input) + input.aging_months * DAYS_PER_MONTH
+}
+
+/// The stage the wine is in on `day`, counting from the start of
+/// fermentation.
+pub fn stage_on(input: &SimulationInput, day: u32) -> Result<Stage, SimulationError> {
+    Ok(if day >= bottling_day(input) {
+        Stage::Bottled
+    } else if day >= stabilization_day(input) {
+        Stage::Stabilized
+    } else if day >= aging_day(input) {
+        Stage::Aging
+    } else if day >= pressing_day(input) {
+        Stage::Malolactic
+    } else if fermentation_state(input, day)?.fraction_fermented < PRIMARY_START_FRACTION {
+        Stage::Lag
+    } else {
+        Stage::Primary
+    })
+}
+
+/// A stage and the days it covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageSpan {
+    pub stage: Stage,
+    pub start_day: u32,
+    /// The day the next stage starts, or `None` once bottled.
+    pub end_day: Option<u32>,
+}
+
+/// The stages the wine goes through in order, leaving out any it skips.
+pub fn stages(input: &SimulationInput) -> Result<Vec<StageSpan>, SimulationError> {
+    let mut spans: Vec<StageSpan> = Vec::new();
+    for day in 0..=bottling_day(input) {
+        let stage = stage_on(input, day)?;
+        if spans.last().is_none_or(|span| span.stage != stage) {
+            if let Some(span) = spans.last_mut() {
+                span.end_day = Some(day);
+            }
+            spans.push(StageSpan {
+                stage,
+                start_day: day,
+                end_day: None,
+            });
+        }
+    }
+    Ok(spans)
+}
+
+/// Where the wine stands on a given day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageStatus {
+    pub day: u32,
+    pub span: StageSpan,
+}
+
+impl StageStatus {
+    pub fn stage(&self) -> Stage {
+        self.span.stage
+    }
+
+    /// Days until the next stage, or `None` once bottled.
+    pub fn days_left(&self) -> Option<u32> {
+        self.span.end_day.map(|end_day| end_day - self.day)
+    }
+}
+
+impl fmt::Display for StageStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span.end_day {
+            None => write!(
+                f,
+                "{} on day {}.",
+                self.stage().label(),
+                self.span.start_day
+            ),
+            Some(end_day) => write!(
+                f,
+                "{}, day {} of {}.",
+                self.stage().label(),
+                self.day - self.span.start_day + 1,
+                end_day - self.span.start_day
+            ),
+        }
+    }
+}
+
+/// The stage on `day` and how far through it the wine is.
+pub fn status(input: &SimulationInput, day: u32) -> Result<StageStatus, SimulationError> {
+    let span = stages(input)?
+        .into_iter()
+        .rfind(|span| span.start_day <= day)
+        .expect("every plan starts on day 0");
+    Ok(StageStatus { day, span })
+}
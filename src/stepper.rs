@@ -0,0 +1,184 @@
+//! Runs a simulation a day at a time, showing every variable the models
+//! track and letting conditions change partway through.
+
+use crate::degassing::dissolved_co2_on;
+use crate::engine::{
+    FermentationState, Intervention, InterventionKind, MAX_YEAST_TEMPERATURE,
+    MIN_YEAST_TEMPERATURE, SimulationError, SimulationInput, fermentation_state, rate_constant,
+    temperature_on, timeline,
+};
+use crate::stage::{Stage, bottling_day, stage_on};
+use crate::sulfides::{SulfideStage, sulfide_stage_on};
+
+/// Everything the models say about the wine on one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayState {
+    pub day: u32,
+    pub stage: Stage,
+    pub temperature: f64,
+    /// Fermentation rate constant per day at that temperature, while the
+    /// yeast are fermenting.
+    pub rate_constant: Option<f64>,
+    /// Where the fermentation stands, as of pressing once the wine is off
+    /// the skins.
+    pub fermentation: FermentationState,
+    pub specific_gravity: f64,
+    /// Dissolved CO2 in g/L.
+    pub dissolved_co2: f64,
+    pub sulfides: Option<SulfideStage>,
+    /// What was done to the wine that day.
+    pub interventions: Vec<Intervention>,
+}
+
+impl DayState {
+    /// The state as labelled values, in the order they are worth reading.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let fermentation = &self.fermentation;
+        vec![
+            ("Day", self.day.to_string()),
+            ("Stage", self.stage.label().to_owned()),
+            ("Temperature", format!("{:.1} °C", self.temperature)),
+            (
+                "Rate constant",
+                self.rate_constant
+                    .map_or("-".to_owned(), |k| format!("{:.3}/day", k)),
+            ),
+            (
+                "Fermented",
+                format!("{:.1}%", fermentation.fraction_fermented * 100.0),
+            ),
+            ("ABV", format!("{:.2}%", fermentation.abv)),
+            (
+                "Potential ABV",
+                format!("{:.2}%", fermentation.potential_abv),
+            ),
+            (
+                "Residual sugar",
+                format!("{:.1} g/L", fermentation.residual_sugar),
+            ),
+            ("Specific gravity", format!("{:.3}", self.specific_gravity)),
+            ("Dissolved CO2", format!("{:.2} g/L", self.dissolved_co2)),
+            (
+                "Sulfides",
+                self.sulfides
+                    .map_or("none".to_owned(), |stage| stage.descriptor().to_owned()),
+            ),
+            (
+                "Interventions",
+                self.interventions
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        ]
+    }
+}
+
+/// A simulation being stepped through one day at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stepper {
+    input: SimulationInput,
+    day: u32,
+}
+
+impl Stepper {
+    pub fn new(input: SimulationInput) -> Result<Self, SimulationError> {
+        fermentation_state(&input, 0)?;
+        Ok(Stepper { input, day: 0 })
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// The input so far, with any changes made while stepping.
+    pub fn input(&self) -> &SimulationInput {
+        &self.input
+    }
+
+    /// The last day there is to step to, when the wine is bottled.
+    pub fn last_day(&self) -> u32 {
+        bottling_day(&self.input)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.day >= self.last_day()
+    }
+
+    /// Moves to `day`, forwards or back, stopping at bottling.
+    pub fn go_to(&mut self, day: u32) {
+        self.day = day.min(self.last_day());
+    }
+
+    /// Moves on `days` days.
+    pub fn step(&mut self, days: u32) {
+        self.go_to(self.day.saturating_add(days));
+    }
+
+    /// Moves back `days` days.
+    pub fn back(&mut self, days: u32) {
+        self.go_to(self.day.saturating_sub(days));
+    }
+
+    /// Moves on to the first day of the next stage.
+    pub fn next_stage(&mut self) -> Result<(), SimulationError> {
+        let stage = stage_on(&self.input, self.day)?;
+        while !self.is_finished() && stage_on(&self.input, self.day)? == stage {
+            self.day += 1;
+        }
+        Ok(())
+    }
+
+    /// Changes the temperature from today on, replacing any change already
+    /// made today.
+    pub fn set_temperature(&mut self, temperature: f64) {
+        let day = self.day;
+        self.input.interventions.retain(|intervention| {
+            intervention.day != day || intervention.kind != InterventionKind::Temperature
+        });
+        self.intervene(InterventionKind::Temperature, temperature);
+    }
+
+    /// Makes an intervention today.
+    pub fn intervene(&mut self, kind: InterventionKind, amount: f64) {
+        self.input.interventions.push(Intervention {
+            day: self.day,
+            kind,
+            amount,
+        });
+        self.input
+            .interventions
+            .sort_by_key(|intervention| intervention.day);
+    }
+
+    pub fn state(&self) -> Result<DayState, SimulationError> {
+        let input = &self.input;
+        let day = self.day;
+        let stage = stage_on(input, day)?;
+        let fermentation = fermentation_state(input, day.min(input.fermentation_days))?;
+        let temperature = temperature_on(input, day);
+        let dormant = !(MIN_YEAST_TEMPERATURE..=MAX_YEAST_TEMPERATURE).contains(&temperature);
+        let rate_constant = matches!(stage, Stage::Lag | Stage::Primary).then(|| {
+            if dormant {
+                0.0
+            } else {
+                rate_constant(temperature)
+            }
+        });
+        Ok(DayState {
+            day,
+            stage,
+            temperature,
+            rate_constant,
+            specific_gravity: fermentation.specific_gravity(),
+            fermentation,
+            dissolved_co2: dissolved_co2_on(input, day),
+            sulfides: sulfide_stage_on(input, day),
+            interventions: timeline(input)
+                .into_iter()
+                .filter(|intervention| intervention.day == day)
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,935 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use tracing::info;
+
+use crate::achievements::Achievement;
+use crate::cellar::{Batch, Measurement, Task};
+use crate::costs::{Cost, Price};
+use crate::dataset::WineRecord;
+use crate::engine::{
+    Beverage, Closure, Excursion, Inoculation, Intervention, InterventionKind, SimulationInput,
+    SimulationResult,
+};
+use crate::inventory::StockItem;
+
+// Each entry upgrades the schema by one `user_version`.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE simulations (
+        id INTEGER PRIMARY KEY,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        grape_type TEXT NOT NULL,
+        fermentation_days INTEGER NOT NULL,
+        container_type TEXT NOT NULL,
+        sugar_content REAL NOT NULL,
+        temperature REAL NOT NULL,
+        climate TEXT NOT NULL,
+        seed INTEGER,
+        actual_abv REAL NOT NULL,
+        residual_sugar REAL NOT NULL,
+        fraction_fermented REAL NOT NULL,
+        narrative TEXT NOT NULL
+    );
+    CREATE TABLE presets (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        grape_type TEXT NOT NULL,
+        fermentation_days INTEGER NOT NULL,
+        container_type TEXT NOT NULL,
+        sugar_content REAL NOT NULL,
+        temperature REAL NOT NULL,
+        climate TEXT NOT NULL,
+        seed INTEGER
+    );
+    CREATE TABLE user_grapes (
+        id INTEGER PRIMARY KEY,
+        grape TEXT NOT NULL,
+        characteristics TEXT NOT NULL
+    );
+    CREATE TABLE batches (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        started_on TEXT NOT NULL,
+        grape_type TEXT NOT NULL,
+        fermentation_days INTEGER NOT NULL,
+        container_type TEXT NOT NULL,
+        sugar_content REAL NOT NULL,
+        temperature REAL NOT NULL,
+        climate TEXT NOT NULL,
+        seed INTEGER,
+        predicted_abv REAL,
+        notes TEXT NOT NULL DEFAULT ''
+    );
+",
+    "
+    CREATE TABLE measurements (
+        id INTEGER PRIMARY KEY,
+        batch_id INTEGER NOT NULL REFERENCES batches (id) ON DELETE CASCADE,
+        measured_on TEXT NOT NULL,
+        sugar_content REAL NOT NULL,
+        temperature REAL,
+        notes TEXT NOT NULL DEFAULT ''
+    );
+    CREATE INDEX measurements_by_batch ON measurements (batch_id, measured_on);
+",
+    "
+    ALTER TABLE measurements ADD COLUMN specific_gravity REAL;
+",
+    "
+    CREATE TABLE tasks (
+        id INTEGER PRIMARY KEY,
+        batch_id INTEGER NOT NULL REFERENCES batches (id) ON DELETE CASCADE,
+        due_on TEXT NOT NULL,
+        title TEXT NOT NULL,
+        done INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX tasks_by_batch ON tasks (batch_id, due_on);
+",
+    "
+    ALTER TABLE tasks ADD COLUMN uid TEXT NOT NULL DEFAULT '';
+    UPDATE tasks SET uid = 'task-' || id;
+",
+    "
+    ALTER TABLE batches ADD COLUMN volume REAL;
+    CREATE TABLE costs (
+        id INTEGER PRIMARY KEY,
+        batch_id INTEGER NOT NULL REFERENCES batches (id) ON DELETE CASCADE,
+        category TEXT NOT NULL,
+        description TEXT NOT NULL,
+        quantity REAL NOT NULL,
+        unit_price REAL NOT NULL
+    );
+    CREATE TABLE prices (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        category TEXT NOT NULL,
+        unit TEXT NOT NULL,
+        unit_price REAL NOT NULL
+    );
+",
+    "
+    CREATE TABLE stock (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+        unit TEXT NOT NULL,
+        quantity REAL NOT NULL
+    );
+",
+    "
+    ALTER TABLE simulations ADD COLUMN beverage TEXT NOT NULL DEFAULT 'Wine';
+    ALTER TABLE presets ADD COLUMN beverage TEXT NOT NULL DEFAULT 'Wine';
+    ALTER TABLE batches ADD COLUMN beverage TEXT NOT NULL DEFAULT 'Wine';
+",
+    "
+    ALTER TABLE simulations ADD COLUMN aging_container TEXT NOT NULL DEFAULT '';
+    ALTER TABLE simulations ADD COLUMN aging_months INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE presets ADD COLUMN aging_container TEXT NOT NULL DEFAULT '';
+    ALTER TABLE presets ADD COLUMN aging_months INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE batches ADD COLUMN aging_container TEXT NOT NULL DEFAULT '';
+    ALTER TABLE batches ADD COLUMN aging_months INTEGER NOT NULL DEFAULT 0;
+",
+    "
+    ALTER TABLE simulations ADD COLUMN whole_cluster REAL NOT NULL DEFAULT 0;
+    ALTER TABLE presets ADD COLUMN whole_cluster REAL NOT NULL DEFAULT 0;
+    ALTER TABLE batches ADD COLUMN whole_cluster REAL NOT NULL DEFAULT 0;
+",
+    "
+    ALTER TABLE simulations ADD COLUMN press_wine REAL NOT NULL DEFAULT 0;
+    ALTER TABLE presets ADD COLUMN press_wine REAL NOT NULL DEFAULT 0;
+    ALTER TABLE batches ADD COLUMN press_wine REAL NOT NULL DEFAULT 0;
+",
+    "
+    ALTER TABLE simulations ADD COLUMN restart_day INTEGER;
+    ALTER TABLE presets ADD COLUMN restart_day INTEGER;
+    ALTER TABLE batches ADD COLUMN restart_day INTEGER;
+",
+    "
+    ALTER TABLE simulations ADD COLUMN excursions TEXT NOT NULL DEFAULT '';
+    ALTER TABLE presets ADD COLUMN excursions TEXT NOT NULL DEFAULT '';
+    ALTER TABLE batches ADD COLUMN excursions TEXT NOT NULL DEFAULT '';
+",
+    "
+    ALTER TABLE simulations ADD COLUMN yeast TEXT NOT NULL DEFAULT '';
+    ALTER TABLE presets ADD COLUMN yeast TEXT NOT NULL DEFAULT '';
+    ALTER TABLE batches ADD COLUMN yeast TEXT NOT NULL DEFAULT '';
+",
+    "
+    ALTER TABLE simulations ADD COLUMN inoculation TEXT NOT NULL DEFAULT 'Single yeast';
+    ALTER TABLE presets ADD COLUMN inoculation TEXT NOT NULL DEFAULT 'Single yeast';
+    ALTER TABLE batches ADD COLUMN inoculation TEXT NOT NULL DEFAULT 'Single yeast';
+",
+    "
+    ALTER TABLE simulations ADD COLUMN lees_months INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE presets ADD COLUMN lees_months INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE batches ADD COLUMN lees_months INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE simulations ADD COLUMN stirs_per_month INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE presets ADD COLUMN stirs_per_month INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE batches ADD COLUMN stirs_per_month INTEGER NOT NULL DEFAULT 0;
+",
+    "
+    ALTER TABLE simulations ADD COLUMN closure TEXT NOT NULL DEFAULT 'Natural cork';
+    ALTER TABLE presets ADD COLUMN closure TEXT NOT NULL DEFAULT 'Natural cork';
+    ALTER TABLE batches ADD COLUMN closure TEXT NOT NULL DEFAULT 'Natural cork';
+",
+    "
+    ALTER TABLE simulations ADD COLUMN interventions TEXT NOT NULL DEFAULT '';
+    ALTER TABLE presets ADD COLUMN interventions TEXT NOT NULL DEFAULT '';
+    ALTER TABLE batches ADD COLUMN interventions TEXT NOT NULL DEFAULT '';
+",
+    "
+    CREATE TABLE challenge_scores (
+        challenge TEXT PRIMARY KEY,
+        best_score REAL NOT NULL,
+        achieved_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+",
+    "
+    CREATE TABLE achievements (
+        achievement TEXT PRIMARY KEY,
+        earned_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE TABLE profile (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        wines_made INTEGER NOT NULL DEFAULT 0
+    );
+    INSERT INTO profile (id) VALUES (1);
+",
+    "
+    ALTER TABLE profile ADD COLUMN tour_seen INTEGER NOT NULL DEFAULT 0;
+",
+];
+
+const INPUT_COLUMNS: &str = "grape_type, fermentation_days, container_type, sugar_content, \
+    temperature, climate, seed, beverage, aging_container, aging_months, whole_cluster, press_wine, restart_day, excursions, yeast, inoculation, lees_months, stirs_per_month, closure, interventions";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub id: i64,
+    /// UTC timestamp as `YYYY-MM-DD HH:MM:SS`.
+    pub created_at: String,
+    pub input: SimulationInput,
+    pub actual_abv: f64,
+    pub residual_sugar: f64,
+    pub fraction_fermented: f64,
+    pub narrative: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub input: SimulationInput,
+}
+
+/// An unfinished task that has come due, with the batch it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueTask {
+    pub batch_id: i64,
+    pub batch_name: String,
+    pub task: Task,
+}
+
+/// SQLite-backed storage for everything the app keeps between sessions.
+pub struct Store {
+    conn: Connection,
+}
+
+// Reads the twenty `INPUT_COLUMNS` starting at column `first`.
+fn input_from_row(row: &Row, first: usize) -> rusqlite::Result<SimulationInput> {
+    Ok(SimulationInput {
+        grape_type: row.get(first)?,
+        fermentation_days: row.get(first + 1)?,
+        container_type: row.get(first + 2)?,
+        sugar_content: row.get(first + 3)?,
+        temperature: row.get(first + 4)?,
+        climate: row.get(first + 5)?,
+        // SQLite integers are signed; seeds round-trip bit for bit.
+        seed: row
+            .get::<_, Option<i64>>(first + 6)?
+            .map(|seed| seed as u64),
+        beverage: Beverage::from_label(&row.get::<_, String>(first + 7)?).unwrap_or_default(),
+        aging_container: row.get(first + 8)?,
+        aging_months: row.get(first + 9)?,
+        whole_cluster: row.get(first + 10)?,
+        press_wine: row.get(first + 11)?,
+        restart_day: row.get(first + 12)?,
+        excursions: parse_excursions(&row.get::<_, String>(first + 13)?),
+        yeast: row.get(first + 14)?,
+        inoculation: Inoculation::from_label(&row.get::<_, String>(first + 15)?)
+            .unwrap_or_default(),
+        lees_months: row.get(first + 16)?,
+        stirs_per_month: row.get(first + 17)?,
+        closure: Closure::from_label(&row.get::<_, String>(first + 18)?).unwrap_or_default(),
+        interventions: parse_interventions(&row.get::<_, String>(first + 19)?),
+    })
+}
+
+fn seed_param(input: &SimulationInput) -> Option<i64> {
+    input.seed.map(|seed| seed as i64)
+}
+
+// Excursions are kept as `start_day:days:temperature`, separated by `;`.
+fn excursions_param(input: &SimulationInput) -> String {
+    input
+        .excursions
+        .iter()
+        .map(|excursion| {
+            format!(
+                "{}:{}:{}",
+                excursion.start_day, excursion.days, excursion.temperature
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn parse_excursions(text: &str) -> Vec<Excursion> {
+    text.split(';')
+        .filter_map(|excursion| {
+            let mut fields = excursion.split(':');
+            Some(Excursion {
+                start_day: fields.next()?.parse().ok()?,
+                days: fields.next()?.parse().ok()?,
+                temperature: fields.next()?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// Interventions are kept as `day:kind:amount`, separated by `;`.
+fn interventions_param(input: &SimulationInput) -> String {
+    input
+        .interventions
+        .iter()
+        .map(|intervention| {
+            format!(
+                "{}:{}:{}",
+                intervention.day,
+                intervention.kind.label(),
+                intervention.amount
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn parse_interventions(text: &str) -> Vec<Intervention> {
+    text.split(';')
+        .filter_map(|intervention| {
+            let mut fields = intervention.split(':');
+            let day = fields.next()?.parse().ok()?;
+            let kind = InterventionKind::from_label(fields.next()?)?;
+            // Rows saved before amounts were kept get the usual amount.
+            Some(match fields.next() {
+                Some(amount) => Intervention {
+                    day,
+                    kind,
+                    amount: amount.parse().ok()?,
+                },
+                None => Intervention::new(day, kind),
+            })
+        })
+        .collect()
+}
+
+fn batch_from_row(row: &Row) -> rusqlite::Result<Batch> {
+    Ok(Batch {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        started_on: row.get(2)?,
+        input: input_from_row(row, 3)?,
+        predicted_abv: row.get(23)?,
+        notes: row.get(24)?,
+        volume: row.get(25)?,
+    })
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::migrate(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::migrate(Connection::open_in_memory()?)
+    }
+
+    fn migrate(mut conn: Connection) -> rusqlite::Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version < MIGRATIONS.len() {
+            let tx = conn.transaction()?;
+            for migration in &MIGRATIONS[version..] {
+                tx.execute_batch(migration)?;
+            }
+            tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+            tx.commit()?;
+            info!(
+                from = version,
+                to = MIGRATIONS.len(),
+                "migrated store schema"
+            );
+        }
+        Ok(Store { conn })
+    }
+
+    pub fn record_simulation(
+        &self,
+        input: &SimulationInput,
+        result: &SimulationResult,
+        narrative: &str,
+    ) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            &format!(
+                "INSERT INTO simulations ({INPUT_COLUMNS}, actual_abv, residual_sugar, fraction_fermented, narrative)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)"
+            ),
+            params![
+                input.grape_type,
+                input.fermentation_days,
+                input.container_type,
+                input.sugar_content,
+                input.temperature,
+                input.climate,
+                seed_param(input),
+                input.beverage.label(),
+                input.aging_container,
+                input.aging_months,
+                input.whole_cluster,
+                input.press_wine,
+                input.restart_day,
+                excursions_param(input),
+                input.yeast,
+                input.inoculation.label(),
+                input.lees_months,
+                input.stirs_per_month,
+                input.closure.label(),
+                interventions_param(input),
+                result.actual_abv,
+                result.residual_sugar,
+                result.fraction_fermented,
+                narrative,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Most recent simulations first.
+    pub fn history(&self, limit: usize) -> rusqlite::Result<Vec<HistoryEntry>> {
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT id, created_at, {INPUT_COLUMNS}, actual_abv, residual_sugar, fraction_fermented, narrative
+             FROM simulations ORDER BY id DESC LIMIT ?1"
+        ))?;
+        statement
+            .query_map([limit as i64], |row| {
+                Ok(HistoryEntry {
+                    id: row.get(0)?,
+                    created_at: row.get(1)?,
+                    input: input_from_row(row, 2)?,
+                    actual_abv: row.get(22)?,
+                    residual_sugar: row.get(23)?,
+                    fraction_fermented: row.get(24)?,
+                    narrative: row.get(25)?,
+                })
+            })?
+            .collect()
+    }
+
+    pub fn clear_history(&self) -> rusqlite::Result<()> {
+        self.conn.execute("DELETE FROM simulations", [])?;
+        Ok(())
+    }
+
+    /// Saves `input` under `name`, replacing any preset with the same name.
+    pub fn save_preset(&self, name: &str, input: &SimulationInput) -> rusqlite::Result<()> {
+        self.conn.execute(
+            &format!(
+                "INSERT INTO presets (name, {INPUT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
+                 ON CONFLICT (name) DO UPDATE SET
+                    grape_type = excluded.grape_type,
+                    fermentation_days = excluded.fermentation_days,
+                    container_type = excluded.container_type,
+                    sugar_content = excluded.sugar_content,
+                    temperature = excluded.temperature,
+                    climate = excluded.climate,
+                    seed = excluded.seed,
+                    beverage = excluded.beverage,
+                    aging_container = excluded.aging_container,
+                    aging_months = excluded.aging_months,
+                    whole_cluster = excluded.whole_cluster,
+                    press_wine = excluded.press_wine,
+                    restart_day = excluded.restart_day,
+                    excursions = excluded.excursions,
+                    yeast = excluded.yeast,
+                    inoculation = excluded.inoculation,
+                    lees_months = excluded.lees_months,
+                    stirs_per_month = excluded.stirs_per_month,
+                    closure = excluded.closure,
+                    interventions = excluded.interventions"
+            ),
+            params![
+                name,
+                input.grape_type,
+                input.fermentation_days,
+                input.container_type,
+                input.sugar_content,
+                input.temperature,
+                input.climate,
+                seed_param(input),
+                input.beverage.label(),
+                input.aging_container,
+                input.aging_months,
+                input.whole_cluster,
+                input.press_wine,
+                input.restart_day,
+                excursions_param(input),
+                input.yeast,
+                input.inoculation.label(),
+                input.lees_months,
+                input.stirs_per_month,
+                input.closure.label(),
+                interventions_param(input),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn preset(&self, name: &str) -> rusqlite::Result<Option<Preset>> {
+        self.conn
+            .query_row(
+                &format!("SELECT name, {INPUT_COLUMNS} FROM presets WHERE name = ?1"),
+                [name],
+                |row| {
+                    Ok(Preset {
+                        name: row.get(0)?,
+                        input: input_from_row(row, 1)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    pub fn presets(&self) -> rusqlite::Result<Vec<Preset>> {
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT name, {INPUT_COLUMNS} FROM presets ORDER BY name"
+        ))?;
+        statement
+            .query_map([], |row| {
+                Ok(Preset {
+                    name: row.get(0)?,
+                    input: input_from_row(row, 1)?,
+                })
+            })?
+            .collect()
+    }
+
+    pub fn delete_preset(&self, name: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM presets WHERE name = ?1", [name])?;
+        Ok(())
+    }
+
+    pub fn add_user_grape(&self, record: &WineRecord) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO user_grapes (grape, characteristics) VALUES (?1, ?2)",
+            params![record.grape, record.characteristics],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn user_grapes(&self) -> rusqlite::Result<Vec<WineRecord>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT grape, characteristics FROM user_grapes ORDER BY id")?;
+        statement
+            .query_map([], |row| {
+                Ok(WineRecord {
+                    grape: row.get(0)?,
+                    characteristics: row.get(1)?,
+                    ..WineRecord::default()
+                })
+            })?
+            .collect()
+    }
+
+    pub fn add_batch(
+        &self,
+        name: &str,
+        started_on: NaiveDate,
+        input: &SimulationInput,
+        predicted_abv: Option<f64>,
+    ) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            &format!(
+                "INSERT INTO batches (name, started_on, {INPUT_COLUMNS}, predicted_abv)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)"
+            ),
+            params![
+                name,
+                started_on,
+                input.grape_type,
+                input.fermentation_days,
+                input.container_type,
+                input.sugar_content,
+                input.temperature,
+                input.climate,
+                seed_param(input),
+                input.beverage.label(),
+                input.aging_container,
+                input.aging_months,
+                input.whole_cluster,
+                input.press_wine,
+                input.restart_day,
+                excursions_param(input),
+                input.yeast,
+                input.inoculation.label(),
+                input.lees_months,
+                input.stirs_per_month,
+                input.closure.label(),
+                interventions_param(input),
+                predicted_abv,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn batches(&self) -> rusqlite::Result<Vec<Batch>> {
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT id, name, started_on, {INPUT_COLUMNS}, predicted_abv, notes, volume
+             FROM batches ORDER BY started_on DESC, id DESC"
+        ))?;
+        statement.query_map([], batch_from_row)?.collect()
+    }
+
+    /// Batches whose predicted ABV is at least `min_abv` percent.
+    pub fn batches_with_abv_at_least(&self, min_abv: f64) -> rusqlite::Result<Vec<Batch>> {
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT id, name, started_on, {INPUT_COLUMNS}, predicted_abv, notes, volume
+             FROM batches WHERE predicted_abv >= ?1 ORDER BY predicted_abv DESC"
+        ))?;
+        statement.query_map([min_abv], batch_from_row)?.collect()
+    }
+
+    /// Deletes the batch along with its measurements, schedule, and costs.
+    pub fn delete_batch(&self, batch_id: i64) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM batches WHERE id = ?1", [batch_id])?;
+        Ok(())
+    }
+
+    pub fn set_batch_notes(&self, batch_id: i64, notes: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE batches SET notes = ?2 WHERE id = ?1",
+            params![batch_id, notes],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_batch_volume(&self, batch_id: i64, volume: Option<f64>) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE batches SET volume = ?2 WHERE id = ?1",
+            params![batch_id, volume],
+        )?;
+        Ok(())
+    }
+
+    pub fn add_measurement(
+        &self,
+        batch_id: i64,
+        measurement: &Measurement,
+    ) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO measurements (batch_id, measured_on, sugar_content, specific_gravity, temperature, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                batch_id,
+                measurement.measured_on,
+                measurement.sugar_content,
+                measurement.specific_gravity,
+                measurement.temperature,
+                measurement.notes,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Adds all of `measurements` to the batch, or none if any fails.
+    pub fn add_measurements(
+        &self,
+        batch_id: i64,
+        measurements: &[Measurement],
+    ) -> rusqlite::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for measurement in measurements {
+            self.add_measurement(batch_id, measurement)?;
+        }
+        tx.commit()
+    }
+
+    /// The batch's measurements in the order they were taken.
+    pub fn measurements(&self, batch_id: i64) -> rusqlite::Result<Vec<Measurement>> {
+        let mut statement = self.conn.prepare(
+            "SELECT measured_on, sugar_content, specific_gravity, temperature, notes
+             FROM measurements WHERE batch_id = ?1 ORDER BY measured_on, id",
+        )?;
+        statement
+            .query_map([batch_id], |row| {
+                Ok(Measurement {
+                    measured_on: row.get(0)?,
+                    sugar_content: row.get(1)?,
+                    specific_gravity: row.get(2)?,
+                    temperature: row.get(3)?,
+                    notes: row.get(4)?,
+                })
+            })?
+            .collect()
+    }
+
+    /// Replaces the batch's schedule with `tasks`.
+    pub fn set_tasks(&self, batch_id: i64, tasks: &[Task]) -> rusqlite::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn
+            .execute("DELETE FROM tasks WHERE batch_id = ?1", [batch_id])?;
+        for task in tasks {
+            self.conn.execute(
+                "INSERT INTO tasks (batch_id, uid, due_on, title, done) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![batch_id, task.uid, task.due_on, task.title, task.done],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// The batch's schedule, earliest first.
+    pub fn tasks(&self, batch_id: i64) -> rusqlite::Result<Vec<Task>> {
+        let mut statement = self.conn.prepare(
+            "SELECT uid, due_on, title, done FROM tasks WHERE batch_id = ?1 ORDER BY due_on, id",
+        )?;
+        statement
+            .query_map([batch_id], |row| {
+                Ok(Task {
+                    uid: row.get(0)?,
+                    due_on: row.get(1)?,
+                    title: row.get(2)?,
+                    done: row.get(3)?,
+                })
+            })?
+            .collect()
+    }
+
+    /// Unfinished tasks of every batch due on or before `on`, oldest first.
+    pub fn due_tasks(&self, on: NaiveDate) -> rusqlite::Result<Vec<DueTask>> {
+        let mut statement = self.conn.prepare(
+            "SELECT batches.id, batches.name, tasks.uid, tasks.due_on, tasks.title
+             FROM tasks JOIN batches ON batches.id = tasks.batch_id
+             WHERE NOT tasks.done AND tasks.due_on <= ?1
+             ORDER BY tasks.due_on, tasks.id",
+        )?;
+        statement
+            .query_map([on], |row| {
+                Ok(DueTask {
+                    batch_id: row.get(0)?,
+                    batch_name: row.get(1)?,
+                    task: Task {
+                        uid: row.get(2)?,
+                        due_on: row.get(3)?,
+                        title: row.get(4)?,
+                        done: false,
+                    },
+                })
+            })?
+            .collect()
+    }
+
+    /// Replaces the batch's costs with `costs`.
+    pub fn set_costs(&self, batch_id: i64, costs: &[Cost]) -> rusqlite::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn
+            .execute("DELETE FROM costs WHERE batch_id = ?1", [batch_id])?;
+        for cost in costs {
+            self.conn.execute(
+                "INSERT INTO costs (batch_id, category, description, quantity, unit_price)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    batch_id,
+                    cost.category,
+                    cost.description,
+                    cost.quantity,
+                    cost.unit_price,
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    pub fn costs(&self, batch_id: i64) -> rusqlite::Result<Vec<Cost>> {
+        let mut statement = self.conn.prepare(
+            "SELECT category, description, quantity, unit_price
+             FROM costs WHERE batch_id = ?1 ORDER BY id",
+        )?;
+        statement
+            .query_map([batch_id], |row| {
+                Ok(Cost {
+                    category: row.get(0)?,
+                    description: row.get(1)?,
+                    quantity: row.get(2)?,
+                    unit_price: row.get(3)?,
+                })
+            })?
+            .collect()
+    }
+
+    /// Adds `price` to the price list, replacing any with the same name.
+    pub fn save_price(&self, price: &Price) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO prices (name, category, unit, unit_price) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (name) DO UPDATE SET
+                category = excluded.category,
+                unit = excluded.unit,
+                unit_price = excluded.unit_price",
+            params![price.name, price.category, price.unit, price.unit_price],
+        )?;
+        Ok(())
+    }
+
+    pub fn prices(&self) -> rusqlite::Result<Vec<Price>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT name, category, unit, unit_price FROM prices ORDER BY name")?;
+        statement
+            .query_map([], |row| {
+                Ok(Price {
+                    name: row.get(0)?,
+                    category: row.get(1)?,
+                    unit: row.get(2)?,
+                    unit_price: row.get(3)?,
+                })
+            })?
+            .collect()
+    }
+
+    pub fn delete_price(&self, name: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM prices WHERE name = ?1", [name])?;
+        Ok(())
+    }
+
+    /// Sets the on-hand quantity of an item, adding it if it is new.
+    pub fn save_stock_item(&self, item: &StockItem) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO stock (name, unit, quantity) VALUES (?1, ?2, ?3)
+             ON CONFLICT (name) DO UPDATE SET unit = excluded.unit, quantity = excluded.quantity",
+            params![item.name, item.unit, item.quantity],
+        )?;
+        Ok(())
+    }
+
+    pub fn stock(&self) -> rusqlite::Result<Vec<StockItem>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT name, unit, quantity FROM stock ORDER BY name")?;
+        statement
+            .query_map([], |row| {
+                Ok(StockItem {
+                    name: row.get(0)?,
+                    unit: row.get(1)?,
+                    quantity: row.get(2)?,
+                })
+            })?
+            .collect()
+    }
+
+    pub fn delete_stock_item(&self, name: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM stock WHERE name = ?1", [name])?;
+        Ok(())
+    }
+
+    /// Takes `items` out of stock, stopping at zero for anything short.
+    pub fn take_stock(&self, items: &[StockItem]) -> rusqlite::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for item in items {
+            self.conn.execute(
+                "UPDATE stock SET quantity = MAX(quantity - ?2, 0) WHERE name = ?1",
+                params![item.name, item.quantity],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Records a score for a challenge, returning whether it beats the best
+    /// so far.
+    pub fn record_challenge_score(&self, challenge: &str, score: f64) -> rusqlite::Result<bool> {
+        let changed = self.conn.execute(
+            "INSERT INTO challenge_scores (challenge, best_score) VALUES (?1, ?2)
+             ON CONFLICT (challenge) DO UPDATE SET
+                best_score = excluded.best_score,
+                achieved_at = excluded.achieved_at
+             WHERE excluded.best_score > challenge_scores.best_score",
+            params![challenge, score],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// The best score for each challenge played, by name.
+    pub fn challenge_scores(&self) -> rusqlite::Result<Vec<(String, f64)>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT challenge, best_score FROM challenge_scores ORDER BY challenge")?;
+        statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect()
+    }
+
+    /// Counts another wine made and returns how many have been made.
+    pub fn count_wine_made(&self) -> rusqlite::Result<u32> {
+        self.conn
+            .execute("UPDATE profile SET wines_made = wines_made + 1", [])?;
+        self.wines_made()
+    }
+
+    /// How many wines have been made, kept when the history is cleared.
+    pub fn wines_made(&self) -> rusqlite::Result<u32> {
+        self.conn
+            .query_row("SELECT wines_made FROM profile", [], |row| row.get(0))
+    }
+
+    /// Whether the first-run tour has been finished or dismissed.
+    pub fn tour_seen(&self) -> rusqlite::Result<bool> {
+        self.conn
+            .query_row("SELECT tour_seen FROM profile", [], |row| row.get(0))
+    }
+
+    pub fn mark_tour_seen(&self) -> rusqlite::Result<()> {
+        self.conn.execute("UPDATE profile SET tour_seen = 1", [])?;
+        Ok(())
+    }
+
+    /// Records an achievement, returning whether it is newly earned.
+    pub fn record_achievement(&self, achievement: Achievement) -> rusqlite::Result<bool> {
+        let changed = self.conn.execute(
+            "INSERT OR IGNORE INTO achievements (achievement) VALUES (?1)",
+            params![achievement.label()],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// The achievements earned, with when each was earned as a UTC timestamp
+    /// (`YYYY-MM-DD HH:MM:SS`), in the order they were earned.
+    pub fn achievements(&self) -> rusqlite::Result<Vec<(Achievement, String)>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT achievement, earned_at FROM achievements ORDER BY earned_at, rowid")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut earned = Vec::new();
+        for row in rows {
+            let (label, earned_at) = row?;
+            // Skip achievements from a newer release.
+            if let Some(achievement) = Achievement::from_label(&label) {
+                earned.push((achievement, earned_at));
+            }
+        }
+        Ok(earned)
+    }
+}
@@ -0,0 +1,139 @@
+//! Hydrogen sulfide from yeast short of nitrogen, and what it turns into if
+//! it is left in the wine. Aeration and copper only work on the early
+//! stages, so when they are made matters as much as whether they are.
+
+use crate::engine::{InterventionKind, SimulationInput, fermentation_state, timeline};
+use crate::stage::bottling_day;
+
+/// Yeast-assimilable nitrogen in mg/L the yeast need per g/L of sugar.
+const NITROGEN_PER_SUGAR: f64 = 0.8;
+/// Fraction fermented after which the yeast can no longer take up nutrient.
+const NUTRIENT_UPTAKE_LIMIT: f64 = 1.0 / 3.0;
+/// Share of the nitrogen demand the must can fall short by before the
+/// yeast start giving off hydrogen sulfide.
+const TOLERABLE_STRESS: f64 = 0.25;
+/// Days after it forms that hydrogen sulfide starts turning into
+/// mercaptans, which aeration can't remove.
+const MERCAPTAN_DAYS: u32 = 10;
+/// Days after it forms that the mercaptans oxidize to disulfides, which
+/// copper can't remove either.
+const DISULFIDE_DAYS: u32 = 60;
+
+/// How far a sulfide fault has gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SulfideStage {
+    HydrogenSulfide,
+    Mercaptans,
+    Disulfides,
+}
+
+impl SulfideStage {
+    /// The stage `days` days after the hydrogen sulfide formed.
+    fn after(days: u32) -> Self {
+        match days {
+            d if d < MERCAPTAN_DAYS => SulfideStage::HydrogenSulfide,
+            d if d < DISULFIDE_DAYS => SulfideStage::Mercaptans,
+            _ => SulfideStage::Disulfides,
+        }
+    }
+
+    pub fn descriptor(self) -> &'static str {
+        match self {
+            SulfideStage::HydrogenSulfide => "a rotten-egg reek of hydrogen sulfide",
+            SulfideStage::Mercaptans => "cooked-cabbage and burnt-rubber mercaptans",
+            SulfideStage::Disulfides => "stubborn canned-corn and onion disulfides",
+        }
+    }
+
+    fn is_cured_by(self, kind: InterventionKind) -> bool {
+        match kind {
+            InterventionKind::Aeration | InterventionKind::Racking => {
+                self == SulfideStage::HydrogenSulfide
+            }
+            InterventionKind::Copper => self <= SulfideStage::Mercaptans,
+            _ => false,
+        }
+    }
+}
+
+/// How a sulfide fault turned out by bottling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SulfideFault {
+    /// The day the yeast started giving off hydrogen sulfide.
+    pub onset_day: u32,
+    /// The intervention that cleared it and the day it was made, if any.
+    pub cured_by: Option<(InterventionKind, u32)>,
+    /// The fault left at bottling, if it wasn't cleared.
+    pub remaining: Option<SulfideStage>,
+}
+
+fn fraction_fermented(input: &SimulationInput, day: u32) -> f64 {
+    fermentation_state(input, day).map_or(0.0, |state| state.fraction_fermented)
+}
+
+/// Share from 0 to 1 of the yeast's nitrogen needs the must falls short by,
+/// counting nutrient added early enough to be taken up.
+pub fn nutrient_stress(input: &SimulationInput) -> f64 {
+    let Ok(state) = fermentation_state(input, 0) else {
+        return 0.0;
+    };
+    let demand = state.sugar_content * NITROGEN_PER_SUGAR;
+    if demand <= 0.0 {
+        return 0.0;
+    }
+    let added: f64 = input
+        .interventions
+        .iter()
+        .filter(|intervention| {
+            intervention.kind == InterventionKind::Nutrient
+                && intervention.day <= input.fermentation_days
+                && fraction_fermented(input, intervention.day) < NUTRIENT_UPTAKE_LIMIT
+        })
+        .map(|intervention| intervention.amount.max(0.0))
+        .sum();
+    let nitrogen = input.beverage.yeast_nitrogen() + added;
+    ((demand - nitrogen) / demand).clamp(0.0, 1.0)
+}
+
+/// The day the yeast run out of nitrogen and start giving off hydrogen
+/// sulfide, or `None` if they never do.
+pub fn h2s_onset_day(input: &SimulationInput) -> Option<u32> {
+    let stress = nutrient_stress(input);
+    if stress <= TOLERABLE_STRESS {
+        return None;
+    }
+    // The more they fall short, the sooner the nitrogen runs out.
+    (0..=input.fermentation_days).find(|&day| fraction_fermented(input, day) >= 1.0 - stress)
+}
+
+/// The sulfide fault from nutrient stress and how the interventions dealt
+/// with it, or `None` if there was none.
+pub fn sulfide_fault(input: &SimulationInput) -> Option<SulfideFault> {
+    let onset_day = h2s_onset_day(input)?;
+    let bottling_day = bottling_day(input);
+    // The timeline is in day order, so the first remedy that works wins.
+    let cured_by = timeline(input)
+        .into_iter()
+        .filter(|intervention| (onset_day..=bottling_day).contains(&intervention.day))
+        .find(|intervention| {
+            SulfideStage::after(intervention.day - onset_day).is_cured_by(intervention.kind)
+        })
+        .map(|intervention| (intervention.kind, intervention.day));
+    Some(SulfideFault {
+        onset_day,
+        cured_by,
+        remaining: cured_by
+            .is_none()
+            .then(|| SulfideStage::after(bottling_day - onset_day)),
+    })
+}
+
+/// How far the sulfide fault has gone on `day`, or `None` if there is none
+/// in the wine that day.
+pub fn sulfide_stage_on(input: &SimulationInput, day: u32) -> Option<SulfideStage> {
+    let fault = sulfide_fault(input)?;
+    let cured = fault
+        .cured_by
+        .is_some_and(|(_, cured_day)| day >= cured_day);
+    (day >= fault.onset_day && !cured).then(|| SulfideStage::after(day - fault.onset_day))
+}
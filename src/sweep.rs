@@ -0,0 +1,368 @@
+//! Parameter sweeps: a scenario run over a grid of inputs. Each `[sweep]`
+//! entry in a scenario file names a field and the values it takes, as in
+//! `primary_temp = "14..28 step 2"`, and the scenario is run once for
+//! every combination. The runs are simulated in parallel and gathered into
+//! a spreadsheet, and into a plot of how they turned out.
+
+use std::fmt;
+use std::fmt::Write as _;
+use std::thread;
+
+use crate::calibration::{self, with_calibration};
+use crate::dataset::Dataset;
+use crate::engine::{SimulationError, SimulationInput, SimulationResult, run_simulation};
+use crate::export::Spreadsheet;
+
+/// Most runs a scenario's sweeps may expand into.
+pub const MAX_RUNS: usize = 100_000;
+
+/// An input a sweep can vary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepParameter {
+    Temperature,
+    Sugar,
+    FermentationDays,
+    AgingMonths,
+    WholeCluster,
+    PressWine,
+    LeesMonths,
+    StirsPerMonth,
+    Seed,
+}
+
+impl SweepParameter {
+    pub const ALL: [SweepParameter; 9] = [
+        SweepParameter::Temperature,
+        SweepParameter::Sugar,
+        SweepParameter::FermentationDays,
+        SweepParameter::AgingMonths,
+        SweepParameter::WholeCluster,
+        SweepParameter::PressWine,
+        SweepParameter::LeesMonths,
+        SweepParameter::StirsPerMonth,
+        SweepParameter::Seed,
+    ];
+
+    /// The field it's named by in a scenario file.
+    pub fn label(self) -> &'static str {
+        match self {
+            SweepParameter::Temperature => "primary_temp",
+            SweepParameter::Sugar => "sugar_g_per_l",
+            SweepParameter::FermentationDays => "primary_age",
+            SweepParameter::AgingMonths => "age",
+            SweepParameter::WholeCluster => "whole_cluster_percent",
+            SweepParameter::PressWine => "press_wine_percent",
+            SweepParameter::LeesMonths => "lees_months",
+            SweepParameter::StirsPerMonth => "stirs_per_month",
+            SweepParameter::Seed => "seed",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|parameter| parameter.label() == label)
+    }
+
+    /// Whether it only takes whole numbers.
+    pub fn is_count(self) -> bool {
+        !matches!(
+            self,
+            SweepParameter::Temperature
+                | SweepParameter::Sugar
+                | SweepParameter::WholeCluster
+                | SweepParameter::PressWine
+        )
+    }
+
+    fn apply(self, input: &mut SimulationInput, value: f64) {
+        match self {
+            SweepParameter::Temperature => input.temperature = value,
+            SweepParameter::Sugar => input.sugar_content = value,
+            SweepParameter::FermentationDays => input.fermentation_days = value as u32,
+            SweepParameter::AgingMonths => input.aging_months = value as u32,
+            SweepParameter::WholeCluster => input.whole_cluster = value,
+            SweepParameter::PressWine => input.press_wine = value,
+            SweepParameter::LeesMonths => input.lees_months = value as u32,
+            SweepParameter::StirsPerMonth => input.stirs_per_month = value as u32,
+            SweepParameter::Seed => input.seed = Some(value as u64),
+        }
+    }
+}
+
+/// Why a sweep can't be read, as `(field, text)` with the text as written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepError(pub String, pub String);
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "can't sweep {} over \"{}\"; write a range such as \"14..28 step 2\"",
+            self.0, self.1
+        )
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+/// One input run from `start` to `end`, both included, `step` apart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    pub parameter: SweepParameter,
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+}
+
+impl Sweep {
+    /// Reads a sweep of the field `field` over `text`, such as
+    /// "14..28 step 2"; the step is 1 if left out.
+    pub fn parse(field: &str, text: &str) -> Result<Self, SweepError> {
+        let error = || SweepError(field.to_owned(), text.to_owned());
+        let parameter = SweepParameter::from_label(field).ok_or_else(error)?;
+        let (range, step) = match text.split_once("step") {
+            Some((range, step)) => (range, step.trim().parse().map_err(|_| error())?),
+            None => (text, 1.0),
+        };
+        let (start, end) = range.split_once("..").ok_or_else(error)?;
+        let end = end.strip_prefix('=').unwrap_or(end);
+        let sweep = Sweep {
+            parameter,
+            start: start.trim().parse().map_err(|_| error())?,
+            end: end.trim().parse().map_err(|_| error())?,
+            step,
+        };
+        let whole = |value: f64| value.fract() == 0.0 && value >= 0.0;
+        let valid = [sweep.start, sweep.end, sweep.step]
+            .iter()
+            .all(|value| value.is_finite())
+            && sweep.step > 0.0
+            && sweep.end >= sweep.start
+            && (!parameter.is_count()
+                || (whole(sweep.start) && whole(sweep.end) && whole(sweep.step)));
+        if valid { Ok(sweep) } else { Err(error()) }
+    }
+
+    /// How many values it takes, `usize::MAX` for too many to count.
+    pub fn count(&self) -> usize {
+        // Rounding error mustn't drop the end of a range like 0.1..0.3.
+        let steps = ((self.end - self.start) / self.step + 1e-9).floor() as usize;
+        // The cast saturates, so a vast range can't wrap round to nothing.
+        steps.saturating_add(1)
+    }
+
+    /// Every value it takes, in order.
+    pub fn values(&self) -> Vec<f64> {
+        (0..self.count())
+            .map(|index| self.start + index as f64 * self.step)
+            .collect()
+    }
+}
+
+/// The range as written in a scenario file, such as "14..28 step 2".
+impl fmt::Display for Sweep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{} step {}", self.start, self.end, self.step)
+    }
+}
+
+/// How many runs `sweeps` expand into.
+pub fn run_count(sweeps: &[Sweep]) -> usize {
+    sweeps
+        .iter()
+        .map(Sweep::count)
+        .fold(1, usize::saturating_mul)
+}
+
+/// One combination of swept values and how it turned out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepRun {
+    /// The value each sweep took, in the sweeps' order.
+    pub values: Vec<f64>,
+    pub input: SimulationInput,
+    pub result: Result<SimulationResult, SimulationError>,
+}
+
+impl SweepRun {
+    /// The swept values, such as "primary_temp 16, seed 3".
+    pub fn name(&self, sweeps: &[Sweep]) -> String {
+        describe(sweeps, &self.values)
+    }
+}
+
+fn describe(sweeps: &[Sweep], values: &[f64]) -> String {
+    sweeps
+        .iter()
+        .zip(values)
+        .map(|(sweep, value)| format!("{} {}", sweep.parameter.label(), value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Every combination of the values of `sweeps` applied to `input`, the
+/// last sweep varying fastest.
+pub fn expand(input: &SimulationInput, sweeps: &[Sweep]) -> Vec<(Vec<f64>, SimulationInput)> {
+    let mut runs = vec![(Vec::new(), input.clone())];
+    for sweep in sweeps {
+        runs = runs
+            .into_iter()
+            .flat_map(|(values, input)| {
+                sweep.values().into_iter().map(move |value| {
+                    let mut values = values.clone();
+                    values.push(value);
+                    let mut input = input.clone();
+                    sweep.parameter.apply(&mut input, value);
+                    (values, input)
+                })
+            })
+            .collect();
+    }
+    runs
+}
+
+/// Simulates every combination of `sweeps` on `input`, spread over the
+/// machine's cores, in the order `expand` gives. Every run is on the
+/// constants of the calling thread.
+pub fn run_sweeps(input: &SimulationInput, sweeps: &[Sweep], wine_data: &Dataset) -> Vec<SweepRun> {
+    let runs = expand(input, sweeps);
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk = runs.len().div_ceil(threads).max(1);
+    let calibration = calibration::installed();
+    thread::scope(|scope| {
+        let handles: Vec<_> = runs
+            .chunks(chunk)
+            .map(|runs| {
+                scope.spawn(move || {
+                    with_calibration(calibration, || {
+                        runs.iter()
+                            .map(|(values, input)| SweepRun {
+                                values: values.clone(),
+                                input: input.clone(),
+                                result: run_simulation(input, wine_data),
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("simulations don't panic"))
+            .collect()
+    })
+}
+
+/// The runs one row each, named by their swept values.
+pub fn spreadsheet(runs: &[SweepRun], sweeps: &[Sweep]) -> Spreadsheet {
+    let mut spreadsheet = Spreadsheet::default();
+    for run in runs {
+        spreadsheet.push(&run.name(sweeps), "", &run.input, run.result.as_ref());
+    }
+    spreadsheet
+}
+
+const PLOT_WIDTH: f64 = 640.0;
+const PANEL_HEIGHT: f64 = 240.0;
+const MARGIN: f64 = 50.0;
+const LEGEND_WIDTH: f64 = 220.0;
+const COLORS: [&str; 8] = [
+    "#7b1e3a", "#c9a227", "#2f6f4f", "#3b5b92", "#d2691e", "#6a4c93", "#4a4a4a", "#b5475b",
+];
+
+/// An SVG plot of the ABV and residual sugar of `runs` against the first
+/// sweep, one line for each combination of the others. Failed runs leave
+/// gaps. Empty if there is no sweep.
+pub fn plot(runs: &[SweepRun], sweeps: &[Sweep]) -> String {
+    let Some(first) = sweeps.first() else {
+        return String::new();
+    };
+    // The first sweep varies slowest, so a line's runs are spread out by
+    // the number of lines.
+    let line_count = runs.len() / first.count();
+    let lines: Vec<Vec<&SweepRun>> = (0..line_count)
+        .map(|line| runs.iter().skip(line).step_by(line_count).collect())
+        .collect();
+    let (low, high) = (first.start, first.end.max(first.start + f64::EPSILON));
+    let width = PLOT_WIDTH + LEGEND_WIDTH;
+    let height = 2.0 * PANEL_HEIGHT;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12"><rect width="{width}" height="{height}" fill="#fff"/>"##
+    );
+    let panels: [(&str, fn(&SimulationResult) -> f64); 2] = [
+        ("ABV (%)", |result| result.actual_abv),
+        ("Residual sugar (g/L)", |result| result.residual_sugar),
+    ];
+    for (panel, (title, metric)) in panels.into_iter().enumerate() {
+        let top = panel as f64 * PANEL_HEIGHT;
+        let bottom = top + PANEL_HEIGHT - MARGIN;
+        let plot_top = top + MARGIN / 2.0;
+        let ys: Vec<f64> = runs
+            .iter()
+            .filter_map(|run| run.result.as_ref().ok().map(metric))
+            .collect();
+        let y_low = ys.iter().copied().fold(f64::INFINITY, f64::min).min(0.0);
+        let y_high = ys.iter().copied().fold(0.0, f64::max).max(y_low + 1.0);
+        let x = |value: f64| MARGIN + (value - low) / (high - low) * (PLOT_WIDTH - 1.5 * MARGIN);
+        let y = |value: f64| bottom - (value - y_low) / (y_high - y_low) * (bottom - plot_top);
+        let _ = write!(
+            svg,
+            r##"<line x1="{left}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="#000"/><line x1="{left}" y1="{plot_top}" x2="{left}" y2="{bottom}" stroke="#000"/><text x="{left}" y="{title_y}">{title}</text><text x="{left}" y="{label_y}" text-anchor="middle">{low}</text><text x="{right}" y="{label_y}" text-anchor="middle">{high}</text><text x="{axis_x}" y="{label_y}" text-anchor="middle">{name}</text><text x="{tick_x}" y="{bottom}" text-anchor="end">{y_low:.1}</text><text x="{tick_x}" y="{plot_top}" text-anchor="end">{y_high:.1}</text>"##,
+            left = MARGIN,
+            right = x(high),
+            title_y = plot_top - 6.0,
+            label_y = bottom + 16.0,
+            axis_x = (MARGIN + x(high)) / 2.0,
+            name = first.parameter.label(),
+            tick_x = MARGIN - 4.0,
+            low = first.start,
+            high = first.end,
+        );
+        for (index, line) in lines.iter().enumerate() {
+            let color = COLORS[index % COLORS.len()];
+            let mut path = String::new();
+            let mut pen_down = false;
+            for run in line.iter() {
+                match &run.result {
+                    Ok(result) => {
+                        let command = if pen_down { 'L' } else { 'M' };
+                        let _ = write!(
+                            path,
+                            "{}{:.1},{:.1} ",
+                            command,
+                            x(run.values[0]),
+                            y(metric(result))
+                        );
+                        pen_down = true;
+                    }
+                    Err(_) => pen_down = false,
+                }
+            }
+            let _ = write!(
+                svg,
+                r##"<path d="{}" fill="none" stroke="{}" stroke-width="2"/>"##,
+                path.trim_end(),
+                color
+            );
+        }
+    }
+    if sweeps.len() > 1 {
+        for (index, line) in lines.iter().enumerate() {
+            let legend_y = MARGIN / 2.0 + index as f64 * 16.0;
+            if legend_y > height - 16.0 {
+                break;
+            }
+            let name = describe(&sweeps[1..], &line[0].values[1..]);
+            let _ = write!(
+                svg,
+                r##"<rect x="{x}" y="{rect_y}" width="10" height="10" fill="{color}"/><text x="{text_x}" y="{legend_y}">{name}</text>"##,
+                x = PLOT_WIDTH,
+                rect_y = legend_y - 9.0,
+                color = COLORS[index % COLORS.len()],
+                text_x = PLOT_WIDTH + 14.0,
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
@@ -0,0 +1,139 @@
+//! The official sweetness categories of EU wine labelling (Regulation (EU)
+//! 2019/33, Annex III), which the OIV's follow closely. Still wines are
+//! judged on residual sugar and, near the dry and medium-dry limits, on
+//! titratable acidity too; sparkling wines on residual sugar alone.
+
+/// Residual sugar in g/L a still wine may have and be dry, or medium dry,
+/// whatever its acidity.
+const DRY_SUGAR: f64 = 4.0;
+const MEDIUM_DRY_SUGAR: f64 = 12.0;
+/// Residual sugar in g/L a still wine may have and be dry, or medium dry,
+/// if its acidity is high enough: at least the sugar less the allowance.
+const ACID_DRY_SUGAR: f64 = 9.0;
+const ACID_DRY_ALLOWANCE: f64 = 2.0;
+const ACID_MEDIUM_DRY_SUGAR: f64 = 18.0;
+const ACID_MEDIUM_DRY_ALLOWANCE: f64 = 10.0;
+/// Residual sugar in g/L above which a still wine is sweet.
+const MEDIUM_SUGAR: f64 = 45.0;
+
+/// The category of a still wine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StillCategory {
+    Dry,
+    MediumDry,
+    Medium,
+    Sweet,
+}
+
+impl StillCategory {
+    pub const ALL: [StillCategory; 4] = [
+        StillCategory::Dry,
+        StillCategory::MediumDry,
+        StillCategory::Medium,
+        StillCategory::Sweet,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StillCategory::Dry => "dry",
+            StillCategory::MediumDry => "medium dry",
+            StillCategory::Medium => "medium",
+            StillCategory::Sweet => "sweet",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// The category for `residual_sugar` and `titratable_acidity`, both in
+    /// g/L, the acidity as tartaric acid.
+    pub fn classify(residual_sugar: f64, titratable_acidity: f64) -> Self {
+        let acid_allows = |limit: f64, allowance: f64| {
+            residual_sugar <= limit && titratable_acidity >= residual_sugar - allowance
+        };
+        if residual_sugar <= DRY_SUGAR || acid_allows(ACID_DRY_SUGAR, ACID_DRY_ALLOWANCE) {
+            StillCategory::Dry
+        } else if residual_sugar <= MEDIUM_DRY_SUGAR
+            || acid_allows(ACID_MEDIUM_DRY_SUGAR, ACID_MEDIUM_DRY_ALLOWANCE)
+        {
+            StillCategory::MediumDry
+        } else if residual_sugar <= MEDIUM_SUGAR {
+            StillCategory::Medium
+        } else {
+            StillCategory::Sweet
+        }
+    }
+}
+
+/// The category of a sparkling wine, driest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparklingCategory {
+    BrutNature,
+    ExtraBrut,
+    Brut,
+    ExtraDry,
+    Dry,
+    Demisec,
+    Doux,
+}
+
+impl SparklingCategory {
+    pub const ALL: [SparklingCategory; 7] = [
+        SparklingCategory::BrutNature,
+        SparklingCategory::ExtraBrut,
+        SparklingCategory::Brut,
+        SparklingCategory::ExtraDry,
+        SparklingCategory::Dry,
+        SparklingCategory::Demisec,
+        SparklingCategory::Doux,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SparklingCategory::BrutNature => "brut nature",
+            SparklingCategory::ExtraBrut => "extra brut",
+            SparklingCategory::Brut => "brut",
+            SparklingCategory::ExtraDry => "extra dry",
+            SparklingCategory::Dry => "sec",
+            SparklingCategory::Demisec => "demi-sec",
+            SparklingCategory::Doux => "doux",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// Residual sugar in g/L up to which a wine may carry the category.
+    /// Brut nature and brut stop just short of theirs.
+    fn limit(self) -> f64 {
+        match self {
+            SparklingCategory::BrutNature => 3.0,
+            SparklingCategory::ExtraBrut => 6.0,
+            SparklingCategory::Brut => 12.0,
+            SparklingCategory::ExtraDry => 17.0,
+            SparklingCategory::Dry => 32.0,
+            SparklingCategory::Demisec => 50.0,
+            SparklingCategory::Doux => f64::INFINITY,
+        }
+    }
+
+    /// The driest category `residual_sugar` in g/L allows. The ranges
+    /// overlap, as extra brut's takes in brut nature's.
+    pub fn classify(residual_sugar: f64) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|category| match category {
+                SparklingCategory::BrutNature | SparklingCategory::Brut => {
+                    residual_sugar < category.limit()
+                }
+                _ => residual_sugar <= category.limit(),
+            })
+            .unwrap_or(SparklingCategory::Doux)
+    }
+}
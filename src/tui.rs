@@ -0,0 +1,318 @@
+use std::io;
+
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Axis, Block, Chart, GraphType, Paragraph, Wrap};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    Beverage, CLIMATES, CONTAINERS, GRAPES, SimulationInput, fermentation_curve, run_simulation,
+    seeded,
+};
+use wine_maker::narrative::narrative;
+
+use crate::logging::LogBuffer;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Grape,
+    Days,
+    Container,
+    Climate,
+    Sugar,
+    Temperature,
+    Seed,
+}
+
+impl Field {
+    const ALL: [Field; 7] = [
+        Field::Grape,
+        Field::Days,
+        Field::Container,
+        Field::Climate,
+        Field::Sugar,
+        Field::Temperature,
+        Field::Seed,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Field::Grape => "Grape",
+            Field::Days => "Days",
+            Field::Container => "Container",
+            Field::Climate => "Climate",
+            Field::Sugar => "Sugar (g/L)",
+            Field::Temperature => "Temp (°C)",
+            Field::Seed => "Seed",
+        }
+    }
+
+    fn options(self) -> Option<&'static [&'static str]> {
+        match self {
+            Field::Grape => Some(&GRAPES),
+            Field::Container => Some(&CONTAINERS),
+            Field::Climate => Some(&CLIMATES),
+            _ => None,
+        }
+    }
+}
+
+struct TuiApp {
+    wine_data: Dataset,
+    log_buffer: LogBuffer,
+    focus: usize,
+    grape: usize,
+    container: usize,
+    climate: usize,
+    fermentation_days: String,
+    sugar_content: String,
+    temperature: String,
+    seed: String,
+    result_text: String,
+    abv_curve: Vec<(f64, f64)>,
+    show_log: bool,
+}
+
+impl TuiApp {
+    fn new(wine_data: Dataset, log_buffer: LogBuffer) -> Self {
+        Self {
+            wine_data,
+            log_buffer,
+            focus: 0,
+            grape: 0,
+            container: 0,
+            climate: 1,
+            fermentation_days: "14".to_owned(),
+            sugar_content: "220".to_owned(),
+            temperature: "20".to_owned(),
+            seed: String::new(),
+            result_text: "Press Enter to simulate.".to_owned(),
+            abv_curve: Vec::new(),
+            show_log: false,
+        }
+    }
+
+    fn field(&self) -> Field {
+        Field::ALL[self.focus]
+    }
+
+    fn value(&self, field: Field) -> &str {
+        match field {
+            Field::Grape => GRAPES[self.grape],
+            Field::Container => CONTAINERS[self.container],
+            Field::Climate => CLIMATES[self.climate],
+            Field::Days => &self.fermentation_days,
+            Field::Sugar => &self.sugar_content,
+            Field::Temperature => &self.temperature,
+            Field::Seed => &self.seed,
+        }
+    }
+
+    fn text_mut(&mut self, field: Field) -> Option<&mut String> {
+        match field {
+            Field::Days => Some(&mut self.fermentation_days),
+            Field::Sugar => Some(&mut self.sugar_content),
+            Field::Temperature => Some(&mut self.temperature),
+            Field::Seed => Some(&mut self.seed),
+            _ => None,
+        }
+    }
+
+    fn cycle(&mut self, forward: bool) {
+        let field = self.field();
+        let Some(options) = field.options() else {
+            return;
+        };
+        let index = match field {
+            Field::Grape => &mut self.grape,
+            Field::Container => &mut self.container,
+            _ => &mut self.climate,
+        };
+        *index = if forward {
+            (*index + 1) % options.len()
+        } else {
+            (*index + options.len() - 1) % options.len()
+        };
+    }
+
+    fn simulate(&mut self) {
+        let input = SimulationInput {
+            grape_type: GRAPES[self.grape].to_owned(),
+            fermentation_days: self.fermentation_days.trim().parse().unwrap_or_default(),
+            container_type: CONTAINERS[self.container].to_owned(),
+            sugar_content: self.sugar_content.trim().parse().unwrap_or_default(),
+            temperature: self.temperature.trim().parse().unwrap_or_default(),
+            climate: CLIMATES[self.climate].to_owned(),
+            seed: self.seed.trim().parse().ok(),
+            beverage: Beverage::Wine,
+            ..SimulationInput::default()
+        };
+        let input = seeded(&input);
+
+        self.result_text = match run_simulation(&input, &self.wine_data) {
+            Ok(result) => narrative(&input, &result),
+            Err(err) => err.to_string(),
+        };
+        self.abv_curve = fermentation_curve(&input)
+            .map(|curve| {
+                curve
+                    .iter()
+                    .map(|state| (state.day as f64, state.abv))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    /// Returns `false` once the user asks to quit.
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('l') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.show_log = !self.show_log;
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                self.focus = (self.focus + Field::ALL.len() - 1) % Field::ALL.len();
+            }
+            KeyCode::Down | KeyCode::Tab => self.focus = (self.focus + 1) % Field::ALL.len(),
+            KeyCode::Left => self.cycle(false),
+            KeyCode::Right => self.cycle(true),
+            KeyCode::Enter => self.simulate(),
+            KeyCode::Backspace => {
+                if let Some(text) = self.text_mut(self.field()) {
+                    text.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(text) = self.text_mut(self.field()) {
+                    text.push(c);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [form, output] =
+            Layout::horizontal([Constraint::Length(40), Constraint::Min(0)]).areas(main);
+        let [chart, results] =
+            Layout::vertical([Constraint::Percentage(45), Constraint::Min(0)]).areas(output);
+
+        self.draw_form(frame, form);
+        self.draw_chart(frame, chart);
+
+        if self.show_log {
+            let lines: Vec<Line> = self.log_buffer.lines().into_iter().map(Line::from).collect();
+            let scroll = lines.len().saturating_sub(results.height.saturating_sub(2) as usize);
+            frame.render_widget(
+                Paragraph::new(lines)
+                    .scroll((scroll as u16, 0))
+                    .block(Block::bordered().title("Log")),
+                results,
+            );
+        } else {
+            frame.render_widget(
+                Paragraph::new(self.result_text.as_str())
+                    .wrap(Wrap { trim: true })
+                    .block(Block::bordered().title("Results")),
+                results,
+            );
+        }
+
+        frame.render_widget(
+            Line::from(
+                "↑/↓ move  ←/→ choose  Enter simulate  Ctrl+L log  Esc quit".dark_gray(),
+            ),
+            help,
+        );
+    }
+
+    fn draw_form(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = Field::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, &field)| {
+                let value = match field.options() {
+                    Some(_) => format!("‹ {} ›", self.value(field)),
+                    None => self.value(field).to_owned(),
+                };
+                let line = Line::from(vec![
+                    Span::from(format!("{:<12}", field.label())).bold(),
+                    Span::from(value),
+                ]);
+                if i == self.focus {
+                    line.reversed()
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Wine Fermentation Simulator")),
+            area,
+        );
+    }
+
+    fn draw_chart(&self, frame: &mut Frame, area: Rect) {
+        let max_day = self.abv_curve.last().map_or(1.0, |&(day, _)| day.max(1.0));
+        let max_abv = self
+            .abv_curve
+            .iter()
+            .map(|&(_, abv)| abv)
+            .fold(1.0, f64::max)
+            .ceil();
+
+        let series = ratatui::widgets::Dataset::default()
+            .name("ABV %")
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Magenta))
+            .data(&self.abv_curve);
+
+        let chart = Chart::new(vec![series])
+            .block(Block::bordered().title("Fermentation"))
+            .x_axis(
+                Axis::default()
+                    .title("Day")
+                    .bounds([0.0, max_day])
+                    .labels(["0".to_owned(), format!("{}", max_day)]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("ABV %")
+                    .bounds([0.0, max_abv])
+                    .labels(["0".to_owned(), format!("{:.0}", max_abv)]),
+            );
+
+        frame.render_widget(chart, area);
+    }
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut TuiApp) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.handle_key(key.code, key.modifiers)
+        {
+            return Ok(());
+        }
+    }
+}
+
+pub fn run(wine_data: Dataset, log_buffer: LogBuffer) -> io::Result<()> {
+    let mut app = TuiApp::new(wine_data, log_buffer);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
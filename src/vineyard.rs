@@ -0,0 +1,269 @@
+//! An optional stage ahead of fermentation: the growing season decides what
+//! the grapes come in with, so the must sugar doesn't have to be guessed.
+
+use std::ops::RangeInclusive;
+
+use crate::cellar::Process;
+use crate::engine::{SimulationInput, climate_sugar_factor};
+
+/// Growing degree days (°C, base 10, April to October) bounding Winkler
+/// regions I to IV; the warmest is region V.
+const WINKLER_LIMITS: [f64; 4] = [1390.0, 1670.0, 1940.0, 2220.0];
+
+// Harvest sugar in g/L rises with heat from this much at 1400 GDD.
+const SUGAR_AT_1400_GDD: f64 = 180.0;
+const SUGAR_PER_GDD: f64 = 0.06;
+// Titratable acidity in g/L falls with heat from this much at 1000 GDD.
+const ACIDITY_AT_1000_GDD: f64 = 12.0;
+const ACIDITY_PER_GDD: f64 = 0.004;
+/// Sugar lost to berries swelling per mm of rain in the last weeks before harvest.
+const SUGAR_PER_HARVEST_RAIN: f64 = 0.3;
+/// Season rainfall in mm past which vine vigor delays phenolic ripeness.
+const VIGOR_RAINFALL: f64 = 400.0;
+
+/// Days after véraison the grapes are usually picked.
+pub const TYPICAL_HARVEST_DAY: u32 = 45;
+/// The latest pick the ripening curve goes out to.
+pub const LATEST_HARVEST_DAY: u32 = 84;
+/// Sugar in g/L when the berries soften and color at véraison.
+const VERAISON_SUGAR: f64 = 100.0;
+/// Daily rate at which sugar loading slows towards its plateau.
+const SUGAR_LOADING_RATE: f64 = 0.04;
+/// Daily fraction of the acidity respired as the grapes hang.
+const ACID_RESPIRATION_RATE: f64 = 0.015;
+/// Gain in phenolic ripeness per day of hang time.
+const RIPENESS_PER_DAY: f64 = 0.01;
+
+/// What a growing season was like.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vintage {
+    /// Growing degree days, °C base 10, April to October.
+    pub growing_degree_days: f64,
+    /// Growing-season rainfall in mm.
+    pub rainfall: f64,
+    /// Rainfall in mm in the two weeks before harvest.
+    pub harvest_rain: f64,
+}
+
+/// A wine region, the season it typically has, and how its classic wine
+/// is made.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub name: &'static str,
+    pub typical: Vintage,
+    /// Grapes and their percentage of the classic blend, main grape first.
+    pub blend: &'static [(&'static str, u32)],
+    /// One of `engine::CONTAINERS`.
+    pub container: &'static str,
+    /// Fermentation temperature in °C.
+    pub temperature: f64,
+    pub process: Process,
+    /// What the classic wine of the region is like.
+    pub style: &'static str,
+}
+
+const fn season(growing_degree_days: f64, rainfall: f64, harvest_rain: f64) -> Vintage {
+    Vintage {
+        growing_degree_days,
+        rainfall,
+        harvest_rain,
+    }
+}
+
+pub const REGIONS: [Region; 6] = [
+    Region {
+        name: "Mosel",
+        typical: season(1100.0, 450.0, 30.0),
+        blend: &[("Riesling", 100)],
+        container: "Steel Tank",
+        temperature: 15.0,
+        process: Process {
+            malolactic: false,
+            aging_months: 6,
+        },
+        style: "Light, racy Riesling fermented cool in steel, often with some sweetness left.",
+    },
+    Region {
+        name: "Burgundy",
+        typical: season(1300.0, 420.0, 25.0),
+        blend: &[("Pinot Noir", 100)],
+        container: "Oak Barrel",
+        temperature: 28.0,
+        process: Process {
+            malolactic: true,
+            aging_months: 15,
+        },
+        style: "Pale, perfumed Pinot Noir from a single grape, aged in oak barrels.",
+    },
+    Region {
+        name: "Bordeaux",
+        typical: season(1600.0, 450.0, 30.0),
+        blend: &[
+            ("Merlot", 60),
+            ("Cabernet Sauvignon", 30),
+            ("Cabernet Franc", 10),
+        ],
+        container: "Oak Barrel",
+        temperature: 28.0,
+        process: Process {
+            malolactic: true,
+            aging_months: 18,
+        },
+        style: "A firm, structured blend of Merlot and the Cabernets, aged in new oak.",
+    },
+    Region {
+        name: "Rioja",
+        typical: season(1750.0, 250.0, 15.0),
+        blend: &[("Tempranillo", 80), ("Garnacha", 15), ("Graciano", 5)],
+        container: "Oak Barrel",
+        temperature: 27.0,
+        process: Process {
+            malolactic: true,
+            aging_months: 24,
+        },
+        style: "Tempranillo-led reds given long oak aging before release.",
+    },
+    Region {
+        name: "Napa Valley",
+        typical: season(1900.0, 150.0, 5.0),
+        blend: &[("Cabernet Sauvignon", 85), ("Merlot", 15)],
+        container: "Oak Barrel",
+        temperature: 29.0,
+        process: Process {
+            malolactic: true,
+            aging_months: 20,
+        },
+        style: "Ripe, full-bodied Cabernet Sauvignon with plenty of new oak.",
+    },
+    Region {
+        name: "Barossa Valley",
+        typical: season(2100.0, 200.0, 5.0),
+        blend: &[("Shiraz", 70), ("Grenache", 20), ("Mourvèdre", 10)],
+        container: "Oak Barrel",
+        temperature: 28.0,
+        process: Process {
+            malolactic: true,
+            aging_months: 18,
+        },
+        style: "Rich, high-alcohol Shiraz, on its own or blended with Grenache and Mourvèdre.",
+    },
+];
+
+impl Region {
+    /// The grape the simulation is run with.
+    pub fn main_grape(&self) -> &'static str {
+        self.blend[0].0
+    }
+
+    /// Describes the blend, like "Merlot 60%, Cabernet Sauvignon 30%".
+    pub fn describe_blend(&self) -> String {
+        self.blend
+            .iter()
+            .map(|(grape, percent)| format!("{} {}%", grape, percent))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Sets up `input` for the region's classic wine from a typical vintage.
+    pub fn apply_to(&self, input: &mut SimulationInput) {
+        input.grape_type = self.main_grape().to_owned();
+        input.container_type = self.container.to_owned();
+        input.aging_container = String::new();
+        input.aging_months = self.process.aging_months;
+        input.temperature = self.temperature;
+        self.typical.harvest().apply_to(input);
+    }
+}
+
+/// What to expect of the grapes at harvest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Harvest {
+    /// Sugar in g/L.
+    pub sugar: RangeInclusive<f64>,
+    /// Titratable acidity in g/L.
+    pub acidity: RangeInclusive<f64>,
+    /// How ripe the skins and seeds are, from 0 (green) to 1 (fully ripe).
+    pub phenolic_ripeness: RangeInclusive<f64>,
+    /// One of `engine::CLIMATES`.
+    pub climate: &'static str,
+}
+
+fn spread(mid: f64, fraction: f64) -> RangeInclusive<f64> {
+    mid * (1.0 - fraction)..=mid * (1.0 + fraction)
+}
+
+fn middle(range: &RangeInclusive<f64>) -> f64 {
+    (range.start() + range.end()) / 2.0
+}
+
+impl Vintage {
+    /// Winkler region from I (coolest) to V (warmest).
+    pub fn winkler_region(&self) -> u8 {
+        1 + WINKLER_LIMITS
+            .iter()
+            .filter(|&&limit| self.growing_degree_days >= limit)
+            .count() as u8
+    }
+
+    /// What the grapes come in with when picked on the usual day.
+    pub fn harvest(&self) -> Harvest {
+        self.harvest_on(TYPICAL_HARVEST_DAY)
+    }
+
+    /// What the grapes come in with when picked `day` days after véraison.
+    /// Earlier picks have less sugar and more acid, later ones the opposite.
+    pub fn harvest_on(&self, day: u32) -> Harvest {
+        let gdd = self.growing_degree_days;
+        let harvest_rain = self.harvest_rain.max(0.0);
+        let sugar = (SUGAR_AT_1400_GDD + SUGAR_PER_GDD * (gdd - 1400.0)
+            - SUGAR_PER_HARVEST_RAIN * harvest_rain)
+            .clamp(140.0, 280.0);
+        let acidity = (ACIDITY_AT_1000_GDD - ACIDITY_PER_GDD * (gdd - 1000.0)).clamp(4.5, 14.0);
+        let ripeness = ((gdd - 1000.0) / 1200.0
+            - (self.rainfall - VIGOR_RAINFALL).max(0.0) / 1000.0)
+            .clamp(0.0, 1.0);
+
+        let loaded = |day: u32| 1.0 - (-SUGAR_LOADING_RATE * f64::from(day)).exp();
+        let sugar =
+            VERAISON_SUGAR + (sugar - VERAISON_SUGAR) * loaded(day) / loaded(TYPICAL_HARVEST_DAY);
+        let hang_time = f64::from(day) - f64::from(TYPICAL_HARVEST_DAY);
+        let acidity = acidity * (-ACID_RESPIRATION_RATE * hang_time).exp();
+        let ripeness = (ripeness + RIPENESS_PER_DAY * hang_time).clamp(0.0, 1.0);
+        let climate = match self.winkler_region() {
+            1 => "Cool",
+            2 | 3 => "Moderate",
+            _ => "Warm",
+        };
+        Harvest {
+            sugar: spread(sugar, 0.08),
+            acidity: spread(acidity, 0.10),
+            phenolic_ripeness: (ripeness - 0.1).max(0.0)..=(ripeness + 0.1).min(1.0),
+            climate,
+        }
+    }
+
+    /// The harvest for a pick each week from véraison to `LATEST_HARVEST_DAY`.
+    pub fn ripening_curve(&self) -> Vec<(u32, Harvest)> {
+        (0..=LATEST_HARVEST_DAY)
+            .step_by(7)
+            .map(|day| (day, self.harvest_on(day)))
+            .collect()
+    }
+}
+
+impl Harvest {
+    pub fn expected_sugar(&self) -> f64 {
+        middle(&self.sugar)
+    }
+
+    pub fn expected_acidity(&self) -> f64 {
+        middle(&self.acidity)
+    }
+
+    /// Sets the climate and must sugar of `input` so the simulation starts
+    /// from the expected harvest sugar.
+    pub fn apply_to(&self, input: &mut SimulationInput) {
+        input.climate = self.climate.to_owned();
+        input.sugar_content = self.expected_sugar() / climate_sugar_factor(self.climate);
+    }
+}
@@ -0,0 +1,278 @@
+//! Climate normals for a typed location, from the Open-Meteo geocoding and
+//! historical weather APIs, mapped onto a `vineyard::Vintage`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{Datelike, Local, NaiveDate};
+use serde_json::Value;
+
+use crate::vineyard::Vintage;
+
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+const ARCHIVE_URL: &str = "https://archive-api.open-meteo.com/v1/archive";
+/// Past seasons averaged into the normals.
+pub const NORMAL_YEARS: i32 = 10;
+/// Base temperature in °C for growing degree days.
+const GDD_BASE: f64 = 10.0;
+/// Days a growing season needs on record to count towards the normals.
+const MIN_SEASON_DAYS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub country: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Place {
+    /// Vintages south of the equator grow from October to April.
+    pub fn is_southern(&self) -> bool {
+        self.latitude < 0.0
+    }
+}
+
+/// One day of weather history, temperatures in °C and precipitation in mm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyWeather {
+    pub date: NaiveDate,
+    pub max_temperature: f64,
+    pub min_temperature: f64,
+    pub precipitation: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClimateNormals {
+    pub place: Place,
+    /// How many complete seasons were averaged.
+    pub seasons: usize,
+    pub vintage: Vintage,
+}
+
+impl ClimateNormals {
+    /// One of `engine::CLIMATES`.
+    pub fn climate(&self) -> &'static str {
+        self.vintage.harvest().climate
+    }
+}
+
+#[derive(Debug)]
+pub enum WeatherError {
+    Http(String),
+    Json(serde_json::Error),
+    UnknownPlace(String),
+    MissingField(&'static str),
+    NoSeasons,
+}
+
+impl fmt::Display for WeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeatherError::Http(err) => write!(f, "{}", err),
+            WeatherError::Json(err) => write!(f, "{}", err),
+            WeatherError::UnknownPlace(name) => write!(f, "no place called {:?} was found", name),
+            WeatherError::MissingField(field) => write!(f, "the response has no {} field", field),
+            WeatherError::NoSeasons => write!(f, "no complete growing seasons on record"),
+        }
+    }
+}
+
+impl std::error::Error for WeatherError {}
+
+impl From<serde_json::Error> for WeatherError {
+    fn from(err: serde_json::Error) -> Self {
+        WeatherError::Json(err)
+    }
+}
+
+/// The vintage year a day's weather counts towards, if it falls in the
+/// growing season (April to October, or October to April in the south).
+fn season_of(date: NaiveDate, southern: bool) -> Option<i32> {
+    match (southern, date.month()) {
+        (false, 4..=10) => Some(date.year()),
+        (true, 10..=12) => Some(date.year() + 1),
+        (true, 1..=4) => Some(date.year()),
+        _ => None,
+    }
+}
+
+/// Whether a day falls in the last two weeks before the usual harvest, at
+/// the end of September or of March in the south.
+fn before_harvest(date: NaiveDate, southern: bool) -> bool {
+    let (month, first_day) = if southern { (3, 18) } else { (9, 17) };
+    date.month() == month && date.day() >= first_day
+}
+
+/// Averages the complete growing seasons in `daily` into a typical vintage.
+pub fn growing_season_normals(daily: &[DailyWeather], southern: bool) -> Option<(usize, Vintage)> {
+    let mut seasons: BTreeMap<i32, (usize, Vintage)> = BTreeMap::new();
+    for day in daily {
+        let Some(year) = season_of(day.date, southern) else {
+            continue;
+        };
+        let (days, season) = seasons.entry(year).or_insert((
+            0,
+            Vintage {
+                growing_degree_days: 0.0,
+                rainfall: 0.0,
+                harvest_rain: 0.0,
+            },
+        ));
+        *days += 1;
+        let mean = (day.max_temperature + day.min_temperature) / 2.0;
+        season.growing_degree_days += (mean - GDD_BASE).max(0.0);
+        season.rainfall += day.precipitation;
+        if before_harvest(day.date, southern) {
+            season.harvest_rain += day.precipitation;
+        }
+    }
+
+    let complete: Vec<Vintage> = seasons
+        .into_values()
+        .filter(|(days, _)| *days >= MIN_SEASON_DAYS)
+        .map(|(_, season)| season)
+        .collect();
+    if complete.is_empty() {
+        return None;
+    }
+    let count = complete.len() as f64;
+    let average = |field: fn(&Vintage) -> f64| complete.iter().map(field).sum::<f64>() / count;
+    Some((
+        complete.len(),
+        Vintage {
+            growing_degree_days: average(|season| season.growing_degree_days),
+            rainfall: average(|season| season.rainfall),
+            harvest_rain: average(|season| season.harvest_rain),
+        },
+    ))
+}
+
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn parse_place(text: &str, location: &str) -> Result<Place, WeatherError> {
+    let value: Value = serde_json::from_str(text)?;
+    let result = value
+        .get("results")
+        .and_then(Value::as_array)
+        .and_then(|results| results.first())
+        .ok_or_else(|| WeatherError::UnknownPlace(location.to_owned()))?;
+    let coordinate = |field| {
+        result
+            .get(field)
+            .and_then(Value::as_f64)
+            .ok_or(WeatherError::MissingField(field))
+    };
+    let label = |field| {
+        result
+            .get(field)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    };
+    Ok(Place {
+        name: label("name").to_owned(),
+        country: label("country").to_owned(),
+        latitude: coordinate("latitude")?,
+        longitude: coordinate("longitude")?,
+    })
+}
+
+/// Days with a missing value are left out.
+fn parse_daily(text: &str) -> Result<Vec<DailyWeather>, WeatherError> {
+    let value: Value = serde_json::from_str(text)?;
+    let daily = value
+        .get("daily")
+        .ok_or(WeatherError::MissingField("daily"))?;
+    let column = |field| {
+        daily
+            .get(field)
+            .and_then(Value::as_array)
+            .ok_or(WeatherError::MissingField(field))
+    };
+    let (dates, max, min, precipitation) = (
+        column("time")?,
+        column("temperature_2m_max")?,
+        column("temperature_2m_min")?,
+        column("precipitation_sum")?,
+    );
+    Ok(dates
+        .iter()
+        .zip(max)
+        .zip(min)
+        .zip(precipitation)
+        .filter_map(|(((date, max), min), precipitation)| {
+            Some(DailyWeather {
+                date: NaiveDate::parse_from_str(date.as_str()?, "%Y-%m-%d").ok()?,
+                max_temperature: max.as_f64()?,
+                min_temperature: min.as_f64()?,
+                precipitation: precipitation.as_f64()?,
+            })
+        })
+        .collect())
+}
+
+fn fetch_text(url: String, on_done: impl 'static + Send + FnOnce(Result<String, WeatherError>)) {
+    ehttp::fetch(ehttp::Request::get(url), move |response| {
+        on_done(
+            response
+                .and_then(|response| {
+                    if !response.ok {
+                        return Err(format!("{} {}", response.status, response.status_text));
+                    }
+                    response
+                        .text()
+                        .map(str::to_owned)
+                        .ok_or_else(|| "the response is not UTF-8 text".to_owned())
+                })
+                .map_err(WeatherError::Http),
+        )
+    });
+}
+
+/// Looks up `location` and averages its last `NORMAL_YEARS` seasons.
+/// `on_done` is called from a background thread, or later on the web.
+pub fn fetch_normals(
+    location: &str,
+    on_done: impl 'static + Send + FnOnce(Result<ClimateNormals, WeatherError>),
+) {
+    let location = location.trim().to_owned();
+    tracing::info!(location, "looking up climate normals");
+    let url = format!("{}?count=1&name={}", GEOCODING_URL, encode_query(&location));
+    fetch_text(url, move |text| {
+        let place = match text.and_then(|text| parse_place(&text, &location)) {
+            Ok(place) => place,
+            Err(err) => return on_done(Err(err)),
+        };
+        let this_year = Local::now().date_naive().year();
+        let url = format!(
+            "{}?latitude={}&longitude={}&start_date={}-01-01&end_date={}-12-31\
+             &daily=temperature_2m_max,temperature_2m_min,precipitation_sum&timezone=auto",
+            ARCHIVE_URL,
+            place.latitude,
+            place.longitude,
+            this_year - NORMAL_YEARS,
+            this_year - 1,
+        );
+        fetch_text(url, move |text| {
+            on_done(text.and_then(|text| parse_daily(&text)).and_then(|daily| {
+                let (seasons, vintage) = growing_season_normals(&daily, place.is_southern())
+                    .ok_or(WeatherError::NoSeasons)?;
+                Ok(ClimateNormals {
+                    place,
+                    seasons,
+                    vintage,
+                })
+            }))
+        });
+    });
+}
@@ -0,0 +1,73 @@
+use std::sync::mpsc::{self, Receiver};
+
+use eframe::egui;
+use eframe::wasm_bindgen::JsCast as _;
+use wine_maker::dataset::Dataset;
+use wine_maker::permalink::URL_KEY;
+
+use crate::gui::WineFermentationApp;
+use crate::logging;
+
+const DATASET_URL: &str = "WineDataset.csv";
+
+pub fn fetch_dataset(ctx: egui::Context, url: &str) -> Receiver<Result<Dataset, String>> {
+    let (sender, receiver) = mpsc::channel();
+    tracing::info!(url, "fetching dataset");
+    ehttp::fetch(ehttp::Request::get(url), move |response| {
+        let dataset = response
+            .and_then(|response| {
+                if !response.ok {
+                    return Err(format!("{} {}", response.status, response.status_text));
+                }
+                response
+                    .text()
+                    .map(str::to_owned)
+                    .ok_or_else(|| "dataset is not UTF-8 text".to_owned())
+            })
+            .and_then(|text| Dataset::from_csv_str(&text).map_err(|err| err.to_string()));
+        let _ = sender.send(dataset);
+        ctx.request_repaint();
+    });
+    receiver
+}
+
+pub fn start() {
+    let log_buffer = logging::init(false, false);
+    let web_options = eframe::WebOptions::default();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let canvas = web_sys::window()
+            .expect("No window")
+            .document()
+            .expect("No document")
+            .get_element_by_id("the_canvas_id")
+            .expect("Failed to find the_canvas_id")
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .expect("the_canvas_id was not a HtmlCanvasElement");
+
+        let start_result = eframe::WebRunner::new()
+            .start(
+                canvas,
+                web_options,
+                Box::new(move |creation_context| {
+                    let mut app = WineFermentationApp::new(Dataset::default(), log_buffer);
+                    let location = &creation_context.integration_info.web_info.location;
+                    let page = location.url.split('#').next().unwrap_or_default();
+                    app.set_share_link_base(page.to_owned());
+                    if let Some(code) = location.hash.strip_prefix(&format!("#{}=", URL_KEY)) {
+                        app.open_shared(code);
+                    }
+                    app.load_dataset_from(fetch_dataset(
+                        creation_context.egui_ctx.clone(),
+                        DATASET_URL,
+                    ));
+                    Ok(Box::new(app))
+                }),
+            )
+            .await;
+
+        if let Err(err) = start_result {
+            tracing::error!("Failed to start eframe: {:?}", err);
+        }
+    });
+}
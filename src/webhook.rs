@@ -0,0 +1,243 @@
+//! Results sent off to be gathered with other people's, as for a club's
+//! shared record of everyone's wines. A simulation spreadsheet or a batch's
+//! readings is POSTed as JSON to any webhook, or appended as rows to a
+//! Google Sheet through the Sheets API.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::cellar::{Batch, Measurement};
+use crate::export::{COLUMNS, Cell, Spreadsheet};
+
+const SHEETS_URL: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+
+pub const BATCH_LOG_COLUMNS: [&str; 7] = [
+    "Batch",
+    "Date",
+    "Day",
+    "Specific gravity",
+    "Residual sugar (g/L)",
+    "Temperature (°C)",
+    "Notes",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WebhookTarget {
+    /// Any URL taking a JSON POST, such as an Apps Script or Zapier hook.
+    Webhook { url: String },
+    /// Rows appended to one sheet of a Google spreadsheet.
+    GoogleSheets {
+        /// The long ID in the spreadsheet's URL.
+        spreadsheet_id: String,
+        /// The sheet's tab name, such as `Sheet1`.
+        sheet: String,
+        /// An OAuth access token with the spreadsheets scope. Google's
+        /// tokens last an hour.
+        access_token: String,
+    },
+}
+
+/// Where results go, kept with the app's settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub target: Option<WebhookTarget>,
+    /// Whether each simulation is sent as it is run.
+    pub send_each_run: bool,
+}
+
+#[derive(Debug)]
+pub enum WebhookError {
+    /// The target leaves this setting blank.
+    MissingSetting(&'static str),
+    Http(String),
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::MissingSetting(setting) => write!(f, "no {} is set", setting),
+            WebhookError::Http(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+/// A table to send: a header, rows under it, and what they hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Upload {
+    /// `wines` or `batch_log`, so one webhook can take both.
+    pub kind: &'static str,
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Upload {
+    /// The wines in `sheet`, under the spreadsheet export's columns.
+    pub fn wines(sheet: &Spreadsheet) -> Self {
+        Upload {
+            kind: "wines",
+            columns: COLUMNS.to_vec(),
+            rows: sheet.rows().to_vec(),
+        }
+    }
+
+    /// A batch's readings, one row each.
+    pub fn batch_log(batch: &Batch, measurements: &[Measurement]) -> Self {
+        Upload {
+            kind: "batch_log",
+            columns: BATCH_LOG_COLUMNS.to_vec(),
+            rows: measurements
+                .iter()
+                .map(|measurement| {
+                    vec![
+                        batch.name.as_str().into(),
+                        measurement.measured_on.to_string().into(),
+                        (batch.day_of(measurement.measured_on) as f64).into(),
+                        measurement.specific_gravity.into(),
+                        measurement.sugar_content.into(),
+                        measurement.temperature.into(),
+                        measurement.notes.as_str().into(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    /// The webhook body: the kind, and each row as an object keyed by
+    /// column, with empty cells left out.
+    pub fn to_json(&self) -> Value {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .filter(|(_, cell)| **cell != Cell::Empty)
+                    .map(|(column, cell)| (column.to_string(), cell_value(cell)))
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        json!({
+            "source": "wine-maker",
+            "kind": self.kind,
+            "rows": rows,
+        })
+    }
+
+    /// The Sheets API body: the rows without the header, with empty cells
+    /// as blank strings so columns stay lined up.
+    pub fn to_sheet_values(&self) -> Value {
+        let values: Vec<Vec<Value>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Cell::Empty => json!(""),
+                        cell => cell_value(cell),
+                    })
+                    .collect()
+            })
+            .collect();
+        json!({ "values": values })
+    }
+
+    pub fn request(&self, target: &WebhookTarget) -> Result<ehttp::Request, WebhookError> {
+        let (url, body, token) = match target {
+            WebhookTarget::Webhook { url } => {
+                let url = url.trim();
+                if url.is_empty() {
+                    return Err(WebhookError::MissingSetting("webhook URL"));
+                }
+                (url.to_owned(), self.to_json(), None)
+            }
+            WebhookTarget::GoogleSheets {
+                spreadsheet_id,
+                sheet,
+                access_token,
+            } => {
+                let (spreadsheet_id, sheet, access_token) =
+                    (spreadsheet_id.trim(), sheet.trim(), access_token.trim());
+                if spreadsheet_id.is_empty() {
+                    return Err(WebhookError::MissingSetting("spreadsheet ID"));
+                }
+                if access_token.is_empty() {
+                    return Err(WebhookError::MissingSetting("access token"));
+                }
+                let url = format!(
+                    "{}/{}/values/{}:append?valueInputOption=RAW&insertDataOption=INSERT_ROWS",
+                    SHEETS_URL,
+                    encode(spreadsheet_id),
+                    encode(if sheet.is_empty() { "Sheet1" } else { sheet }),
+                );
+                (url, self.to_sheet_values(), Some(access_token))
+            }
+        };
+        let mut request = ehttp::Request::post(url, body.to_string().into_bytes());
+        request.headers.insert("Content-Type", "application/json");
+        if let Some(token) = token {
+            request
+                .headers
+                .insert("Authorization", format!("Bearer {}", token));
+        }
+        Ok(request)
+    }
+}
+
+fn cell_value(cell: &Cell) -> Value {
+    match cell {
+        Cell::Empty => Value::Null,
+        Cell::Text(text) => json!(text),
+        Cell::Number(number) => json!(number),
+    }
+}
+
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Sends `upload` to `target`. `on_done` is called from a background
+/// thread, or later on the web.
+pub fn send(
+    target: &WebhookTarget,
+    upload: &Upload,
+    on_done: impl 'static + Send + FnOnce(Result<(), WebhookError>),
+) {
+    let request = match upload.request(target) {
+        Ok(request) => request,
+        Err(err) => return on_done(Err(err)),
+    };
+    tracing::info!(
+        kind = upload.kind,
+        rows = upload.rows.len(),
+        "sending results"
+    );
+    ehttp::fetch(request, move |response| {
+        on_done(
+            response
+                .and_then(|response| {
+                    if response.ok {
+                        Ok(())
+                    } else {
+                        Err(format!("{} {}", response.status, response.status_text))
+                    }
+                })
+                .map_err(WebhookError::Http),
+        )
+    });
+}
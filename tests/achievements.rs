@@ -0,0 +1,66 @@
+use wine_maker::achievements::{Achievement, earned};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{SUGAR_PER_ABV, SimulationInput, climate_sugar_factor, run_simulation};
+
+fn zinfandel(abv: f64) -> SimulationInput {
+    SimulationInput {
+        grape_type: "Zinfandel".to_owned(),
+        fermentation_days: 45,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content: abv * SUGAR_PER_ABV / climate_sugar_factor("Warm"),
+        temperature: 24.0,
+        climate: "Warm".to_owned(),
+        seed: Some(5),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn wines_earn_achievements() {
+    let data = Dataset::default();
+    // Past the yeast's tolerance it takes a restart to get there.
+    let monster = SimulationInput {
+        restart_day: Some(10),
+        ..zinfandel(16.0)
+    };
+    let result = run_simulation(&monster, &data).unwrap();
+    let achievements = earned(&monster, &result, 1);
+    assert!(achievements.contains(&Achievement::FirstWine));
+    assert!(
+        achievements.contains(&Achievement::MonsterZin),
+        "{} % ABV",
+        result.actual_abv
+    );
+    assert!(achievements.contains(&Achievement::SurvivedStuckFerment));
+    assert!(!achievements.contains(&Achievement::Mead));
+
+    let modest = zinfandel(12.0);
+    let result = run_simulation(&modest, &data).unwrap();
+    assert!(!earned(&modest, &result, 1).contains(&Achievement::MonsterZin));
+}
+
+#[test]
+fn milestones_count_the_wines_made() {
+    let input = zinfandel(12.0);
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    let milestones = |made| {
+        earned(&input, &result, made)
+            .into_iter()
+            .filter(|achievement| achievement.wines_needed().is_some())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(milestones(9), []);
+    assert_eq!(milestones(10), [Achievement::TenWines]);
+    assert_eq!(
+        milestones(120),
+        [
+            Achievement::TenWines,
+            Achievement::FiftyWines,
+            Achievement::HundredWines
+        ]
+    );
+    assert_eq!(
+        Achievement::from_label("survived a stuck ferment"),
+        Some(Achievement::SurvivedStuckFerment)
+    );
+}
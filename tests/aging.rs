@@ -0,0 +1,199 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::degassing::{
+    co2_at_pressing, dissolved_co2_after, needs_degassing, spritz_threshold,
+};
+use wine_maker::engine::{
+    CONTAINER_CATALOG, CONTAINERS, Closure, Material, SimulationInput, bottle_aging, container,
+    lees_contact, oxygen_ingress, peak_years, reduction_risk, run_simulation,
+};
+use wine_maker::narrative::narrative;
+
+fn steel_ferment() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Chardonnay".to_owned(),
+        fermentation_days: 14,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content: 220.0,
+        temperature: 16.0,
+        climate: "Moderate".to_owned(),
+        seed: Some(7),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn unaged_steel_wine_stays_protected() {
+    let input = steel_ferment();
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert_eq!(result.aging_note, "");
+    assert!(result.oxygen_uptake < 1.0);
+    assert!(result.oxygen_exposure.starts_with("protected"));
+    assert!(!narrative(&input, &result).contains("aged for"));
+}
+
+#[test]
+fn each_stage_adds_its_own_vessel() {
+    let input = SimulationInput {
+        aging_container: "Oak Barrel".to_owned(),
+        aging_months: 12,
+        ..steel_ferment()
+    };
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert_eq!(result.container_note, "a pristine, clean character");
+    assert_eq!(result.aging_note, "woody, oaky undertones");
+    assert!(result.oxygen_uptake > 25.0);
+    assert!(result.oxygen_exposure.contains("nutty"));
+    assert!(narrative(&input, &result).contains("aged for 12 months in a Oak Barrel"));
+
+    // Left empty, the wine ages where it fermented.
+    let in_steel = SimulationInput {
+        aging_container: String::new(),
+        ..input
+    };
+    let result = run_simulation(&in_steel, &Dataset::default()).unwrap();
+    assert_eq!(result.aging_note, "a pristine, clean character");
+    assert!(result.oxygen_uptake < 3.0);
+}
+
+#[test]
+fn catalog_names_every_vessel() {
+    assert_eq!(CONTAINERS.len(), CONTAINER_CATALOG.len());
+    for name in CONTAINERS {
+        assert_eq!(container(name).unwrap().name, name);
+    }
+    let qvevri = container("qvevri").unwrap();
+    assert_eq!(qvevri.material, Material::Clay);
+    assert!(qvevri.oxygen_transfer < container("Clay Amphora").unwrap().oxygen_transfer);
+    assert!(oxygen_ingress("Glass Carboy") < oxygen_ingress("Barrique"));
+    assert!(container("Bathtub").is_none());
+}
+
+#[test]
+fn catalog_vessels_color_the_wine() {
+    let input = SimulationInput {
+        aging_container: "Concrete Egg".to_owned(),
+        aging_months: 6,
+        ..steel_ferment()
+    };
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert_eq!(result.aging_note, "a round, textured mouthfeel");
+    assert!((result.oxygen_uptake - (14.0 / 365.0 + 4.0)).abs() < 1e-9);
+}
+
+#[test]
+fn stirred_lees_add_texture_and_soften_the_acidity() {
+    let data = Dataset::default();
+    let plain = run_simulation(&steel_ferment(), &data).unwrap();
+    assert_eq!(plain.lees_note, "");
+
+    let resting = SimulationInput {
+        lees_months: 4,
+        ..steel_ferment()
+    };
+    let result = run_simulation(&resting, &data).unwrap();
+    assert!(result.lees_note.contains("brioche"));
+    assert_eq!(result.acidity, plain.acidity);
+    assert!(narrative(&resting, &result).contains("undisturbed on its lees for 4 months"));
+
+    let stirred = SimulationInput {
+        stirs_per_month: 4,
+        ..resting.clone()
+    };
+    assert!(lees_contact(&stirred) > lees_contact(&resting));
+    let result = run_simulation(&stirred, &data).unwrap();
+    assert_eq!(result.acidity, "soft");
+    assert!(narrative(&stirred, &result).contains("stirred 4 times a month"));
+
+    // Reds aren't aged sur lie.
+    let red = SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        ..stirred
+    };
+    assert_eq!(lees_contact(&red), 0.0);
+    assert_eq!(run_simulation(&red, &data).unwrap().lees_note, "");
+}
+
+#[test]
+fn closures_set_the_pace_in_bottle() {
+    let sealed = |closure| SimulationInput {
+        closure,
+        ..steel_ferment()
+    };
+    let cork = sealed(Closure::NaturalCork);
+    let diam = sealed(Closure::Diam);
+    let screwcap = sealed(Closure::Screwcap);
+    assert!(peak_years(&cork) < peak_years(&diam));
+    assert!(peak_years(&diam) < peak_years(&screwcap));
+
+    // Kept from oxygen in steel, the wine closes up under a screwcap.
+    assert!(!reduction_risk(&cork));
+    assert!(reduction_risk(&screwcap));
+    let projection = bottle_aging(&screwcap, 10);
+    assert_eq!(projection.len(), 11);
+    assert!(projection[1].stage.contains("reductive"));
+    assert!(!projection[2].stage.contains("reductive"));
+    let result = run_simulation(&screwcap, &Dataset::default()).unwrap();
+    assert!(result.reduction_risk);
+    assert!(narrative(&screwcap, &result).contains("reductive for the first year"));
+
+    // Cork lets the wine develop, and eventually fade, sooner.
+    let cork_projection = bottle_aging(&cork, 10);
+    assert!(cork_projection[5].oxygen > projection[5].oxygen);
+    assert_eq!(cork_projection[5].stage, "past its best and oxidized");
+    assert_eq!(projection[5].stage, "at its peak, with tertiary notes");
+
+    // Time in oak uses up some of what the wine could take in bottle.
+    let oaked = SimulationInput {
+        aging_container: "Barrique".to_owned(),
+        aging_months: 12,
+        ..screwcap
+    };
+    assert!(!reduction_risk(&oaked));
+    assert!(peak_years(&oaked) < peak_years(&sealed(Closure::Screwcap)));
+}
+
+#[test]
+fn young_wine_needs_degassing_until_aging_lets_the_co2_out() {
+    let young = steel_ferment();
+    assert!(needs_degassing(&young));
+    let result = run_simulation(&young, &Dataset::default()).unwrap();
+    assert!(result.needs_degassing);
+    assert!(result.dissolved_co2 > spritz_threshold(&young));
+    assert!(narrative(&young, &result).contains("spritzy unless degassed"));
+
+    // Colder ferments hold more.
+    let warm = SimulationInput {
+        temperature: 24.0,
+        ..young.clone()
+    };
+    assert!(co2_at_pressing(&warm) < co2_at_pressing(&young));
+
+    let barrel = SimulationInput {
+        aging_container: "Oak Barrel".to_owned(),
+        aging_months: 12,
+        ..young.clone()
+    };
+    let co2: Vec<f64> = (0..=12)
+        .map(|months| dissolved_co2_after(&barrel, months))
+        .collect();
+    assert!(co2.windows(2).all(|w| w[1] < w[0]));
+    // Each racking knocks out more than a month's slow loss.
+    assert!(co2[2] - co2[3] > co2[1] - co2[2]);
+    assert!(!needs_degassing(&barrel));
+    let result = run_simulation(&barrel, &Dataset::default()).unwrap();
+    assert!(!narrative(&barrel, &result).contains("degassed"));
+
+    // Steel holds on to it longer than oak, and stirring the lees helps.
+    let steel = SimulationInput {
+        aging_container: String::new(),
+        aging_months: 2,
+        ..young.clone()
+    };
+    let stirred = SimulationInput {
+        lees_months: 2,
+        stirs_per_month: 4,
+        ..steel.clone()
+    };
+    assert!(dissolved_co2_after(&steel, 2) > dissolved_co2_after(&barrel, 2));
+    assert!(dissolved_co2_after(&stirred, 2) < dissolved_co2_after(&steel, 2));
+}
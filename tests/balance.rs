@@ -0,0 +1,43 @@
+use wine_maker::balance::{BalanceFault, balance_faults};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Beverage, run_simulation};
+use wine_maker::optimizer::quality;
+
+#[test]
+fn flags_each_fault() {
+    let wine = Beverage::Wine;
+    assert_eq!(
+        balance_faults(wine, 15.5, 2.0, 2.0, 6.0, 15.6),
+        [BalanceFault::Hot]
+    );
+    // Sugar or body covers the alcohol.
+    assert!(balance_faults(wine, 15.5, 30.0, 30.0, 6.5, 17.0).is_empty());
+    assert!(balance_faults(wine, 15.5, 2.0, 2.0, 6.5, 16.8).is_empty());
+    assert_eq!(
+        balance_faults(wine, 12.0, 30.0, 34.0, 5.0, 13.5),
+        [BalanceFault::Flabby]
+    );
+    assert_eq!(
+        balance_faults(wine, 9.5, 2.0, 0.0, 9.5, 9.6),
+        [BalanceFault::Austere]
+    );
+    assert!(balance_faults(wine, 12.5, 2.0, 2.0, 6.5, 12.6).is_empty());
+    for fault in BalanceFault::ALL {
+        assert_eq!(BalanceFault::from_label(fault.label()), Some(fault));
+    }
+}
+
+#[test]
+fn faults_cost_quality_points() {
+    let mut input = Beverage::Wine.defaults();
+    input.grape_type = "Zinfandel".to_owned();
+    input.climate = "Warm".to_owned();
+    input.sugar_content = 300.0;
+    input.fermentation_days = 30;
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert!(!result.balance_faults.is_empty());
+    let factors = quality(&input, &result).factors;
+    for fault in &result.balance_faults {
+        assert!(factors.iter().any(|(factor, _)| *factor == fault.label()));
+    }
+}
@@ -0,0 +1,57 @@
+use wine_maker::benchmark::{Axis, BENCHMARKS, Profile, benchmark, rank};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Inoculation, SimulationInput, climate_sugar_factor, run_simulation};
+
+fn must(grape: &str, climate: &str, sugar: f64, temperature: f64) -> SimulationInput {
+    SimulationInput {
+        grape_type: grape.to_owned(),
+        fermentation_days: 21,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content: sugar / climate_sugar_factor(climate),
+        temperature,
+        climate: climate.to_owned(),
+        seed: Some(1),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn a_wine_made_the_classic_way_is_closest_to_its_classic() {
+    let data = Dataset::default();
+    let napa = SimulationInput {
+        aging_container: "Barrique".to_owned(),
+        aging_months: 18,
+        inoculation: Inoculation::SequentialMalolactic,
+        ..must("Cabernet Sauvignon", "Warm", 245.0, 28.0)
+    };
+    let ranked = rank(&run_simulation(&napa, &data).unwrap());
+    assert_eq!(ranked.len(), BENCHMARKS.len());
+    assert_eq!(ranked[0].0.name, "Napa Valley Cabernet Sauvignon");
+    assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+    // Stopped early, cool and sweet.
+    let kabinett = SimulationInput {
+        fermentation_days: 7,
+        ..must("Riesling", "Cool", 190.0, 14.0)
+    };
+    let ranked = rank(&run_simulation(&kabinett, &data).unwrap());
+    assert_eq!(ranked[0].0.name, "Mosel Riesling Kabinett");
+}
+
+#[test]
+fn profiles_are_scaled_and_compared() {
+    let profile = Profile::new(10.5, 50.0, 0.85, 0.0, 100.0, 8.0);
+    assert_eq!(profile.get(Axis::Alcohol), 0.5);
+    assert_eq!(profile.get(Axis::Sweetness), 0.5);
+    // Past the end of a scale is the end of it.
+    assert_eq!(profile.get(Axis::Oxygen), 1.0);
+    assert_eq!(profile.similarity(&profile), 100.0);
+
+    let opposite = Profile {
+        values: profile
+            .values
+            .map(|value| if value < 0.5 { 1.0 } else { 0.0 }),
+    };
+    assert!(profile.similarity(&opposite) < 50.0);
+    assert_eq!(benchmark("chablis").unwrap().grape, "Chardonnay");
+}
@@ -0,0 +1,70 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    Beverage, SimulationInput, run_simulation, sugar_from_honey, sugar_from_original_gravity,
+};
+use wine_maker::narrative::narrative;
+
+#[test]
+fn defaults_pick_from_each_beverages_substrates() {
+    for beverage in Beverage::ALL {
+        let input = beverage.defaults();
+        assert_eq!(input.beverage, beverage);
+        assert!(beverage.substrates().contains(&input.grape_type.as_str()));
+        let (low, high) = beverage.sugar_range();
+        assert!((low..=high).contains(&input.sugar_content));
+        assert_eq!(Beverage::from_label(beverage.label()), Some(beverage));
+    }
+    assert_eq!(
+        Beverage::from_label("fruit wine"),
+        Some(Beverage::FruitWine)
+    );
+}
+
+#[test]
+fn sugar_from_honey_and_gravity() {
+    assert!((sugar_from_honey(3.0, 10.0) - 240.0).abs() < 1e-9);
+    assert_eq!(sugar_from_honey(3.0, 0.0), 0.0);
+    assert!((sugar_from_original_gravity(1.050) - 107.5).abs() < 1e-9);
+    assert_eq!(sugar_from_original_gravity(0.990), 0.0);
+}
+
+#[test]
+fn mead_ignores_the_climate_and_has_no_tannins() {
+    let cool = SimulationInput {
+        climate: "Cool".to_owned(),
+        ..Beverage::Mead.defaults()
+    };
+    let warm = SimulationInput {
+        climate: "Warm".to_owned(),
+        ..cool.clone()
+    };
+    let result = run_simulation(&cool, &Dataset::default()).unwrap();
+    let warm_result = run_simulation(&warm, &Dataset::default()).unwrap();
+    assert_eq!(result.sugar_content, cool.sugar_content);
+    assert_eq!(warm_result.sugar_content, result.sugar_content);
+    assert_eq!(result.tannin_level, "no");
+    assert_eq!(result.grape_characteristics, "Wildflower honey, beeswax");
+
+    let text = narrative(&cool, &result);
+    assert!(
+        text.starts_with("Your Wildflower mead was fermented"),
+        "{text}"
+    );
+    assert!(!text.contains("climate"), "{text}");
+    assert!(text.ends_with("Enjoy your mead."), "{text}");
+}
+
+#[test]
+fn ciders_use_cider_sweetness_terms() {
+    let input = SimulationInput {
+        fermentation_days: 3,
+        ..Beverage::Cider.defaults()
+    };
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert!(
+        ["dry", "medium dry", "medium sweet", "sweet"].contains(&result.sweetness.as_str()),
+        "{}",
+        result.sweetness
+    );
+    assert_eq!(result.acidity, "crisp");
+}
@@ -0,0 +1,59 @@
+use wine_maker::body::{GLYCEROL_PER_ABV, body_index, glycerol, oak_months, strain_glycerol};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Beverage, Inoculation, run_simulation};
+
+#[test]
+fn warm_fermentations_make_more_glycerol() {
+    assert_eq!(glycerol(12.0, 20.0, 200.0, 1.0), 12.0 * GLYCEROL_PER_ABV);
+    assert!(glycerol(12.0, 28.0, 200.0, 1.0) > glycerol(12.0, 14.0, 200.0, 1.0));
+    assert_eq!(
+        glycerol(12.0, 60.0, 200.0, 1.0),
+        glycerol(12.0, 35.0, 200.0, 1.0)
+    );
+}
+
+#[test]
+fn sugary_musts_and_some_yeasts_make_more_glycerol() {
+    let ordinary = glycerol(12.0, 20.0, 210.0, 1.0);
+    assert!(glycerol(12.0, 20.0, 280.0, 1.0) > ordinary);
+    assert_eq!(
+        glycerol(12.0, 20.0, 600.0, 1.0),
+        glycerol(12.0, 20.0, 500.0, 1.0)
+    );
+
+    let mut input = Beverage::Wine.defaults();
+    input.yeast = "EC-1118".to_owned();
+    let champagne = strain_glycerol(&input);
+    input.yeast = "D47".to_owned();
+    assert!(strain_glycerol(&input) > champagne);
+    input.inoculation = Inoculation::NonSaccharomyces;
+    assert!(strain_glycerol(&input) > 1.15);
+}
+
+#[test]
+fn sugar_lees_and_oak_add_body() {
+    let mut input = Beverage::Wine.defaults();
+    input.grape_type = "Chardonnay".to_owned();
+    input.container_type = "Steel Tank".to_owned();
+    input.aging_months = 0;
+    let plain = glycerol(11.0, 20.0, 200.0, 1.0);
+    let dry = body_index(&input, 11.0, 0.0, plain);
+    assert_eq!(dry, 11.0);
+    let off_dry = body_index(&input, 11.0, 20.0, plain);
+    assert!(off_dry > 11.0);
+
+    input.lees_months = 6;
+    input.stirs_per_month = 2;
+    input.aging_container = "Barrique".to_owned();
+    input.aging_months = 9;
+    assert_eq!(oak_months(&input), 9.0);
+    assert!(body_index(&input, 11.0, 0.0, plain) > dry + 1.0);
+}
+
+#[test]
+fn results_describe_the_body_index() {
+    let input = Beverage::Wine.defaults();
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert!(result.body_index >= result.actual_abv);
+    assert!(result.glycerol > 0.0);
+}
@@ -0,0 +1,52 @@
+use wine_maker::branch::Branch;
+use wine_maker::engine::{
+    Beverage, Excursion, InterventionKind, SimulationInput, fermentation_curve, temperature_on,
+};
+use wine_maker::sulfides::sulfide_fault;
+
+fn plan() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        fermentation_days: 14,
+        sugar_content: 220.0,
+        temperature: 22.0,
+        excursions: vec![Excursion::heat_wave(8)],
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn branches_share_the_past_and_part_after_the_fork() {
+    let plan = plan();
+    let unchanged = Branch::fork(&plan, 5);
+    assert_eq!(
+        unchanged.curve().unwrap(),
+        fermentation_curve(&plan).unwrap()
+    );
+
+    let mut cooler = Branch::fork(&plan, 5);
+    cooler.set_temperature(14.0);
+    assert_eq!(cooler.label(), "From day 5: Temperature 14.0 °C");
+    // The heat wave after the fork is replaced too.
+    assert_eq!(temperature_on(&cooler.input, 9), 14.0);
+    assert_eq!(temperature_on(&cooler.input, 4), 22.0);
+
+    let planned = fermentation_curve(&plan).unwrap();
+    let branched = cooler.curve().unwrap();
+    assert_eq!(planned[..=5], branched[..=5]);
+    assert!(branched[14].abv < planned[14].abv);
+}
+
+#[test]
+fn an_extra_dose_of_nutrient_heads_off_sulfides() {
+    let mead = SimulationInput {
+        interventions: Vec::new(),
+        ..Beverage::Mead.defaults()
+    };
+    assert!(sulfide_fault(&mead).is_some());
+
+    let mut fed = Branch::fork(&mead, 1);
+    fed.add(InterventionKind::Nutrient, 300.0);
+    assert!(sulfide_fault(&fed.input).is_none());
+    assert_eq!(fed.label(), "From day 1: Yeast nutrient 300.0 mg/L N");
+}
@@ -0,0 +1,142 @@
+use wine_maker::calculators::{
+    BottleFormat, DEFAULT_PRESS_EFFICIENCY, DilutionTarget, DilutionWarning, FiningWarning,
+    GrapeColor, HEAT_STABLE_PROTEIN, HYDROMETER_CALIBRATION_TEMPERATURE, OakAdjunct,
+    PrimingWarning, SUGAR_PER_CO2_VOLUME, bentonite_fining, bottling_plan, co2_volumes,
+    corrected_gravity, dilution, grape_protein, juice_yield, oak_addition, priming, residual_co2,
+    sulfite_addition,
+};
+
+#[test]
+fn warm_hydrometer_readings_are_corrected_upwards() {
+    let corrected = corrected_gravity(1.050, 28.0, HYDROMETER_CALIBRATION_TEMPERATURE);
+    assert!((corrected - 1.052).abs() < 0.0005, "{corrected}");
+    assert!((corrected_gravity(1.050, 20.0, 20.0) - 1.050).abs() < 1e-12);
+}
+
+#[test]
+fn bottling_counts_allow_for_lees_and_racking() {
+    let plan = bottling_plan(23.0, BottleFormat::Standard, 2);
+    assert!((plan.wine_volume - 23.0 * 0.95 * 0.98 * 0.98).abs() < 1e-9);
+    assert_eq!(plan.bottles, 27);
+    assert_eq!(plan.closures, 27);
+    assert!(plan.leftover < BottleFormat::Standard.volume());
+
+    assert_eq!(bottling_plan(23.0, BottleFormat::Magnum, 2).bottles, 13);
+    assert_eq!(bottling_plan(23.0, BottleFormat::Half, 2).bottles, 55);
+}
+
+#[test]
+fn reds_yield_more_juice_than_whites() {
+    let red = juice_yield(100.0, GrapeColor::Red, DEFAULT_PRESS_EFFICIENCY);
+    let white = juice_yield(100.0, GrapeColor::White, DEFAULT_PRESS_EFFICIENCY);
+    assert!((red - 68.0).abs() < 1e-9, "{red}");
+    assert!(white < red);
+    assert_eq!(juice_yield(100.0, GrapeColor::White, 1.5), 75.0);
+}
+
+#[test]
+fn dilutes_sweet_must_to_a_target_sugar() {
+    let diluted = dilution(20.0, 300.0, Some(9.0), DilutionTarget::Sugar(250.0)).unwrap();
+    assert!((diluted.water - 4.0).abs() < 1e-9);
+    assert!((diluted.volume - 24.0).abs() < 1e-9);
+    assert!((diluted.acidity.unwrap() - 7.5).abs() < 1e-9);
+    assert_eq!(diluted.warnings, [DilutionWarning::FlavorDilution]);
+
+    assert!(dilution(20.0, 200.0, None, DilutionTarget::Sugar(250.0)).is_none());
+    assert!(dilution(20.0, 200.0, None, DilutionTarget::Acidity(6.0)).is_none());
+}
+
+#[test]
+fn warns_past_the_legal_amelioration_limit() {
+    let diluted = dilution(10.0, 220.0, Some(12.0), DilutionTarget::Acidity(4.5)).unwrap();
+    assert_eq!(
+        diluted.warnings,
+        [
+            DilutionWarning::OverLegalLimit,
+            DilutionWarning::AcidityBelowMinimum,
+            DilutionWarning::FlavorDilution,
+        ]
+    );
+}
+
+#[test]
+fn tirage_sugar_for_six_bar() {
+    let tirage = priming(co2_volumes(6.0), 20.0, 0.0);
+    assert!((22.0..25.0).contains(&tirage.sugar), "{}", tirage.sugar);
+    assert!((tirage.pressure - 6.0).abs() < 1e-9);
+    assert_eq!(tirage.warnings, [PrimingWarning::NeedsSparklingBottles]);
+}
+
+#[test]
+fn pet_nat_with_too_much_sugar_left_should_wait() {
+    let pet_nat = priming(2.5, 20.0, 15.0);
+    assert_eq!(pet_nat.sugar, 0.0);
+    let [PrimingWarning::ResidualSugarTooHigh { bottle_at }, _] = pet_nat.warnings[..] else {
+        panic!("{:?}", pet_nat.warnings);
+    };
+    assert!((bottle_at - (2.5 - residual_co2(20.0)) * SUGAR_PER_CO2_VOLUME).abs() < 1e-9);
+}
+
+#[test]
+fn sulfite_needed_rises_with_ph() {
+    let addition = sulfite_addition(23.0, 3.4, 10.0, GrapeColor::White.molecular_so2_target());
+    assert!((addition.free_so2_target - 31.9).abs() < 0.1);
+    assert!((addition.metabisulfite - 0.875).abs() < 0.001);
+    assert!(addition.is_practical());
+
+    assert!(!sulfite_addition(23.0, 3.9, 10.0, 0.8).is_practical());
+    assert_eq!(sulfite_addition(23.0, 3.0, 40.0, 0.5).metabisulfite, 0.0);
+}
+
+#[test]
+fn oak_chips_extract_faster_than_staves() {
+    let chips = oak_addition(23.0, OakAdjunct::Chips, 2.0, 14);
+    assert!((chips.grams - 46.0).abs() < 1e-9);
+    assert!(chips.extracted > 0.9);
+    assert!((chips.barrel_months - 12.0).abs() < 1.0);
+    assert!(!chips.is_over_oaked());
+
+    let staves = oak_addition(23.0, OakAdjunct::Staves, 3.0, 14);
+    assert!(staves.extracted < 0.5);
+}
+
+#[test]
+fn heavy_oak_dosing_warns() {
+    assert!(oak_addition(23.0, OakAdjunct::Chips, 6.0, 30).is_over_oaked());
+    assert!(!oak_addition(23.0, OakAdjunct::Chips, 6.0, 2).is_over_oaked());
+}
+
+#[test]
+fn unfined_whites_haze_and_warm_storage_needs_more_bentonite() {
+    let unfined = bentonite_fining(23.0, "Sauvignon Blanc", 0.0, 15.0);
+    assert_eq!(unfined.residual_protein, grape_protein("Sauvignon Blanc"));
+    assert!(matches!(
+        unfined.warnings[..],
+        [FiningWarning::HazeRisk { .. }]
+    ));
+    assert!(!unfined.is_heat_stable());
+
+    let fined = bentonite_fining(23.0, "Sauvignon Blanc", unfined.needed, 15.0);
+    assert!((fined.grams - unfined.needed * 0.23).abs() < 1e-9);
+    assert!(fined.residual_protein <= HEAT_STABLE_PROTEIN);
+    assert!(fined.is_heat_stable());
+
+    // The same dose is marginal in a warm garage.
+    let warm = bentonite_fining(23.0, "Sauvignon Blanc", unfined.needed, 28.0);
+    assert!(matches!(
+        warm.warnings[..],
+        [FiningWarning::WarmStorageHaze { .. }]
+    ));
+    assert!(warm.needed > fined.needed);
+}
+
+#[test]
+fn reds_need_no_bentonite() {
+    let merlot = bentonite_fining(23.0, "Merlot", 0.0, 28.0);
+    assert_eq!(merlot.needed, 0.0);
+    assert!(merlot.warnings.is_empty());
+    assert_eq!(
+        bentonite_fining(23.0, "Merlot", 50.0, 15.0).warnings,
+        [FiningWarning::OverFined]
+    );
+}
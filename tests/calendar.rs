@@ -0,0 +1,62 @@
+use chrono::NaiveDate;
+use wine_maker::calendar::schedule_to_ical;
+use wine_maker::cellar::{Batch, Process};
+use wine_maker::engine::SimulationInput;
+
+fn batch() -> Batch {
+    Batch {
+        id: 7,
+        name: "Merlot, 2025".to_owned(),
+        started_on: NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(),
+        input: SimulationInput {
+            fermentation_days: 14,
+            ..SimulationInput::default()
+        },
+        predicted_abv: None,
+        notes: String::new(),
+        volume: None,
+    }
+}
+
+fn stamp() -> chrono::NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, 9, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+#[test]
+fn exports_tasks_as_all_day_events() {
+    let batch = batch();
+    let tasks = batch.schedule(&Process::default());
+    let ics = schedule_to_ical(&batch, &tasks, stamp());
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), tasks.len());
+    assert!(ics.contains("UID:rack-gross-lees.batch-7@wine-maker\r\n"));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20250915\r\nDTEND;VALUE=DATE:20250916\r\n"));
+    assert!(ics.contains("SUMMARY:Merlot\\, 2025: Bottle\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 75));
+}
+
+#[test]
+fn regenerated_schedules_keep_their_event_ids() {
+    let batch = batch();
+    let uids = |process| {
+        let ics = schedule_to_ical(&batch, &batch.schedule(&process), stamp());
+        ics.lines()
+            .filter(|line| line.starts_with("UID:"))
+            .map(str::to_owned)
+            .collect::<Vec<_>>()
+    };
+    let short = uids(Process {
+        malolactic: false,
+        aging_months: 6,
+    });
+    let long = uids(Process {
+        malolactic: true,
+        aging_months: 12,
+    });
+    assert!(short.iter().all(|uid| long.contains(uid)));
+}
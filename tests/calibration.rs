@@ -0,0 +1,111 @@
+use chrono::{NaiveDate, TimeDelta};
+use wine_maker::calibration::{self, Calibration, CalibrationError, MIN_READINGS, calibrate};
+use wine_maker::cellar::{Batch, Measurement};
+use wine_maker::engine::{
+    SUGAR_PER_ABV, SimulationInput, fermentation_state_calibrated, rate_constant,
+};
+
+fn batch(id: i64, temperature: f64) -> Batch {
+    Batch {
+        id,
+        name: format!("batch {}", id),
+        started_on: NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(),
+        input: SimulationInput {
+            grape_type: "Merlot".to_owned(),
+            fermentation_days: 14,
+            sugar_content: 220.0,
+            temperature,
+            ..SimulationInput::default()
+        },
+        predicted_abv: None,
+        notes: String::new(),
+        volume: None,
+    }
+}
+
+/// Hydrometer readings on `days` as a cellar running on `truth` would give.
+fn readings(batch: &Batch, truth: &Calibration, days: &[u32]) -> Vec<Measurement> {
+    days.iter()
+        .map(|&day| {
+            let k = truth.rate_constant(batch.input.temperature);
+            let state = fermentation_state_calibrated(&batch.input, day, k, truth);
+            Measurement {
+                measured_on: batch.started_on + TimeDelta::days(day.into()),
+                sugar_content: state.residual_sugar,
+                specific_gravity: Some(state.specific_gravity()),
+                temperature: None,
+                notes: String::new(),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn recovers_the_constants_a_cellar_runs_on() {
+    let truth = Calibration {
+        k_ref: 0.15,
+        q10: 2.5,
+        sugar_per_abv: 17.5,
+    };
+    let batches: Vec<(Batch, Vec<Measurement>)> = [(1, 16.0), (2, 24.0)]
+        .into_iter()
+        .map(|(id, temperature)| {
+            let batch = batch(id, temperature);
+            let measurements = readings(&batch, &truth, &[2, 4, 7, 10, 14]);
+            (batch, measurements)
+        })
+        .collect();
+
+    let fit = calibrate(&batches).unwrap();
+    assert_eq!(fit.batches, 2);
+    assert_eq!(fit.readings, 10);
+    assert!((fit.calibration.k_ref - truth.k_ref).abs() < 0.01);
+    assert!((fit.calibration.q10 - truth.q10).abs() < 0.1);
+    assert!((fit.calibration.sugar_per_abv - truth.sugar_per_abv).abs() < 0.2);
+    assert!(fit.fitted_error < 0.5);
+    assert!(fit.fitted_error < fit.standard_error);
+}
+
+#[test]
+fn needs_enough_readings_after_the_start() {
+    let batch = batch(1, 20.0);
+    let measurements = readings(&batch, &Calibration::default(), &[0, 3]);
+    assert_eq!(
+        calibrate(&[(batch, measurements)]),
+        Err(CalibrationError::TooFewReadings(1))
+    );
+    assert!(
+        CalibrationError::TooFewReadings(1)
+            .to_string()
+            .contains(&MIN_READINGS.to_string())
+    );
+}
+
+#[test]
+fn installed_constants_drive_the_simulation() {
+    let standard = rate_constant(20.0);
+    calibration::install(Calibration {
+        k_ref: standard * 2.0,
+        ..Calibration::default()
+    });
+    assert_eq!(rate_constant(20.0), standard * 2.0);
+    calibration::uninstall();
+    assert_eq!(rate_constant(20.0), standard);
+    assert_eq!(calibration::installed().sugar_per_abv, SUGAR_PER_ABV);
+}
+
+#[test]
+fn a_panicking_run_gives_back_the_thread_calibration() {
+    let fast = Calibration {
+        k_ref: rate_constant(20.0) * 3.0,
+        ..Calibration::default()
+    };
+    let caught = std::panic::catch_unwind(|| {
+        calibration::with_calibration(fast, || {
+            assert_eq!(calibration::installed(), fast);
+            panic!("the run failed");
+        })
+    });
+    assert!(caught.is_err());
+    assert_ne!(calibration::installed(), fast);
+}
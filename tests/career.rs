@@ -0,0 +1,87 @@
+use wine_maker::career::{BATCH_COST, Career, CareerError, Event, MAX_BATCHES};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::SimulationInput;
+
+#[test]
+fn seasons_are_the_same_for_the_same_seed_and_year() {
+    let career = Career::new("Mosel", 7).unwrap();
+    assert_eq!(career.season().unwrap(), career.season().unwrap());
+    let plan = career.plan().unwrap();
+    assert_eq!(plan.grape_type, "Riesling");
+
+    // No year rolls two weather or two market events.
+    let mut later = career.clone();
+    for year in 1..=40 {
+        later.year = year;
+        let season = later.season().unwrap();
+        assert!(
+            season
+                .events
+                .iter()
+                .filter(|event| event.is_weather())
+                .count()
+                <= 1
+        );
+        assert!(
+            season
+                .events
+                .iter()
+                .filter(|event| !event.is_weather())
+                .count()
+                <= 1
+        );
+        assert!(season.yield_factor() > 0.0);
+    }
+    assert_eq!(Event::from_label("hail"), Some(Event::Hail));
+    assert!(matches!(
+        Career::new("Atlantis", 7),
+        Err(CareerError::UnknownRegion(_))
+    ));
+}
+
+#[test]
+fn a_vintage_sells_its_batches_and_moves_the_reputation() {
+    let mut career = Career::new("Mosel", 7).unwrap();
+    let plan = career.plan().unwrap();
+    let mine = SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        sugar_content: 300.0,
+        fermentation_days: 28,
+        seed: Some(1),
+        ..plan.clone()
+    };
+    career.add_batch("Estate", &mine).unwrap();
+    // The fruit is the year's harvest whatever the plan says.
+    assert_eq!(career.batches[0].input.grape_type, plan.grape_type);
+    assert_eq!(career.batches[0].input.sugar_content, plan.sugar_content);
+    assert_eq!(career.batches[0].input.fermentation_days, 28);
+    for _ in 1..MAX_BATCHES {
+        career.add_batch("More", &mine).unwrap();
+    }
+    assert_eq!(
+        career.add_batch("One too many", &mine),
+        Err(CareerError::TooManyBatches)
+    );
+    career.remove_batch(2);
+    career.remove_batch(1);
+
+    let (reputation, cash) = (career.reputation, career.cash);
+    let report = career.finish_vintage(&Dataset::default()).unwrap().clone();
+    assert_eq!(report.year, 1);
+    assert_eq!(report.batches.len(), 1);
+    let score = report.batches[0].score;
+    assert!(
+        (career.reputation - reputation).abs() <= (score - reputation).abs(),
+        "the reputation moves towards the score"
+    );
+    let revenue = report.batches[0].revenue();
+    assert!((career.cash - (cash + revenue - BATCH_COST)).abs() < 1e-6);
+
+    assert_eq!(career.year, 2);
+    assert!(career.batches.is_empty());
+    assert_eq!(career.history, [report]);
+    assert_eq!(
+        career.finish_vintage(&Dataset::default()).err(),
+        Some(CareerError::NoBatches)
+    );
+}
@@ -0,0 +1,226 @@
+use chrono::NaiveDate;
+use wine_maker::cellar::{Batch, Measurement, Process};
+use wine_maker::engine::{
+    Beverage, SimulationInput, fermentation_state, fermentation_state_at_rate, sugar_per_abv,
+};
+use wine_maker::stage::Stage;
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+}
+
+fn measurement(day: u32, sugar_content: f64) -> Measurement {
+    Measurement {
+        measured_on: date(day),
+        sugar_content,
+        specific_gravity: None,
+        temperature: None,
+        notes: String::new(),
+    }
+}
+
+fn batch(fermentation_days: u32) -> Batch {
+    Batch {
+        id: 1,
+        name: "merlot".to_owned(),
+        started_on: date(1),
+        input: SimulationInput {
+            grape_type: "Merlot".to_owned(),
+            fermentation_days,
+            sugar_content: 220.0,
+            temperature: 20.0,
+            ..SimulationInput::default()
+        },
+        predicted_abv: None,
+        notes: String::new(),
+        volume: None,
+    }
+}
+
+#[test]
+fn pairs_readings_with_the_planned_day() {
+    let batch = batch(7);
+    let measurements = [
+        measurement(3, 160.0),
+        measurement(3, 150.0),
+        measurement(11, 4.0),
+    ];
+
+    let progress = batch.progress(&measurements).unwrap();
+    assert_eq!(progress.len(), 11);
+    assert_eq!(progress[2].measured, Some(measurements[1].clone()));
+    assert_eq!(progress[10].measured, Some(measurements[2].clone()));
+    assert!(progress[5].measured.is_none());
+    assert_eq!(progress[10].planned.day, 10);
+}
+
+#[test]
+fn tracks_the_wall_clock_day() {
+    let batch = batch(14);
+
+    let tracking = batch.tracking(date(10)).unwrap();
+    assert!(tracking.is_active());
+    assert!(
+        tracking
+            .to_string()
+            .starts_with("Today is day 9 of 14; predicted SG ")
+    );
+
+    let before = batch
+        .tracking(NaiveDate::from_ymd_opt(2025, 8, 30).unwrap())
+        .unwrap();
+    assert!(!before.is_active());
+    assert_eq!(before.planned, None);
+    assert!(!batch.tracking(date(20)).unwrap().is_active());
+}
+
+#[test]
+fn refits_the_rate_to_readings() {
+    let batch = batch(14);
+    let measurements: Vec<Measurement> = [2, 4, 6]
+        .into_iter()
+        .map(|day| {
+            let state = fermentation_state_at_rate(&batch.input, day, 0.3);
+            measurement(1 + day, state.residual_sugar)
+        })
+        .collect();
+
+    let forecast = batch.forecast(&measurements).unwrap();
+    assert!((forecast.rate_constant - 0.3).abs() < 1e-9);
+    assert_eq!(forecast.readings, 3);
+    assert_eq!(
+        forecast.finish_date,
+        date(1) + chrono::TimeDelta::days(forecast.finish_day as i64)
+    );
+    assert!(batch.forecast(&[]).is_none());
+}
+
+#[test]
+fn reports_status_from_the_latest_reading_or_the_plan() {
+    let batch = batch(14);
+    let mut schedule = batch.schedule(&Process::default());
+    schedule[0].done = true;
+
+    let planned = batch.status(&[], &schedule, date(10)).unwrap();
+    assert_eq!(planned.day, 9);
+    assert_eq!(planned.days_remaining, 5);
+    assert_eq!(
+        planned.abv,
+        fermentation_state(&batch.input, 9).unwrap().abv
+    );
+    assert_eq!(planned.measured_on, None);
+    assert_eq!(planned.next_task.as_ref(), Some(&schedule[1]));
+
+    let measurements = [measurement(5, 150.0), measurement(12, 20.0)];
+    let measured = batch.status(&measurements, &schedule, date(10)).unwrap();
+    assert_eq!(measured.measured_on, Some(date(5)));
+    assert_eq!(measured.residual_sugar, 150.0);
+    assert!((measured.abv - (batch.initial_sugar() - 150.0) / sugar_per_abv()).abs() < 1e-9);
+    let forecast = batch.forecast(&measurements).unwrap();
+    assert_eq!(
+        measured.days_remaining,
+        (forecast.finish_date - date(10)).num_days().max(0)
+    );
+}
+
+#[test]
+fn schedules_cellar_work_after_primary() {
+    let batch = batch(14);
+    let schedule = batch.schedule(&Process {
+        malolactic: true,
+        aging_months: 9,
+    });
+    let titles: Vec<&str> = schedule.iter().map(|task| task.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        [
+            "Add yeast nutrient",
+            "Add yeast nutrient at the one-third sugar break",
+            "Rack off the gross lees and inoculate for malolactic fermentation",
+            "Confirm malolactic fermentation is complete",
+            "Add sulfite",
+            "Rack off the fine lees, top up, and check sulfite",
+            "Rack off the fine lees, top up, and check sulfite",
+            "Stabilize and fine",
+            "Bottle",
+        ]
+    );
+    assert_eq!(schedule[1].due_on, date(4));
+    assert_eq!(schedule[2].due_on, date(15));
+    assert_eq!(
+        schedule.last().unwrap().due_on,
+        NaiveDate::from_ymd_opt(2026, 7, 27).unwrap()
+    );
+    assert!(schedule.is_sorted_by_key(|task| task.due_on));
+}
+
+#[test]
+fn meads_get_staggered_nutrient_additions() {
+    let mut batch = batch(30);
+    batch.input.beverage = Beverage::Mead;
+    let schedule = batch.schedule(&Process::default());
+    let nutrients: Vec<(&str, NaiveDate)> = schedule
+        .iter()
+        .filter(|task| task.uid.starts_with("nutrient"))
+        .map(|task| (task.uid.as_str(), task.due_on))
+        .collect();
+    assert_eq!(
+        nutrients,
+        [
+            ("nutrient-1", date(2)),
+            ("nutrient-day-2", date(3)),
+            ("nutrient-day-3", date(4)),
+            ("nutrient-2", date(4)),
+        ]
+    );
+}
+
+#[test]
+fn lays_the_plan_out_on_the_calendar() {
+    let batch = Batch {
+        input: SimulationInput {
+            aging_months: 6,
+            ..batch(14).input
+        },
+        ..batch(14)
+    };
+
+    let timeline = batch.timeline().unwrap();
+    assert_eq!(timeline[0].starts_on, batch.started_on);
+    for pair in timeline.windows(2) {
+        assert_eq!(pair[0].ends_on, Some(pair[1].starts_on));
+    }
+    let bottled = timeline.last().unwrap();
+    assert_eq!(bottled.stage, Stage::Bottled);
+    assert_eq!(bottled.starts_on, batch.bottled_on());
+    assert_eq!(bottled.ends_on, None);
+    assert!(batch.bottled_on() > date(1) + chrono::Months::new(6));
+}
+
+#[test]
+fn reports_readings_against_the_plan() {
+    let batch = batch(14);
+    let plan = batch.progress(&[]).unwrap();
+    let planned = |day: usize| plan[day].planned.residual_sugar;
+    let mut behind = measurement(3, planned(2) + 10.0);
+    behind.temperature = Some(18.0);
+    let measurements = [
+        measurement(1, planned(0)),
+        behind,
+        measurement(5, 0.0),
+        measurement(5, planned(4) - 4.0),
+    ];
+
+    let report = batch.variance(&measurements).unwrap().unwrap();
+    assert_eq!(report.variances.len(), 3);
+    assert_eq!(report.variances[1].day, 2);
+    assert!((report.bias - 2.0).abs() < 1e-9);
+    assert!((report.mean_absolute_error - 14.0 / 3.0).abs() < 1e-9);
+    assert!((report.rms_error - (116.0f64 / 3.0).sqrt()).abs() < 1e-9);
+    assert_eq!(report.worst().day, 2);
+    assert_eq!(report.temperature_bias, Some(-2.0));
+    let summary = report.to_string();
+    assert!(summary.starts_with("Over 3 readings the ferment ran 2.0 g/L behind plan on average"));
+    assert!(summary.ends_with(" It ran 2.0 °C cooler than planned."));
+    assert!(batch.variance(&[]).unwrap().is_none());
+}
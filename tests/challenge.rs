@@ -0,0 +1,64 @@
+use wine_maker::challenge::{Level, PASS_SCORE, challenge, unlocked_levels};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{SUGAR_PER_ABV, SimulationInput, climate_sugar_factor, run_simulation};
+
+fn cool_riesling(abv: f64) -> SimulationInput {
+    SimulationInput {
+        grape_type: "Riesling".to_owned(),
+        fermentation_days: 28,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content: abv * SUGAR_PER_ABV / climate_sugar_factor("Cool"),
+        temperature: 16.0,
+        climate: "Cool".to_owned(),
+        seed: Some(3),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn scores_a_wine_against_its_target() {
+    let data = Dataset::default();
+    let target = challenge("a dry, high-acid, 11.5% ABV white").unwrap();
+    assert_eq!(target.level, Level::Cellarhand);
+
+    let made = cool_riesling(11.6);
+    let score = target.score(&made, &run_simulation(&made, &data).unwrap());
+    assert!(score.passed(), "{:?}", score);
+    assert_eq!(score.checks.len(), 4);
+
+    // Too strong, and from a red grape.
+    let missed = SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        ..cool_riesling(14.0)
+    };
+    let score = target.score(&missed, &run_simulation(&missed, &data).unwrap());
+    assert!(!score.passed());
+    assert_eq!(score.checks[0].points, 0.0);
+    assert_eq!(score.checks[1].points, 0.0);
+}
+
+#[test]
+fn passing_a_level_opens_the_next() {
+    assert_eq!(unlocked_levels(&[]), [Level::Apprentice]);
+    let passed_first = [(
+        Level::Apprentice
+            .challenges()
+            .next()
+            .unwrap()
+            .name
+            .to_owned(),
+        PASS_SCORE,
+    )];
+    assert_eq!(
+        unlocked_levels(&passed_first),
+        [Level::Apprentice, Level::Cellarhand]
+    );
+    let only_tried = [(passed_first[0].0.clone(), PASS_SCORE - 1.0)];
+    assert_eq!(unlocked_levels(&only_tried), [Level::Apprentice]);
+    // Every level has something to play.
+    assert!(
+        Level::ALL
+            .iter()
+            .all(|level| level.challenges().count() >= 2)
+    );
+}
@@ -0,0 +1,66 @@
+use wine_maker::chemistry::{chemistry_panel, estimated_ph, sulfur_dioxide, volatile_acidity};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Beverage, VOLATILE_FLAW, run_simulation};
+
+#[test]
+fn panel_lists_every_measurement_with_units_and_ranges() {
+    let input = Beverage::Wine.defaults();
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    let panel = chemistry_panel(&input, &result);
+    let names: Vec<&str> = panel.iter().map(|measurement| measurement.name).collect();
+    assert_eq!(
+        names,
+        [
+            "Alcohol",
+            "Residual sugar",
+            "Titratable acidity",
+            "pH",
+            "Volatile acidity",
+            "Free SO2",
+            "Total SO2",
+            "Glycerol",
+            "Dissolved CO2",
+        ]
+    );
+    assert_eq!(
+        panel[0].value_text(),
+        format!("{:.1} % ABV", result.actual_abv)
+    );
+    assert!(!panel[3].value_text().contains(' '));
+    for measurement in &panel {
+        assert!(measurement.typical.0 < measurement.typical.1);
+    }
+}
+
+#[test]
+fn more_acid_means_lower_ph() {
+    let input = Beverage::Wine.defaults();
+    assert!(estimated_ph(&input, 8.0) < estimated_ph(&input, 5.0));
+}
+
+#[test]
+fn oxygen_and_sugar_use_up_free_so2() {
+    let mut result = run_simulation(&Beverage::Wine.defaults(), &Dataset::default()).unwrap();
+    result.oxygen_uptake = 0.0;
+    result.residual_sugar = 0.0;
+    let (fresh, total) = sulfur_dioxide(&result);
+    assert!(fresh < total);
+    result.residual_sugar = 60.0;
+    assert!(sulfur_dioxide(&result).0 < fresh);
+    result.oxygen_uptake = 100.0;
+    assert_eq!(sulfur_dioxide(&result), (0.0, 0.0));
+}
+
+#[test]
+fn only_the_volatile_flaw_raises_volatile_acidity() {
+    let input = Beverage::Wine.defaults();
+    let mut result = run_simulation(&input, &Dataset::default()).unwrap();
+    result.off_flavors.clear();
+    let clean = volatile_acidity(&input, &result);
+    result
+        .off_flavors
+        .push("volatile, but not acetic".to_owned());
+    assert_eq!(volatile_acidity(&input, &result), clean);
+    result.off_flavors.push(VOLATILE_FLAW.to_owned());
+    assert!(volatile_acidity(&input, &result) > clean);
+}
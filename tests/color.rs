@@ -0,0 +1,77 @@
+use wine_maker::color::{wine_color, wine_color_after};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Beverage, SimulationInput, run_simulation};
+
+fn syrah() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Syrah".to_owned(),
+        fermentation_days: 10,
+        container_type: "Steel Tank".to_owned(),
+        aging_container: "Barrique".to_owned(),
+        sugar_content: 240.0,
+        temperature: 26.0,
+        climate: "Moderate".to_owned(),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn young_reds_are_deep_and_purple() {
+    let color = wine_color(&syrah());
+    assert!(color.anthocyanins > 500.0);
+    assert_eq!(color.polymeric, 0.0);
+    assert_eq!(color.descriptor(), "deep purple");
+
+    let pinot = wine_color(&SimulationInput {
+        grape_type: "Pinot Noir".to_owned(),
+        ..syrah()
+    });
+    assert!(pinot.intensity < color.intensity);
+    assert!(pinot.descriptor().starts_with("pale"));
+}
+
+#[test]
+fn aging_binds_and_browns_the_color() {
+    let input = syrah();
+    let colors: Vec<_> = (0..=36)
+        .map(|months| wine_color_after(&input, months))
+        .collect();
+    assert!(
+        colors
+            .windows(2)
+            .all(|w| w[1].anthocyanins < w[0].anthocyanins)
+    );
+    assert!(colors.windows(2).all(|w| w[1].polymeric > w[0].polymeric));
+    assert!(colors.windows(2).all(|w| w[1].browning > w[0].browning));
+    assert!(colors[36].intensity < colors[0].intensity);
+    assert!(colors[36].descriptor().ends_with("garnet"));
+    assert_ne!(colors[36].hex(), colors[0].hex());
+}
+
+#[test]
+fn whites_and_meads_are_golden() {
+    let riesling = SimulationInput {
+        grape_type: "Riesling".to_owned(),
+        aging_container: String::new(),
+        ..syrah()
+    };
+    let color = wine_color(&riesling);
+    assert_eq!(color.anthocyanins, 0.0);
+    assert_eq!(color.descriptor(), "pale straw");
+
+    let buckwheat = SimulationInput {
+        grape_type: "Buckwheat".to_owned(),
+        ..Beverage::Mead.defaults()
+    };
+    assert_eq!(wine_color(&buckwheat).descriptor(), "amber");
+}
+
+#[test]
+fn results_carry_the_swatch() {
+    let result = run_simulation(&syrah(), &Dataset::default()).unwrap();
+    let color = wine_color(&syrah());
+    assert_eq!(result.color, color.descriptor());
+    assert_eq!(result.color_hex, color.hex());
+    assert_eq!(result.color_hex.len(), 7);
+    assert!(result.color_hex.starts_with('#'));
+}
@@ -0,0 +1,89 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDate;
+use wine_maker::cellar::{Batch, Measurement, Process};
+use wine_maker::companion::{CompanionServer, page_html};
+use wine_maker::engine::SimulationInput;
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+}
+
+fn batch() -> Batch {
+    Batch {
+        id: 1,
+        name: "Merlot <2025>".to_owned(),
+        started_on: date(1),
+        input: SimulationInput {
+            grape_type: "Merlot".to_owned(),
+            fermentation_days: 14,
+            sugar_content: 220.0,
+            temperature: 20.0,
+            ..SimulationInput::default()
+        },
+        predicted_abv: None,
+        notes: String::new(),
+        volume: None,
+    }
+}
+
+fn get(port: u16, path: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    // Headers like a phone's browser sends, all of which have to be read.
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nUser-Agent: Mozilla/5.0 (Linux; Android 14)\r\nAccept: text/html,application/xhtml+xml\r\nAccept-Language: en-GB,en;q=0.9\r\nAccept-Encoding: gzip, deflate\r\nConnection: keep-alive\r\n\r\n",
+        path
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn shows_the_status_chart_and_tasks_still_to_do() {
+    let batch = batch();
+    let mut schedule = batch.schedule(&Process::default());
+    schedule[0].done = true;
+    let measurements = [Measurement {
+        measured_on: date(5),
+        sugar_content: 150.0,
+        specific_gravity: None,
+        temperature: None,
+        notes: String::new(),
+    }];
+
+    let html = page_html(&batch, &measurements, &schedule, date(10)).unwrap();
+    assert!(html.contains("<h1>Merlot &lt;2025&gt;</h1>"));
+    assert!(html.contains("Day 9 of 14"));
+    assert!(html.contains("from the reading on 2025-09-05"));
+    assert!(html.contains("<svg"));
+    assert!(html.contains("<circle"));
+    assert!(html.contains(&schedule[1].title));
+    assert!(!html.contains(&format!("<strong>{}</strong>", schedule[0].due_on)));
+}
+
+#[test]
+fn serves_the_page_and_nothing_else() {
+    let server = CompanionServer::start(0).unwrap();
+    server.set_page("<p>cellar</p>".to_owned());
+
+    let page = get(server.port(), "/");
+    assert!(page.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(page.ends_with("\r\n\r\n<p>cellar</p>"));
+    assert!(get(server.port(), "/secrets").starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[test]
+fn an_idle_client_does_not_hold_up_the_page() {
+    let server = CompanionServer::start(0).unwrap();
+    server.set_page("<p>cellar</p>".to_owned());
+
+    let _idle = TcpStream::connect(("127.0.0.1", server.port())).unwrap();
+    let started = Instant::now();
+    assert!(get(server.port(), "/").starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
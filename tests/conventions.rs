@@ -0,0 +1,38 @@
+use wine_maker::conventions::{
+    StrengthConvention, SugarConvention, alcohol_grams_per_liter, sugar_percent_by_weight,
+    wine_density,
+};
+
+#[test]
+fn dry_wine_is_lighter_than_water_and_sweet_wine_heavier() {
+    assert!(wine_density(2.0, 13.0) < 1.0);
+    assert!(wine_density(120.0, 10.0) > 1.0);
+    let percent = sugar_percent_by_weight(100.0, 10.0);
+    assert!(percent > 9.5 && percent < 10.0);
+}
+
+#[test]
+fn writes_strength_each_way() {
+    assert_eq!(StrengthConvention::Abv.format(14.4), "14.4% ABV");
+    assert_eq!(StrengthConvention::UsProof.format(14.4), "28.8° proof");
+    assert_eq!(StrengthConvention::UkProof.format(12.0), "21.0° UK proof");
+    assert!((alcohol_grams_per_liter(12.0) - 94.68).abs() < 1e-9);
+    assert_eq!(
+        StrengthConvention::GramsPerLiter.alongside(12.0),
+        "12.0% ABV (95 g/L of alcohol)"
+    );
+}
+
+#[test]
+fn the_usual_units_stand_alone() {
+    assert_eq!(
+        SugarConvention::GramsPerLiter.alongside(85.8, 5.1),
+        "85.8 g/L"
+    );
+    assert_eq!(StrengthConvention::Abv.alongside(5.1), "5.1% ABV");
+    assert!(
+        SugarConvention::PercentByWeight
+            .alongside(85.8, 5.1)
+            .starts_with("85.8 g/L (8.")
+    );
+}
@@ -0,0 +1,55 @@
+use wine_maker::costs::{Cost, Price, cost_report};
+
+fn cost(category: &str, quantity: f64, unit_price: f64) -> Cost {
+    Cost {
+        category: category.to_owned(),
+        description: String::new(),
+        quantity,
+        unit_price,
+    }
+}
+
+#[test]
+fn reports_cost_per_liter_and_bottle() {
+    let costs = [
+        cost("Grapes/Juice", 100.0, 1.5),
+        cost("Bottles", 30.0, 0.8),
+        cost("Closures", 30.0, 0.2),
+        cost("Grapes/Juice", 1.0, 6.0),
+    ];
+    let report = cost_report(&costs, Some(23.0));
+
+    assert_eq!(report.total, 186.0);
+    assert_eq!(
+        report.by_category,
+        [
+            ("Grapes/Juice".to_owned(), 156.0),
+            ("Bottles".to_owned(), 24.0),
+            ("Closures".to_owned(), 6.0),
+        ]
+    );
+    assert_eq!(report.bottles, Some(30));
+    assert_eq!(report.per_bottle, Some(6.2));
+    assert!((report.per_liter.unwrap() - 186.0 / 23.0).abs() < 1e-12);
+}
+
+#[test]
+fn per_unit_costs_wait_for_the_volume() {
+    let report = cost_report(&[cost("Yeast", 1.0, 4.5)], None);
+    assert_eq!(report.total, 4.5);
+    assert_eq!(report.per_liter, None);
+    assert_eq!(report.per_bottle, None);
+}
+
+#[test]
+fn prices_become_costs() {
+    let price = Price {
+        name: "Bordeaux bottle".to_owned(),
+        category: "Bottles".to_owned(),
+        unit: "bottle".to_owned(),
+        unit_price: 0.8,
+    };
+    let cost = price.cost(30.0);
+    assert_eq!(cost.description, "Bordeaux bottle (bottle)");
+    assert_eq!(cost.total(), 24.0);
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "serde")]
+
+use wine_maker::dataset::Dataset;
+use wine_maker::descriptors::{self, ALCOHOL, BODY, DescriptorError, DescriptorScales, SWEETNESS};
+use wine_maker::engine::{Beverage, run_simulation};
+
+#[test]
+fn built_in_scales_keep_their_boundaries() {
+    let scales = DescriptorScales::default();
+    let sweetness = |beverage, sugar| scales.describe(SWEETNESS, beverage, sugar);
+    assert_eq!(sweetness(Beverage::Wine, 5.0), "bone dry");
+    assert_eq!(
+        sweetness(Beverage::Wine, 5.1),
+        "with just a subtle hint of sweetness"
+    );
+    assert_eq!(sweetness(Beverage::Wine, 36.0), "extremely sweet");
+    assert_eq!(sweetness(Beverage::FruitWine, 21.0), "noticeably sweet");
+    assert_eq!(sweetness(Beverage::Mead, 51.0), "sack-sweet");
+    assert_eq!(sweetness(Beverage::Cider, 9.5), "medium dry");
+
+    let body = |beverage, abv| scales.describe(BODY, beverage, abv);
+    assert_eq!(body(Beverage::Wine, 10.0), "medium-bodied");
+    assert_eq!(body(Beverage::Wine, 12.0), "medium-bodied");
+    assert_eq!(body(Beverage::Wine, 12.1), "full-bodied");
+    assert_eq!(body(Beverage::Cider, 6.4), "light-bodied");
+
+    let alcohol = |abv| scales.describe(ALCOHOL, Beverage::Wine, abv);
+    assert_eq!(alcohol(1.0), "extremely low");
+    assert_eq!(alcohol(4.9), "very low");
+    assert_eq!(alcohol(13.5), "high");
+    assert_eq!(alcohol(20.0), "extremely high");
+    assert!(scales.extra(10.0, 12.0, 12.0).is_empty());
+}
+
+const CUSTOM: &str = r#"{
+    "sweetness": {
+        "measure": "ResidualSugar",
+        "base": "dry",
+        "bands": [
+            { "from": 4.0, "label": "off-dry" },
+            { "from": 12.0, "label": "medium" },
+            { "from": 45.0, "label": "sweet" }
+        ]
+    },
+    "strength": {
+        "measure": "Abv",
+        "base": "session",
+        "bands": [{ "from": 13.0, "inclusive": true, "label": "strong" }]
+    }
+}"#;
+
+#[test]
+fn a_file_replaces_and_adds_scales() {
+    let scales = DescriptorScales::from_json(CUSTOM).unwrap();
+    assert_eq!(scales.describe(SWEETNESS, Beverage::Wine, 8.0), "off-dry");
+    // Meads keep their own scale, which the file left alone.
+    assert_eq!(scales.describe(SWEETNESS, Beverage::Mead, 8.0), "dry");
+    assert_eq!(scales.describe(BODY, Beverage::Wine, 12.1), "full-bodied");
+    assert_eq!(
+        scales.extra(2.0, 13.0, 13.0),
+        vec![("strength".to_owned(), "strong".to_owned())]
+    );
+}
+
+#[test]
+fn rejects_bands_out_of_order() {
+    let json = r#"{ "body": { "measure": "Abv", "base": "thin", "bands": [
+        { "from": 12.0, "label": "full" }, { "from": 10.0, "label": "medium" }
+    ] } }"#;
+    assert!(matches!(
+        DescriptorScales::from_json(json),
+        Err(DescriptorError::Unsorted(name)) if name == "body"
+    ));
+}
+
+#[test]
+fn installed_scales_describe_results_but_not_the_games() {
+    let mut input = Beverage::Wine.defaults();
+    input.sugar_content = 200.0;
+    input.fermentation_days = 30;
+    let standard = run_simulation(&input, &Dataset::default()).unwrap();
+
+    descriptors::install(DescriptorScales::from_json(CUSTOM).unwrap());
+    let custom = run_simulation(&input, &Dataset::default());
+    descriptors::uninstall();
+    let custom = custom.unwrap();
+
+    assert_eq!(
+        custom.sweetness,
+        DescriptorScales::from_json(CUSTOM).unwrap().describe(
+            SWEETNESS,
+            Beverage::Wine,
+            custom.perceived_sugar
+        )
+    );
+    assert_eq!(custom.extra_descriptors.len(), 1);
+    assert!(standard.extra_descriptors.is_empty());
+    assert_eq!(
+        descriptors::standard().describe(SWEETNESS, Beverage::Wine, custom.perceived_sugar),
+        standard.sweetness
+    );
+}
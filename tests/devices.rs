@@ -0,0 +1,38 @@
+#![cfg(feature = "serde")]
+
+use chrono::NaiveDate;
+use wine_maker::devices::{ImportError, import_readings};
+
+#[test]
+fn imports_tilt_csv_in_fahrenheit() {
+    let csv = "Timestamp,Timepoint,SG,Temp,Color,Beer,Comment\n\
+               2025-09-28 14:00:00,45928.58,1.090,68,RED,Merlot,\n\
+               2025-09-29 14:00:00,45929.58,1.071,70,RED,Merlot,vigorous\n";
+    let readings = import_readings(csv).unwrap();
+    assert_eq!(readings.len(), 2);
+    assert_eq!(
+        readings[0].taken_at.date(),
+        NaiveDate::from_ymd_opt(2025, 9, 28).unwrap()
+    );
+    assert_eq!(readings[1].specific_gravity, 1.071);
+    assert!((readings[0].temperature.unwrap() - 20.0).abs() < 1e-9);
+}
+
+#[test]
+fn imports_ispindel_json_lines_in_plato() {
+    let json = r#"{"name":"iSpindel001","ID":123,"angle":60.1,"temperature":21.5,"temp_units":"C","gravity":12.5,"timestamp":"2025-09-28T14:00:00Z"}
+{"name":"iSpindel001","ID":123,"angle":55.2,"temperature":22.0,"temp_units":"C","gravity":10.0,"timestamp":"2025-09-29T14:00:00Z"}"#;
+    let readings = import_readings(json).unwrap();
+    assert_eq!(readings.len(), 2);
+    assert!((readings[0].specific_gravity - 1.0505).abs() < 0.0005);
+    assert_eq!(readings[1].temperature, Some(22.0));
+}
+
+#[test]
+fn requires_a_gravity_column() {
+    let csv = "Timestamp,Temp\n2025-09-28 14:00:00,68\n";
+    assert!(matches!(
+        import_readings(csv),
+        Err(ImportError::MissingField("gravity"))
+    ));
+}
@@ -0,0 +1,111 @@
+use std::sync::LazyLock;
+
+use proptest::prelude::*;
+use wine_maker::dataset::{Dataset, WINE_DATA_CSV};
+use wine_maker::engine::{
+    Beverage, Closure, Inoculation, MAX_YEAST_TEMPERATURE, MIN_YEAST_TEMPERATURE, SimulationInput,
+    YEAST_ALCOHOL_TOLERANCE, run_simulation,
+};
+
+static WINE_DATA: LazyLock<Dataset> =
+    LazyLock::new(|| Dataset::from_csv_str(WINE_DATA_CSV).unwrap());
+
+fn simulation_input() -> impl Strategy<Value = SimulationInput> {
+    (
+        prop::sample::select(vec!["Cabernet Sauvignon", "Pinot Noir", "Riesling", "Gamay"]),
+        0u32..60,
+        prop::sample::select(vec!["Oak Barrel", "Steel Tank", "Clay Amphora"]),
+        0.0f64..400.0,
+        MIN_YEAST_TEMPERATURE..=MAX_YEAST_TEMPERATURE,
+        prop::sample::select(vec!["Cool", "Moderate", "Warm", ""]),
+        any::<u64>(),
+    )
+        .prop_map(
+            |(grape, days, container, sugar, temperature, climate, seed)| SimulationInput {
+                grape_type: grape.to_owned(),
+                fermentation_days: days,
+                container_type: container.to_owned(),
+                aging_container: String::new(),
+                aging_months: 0,
+                whole_cluster: 0.0,
+                press_wine: 0.0,
+                sugar_content: sugar,
+                temperature,
+                climate: climate.to_owned(),
+                seed: Some(seed),
+                restart_day: None,
+                excursions: Vec::new(),
+                yeast: String::new(),
+                inoculation: Inoculation::Single,
+                lees_months: 0,
+                stirs_per_month: 0,
+                closure: Closure::NaturalCork,
+                interventions: Vec::new(),
+                beverage: Beverage::Wine,
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn residual_sugar_is_never_negative(input in simulation_input()) {
+        let result = run_simulation(&input, &Dataset::default()).unwrap();
+        prop_assert!(result.residual_sugar >= 0.0, "residual sugar {}", result.residual_sugar);
+    }
+
+    #[test]
+    fn fraction_fermented_is_a_fraction(input in simulation_input()) {
+        let result = run_simulation(&input, &Dataset::default()).unwrap();
+        prop_assert!((0.0..=1.0).contains(&result.fraction_fermented));
+    }
+
+    #[test]
+    fn abv_never_exceeds_yeast_tolerance(input in simulation_input()) {
+        let result = run_simulation(&input, &Dataset::default()).unwrap();
+        prop_assert!(result.actual_abv <= YEAST_ALCOHOL_TOLERANCE);
+        prop_assert!(result.actual_abv <= result.potential_abv + 1e-9);
+    }
+
+    #[test]
+    fn sugar_is_conserved(input in simulation_input()) {
+        let result = run_simulation(&input, &Dataset::default()).unwrap();
+        let consumed = result.fraction_fermented * result.sugar_content;
+        prop_assert!((consumed + result.residual_sugar - result.sugar_content).abs() < 1e-6);
+    }
+
+    #[test]
+    fn longer_ferments_never_lose_alcohol(input in simulation_input(), extra_days in 0u32..30) {
+        let shorter = run_simulation(&input, &Dataset::default()).unwrap();
+        let longer_input = SimulationInput {
+            fermentation_days: input.fermentation_days + extra_days,
+            ..input
+        };
+        let longer = run_simulation(&longer_input, &Dataset::default()).unwrap();
+        prop_assert!(longer.actual_abv >= shorter.actual_abv);
+        prop_assert!(longer.residual_sugar <= shorter.residual_sugar);
+    }
+
+    #[test]
+    fn warmer_ferments_are_never_slower(input in simulation_input(), warmer_by in 0.0f64..10.0) {
+        let temperature = (input.temperature + warmer_by).min(MAX_YEAST_TEMPERATURE);
+        let cooler = run_simulation(&input, &Dataset::default()).unwrap();
+        let warmer = run_simulation(&SimulationInput { temperature, ..input }, &Dataset::default()).unwrap();
+        prop_assert!(warmer.fraction_fermented >= cooler.fraction_fermented - 1e-12);
+    }
+
+    #[test]
+    fn out_of_range_temperatures_fail(input in simulation_input(), offset in 0.1f64..30.0) {
+        let too_cold = SimulationInput { temperature: MIN_YEAST_TEMPERATURE - offset, ..input.clone() };
+        let too_hot = SimulationInput { temperature: MAX_YEAST_TEMPERATURE + offset, ..input };
+        prop_assert!(run_simulation(&too_cold, &Dataset::default()).is_err());
+        prop_assert!(run_simulation(&too_hot, &Dataset::default()).is_err());
+    }
+
+    #[test]
+    fn same_seed_gives_same_wine(input in simulation_input()) {
+        prop_assert_eq!(
+            run_simulation(&input, &WINE_DATA),
+            run_simulation(&input, &WINE_DATA)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    Beverage, Intervention, InterventionKind, SimulationInput, run_simulation,
+};
+use wine_maker::export::{COLUMNS, Cell, Spreadsheet};
+
+fn column(name: &str) -> usize {
+    COLUMNS.iter().position(|column| *column == name).unwrap()
+}
+
+#[test]
+fn one_row_per_wine_with_inputs_and_outputs() {
+    let input = SimulationInput {
+        seed: Some(7),
+        interventions: vec![
+            Intervention::new(1, InterventionKind::Nutrient),
+            Intervention::new(4, InterventionKind::Aeration),
+        ],
+        ..Beverage::Wine.defaults()
+    };
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    let mut sheet = Spreadsheet::default();
+    sheet.push("Cab", "2025-09-01", &input, Ok(&result));
+
+    let row = &sheet.rows()[0];
+    assert_eq!(row.len(), COLUMNS.len());
+    assert_eq!(row[column("Name")], Cell::from("Cab"));
+    assert_eq!(row[column("Sugar (g/L)")], Cell::Number(240.0));
+    assert_eq!(row[column("Seed")], Cell::from("7"));
+    assert_eq!(row[column("Restart day")], Cell::Empty);
+    assert_eq!(row[column("ABV (%)")], Cell::Number(result.actual_abv));
+    assert_eq!(row[column("Error")], Cell::Empty);
+    let Cell::Text(interventions) = &row[column("Interventions")] else {
+        panic!("interventions should be text");
+    };
+    assert!(
+        interventions.ends_with("day 4: Aeration"),
+        "{}",
+        interventions
+    );
+}
+
+#[test]
+fn failed_simulations_keep_their_inputs_and_the_error() {
+    let input = SimulationInput {
+        temperature: 60.0,
+        ..Beverage::Wine.defaults()
+    };
+    let err = run_simulation(&input, &Dataset::default()).unwrap_err();
+    let mut sheet = Spreadsheet::default();
+    sheet.push("Too hot", "", &input, Err(&err));
+
+    let row = &sheet.rows()[0];
+    assert_eq!(row.len(), COLUMNS.len());
+    assert_eq!(row[column("Temperature (°C)")], Cell::Number(60.0));
+    assert_eq!(row[column("ABV (%)")], Cell::Empty);
+    assert_eq!(row[column("Error")], Cell::from(err.to_string()));
+}
+
+#[test]
+fn csv_has_a_header_and_a_line_per_wine() {
+    let data = Dataset::default();
+    let mut sheet = Spreadsheet::default();
+    for beverage in Beverage::ALL {
+        let input = beverage.defaults();
+        let result = run_simulation(&input, &data).unwrap();
+        sheet.push(beverage.label(), "", &input, Ok(&result));
+    }
+
+    let csv = sheet.to_csv_string().unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 1 + Beverage::ALL.len());
+    assert!(lines[0].starts_with("Name,Date,Beverage,Grape,"));
+    assert!(lines[1].starts_with("Wine,,Wine,Cabernet Sauvignon,14,"));
+}
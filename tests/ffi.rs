@@ -0,0 +1,44 @@
+#![cfg(feature = "ffi")]
+
+use std::ffi::{CStr, CString, c_char};
+
+use wine_maker::ffi::{WM_ERR_BUFFER_TOO_SMALL, WM_ERR_INVALID_INPUT, WM_ERR_SIMULATION, wm_simulate};
+
+fn simulate(json: &str, capacity: usize) -> (isize, String) {
+    let json_in = CString::new(json).unwrap();
+    let mut out = vec![0 as c_char; capacity.max(1)];
+    let status = unsafe { wm_simulate(json_in.as_ptr(), out.as_mut_ptr(), capacity) };
+    let text = unsafe { CStr::from_ptr(out.as_ptr()) }
+        .to_string_lossy()
+        .into_owned();
+    (status, text)
+}
+
+#[test]
+fn writes_result_json() {
+    let (status, json) = simulate(
+        r#"{"grape_type": "Riesling", "fermentation_days": 10, "sugar_content": 190.0, "temperature": 14.0, "climate": "Cool", "seed": 7}"#,
+        4096,
+    );
+    assert_eq!(status, json.len() as isize);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value["actual_abv"].as_f64().unwrap() > 0.0);
+    assert!(value["narrative"].as_str().unwrap().contains("Riesling"));
+}
+
+#[test]
+fn reports_failed_fermentation() {
+    let (status, json) = simulate(r#"{"temperature": 50.0}"#, 4096);
+    assert_eq!(status, WM_ERR_SIMULATION);
+    assert!(json.contains("\"error\""));
+}
+
+#[test]
+fn rejects_invalid_json() {
+    assert_eq!(simulate("not json", 4096).0, WM_ERR_INVALID_INPUT);
+}
+
+#[test]
+fn rejects_small_buffers() {
+    assert_eq!(simulate(r#"{"temperature": 20.0}"#, 16).0, WM_ERR_BUFFER_TOO_SMALL);
+}
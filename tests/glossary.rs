@@ -0,0 +1,54 @@
+use wine_maker::glossary::{Glossary, TERMS, term};
+
+#[test]
+fn every_link_leads_to_a_term() {
+    for entry in &TERMS {
+        assert!(
+            TERMS.iter().filter(|other| other.key == entry.key).count() == 1,
+            "{} is defined twice",
+            entry.key
+        );
+        for key in entry.see_also {
+            assert!(
+                term(key).is_some(),
+                "{} links to unknown {}",
+                entry.key,
+                key
+            );
+        }
+    }
+}
+
+#[test]
+fn searches_names_abbreviations_then_definitions() {
+    let glossary = Glossary::default();
+    let found = glossary.search("yan");
+    assert_eq!(found[0].term.key, "yan");
+    assert_eq!(found[0].name, "Yeast assimilable nitrogen");
+
+    // Named first, then those that only mention it.
+    let found: Vec<&str> = glossary
+        .search("tannin")
+        .iter()
+        .map(|entry| entry.term.key)
+        .collect();
+    assert_eq!(found[0], "tannin");
+    assert!(found.contains(&"press_wine"));
+    assert_eq!(glossary.search("").len(), TERMS.len());
+    assert!(glossary.search("no such thing").is_empty());
+}
+
+#[test]
+fn translations_replace_the_english() {
+    let glossary = Glossary::from_csv_str(
+        "key,name,definition\n\
+         mlf,Fermentation malolactique,Des bactéries transforment l'acide malique en acide lactique.\n\
+         nonsense,Rien,Rien du tout.\n",
+    )
+    .unwrap();
+    let entry = glossary.entry("mlf").unwrap();
+    assert_eq!(entry.name, "Fermentation malolactique");
+    assert_eq!(entry.term.abbreviation, Some("MLF"));
+    assert_eq!(glossary.entry("ta").unwrap().name, "Titratable acidity");
+    assert_eq!(glossary.search("malolactique")[0].term.key, "mlf");
+}
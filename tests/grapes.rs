@@ -0,0 +1,83 @@
+use wine_maker::dataset::{Dataset, WINE_DATA_CSV};
+use wine_maker::engine::{Beverage, GRAPES, is_red_grape, run_simulation};
+use wine_maker::grapes::{GRAPE_PROFILES, StyleGoal, grape_profile, suggest_grapes, within};
+
+#[test]
+fn every_grape_has_a_profile() {
+    for grape in GRAPES {
+        let profile = grape_profile(grape).unwrap();
+        assert_eq!(profile.name, grape);
+    }
+    assert_eq!(grape_profile(" riesling ").unwrap().name, "Riesling");
+    assert!(grape_profile("Wildflower").is_none());
+}
+
+#[test]
+fn typical_values_fall_inside_the_ranges() {
+    for profile in GRAPE_PROFILES {
+        assert!(within(profile.sugar, profile.typical_sugar()));
+        assert!(within(profile.temperature, profile.typical_temperature()));
+        let (low, high) = profile.fermentation_days;
+        assert!((low..=high).contains(&profile.typical_fermentation_days()));
+        assert!(profile.acidity.0 < profile.acidity.1);
+    }
+}
+
+#[test]
+fn filled_in_defaults_simulate() {
+    let riesling = grape_profile("Riesling").unwrap();
+    let mut input = Beverage::Wine.defaults();
+    riesling.apply_to(&mut input);
+    assert_eq!(input.grape_type, "Riesling");
+    assert!(!within(riesling.sugar, 300.0));
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert!(result.actual_abv > 9.0 && result.actual_abv < 14.0);
+}
+
+#[test]
+fn reads_type_style_and_abv_when_the_dataset_has_them() {
+    let dataset = Dataset::from_csv_str(
+        "Grape,Characteristics,Type,Style,ABV\n\
+         Riesling,\"Lime, Slate\",White,Fresh & Elegant ,ABV 11.50%\n",
+    )
+    .unwrap();
+    let record = &dataset.records()[0];
+    assert_eq!(record.wine_type, "White");
+    assert_eq!(record.style, "Fresh & Elegant");
+    assert_eq!(record.abv, Some(11.5));
+
+    let bare = Dataset::from_csv_str("Grape,Characteristics\nRiesling,Lime\n").unwrap();
+    assert_eq!(bare.records()[0].style, "");
+    assert_eq!(bare.records()[0].abv, None);
+}
+
+#[test]
+fn suggests_grapes_of_the_right_color_in_style() {
+    let dataset = Dataset::from_csv_str(WINE_DATA_CSV).unwrap();
+    let reds = suggest_grapes(&dataset, "Warm", StyleGoal::BigTannicRed);
+    assert_eq!(reds[0].grape, "Shiraz");
+    assert!(reds.iter().all(|suggestion| is_red_grape(suggestion.grape)));
+    assert!(reds.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+    let whites = suggest_grapes(&dataset, "Cool", StyleGoal::LightDryWhite);
+    assert!(
+        whites
+            .iter()
+            .all(|suggestion| !is_red_grape(suggestion.grape))
+    );
+    assert!(whites.iter().all(|suggestion| suggestion.in_style > 0));
+}
+
+#[test]
+fn climate_lowers_grapes_that_would_not_ripen() {
+    let dataset = Dataset::from_csv_str(WINE_DATA_CSV).unwrap();
+    let shiraz = |climate| {
+        suggest_grapes(&dataset, climate, StyleGoal::BigTannicRed)
+            .into_iter()
+            .find(|suggestion| suggestion.grape == "Shiraz")
+            .unwrap()
+    };
+    let (warm, cool) = (shiraz("Warm"), shiraz("Cool"));
+    assert!(warm.suits_climate && !cool.suits_climate);
+    assert!((cool.score - warm.score / 2.0).abs() < 1e-9);
+}
@@ -0,0 +1,32 @@
+use wine_maker::inventory::{
+    BOTTLES, CORKS, SULFITE, StockItem, YEAST, batch_requirements, shortages,
+};
+
+#[test]
+fn sizes_supplies_to_the_batch() {
+    let needed = batch_requirements(23.0);
+    assert_eq!(
+        needed,
+        [
+            StockItem::new(YEAST, "packets", 1.0),
+            StockItem::new(SULFITE, "g", 2.0),
+            StockItem::new(BOTTLES, "bottles", 31.0),
+            StockItem::new(CORKS, "corks", 31.0),
+        ]
+    );
+    assert!(batch_requirements(0.0).is_empty());
+}
+
+#[test]
+fn reports_what_stock_cannot_cover() {
+    let stock = [
+        StockItem::new("yeast packets", "packets", 3.0),
+        StockItem::new(SULFITE, "g", 100.0),
+        StockItem::new(BOTTLES, "bottles", 24.0),
+    ];
+    let short = shortages(&stock, &batch_requirements(23.0));
+    let names: Vec<&str> = short.iter().map(|s| s.needed.name.as_str()).collect();
+    assert_eq!(names, [BOTTLES, CORKS]);
+    assert_eq!(short[0].missing(), 7.0);
+    assert_eq!(short[1].on_hand, 0.0);
+}
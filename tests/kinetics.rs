@@ -0,0 +1,227 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    Excursion, Inoculation, Intervention, InterventionKind, MAX_FERMENTATION_DAYS,
+    RESTART_YEAST_TOLERANCE, SUGAR_PER_ABV, SimulationError, SimulationInput,
+    YEAST_ALCOHOL_TOLERANCE, YEAST_DIE_OFF_WINDOW, fermentation_curve, fermentation_state,
+    fermentation_temperature, is_high_gravity, restart_day, run_simulation, temperature_on,
+    timeline,
+};
+use wine_maker::narrative::narrative;
+
+fn must(sugar_content: f64) -> SimulationInput {
+    SimulationInput {
+        grape_type: "Zinfandel".to_owned(),
+        fermentation_days: 40,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content,
+        temperature: 24.0,
+        climate: "Moderate".to_owned(),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn strong_musts_taper_towards_the_tolerance() {
+    let curve = fermentation_curve(&must(300.0)).unwrap();
+    let last = curve.last().unwrap();
+    assert!(last.abv < YEAST_ALCOHOL_TOLERANCE);
+    assert!(last.abv > YEAST_ALCOHOL_TOLERANCE - 0.5);
+
+    let gains: Vec<f64> = curve.windows(2).map(|w| w[1].abv - w[0].abv).collect();
+    assert!(gains.iter().all(|&gain| gain >= 0.0));
+    // Once past the slowdown, each day adds less than the one before.
+    let slowing = curve
+        .iter()
+        .position(|state| state.abv > YEAST_ALCOHOL_TOLERANCE - YEAST_DIE_OFF_WINDOW)
+        .unwrap();
+    assert!(gains[slowing..].windows(2).all(|w| w[1] <= w[0]));
+}
+
+#[test]
+fn light_musts_ferment_out_untouched() {
+    let potential = (YEAST_ALCOHOL_TOLERANCE - YEAST_DIE_OFF_WINDOW) * SUGAR_PER_ABV;
+    let last = *fermentation_curve(&must(potential - 10.0))
+        .unwrap()
+        .last()
+        .unwrap();
+    assert!(last.fraction_fermented > 0.99);
+}
+
+#[test]
+fn fresh_yeast_restarts_a_sluggish_finish() {
+    let stuck = must(300.0);
+    assert!(is_high_gravity(&stuck));
+    assert!(!is_high_gravity(&must(200.0)));
+
+    let restarted = SimulationInput {
+        restart_day: Some(10),
+        ..stuck.clone()
+    };
+    let stuck_curve = fermentation_curve(&stuck).unwrap();
+    let restarted_curve = fermentation_curve(&restarted).unwrap();
+    assert_eq!(stuck_curve[..=10], restarted_curve[..=10]);
+    let finish = restarted_curve.last().unwrap();
+    assert!(finish.abv > YEAST_ALCOHOL_TOLERANCE);
+    assert!(finish.abv < RESTART_YEAST_TOLERANCE);
+    assert!(finish.residual_sugar < stuck_curve.last().unwrap().residual_sugar);
+    assert!(restarted_curve.windows(2).all(|w| w[1].abv >= w[0].abv));
+}
+
+#[test]
+fn excursions_slow_or_stress_the_fermentation() {
+    let steady = SimulationInput {
+        fermentation_days: 10,
+        temperature: 20.0,
+        ..must(220.0)
+    };
+    let outage = SimulationInput {
+        excursions: vec![Excursion::power_outage(2)],
+        ..steady.clone()
+    };
+    let heat = SimulationInput {
+        excursions: vec![Excursion::heat_wave(2)],
+        ..steady.clone()
+    };
+    assert_eq!(temperature_on(&outage, 1), 20.0);
+    assert_eq!(temperature_on(&outage, 4), 10.0);
+    assert_eq!(temperature_on(&outage, 5), 20.0);
+
+    let data = Dataset::default();
+    let steady_result = run_simulation(&steady, &data).unwrap();
+    let outage_result = run_simulation(&outage, &data).unwrap();
+    let heat_result = run_simulation(&heat, &data).unwrap();
+    assert!(outage_result.actual_abv < steady_result.actual_abv);
+    assert!(heat_result.actual_abv > steady_result.actual_abv);
+
+    assert_eq!(steady_result.stall_risk, 0.0);
+    assert!(steady_result.off_flavors.is_empty());
+    assert!(outage_result.stall_risk > 0.0);
+    assert!(heat_result.stall_risk > outage_result.stall_risk);
+    assert!(heat_result.off_flavors[0].contains("fusel"));
+    assert!(narrative(&heat, &heat_result).contains("2 days at 32°C from day 2"));
+}
+
+#[test]
+fn the_timeline_drives_temperature_and_restarts() {
+    let outage = SimulationInput {
+        fermentation_days: 10,
+        temperature: 20.0,
+        excursions: vec![Excursion::power_outage(2)],
+        restart_day: Some(8),
+        interventions: vec![Intervention::new(6, InterventionKind::Aeration)],
+        ..must(220.0)
+    };
+    let days: Vec<(u32, InterventionKind)> = timeline(&outage)
+        .iter()
+        .map(|intervention| (intervention.day, intervention.kind))
+        .collect();
+    assert_eq!(
+        days,
+        [
+            (2, InterventionKind::Temperature),
+            (5, InterventionKind::Temperature),
+            (6, InterventionKind::Aeration),
+            (8, InterventionKind::Restart),
+        ]
+    );
+
+    // The same outage entered as temperature changes ferments the same way.
+    let entered = SimulationInput {
+        excursions: Vec::new(),
+        restart_day: None,
+        interventions: timeline(&outage),
+        ..outage.clone()
+    };
+    assert_eq!(restart_day(&entered), Some(8));
+    for day in 0..=10 {
+        assert_eq!(temperature_on(&entered, day), temperature_on(&outage, day));
+    }
+    assert_eq!(
+        fermentation_curve(&entered).unwrap(),
+        fermentation_curve(&outage).unwrap()
+    );
+}
+
+#[test]
+fn long_ferments_stop_at_the_limit() {
+    let long = SimulationInput {
+        fermentation_days: MAX_FERMENTATION_DAYS,
+        excursions: vec![Excursion::power_outage(2), Excursion::heat_wave(200)],
+        restart_day: Some(30),
+        ..must(300.0)
+    };
+    let curve = fermentation_curve(&long).unwrap();
+    assert_eq!(curve.len(), MAX_FERMENTATION_DAYS as usize + 1);
+    for day in [0, 4, 30, 31, 201, MAX_FERMENTATION_DAYS] {
+        let state = fermentation_state(&long, day).unwrap();
+        assert!((curve[day as usize].abv - state.abv).abs() < 1e-9);
+    }
+    let data = Dataset::default();
+    assert!(run_simulation(&long, &data).is_ok());
+
+    for days in [MAX_FERMENTATION_DAYS + 1, u32::MAX] {
+        let too_long = SimulationInput {
+            fermentation_days: days,
+            ..long.clone()
+        };
+        let error = SimulationError::TooManyDays(days);
+        assert_eq!(run_simulation(&too_long, &data).unwrap_err(), error);
+        assert_eq!(fermentation_curve(&too_long).unwrap_err(), error);
+    }
+}
+
+#[test]
+fn fermentation_temperature_shapes_the_aromas() {
+    let data = Dataset::default();
+    let hot = SimulationInput {
+        temperature: 32.0,
+        ..must(220.0)
+    };
+    let result = run_simulation(&hot, &data).unwrap();
+    assert_eq!(result.off_flavors, ["hot, solvent-like fusel alcohols"]);
+    assert_eq!(result.esters, "");
+    assert!(narrative(&hot, &result).contains("The warm fermentation left hot"));
+
+    let cold_white = SimulationInput {
+        grape_type: "Sauvignon Blanc".to_owned(),
+        temperature: 11.0,
+        ..must(220.0)
+    };
+    let result = run_simulation(&cold_white, &data).unwrap();
+    assert!(result.off_flavors.is_empty());
+    assert!(result.esters.contains("esters"));
+
+    // A long heat wave early on drags the whole ferment warm.
+    let heat_wave = SimulationInput {
+        excursions: vec![Excursion {
+            start_day: 0,
+            days: 6,
+            temperature: 34.0,
+        }],
+        ..cold_white.clone()
+    };
+    assert!(fermentation_temperature(&heat_wave) > fermentation_temperature(&cold_white));
+    assert!((fermentation_temperature(&cold_white) - 11.0).abs() < 1e-9);
+    let result = run_simulation(&heat_wave, &data).unwrap();
+    assert_eq!(result.esters, "");
+}
+
+#[test]
+fn slower_inoculations_weigh_late_excursions_more() {
+    let single = SimulationInput {
+        fermentation_days: 20,
+        temperature: 20.0,
+        excursions: vec![Excursion {
+            start_day: 10,
+            days: 4,
+            temperature: 30.0,
+        }],
+        ..must(220.0)
+    };
+    let co_inoculated = SimulationInput {
+        inoculation: Inoculation::CoInoculatedMalolactic,
+        ..single.clone()
+    };
+    // The slower ferment has more sugar left when the heat comes.
+    assert!(fermentation_temperature(&co_inoculated) > fermentation_temperature(&single));
+}
@@ -0,0 +1,27 @@
+use wine_maker::kit::{KIT_BATCH_VOLUME, Kit, KitSize};
+
+#[test]
+fn made_up_as_instructed_kits_start_at_the_printed_gravity() {
+    let kit = Kit::new("Merlot", KitSize::SixWeek);
+    assert_eq!(kit.volume(), KIT_BATCH_VOLUME);
+    let plan = kit.plan().unwrap();
+    assert!((plan.starting_gravity - 1.090).abs() < 1e-9);
+    assert_eq!(plan.rack_day, Some(8));
+    assert_eq!(plan.stabilize_day, Some(14));
+    assert_eq!(plan.bottle_day, Some(42));
+    assert_eq!(plan.input.fermentation_days, 14);
+}
+
+#[test]
+fn less_water_means_a_stronger_must() {
+    let mut kit = Kit::new("Merlot", KitSize::FourWeek);
+    let as_instructed = kit.sugar_content();
+    kit.water -= 3.0;
+    assert!((kit.sugar_content() - as_instructed * 23.0 / 20.0).abs() < 1e-9);
+
+    // Too concentrated for the yeast to finish dry.
+    kit.water = 5.0;
+    let plan = kit.plan().unwrap();
+    assert_eq!(plan.stabilize_day, None);
+    assert_eq!(plan.bottle_day, None);
+}
@@ -0,0 +1,58 @@
+use chrono::NaiveDate;
+use wine_maker::cellar::Batch;
+use wine_maker::engine::{InterventionKind, SimulationInput, specific_gravity};
+use wine_maker::log_sheet::{log_rows, log_sheet_html};
+use wine_maker::stage::Stage;
+
+fn batch() -> Batch {
+    Batch {
+        id: 1,
+        name: "Syrah <2025>".to_owned(),
+        started_on: NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(),
+        input: SimulationInput {
+            grape_type: "Syrah".to_owned(),
+            fermentation_days: 10,
+            sugar_content: 230.0,
+            temperature: 22.0,
+            ..SimulationInput::default()
+        },
+        predicted_abv: Some(13.2),
+        notes: String::new(),
+        volume: Some(23.0),
+    }
+}
+
+#[test]
+fn lists_every_fermentation_day_then_weekly_follow_ups() {
+    let rows = log_rows(&batch()).unwrap();
+    let days: Vec<u32> = rows.iter().map(|row| row.day).collect();
+    assert_eq!(days[..11], (0..=10).collect::<Vec<_>>()[..]);
+    assert_eq!(days[11..], [17, 24, 31]);
+    assert_eq!(rows[0].date, batch().started_on);
+    assert_eq!(rows[0].stage, Stage::Lag);
+    assert!(
+        (rows[0].specific_gravity - specific_gravity(rows[0].residual_sugar, 0.0)).abs() < 1e-9
+    );
+    assert!(
+        rows.windows(2)
+            .all(|pair| pair[1].specific_gravity <= pair[0].specific_gravity)
+    );
+}
+
+#[test]
+fn marks_planned_work_on_its_day() {
+    let mut batch = batch();
+    batch.input.restart_day = Some(6);
+    let rows = log_rows(&batch).unwrap();
+    assert_eq!(rows[6].planned.len(), 1);
+    assert_eq!(rows[6].planned[0].kind, InterventionKind::Restart);
+    assert!(rows[5].planned.is_empty());
+}
+
+#[test]
+fn the_page_has_a_row_per_day_and_escapes_the_name() {
+    let html = log_sheet_html(&batch()).unwrap();
+    assert!(html.contains("<h1>Syrah &lt;2025&gt;</h1>"));
+    assert!(html.contains("23 L, 13.2% ABV predicted"));
+    assert_eq!(html.matches("<tr><td>").count(), 14);
+}
@@ -0,0 +1,24 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Beverage, MODEL_VERSION, run_simulation};
+use wine_maker::model_changelog::{CHANGELOG, changes_since, is_outdated};
+
+#[test]
+fn results_are_stamped_with_the_current_model() {
+    let result = run_simulation(&Beverage::Wine.defaults(), &Dataset::default()).unwrap();
+    assert_eq!(result.model_version, MODEL_VERSION);
+    assert!(!is_outdated(result.model_version));
+}
+
+#[test]
+fn changelog_covers_every_version_in_order() {
+    let versions: Vec<u32> = CHANGELOG.iter().map(|change| change.version).collect();
+    assert_eq!(versions, (1..=MODEL_VERSION).collect::<Vec<_>>());
+    assert!(CHANGELOG.iter().all(|change| !change.changes.is_empty()));
+}
+
+#[test]
+fn unversioned_results_see_every_change() {
+    assert!(is_outdated(0));
+    assert_eq!(changes_since(0).count(), CHANGELOG.len());
+    assert_eq!(changes_since(MODEL_VERSION).count(), 0);
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "mqtt")]
+
+use chrono::NaiveDate;
+use serde_json::{Value, json};
+use wine_maker::cellar::{Batch, BatchStatus, Task};
+use wine_maker::engine::SimulationInput;
+use wine_maker::mqtt::{HomeAssistantConfig, discovery_messages, parse_message, state_message};
+use wine_maker::stage::Stage;
+
+fn batch() -> Batch {
+    Batch {
+        id: 7,
+        name: "Garage merlot".to_owned(),
+        started_on: NaiveDate::from_ymd_opt(2025, 9, 20).unwrap(),
+        input: SimulationInput {
+            grape_type: "Merlot".to_owned(),
+            ..SimulationInput::default()
+        },
+        predicted_abv: None,
+        notes: String::new(),
+        volume: None,
+    }
+}
+
+#[test]
+fn bare_numbers_follow_the_topic() {
+    assert_eq!(parse_message(b"1.046", true), (Some(1.046), None));
+    assert_eq!(parse_message(b" 18.5\n", false), (None, Some(18.5)));
+    // Plato from an iSpindel and °F from a Tilt bridge are converted.
+    let (gravity, _) = parse_message(b"12", true);
+    assert!((gravity.unwrap() - 1.048).abs() < 0.001);
+    let (_, temperature) = parse_message(b"68", false);
+    assert!((temperature.unwrap() - 20.0).abs() < 1e-9);
+}
+
+#[test]
+fn json_messages_carry_both_readings() {
+    assert_eq!(
+        parse_message(br#"{"gravity": 1.02, "temperature": 19.0}"#, true),
+        (Some(1.02), Some(19.0))
+    );
+    let (gravity, temperature) =
+        parse_message(br#"{"SG": "1.010", "Temp": 64.4, "temp_units": "F"}"#, true);
+    assert_eq!(gravity, Some(1.01));
+    assert!((temperature.unwrap() - 18.0).abs() < 1e-9);
+    assert_eq!(
+        parse_message(br#"{"temperature": 21.5}"#, false),
+        (None, Some(21.5))
+    );
+}
+
+#[test]
+fn malformed_payloads_read_as_nothing() {
+    for payload in [
+        &b""[..],
+        b"n/a",
+        b"{\"gravity\": ",
+        b"{\"gravity\": \"high\"}",
+        b"[1.02]",
+        b"\xff\xfe",
+    ] {
+        assert_eq!(parse_message(payload, true), (None, None));
+        assert_eq!(parse_message(payload, false), (None, None));
+    }
+}
+
+#[test]
+fn discovery_announces_each_sensor_of_the_batch() {
+    let config = HomeAssistantConfig::default();
+    let messages = discovery_messages(&config, &batch());
+    let topics: Vec<&str> = messages.iter().map(|(topic, _)| topic.as_str()).collect();
+    assert_eq!(
+        topics,
+        [
+            "homeassistant/sensor/wine_maker_batch_7/days_remaining/config",
+            "homeassistant/sensor/wine_maker_batch_7/abv/config",
+            "homeassistant/sensor/wine_maker_batch_7/specific_gravity/config",
+            "homeassistant/sensor/wine_maker_batch_7/stage/config",
+            "homeassistant/sensor/wine_maker_batch_7/next_task/config",
+            "homeassistant/sensor/wine_maker_batch_7/next_task_due/config",
+        ]
+    );
+
+    let payloads: Vec<Value> = messages
+        .iter()
+        .map(|(_, payload)| serde_json::from_str(payload).unwrap())
+        .collect();
+    for payload in &payloads {
+        assert_eq!(payload["state_topic"], "wine-maker/batch/7/state");
+        assert_eq!(
+            payload["device"]["identifiers"],
+            json!(["wine_maker_batch_7"])
+        );
+        assert_eq!(payload["device"]["name"], "Garage merlot");
+        assert_eq!(payload["device"]["model"], "Merlot");
+    }
+    let abv = &payloads[1];
+    assert_eq!(abv["unique_id"], "wine_maker_batch_7_abv");
+    assert_eq!(abv["value_template"], "{{ value_json.abv }}");
+    assert_eq!(abv["unit_of_measurement"], "%");
+    assert!(abv.get("device_class").is_none());
+    assert_eq!(payloads[0]["device_class"], "duration");
+    assert_eq!(payloads[5]["device_class"], "date");
+}
+
+#[test]
+fn state_is_published_under_the_state_prefix() {
+    let config = HomeAssistantConfig {
+        state_prefix: "cellar".to_owned(),
+        ..HomeAssistantConfig::default()
+    };
+    let status = BatchStatus {
+        day: 5,
+        days_remaining: 9,
+        abv: 6.4567,
+        residual_sugar: 110.04,
+        specific_gravity: 1.04249,
+        measured_on: NaiveDate::from_ymd_opt(2025, 9, 24),
+        stage: Some(Stage::Primary),
+        next_task: Some(Task {
+            uid: "press".to_owned(),
+            due_on: NaiveDate::from_ymd_opt(2025, 10, 4).unwrap(),
+            title: "Press".to_owned(),
+            done: false,
+        }),
+    };
+    let (topic, payload) = state_message(&config, 7, &status);
+    assert_eq!(topic, "cellar/batch/7/state");
+    assert_eq!(
+        serde_json::from_str::<Value>(&payload).unwrap(),
+        json!({
+            "day": 5,
+            "days_remaining": 9,
+            "abv": 6.5,
+            "specific_gravity": 1.042,
+            "residual_sugar": 110.0,
+            "measured_on": "2025-09-24",
+            "stage": "Primary fermentation",
+            "next_task": "Press",
+            "next_task_due": "2025-10-04",
+        })
+    );
+
+    let planned = BatchStatus {
+        measured_on: None,
+        stage: None,
+        next_task: None,
+        ..status
+    };
+    let (_, payload) = state_message(&config, 7, &planned);
+    let payload: Value = serde_json::from_str(&payload).unwrap();
+    assert!(payload["measured_on"].is_null());
+    assert!(payload["stage"].is_null());
+    assert!(payload["next_task_due"].is_null());
+}
@@ -0,0 +1,102 @@
+use wine_maker::dataset::{Dataset, WineRecord};
+use wine_maker::engine::{Beverage, Closure, Inoculation, SimulationInput, run_simulation};
+use wine_maker::narrative::narrative;
+
+// One record per grape, so the seeded pick is the same no matter how the RNG
+// maps seeds to indices.
+fn fixture_data() -> Dataset {
+    [
+        ("Cabernet Sauvignon", "Blackcurrant, Cedar, Tobacco"),
+        ("Pinot Noir", "Red Cherry, Raspberry, Forest Floor"),
+        ("Riesling", "Lime, Green Apple, Petrol"),
+        ("Tempranillo", "Vanilla, Blackberry, Leather"),
+    ]
+    .into_iter()
+    .map(|(grape, characteristics)| WineRecord {
+        grape: grape.to_owned(),
+        characteristics: characteristics.to_owned(),
+        ..WineRecord::default()
+    })
+    .collect::<Vec<_>>()
+    .into()
+}
+
+fn simulate(
+    grape: &str,
+    days: u32,
+    container: &str,
+    sugar: f64,
+    temperature: f64,
+    climate: &str,
+) -> String {
+    let input = SimulationInput {
+        grape_type: grape.to_owned(),
+        fermentation_days: days,
+        container_type: container.to_owned(),
+        aging_container: String::new(),
+        aging_months: 0,
+        whole_cluster: 0.0,
+        press_wine: 0.0,
+        sugar_content: sugar,
+        temperature,
+        climate: climate.to_owned(),
+        seed: Some(42),
+        restart_day: None,
+        excursions: Vec::new(),
+        yeast: String::new(),
+        inoculation: Inoculation::Single,
+        lees_months: 0,
+        stirs_per_month: 0,
+        closure: Closure::NaturalCork,
+        interventions: Vec::new(),
+        beverage: Beverage::Wine,
+    };
+    match run_simulation(&input, &fixture_data()) {
+        Ok(result) => narrative(&input, &result),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[test]
+fn warm_cabernet_in_oak_hits_alcohol_cap() {
+    insta::assert_snapshot!(simulate(
+        "Cabernet Sauvignon",
+        14,
+        "Oak Barrel",
+        260.0,
+        24.0,
+        "Warm"
+    ));
+}
+
+#[test]
+fn cool_riesling_in_steel_stays_sweet() {
+    insta::assert_snapshot!(simulate("Riesling", 6, "Steel Tank", 190.0, 12.0, "Cool"));
+}
+
+#[test]
+fn moderate_pinot_noir_in_amphora() {
+    insta::assert_snapshot!(simulate(
+        "Pinot Noir",
+        10,
+        "Clay Amphora",
+        210.0,
+        18.0,
+        "Moderate"
+    ));
+}
+
+#[test]
+fn short_tempranillo_ferment_without_climate() {
+    insta::assert_snapshot!(simulate("Tempranillo", 3, "Steel Tank", 220.0, 20.0, ""));
+}
+
+#[test]
+fn grape_missing_from_dataset() {
+    insta::assert_snapshot!(simulate("Zinfandel", 12, "Oak Barrel", 250.0, 22.0, "Warm"));
+}
+
+#[test]
+fn temperature_out_of_range_fails() {
+    insta::assert_snapshot!(simulate("Merlot", 10, "Oak Barrel", 220.0, 45.0, "Moderate"));
+}
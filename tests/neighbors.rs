@@ -0,0 +1,67 @@
+use wine_maker::dataset::{Dataset, WINE_DATA_CSV};
+use wine_maker::engine::{Beverage, SimulationInput, run_simulation};
+use wine_maker::neighbors::{
+    ColumnMapping, csv_headers, describe, load_reference_wines, nearest_wines,
+};
+
+// Laid out like a wine-review dataset rather than `WineDataset.csv`.
+const REVIEWS: &str = "country,description,province,title,variety,alcohol
+US,Jammy,California,Ridge Lytton Springs,Zinfandel,14.2
+Italy,Bright,Tuscany,Chianti Classico,Sangiovese,13.5
+Germany,Racy,Mosel,Dr. Loosen Riesling,Riesling,8.5
+US,Brambly,California,,Zinfandel,15.5
+";
+
+fn zinfandel() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Zinfandel".to_owned(),
+        sugar_content: 245.0,
+        climate: "Warm".to_owned(),
+        ..Beverage::Wine.defaults()
+    }
+}
+
+#[test]
+fn guesses_a_mapping_from_common_headers() {
+    let headers = csv_headers(REVIEWS).unwrap();
+    let mapping = ColumnMapping::guess(&headers).unwrap();
+    assert_eq!(mapping.grape, "variety");
+    assert_eq!(mapping.name.as_deref(), Some("title"));
+    assert_eq!(mapping.abv.as_deref(), Some("alcohol"));
+    assert_eq!(mapping.region.as_deref(), Some("province"));
+    assert_eq!(mapping.wine_type, None);
+    assert!(ColumnMapping::guess(&["description".to_owned()]).is_none());
+}
+
+#[test]
+fn finds_the_closest_wines() {
+    let mapping = ColumnMapping::guess(&csv_headers(REVIEWS).unwrap()).unwrap();
+    let wines = load_reference_wines(REVIEWS, &mapping).unwrap();
+    assert_eq!(wines.len(), 4);
+    let input = zinfandel();
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    let neighbors = nearest_wines(&input, &result, &wines, 2);
+    assert_eq!(neighbors.len(), 2);
+    assert!(
+        neighbors
+            .iter()
+            .all(|neighbor| neighbor.wine.grape == "Zinfandel")
+    );
+    assert!(neighbors[0].distance <= neighbors[1].distance);
+    assert_eq!(
+        describe(&neighbors[0]),
+        "Resembles Ridge Lytton Springs (California, US), a Zinfandel at ~14.2% ABV"
+    );
+}
+
+#[test]
+fn reads_the_bundled_dataset_with_the_default_mapping() {
+    let wines = load_reference_wines(WINE_DATA_CSV, &ColumnMapping::default()).unwrap();
+    assert!(wines.len() > 1000);
+    assert!(wines.iter().all(|wine| !wine.grape.is_empty()));
+    let mapping = ColumnMapping {
+        grape: "Varietal".to_owned(),
+        ..ColumnMapping::default()
+    };
+    assert!(load_reference_wines(WINE_DATA_CSV, &mapping).is_err());
+}
@@ -0,0 +1,63 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{SimulationInput, run_simulation};
+use wine_maker::optimizer::{SearchSpace, optimize, quality};
+
+fn plan() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        fermentation_days: 21,
+        container_type: "Oak Barrel".to_owned(),
+        sugar_content: 230.0,
+        temperature: 24.0,
+        climate: "Moderate".to_owned(),
+        seed: Some(1),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn faults_cost_points() {
+    let data = Dataset::default();
+    let plan = plan();
+    let clean = quality(&plan, &run_simulation(&plan, &data).unwrap());
+    let hot = SimulationInput {
+        temperature: 32.0,
+        ..plan
+    };
+    let hot = quality(&hot, &run_simulation(&hot, &data).unwrap());
+    assert!(hot.score < clean.score);
+    assert!(
+        hot.factors
+            .iter()
+            .any(|(factor, _)| *factor == "Off-flavors")
+    );
+    assert!((0.0..=100.0).contains(&clean.score));
+}
+
+#[test]
+fn finds_the_best_plans_for_the_fruit() {
+    let data = Dataset::default();
+    let plan = plan();
+    let space = SearchSpace::default();
+    let plans = optimize(&plan, &space, &data, 5);
+    assert_eq!(plans.len(), 5);
+    assert!(
+        plans
+            .windows(2)
+            .all(|w| w[0].quality.score >= w[1].quality.score)
+    );
+    for best in &plans {
+        assert_eq!(best.input.grape_type, "Merlot");
+        assert_eq!(best.input.sugar_content, 230.0);
+        assert!(best.result.off_flavors.is_empty());
+    }
+    // The best of the search does at least as well as the plan as given.
+    let given = quality(&plan, &run_simulation(&plan, &data).unwrap());
+    assert!(plans[0].quality.score >= given.score);
+
+    let narrow = SearchSpace {
+        temperatures: vec![50.0],
+        ..space
+    };
+    assert!(optimize(&plan, &narrow, &data, 5).is_empty());
+}
@@ -0,0 +1,68 @@
+use wine_maker::engine::{
+    Beverage, Closure, Excursion, Inoculation, Intervention, InterventionKind, SimulationInput,
+};
+use wine_maker::permalink::{PermalinkError, decode, encode};
+
+fn everything() -> SimulationInput {
+    SimulationInput {
+        beverage: Beverage::Wine,
+        grape_type: "Pinot Noir".to_owned(),
+        fermentation_days: 12,
+        container_type: "Oak Barrel".to_owned(),
+        aging_container: "Clay Amphora".to_owned(),
+        aging_months: 9,
+        whole_cluster: 30.0,
+        press_wine: 12.5,
+        sugar_content: 231.7,
+        temperature: 0.1 + 0.2,
+        climate: "Cool".to_owned(),
+        seed: Some(u64::MAX),
+        restart_day: Some(6),
+        excursions: vec![Excursion::heat_wave(3)],
+        yeast: "EC-1118".to_owned(),
+        inoculation: Inoculation::SequentialMalolactic,
+        lees_months: 2,
+        stirs_per_month: 1,
+        closure: Closure::Diam,
+        interventions: vec![
+            Intervention::new(1, InterventionKind::Nutrient),
+            Intervention {
+                day: 5,
+                kind: InterventionKind::Temperature,
+                amount: 17.25,
+            },
+        ],
+    }
+}
+
+#[test]
+fn round_trips_every_input_exactly() {
+    let input = everything();
+    let code = encode(&input);
+    assert!(
+        code.bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_'),
+        "{}",
+        code
+    );
+    assert_eq!(decode(&code).unwrap(), input);
+
+    for beverage in Beverage::ALL {
+        let input = beverage.defaults();
+        assert_eq!(decode(&encode(&input)).unwrap(), input);
+    }
+}
+
+#[test]
+fn accepts_a_whole_link() {
+    let input = everything();
+    let link = format!("https://example.com/wine-maker/#sim={}\n", encode(&input));
+    assert_eq!(decode(&link).unwrap(), input);
+}
+
+#[test]
+fn rejects_bad_codes() {
+    assert_eq!(decode("not a code!"), Err(PermalinkError::Malformed));
+    let code = encode(&everything());
+    assert!(decode(&code[..code.len() / 2]).is_err());
+}
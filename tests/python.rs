@@ -0,0 +1,103 @@
+#![cfg(feature = "python")]
+
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+fn with_module(test: impl for<'py> FnOnce(Python<'py>, Bound<'py, PyModule>)) {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "wine_maker").unwrap();
+        wine_maker::python::wine_maker(&module).unwrap();
+        test(py, module);
+    });
+}
+
+fn riesling(py: Python<'_>) -> Bound<'_, PyDict> {
+    let inputs = PyDict::new(py);
+    inputs.set_item("grape_type", "Riesling").unwrap();
+    inputs.set_item("fermentation_days", 10).unwrap();
+    inputs.set_item("sugar_content", 190.0).unwrap();
+    inputs.set_item("temperature", 14.0).unwrap();
+    inputs.set_item("climate", "Cool").unwrap();
+    inputs.set_item("seed", 42).unwrap();
+    inputs
+}
+
+#[test]
+fn run_simulation_returns_result_fields_and_narrative() {
+    with_module(|py, module| {
+        let result = module
+            .getattr("run_simulation")
+            .unwrap()
+            .call1((riesling(py),))
+            .unwrap();
+        let result = result.downcast::<PyDict>().unwrap();
+        let get = |key: &str| result.get_item(key).unwrap().unwrap();
+
+        assert!(get("actual_abv").extract::<f64>().unwrap() > 0.0);
+        assert!(get("residual_sugar").extract::<f64>().unwrap() >= 0.0);
+        assert!(get("sweetness").extract::<String>().is_ok());
+        assert!(get("balance_faults").extract::<Vec<String>>().is_ok());
+        assert!(get("off_flavors").extract::<Vec<String>>().is_ok());
+        assert!(get("needs_degassing").extract::<bool>().is_ok());
+        assert!(
+            get("narrative")
+                .extract::<String>()
+                .unwrap()
+                .contains("Riesling")
+        );
+    });
+}
+
+#[test]
+fn seeded_runs_repeat() {
+    with_module(|py, module| {
+        let run = module.getattr("run_simulation").unwrap();
+        let abv = || {
+            run.call1((riesling(py),))
+                .unwrap()
+                .get_item("actual_abv")
+                .unwrap()
+                .extract::<f64>()
+                .unwrap()
+        };
+        assert_eq!(abv(), abv());
+    });
+}
+
+#[test]
+fn rejects_unknown_input_keys() {
+    with_module(|py, module| {
+        let inputs = riesling(py);
+        inputs.set_item("sugar", 190.0).unwrap();
+        let err = module
+            .getattr("run_simulation")
+            .unwrap()
+            .call1((inputs,))
+            .unwrap_err();
+        assert!(err.is_instance_of::<PyKeyError>(py));
+        assert!(err.to_string().contains("\"sugar\""));
+    });
+}
+
+#[test]
+fn rejects_unknown_labels_and_failed_fermentations() {
+    with_module(|py, module| {
+        let run = module.getattr("run_simulation").unwrap();
+
+        let inputs = riesling(py);
+        inputs.set_item("closure", "Wax").unwrap();
+        let err = run.call1((inputs,)).unwrap_err();
+        assert!(err.is_instance_of::<PyValueError>(py));
+        assert!(err.to_string().contains("unknown closure"));
+
+        let inputs = riesling(py);
+        inputs.set_item("temperature", 50.0).unwrap();
+        assert!(
+            run.call1((inputs,))
+                .unwrap_err()
+                .is_instance_of::<PyValueError>(py)
+        );
+    });
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "qr")]
+
+use wine_maker::engine::{Beverage, SimulationInput};
+use wine_maker::permalink::encode;
+use wine_maker::qr::{QrImage, recipe_summary};
+
+#[test]
+fn encodes_a_share_code() {
+    let input = Beverage::Wine.defaults();
+    let image = QrImage::new(&encode(&input)).unwrap();
+    // Versions grow by four modules from 21.
+    assert!(image.width() >= 21);
+    assert_eq!((image.width() - 21) % 4, 0);
+    // The finder pattern's corner.
+    assert!(image.is_dark(0, 0));
+
+    let svg = image.to_svg(4);
+    let side = (image.width() + 8) * 4;
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains(&format!("width=\"{}\"", side)));
+    assert!(svg.ends_with("</svg>"));
+}
+
+#[test]
+fn too_much_text_is_an_error() {
+    assert!(QrImage::new(&"x".repeat(8000)).is_err());
+}
+
+#[test]
+fn the_recipe_reads_without_the_app() {
+    let input = SimulationInput {
+        yeast: "EC-1118".to_owned(),
+        aging_months: 6,
+        seed: Some(42),
+        ..Beverage::Wine.defaults()
+    };
+    let recipe = recipe_summary(&input);
+    assert!(recipe.starts_with("Cabernet Sauvignon (Wine)\n240 g/L sugar"));
+    assert!(recipe.contains("14 days at 24.0 °C in Oak Barrel"));
+    assert!(recipe.contains("Aged 6 months in Oak Barrel"));
+    assert!(recipe.ends_with("Seed: 42"));
+}
@@ -0,0 +1,50 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::rate_constant;
+use wine_maker::quiz::{Topic, quiz};
+
+#[test]
+fn a_quiz_covers_every_topic_and_repeats_for_a_seed() {
+    let data = Dataset::default();
+    let questions = quiz(11, Topic::ALL.len(), &data).unwrap();
+    for topic in Topic::ALL {
+        assert!(
+            questions.iter().any(|question| question.topic == topic),
+            "no {} question",
+            topic.label()
+        );
+    }
+    assert_eq!(questions, quiz(11, Topic::ALL.len(), &data).unwrap());
+    assert_ne!(questions, quiz(12, Topic::ALL.len(), &data).unwrap());
+}
+
+#[test]
+fn answers_follow_the_model() {
+    let questions = quiz(3, 60, &Dataset::default()).unwrap();
+    assert!(questions.iter().any(|question| question.answer));
+    assert!(questions.iter().any(|question| !question.answer));
+    for question in &questions {
+        assert!(question.is_correct(question.answer));
+        assert!(!question.is_correct(!question.answer));
+        assert!(question.explanation.starts_with("The model"));
+    }
+
+    // The speed-ups quoted are the engine's own.
+    for question in questions
+        .iter()
+        .filter(|question| question.topic == Topic::Temperature)
+    {
+        let degrees: Vec<f64> = question
+            .statement
+            .split(' ')
+            .filter_map(|word| word.parse().ok())
+            .collect();
+        let speed_up = rate_constant(degrees[1]) / rate_constant(degrees[0]);
+        assert!(
+            question
+                .explanation
+                .contains(&format!("{:.1} times", speed_up)),
+            "{}",
+            question.explanation
+        );
+    }
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "serde")]
+
+use wine_maker::engine::{
+    Beverage, Closure, Excursion, Inoculation, Intervention, InterventionKind, SimulationInput,
+};
+use wine_maker::recipe::{Recipe, RecipeError};
+
+#[test]
+fn round_trips_every_input() {
+    let recipe = Recipe {
+        name: "Estate Pinot".to_owned(),
+        notes: "Pick at dawn.".to_owned(),
+        input: SimulationInput {
+            beverage: Beverage::Wine,
+            grape_type: "Pinot Noir".to_owned(),
+            fermentation_days: 12,
+            container_type: "Oak Barrel".to_owned(),
+            aging_container: "Clay Amphora".to_owned(),
+            aging_months: 9,
+            whole_cluster: 30.0,
+            press_wine: 15.0,
+            sugar_content: 231.5,
+            temperature: 21.3,
+            climate: "Cool".to_owned(),
+            seed: Some(42),
+            restart_day: Some(8),
+            excursions: vec![Excursion::power_outage(4)],
+            yeast: "EC-1118".to_owned(),
+            inoculation: Inoculation::SequentialMalolactic,
+            lees_months: 2,
+            stirs_per_month: 1,
+            closure: Closure::Diam,
+            interventions: vec![Intervention {
+                day: 1,
+                kind: InterventionKind::Nutrient,
+                amount: 45.0,
+            }],
+        },
+    };
+    assert_eq!(Recipe::from_json(&recipe.to_json()).unwrap(), recipe);
+}
+
+#[test]
+fn fills_in_a_minimal_recipe_from_the_beverage_defaults() {
+    let recipe = Recipe::from_json(
+        r#"{
+            "format": "wine-maker-recipe",
+            "version": 1,
+            "name": "Farmhouse cider",
+            "type": "Cider",
+            "og": 1.05,
+            "additions": [{ "day": 2, "kind": "Aeration" }]
+        }"#,
+    )
+    .unwrap();
+    let defaults = Beverage::Cider.defaults();
+    assert_eq!(recipe.name, "Farmhouse cider");
+    assert_eq!(recipe.input.grape_type, defaults.grape_type);
+    assert_eq!(recipe.input.temperature, defaults.temperature);
+    assert!((90.0..140.0).contains(&recipe.input.sugar_content));
+    assert_eq!(
+        recipe.input.interventions,
+        vec![Intervention::new(2, InterventionKind::Aeration)]
+    );
+}
+
+#[test]
+fn rejects_other_formats_and_unknown_labels() {
+    assert!(matches!(
+        Recipe::from_json(r#"{ "format": "beerxml", "version": 1, "type": "Wine" }"#),
+        Err(RecipeError::UnknownFormat(_))
+    ));
+    assert!(matches!(
+        Recipe::from_json(r#"{ "format": "wine-maker-recipe", "version": 1, "type": "Wine" }"#),
+        Err(RecipeError::MissingSugar)
+    ));
+    assert!(matches!(
+        Recipe::from_json(
+            r#"{ "format": "wine-maker-recipe", "version": 1, "type": "Wine",
+                 "og": 1.09, "closure": "Wax" }"#
+        ),
+        Err(RecipeError::UnknownLabel("closure", _))
+    ));
+}
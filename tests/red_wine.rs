@@ -0,0 +1,74 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{FREE_RUN_SHARE, SimulationInput, is_red_grape, run_simulation};
+use wine_maker::narrative::narrative;
+
+fn pinot(whole_cluster: f64) -> SimulationInput {
+    SimulationInput {
+        grape_type: "Pinot Noir".to_owned(),
+        fermentation_days: 21,
+        container_type: "Steel Tank".to_owned(),
+        whole_cluster,
+        sugar_content: 230.0,
+        temperature: 24.0,
+        climate: "Moderate".to_owned(),
+        seed: Some(3),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn stems_add_tannin_and_water() {
+    let destemmed = run_simulation(&pinot(0.0), &Dataset::default()).unwrap();
+    let some = run_simulation(&pinot(30.0), &Dataset::default()).unwrap();
+    let whole = run_simulation(&pinot(100.0), &Dataset::default()).unwrap();
+
+    assert_eq!(destemmed.tannin_level, "delicate, low tannins");
+    assert!(some.tannin_level.contains("stem spice"));
+    assert!(whole.tannin_level.contains("green, spicy stem tannins"));
+    assert!(whole.actual_abv < destemmed.actual_abv);
+    assert!(destemmed.actual_abv - whole.actual_abv < 0.5);
+}
+
+#[test]
+fn only_red_grapes_take_stems() {
+    assert!(is_red_grape("syrah"));
+    assert!(!is_red_grape("Chardonnay"));
+    let chardonnay = SimulationInput {
+        grape_type: "Chardonnay".to_owned(),
+        ..pinot(100.0)
+    };
+    let result = run_simulation(&chardonnay, &Dataset::default()).unwrap();
+    assert_eq!(result.tannin_level, "minimal tannins");
+    let no_stems = SimulationInput {
+        whole_cluster: 0.0,
+        ..chardonnay
+    };
+    let baseline = run_simulation(&no_stems, &Dataset::default()).unwrap();
+    assert_eq!(result.actual_abv, baseline.actual_abv);
+}
+
+#[test]
+fn press_wine_trades_harsher_tannins_for_yield() {
+    let free_run = run_simulation(&pinot(0.0), &Dataset::default()).unwrap();
+    assert!((free_run.wine_yield - FREE_RUN_SHARE).abs() < 1e-9);
+
+    let pressed = SimulationInput {
+        press_wine: 100.0,
+        ..pinot(0.0)
+    };
+    let result = run_simulation(&pressed, &Dataset::default()).unwrap();
+    assert!((result.wine_yield - 1.0).abs() < 1e-9);
+    assert!(result.tannin_level.contains("astringent press wine"));
+    assert!(narrative(&pressed, &result).contains("kept 100% of the wine"));
+
+    let white = SimulationInput {
+        grape_type: "Riesling".to_owned(),
+        ..pressed
+    };
+    assert_eq!(
+        run_simulation(&white, &Dataset::default())
+            .unwrap()
+            .wine_yield,
+        1.0
+    );
+}
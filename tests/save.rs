@@ -0,0 +1,114 @@
+#![cfg(feature = "serde")]
+
+use wine_maker::career::Career;
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    Beverage, Closure, Inoculation, MODEL_VERSION, SimulationInput, SimulationResult,
+    run_simulation,
+};
+use wine_maker::save::{CURRENT_VERSION, SaveError, SaveFile};
+
+fn input() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Riesling".to_owned(),
+        fermentation_days: 10,
+        container_type: "Steel Tank".to_owned(),
+        aging_container: String::new(),
+        aging_months: 0,
+        whole_cluster: 0.0,
+        press_wine: 0.0,
+        sugar_content: 190.0,
+        temperature: 14.0,
+        climate: "Cool".to_owned(),
+        seed: Some(42),
+        restart_day: None,
+        excursions: Vec::new(),
+        yeast: String::new(),
+        inoculation: Inoculation::Single,
+        lees_months: 0,
+        stirs_per_month: 0,
+        closure: Closure::NaturalCork,
+        interventions: Vec::new(),
+        beverage: Beverage::Wine,
+    }
+}
+
+#[test]
+fn round_trips_current_version() {
+    let result = run_simulation(&input(), &Dataset::default()).unwrap();
+    let save_file = SaveFile::new(input(), Some(result.clone()));
+    let loaded = SaveFile::from_json(&save_file.to_json()).unwrap();
+    assert_eq!(loaded.version, CURRENT_VERSION);
+    assert_eq!(loaded.input, input());
+    assert_eq!(loaded.result.unwrap().sweetness, result.sweetness);
+}
+
+#[test]
+fn migrates_unversioned_input() {
+    let json = r#"{"grape_type": "Riesling", "fermentation_days": 10, "container_type": "Steel Tank",
+        "sugar_content": 190.0, "temperature": 14.0, "climate": "Cool", "seed": 42}"#;
+    let save_file = SaveFile::from_json(json).unwrap();
+    assert_eq!(save_file, SaveFile::new(input(), None));
+}
+
+#[test]
+fn rejects_newer_versions() {
+    let json = format!(r#"{{"version": {}, "input": {{}}}}"#, CURRENT_VERSION + 1);
+    assert!(matches!(
+        SaveFile::from_json(&json),
+        Err(SaveError::UnsupportedVersion(version)) if version == CURRENT_VERSION + 1
+    ));
+}
+
+#[test]
+fn round_trips_a_career() {
+    let mut career = Career::new("Mosel", 3).unwrap();
+    career.add_batch("Estate", &input()).unwrap();
+    career.finish_vintage(&Dataset::default()).unwrap();
+    let save_file = SaveFile::new(input(), None).with_career(career.clone());
+    let loaded = SaveFile::from_json(&save_file.to_json()).unwrap();
+    assert_eq!(loaded.career, Some(career));
+    // Sessions saved before careers load without one.
+    assert_eq!(
+        SaveFile::from_json(&SaveFile::new(input(), None).to_json())
+            .unwrap()
+            .career,
+        None
+    );
+}
+
+#[test]
+fn stamps_the_model_version_of_the_result() {
+    let result = run_simulation(&input(), &Dataset::default()).unwrap();
+    assert_eq!(
+        SaveFile::new(input(), Some(result)).model_version,
+        MODEL_VERSION
+    );
+    assert_eq!(SaveFile::new(input(), None).model_version, 0);
+}
+
+#[test]
+fn resimulates_results_from_an_older_model_only_when_they_change() {
+    let current = run_simulation(&input(), &Dataset::default()).unwrap();
+    let session = |result: SimulationResult| SaveFile::new(input(), Some(result));
+    assert_eq!(
+        session(current.clone()).resimulated(&Dataset::default()),
+        None
+    );
+
+    let same_numbers = SimulationResult {
+        model_version: 0,
+        ..current.clone()
+    };
+    assert_eq!(session(same_numbers).resimulated(&Dataset::default()), None);
+
+    let old_numbers = SimulationResult {
+        model_version: 0,
+        actual_abv: current.actual_abv + 0.5,
+        ..current.clone()
+    };
+    assert_eq!(
+        session(old_numbers).resimulated(&Dataset::default()),
+        Some(current)
+    );
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "scenario")]
+
+use wine_maker::calibration::{self, Calibration, ModelProfile};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    Beverage, Intervention, InterventionKind, SimulationInput, run_simulation,
+};
+use wine_maker::scenario::{Scenario, ScenarioError, ScenarioModel};
+use wine_maker::sweep::Sweep;
+
+fn basement() -> ModelProfile {
+    ModelProfile {
+        name: "basement cellar".to_owned(),
+        calibration: Calibration {
+            k_ref: 0.2,
+            q10: 2.5,
+            sugar_per_abv: 17.2,
+        },
+    }
+}
+
+#[test]
+fn round_trips_inputs_seed_and_model() {
+    let mut scenario = Scenario::new(
+        "Cool Cabernet",
+        SimulationInput {
+            seed: Some(42),
+            temperature: 18.0,
+            interventions: vec![Intervention::new(3, InterventionKind::Nutrient)],
+            ..Beverage::Wine.defaults()
+        },
+    )
+    .with_model(ScenarioModel {
+        profile: Some("basement cellar".to_owned()),
+        k_ref: Some(0.25),
+        ..ScenarioModel::default()
+    });
+    scenario.notes = "Does a cooler ferment keep more fruit?".to_owned();
+    scenario.sweeps = vec![Sweep::parse("primary_temp", "14..28 step 2").unwrap()];
+    assert_eq!(Scenario::from_toml(&scenario.to_toml()).unwrap(), scenario);
+}
+
+#[test]
+fn rejects_other_formats() {
+    let text =
+        "format = \"wine-maker-recipe\"\nversion = 1\ntype = \"Wine\"\nsugar_g_per_l = 220.0\n";
+    assert!(matches!(
+        Scenario::from_toml(text),
+        Err(ScenarioError::UnknownFormat(_))
+    ));
+}
+
+#[test]
+fn constants_given_replace_the_profiles() {
+    let profiles = [basement()];
+    assert_eq!(
+        ScenarioModel::default().calibration(&profiles).unwrap(),
+        Calibration::default()
+    );
+    let model = ScenarioModel {
+        profile: Some("basement cellar".to_owned()),
+        q10: Some(2.0),
+        ..ScenarioModel::default()
+    };
+    assert_eq!(
+        model.calibration(&profiles).unwrap(),
+        Calibration {
+            q10: 2.0,
+            ..basement().calibration
+        }
+    );
+    assert!(matches!(
+        model.calibration(&[]),
+        Err(ScenarioError::UnknownProfile(name)) if name == "basement cellar"
+    ));
+}
+
+#[test]
+fn runs_on_its_model_and_puts_the_old_one_back() {
+    let input = SimulationInput {
+        seed: Some(7),
+        ..Beverage::Wine.defaults()
+    };
+    let dataset = Dataset::default();
+    let scenario = Scenario::new("Basement", input.clone()).with_model(ScenarioModel {
+        profile: Some("basement cellar".to_owned()),
+        ..ScenarioModel::default()
+    });
+    let result = scenario.run(&dataset, &[basement()]).unwrap();
+    assert_eq!(calibration::installed(), Calibration::default());
+
+    calibration::install(basement().calibration);
+    let expected = run_simulation(&input, &dataset).unwrap();
+    calibration::uninstall();
+    assert_eq!(result, expected);
+}
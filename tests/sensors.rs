@@ -0,0 +1,51 @@
+#![cfg(any(feature = "mqtt", feature = "serial", feature = "ble"))]
+
+use wine_maker::sensors::{TiltColor, parse_line, parse_tilt};
+
+#[test]
+fn parses_readings_sent_one_per_line() {
+    assert_eq!(parse_line("1.050\r\n"), Some((1.050, None)));
+    assert_eq!(parse_line("1.046, 20.5"), Some((1.046, Some(20.5))));
+    assert_eq!(parse_line("1.046;68"), Some((1.046, Some(20.0))));
+    assert_eq!(
+        parse_line(r#"{"gravity": 1.040, "temperature": 18.0}"#),
+        Some((1.040, Some(18.0)))
+    );
+
+    let (plato, _) = parse_line("12.5").unwrap();
+    assert!((plato - 1.050).abs() < 0.001, "{}", plato);
+
+    assert_eq!(parse_line("booting..."), None);
+    assert_eq!(parse_line(""), None);
+    assert_eq!(parse_line(r#"{"temperature": 18.0}"#), None);
+}
+
+fn tilt(color_digit: u8, major: u16, minor: u16) -> Vec<u8> {
+    let mut data = vec![0x02, 0x15, 0xa4, 0x95, 0xbb, color_digit << 4];
+    data.extend([
+        0xc5, 0xb1, 0x4b, 0x44, 0xb5, 0x12, 0x13, 0x70, 0xf0, 0x2d, 0x74, 0xde,
+    ]);
+    data.extend(major.to_be_bytes());
+    data.extend(minor.to_be_bytes());
+    data.push(0xc5);
+    data
+}
+
+#[test]
+fn reads_tilt_advertisements() {
+    let (color, gravity, temperature) = parse_tilt(&tilt(1, 68, 1050)).unwrap();
+    assert_eq!(color, TiltColor::Red);
+    assert!((gravity - 1.050).abs() < 1e-9);
+    assert!((temperature - 20.0).abs() < 1e-9);
+
+    let (color, gravity, temperature) = parse_tilt(&tilt(8, 689, 10495)).unwrap();
+    assert_eq!(color, TiltColor::Pink);
+    assert!((gravity - 1.0495).abs() < 1e-9);
+    assert!((temperature - 20.5).abs() < 1e-9);
+
+    assert_eq!(parse_tilt(&tilt(9, 68, 1050)), None);
+    let mut other_beacon = tilt(1, 68, 1050);
+    other_beacon[10] = 0;
+    assert_eq!(parse_tilt(&other_beacon), None);
+    assert_eq!(parse_tilt(&tilt(1, 68, 1050)[..20]), None);
+}
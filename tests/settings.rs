@@ -0,0 +1,136 @@
+#![cfg(feature = "serde")]
+
+use std::path::PathBuf;
+use wine_maker::calibration::{Calibration, ModelProfile};
+use wine_maker::conventions::{StrengthConvention, SugarConvention};
+use wine_maker::engine::Beverage;
+
+use wine_maker::settings::{
+    LEGACY_PROFILE, RECENT_LIMIT, RecentKind, Settings, TemperatureUnit, Theme,
+};
+
+#[test]
+fn converts_temperatures_both_ways() {
+    let fahrenheit = TemperatureUnit::Fahrenheit;
+    assert_eq!(fahrenheit.from_celsius(20.0), 68.0);
+    assert!((fahrenheit.to_celsius(fahrenheit.from_celsius(24.0)) - 24.0).abs() < 1e-9);
+    assert_eq!(TemperatureUnit::Celsius.to_celsius(18.0), 18.0);
+}
+
+#[test]
+fn a_missing_file_gives_the_defaults() {
+    let path = std::env::temp_dir().join("wine-maker-no-such-settings.json");
+    assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+}
+
+#[test]
+fn round_trips_through_a_file() {
+    let settings = Settings {
+        theme: Theme::Dark,
+        temperature_unit: TemperatureUnit::Fahrenheit,
+        sugar_convention: SugarConvention::PercentByWeight,
+        strength_convention: StrengthConvention::UsProof,
+        locale: "fr".to_owned(),
+        defaults: Some(Beverage::Mead.defaults()),
+        profiles: vec![ModelProfile {
+            name: "basement cellar".to_owned(),
+            calibration: Calibration {
+                k_ref: 0.25,
+                q10: 2.2,
+                sugar_per_abv: 17.0,
+            },
+        }],
+        profile: "basement cellar".to_owned(),
+        data_dir: None,
+        recent: Vec::new(),
+    };
+    let path = std::env::temp_dir()
+        .join(format!("wine-maker-settings-{}", std::process::id()))
+        .join("settings.json");
+    settings.save(&path).unwrap();
+    assert_eq!(Settings::load(&path).unwrap(), settings);
+    assert_eq!(settings.glossary_file(), "glossary.fr.csv");
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn keeps_the_latest_recent_files_first_without_repeats() {
+    let mut settings = Settings::default();
+    for index in 0..RECENT_LIMIT + 2 {
+        settings.add_recent(RecentKind::Session, format!("{}.json", index).into());
+    }
+    settings.add_recent(RecentKind::Dataset, "3.json".into());
+    assert_eq!(settings.recent.len(), RECENT_LIMIT);
+    assert_eq!(settings.recent[0].kind, RecentKind::Dataset);
+    assert_eq!(settings.recent[1].path, PathBuf::from("9.json"));
+    assert_eq!(
+        settings
+            .recent
+            .iter()
+            .filter(|recent| recent.path == PathBuf::from("3.json"))
+            .count(),
+        1
+    );
+
+    settings.remove_recent(&PathBuf::from("3.json"));
+    assert!(
+        settings
+            .recent
+            .iter()
+            .all(|recent| recent.path != PathBuf::from("3.json"))
+    );
+}
+
+#[test]
+fn saves_profiles_by_name_and_forgets_a_deleted_pick() {
+    let mut settings = Settings::default();
+    assert!(settings.selected_profile().is_none());
+    let warm = Calibration {
+        k_ref: 0.3,
+        ..Calibration::default()
+    };
+    settings.save_profile(ModelProfile {
+        name: "garage".to_owned(),
+        calibration: Calibration::default(),
+    });
+    settings.save_profile(ModelProfile {
+        name: "garage".to_owned(),
+        calibration: warm,
+    });
+    assert_eq!(settings.profiles.len(), 1);
+    assert_eq!(settings.selected_profile().unwrap().calibration, warm);
+    settings.remove_profile("garage");
+    assert!(settings.profiles.is_empty());
+    assert_eq!(settings.profile, "");
+}
+
+#[test]
+fn a_calibration_saved_before_profiles_becomes_one() {
+    let dir = std::env::temp_dir().join(format!("wine-maker-legacy-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("settings.json");
+    std::fs::write(
+        &path,
+        r#"{"theme": "Dark", "calibration": {"k_ref": 0.3, "q10": 2.5, "sugar_per_abv": 17.5}}"#,
+    )
+    .unwrap();
+    let settings = Settings::load(&path).unwrap();
+    assert_eq!(settings.theme, Theme::Dark);
+    assert_eq!(settings.profile, LEGACY_PROFILE);
+    assert_eq!(
+        settings.selected_profile().unwrap().calibration,
+        Calibration {
+            k_ref: 0.3,
+            q10: 2.5,
+            sugar_per_abv: 17.5,
+        }
+    );
+
+    // Saved again, it stays a profile.
+    settings.save(&path).unwrap();
+    assert_eq!(Settings::load(&path).unwrap(), settings);
+
+    std::fs::write(&path, r#"{"calibration": null}"#).unwrap();
+    assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+    let _ = std::fs::remove_dir_all(&dir);
+}
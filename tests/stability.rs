@@ -0,0 +1,101 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{SimulationInput, SimulationResult, run_simulation};
+use wine_maker::stability::{BottlingConditions, Risk, refermentation, stability_report};
+
+fn riesling() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Riesling".to_owned(),
+        fermentation_days: 14,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content: 200.0,
+        temperature: 16.0,
+        climate: "Cool".to_owned(),
+        ..SimulationInput::default()
+    }
+}
+
+fn finished(input: &SimulationInput, residual_sugar: f64) -> SimulationResult {
+    SimulationResult {
+        residual_sugar,
+        ..run_simulation(input, &Dataset::default()).unwrap()
+    }
+}
+
+#[test]
+fn sweet_wine_with_live_yeast_can_burst_bottles() {
+    let input = riesling();
+    let sweet = finished(&input, 20.0);
+    let warm = BottlingConditions {
+        free_so2: 0.0,
+        storage_temperature: 22.0,
+        ..BottlingConditions::default()
+    };
+    let risk = refermentation(&input, &sweet, &warm);
+    assert_eq!(risk.risk, Risk::High);
+    assert!(risk.pressure > 4.0);
+    assert!(risk.could_burst());
+    assert!(stability_report(&input, &sweet, &warm).warnings()[0].contains("burst"));
+
+    // Cold storage slows the yeast but doesn't stop them.
+    let cold = refermentation(
+        &input,
+        &sweet,
+        &BottlingConditions {
+            storage_temperature: 8.0,
+            ..warm
+        },
+    );
+    assert!(cold.probability < risk.probability);
+
+    let stabilized = BottlingConditions {
+        sterile_filtered: true,
+        sorbate: true,
+        ..warm
+    };
+    let risk = refermentation(&input, &sweet, &stabilized);
+    assert_eq!(risk.risk, Risk::Negligible);
+    assert!(!risk.could_burst());
+
+    // Sulfite alone helps but is not enough for a sweet wine.
+    let sulfited = refermentation(&input, &sweet, &BottlingConditions::default());
+    assert!(sulfited.probability < refermentation(&input, &sweet, &warm).probability);
+    assert!(sulfited.probability > risk.probability);
+}
+
+#[test]
+fn dry_wine_has_nothing_to_referment() {
+    let input = riesling();
+    let dry = finished(&input, 1.0);
+    let risk = refermentation(&input, &dry, &BottlingConditions::default());
+    assert_eq!(risk.fermentable_sugar, 0.0);
+    assert_eq!(risk.probability, 0.0);
+    assert_eq!(risk.risk, Risk::Negligible);
+}
+
+#[test]
+fn whites_need_fining_to_be_stable() {
+    let input = riesling();
+    let dry = finished(&input, 1.0);
+    let unfined = stability_report(&input, &dry, &BottlingConditions::default());
+    assert!(!unfined.is_stable());
+    assert!(unfined.warnings()[0].contains("haze"));
+
+    let fined = stability_report(
+        &input,
+        &dry,
+        &BottlingConditions {
+            bentonite: 40.0,
+            ..BottlingConditions::default()
+        },
+    );
+    assert!(fined.is_stable());
+    assert!(fined.warnings().is_empty());
+
+    let merlot = SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        ..input
+    };
+    let report = stability_report(&merlot, &dry, &BottlingConditions::default());
+    assert!(report.protein.is_none());
+    assert!(report.is_stable());
+}
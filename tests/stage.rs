@@ -0,0 +1,72 @@
+use wine_maker::engine::{AMBIENT_YEAST, Inoculation, SimulationInput, wild_ferment};
+use wine_maker::stage::{Stage, bottling_day, stage_on, stages, status};
+
+fn plan(inoculation: Inoculation) -> SimulationInput {
+    SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        fermentation_days: 14,
+        sugar_content: 220.0,
+        temperature: 22.0,
+        aging_months: 6,
+        inoculation,
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn stages_follow_the_process_in_order() {
+    let malolactic = plan(Inoculation::SequentialMalolactic);
+    let spans = stages(&malolactic).unwrap();
+    let order: Vec<Stage> = spans.iter().map(|span| span.stage).collect();
+    assert_eq!(order, Stage::ALL);
+    assert_eq!(spans[2].start_day, 14);
+    assert_eq!(spans[3].start_day, 14 + 42);
+    assert_eq!(spans[5].start_day, bottling_day(&malolactic));
+    assert_eq!(spans[4].end_day, Some(bottling_day(&malolactic)));
+    assert_eq!(spans[5].end_day, None);
+    assert!(
+        spans
+            .windows(2)
+            .all(|w| w[0].end_day == Some(w[1].start_day))
+    );
+
+    // Without a malolactic fermentation, aging starts at pressing.
+    let plan = plan(Inoculation::Single);
+    assert!(
+        stages(&plan)
+            .unwrap()
+            .iter()
+            .all(|span| span.stage != Stage::Malolactic)
+    );
+    assert_eq!(stage_on(&plan, 14).unwrap(), Stage::Aging);
+    assert_eq!(stage_on(&plan, 1000).unwrap(), Stage::Bottled);
+}
+
+#[test]
+fn wild_yeast_linger_in_the_lag_phase() {
+    let wild = SimulationInput {
+        yeast: AMBIENT_YEAST.to_owned(),
+        seed: Some(7),
+        ..plan(Inoculation::Single)
+    };
+    let lag_days = wild_ferment(&wild).unwrap().lag_days;
+    assert_eq!(stage_on(&wild, 0).unwrap(), Stage::Lag);
+    assert_eq!(
+        stage_on(&wild, lag_days.floor() as u32).unwrap(),
+        Stage::Lag
+    );
+    assert_eq!(
+        stage_on(&wild, lag_days.ceil() as u32 + 1).unwrap(),
+        Stage::Primary
+    );
+
+    let today = status(&wild, 10).unwrap();
+    assert_eq!(today.stage(), Stage::Primary);
+    assert_eq!(today.days_left(), Some(4));
+    assert!(today.to_string().starts_with("Primary fermentation, day "));
+    assert!(!Stage::Aging.is_fermenting());
+    assert_eq!(
+        Stage::from_label("malolactic fermentation"),
+        Some(Stage::Malolactic)
+    );
+}
@@ -0,0 +1,90 @@
+use wine_maker::engine::{Beverage, InterventionKind, SimulationInput, fermentation_state};
+use wine_maker::stage::{Stage, bottling_day};
+use wine_maker::stepper::Stepper;
+use wine_maker::sulfides::SulfideStage;
+
+fn plan() -> SimulationInput {
+    SimulationInput {
+        grape_type: "Merlot".to_owned(),
+        fermentation_days: 14,
+        sugar_content: 220.0,
+        temperature: 22.0,
+        aging_months: 3,
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn steps_through_the_plan_day_by_day() {
+    let plan = plan();
+    let mut stepper = Stepper::new(plan.clone()).unwrap();
+    assert_eq!(stepper.state().unwrap().stage, Stage::Lag);
+
+    stepper.step(5);
+    let state = stepper.state().unwrap();
+    assert_eq!(state.day, 5);
+    assert_eq!(state.stage, Stage::Primary);
+    assert_eq!(state.fermentation, fermentation_state(&plan, 5).unwrap());
+    assert!(state.rate_constant.unwrap() > 0.0);
+    assert_eq!(state.fields()[0], ("Day", "5".to_owned()));
+
+    stepper.next_stage().unwrap();
+    let pressed = stepper.state().unwrap();
+    assert_eq!((pressed.day, pressed.stage), (14, Stage::Aging));
+    assert_eq!(pressed.rate_constant, None);
+    assert!(pressed.dissolved_co2 < state.dissolved_co2);
+
+    stepper.step(1000);
+    assert!(stepper.is_finished());
+    assert_eq!(stepper.day(), bottling_day(&plan));
+    stepper.back(1000);
+    assert_eq!(stepper.day(), 0);
+    assert!(
+        Stepper::new(SimulationInput {
+            temperature: 50.0,
+            ..plan
+        })
+        .is_err()
+    );
+}
+
+#[test]
+fn changes_midway_only_touch_what_follows() {
+    let plan = plan();
+    let mut stepper = Stepper::new(plan.clone()).unwrap();
+    stepper.step(3);
+    stepper.set_temperature(12.0);
+    stepper.set_temperature(10.0);
+    assert_eq!(stepper.input().interventions.len(), 1);
+
+    let chilled = stepper.input().clone();
+    for day in 0..=3 {
+        assert_eq!(
+            fermentation_state(&chilled, day).unwrap(),
+            fermentation_state(&plan, day).unwrap()
+        );
+    }
+    stepper.step(4);
+    let state = stepper.state().unwrap();
+    assert_eq!(state.temperature, 10.0);
+    assert!(state.fermentation.abv < fermentation_state(&plan, 7).unwrap().abv);
+
+    // A starved mead reeks until it's aerated.
+    let mead = Beverage::Mead.defaults();
+    let starved = SimulationInput {
+        interventions: Vec::new(),
+        ..mead
+    };
+    let mut stepper = Stepper::new(starved).unwrap();
+    while stepper.state().unwrap().sulfides.is_none() && !stepper.is_finished() {
+        stepper.step(1);
+    }
+    assert_eq!(
+        stepper.state().unwrap().sulfides,
+        Some(SulfideStage::HydrogenSulfide)
+    );
+    stepper.intervene(InterventionKind::Aeration, 0.0);
+    let state = stepper.state().unwrap();
+    assert_eq!(state.sulfides, None);
+    assert_eq!(state.interventions[0].kind, InterventionKind::Aeration);
+}
@@ -0,0 +1,290 @@
+#![cfg(feature = "sqlite")]
+
+use chrono::NaiveDate;
+use wine_maker::achievements::Achievement;
+use wine_maker::cellar::{Measurement, Task};
+use wine_maker::costs::Price;
+use wine_maker::dataset::{Dataset, WineRecord};
+use wine_maker::engine::{
+    Beverage, Closure, Excursion, Inoculation, Intervention, InterventionKind, SimulationInput,
+    run_simulation,
+};
+use wine_maker::inventory::StockItem;
+use wine_maker::store::Store;
+
+fn input(grape: &str, sugar_content: f64) -> SimulationInput {
+    SimulationInput {
+        grape_type: grape.to_owned(),
+        fermentation_days: 14,
+        container_type: "Oak Barrel".to_owned(),
+        aging_container: "Clay Amphora".to_owned(),
+        aging_months: 6,
+        whole_cluster: 25.0,
+        press_wine: 50.0,
+        sugar_content,
+        temperature: 20.0,
+        climate: "Moderate".to_owned(),
+        seed: Some(u64::MAX),
+        restart_day: Some(9),
+        excursions: vec![Excursion::power_outage(2), Excursion::heat_wave(6)],
+        yeast: "QA23".to_owned(),
+        inoculation: Inoculation::CoInoculatedMalolactic,
+        lees_months: 4,
+        stirs_per_month: 2,
+        closure: Closure::Screwcap,
+        interventions: vec![
+            Intervention {
+                day: 0,
+                kind: InterventionKind::Nutrient,
+                amount: 80.0,
+            },
+            Intervention::new(9, InterventionKind::Copper),
+            Intervention {
+                day: 12,
+                kind: InterventionKind::Temperature,
+                amount: 14.5,
+            },
+        ],
+        beverage: Beverage::Wine,
+    }
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+}
+
+#[test]
+fn records_history_newest_first() {
+    let store = Store::open_in_memory().unwrap();
+    for grape in ["Merlot", "Syrah"] {
+        let input = input(grape, 220.0);
+        let result = run_simulation(&input, &Dataset::default()).unwrap();
+        store
+            .record_simulation(&input, &result, "narrative")
+            .unwrap();
+    }
+
+    let history = store.history(10).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].input, input("Syrah", 220.0));
+    assert_eq!(store.history(1).unwrap().len(), 1);
+}
+
+#[test]
+fn saving_a_preset_replaces_one_with_the_same_name() {
+    let store = Store::open_in_memory().unwrap();
+    store
+        .save_preset("house red", &input("Merlot", 220.0))
+        .unwrap();
+    store
+        .save_preset("house red", &input("Malbec", 240.0))
+        .unwrap();
+
+    let presets = store.presets().unwrap();
+    assert_eq!(presets.len(), 1);
+    assert_eq!(presets[0].input, input("Malbec", 240.0));
+    assert!(store.preset("missing").unwrap().is_none());
+}
+
+#[test]
+fn finds_batches_over_an_abv() {
+    let store = Store::open_in_memory().unwrap();
+    store
+        .add_batch("light", date(20), &input("Riesling", 180.0), Some(10.7))
+        .unwrap();
+    store
+        .add_batch("big", date(28), &input("Zinfandel", 260.0), Some(14.9))
+        .unwrap();
+
+    let strong = store.batches_with_abv_at_least(13.0).unwrap();
+    assert_eq!(strong.len(), 1);
+    assert_eq!(strong[0].name, "big");
+    assert_eq!(store.batches().unwrap()[0].name, "big");
+}
+
+#[test]
+fn keeps_user_grapes() {
+    let store = Store::open_in_memory().unwrap();
+    let record = WineRecord {
+        grape: "Saperavi".to_owned(),
+        characteristics: "inky and tart".to_owned(),
+        ..WineRecord::default()
+    };
+    store.add_user_grape(&record).unwrap();
+    assert_eq!(store.user_grapes().unwrap(), vec![record]);
+}
+
+#[test]
+fn deleting_a_batch_removes_its_measurements() {
+    let store = Store::open_in_memory().unwrap();
+    let batch_id = store
+        .add_batch("merlot", date(1), &input("Merlot", 220.0), None)
+        .unwrap();
+    let measurement = Measurement {
+        measured_on: date(3),
+        sugar_content: 150.0,
+        specific_gravity: None,
+        temperature: Some(21.5),
+        notes: "vigorous".to_owned(),
+    };
+    store.add_measurement(batch_id, &measurement).unwrap();
+    assert_eq!(store.measurements(batch_id).unwrap(), vec![measurement]);
+
+    store.delete_batch(batch_id).unwrap();
+    assert!(store.batches().unwrap().is_empty());
+    assert!(store.measurements(batch_id).unwrap().is_empty());
+}
+
+#[test]
+fn replaces_a_batch_schedule() {
+    let store = Store::open_in_memory().unwrap();
+    let batch_id = store
+        .add_batch("merlot", date(1), &input("Merlot", 220.0), None)
+        .unwrap();
+    let task = |day, title: &str| Task {
+        uid: title.to_lowercase(),
+        due_on: date(day),
+        title: title.to_owned(),
+        done: false,
+    };
+    store
+        .set_tasks(batch_id, &[task(20, "Bottle"), task(15, "Rack")])
+        .unwrap();
+    store.set_tasks(batch_id, &[task(15, "Rack")]).unwrap();
+    assert_eq!(store.tasks(batch_id).unwrap(), vec![task(15, "Rack")]);
+
+    store.delete_batch(batch_id).unwrap();
+    assert!(store.tasks(batch_id).unwrap().is_empty());
+}
+
+#[test]
+fn lists_unfinished_tasks_that_are_due() {
+    let store = Store::open_in_memory().unwrap();
+    let batch_id = store
+        .add_batch("merlot", date(1), &input("Merlot", 220.0), None)
+        .unwrap();
+    let task = |day, title: &str, done| Task {
+        uid: title.to_lowercase(),
+        due_on: date(day),
+        title: title.to_owned(),
+        done,
+    };
+    store
+        .set_tasks(
+            batch_id,
+            &[
+                task(2, "Nutrient", true),
+                task(15, "Rack", false),
+                task(20, "Bottle", false),
+            ],
+        )
+        .unwrap();
+
+    let due = store.due_tasks(date(15)).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].batch_name, "merlot");
+    assert_eq!(due[0].task, task(15, "Rack", false));
+}
+
+#[test]
+fn keeps_batch_costs_and_a_price_list() {
+    let store = Store::open_in_memory().unwrap();
+    let batch_id = store
+        .add_batch("merlot", date(1), &input("Merlot", 220.0), None)
+        .unwrap();
+    store.set_batch_volume(batch_id, Some(23.0)).unwrap();
+    assert_eq!(store.batches().unwrap()[0].volume, Some(23.0));
+
+    let price = Price {
+        name: "Bordeaux bottle".to_owned(),
+        category: "Bottles".to_owned(),
+        unit: "bottle".to_owned(),
+        unit_price: 0.8,
+    };
+    store.save_price(&price).unwrap();
+    store
+        .save_price(&Price {
+            unit_price: 0.9,
+            ..price.clone()
+        })
+        .unwrap();
+    let prices = store.prices().unwrap();
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].unit_price, 0.9);
+
+    store.set_costs(batch_id, &[price.cost(30.0)]).unwrap();
+    assert_eq!(store.costs(batch_id).unwrap(), vec![price.cost(30.0)]);
+    store.delete_batch(batch_id).unwrap();
+    assert!(store.costs(batch_id).unwrap().is_empty());
+}
+
+#[test]
+fn starting_a_batch_takes_stock() {
+    let store = Store::open_in_memory().unwrap();
+    store
+        .save_stock_item(&StockItem::new("Bottles", "bottles", 24.0))
+        .unwrap();
+    store
+        .save_stock_item(&StockItem::new("Corks", "corks", 100.0))
+        .unwrap();
+    store
+        .take_stock(&[
+            StockItem::new("bottles", "bottles", 31.0),
+            StockItem::new("Corks", "corks", 31.0),
+            StockItem::new("Yeast packets", "packets", 1.0),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        store.stock().unwrap(),
+        vec![
+            StockItem::new("Bottles", "bottles", 0.0),
+            StockItem::new("Corks", "corks", 69.0),
+        ]
+    );
+}
+
+#[test]
+fn keeps_the_best_challenge_scores() {
+    let store = Store::open_in_memory().unwrap();
+    assert!(store.record_challenge_score("A dry red", 62.0).unwrap());
+    assert!(store.record_challenge_score("A dry red", 85.0).unwrap());
+    assert!(!store.record_challenge_score("A dry red", 70.0).unwrap());
+    assert!(store.record_challenge_score("A light white", 40.0).unwrap());
+    assert_eq!(
+        store.challenge_scores().unwrap(),
+        [
+            ("A dry red".to_owned(), 85.0),
+            ("A light white".to_owned(), 40.0)
+        ]
+    );
+}
+
+#[test]
+fn keeps_achievements_and_the_wine_count() {
+    let store = Store::open_in_memory().unwrap();
+    assert_eq!(store.wines_made().unwrap(), 0);
+    assert_eq!(store.count_wine_made().unwrap(), 1);
+    assert_eq!(store.count_wine_made().unwrap(), 2);
+    store.clear_history().unwrap();
+    assert_eq!(store.wines_made().unwrap(), 2);
+
+    assert!(store.record_achievement(Achievement::FirstWine).unwrap());
+    assert!(store.record_achievement(Achievement::BoneDry).unwrap());
+    assert!(!store.record_achievement(Achievement::FirstWine).unwrap());
+    let earned: Vec<Achievement> = store
+        .achievements()
+        .unwrap()
+        .into_iter()
+        .map(|(achievement, _)| achievement)
+        .collect();
+    assert_eq!(earned, [Achievement::FirstWine, Achievement::BoneDry]);
+}
+
+#[test]
+fn remembers_the_tour_was_seen() {
+    let store = Store::open_in_memory().unwrap();
+    assert!(!store.tour_seen().unwrap());
+    store.mark_tour_seen().unwrap();
+    assert!(store.tour_seen().unwrap());
+}
@@ -0,0 +1,99 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    Beverage, Intervention, InterventionKind, SimulationInput, fermentation_state, run_simulation,
+};
+use wine_maker::narrative::narrative;
+use wine_maker::sulfides::{SulfideStage, h2s_onset_day, nutrient_stress, sulfide_fault};
+
+fn starved_mead(interventions: &[(u32, InterventionKind)]) -> SimulationInput {
+    SimulationInput {
+        interventions: interventions
+            .iter()
+            .map(|&(day, kind)| Intervention::new(day, kind))
+            .collect(),
+        ..Beverage::Mead.defaults()
+    }
+}
+
+#[test]
+fn fed_musts_stay_clean() {
+    let mead = Beverage::Mead.defaults();
+    assert!(nutrient_stress(&mead) < 0.25);
+    assert!(sulfide_fault(&mead).is_none());
+    for beverage in Beverage::ALL {
+        assert!(sulfide_fault(&beverage.defaults()).is_none());
+    }
+}
+
+#[test]
+fn starved_yeast_give_off_hydrogen_sulfide() {
+    let mead = starved_mead(&[]);
+    assert!(nutrient_stress(&mead) > 0.8);
+    let onset = h2s_onset_day(&mead).unwrap();
+    assert!(onset < mead.fermentation_days);
+
+    let fault = sulfide_fault(&mead).unwrap();
+    assert_eq!(fault.cured_by, None);
+    assert_eq!(fault.remaining, Some(SulfideStage::Mercaptans));
+    let result = run_simulation(&mead, &Dataset::default()).unwrap();
+    assert!(
+        result
+            .off_flavors
+            .iter()
+            .any(|flavor| flavor.contains("mercaptans"))
+    );
+    let text = narrative(&mead, &result);
+    assert!(text.contains("the yeast began giving off hydrogen sulfide"));
+    assert!(!text.contains("The warm fermentation left"));
+
+    // Nutrient after the one-third sugar break comes too late.
+    let late = (0..mead.fermentation_days)
+        .find(|&day| fermentation_state(&mead, day).unwrap().fraction_fermented > 0.4)
+        .unwrap();
+    let fed_late = starved_mead(&[(late, InterventionKind::Nutrient)]);
+    assert_eq!(nutrient_stress(&fed_late), nutrient_stress(&mead));
+}
+
+#[test]
+fn remedy_timing_decides_the_fault() {
+    let onset = h2s_onset_day(&starved_mead(&[])).unwrap();
+
+    let aerated = starved_mead(&[(onset + 2, InterventionKind::Aeration)]);
+    let fault = sulfide_fault(&aerated).unwrap();
+    assert_eq!(
+        fault.cured_by,
+        Some((InterventionKind::Aeration, onset + 2))
+    );
+    assert_eq!(fault.remaining, None);
+    let result = run_simulation(&aerated, &Dataset::default()).unwrap();
+    assert!(result.off_flavors.is_empty());
+    assert!(narrative(&aerated, &result).contains("blew it off"));
+
+    // Once it has turned to mercaptans, only copper helps.
+    let aerated_late = starved_mead(&[(onset + 20, InterventionKind::Aeration)]);
+    assert_eq!(
+        sulfide_fault(&aerated_late).unwrap().remaining,
+        Some(SulfideStage::Mercaptans)
+    );
+    let copper = starved_mead(&[
+        (onset + 20, InterventionKind::Aeration),
+        (onset + 25, InterventionKind::Copper),
+    ]);
+    assert_eq!(
+        sulfide_fault(&copper).unwrap().cured_by,
+        Some((InterventionKind::Copper, onset + 25))
+    );
+
+    // And after months of aging, nothing does.
+    let aged = SimulationInput {
+        aging_months: 6,
+        ..starved_mead(&[(onset + 100, InterventionKind::Copper)])
+    };
+    let fault = sulfide_fault(&aged).unwrap();
+    assert_eq!(fault.cured_by, None);
+    assert_eq!(fault.remaining, Some(SulfideStage::Disulfides));
+
+    // Copper before there is anything to bind does nothing.
+    let early = starved_mead(&[(0, InterventionKind::Copper)]);
+    assert_eq!(sulfide_fault(&early).unwrap().cured_by, None);
+}
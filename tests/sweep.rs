@@ -0,0 +1,77 @@
+#![cfg(feature = "scenario")]
+
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Beverage, SimulationInput};
+use wine_maker::sweep::{
+    MAX_RUNS, Sweep, SweepError, SweepParameter, expand, plot, run_count, run_sweeps,
+};
+
+#[test]
+fn reads_ranges_with_and_without_a_step() {
+    let sweep = Sweep::parse("primary_temp", "14..28 step 2").unwrap();
+    assert_eq!(sweep.parameter, SweepParameter::Temperature);
+    assert_eq!(sweep.values().len(), 8);
+    assert_eq!(sweep.values().last(), Some(&28.0));
+    assert_eq!(Sweep::parse("primary_temp", &sweep.to_string()), Ok(sweep));
+    assert_eq!(Sweep::parse("seed", "1..=5").unwrap().values().len(), 5);
+    assert_eq!(
+        Sweep::parse("sugar_g_per_l", "0.1..0.3 step 0.1")
+            .unwrap()
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn rejects_unknown_fields_and_bad_ranges() {
+    for (field, text) in [
+        ("grape", "1..3"),
+        ("primary_temp", "28..14"),
+        ("primary_temp", "14..28 step 0"),
+        ("primary_age", "7..14 step 0.5"),
+        ("primary_temp", "warm"),
+        ("primary_temp", "0..inf"),
+        ("primary_temp", "0..10 step inf"),
+        ("primary_temp", "nan..10"),
+    ] {
+        assert_eq!(
+            Sweep::parse(field, text),
+            Err(SweepError(field.to_owned(), text.to_owned()))
+        );
+    }
+}
+
+#[test]
+fn counts_vast_ranges_without_overflowing() {
+    let vast = Sweep::parse("primary_temp", "0..1e30 step 1e-30").unwrap();
+    assert_eq!(vast.count(), usize::MAX);
+    assert!(run_count(&[vast, vast]) > MAX_RUNS);
+}
+
+#[test]
+fn runs_every_combination_in_order() {
+    let input = SimulationInput {
+        seed: Some(1),
+        ..Beverage::Wine.defaults()
+    };
+    let sweeps = [
+        Sweep::parse("primary_temp", "16..20 step 2").unwrap(),
+        Sweep::parse("primary_age", "10..12 step 2").unwrap(),
+    ];
+    assert_eq!(run_count(&sweeps), 6);
+    let expanded = expand(&input, &sweeps);
+    assert_eq!(expanded[1].0, vec![16.0, 12.0]);
+    assert_eq!(expanded[1].1.temperature, 16.0);
+    assert_eq!(expanded[1].1.fermentation_days, 12);
+
+    let runs = run_sweeps(&input, &sweeps, &Dataset::default());
+    assert_eq!(runs.len(), 6);
+    for (run, (values, input)) in runs.iter().zip(&expanded) {
+        assert_eq!(&run.values, values);
+        assert_eq!(&run.input, input);
+    }
+    assert_eq!(runs[5].name(&sweeps), "primary_temp 20, primary_age 12");
+    let svg = plot(&runs, &sweeps);
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    assert!(svg.contains("primary_age 10"));
+}
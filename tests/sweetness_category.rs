@@ -0,0 +1,77 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::descriptors::{SWEETNESS, standard};
+use wine_maker::engine::{
+    Beverage, Inoculation, perceived_sugar, run_simulation, titratable_acidity,
+};
+use wine_maker::sweetness_category::{SparklingCategory, StillCategory};
+
+#[test]
+fn acidity_stretches_the_dry_and_medium_dry_limits() {
+    assert_eq!(StillCategory::classify(4.0, 3.0), StillCategory::Dry);
+    assert_eq!(StillCategory::classify(7.0, 4.0), StillCategory::MediumDry);
+    assert_eq!(StillCategory::classify(7.0, 5.0), StillCategory::Dry);
+    assert_eq!(StillCategory::classify(9.5, 9.0), StillCategory::MediumDry);
+    assert_eq!(StillCategory::classify(15.0, 4.0), StillCategory::Medium);
+    assert_eq!(StillCategory::classify(15.0, 5.0), StillCategory::MediumDry);
+    assert_eq!(StillCategory::classify(45.0, 7.0), StillCategory::Medium);
+    assert_eq!(StillCategory::classify(45.5, 7.0), StillCategory::Sweet);
+}
+
+#[test]
+fn sparkling_takes_the_driest_category_that_fits() {
+    let classify = |sugar| SparklingCategory::classify(sugar).label();
+    assert_eq!(classify(2.9), "brut nature");
+    assert_eq!(classify(3.0), "extra brut");
+    assert_eq!(classify(6.0), "extra brut");
+    assert_eq!(classify(11.9), "brut");
+    assert_eq!(classify(12.0), "extra dry");
+    assert_eq!(classify(32.0), "sec");
+    assert_eq!(classify(50.0), "demi-sec");
+    assert_eq!(classify(50.1), "doux");
+    for category in SparklingCategory::ALL {
+        assert_eq!(
+            SparklingCategory::from_label(category.label()),
+            Some(category)
+        );
+    }
+}
+
+#[test]
+fn results_carry_the_categories() {
+    let mut input = Beverage::Wine.defaults();
+    input.grape_type = "Riesling".to_owned();
+    input.climate = "Cool".to_owned();
+    let before = titratable_acidity(&input);
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert_eq!(result.titratable_acidity, before);
+    assert_eq!(
+        result.eu_sweetness,
+        StillCategory::classify(result.residual_sugar, before).label()
+    );
+    assert!(!result.eu_sparkling_sweetness.is_empty());
+
+    input.inoculation = Inoculation::SequentialMalolactic;
+    assert!(titratable_acidity(&input) < before);
+
+    let mead = run_simulation(&Beverage::Mead.defaults(), &Dataset::default()).unwrap();
+    assert!(mead.eu_sweetness.is_empty());
+}
+
+#[test]
+fn crisp_acidity_makes_sugar_taste_drier() {
+    let mut input = Beverage::Wine.defaults();
+    input.grape_type = "Riesling".to_owned();
+    input.climate = "Cool".to_owned();
+    let tart = perceived_sugar(22.0, titratable_acidity(&input), Beverage::Wine);
+    assert!(tart < 20.0);
+    assert_eq!(
+        standard().describe(SWEETNESS, Beverage::Wine, tart),
+        "with just a subtle hint of sweetness"
+    );
+    assert!(perceived_sugar(22.0, 4.5, Beverage::Wine) > 22.0);
+    assert_eq!(perceived_sugar(1.0, 12.0, Beverage::Wine), 0.0);
+
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    let label = standard().describe(SWEETNESS, Beverage::Wine, result.perceived_sugar);
+    assert!(result.sweetness.starts_with(&label));
+}
@@ -0,0 +1,82 @@
+use wine_maker::engine::{CONTAINERS, GRAPES, SimulationInput, fermentation_state_at_rate};
+use wine_maker::vineyard::{LATEST_HARVEST_DAY, REGIONS, TYPICAL_HARVEST_DAY, Vintage};
+
+fn vintage(growing_degree_days: f64, harvest_rain: f64) -> Vintage {
+    Vintage {
+        growing_degree_days,
+        rainfall: 300.0,
+        harvest_rain,
+    }
+}
+
+#[test]
+fn warmer_seasons_bring_riper_grapes() {
+    let cool = vintage(1200.0, 0.0).harvest();
+    let warm = vintage(2100.0, 0.0).harvest();
+    assert_eq!(cool.climate, "Cool");
+    assert_eq!(warm.climate, "Warm");
+    assert!(warm.expected_sugar() > cool.expected_sugar());
+    assert!(warm.expected_acidity() < cool.expected_acidity());
+    assert!(warm.phenolic_ripeness.start() > cool.phenolic_ripeness.start());
+    assert_eq!(vintage(1500.0, 0.0).winkler_region(), 2);
+}
+
+#[test]
+fn harvest_rain_dilutes_the_sugar() {
+    let dry = vintage(1600.0, 0.0).harvest();
+    let wet = vintage(1600.0, 40.0).harvest();
+    assert!((dry.expected_sugar() - wet.expected_sugar() - 12.0).abs() < 1e-9);
+}
+
+#[test]
+fn harvest_sets_the_starting_sugar() {
+    for region in REGIONS {
+        let harvest = region.typical.harvest();
+        let mut input = SimulationInput::default();
+        harvest.apply_to(&mut input);
+        let start = fermentation_state_at_rate(&input, 0, 0.0).sugar_content;
+        assert!(
+            (start - harvest.expected_sugar()).abs() < 1e-9,
+            "{}",
+            region.name
+        );
+    }
+}
+
+#[test]
+fn later_picks_trade_acid_for_sugar() {
+    let vintage = REGIONS[2].typical;
+    assert_eq!(vintage.harvest(), vintage.harvest_on(TYPICAL_HARVEST_DAY));
+    let curve = vintage.ripening_curve();
+    assert_eq!(curve.first().unwrap().0, 0);
+    assert_eq!(curve.last().unwrap().0, LATEST_HARVEST_DAY);
+    for pair in curve.windows(2) {
+        let ((_, earlier), (_, later)) = (&pair[0], &pair[1]);
+        assert!(later.expected_sugar() > earlier.expected_sugar());
+        assert!(later.expected_acidity() < earlier.expected_acidity());
+    }
+}
+
+#[test]
+fn region_presets_can_be_simulated() {
+    for region in REGIONS {
+        assert!(GRAPES.contains(&region.main_grape()), "{}", region.name);
+        assert!(CONTAINERS.contains(&region.container), "{}", region.name);
+        let total: u32 = region.blend.iter().map(|(_, percent)| percent).sum();
+        assert_eq!(total, 100, "{}", region.name);
+    }
+
+    let rioja = REGIONS
+        .iter()
+        .find(|region| region.name == "Rioja")
+        .unwrap();
+    assert_eq!(
+        rioja.describe_blend(),
+        "Tempranillo 80%, Garnacha 15%, Graciano 5%"
+    );
+    let mut input = SimulationInput::default();
+    rioja.apply_to(&mut input);
+    assert_eq!(input.grape_type, "Tempranillo");
+    assert_eq!(input.container_type, "Oak Barrel");
+    assert_eq!(input.climate, rioja.typical.harvest().climate);
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "weather")]
+
+use chrono::NaiveDate;
+use wine_maker::weather::{ClimateNormals, DailyWeather, Place, growing_season_normals};
+
+/// Mild days with a millimeter of rain each, from `start` to `end`.
+fn daily(start: NaiveDate, end: NaiveDate) -> Vec<DailyWeather> {
+    let mut days = Vec::new();
+    let mut date = start;
+    while date <= end {
+        days.push(DailyWeather {
+            date,
+            max_temperature: 20.0,
+            min_temperature: 10.0,
+            precipitation: 1.0,
+        });
+        date = date.succ_opt().unwrap();
+    }
+    days
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn averages_complete_northern_seasons() {
+    let history = daily(date(2022, 3, 1), date(2024, 6, 30));
+    let (seasons, vintage) = growing_season_normals(&history, false).unwrap();
+    assert_eq!(seasons, 2);
+    assert!((vintage.growing_degree_days - 214.0 * 5.0).abs() < 1e-9);
+    assert!((vintage.rainfall - 214.0).abs() < 1e-9);
+    assert!((vintage.harvest_rain - 14.0).abs() < 1e-9);
+
+    let normals = ClimateNormals {
+        place: Place {
+            name: "Salem".to_owned(),
+            country: "United States".to_owned(),
+            latitude: 44.94,
+            longitude: -123.04,
+        },
+        seasons,
+        vintage,
+    };
+    assert_eq!(normals.climate(), "Cool");
+}
+
+#[test]
+fn southern_seasons_span_the_new_year() {
+    let history = daily(date(2022, 3, 1), date(2024, 6, 30));
+    let (seasons, vintage) = growing_season_normals(&history, true).unwrap();
+    assert_eq!(seasons, 2);
+    assert!((vintage.rainfall - 212.5).abs() < 1e-9);
+    assert!((vintage.harvest_rain - 14.0).abs() < 1e-9);
+
+    assert!(growing_season_normals(&history[..100], true).is_none());
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "webhook")]
+
+use chrono::NaiveDate;
+use serde_json::json;
+use wine_maker::cellar::{Batch, Measurement};
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{Beverage, run_simulation};
+use wine_maker::export::{COLUMNS, Cell, Spreadsheet};
+use wine_maker::webhook::{Upload, WebhookError, WebhookTarget};
+
+fn batch_log() -> Upload {
+    let batch = Batch {
+        id: 1,
+        name: "merlot".to_owned(),
+        started_on: NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(),
+        input: Beverage::Wine.defaults(),
+        predicted_abv: None,
+        notes: String::new(),
+        volume: None,
+    };
+    let measurement = Measurement {
+        measured_on: NaiveDate::from_ymd_opt(2025, 9, 4).unwrap(),
+        sugar_content: 150.0,
+        specific_gravity: Some(1.062),
+        temperature: None,
+        notes: "vigorous".to_owned(),
+    };
+    Upload::batch_log(&batch, &[measurement])
+}
+
+#[test]
+fn sends_wines_under_the_spreadsheet_columns() {
+    let input = Beverage::Wine.defaults();
+    let result = run_simulation(&input, &Dataset::default());
+    let mut sheet = Spreadsheet::default();
+    sheet.push("Run 1", "2025-09-01", &input, result.as_ref());
+
+    let upload = Upload::wines(&sheet);
+    assert_eq!(upload.columns, COLUMNS);
+    assert_eq!(upload.rows, sheet.rows());
+    let body = upload.to_json();
+    assert_eq!(body["kind"], "wines");
+    assert_eq!(body["rows"][0]["Name"], "Run 1");
+    // Empty cells are left out.
+    assert!(body["rows"][0].get("Error").is_none());
+}
+
+#[test]
+fn keys_batch_logs_by_column_and_lines_sheet_rows_up() {
+    let upload = batch_log();
+    assert_eq!(
+        upload.to_json()["rows"],
+        json!([{
+            "Batch": "merlot",
+            "Date": "2025-09-04",
+            "Day": 3.0,
+            "Specific gravity": 1.062,
+            "Residual sugar (g/L)": 150.0,
+            "Notes": "vigorous",
+        }])
+    );
+    assert_eq!(
+        upload.to_sheet_values()["values"][0],
+        json!(["merlot", "2025-09-04", 3.0, 1.062, 150.0, "", "vigorous"])
+    );
+    assert_eq!(upload.rows[0][5], Cell::Empty);
+}
+
+#[test]
+fn appends_to_the_named_sheet_and_needs_a_token() {
+    let upload = batch_log();
+    let target = WebhookTarget::GoogleSheets {
+        spreadsheet_id: "abc123".to_owned(),
+        sheet: "Club results".to_owned(),
+        access_token: "token".to_owned(),
+    };
+    let request = upload.request(&target).unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(
+        request.url,
+        "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/Club%20results:append\
+         ?valueInputOption=RAW&insertDataOption=INSERT_ROWS"
+    );
+
+    let no_token = WebhookTarget::GoogleSheets {
+        spreadsheet_id: "abc123".to_owned(),
+        sheet: String::new(),
+        access_token: " ".to_owned(),
+    };
+    assert!(matches!(
+        upload.request(&no_token),
+        Err(WebhookError::MissingSetting("access token"))
+    ));
+    assert!(matches!(
+        upload.request(&WebhookTarget::Webhook { url: String::new() }),
+        Err(WebhookError::MissingSetting("webhook URL"))
+    ));
+}
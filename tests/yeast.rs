@@ -0,0 +1,186 @@
+use wine_maker::dataset::Dataset;
+use wine_maker::engine::{
+    AMBIENT_YEAST, Inoculation, SimulationInput, YEAST_ALCOHOL_TOLERANCE, YEAST_STRAINS,
+    fermentation_state, run_simulation, seeded, wild_ferment, yeast_strain, yeast_tolerance,
+};
+use wine_maker::narrative::narrative;
+
+fn sauvignon_blanc(yeast: &str, temperature: f64) -> SimulationInput {
+    SimulationInput {
+        grape_type: "Sauvignon Blanc".to_owned(),
+        fermentation_days: 14,
+        container_type: "Steel Tank".to_owned(),
+        sugar_content: 210.0,
+        temperature,
+        climate: "Cool".to_owned(),
+        seed: Some(3),
+        yeast: yeast.to_owned(),
+        ..SimulationInput::default()
+    }
+}
+
+#[test]
+fn cool_sauvignon_blanc_gets_passionfruit_thiols() {
+    let input = sauvignon_blanc("QA23", 13.0);
+    let result = run_simulation(&input, &Dataset::default()).unwrap();
+    assert_eq!(
+        result.yeast_aromas,
+        ["passionfruit", "grapefruit", "citrus blossom"]
+    );
+    assert!(narrative(&input, &result).contains("The QA23 yeast brought out passionfruit"));
+
+    // Warm, the thiols are lost.
+    let warm = sauvignon_blanc("QA23", 22.0);
+    let result = run_simulation(&warm, &Dataset::default()).unwrap();
+    assert_eq!(result.yeast_aromas, ["lemon"]);
+
+    // And other grapes have none to release.
+    let chardonnay = SimulationInput {
+        grape_type: "Chardonnay".to_owned(),
+        ..input
+    };
+    let result = run_simulation(&chardonnay, &Dataset::default()).unwrap();
+    assert_eq!(result.yeast_aromas, ["citrus blossom"]);
+}
+
+#[test]
+fn neutral_or_unspecified_yeast_adds_nothing() {
+    for yeast in ["", "EC-1118", "Bread yeast"] {
+        let input = sauvignon_blanc(yeast, 13.0);
+        let result = run_simulation(&input, &Dataset::default()).unwrap();
+        assert!(result.yeast_aromas.is_empty());
+        assert!(!narrative(&input, &result).contains("yeast brought out"));
+    }
+}
+
+#[test]
+fn strains_are_looked_up_by_name() {
+    for strain in &YEAST_STRAINS {
+        assert_eq!(yeast_strain(&strain.name.to_lowercase()), Some(strain));
+    }
+}
+
+#[test]
+fn wild_ferments_are_drawn_from_the_seed() {
+    let wild = |seed| SimulationInput {
+        seed: Some(seed),
+        ..sauvignon_blanc(AMBIENT_YEAST, 18.0)
+    };
+    assert!(wild_ferment(&sauvignon_blanc("QA23", 18.0)).is_none());
+    assert_eq!(wild_ferment(&wild(5)), wild_ferment(&wild(5)));
+
+    let draws: Vec<_> = (0..200)
+        .map(|seed| wild_ferment(&wild(seed)).unwrap())
+        .collect();
+    for draw in &draws {
+        assert!((1.0..5.0).contains(&draw.lag_days));
+        assert!(draw.tolerance < YEAST_ALCOHOL_TOLERANCE);
+    }
+    assert!(draws.iter().any(|draw| draw.flawed));
+    assert!(draws.iter().any(|draw| !draw.flawed));
+    assert!(
+        draws
+            .iter()
+            .any(|draw| draw.character != draws[0].character)
+    );
+}
+
+#[test]
+fn unseeded_wild_ferments_draw_a_seed_per_run() {
+    let input = SimulationInput {
+        seed: None,
+        sugar_content: 280.0,
+        fermentation_days: 40,
+        ..sauvignon_blanc(AMBIENT_YEAST, 18.0)
+    };
+    assert_eq!(
+        seeded(&SimulationInput {
+            seed: Some(7),
+            ..input.clone()
+        })
+        .seed,
+        Some(7)
+    );
+
+    let data = Dataset::default();
+    let strengths: Vec<f64> = (0..20)
+        .map(|_| run_simulation(&input, &data).unwrap().actual_abv)
+        .collect();
+    assert!(strengths.iter().any(|&abv| abv != strengths[0]));
+
+    let run = seeded(&input);
+    assert!(run.seed.is_some());
+    assert_eq!(wild_ferment(&run), wild_ferment(&run));
+}
+
+#[test]
+fn wild_ferments_start_late_and_finish_weaker() {
+    let data = Dataset::default();
+    for seed in 0..20 {
+        let input = SimulationInput {
+            seed: Some(seed),
+            sugar_content: 280.0,
+            fermentation_days: 40,
+            ..sauvignon_blanc(AMBIENT_YEAST, 18.0)
+        };
+        let wild = wild_ferment(&input).unwrap();
+        assert_eq!(fermentation_state(&input, 1).unwrap().abv, 0.0);
+        let result = run_simulation(&input, &data).unwrap();
+        assert!(result.actual_abv < yeast_tolerance(&input));
+        assert!(result.actual_abv > yeast_tolerance(&input) - 1.0);
+        let text = narrative(&input, &result);
+        assert!(text.contains("The native yeast took"));
+        assert!(text.contains(wild.character));
+        assert_eq!(
+            result
+                .off_flavors
+                .iter()
+                .any(|flavor| flavor == wild.character),
+            wild.flawed
+        );
+        assert!(!text.contains("Ambient yeast brought out"));
+    }
+}
+
+#[test]
+fn inoculation_strategies_change_pace_and_character() {
+    let data = Dataset::default();
+    let with = |inoculation| SimulationInput {
+        inoculation,
+        climate: "Moderate".to_owned(),
+        ..sauvignon_blanc("", 18.0)
+    };
+    let single = run_simulation(&with(Inoculation::Single), &data).unwrap();
+    assert_eq!(single.acidity, "moderate");
+    assert_eq!(single.malolactic_note, "");
+
+    let co_pitch = with(Inoculation::NonSaccharomyces);
+    let result = run_simulation(&co_pitch, &data).unwrap();
+    assert!(result.actual_abv < single.actual_abv);
+    assert_eq!(result.yeast_aromas, ["complex, floral esters"]);
+    assert!(narrative(&co_pitch, &result).contains("The yeast brought out complex"));
+
+    let sequential = with(Inoculation::SequentialMalolactic);
+    let result = run_simulation(&sequential, &data).unwrap();
+    assert_eq!(result.actual_abv, single.actual_abv);
+    assert_eq!(result.acidity, "soft");
+    assert!(result.malolactic_note.contains("buttery"));
+    assert!(narrative(&sequential, &result).contains("once the yeast finished"));
+
+    let co_inoculated = with(Inoculation::CoInoculatedMalolactic);
+    let result = run_simulation(&co_inoculated, &data).unwrap();
+    assert!(result.actual_abv < single.actual_abv);
+    assert_eq!(result.acidity, "soft");
+    assert!(!result.malolactic_note.contains("buttery"));
+    assert_eq!(
+        Inoculation::CoInoculatedMalolactic.malolactic_days_after(),
+        0
+    );
+
+    for inoculation in Inoculation::ALL {
+        assert_eq!(
+            Inoculation::from_label(inoculation.label()),
+            Some(inoculation)
+        );
+    }
+}